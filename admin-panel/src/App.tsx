@@ -24,8 +24,26 @@ type AdminOverview = {
   bulk_reload_recommended: boolean
 }
 
+const ADMIN_TOKEN_KEY = 'scryfall-cache-admin-token'
+
+// Send the admin token (ADMIN_TOKEN) if one was entered; ask for it once on a 401
+async function fetchWithToken(url: string, init?: RequestInit, retry = true): Promise<Response> {
+  const token = sessionStorage.getItem(ADMIN_TOKEN_KEY)
+  const headers = new Headers(init?.headers)
+  if (token) headers.set('Authorization', `Bearer ${token}`)
+  const res = await fetch(url, { ...init, headers })
+  if (res.status === 401 && retry) {
+    const entered = window.prompt('Admin token')
+    if (entered) {
+      sessionStorage.setItem(ADMIN_TOKEN_KEY, entered)
+      return fetchWithToken(url, init, false)
+    }
+  }
+  return res
+}
+
 async function fetchJson<T>(url: string, init?: RequestInit): Promise<T> {
-  const res = await fetch(url, init)
+  const res = await fetchWithToken(url, init)
   const text = await res.text()
   const json = text ? (JSON.parse(text) as T) : ({} as T)
   if (!res.ok) {
@@ -93,7 +111,7 @@ function App() {
           </div>
         </div>
         <div className="topbar__meta">
-          <a className="link" href="/api-docs" target="_blank" rel="noreferrer">
+          <a className="link" href="/docs" target="_blank" rel="noreferrer">
             OpenAPI
           </a>
           <a className="link" href="/metrics" target="_blank" rel="noreferrer">
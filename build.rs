@@ -0,0 +1,14 @@
+// `sqlx::migrate!` embeds the migration files at build time; rebuild when they change
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+
+    // gRPC server stubs for the `grpc` feature
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto");
+        tonic_build::configure()
+            .build_client(false)
+            .compile_protos(&["proto/scryfall_cache/v1/cards.proto"], &["proto"])
+            .expect("Failed to compile proto/scryfall_cache/v1/cards.proto (is protoc installed?)");
+    }
+}
@@ -0,0 +1,12 @@
+-- Ledger of incremental sync runs between full bulk loads
+CREATE TABLE IF NOT EXISTS sync_ledger (
+    id SERIAL PRIMARY KEY,
+    started_at TIMESTAMP NOT NULL,
+    completed_at TIMESTAMP NOT NULL,
+    since TIMESTAMP NOT NULL,
+    cards_updated INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_ledger_started_at ON sync_ledger(started_at DESC);
@@ -0,0 +1,9 @@
+-- Which bulk data types (oracle_cards, default_cards, all_cards, ...) each card was imported from
+CREATE TABLE IF NOT EXISTS card_provenance (
+    card_id UUID NOT NULL,
+    bulk_type TEXT NOT NULL,
+    imported_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (card_id, bulk_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_provenance_bulk_type ON card_provenance(bulk_type);
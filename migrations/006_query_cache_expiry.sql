@@ -0,0 +1,4 @@
+-- Per-entry expiry for the query cache, so each query class can use its own TTL
+ALTER TABLE query_cache ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_query_cache_expires_at ON query_cache(expires_at);
@@ -0,0 +1,2 @@
+-- Count of cache hits per query_cache entry, surfaced in /stats and /admin/cache/queries
+ALTER TABLE query_cache ADD COLUMN IF NOT EXISTS hit_count BIGINT NOT NULL DEFAULT 0;
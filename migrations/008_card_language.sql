@@ -0,0 +1,17 @@
+-- Language of each printing plus its localized (printed) name, type line and rules text,
+-- so localized printings imported from all_cards can be filtered and looked up
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS lang TEXT;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS printed_name TEXT;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS printed_type_line TEXT;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS printed_text TEXT;
+
+-- Backfill rows imported before these columns existed
+UPDATE cards SET
+    lang = COALESCE(raw_json->>'lang', 'en'),
+    printed_name = raw_json->>'printed_name',
+    printed_type_line = raw_json->>'printed_type_line',
+    printed_text = raw_json->>'printed_text'
+WHERE lang IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_cards_lang ON cards(lang);
+CREATE INDEX IF NOT EXISTS idx_cards_printed_name ON cards(LOWER(printed_name));
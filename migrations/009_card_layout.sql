@@ -0,0 +1,8 @@
+-- Scryfall layout of each card, promoted from raw_json so tokens and emblems
+-- can be filtered (is:token / is:emblem) and kept out of named lookups
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS layout TEXT;
+
+-- Backfill rows imported before the column existed
+UPDATE cards SET layout = COALESCE(raw_json->>'layout', 'normal') WHERE layout IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_cards_layout ON cards(layout);
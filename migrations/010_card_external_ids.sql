@@ -0,0 +1,19 @@
+-- External ids promoted from raw_json so cards can be looked up by the id spaces
+-- used by Gatherer, MTG Arena and MTGO (/cards/multiverse|arena|mtgo/:id)
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS multiverse_ids INTEGER[];
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS arena_id INTEGER;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS mtgo_id INTEGER;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS mtgo_foil_id INTEGER;
+
+-- Backfill rows imported before the columns existed
+UPDATE cards SET
+    multiverse_ids = ARRAY(SELECT jsonb_array_elements_text(raw_json->'multiverse_ids')::INTEGER),
+    arena_id = (raw_json->>'arena_id')::INTEGER,
+    mtgo_id = (raw_json->>'mtgo_id')::INTEGER,
+    mtgo_foil_id = (raw_json->>'mtgo_foil_id')::INTEGER
+WHERE multiverse_ids IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_cards_multiverse_ids ON cards USING GIN (multiverse_ids);
+CREATE INDEX IF NOT EXISTS idx_cards_arena_id ON cards(arena_id);
+CREATE INDEX IF NOT EXISTS idx_cards_mtgo_id ON cards(mtgo_id);
+CREATE INDEX IF NOT EXISTS idx_cards_mtgo_foil_id ON cards(mtgo_foil_id);
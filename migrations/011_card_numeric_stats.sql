@@ -0,0 +1,10 @@
+-- Numeric power/toughness for comparisons (pow>=3). Values such as '*', '1+*' or 'X'
+-- stay in power/toughness but are NULL here, so comparisons skip those cards instead
+-- of failing the cast
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS power_num NUMERIC
+    GENERATED ALWAYS AS (CASE WHEN power ~ '^[+-]?[0-9]+(\.[0-9]+)?$' THEN power::NUMERIC END) STORED;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS toughness_num NUMERIC
+    GENERATED ALWAYS AS (CASE WHEN toughness ~ '^[+-]?[0-9]+(\.[0-9]+)?$' THEN toughness::NUMERIC END) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_cards_power_num ON cards(power_num);
+CREATE INDEX IF NOT EXISTS idx_cards_toughness_num ON cards(toughness_num);
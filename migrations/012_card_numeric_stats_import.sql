@@ -0,0 +1,12 @@
+-- Numeric power/toughness/loyalty are now computed during import, so they become
+-- plain columns. DOUBLE PRECISION maps onto Card's Option<f64> fields like cmc does.
+ALTER TABLE cards ALTER COLUMN power_num DROP EXPRESSION IF EXISTS;
+ALTER TABLE cards ALTER COLUMN toughness_num DROP EXPRESSION IF EXISTS;
+ALTER TABLE cards ALTER COLUMN power_num TYPE DOUBLE PRECISION;
+ALTER TABLE cards ALTER COLUMN toughness_num TYPE DOUBLE PRECISION;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS loyalty_num DOUBLE PRECISION;
+
+UPDATE cards SET loyalty_num = loyalty::DOUBLE PRECISION
+WHERE loyalty_num IS NULL AND loyalty ~ '^[+-]?[0-9]+(\.[0-9]+)?$';
+
+CREATE INDEX IF NOT EXISTS idx_cards_loyalty_num ON cards(loyalty_num);
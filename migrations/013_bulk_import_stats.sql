@@ -0,0 +1,3 @@
+-- Per-import statistics surfaced by /api/admin/imports
+ALTER TABLE bulk_data_metadata ADD COLUMN IF NOT EXISTS duration_ms BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE bulk_data_metadata ADD COLUMN IF NOT EXISTS failed_cards INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Tenant whose search cached each query_cache entry. Keys of tenants other than the
+-- default one are prefixed with the tenant id, so tenants never share cached results.
+ALTER TABLE query_cache ADD COLUMN IF NOT EXISTS tenant_id TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS idx_query_cache_tenant_id ON query_cache(tenant_id);
@@ -0,0 +1,16 @@
+-- Numeric prices for usd/eur/tix filters, computed during import from the prices JSON
+-- (Scryfall sends them as strings) so comparisons can use an index instead of a cast.
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS price_usd DOUBLE PRECISION;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS price_eur DOUBLE PRECISION;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS price_tix DOUBLE PRECISION;
+
+UPDATE cards SET
+    price_usd = CASE WHEN prices->>'usd' ~ '^[0-9]+(\.[0-9]+)?$' THEN (prices->>'usd')::DOUBLE PRECISION END,
+    price_eur = CASE WHEN prices->>'eur' ~ '^[0-9]+(\.[0-9]+)?$' THEN (prices->>'eur')::DOUBLE PRECISION END,
+    price_tix = CASE WHEN prices->>'tix' ~ '^[0-9]+(\.[0-9]+)?$' THEN (prices->>'tix')::DOUBLE PRECISION END
+WHERE price_usd IS NULL AND price_eur IS NULL AND price_tix IS NULL
+  AND (prices->>'usd' IS NOT NULL OR prices->>'eur' IS NOT NULL OR prices->>'tix' IS NOT NULL);
+
+CREATE INDEX IF NOT EXISTS idx_cards_price_usd ON cards(price_usd);
+CREATE INDEX IF NOT EXISTS idx_cards_price_eur ON cards(price_eur);
+CREATE INDEX IF NOT EXISTS idx_cards_price_tix ON cards(price_tix);
@@ -0,0 +1,11 @@
+-- Lowercased face names of multi-faced cards, computed during import, so named lookups
+-- resolve one half of a split or adventure card ("Fire" -> "Fire // Ice").
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS face_names TEXT[];
+
+UPDATE cards SET face_names = (
+    SELECT array_agg(LOWER(face->>'name'))
+    FROM jsonb_array_elements(card_faces) AS face
+)
+WHERE face_names IS NULL AND jsonb_typeof(card_faces) = 'array' AND jsonb_array_length(card_faces) > 0;
+
+CREATE INDEX IF NOT EXISTS idx_cards_face_names ON cards USING GIN (face_names);
@@ -0,0 +1,11 @@
+-- Owned quantities of printings in user collections, surfaced by /collections/{id}
+CREATE TABLE IF NOT EXISTS collection_cards (
+    tenant_id TEXT NOT NULL DEFAULT 'default',
+    collection_id TEXT NOT NULL,
+    card_id UUID NOT NULL,
+    finish TEXT NOT NULL DEFAULT 'nonfoil',
+    quantity INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW(),
+    updated_at TIMESTAMP DEFAULT NOW(),
+    PRIMARY KEY (tenant_id, collection_id, card_id, finish)
+);
@@ -0,0 +1,23 @@
+-- Saved searches re-evaluated after every bulk import, surfaced by /saved-searches
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id UUID PRIMARY KEY,
+    tenant_id TEXT NOT NULL DEFAULT 'default',
+    query TEXT NOT NULL,
+    name TEXT,
+    runs INTEGER NOT NULL DEFAULT 0,
+    new_matches INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT NOW(),
+    last_run_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_searches_tenant_id ON saved_searches(tenant_id);
+
+-- Cards matching each saved search, with the run they first matched in (0 = at registration)
+CREATE TABLE IF NOT EXISTS saved_search_matches (
+    saved_search_id UUID NOT NULL,
+    card_id UUID NOT NULL,
+    first_run INTEGER NOT NULL,
+    PRIMARY KEY (saved_search_id, card_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_search_matches_run ON saved_search_matches(saved_search_id, first_run);
@@ -0,0 +1,12 @@
+-- Bulk data entries that failed to parse, surfaced by /api/admin/imports/{id}/errors
+CREATE TABLE IF NOT EXISTS bulk_import_errors (
+    id BIGSERIAL PRIMARY KEY,
+    import_id INTEGER NOT NULL,
+    position BIGINT NOT NULL,
+    card_id TEXT,
+    card_name TEXT,
+    category TEXT NOT NULL,
+    message TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bulk_import_errors_import_id ON bulk_import_errors(import_id);
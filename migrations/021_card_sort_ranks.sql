@@ -0,0 +1,13 @@
+-- Popularity ranks for order=edhrec and order=penny, computed during import from the
+-- Scryfall JSON. Ranks are sparse (most cards have none), so sorts put NULLs last.
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS edhrec_rank INTEGER;
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS penny_rank INTEGER;
+
+UPDATE cards SET
+    edhrec_rank = CASE WHEN raw_json->>'edhrec_rank' ~ '^[0-9]+$' THEN (raw_json->>'edhrec_rank')::INTEGER END,
+    penny_rank = CASE WHEN raw_json->>'penny_rank' ~ '^[0-9]+$' THEN (raw_json->>'penny_rank')::INTEGER END
+WHERE edhrec_rank IS NULL AND penny_rank IS NULL
+  AND (raw_json->>'edhrec_rank' IS NOT NULL OR raw_json->>'penny_rank' IS NOT NULL);
+
+CREATE INDEX IF NOT EXISTS idx_cards_edhrec_rank ON cards(edhrec_rank);
+CREATE INDEX IF NOT EXISTS idx_cards_penny_rank ON cards(penny_rank);
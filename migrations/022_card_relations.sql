@@ -0,0 +1,22 @@
+-- Links between cards from Scryfall's all_parts (tokens a card makes, meld pairs, combo
+-- pieces), written during import, for GET /cards/{id}/related.
+CREATE TABLE IF NOT EXISTS card_relations (
+    card_id UUID NOT NULL,
+    related_id UUID NOT NULL,
+    component TEXT NOT NULL,
+    name TEXT NOT NULL,
+    type_line TEXT,
+    PRIMARY KEY (card_id, related_id)
+);
+
+-- Fill the table from already stored cards once, imports keep it current afterwards
+INSERT INTO card_relations (card_id, related_id, component, name, type_line)
+SELECT c.id, (part->>'id')::UUID, part->>'component', part->>'name', part->>'type_line'
+FROM cards c, jsonb_array_elements(c.raw_json->'all_parts') AS part
+WHERE jsonb_typeof(c.raw_json->'all_parts') = 'array'
+  AND part->>'id' ~ '^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$'
+  AND part->>'id' <> c.id::text
+  AND part->>'component' IS NOT NULL
+  AND part->>'name' IS NOT NULL
+  AND NOT EXISTS (SELECT 1 FROM card_relations)
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,3 @@
+-- Keyset index for cursor pagination, which orders by (name, id) and resumes with
+-- `name > $n OR (name = $n AND id > $id)`. The full-text index on name can't serve either.
+CREATE INDEX IF NOT EXISTS idx_cards_name_id ON cards(name, id);
@@ -15,6 +15,8 @@ echo "Running local checks in: $root_dir"
 
 run cargo fmt --check
 run cargo test
+# The SQLite backend is behind a feature; build and test it too so it doesn't rot
+run cargo test --no-default-features --features sqlite,native-tls
 
 if [[ -d "admin-panel" ]]; then
   # admin-panel is optional for pure-backend contributors.
@@ -9,7 +9,7 @@ echo ""
 
 # Build SQLite version
 echo "Building SQLite version..."
-cargo build --release --no-default-features --features sqlite
+cargo build --release --no-default-features --features sqlite,rustls
 echo "✓ Build complete"
 echo ""
 
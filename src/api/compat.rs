@@ -0,0 +1,272 @@
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::config::Settings;
+use crate::errors::ErrorResponse;
+use crate::models::card::Card;
+
+/// Scryfall's page size for `/cards/search`, used by default in Scryfall format
+pub const SCRYFALL_PAGE_SIZE: usize = 175;
+
+/// Scryfall's message for a search that matched nothing
+const NO_MATCHES_DETAILS: &str = "Your query didn’t match any cards. Adjust your search terms or refer to the syntax guide at https://scryfall.com/docs/reference";
+
+/// Response envelope used by `/cards/search`, `/cards/named` and `/cards/autocomplete`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseFormat {
+    /// This service's `{ success, data, error }` envelope
+    #[default]
+    Native,
+    /// Scryfall's own response objects (`list`, `card`, `catalog`, `error`), so
+    /// Scryfall SDKs can use this service as their base URL
+    Scryfall,
+}
+
+impl ResponseFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "native" => Some(ResponseFormat::Native),
+            "scryfall" => Some(ResponseFormat::Scryfall),
+            _ => None,
+        }
+    }
+
+    /// Default format from `SCRYFALL_COMPAT_RESPONSES`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let enabled = settings
+            .var("SCRYFALL_COMPAT_RESPONSES")
+            .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        if enabled {
+            ResponseFormat::Scryfall
+        } else {
+            ResponseFormat::Native
+        }
+    }
+
+    /// Pick the format for one request: an explicit `?format=` wins, then an `Accept`
+    /// media type with `profile="scryfall"` or `profile="native"`, then `self`.
+    /// Returns `Err` with the offending value for an unknown `format`.
+    pub fn negotiate<'a>(
+        self,
+        format: Option<&'a str>,
+        headers: &HeaderMap,
+    ) -> Result<Self, &'a str> {
+        if let Some(value) = format {
+            return Self::parse(value).ok_or(value);
+        }
+
+        let profile = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .flat_map(|media_type| media_type.split(';').skip(1))
+            .filter_map(|param| param.split_once('='))
+            .filter(|(name, _)| name.trim().eq_ignore_ascii_case("profile"))
+            .find_map(|(_, value)| Self::parse(value.trim().trim_matches('"')));
+
+        Ok(profile.unwrap_or(self))
+    }
+
+    /// Render an error in this format
+    pub fn error(self, error: impl Into<ErrorResponse>) -> Response {
+        let error = error.into();
+        match self {
+            ResponseFormat::Native => error.into_response(),
+            ResponseFormat::Scryfall => ScryfallError::from(error).into_response(),
+        }
+    }
+}
+
+/// Scryfall `list` object, as returned by `/cards/search`
+#[derive(Debug, Serialize)]
+pub struct ScryfallList<T> {
+    pub object: &'static str,
+    pub total_cards: usize,
+    pub has_more: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page: Option<String>,
+    /// Non-fatal problems with the request, as in Scryfall's own lists
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    pub data: Vec<T>,
+}
+
+impl ScryfallList<serde_json::Value> {
+    /// A page of search results, as raw Scryfall card objects
+    pub fn cards(
+        cards: Vec<Card>,
+        total_cards: usize,
+        has_more: bool,
+        next_page: Option<String>,
+    ) -> Self {
+        Self {
+            object: "list",
+            total_cards,
+            has_more,
+            next_page,
+            warnings: Vec::new(),
+            data: cards.into_iter().map(|card| card.raw_json).collect(),
+        }
+    }
+}
+
+/// Scryfall `catalog` object, as returned by `/cards/autocomplete`
+#[derive(Debug, Serialize)]
+pub struct ScryfallCatalog {
+    pub object: &'static str,
+    pub total_values: usize,
+    pub data: Vec<String>,
+}
+
+impl ScryfallCatalog {
+    pub fn new(data: Vec<String>) -> Self {
+        Self {
+            object: "catalog",
+            total_values: data.len(),
+            data,
+        }
+    }
+}
+
+/// Scryfall `error` object
+#[derive(Debug, Serialize)]
+pub struct ScryfallError {
+    pub object: &'static str,
+    pub code: &'static str,
+    pub status: u16,
+    pub details: String,
+}
+
+impl ScryfallError {
+    /// The 404 Scryfall returns when a search matches no cards
+    pub fn no_matches() -> Self {
+        Self {
+            object: "error",
+            code: "not_found",
+            status: 404,
+            details: NO_MATCHES_DETAILS.to_string(),
+        }
+    }
+}
+
+impl From<ErrorResponse> for ScryfallError {
+    fn from(error: ErrorResponse) -> Self {
+        let status = error.error.code.status_code();
+        let code = match status {
+            400 => "bad_request",
+            404 => "not_found",
+            503 => "unavailable",
+            _ => "internal_server_error",
+        };
+        Self {
+            object: "error",
+            code,
+            status,
+            details: error.error.message,
+        }
+    }
+}
+
+impl IntoResponse for ScryfallError {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(self)).into_response()
+    }
+}
+
+/// Scryfall `card` object for a single card lookup
+pub fn card_response(card: Card) -> Response {
+    (StatusCode::OK, Json(card.raw_json)).into_response()
+}
+
+/// Absolute URL of the next search page: the request URL with `page` replaced. Other
+/// parameters (including `format`) are kept so SDKs following `next_page` get the same
+/// response shape.
+pub fn next_page_url(headers: &HeaderMap, path: &str, query: &str, page: usize) -> String {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("localhost");
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("http");
+
+    let mut params: Vec<String> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            !matches!(name, "page" | "cursor")
+        })
+        .map(str::to_string)
+        .collect();
+    params.push(format!("page={}", page));
+
+    format!("{}://{}{}?{}", scheme, host, path, params.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorCode;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_negotiate_format() {
+        let mut headers = HeaderMap::new();
+        let native = ResponseFormat::Native;
+        assert_eq!(native.negotiate(None, &headers), Ok(ResponseFormat::Native));
+        assert_eq!(
+            native.negotiate(Some("Scryfall"), &headers),
+            Ok(ResponseFormat::Scryfall)
+        );
+        assert_eq!(native.negotiate(Some("xml"), &headers), Err("xml"));
+
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/json; profile=\"scryfall\""),
+        );
+        assert_eq!(
+            native.negotiate(None, &headers),
+            Ok(ResponseFormat::Scryfall)
+        );
+        assert_eq!(
+            native.negotiate(Some("native"), &headers),
+            Ok(ResponseFormat::Native)
+        );
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        let error = ScryfallError::from(ErrorResponse::card_not_found("Black Lotus"));
+        assert_eq!(error.code, "not_found");
+        assert_eq!(error.status, 404);
+
+        let error = ScryfallError::from(ErrorResponse::new(ErrorCode::InvalidQuery, "bad"));
+        assert_eq!(error.code, "bad_request");
+        assert_eq!(error.status, 400);
+    }
+
+    #[test]
+    fn test_next_page_url() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_static("cards.local:8080"));
+        let url = next_page_url(
+            &headers,
+            "/cards/search",
+            "q=c%3Ar&page=1&format=scryfall",
+            2,
+        );
+        assert_eq!(
+            url,
+            "http://cards.local:8080/cards/search?q=c%3Ar&format=scryfall&page=2"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{bail, Context, Result};
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+
+use crate::config::Settings;
+
+/// Cross-origin access for browser clients such as web deck builders. The defaults allow
+/// any origin, method and header without credentials, which suits development; production
+/// deployments serving browsers should list their origins.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// Send CORS headers at all; disabled, browsers block every cross-origin call
+    pub enabled: bool,
+    /// Origins allowed to call the API (`None` = any)
+    pub allowed_origins: Option<Vec<HeaderValue>>,
+    /// Methods allowed in cross-origin requests (`None` = any)
+    pub allowed_methods: Option<Vec<Method>>,
+    /// Request headers allowed in cross-origin requests (`None` = any)
+    pub allowed_headers: Option<Vec<HeaderName>>,
+    /// Let browsers send cookies and HTTP auth; needs explicit origins
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response
+    pub max_age: Duration,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_origins: None,
+            allowed_methods: None,
+            allowed_headers: None,
+            allow_credentials: false,
+            max_age: Duration::from_secs(600),
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Load configuration from `settings`. Lists are comma-separated; `*` (or
+    /// unset) allows anything.
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        let defaults = Self::default();
+        let var = |name: &str| settings.var(name).ok();
+
+        let config = Self {
+            enabled: var("CORS_ENABLED")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            allowed_origins: parse_list(var("CORS_ALLOWED_ORIGINS"), |origin| {
+                let origin = origin.trim_end_matches('/');
+                if !origin.starts_with("http://") && !origin.starts_with("https://") {
+                    bail!(
+                        "'{}' is not an origin (expected http(s)://host[:port])",
+                        origin
+                    );
+                }
+                HeaderValue::from_str(origin).map_err(Into::into)
+            })
+            .context("Invalid CORS_ALLOWED_ORIGINS")?,
+            allowed_methods: parse_list(var("CORS_ALLOWED_METHODS"), |method| {
+                Method::from_bytes(method.to_ascii_uppercase().as_bytes()).map_err(Into::into)
+            })
+            .context("Invalid CORS_ALLOWED_METHODS")?,
+            allowed_headers: parse_list(var("CORS_ALLOWED_HEADERS"), |header| {
+                HeaderName::from_bytes(header.as_bytes()).map_err(Into::into)
+            })
+            .context("Invalid CORS_ALLOWED_HEADERS")?,
+            allow_credentials: var("CORS_ALLOW_CREDENTIALS")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.allow_credentials),
+            max_age: var("CORS_MAX_AGE_SECONDS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.max_age),
+        };
+
+        // Browsers refuse credentials from `Access-Control-Allow-Origin: *`, and echoing
+        // every origin back would let any site make authenticated calls
+        if config.enabled && config.allow_credentials && config.allowed_origins.is_none() {
+            bail!("CORS_ALLOW_CREDENTIALS requires CORS_ALLOWED_ORIGINS to list the origins");
+        }
+        Ok(config)
+    }
+
+    /// The CORS layer for this configuration, or one allowing no cross-origin calls when
+    /// disabled
+    pub fn layer(&self) -> CorsLayer {
+        if !self.enabled {
+            return CorsLayer::new();
+        }
+
+        let origins = match &self.allowed_origins {
+            Some(origins) => AllowOrigin::list(origins.iter().cloned()),
+            None => AllowOrigin::any(),
+        };
+        // Credentials rule out wildcards: echo what the preflight request asks for instead
+        let methods = match &self.allowed_methods {
+            Some(methods) => AllowMethods::list(methods.iter().cloned()),
+            None if self.allow_credentials => AllowMethods::mirror_request(),
+            None => AllowMethods::any(),
+        };
+        let headers = match &self.allowed_headers {
+            Some(headers) => AllowHeaders::list(headers.iter().cloned()),
+            None if self.allow_credentials => AllowHeaders::mirror_request(),
+            None => AllowHeaders::any(),
+        };
+
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .allow_credentials(self.allow_credentials)
+            .max_age(self.max_age)
+    }
+}
+
+/// Parse a comma-separated list with `parse`; unset, empty or `*` means `None` (any)
+fn parse_list<T>(
+    value: Option<String>,
+    parse: impl Fn(&str) -> Result<T>,
+) -> Result<Option<Vec<T>>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let items: Vec<&str> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect();
+    if items.is_empty() || items.contains(&"*") {
+        return Ok(None);
+    }
+    items
+        .into_iter()
+        .map(parse)
+        .collect::<Result<_>>()
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list() {
+        let methods = parse_list(Some("get, post".to_string()), |m| {
+            Method::from_bytes(m.to_ascii_uppercase().as_bytes()).map_err(Into::into)
+        })
+        .unwrap();
+        assert_eq!(methods, Some(vec![Method::GET, Method::POST]));
+
+        let parse = |s: &str| Ok(s.to_string());
+        assert_eq!(parse_list(None, parse).unwrap(), None);
+        assert_eq!(parse_list(Some("*".to_string()), parse).unwrap(), None);
+        assert_eq!(parse_list(Some(" ".to_string()), parse).unwrap(), None);
+        assert!(parse_list(Some("a,b".to_string()), |_| -> Result<()> {
+            bail!("invalid")
+        })
+        .is_err());
+    }
+}
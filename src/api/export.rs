@@ -0,0 +1,251 @@
+use crate::models::card::Card;
+
+/// Output format for `/admin/export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One raw Scryfall card object per line (same shape as Scryfall bulk data)
+    Ndjson,
+    /// Flat CSV of the scalar card columns
+    Csv,
+}
+
+/// A CSV column: its header and how the value is read from a card
+type CsvField = (&'static str, fn(&Card) -> Option<String>);
+
+fn list(values: &Option<Vec<String>>) -> Option<String> {
+    values.as_ref().map(|v| v.join(";"))
+}
+
+fn price(card: &Card, key: &str) -> Option<String> {
+    let value = card.prices.as_ref()?.get(key)?;
+    value.as_str().map(str::to_string)
+}
+
+fn raw_number(card: &Card, key: &str) -> Option<String> {
+    card.raw_json.get(key)?.as_i64().map(|n| n.to_string())
+}
+
+/// Every column CSV output can select, in default order. The first
+/// `EXPORT_CSV_COLUMNS` are the ones `/admin/export` writes.
+const CSV_FIELDS: &[CsvField] = &[
+    ("id", |c| Some(c.id.to_string())),
+    ("oracle_id", |c| c.oracle_id.map(|id| id.to_string())),
+    ("name", |c| Some(c.name.clone())),
+    ("mana_cost", |c| c.mana_cost.clone()),
+    ("cmc", |c| c.cmc.map(|cmc| cmc.to_string())),
+    ("type_line", |c| c.type_line.clone()),
+    ("oracle_text", |c| c.oracle_text.clone()),
+    ("colors", |c| list(&c.colors)),
+    ("color_identity", |c| list(&c.color_identity)),
+    ("set_code", |c| c.set_code.clone()),
+    ("set_name", |c| c.set_name.clone()),
+    ("collector_number", |c| c.collector_number.clone()),
+    ("rarity", |c| c.rarity.clone()),
+    ("power", |c| c.power.clone()),
+    ("toughness", |c| c.toughness.clone()),
+    ("loyalty", |c| c.loyalty.clone()),
+    ("keywords", |c| list(&c.keywords)),
+    ("released_at", |c| c.released_at.map(|d| d.to_string())),
+    ("lang", |c| c.lang.clone()),
+    ("layout", |c| c.layout.clone()),
+    ("price_usd", |c| price(c, "usd")),
+    ("price_usd_foil", |c| price(c, "usd_foil")),
+    ("price_usd_etched", |c| price(c, "usd_etched")),
+    ("price_eur", |c| price(c, "eur")),
+    ("price_eur_foil", |c| price(c, "eur_foil")),
+    ("price_tix", |c| price(c, "tix")),
+    ("edhrec_rank", |c| c.edhrec_rank.map(|r| r.to_string())),
+    ("penny_rank", |c| c.penny_rank.map(|r| r.to_string())),
+    ("arena_id", |c| c.arena_id.map(|id| id.to_string())),
+    ("mtgo_id", |c| c.mtgo_id.map(|id| id.to_string())),
+    ("tcgplayer_id", |c| raw_number(c, "tcgplayer_id")),
+    ("cardmarket_id", |c| raw_number(c, "cardmarket_id")),
+];
+
+/// Columns written by the `/admin/export` CSV
+const EXPORT_CSV_COLUMNS: usize = 19;
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "ndjson" | "jsonl" => Some(ExportFormat::Ndjson),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "cards.ndjson",
+            ExportFormat::Csv => "cards.csv",
+        }
+    }
+
+    /// Bytes written before the first card (the CSV header row)
+    pub fn header(&self) -> Option<String> {
+        match self {
+            ExportFormat::Ndjson => None,
+            ExportFormat::Csv => Some(csv_header(&CSV_FIELDS[..EXPORT_CSV_COLUMNS])),
+        }
+    }
+
+    /// Encode a single card as one line of output, including the trailing newline
+    pub fn encode(&self, card: &Card) -> String {
+        match self {
+            ExportFormat::Ndjson => {
+                let mut line = card.raw_json.to_string();
+                line.push('\n');
+                line
+            }
+            ExportFormat::Csv => csv_row(&CSV_FIELDS[..EXPORT_CSV_COLUMNS], card),
+        }
+    }
+}
+
+/// A selection of CSV columns, for `/cards/search?format=csv&columns=...`
+#[derive(Clone)]
+pub struct CsvColumns(Vec<CsvField>);
+
+impl Default for CsvColumns {
+    /// Every known column
+    fn default() -> Self {
+        Self(CSV_FIELDS.to_vec())
+    }
+}
+
+impl CsvColumns {
+    /// Parse a comma-separated list of column names, kept in the given order
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let columns = value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                CSV_FIELDS
+                    .iter()
+                    .find(|(column, _)| column.eq_ignore_ascii_case(name))
+                    .copied()
+                    .ok_or_else(|| {
+                        format!(
+                            "Unknown CSV column '{}' (expected one of: {})",
+                            name,
+                            Self::names().join(", ")
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if columns.is_empty() {
+            return Err("At least one CSV column is required".to_string());
+        }
+        Ok(Self(columns))
+    }
+
+    /// Names of every column that can be selected
+    pub fn names() -> Vec<&'static str> {
+        CSV_FIELDS.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Header row, including the trailing newline
+    pub fn header(&self) -> String {
+        csv_header(&self.0)
+    }
+
+    /// Encode a single card as one row, including the trailing newline
+    pub fn row(&self, card: &Card) -> String {
+        csv_row(&self.0, card)
+    }
+}
+
+fn csv_header(fields: &[CsvField]) -> String {
+    let names: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+    format!("{}\n", names.join(","))
+}
+
+fn csv_row(fields: &[CsvField], card: &Card) -> String {
+    let mut row = fields
+        .iter()
+        .map(|(_, value)| csv_escape(value(card).as_deref().unwrap_or("")))
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push('\n');
+    row
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(ExportFormat::parse("NDJSON"), Some(ExportFormat::Ndjson));
+        assert_eq!(ExportFormat::parse("csv"), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::parse("xml"), None);
+    }
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("Sol Ring"), "Sol Ring");
+        assert_eq!(csv_escape("Draw, then discard"), "\"Draw, then discard\"");
+        assert_eq!(csv_escape("\"Ach! Hans, run!\""), "\"\"\"Ach! Hans, run!\"\"\"");
+    }
+
+    #[test]
+    fn test_csv_row_matches_header_width() {
+        let card = Card::from_scryfall_json(serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Lightning Bolt",
+            "oracle_text": "Lightning Bolt deals 3 damage to any target.",
+            "colors": ["R"],
+        }))
+        .unwrap();
+
+        let row = ExportFormat::Csv.encode(&card);
+        assert!(row.starts_with("550c74d4-1fcb-406a-b02a-639a760a4380,,Lightning Bolt,"));
+        assert!(row.ends_with('\n'));
+        assert_eq!(row.trim_end().split(',').count(), EXPORT_CSV_COLUMNS);
+        assert!(ExportFormat::Csv
+            .header()
+            .unwrap()
+            .starts_with("id,oracle_id,name,"));
+    }
+
+    #[test]
+    fn test_csv_columns_selection() {
+        let card = Card::from_scryfall_json(serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Lightning Bolt",
+            "set": "m10",
+            "collector_number": "146",
+            "prices": {"usd": "1.25", "eur": null},
+            "tcgplayer_id": 33437,
+        }))
+        .unwrap();
+
+        let columns =
+            CsvColumns::parse("name, SET_CODE,collector_number,price_usd,price_eur,tcgplayer_id")
+                .unwrap();
+        assert_eq!(
+            columns.header(),
+            "name,set_code,collector_number,price_usd,price_eur,tcgplayer_id\n"
+        );
+        assert_eq!(columns.row(&card), "Lightning Bolt,m10,146,1.25,,33437\n");
+
+        assert!(CsvColumns::parse("name,artist").is_err());
+        assert!(CsvColumns::parse(" , ").is_err());
+    }
+}
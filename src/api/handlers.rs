@@ -1,22 +1,55 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{OriginalUri, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json, Html},
 };
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::cache::manager::{CacheManager, CacheStats};
-use crate::errors::{ErrorCode, ErrorResponse};
-use crate::graphql::GraphQLSchema;
-use crate::models::card::Card;
-use crate::query::{QueryParser, QueryValidator};
+use crate::api::compat::{
+    self, ResponseFormat, ScryfallCatalog, ScryfallError, ScryfallList, SCRYFALL_PAGE_SIZE,
+};
+use crate::api::cors::CorsConfig;
+use crate::api::export::{CsvColumns, ExportFormat};
+use crate::api::health::HealthConfig;
+use crate::api::http_cache::{CacheHint, CachedRoute, HttpCacheConfig};
+use crate::api::parquet;
+use crate::audit::AdminAuditLog;
+use crate::background::bulk_refresh::BulkRefreshConfig;
+use crate::background::card_repair::{DEFAULT_REPAIR_LIMIT, MAX_REPAIR_LIMIT};
+use crate::background::StartupStatus;
+use crate::cache::analytics::TopQuery;
+use crate::cache::manager::{CacheManager, CacheStats, SearchPage};
+use crate::cache::warmup::{CacheWarmer, WarmupRun};
+use crate::circuit_breaker::{CircuitBreakerMetrics, CircuitState};
+use crate::collections::{self, CollectionValue};
+use crate::config::{BatchConfig, IdempotencyConfig};
+use crate::db::{AutocompleteOptions, CardProjection};
+use crate::deadline::RequestTimeoutConfig;
+use crate::errors::{ErrorCode, ErrorResponse, ServiceError};
+use crate::graphql::{GraphQLConfig, GraphQLSchema};
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::models::bulk_import::{BulkImport, BulkImportError};
+use crate::models::card::{parse_lang_code, Card, CardFace, ExternalId, RelatedCard};
+use crate::models::collection::{CollectionEntry, Finish};
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::RepairReport;
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::SchemaVersion;
+use crate::query::{
+    CursorSigner, QueryLimits, QueryLimitsUpdate, QueryParser, QueryValidator, SearchCursor,
+    SearchOrder, SortDirection, SortOrder, UniqueMode,
+};
+use crate::render::{self, RenderFormat, RenderedCard, Symbology};
 use crate::scryfall::bulk_loader::BulkLoader;
+use crate::scryfall::preview::{PreviewPollStatus, PreviewPoller};
+use crate::tenant::{TenantRegistry, TenantStats};
+use crate::utils::features;
 
 lazy_static::lazy_static! {
     static ref START_TIME: Instant = Instant::now();
@@ -28,8 +61,41 @@ pub struct AppStateInner {
     pub cache_manager: Arc<CacheManager>,
     pub bulk_loader: BulkLoader,
     pub query_validator: QueryValidator,
+    pub cursor_signer: CursorSigner,
     pub graphql_schema: GraphQLSchema,
     pub instance_id: String,
+    pub startup: Arc<StartupStatus>,
+    pub health: HealthConfig,
+    pub warmer: Arc<CacheWarmer>,
+    /// Default envelope for the Scryfall-compatible card endpoints
+    pub response_format: ResponseFormat,
+    /// Also mount the Scryfall-compatible routes under `/scryfall`
+    pub scryfall_compat_prefix: bool,
+    /// Serve Swagger UI at `/docs`
+    pub swagger_ui: bool,
+    /// Cross-origin access for browser clients
+    pub cors: CorsConfig,
+    /// `Cache-Control` lifetimes of card and search responses
+    pub http_cache: HttpCacheConfig,
+    pub batch: BatchConfig,
+    /// Replay of responses to admin and batch POSTs repeating an `Idempotency-Key`
+    pub idempotency: IdempotencyConfig,
+    /// Default and maximum time budget of a request
+    pub request_timeout: RequestTimeoutConfig,
+    pub bulk_refresh: BulkRefreshConfig,
+    pub graphql: GraphQLConfig,
+    /// Replica mode: admin mutations are rejected with 403
+    pub read_only: bool,
+    /// Admin routes and `/metrics` are served by a separate listener, not the API router
+    pub admin_port: Option<u16>,
+    /// Bearer token required for the admin routes and `/metrics`
+    pub admin_token: Option<String>,
+    /// Tenants seen since startup, with their rate limits and usage counters
+    pub tenants: Arc<TenantRegistry>,
+    /// Durable trail of admin mutations
+    pub audit_log: Arc<AdminAuditLog>,
+    /// Fetches newly previewed cards between bulk loads
+    pub preview_poller: Arc<PreviewPoller>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -41,6 +107,8 @@ pub struct AdminOverview {
     pub cache_entries_total: i64,
     pub bulk_last_import: Option<String>,
     pub bulk_reload_recommended: bool,
+    /// Preview poller settings and activity since startup
+    pub preview_poll: PreviewPollStatus,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -50,6 +118,155 @@ pub struct AdminOverviewResponse {
     pub error: Option<crate::errors::response::ErrorDetail>,
 }
 
+/// Build and runtime capabilities of this binary
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeaturesReport {
+    pub version: String,
+    /// CPU architecture the binary was compiled for (e.g. `x86_64`, `aarch64`)
+    pub target_arch: String,
+    pub target_os: String,
+    /// Cargo features enabled at compile time
+    pub compiled_features: Vec<String>,
+    pub database_backend: String,
+    /// TLS implementation for outbound HTTPS (`native-tls`, `rustls` or `none`)
+    pub tls_backend: String,
+    /// Status of optional subsystems detected at runtime
+    pub subsystems: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeaturesResponse {
+    pub success: bool,
+    pub data: Option<FeaturesReport>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CircuitBreakersResponse {
+    pub success: bool,
+    pub data: Option<Vec<CircuitBreakerMetrics>>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueryLimitsResponse {
+    pub success: bool,
+    pub data: Option<QueryLimits>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CircuitBreakerResponse {
+    pub success: bool,
+    pub data: Option<CircuitBreakerMetrics>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueryCacheEntriesResponse {
+    pub success: bool,
+    pub data: Option<Vec<QueryCacheEntry>>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueryCachePurgeResponse {
+    pub success: bool,
+    pub data: Option<QueryCachePurge>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TopQueriesResponse {
+    pub success: bool,
+    pub data: Option<Vec<TopQuery>>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkImportsResponse {
+    pub success: bool,
+    pub data: Option<Vec<BulkImport>>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Parse failures recorded for one bulk import
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkImportErrors {
+    pub import_id: i32,
+    /// Recorded failures per category. At most 1000 failures are recorded per import;
+    /// `failed_cards` in the import history has the full count.
+    pub categories: std::collections::BTreeMap<String, usize>,
+    /// Failures in file order, filtered by `category` and capped at `limit`
+    pub errors: Vec<BulkImportError>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkImportErrorsResponse {
+    pub success: bool,
+    pub data: Option<BulkImportErrors>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Restore response: the import history entry recorded for the snapshot
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreResponse {
+    pub success: bool,
+    pub data: Option<BulkImport>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Card repair response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepairResponse {
+    pub success: bool,
+    pub data: Option<RepairReport>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Card repair parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct RepairParams {
+    /// Most cards to re-fetch (default 1000, at most 10000)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminAuditResponse {
+    pub success: bool,
+    pub data: Option<Vec<AdminAuditEntry>>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TenantStatsResponse {
+    pub success: bool,
+    pub data: Option<Vec<TenantStats>>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SchemaVersionResponse {
+    pub success: bool,
+    pub data: Option<SchemaVersion>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Cache warm-up configuration and most recent run
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WarmupStatus {
+    /// Number of queries in the warm-up list
+    pub configured_queries: usize,
+    pub last_run: Option<WarmupRun>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WarmupStatusResponse {
+    pub success: bool,
+    pub data: Option<WarmupStatus>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
 /// Generic API response wrapper
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ApiResponse<T> {
@@ -82,6 +299,27 @@ pub struct SearchParams {
     pub page: Option<usize>,
     /// Number of results per page (default: 100, max: 1000)
     pub page_size: Option<usize>,
+    /// Opaque cursor from a previous response's `next_cursor`. When set, results resume
+    /// after the cursor (keyset pagination) and `page` is ignored.
+    pub cursor: Option<String>,
+    /// Omit `raw_json` and `card_faces` from each card (returned as `{}` and `null`),
+    /// which makes large pages much cheaper to read and serialize (default: false)
+    pub summary: Option<bool>,
+    /// Which printings to return: `cards` (one per card), `prints` (every printing) or
+    /// `art` (one per unique artwork). Deduplicated searches return the most recently
+    /// released printing of each group (default: server setting, `cards` unless changed)
+    pub unique: Option<String>,
+    /// Sort field: `name`, `released`, `usd`, `eur`, `tix`, `edhrec` or `penny`. Cards
+    /// without a value sort last; ties fall back to name (default: `name`)
+    pub order: Option<String>,
+    /// Sort direction: `auto`, `asc` or `desc`. `auto` puts the newest and most expensive
+    /// cards first and sorts names and ranks ascending (default: `auto`)
+    pub dir: Option<String>,
+    /// Response envelope: `native` or `scryfall` (default: server setting). `csv` streams
+    /// every match as CSV instead of a page; `limit` caps the rows
+    pub format: Option<String>,
+    /// Comma-separated columns for `format=csv`, in output order (default: all)
+    pub columns: Option<String>,
 }
 
 /// Paginated response wrapper
@@ -99,6 +337,12 @@ pub struct PaginatedResponse<T> {
     pub total_pages: usize,
     /// Whether there are more pages available
     pub has_more: bool,
+    /// Cursor for fetching the next page with keyset pagination (absent on the last page)
+    pub next_cursor: Option<String>,
+    /// Results came from Scryfall, which had more matches than the configured
+    /// `SCRYFALL_SEARCH_MAX_PAGES` / `SCRYFALL_SEARCH_MAX_CARDS` cap let us fetch.
+    /// `total` still counts every match; pages past the fetched cards are empty.
+    pub truncated: bool,
 }
 
 /// Named card lookup parameters
@@ -108,6 +352,234 @@ pub struct NamedParams {
     pub fuzzy: Option<String>,
     /// Exact card name search (case-insensitive)
     pub exact: Option<String>,
+    /// Return the printing in this language (Scryfall language code, e.g. `ja`, `de`).
+    /// Localized printed names match too. Defaults to preferring English printings.
+    pub lang: Option<String>,
+    /// Allow tokens and emblems to match (default: false)
+    pub include_tokens: Option<bool>,
+    /// Response envelope: `native` or `scryfall` (default: server setting)
+    pub format: Option<String>,
+}
+
+/// Export query parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct ExportParams {
+    /// Output format: `ndjson` (default, raw Scryfall card objects) or `csv`
+    pub format: Option<String>,
+    /// Optional Scryfall query restricting which cards are exported
+    pub q: Option<String>,
+}
+
+/// Query cache listing parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct QueryCacheListParams {
+    /// Maximum entries to return (default 100, max 1000)
+    pub limit: Option<i64>,
+}
+
+/// Bulk import history parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct BulkImportListParams {
+    /// Maximum imports to return (default 20, max 1000)
+    pub limit: Option<i64>,
+}
+
+/// Bulk import error parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct BulkImportErrorParams {
+    /// Only failures in this category, e.g. `missing_name`
+    pub category: Option<String>,
+    /// Maximum failures to return (default 100, max 1000)
+    pub limit: Option<usize>,
+}
+
+/// Admin audit log parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct AdminAuditParams {
+    /// Maximum entries to return (default 100, max 1000)
+    pub limit: Option<i64>,
+    /// Only entries of this actor, e.g. `anonymous` or `key:<fingerprint>`
+    pub actor: Option<String>,
+}
+
+/// Top queries parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct TopQueriesParams {
+    /// Maximum queries to return (default 20, max 1000)
+    pub limit: Option<usize>,
+}
+
+/// Number of cached query entries evicted from each tier
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueryCachePurge {
+    pub database_entries: u64,
+    pub redis_entries: u64,
+}
+
+/// Rendered card text parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct RenderParams {
+    /// Output format: `html` (default) or `markdown`
+    pub format: Option<String>,
+}
+
+/// Random card query parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct RandomParams {
+    /// Optional Scryfall query restricting which cards can be picked
+    pub q: Option<String>,
+}
+
+/// Card sample query parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct SampleParams {
+    /// Optional Scryfall query restricting which cards are sampled
+    pub q: Option<String>,
+    /// Sample size (default 100, at most the configured `max_results`)
+    pub n: Option<usize>,
+    /// Seed of the sample; the same seed draws the same cards. Random when omitted.
+    pub seed: Option<u64>,
+}
+
+/// Parquet search export query parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct ParquetParams {
+    /// Scryfall query selecting the exported cards
+    pub q: String,
+}
+
+/// Card identifier for `/cards/collection`, using Scryfall's identifier format.
+/// Supported combinations: `id`, `name`, `name` + `set`, `set` + `collector_number`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CollectionIdentifier {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collector_number: Option<String>,
+}
+
+/// Scryfall-compatible collection request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CollectionRequest {
+    /// Card identifiers to resolve (max 75, as on Scryfall)
+    pub identifiers: Vec<CollectionIdentifier>,
+}
+
+/// Collection lookup result
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectionData {
+    /// Cards found, in request order
+    pub data: Vec<Card>,
+    /// Identifiers that matched no card
+    pub not_found: Vec<CollectionIdentifier>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectionResponse {
+    pub success: bool,
+    pub data: Option<CollectionData>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Owned quantity of a printing, identified by `id` or by `set` + `collector_number`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CollectionCardInput {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collector_number: Option<String>,
+    /// `nonfoil` (default), `foil` or `etched`
+    #[serde(default)]
+    pub finish: Finish,
+    /// Copies owned, replacing the stored quantity; 0 removes the entry
+    pub quantity: i32,
+}
+
+/// Collection import request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CollectionCardsRequest {
+    /// Owned quantities to store (max `BATCH_MAX_IDS`)
+    pub cards: Vec<CollectionCardInput>,
+}
+
+/// Collection import result
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectionUpsertData {
+    pub collection_id: String,
+    /// Entries stored with a positive quantity
+    pub upserted: usize,
+    /// Entries removed by a quantity of 0
+    pub removed: usize,
+    /// Inputs that matched no card; nothing was stored for them
+    pub not_found: Vec<CollectionCardInput>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectionUpsertResponse {
+    pub success: bool,
+    pub data: Option<CollectionUpsertData>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Collection valuation query parameters
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct CollectionValueParams {
+    /// `usd` (default), `eur` or `tix`
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectionValueResponse {
+    pub success: bool,
+    pub data: Option<CollectionValue>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Longest accepted saved search name
+const MAX_SAVED_SEARCH_NAME_LENGTH: usize = 200;
+
+/// Saved search registration request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SavedSearchRequest {
+    /// Scryfall query re-evaluated after every bulk import, e.g. `set:mkm r:mythic`
+    pub query: String,
+    /// Optional label (max 200 characters)
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SavedSearchResponse {
+    pub success: bool,
+    pub data: Option<SavedSearch>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SavedSearchListResponse {
+    pub success: bool,
+    pub data: Option<Vec<SavedSearch>>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// A saved search with the cards that first matched it in its latest evaluation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SavedSearchNewData {
+    pub search: SavedSearch,
+    /// New matches, ordered by name; empty until the first bulk import after registration
+    pub cards: Vec<Card>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SavedSearchNewResponse {
+    pub success: bool,
+    pub data: Option<SavedSearchNewData>,
+    pub error: Option<crate::errors::response::ErrorDetail>,
 }
 
 /// Autocomplete query parameters
@@ -115,6 +587,17 @@ pub struct NamedParams {
 pub struct AutocompleteParams {
     /// Card name prefix to search for (e.g., "light" matches "Lightning Bolt")
     pub q: String,
+    /// Maximum names to return (default 20, max 100)
+    pub limit: Option<i64>,
+    /// Also suggest extras: tokens, emblems, art cards, planes, schemes and vanguards
+    /// (default: false)
+    #[serde(default)]
+    pub include_extras: bool,
+    /// Leave out names only printed in digital-only products (default: false)
+    #[serde(default)]
+    pub exclude_digital: bool,
+    /// Response envelope: `native` or `scryfall` (default: server setting)
+    pub format: Option<String>,
 }
 
 // Concrete response types for OpenAPI generation
@@ -129,6 +612,39 @@ pub struct CardResponse {
     pub error: Option<crate::errors::response::ErrorDetail>,
 }
 
+/// Card faces response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CardFacesResponse {
+    /// Whether the request was successful
+    pub success: bool,
+    /// Faces in printed order (empty for single-faced cards)
+    pub data: Option<Vec<CardFace>>,
+    /// Error details (present if success is false)
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Related cards response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RelatedCardsResponse {
+    /// Whether the request was successful
+    pub success: bool,
+    /// Linked cards grouped by component (empty when the card has none)
+    pub data: Option<Vec<RelatedCard>>,
+    /// Error details (present if success is false)
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// Rendered card text response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RenderedCardResponse {
+    /// Whether the request was successful
+    pub success: bool,
+    /// Rendered text of each face
+    pub data: Option<RenderedCard>,
+    /// Error details (present if success is false)
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
 /// Paginated card list response
 #[derive(Debug, Serialize, ToSchema)]
 pub struct CardListResponse {
@@ -155,6 +671,30 @@ pub struct PaginatedCardData {
     pub total_pages: usize,
     /// Whether there are more pages available
     pub has_more: bool,
+    /// Cursor for fetching the next page with keyset pagination (absent on the last page)
+    pub next_cursor: Option<String>,
+}
+
+/// Card sample response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SampleResponse {
+    /// Whether the request was successful
+    pub success: bool,
+    /// Response data (present if success is true)
+    pub data: Option<SampleData>,
+    /// Error details (present if success is false)
+    pub error: Option<crate::errors::response::ErrorDetail>,
+}
+
+/// A reproducible sample of matching cards
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SampleData {
+    /// Seed the sample was drawn with; pass it again to get the same cards
+    pub seed: u64,
+    /// Number of cards matching the query
+    pub total: usize,
+    /// Sampled cards (fewer than `n` when fewer cards match)
+    pub data: Vec<Card>,
 }
 
 /// Cache statistics response
@@ -299,9 +839,7 @@ pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
         },
         "environment": {
             "rust_version": env!("CARGO_PKG_RUST_VERSION", "unknown"),
-            "database": std::env::var("DATABASE_URL")
-                .map(|url| if url.starts_with("postgres") { "postgresql" } else { "sqlite" })
-                .unwrap_or("unknown"),
+            "database": if cfg!(feature = "sqlite") { "sqlite" } else { "postgresql" },
         },
         "uptime_seconds": START_TIME.elapsed().as_secs(),
     }))
@@ -332,8 +870,8 @@ pub async fn health_live(State(state): State<AppState>) -> impl IntoResponse {
     path = "/health/ready",
     tag = "health",
     responses(
-        (status = 200, description = "Service is ready to receive traffic", body = serde_json::Value),
-        (status = 503, description = "Service is not ready", body = serde_json::Value)
+        (status = 200, description = "Service is ready to receive traffic; status is `degraded` when Redis, a circuit breaker or Scryfall is unhealthy", body = serde_json::Value),
+        (status = 503, description = "Database is unreachable or bulk data is still loading", body = serde_json::Value)
     )
 )]
 pub async fn health_ready(State(state): State<AppState>) -> impl IntoResponse {
@@ -356,15 +894,76 @@ pub async fn health_ready(State(state): State<AppState>) -> impl IntoResponse {
         }
     };
 
-    let status = if db_ok {
-        StatusCode::OK
+    let load_state = state.startup.state();
+    checks.insert(
+        "bulk_data".to_string(),
+        serde_json::Value::String(load_state.as_str().to_string()),
+    );
+    let data_ready = state.startup.is_ready();
+
+    // Optional dependencies only degrade readiness; the service can still answer
+    // from the database without them
+    let mut degraded = false;
+
+    if state.health.check_redis {
+        let redis = if !state.cache_manager.redis_enabled() {
+            "disabled".to_string()
+        } else {
+            match state.cache_manager.test_redis_connection().await {
+                Ok(()) => "ok".to_string(),
+                Err(e) => {
+                    degraded = true;
+                    format!("error: {}", e)
+                }
+            }
+        };
+        checks.insert("redis".to_string(), serde_json::Value::String(redis));
+    }
+
+    if state.health.check_circuit_breakers {
+        let mut breakers = serde_json::Map::new();
+        for breaker in state.cache_manager.circuit_breakers().all() {
+            let breaker_state = breaker.state().await;
+            degraded |= breaker_state != CircuitState::Closed;
+            breakers.insert(
+                breaker.name().to_string(),
+                serde_json::Value::String(breaker_state.as_str().to_string()),
+            );
+        }
+        checks.insert(
+            "circuit_breakers".to_string(),
+            serde_json::Value::Object(breakers),
+        );
+    }
+
+    if state.health.check_upstream {
+        let scryfall = match state
+            .cache_manager
+            .check_upstream(state.health.upstream_cache_ttl)
+            .await
+        {
+            Ok(()) => "ok".to_string(),
+            Err(e) => {
+                degraded = true;
+                format!("error: {}", e)
+            }
+        };
+        checks.insert("scryfall".to_string(), serde_json::Value::String(scryfall));
+    }
+
+    let (status, label) = if !db_ok {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    } else if !data_ready {
+        (StatusCode::SERVICE_UNAVAILABLE, "loading")
+    } else if degraded {
+        (StatusCode::OK, "degraded")
     } else {
-        StatusCode::SERVICE_UNAVAILABLE
+        (StatusCode::OK, "ready")
     };
     (
         status,
         Json(serde_json::json!({
-            "status": if db_ok { "ready" } else { "not_ready" },
+            "status": label,
             "service": "scryfall-cache",
             "version": env!("CARGO_PKG_VERSION"),
             "instance_id": state.instance_id.clone(),
@@ -374,37 +973,220 @@ pub async fn health_ready(State(state): State<AppState>) -> impl IntoResponse {
     )
 }
 
-/// Admin: overview stats for dashboard
+/// Admin: most frequently searched queries since startup
 #[utoipa::path(
     get,
-    path = "/api/admin/stats/overview",
+    path = "/api/admin/stats/top-queries",
     tag = "admin",
+    params(TopQueriesParams),
     responses(
-        (status = 200, description = "Admin overview stats", body = AdminOverviewResponse),
-        (status = 500, description = "Internal server error", body = AdminOverviewResponse)
+        (status = 200, description = "Search queries by frequency, most frequent first", body = TopQueriesResponse)
     )
 )]
-pub async fn admin_stats_overview(State(state): State<AppState>) -> impl IntoResponse {
-    let stats = match state.cache_manager.get_stats().await {
-        Ok(s) => s,
-        Err(e) => {
-            return ErrorResponse::database_error(format!("Failed to load stats: {}", e))
-                .into_response();
-        }
-    };
+pub async fn admin_top_queries(
+    State(state): State<AppState>,
+    Query(params): Query<TopQueriesParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(20).clamp(1, 1000);
+    let queries = state.cache_manager.top_queries(limit);
+    (StatusCode::OK, Json(ApiResponse::success(queries))).into_response()
+}
 
-    let bulk_last_import = match state.bulk_loader.last_import_timestamp().await {
-        Ok(ts) => ts.map(|t| t.to_string()),
-        Err(e) => {
-            return ErrorResponse::database_error(format!(
-                "Failed to load bulk import timestamp: {}",
-                e
-            ))
+/// Admin: bulk data import history, one entry per imported bulk data file
+#[utoipa::path(
+    get,
+    path = "/api/admin/imports",
+    tag = "admin",
+    params(BulkImportListParams),
+    responses(
+        (status = 200, description = "Recorded bulk imports, most recent first", body = BulkImportsResponse),
+        (status = 500, description = "Internal server error", body = BulkImportsResponse)
+    )
+)]
+pub async fn admin_list_imports(
+    State(state): State<AppState>,
+    Query(params): Query<BulkImportListParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(20).clamp(1, 1000);
+
+    match state.bulk_loader.recent_imports(limit).await {
+        Ok(imports) => (StatusCode::OK, Json(ApiResponse::success(imports))).into_response(),
+        Err(e) => {
+            error!("Failed to list bulk imports: {}", e);
+            ErrorResponse::database_error(format!("Failed to list bulk imports: {}", e))
+                .into_response()
+        }
+    }
+}
+
+/// Admin: cards of a bulk import that failed to parse, for triaging data-quality issues
+#[utoipa::path(
+    get,
+    path = "/api/admin/imports/{id}/errors",
+    tag = "admin",
+    params(
+        ("id" = i32, Path, description = "Import id from /api/admin/imports"),
+        BulkImportErrorParams
+    ),
+    responses(
+        (status = 200, description = "Recorded parse failures in file order", body = BulkImportErrorsResponse),
+        (status = 404, description = "Unknown import", body = BulkImportErrorsResponse),
+        (status = 500, description = "Internal server error", body = BulkImportErrorsResponse)
+    )
+)]
+pub async fn admin_import_errors(
+    State(state): State<AppState>,
+    Path(import_id): Path<i32>,
+    Query(params): Query<BulkImportErrorParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+
+    let recorded = match state.bulk_loader.import_errors(import_id).await {
+        Ok(Some(recorded)) => recorded,
+        Ok(None) => {
+            return ErrorResponse::new(
+                ErrorCode::NotFound,
+                format!("Unknown bulk import: {}", import_id),
+            )
+            .into_response();
+        }
+        Err(e) => {
+            error!("Failed to list bulk import errors: {}", e);
+            return ErrorResponse::database_error(format!(
+                "Failed to list bulk import errors: {}",
+                e
+            ))
+            .into_response();
+        }
+    };
+
+    let mut categories = std::collections::BTreeMap::new();
+    for error in &recorded {
+        *categories.entry(error.category.clone()).or_insert(0) += 1;
+    }
+    let errors = recorded
+        .into_iter()
+        .filter(|error| {
+            params
+                .category
+                .as_deref()
+                .map_or(true, |category| error.category == category)
+        })
+        .take(limit)
+        .collect();
+
+    let data = BulkImportErrors {
+        import_id,
+        categories,
+        errors,
+    };
+    (StatusCode::OK, Json(ApiResponse::success(data))).into_response()
+}
+
+/// Admin: audit log of admin mutations, most recent first
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit",
+    tag = "admin",
+    params(AdminAuditParams),
+    responses(
+        (status = 200, description = "Recorded admin mutations, most recent first", body = AdminAuditResponse),
+        (status = 500, description = "Internal server error", body = AdminAuditResponse)
+    )
+)]
+pub async fn admin_audit_log(
+    State(state): State<AppState>,
+    Query(params): Query<AdminAuditParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+
+    match state.audit_log.recent(limit, params.actor.as_deref()).await {
+        Ok(entries) => (StatusCode::OK, Json(ApiResponse::success(entries))).into_response(),
+        Err(e) => {
+            error!("Failed to list admin audit log: {}", e);
+            ErrorResponse::database_error(format!("Failed to list admin audit log: {}", e))
+                .into_response()
+        }
+    }
+}
+
+/// Admin: per-tenant usage since startup and query cache entries, ordered by tenant id
+#[utoipa::path(
+    get,
+    path = "/api/admin/stats/tenants",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Usage counters per tenant", body = TenantStatsResponse),
+        (status = 500, description = "Internal server error", body = TenantStatsResponse)
+    )
+)]
+pub async fn admin_tenant_stats(State(state): State<AppState>) -> impl IntoResponse {
+    match state.cache_manager.query_cache_entries_by_tenant().await {
+        Ok(entries) => {
+            let stats = state.tenants.report(&entries);
+            (StatusCode::OK, Json(ApiResponse::success(stats))).into_response()
+        }
+        Err(e) => {
+            error!("Failed to load tenant stats: {}", e);
+            ErrorResponse::database_error(format!("Failed to load tenant stats: {}", e))
+                .into_response()
+        }
+    }
+}
+
+/// Admin: applied database migrations, for checking a deployment is fully migrated
+#[utoipa::path(
+    get,
+    path = "/api/admin/schema/version",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Schema version and applied migrations", body = SchemaVersionResponse),
+        (status = 500, description = "Internal server error", body = SchemaVersionResponse)
+    )
+)]
+pub async fn admin_schema_version(State(state): State<AppState>) -> impl IntoResponse {
+    match state.cache_manager.schema_version().await {
+        Ok(version) => (StatusCode::OK, Json(ApiResponse::success(version))).into_response(),
+        Err(e) => {
+            error!("Failed to read schema version: {}", e);
+            ErrorResponse::database_error(format!("Failed to read schema version: {}", e))
+                .into_response()
+        }
+    }
+}
+
+/// Admin: overview stats for dashboard
+#[utoipa::path(
+    get,
+    path = "/api/admin/stats/overview",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Admin overview stats", body = AdminOverviewResponse),
+        (status = 500, description = "Internal server error", body = AdminOverviewResponse)
+    )
+)]
+pub async fn admin_stats_overview(State(state): State<AppState>) -> impl IntoResponse {
+    let stats = match state.cache_manager.get_stats().await {
+        Ok(s) => s,
+        Err(e) => {
+            return ErrorResponse::database_error(format!("Failed to load stats: {}", e))
+                .into_response();
+        }
+    };
+
+    let bulk_last_import = match state.bulk_loader.last_import_timestamp().await {
+        Ok(ts) => ts.map(|t| t.to_string()),
+        Err(e) => {
+            return ErrorResponse::database_error(format!(
+                "Failed to load bulk import timestamp: {}",
+                e
+            ))
             .into_response();
         }
     };
 
-    let bulk_reload_recommended = match state.bulk_loader.should_load().await {
+    // Local check only: the overview shouldn't wait on Scryfall
+    let bulk_reload_recommended = match state.bulk_loader.is_stale().await {
         Ok(v) => v,
         Err(e) => {
             return ErrorResponse::database_error(format!(
@@ -423,11 +1205,25 @@ pub async fn admin_stats_overview(State(state): State<AppState>) -> impl IntoRes
         cache_entries_total: stats.total_cache_entries,
         bulk_last_import,
         bulk_reload_recommended,
+        preview_poll: state.preview_poller.status(),
     };
 
     (StatusCode::OK, Json(ApiResponse::success(overview))).into_response()
 }
 
+/// Data generation that pagination cursors are bound to: the last bulk import time, so
+/// cursors issued before a refresh are rejected rather than resuming over different data.
+async fn data_generation(state: &AppState) -> i64 {
+    state
+        .bulk_loader
+        .last_import_timestamp()
+        .await
+        .ok()
+        .flatten()
+        .map(|imported_at| imported_at.and_utc().timestamp())
+        .unwrap_or(0)
+}
+
 /// Search for cards
 #[utoipa::path(
     get,
@@ -435,50 +1231,170 @@ pub async fn admin_stats_overview(State(state): State<AppState>) -> impl IntoRes
     tag = "cards",
     params(SearchParams),
     responses(
-        (status = 200, description = "Search results", body = CardListResponse),
+        (status = 200, description = "Search results, or every match as CSV with `format=csv`", body = CardListResponse),
         (status = 500, description = "Internal server error", body = CardListResponse)
     )
 )]
 pub async fn search_cards(
     State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
     Query(params): Query<SearchParams>,
 ) -> impl IntoResponse {
     info!(
-        "Search request: query='{}', limit={:?}, page={:?}, page_size={:?}",
-        params.q, params.limit, params.page, params.page_size
+        "Search request: query='{}', limit={:?}, page={:?}, page_size={:?}, cursor={}",
+        params.q,
+        params.limit,
+        params.page,
+        params.page_size,
+        params.cursor.is_some()
     );
 
+    // CSV output reports errors in the native envelope
+    let csv = params
+        .format
+        .as_deref()
+        .is_some_and(|value| value.eq_ignore_ascii_case("csv"));
+    let format = match state
+        .response_format
+        .negotiate(params.format.as_deref().filter(|_| !csv), &headers)
+    {
+        Ok(_) if csv => ResponseFormat::Native,
+        Ok(format) => format,
+        Err(value) => return invalid_format(value),
+    };
+    let scryfall = format == ResponseFormat::Scryfall;
+
     // Validate query string
     if let Err(e) = state.query_validator.validate_query_string(&params.q) {
-        return ErrorResponse::validation_error(e.to_string()).into_response();
+        return format.error(ErrorResponse::validation_error(e.to_string()));
     }
 
     // Parse and validate query AST
     match QueryParser::parse(&params.q) {
         Ok(ast) => {
             if let Err(e) = state.query_validator.validate_ast(&ast) {
-                return ErrorResponse::validation_error(e.to_string()).into_response();
+                return format.error(ErrorResponse::validation_error(e.to_string()));
             }
         }
         Err(e) => {
-            return ErrorResponse::invalid_query(format!("Query parse error: {}", e))
-                .into_response();
+            return format.error(ErrorResponse::query_parse_error(&e));
         }
     }
 
-    // Use pagination parameters
+    let unique = match params.unique.as_deref() {
+        None => None,
+        Some(value) => match UniqueMode::parse(value) {
+            Some(unique) => Some(unique),
+            None => {
+                return format.error(ErrorResponse::validation_error(format!(
+                    "Invalid unique mode '{}': expected cards, prints or art",
+                    value
+                )));
+            }
+        },
+    };
+
+    let order = match params.order.as_deref() {
+        None => SortOrder::default(),
+        Some(value) => match SortOrder::parse(value) {
+            Some(order) => order,
+            None => {
+                return format.error(ErrorResponse::validation_error(format!(
+                    "Invalid order '{}': expected name, released, usd, eur, tix, edhrec or penny",
+                    value
+                )));
+            }
+        },
+    };
+    let dir = match params.dir.as_deref() {
+        None => SortDirection::default(),
+        Some(value) => match SortDirection::parse(value) {
+            Some(dir) => dir,
+            None => {
+                return format.error(ErrorResponse::validation_error(format!(
+                    "Invalid dir '{}': expected auto, asc or desc",
+                    value
+                )));
+            }
+        },
+    };
+    let order = SearchOrder::new(order, dir);
+
+    if csv {
+        return search_cards_csv(&state, &params, unique, order);
+    }
+
+    // Use pagination parameters (Scryfall pages hold 175 cards)
+    let default_page_size = if scryfall { SCRYFALL_PAGE_SIZE } else { 100 };
     let page = params.page.unwrap_or(1).max(1);
-    let page_size = params.page_size.unwrap_or(100).min(1000).max(1);
+    let page_size = params
+        .page_size
+        .unwrap_or(default_page_size)
+        .min(1000)
+        .max(1);
+
+    // Scryfall clients page with `next_page` URLs, so cursors only apply to native responses
+    let generation = data_generation(&state).await;
+    let cursor = match params
+        .cursor
+        .as_deref()
+        .filter(|_| !scryfall)
+        .map(|token| state.cursor_signer.verify(token, &params.q, generation))
+    {
+        Some(Ok(cursor)) => Some(cursor),
+        Some(Err(e)) => return ErrorResponse::invalid_cursor(e).into_response(),
+        None => None,
+    };
+    if cursor.is_some() && !order.supports_cursor() {
+        return ErrorResponse::validation_error(
+            "cursor can only be used with the default order (order=name, dir=asc)",
+        )
+        .into_response();
+    }
+
+    // Scryfall card objects are the stored raw JSON, so they always need the full row
+    let projection = if params.summary.unwrap_or(false) && !scryfall {
+        CardProjection::SUMMARY
+    } else {
+        CardProjection::ALL
+    };
 
     // Use the new paginated search which is much faster
     match state
         .cache_manager
-        .search_paginated(&params.q, page, page_size)
+        .search_paginated_projected(
+            &params.q,
+            unique,
+            order,
+            page,
+            page_size,
+            cursor.as_ref(),
+            &projection,
+        )
         .await
     {
-        Ok((cards, total)) => {
+        Ok(SearchPage {
+            cards,
+            total,
+            truncated,
+        }) => {
             let total_pages = total.div_ceil(page_size);
-            let has_more = page < total_pages;
+            let has_more = if cursor.is_some() {
+                cards.len() == page_size
+            } else {
+                page < total_pages
+            };
+            // Cursors resume in (name, id) order, so other orders page by number
+            let next_cursor = if has_more && order.supports_cursor() {
+                cards.last().map(|card| {
+                    state
+                        .cursor_signer
+                        .sign(&SearchCursor::after(card), &params.q, generation)
+                })
+            } else {
+                None
+            };
 
             info!(
                 "Search returned {} cards (page {}/{}), {} total matches",
@@ -487,6 +1403,23 @@ pub async fn search_cards(
                 total_pages,
                 total
             );
+            let hint = CacheHint::cards(CachedRoute::Search, &cards);
+
+            if scryfall {
+                if total == 0 {
+                    return ScryfallError::no_matches().into_response();
+                }
+                let next_page = has_more.then(|| {
+                    let query = uri.query().unwrap_or_default();
+                    compat::next_page_url(&headers, uri.path(), query, page + 1)
+                });
+                let mut list = ScryfallList::cards(cards, total, has_more, next_page);
+                if truncated {
+                    list.warnings
+                        .push(format!("Not all of the {} matching cards were fetched", total));
+                }
+                return hint.attach((StatusCode::OK, Json(list)));
+            }
 
             let response = PaginatedResponse {
                 data: cards,
@@ -495,46 +1428,98 @@ pub async fn search_cards(
                 page_size,
                 total_pages,
                 has_more,
+                next_cursor,
+                truncated,
             };
 
-            (StatusCode::OK, Json(ApiResponse::success(response))).into_response()
+            hint.attach((StatusCode::OK, Json(ApiResponse::success(response))))
         }
         Err(e) => {
             error!("Search failed: {}", e);
-
-            // Map error type to appropriate error code
-            let error_message = e.to_string();
-            if error_message.contains("Scryfall API error")
-                || error_message.contains("Scryfall API unavailable")
-                || error_message.contains("Circuit breaker")
-            {
-                ErrorResponse::new(
-                    ErrorCode::ScryfallApiError,
-                    format!("Upstream Scryfall failure: {}", e),
-                )
-                .into_response()
-            } else if error_message.contains("database")
-                || error_message.contains("connection")
-                || error_message.contains("pool")
-            {
-                ErrorResponse::database_error(format!("Database error during search: {}", e))
-                    .into_response()
-            } else {
-                ErrorResponse::internal_error(format!("Search failed: {}", e)).into_response()
-            }
+            format.error(e)
         }
     }
 }
 
+/// Rejection for an unknown `format` parameter (always in the native envelope)
+fn invalid_format(value: &str) -> axum::response::Response {
+    ErrorResponse::validation_error(format!(
+        "Invalid format '{}': expected 'native' or 'scryfall'",
+        value
+    ))
+    .into_response()
+}
+
+/// Stream every match of a validated search as CSV, one row per card as it is read
+fn search_cards_csv(
+    state: &AppState,
+    params: &SearchParams,
+    unique: Option<UniqueMode>,
+    order: SearchOrder,
+) -> axum::response::Response {
+    let columns = match params.columns.as_deref() {
+        None => CsvColumns::default(),
+        Some(value) => match CsvColumns::parse(value) {
+            Ok(columns) => columns,
+            Err(e) => return ErrorResponse::validation_error(e).into_response(),
+        },
+    };
+    let limit = params.limit.filter(|&limit| limit > 0);
+
+    let cards = match state
+        .cache_manager
+        .stream_search(&params.q, unique, order, limit)
+    {
+        Ok(cards) => cards,
+        Err(e) => {
+            error!("CSV search failed: {}", e);
+            return ErrorResponse::database_error(format!("Failed to search cards: {}", e))
+                .into_response();
+        }
+    };
+
+    let header_row = futures::stream::iter([Ok::<_, anyhow::Error>(columns.header())]);
+    let rows = cards.map(move |card| {
+        card.map(|card| columns.row(&card)).map_err(|e| {
+            error!("CSV search stream failed: {}", e);
+            e
+        })
+    });
+
+    let format = ExportFormat::Csv;
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.file_name()),
+            ),
+        ],
+        axum::body::Body::from_stream(header_row.chain(rows)),
+    )
+        .into_response()
+}
+
 /// Batch fetch cards by ID
 #[utoipa::path(
     post,
     path = "/cards/batch",
     tag = "cards",
-    request_body = BatchCardsRequest,
+    request_body(
+        content = BatchCardsRequest,
+        example = json!({
+            "ids": [
+                "e3285e6b-3e79-4d7c-bf96-d920f973b80d",
+                "56ebc372-aabd-4174-a943-c7bf59e5028d"
+            ],
+            "fetch_missing": false
+        })
+    ),
     responses(
         (status = 200, description = "Batch card lookup result", body = BatchCardsResponse),
         (status = 400, description = "Bad request", body = BatchCardsResponse),
+        (status = 413, description = "Body too large, too deeply nested or too many items", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = BatchCardsResponse)
     )
 )]
@@ -542,10 +1527,7 @@ pub async fn batch_get_cards(
     State(state): State<AppState>,
     Json(req): Json<BatchCardsRequest>,
 ) -> impl IntoResponse {
-    let max_ids: usize = std::env::var("BATCH_MAX_IDS")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(1000);
+    let max_ids = state.batch.max_ids;
 
     if req.ids.is_empty() {
         return ErrorResponse::validation_error("ids must not be empty").into_response();
@@ -572,7 +1554,7 @@ pub async fn batch_get_cards(
         }
         Err(e) => {
             error!("Batch get cards failed: {}", e);
-            ErrorResponse::internal_error(format!("Batch get cards failed: {}", e)).into_response()
+            e.into_response()
         }
     }
 }
@@ -582,10 +1564,14 @@ pub async fn batch_get_cards(
     post,
     path = "/cards/named/batch",
     tag = "cards",
-    request_body = BatchNamedRequest,
+    request_body(
+        content = BatchNamedRequest,
+        example = json!({"names": ["Lightning Bolt", "Counterspell", "sol ring"], "fuzzy": true})
+    ),
     responses(
         (status = 200, description = "Batch named card lookup result", body = BatchNamedResponse),
         (status = 400, description = "Bad request", body = BatchNamedResponse),
+        (status = 413, description = "Body too large, too deeply nested or too many items", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = BatchNamedResponse)
     )
 )]
@@ -593,10 +1579,7 @@ pub async fn batch_get_cards_by_name(
     State(state): State<AppState>,
     Json(req): Json<BatchNamedRequest>,
 ) -> impl IntoResponse {
-    let max_names: usize = std::env::var("BATCH_MAX_NAMES")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(50);
+    let max_names = state.batch.max_names;
 
     if req.names.is_empty() {
         return ErrorResponse::validation_error("names must not be empty").into_response();
@@ -612,47 +1595,25 @@ pub async fn batch_get_cards_by_name(
 
     let fuzzy = req.fuzzy.unwrap_or(true);
 
-    let parallelism: usize = std::env::var("BATCH_PARALLELISM")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(4)
-        .max(1)
-        .min(32);
-
-    let mut indexed: Vec<(usize, BatchNamedResult)> = futures::stream::iter(
-        req.names
-            .into_iter()
-            .enumerate()
-            .map(|(idx, name)| (idx, name)),
-    )
-    .map(|(idx, name)| {
-        let state = state.clone();
-        async move {
-            let res = match state.cache_manager.search_by_name(&name, fuzzy).await {
-                Ok(card_opt) => BatchNamedResult {
-                    name,
-                    card: card_opt,
-                },
-                Err(e) => {
-                    error!("Batch named lookup failed: {}", e);
-                    BatchNamedResult { name, card: None }
-                }
-            };
-            (idx, res)
+    let cards = match state
+        .cache_manager
+        .search_by_names_batch(&req.names, fuzzy, state.batch.parallelism)
+        .await
+    {
+        Ok(cards) => cards,
+        Err(e) => {
+            error!("Batch named lookup failed: {}", e);
+            return e.into_response();
         }
-    })
-    .buffer_unordered(parallelism)
-    .collect()
-    .await;
+    };
 
-    indexed.sort_by_key(|(idx, _)| *idx);
-    let mut results = Vec::with_capacity(indexed.len());
+    let mut results = Vec::with_capacity(cards.len());
     let mut not_found = Vec::new();
-    for (_idx, item) in indexed {
-        if item.card.is_none() {
-            not_found.push(item.name.clone());
+    for (name, card) in req.names.into_iter().zip(cards) {
+        if card.is_none() {
+            not_found.push(name.clone());
         }
-        results.push(item);
+        results.push(BatchNamedResult { name, card });
     }
 
     let data = BatchNamedData { results, not_found };
@@ -664,10 +1625,19 @@ pub async fn batch_get_cards_by_name(
     post,
     path = "/queries/batch",
     tag = "cards",
-    request_body = BatchQueriesRequest,
+    request_body(
+        content = BatchQueriesRequest,
+        example = json!({
+            "queries": [
+                {"id": "red-burn", "query": "c:red t:instant cmc<=1", "page_size": 20},
+                {"id": "rocks", "query": "t:artifact o:\"add {c}\"", "page": 2}
+            ]
+        })
+    ),
     responses(
         (status = 200, description = "Batch query execution result", body = BatchQueriesResponse),
         (status = 400, description = "Bad request", body = BatchQueriesResponse),
+        (status = 413, description = "Body too large, too deeply nested or too many items", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = BatchQueriesResponse)
     )
 )]
@@ -675,10 +1645,7 @@ pub async fn batch_execute_queries(
     State(state): State<AppState>,
     Json(req): Json<BatchQueriesRequest>,
 ) -> impl IntoResponse {
-    let max_queries: usize = std::env::var("BATCH_MAX_QUERIES")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(10);
+    let max_queries = state.batch.max_queries;
 
     if req.queries.is_empty() {
         return ErrorResponse::validation_error("queries must not be empty").into_response();
@@ -692,12 +1659,9 @@ pub async fn batch_execute_queries(
         .into_response();
     }
 
-    let parallelism: usize = std::env::var("BATCH_PARALLELISM")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(4)
-        .max(1)
-        .min(32);
+    let parallelism = state.batch.parallelism;
+
+    let generation = data_generation(&state).await;
 
     let mut indexed: Vec<(usize, BatchQueryResult)> =
         futures::stream::iter(req.queries.into_iter().enumerate())
@@ -751,10 +1715,29 @@ pub async fn batch_execute_queries(
                     let page = item.page.unwrap_or(1).max(1);
                     let page_size = item.page_size.unwrap_or(100).min(1000).max(1);
 
-                    match state.cache_manager.search_paginated(&query, page, page_size).await {
-                        Ok((cards, total)) => {
+                    match state
+                        .cache_manager
+                        .search_paginated(&query, page, page_size, None)
+                        .await
+                    {
+                        Ok(SearchPage {
+                            cards,
+                            total,
+                            truncated,
+                        }) => {
                             let total_pages = total.div_ceil(page_size);
                             let has_more = page < total_pages;
+                            let next_cursor = if has_more {
+                                cards.last().map(|card| {
+                                    state.cursor_signer.sign(
+                                        &SearchCursor::after(card),
+                                        &query,
+                                        generation,
+                                    )
+                                })
+                            } else {
+                                None
+                            };
                             let data = PaginatedResponse {
                                 data: cards,
                                 total,
@@ -762,6 +1745,8 @@ pub async fn batch_execute_queries(
                                 page_size,
                                 total_pages,
                                 has_more,
+                                next_cursor,
+                                truncated,
                             };
                             (
                                 idx,
@@ -816,7 +1801,8 @@ pub async fn get_card(State(state): State<AppState>, Path(id): Path<Uuid>) -> im
     match state.cache_manager.get_card(id).await {
         Ok(Some(card)) => {
             info!("Found card: {}", card.name);
-            (StatusCode::OK, Json(ApiResponse::success(card))).into_response()
+            let hint = CacheHint::card(CachedRoute::Card, &card);
+            hint.attach((StatusCode::OK, Json(ApiResponse::success(card))))
         }
         Ok(None) => {
             info!("Card not found: {}", id);
@@ -824,127 +1810,1601 @@ pub async fn get_card(State(state): State<AppState>, Path(id): Path<Uuid>) -> im
         }
         Err(e) => {
             error!("Get card failed: {}", e);
-            let error_message = e.to_string();
-            if error_message.contains("Scryfall API error")
-                || error_message.contains("Scryfall API unavailable")
-                || error_message.contains("Circuit breaker")
-            {
-                ErrorResponse::new(
-                    ErrorCode::ScryfallApiError,
-                    format!("Upstream Scryfall failure: {}", e),
-                )
-                .into_response()
-            } else if error_message.contains("database")
-                || error_message.contains("connection")
-                || error_message.contains("pool")
-            {
-                ErrorResponse::database_error(format!("Failed to fetch card: {}", e))
-                    .into_response()
-            } else {
-                ErrorResponse::internal_error(format!("Failed to fetch card: {}", e))
-                    .into_response()
-            }
+            e.into_response()
         }
     }
 }
 
-/// Get a card by name (fuzzy or exact)
+/// Get a specific printing by set code and collector number
 #[utoipa::path(
     get,
-    path = "/cards/named",
+    path = "/cards/{set}/{number}",
     tag = "cards",
-    params(NamedParams),
+    params(
+        ("set" = String, Path, description = "Set code (e.g. `lea`)"),
+        ("number" = String, Path, description = "Collector number (e.g. `161`)")
+    ),
     responses(
         (status = 200, description = "Card found", body = CardResponse),
-        (status = 400, description = "Bad request - must provide fuzzy or exact parameter", body = CardResponse),
         (status = 404, description = "Card not found", body = CardResponse),
         (status = 500, description = "Internal server error", body = CardResponse)
     )
 )]
-pub async fn get_card_by_name(
+pub async fn get_card_by_set_and_number(
     State(state): State<AppState>,
-    Query(params): Query<NamedParams>,
+    Path((set, number)): Path<(String, String)>,
 ) -> impl IntoResponse {
-    let (name, fuzzy) = if let Some(name) = params.fuzzy {
-        (name, true)
-    } else if let Some(name) = params.exact {
-        (name, false)
-    } else {
-        return ErrorResponse::validation_error("Must provide either 'fuzzy' or 'exact' parameter")
-            .into_response();
-    };
-
-    info!("Get card by name: name='{}', fuzzy={}", name, fuzzy);
+    info!("Get card request: set={}, number={}", set, number);
 
-    match state.cache_manager.search_by_name(&name, fuzzy).await {
+    let lookup = state
+        .cache_manager
+        .get_card_by_set_and_number(&set, &number);
+    match lookup.await {
         Ok(Some(card)) => {
             info!("Found card: {}", card.name);
-            (StatusCode::OK, Json(ApiResponse::success(card))).into_response()
+            let hint = CacheHint::card(CachedRoute::Card, &card);
+            hint.attach((StatusCode::OK, Json(ApiResponse::success(card))))
         }
         Ok(None) => {
-            info!("Card not found: {}", name);
-            ErrorResponse::card_not_found(name).into_response()
+            info!("Card not found: {}/{}", set, number);
+            ErrorResponse::card_not_found(format!("{}/{}", set, number)).into_response()
         }
         Err(e) => {
-            error!("Get card by name failed: {}", e);
-
-            // Map error type to appropriate error code
-            let error_message = e.to_string();
-            if error_message.contains("database")
-                || error_message.contains("connection")
-                || error_message.contains("pool")
-            {
-                ErrorResponse::database_error(format!("Database error: {}", e)).into_response()
-            } else if error_message.contains("scryfall")
-                || error_message.contains("API")
-                || error_message.contains("rate limit")
-            {
-                ErrorResponse::new(
-                    ErrorCode::ScryfallApiError,
-                    format!("Scryfall API error: {}", e),
-                )
-                .into_response()
-            } else {
-                ErrorResponse::internal_error(format!("Failed to search by name: {}", e))
-                    .into_response()
-            }
+            error!("Get card by set and number failed: {}", e);
+            e.into_response()
         }
     }
 }
 
-/// Get cache statistics
+/// Get a card by its Gatherer multiverse id
 #[utoipa::path(
     get,
-    path = "/stats",
-    tag = "statistics",
+    path = "/cards/multiverse/{id}",
+    tag = "cards",
+    params(
+        ("id" = i32, Path, description = "Multiverse id")
+    ),
     responses(
-        (status = 200, description = "Cache statistics", body = StatsResponse),
-        (status = 500, description = "Internal server error", body = StatsResponse)
+        (status = 200, description = "Card found", body = CardResponse),
+        (status = 404, description = "Card not found", body = CardResponse),
+        (status = 500, description = "Internal server error", body = CardResponse)
     )
 )]
-pub async fn get_stats(State(state): State<AppState>) -> impl IntoResponse {
-    info!("Stats request");
-
-    match state.cache_manager.get_stats().await {
-        Ok(stats) => {
-            info!("Stats: {:?}", stats);
-            (StatusCode::OK, Json(ApiResponse::success(stats))).into_response()
-        }
-        Err(e) => {
-            error!("Get stats failed: {}", e);
-            ErrorResponse::database_error(format!("Failed to retrieve stats: {}", e))
-                .into_response()
-        }
-    }
+pub async fn get_card_by_multiverse_id(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    get_card_by_external_id(state, ExternalId::Multiverse(id)).await
 }
 
-/// Force reload bulk data
+/// Get a card by its MTG Arena id
 #[utoipa::path(
-    post,
-    path = "/admin/reload",
-    tag = "admin",
+    get,
+    path = "/cards/arena/{id}",
+    tag = "cards",
+    params(
+        ("id" = i32, Path, description = "Arena id")
+    ),
+    responses(
+        (status = 200, description = "Card found", body = CardResponse),
+        (status = 404, description = "Card not found", body = CardResponse),
+        (status = 500, description = "Internal server error", body = CardResponse)
+    )
+)]
+pub async fn get_card_by_arena_id(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    get_card_by_external_id(state, ExternalId::Arena(id)).await
+}
+
+/// Get a card by its MTGO catalog id (foil or non-foil)
+#[utoipa::path(
+    get,
+    path = "/cards/mtgo/{id}",
+    tag = "cards",
+    params(
+        ("id" = i32, Path, description = "MTGO catalog id")
+    ),
+    responses(
+        (status = 200, description = "Card found", body = CardResponse),
+        (status = 404, description = "Card not found", body = CardResponse),
+        (status = 500, description = "Internal server error", body = CardResponse)
+    )
+)]
+pub async fn get_card_by_mtgo_id(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    get_card_by_external_id(state, ExternalId::Mtgo(id)).await
+}
+
+async fn get_card_by_external_id(
+    state: AppState,
+    external_id: ExternalId,
+) -> axum::response::Response {
+    info!("Get card request: {}", external_id);
+
+    match state
+        .cache_manager
+        .get_card_by_external_id(external_id)
+        .await
+    {
+        Ok(Some(card)) => {
+            info!("Found card: {}", card.name);
+            let hint = CacheHint::card(CachedRoute::Card, &card);
+            hint.attach((StatusCode::OK, Json(ApiResponse::success(card))))
+        }
+        Ok(None) => {
+            info!("Card not found: {}", external_id);
+            ErrorResponse::card_not_found(external_id.to_string()).into_response()
+        }
+        Err(e) => {
+            error!("Get card by {} id failed: {}", external_id.kind(), e);
+            e.into_response()
+        }
+    }
+}
+
+/// Get the faces of a multi-faced card
+#[utoipa::path(
+    get,
+    path = "/cards/{id}/faces",
+    tag = "cards",
+    params(
+        ("id" = Uuid, Path, description = "Card UUID")
+    ),
+    responses(
+        (status = 200, description = "Card faces (empty for single-faced cards)", body = CardFacesResponse),
+        (status = 404, description = "Card not found", body = CardFacesResponse),
+        (status = 500, description = "Internal server error", body = CardFacesResponse)
+    )
+)]
+pub async fn get_card_faces(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    info!("Get card faces request: id={}", id);
+
+    match state.cache_manager.get_card(id).await {
+        Ok(Some(card)) => {
+            (StatusCode::OK, Json(ApiResponse::success(card.faces()))).into_response()
+        }
+        Ok(None) => ErrorResponse::card_not_found(id.to_string()).into_response(),
+        Err(e) => {
+            error!("Get card faces failed: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+/// Get the cards linked to a card through Scryfall's `all_parts`: tokens it creates,
+/// its meld pair or meld result, and combo pieces
+#[utoipa::path(
+    get,
+    path = "/cards/{id}/related",
+    tag = "cards",
+    params(
+        ("id" = Uuid, Path, description = "Card UUID")
+    ),
+    responses(
+        (status = 200, description = "Related cards (empty when the card has none)", body = RelatedCardsResponse),
+        (status = 404, description = "Card not found", body = RelatedCardsResponse),
+        (status = 500, description = "Internal server error", body = RelatedCardsResponse)
+    )
+)]
+pub async fn get_related_cards(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    info!("Get related cards request: id={}", id);
+
+    match state.cache_manager.get_related_cards(id).await {
+        Ok(Some(related)) => (StatusCode::OK, Json(ApiResponse::success(related))).into_response(),
+        Ok(None) => ErrorResponse::card_not_found(id.to_string()).into_response(),
+        Err(e) => {
+            error!("Get related cards failed: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+/// Render a card's oracle text and mana cost as HTML or Markdown, with `{T}`, `{2}{U}` and
+/// the other symbols replaced by Scryfall's symbol images. Multi-faced cards get one entry
+/// per face.
+#[utoipa::path(
+    get,
+    path = "/cards/{id}/rendered",
+    tag = "cards",
+    params(
+        ("id" = Uuid, Path, description = "Card UUID"),
+        RenderParams
+    ),
+    responses(
+        (status = 200, description = "Rendered card text", body = RenderedCardResponse),
+        (status = 400, description = "Invalid format", body = RenderedCardResponse),
+        (status = 404, description = "Card not found", body = RenderedCardResponse),
+        (status = 500, description = "Internal server error", body = RenderedCardResponse)
+    )
+)]
+pub async fn get_card_rendered(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<RenderParams>,
+) -> impl IntoResponse {
+    info!("Render card request: id={}, format={:?}", id, params.format);
+
+    let format = match params.format.as_deref() {
+        None => RenderFormat::Html,
+        Some(value) => match RenderFormat::parse(value) {
+            Some(format) => format,
+            None => {
+                return ErrorResponse::validation_error(format!(
+                    "Unsupported render format '{}' (expected 'html' or 'markdown')",
+                    value
+                ))
+                .into_response();
+            }
+        },
+    };
+
+    let card = match state.cache_manager.get_card(id).await {
+        Ok(Some(card)) => card,
+        Ok(None) => return ErrorResponse::card_not_found(id.to_string()).into_response(),
+        Err(e) => {
+            error!("Render card failed: {}", e);
+            return e.into_response();
+        }
+    };
+
+    // Without the symbol list the text is still rendered, with symbols left as text
+    let symbology = match state
+        .cache_manager
+        .get_reference_data("symbology", "/symbology")
+        .await
+    {
+        Ok(Some(value)) => Symbology::from_scryfall(&value),
+        Ok(None) => Symbology::default(),
+        Err(e) => {
+            warn!("Symbology unavailable, rendering symbols as text: {}", e);
+            Symbology::default()
+        }
+    };
+
+    let rendered = render::render_card(&card, format, &symbology);
+    (StatusCode::OK, Json(ApiResponse::success(rendered))).into_response()
+}
+
+/// Get a random card
+#[utoipa::path(
+    get,
+    path = "/cards/random",
+    tag = "cards",
+    params(RandomParams),
+    responses(
+        (status = 200, description = "Random card", body = CardResponse),
+        (status = 400, description = "Invalid query", body = CardResponse),
+        (status = 404, description = "No card matches the query", body = CardResponse)
+    )
+)]
+pub async fn get_random_card(
+    State(state): State<AppState>,
+    Query(params): Query<RandomParams>,
+) -> impl IntoResponse {
+    let query = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    info!("Random card request: query={:?}", query);
+
+    if let Some(query) = query {
+        if let Err(e) = state.query_validator.validate_query_string(query) {
+            return ErrorResponse::validation_error(e.to_string()).into_response();
+        }
+        match QueryParser::parse(query) {
+            Ok(ast) => {
+                if let Err(e) = state.query_validator.validate_ast(&ast) {
+                    return ErrorResponse::validation_error(e.to_string()).into_response();
+                }
+            }
+            Err(e) => return ErrorResponse::query_parse_error(&e).into_response(),
+        }
+    }
+
+    match state.cache_manager.random_card(query).await {
+        Ok(Some(card)) => (StatusCode::OK, Json(ApiResponse::success(card))).into_response(),
+        Ok(None) => {
+            ErrorResponse::new(ErrorCode::CardNotFound, "No cards match the query").into_response()
+        }
+        Err(e) => {
+            error!("Random card failed: {}", e);
+            ErrorResponse::database_error(format!("Failed to pick random card: {}", e))
+                .into_response()
+        }
+    }
+}
+
+/// Default size of `/cards/sample` samples
+const DEFAULT_SAMPLE_SIZE: usize = 100;
+
+/// Draw a reproducible random sample of locally stored cards, e.g. to extract a dataset
+/// without paging through every match
+#[utoipa::path(
+    get,
+    path = "/cards/sample",
+    tag = "cards",
+    params(SampleParams),
+    responses(
+        (status = 200, description = "Sampled cards", body = SampleResponse),
+        (status = 400, description = "Invalid query or sample size", body = SampleResponse),
+        (status = 500, description = "Internal server error", body = SampleResponse)
+    )
+)]
+pub async fn get_card_sample(
+    State(state): State<AppState>,
+    Query(params): Query<SampleParams>,
+) -> impl IntoResponse {
+    let query = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let n = params.n.unwrap_or(DEFAULT_SAMPLE_SIZE);
+    // Generated seeds stay below 2^32 so JavaScript clients can pass them back exactly
+    let seed = params
+        .seed
+        .unwrap_or_else(|| u64::from(Uuid::new_v4().as_u128() as u32));
+    info!("Sample request: query={:?}, n={}, seed={}", query, n, seed);
+
+    let max_results = state.query_validator.limits().max_results;
+    if n == 0 || n as i64 > max_results {
+        return ErrorResponse::validation_error(format!("n must be between 1 and {}", max_results))
+            .into_response();
+    }
+
+    if let Some(query) = query {
+        if let Err(e) = state.query_validator.validate_query_string(query) {
+            return ErrorResponse::validation_error(e.to_string()).into_response();
+        }
+        match QueryParser::parse(query) {
+            Ok(ast) => {
+                if let Err(e) = state.query_validator.validate_ast(&ast) {
+                    return ErrorResponse::validation_error(e.to_string()).into_response();
+                }
+            }
+            Err(e) => return ErrorResponse::query_parse_error(&e).into_response(),
+        }
+    }
+
+    match state.cache_manager.sample_cards(query, n, seed).await {
+        Ok((data, total)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(SampleData { seed, total, data })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Card sample failed: {}", e);
+            ErrorResponse::database_error(format!("Failed to sample cards: {}", e)).into_response()
+        }
+    }
+}
+
+/// Stream every card matching a query as a Parquet file, for analytics pipelines.
+/// Requires the `parquet_export` feature; returns 404 otherwise.
+#[utoipa::path(
+    get,
+    path = "/cards/search.parquet",
+    tag = "cards",
+    params(ParquetParams),
+    responses(
+        (status = 200, description = "Parquet file with one row per matching card", content_type = "application/vnd.apache.parquet", body = String),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
+        (status = 404, description = "Parquet export not compiled in", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn search_cards_parquet(
+    State(state): State<AppState>,
+    Query(params): Query<ParquetParams>,
+) -> impl IntoResponse {
+    if !cfg!(feature = "parquet_export") {
+        return ErrorResponse::new(
+            ErrorCode::NotFound,
+            "Parquet export not compiled (missing parquet_export feature)",
+        )
+        .into_response();
+    }
+
+    let query = params.q.trim();
+    info!("Parquet export request: query={:?}", query);
+
+    if query.is_empty() {
+        return ErrorResponse::validation_error("Query parameter 'q' is required").into_response();
+    }
+    if let Err(e) = state.query_validator.validate_query_string(query) {
+        return ErrorResponse::validation_error(e.to_string()).into_response();
+    }
+    match QueryParser::parse(query) {
+        Ok(ast) => {
+            if let Err(e) = state.query_validator.validate_ast(&ast) {
+                return ErrorResponse::validation_error(e.to_string()).into_response();
+            }
+        }
+        Err(e) => return ErrorResponse::query_parse_error(&e).into_response(),
+    }
+
+    let cards = match state.cache_manager.export_cards(Some(query)) {
+        Ok(cards) => cards,
+        Err(e) => {
+            error!("Parquet export failed: {}", e);
+            return ErrorResponse::database_error(format!("Failed to export cards: {}", e))
+                .into_response();
+        }
+    };
+
+    match parquet::encode_stream(cards) {
+        Ok(body) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, parquet::CONTENT_TYPE),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"cards.parquet\"",
+                ),
+            ],
+            axum::body::Body::from_stream(body),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Parquet encoder setup failed: {}", e);
+            ErrorResponse::new(ErrorCode::InternalError, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Maximum identifiers per `/cards/collection` request (Scryfall's limit)
+pub(crate) const MAX_COLLECTION_IDENTIFIERS: usize = 75;
+
+/// Whether a value can be embedded in a generated search query as a single token
+fn is_query_token(value: &str) -> bool {
+    !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '(' | ')'))
+}
+
+/// Resolve a list of card identifiers (Scryfall `/cards/collection` format)
+#[utoipa::path(
+    post,
+    path = "/cards/collection",
+    tag = "cards",
+    request_body(
+        content = CollectionRequest,
+        example = json!({
+            "identifiers": [
+                {"id": "e3285e6b-3e79-4d7c-bf96-d920f973b80d"},
+                {"name": "Counterspell"},
+                {"set": "lea", "collector_number": "161"}
+            ]
+        })
+    ),
+    responses(
+        (status = 200, description = "Resolved cards and unmatched identifiers", body = CollectionResponse),
+        (status = 400, description = "Bad request", body = CollectionResponse),
+        (status = 413, description = "Body too large, too deeply nested or too many items", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = CollectionResponse)
+    )
+)]
+pub async fn get_card_collection(
+    State(state): State<AppState>,
+    Json(req): Json<CollectionRequest>,
+) -> impl IntoResponse {
+    if req.identifiers.is_empty() {
+        return ErrorResponse::validation_error("identifiers must not be empty").into_response();
+    }
+    if req.identifiers.len() > MAX_COLLECTION_IDENTIFIERS {
+        return ErrorResponse::validation_error(format!(
+            "too many identifiers: {} (max {})",
+            req.identifiers.len(),
+            MAX_COLLECTION_IDENTIFIERS
+        ))
+        .into_response();
+    }
+
+    // Resolve all id identifiers in a single batch
+    let ids: Vec<Uuid> = req.identifiers.iter().filter_map(|i| i.id).collect();
+    let mut by_id = std::collections::HashMap::new();
+    if !ids.is_empty() {
+        match state.cache_manager.get_cards_batch(&ids, true).await {
+            Ok((cards, _missing)) => by_id.extend(cards.into_iter().map(|c| (c.id, c))),
+            Err(e) => {
+                error!("Collection lookup failed: {}", e);
+                return e.into_response();
+            }
+        }
+    }
+
+    let mut data = Vec::new();
+    let mut not_found = Vec::new();
+
+    for identifier in req.identifiers {
+        let card = match (
+            identifier.id,
+            identifier.name.as_deref(),
+            identifier.set.as_deref(),
+            identifier.collector_number.as_deref(),
+        ) {
+            (Some(id), _, _, _) => by_id.get(&id).cloned(),
+            (None, Some(name), None, _) => state
+                .cache_manager
+                .search_by_name(name, false)
+                .await
+                .ok()
+                .flatten(),
+            (None, Some(name), Some(set), _) if is_query_token(set) && !name.contains('"') => {
+                let query = format!("name:\"{}\" set:{}", name, set);
+                state
+                    .cache_manager
+                    .search(&query, None)
+                    .await
+                    .ok()
+                    .and_then(|cards| {
+                        cards
+                            .into_iter()
+                            .find(|c| c.name.eq_ignore_ascii_case(name))
+                    })
+            }
+            (None, None, Some(set), Some(number))
+                if is_query_token(set) && is_query_token(number) =>
+            {
+                let query = format!("set:{} cn:{}", set, number);
+                state
+                    .cache_manager
+                    .search(&query, Some(1))
+                    .await
+                    .ok()
+                    .and_then(|cards| cards.into_iter().next())
+            }
+            _ => None,
+        };
+
+        match card {
+            Some(card) => data.push(card),
+            None => not_found.push(identifier),
+        }
+    }
+
+    info!(
+        "Collection lookup: {} found, {} not found",
+        data.len(),
+        not_found.len()
+    );
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(CollectionData { data, not_found })),
+    )
+        .into_response()
+}
+
+/// Import owned quantities into a collection of the calling tenant. Each input replaces
+/// the stored quantity of its printing and finish; a quantity of 0 removes it.
+#[utoipa::path(
+    post,
+    path = "/collections/{id}/cards",
+    tag = "collections",
+    params(
+        ("id" = String, Path, description = "Collection id: 1-64 letters, digits, `-` or `_`")
+    ),
+    request_body(
+        content = CollectionCardsRequest,
+        example = json!({
+            "cards": [
+                {"id": "e3285e6b-3e79-4d7c-bf96-d920f973b80d", "quantity": 4},
+                {"set": "lea", "collector_number": "161", "finish": "nonfoil", "quantity": 1}
+            ]
+        })
+    ),
+    responses(
+        (status = 200, description = "Stored and removed entries, and inputs that matched no card", body = CollectionUpsertResponse),
+        (status = 400, description = "Bad request", body = CollectionUpsertResponse),
+        (status = 403, description = "Read-only replica", body = ErrorResponse),
+        (status = 413, description = "Body too large, too deeply nested or too many items", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = CollectionUpsertResponse)
+    )
+)]
+pub async fn upsert_collection_cards(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Json(req): Json<CollectionCardsRequest>,
+) -> impl IntoResponse {
+    if !collections::is_valid_collection_id(&collection_id) {
+        return ErrorResponse::validation_error("Invalid collection id").into_response();
+    }
+    if req.cards.is_empty() {
+        return ErrorResponse::validation_error("cards must not be empty").into_response();
+    }
+    let max_ids = state.batch.max_ids;
+    if req.cards.len() > max_ids {
+        return ErrorResponse::validation_error(format!(
+            "too many cards: {} (max {})",
+            req.cards.len(),
+            max_ids
+        ))
+        .into_response();
+    }
+    for card in &req.cards {
+        if card.quantity < 0 {
+            return ErrorResponse::validation_error("quantity must not be negative")
+                .into_response();
+        }
+        if card.id.is_none() && (card.set.is_none() || card.collector_number.is_none()) {
+            return ErrorResponse::validation_error(
+                "each card needs an id, or a set and collector_number",
+            )
+            .into_response();
+        }
+    }
+
+    // Resolve all ids in a single batch
+    let ids: Vec<Uuid> = req.cards.iter().filter_map(|c| c.id).collect();
+    let mut known = std::collections::HashSet::new();
+    if !ids.is_empty() {
+        match state.cache_manager.get_cards_batch(&ids, true).await {
+            Ok((cards, _missing)) => known.extend(cards.into_iter().map(|c| c.id)),
+            Err(e) => {
+                error!("Collection import lookup failed: {}", e);
+                return e.into_response();
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    let mut not_found = Vec::new();
+
+    for input in req.cards {
+        let card_id = match (
+            input.id,
+            input.set.as_deref(),
+            input.collector_number.as_deref(),
+        ) {
+            (Some(id), _, _) => known.contains(&id).then_some(id),
+            (None, Some(set), Some(number)) => state
+                .cache_manager
+                .get_card_by_set_and_number(&set.to_lowercase(), number)
+                .await
+                .ok()
+                .flatten()
+                .map(|card| card.id),
+            _ => None,
+        };
+
+        match card_id {
+            Some(card_id) => entries.push(CollectionEntry {
+                card_id,
+                finish: input.finish,
+                quantity: input.quantity,
+                updated_at: None,
+            }),
+            None => not_found.push(input),
+        }
+    }
+
+    if !entries.is_empty() {
+        if let Err(e) = state
+            .cache_manager
+            .upsert_collection_entries(&collection_id, &entries)
+            .await
+        {
+            error!("Failed to store collection {}: {}", collection_id, e);
+            return e.into_response();
+        }
+    }
+
+    let removed = entries.iter().filter(|e| e.quantity == 0).count();
+    info!(
+        "Collection {} import: {} stored, {} removed, {} not found",
+        collection_id,
+        entries.len() - removed,
+        removed,
+        not_found.len()
+    );
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(CollectionUpsertData {
+            collection_id,
+            upserted: entries.len() - removed,
+            removed,
+            not_found,
+        })),
+    )
+        .into_response()
+}
+
+/// Value a collection of the calling tenant from the prices of the cached cards
+#[utoipa::path(
+    get,
+    path = "/collections/{id}/value",
+    tag = "collections",
+    params(
+        ("id" = String, Path, description = "Collection id: 1-64 letters, digits, `-` or `_`"),
+        CollectionValueParams
+    ),
+    responses(
+        (status = 200, description = "Collection value; an unknown collection is worth 0", body = CollectionValueResponse),
+        (status = 400, description = "Bad request", body = CollectionValueResponse),
+        (status = 500, description = "Internal server error", body = CollectionValueResponse)
+    )
+)]
+pub async fn get_collection_value(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Query(params): Query<CollectionValueParams>,
+) -> impl IntoResponse {
+    if !collections::is_valid_collection_id(&collection_id) {
+        return ErrorResponse::validation_error("Invalid collection id").into_response();
+    }
+    let currency = params.currency.as_deref().unwrap_or("usd").to_lowercase();
+    if !collections::CURRENCIES.contains(&currency.as_str()) {
+        return ErrorResponse::validation_error(format!(
+            "Invalid currency: {} (expected one of {})",
+            currency,
+            collections::CURRENCIES.join(", ")
+        ))
+        .into_response();
+    }
+
+    match state
+        .cache_manager
+        .collection_value(&collection_id, &currency)
+        .await
+    {
+        Ok(value) => (StatusCode::OK, Json(ApiResponse::success(value))).into_response(),
+        Err(e) => {
+            error!("Failed to value collection {}: {}", collection_id, e);
+            e.into_response()
+        }
+    }
+}
+
+/// Register a saved search for the calling tenant. Cards matching it now are recorded,
+/// so only cards added by later bulk imports are reported as new.
+#[utoipa::path(
+    post,
+    path = "/saved-searches",
+    tag = "saved-searches",
+    request_body(
+        content = SavedSearchRequest,
+        example = json!({"query": "set:mkm r:mythic", "name": "Karlov Manor mythics"})
+    ),
+    responses(
+        (status = 200, description = "Registered saved search", body = SavedSearchResponse),
+        (status = 400, description = "Invalid query, or it matches too many cards", body = SavedSearchResponse),
+        (status = 403, description = "Read-only replica", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = SavedSearchResponse)
+    )
+)]
+pub async fn create_saved_search(
+    State(state): State<AppState>,
+    Json(req): Json<SavedSearchRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = state.query_validator.validate_query_string(&req.query) {
+        return ErrorResponse::validation_error(e.to_string()).into_response();
+    }
+    match QueryParser::parse(&req.query) {
+        Ok(ast) => {
+            if let Err(e) = state.query_validator.validate_ast(&ast) {
+                return ErrorResponse::validation_error(e.to_string()).into_response();
+            }
+        }
+        Err(e) => return ErrorResponse::query_parse_error(&e).into_response(),
+    }
+    if req
+        .name
+        .as_ref()
+        .is_some_and(|name| name.chars().count() > MAX_SAVED_SEARCH_NAME_LENGTH)
+    {
+        return ErrorResponse::validation_error(format!(
+            "name is longer than {} characters",
+            MAX_SAVED_SEARCH_NAME_LENGTH
+        ))
+        .into_response();
+    }
+
+    match state
+        .cache_manager
+        .create_saved_search(&req.query, req.name)
+        .await
+    {
+        Ok(search) => {
+            info!("Registered saved search {} ({})", search.id, search.query);
+            (StatusCode::OK, Json(ApiResponse::success(search))).into_response()
+        }
+        Err(e) => {
+            error!("Failed to register saved search: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+/// Saved searches of the calling tenant, oldest first
+#[utoipa::path(
+    get,
+    path = "/saved-searches",
+    tag = "saved-searches",
+    responses(
+        (status = 200, description = "Saved searches", body = SavedSearchListResponse),
+        (status = 500, description = "Internal server error", body = SavedSearchListResponse)
+    )
+)]
+pub async fn list_saved_searches(State(state): State<AppState>) -> impl IntoResponse {
+    match state.cache_manager.saved_searches().await {
+        Ok(searches) => (StatusCode::OK, Json(ApiResponse::success(searches))).into_response(),
+        Err(e) => {
+            error!("Failed to list saved searches: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+/// Delete a saved search of the calling tenant
+#[utoipa::path(
+    delete,
+    path = "/saved-searches/{id}",
+    tag = "saved-searches",
+    params(
+        ("id" = Uuid, Path, description = "Saved search id")
+    ),
+    responses(
+        (status = 200, description = "The deleted saved search", body = SavedSearchResponse),
+        (status = 403, description = "Read-only replica", body = ErrorResponse),
+        (status = 404, description = "Saved search not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_saved_search(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.cache_manager.delete_saved_search(id).await {
+        Ok(search) => {
+            info!("Deleted saved search {}", id);
+            (StatusCode::OK, Json(ApiResponse::success(search))).into_response()
+        }
+        Err(e @ ServiceError::NotFound(_)) => e.into_response(),
+        Err(e) => {
+            error!("Failed to delete saved search {}: {}", id, e);
+            e.into_response()
+        }
+    }
+}
+
+/// Cards that first matched a saved search in its latest evaluation, after the most
+/// recent bulk import
+#[utoipa::path(
+    get,
+    path = "/saved-searches/{id}/new",
+    tag = "saved-searches",
+    params(
+        ("id" = Uuid, Path, description = "Saved search id")
+    ),
+    responses(
+        (status = 200, description = "The saved search and its new matches", body = SavedSearchNewResponse),
+        (status = 404, description = "Saved search not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = SavedSearchNewResponse)
+    )
+)]
+pub async fn get_saved_search_new_cards(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.cache_manager.saved_search_new_cards(id).await {
+        Ok((search, cards)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(SavedSearchNewData { search, cards })),
+        )
+            .into_response(),
+        Err(e @ ServiceError::NotFound(_)) => e.into_response(),
+        Err(e) => {
+            error!("Failed to load new matches of saved search {}: {}", id, e);
+            e.into_response()
+        }
+    }
+}
+
+/// Serve proxied Scryfall reference data wrapped in the standard response envelope
+async fn reference_data_response(
+    state: &AppState,
+    endpoint: &'static str,
+    path: &str,
+) -> axum::response::Response {
+    match state.cache_manager.get_reference_data(endpoint, path).await {
+        Ok(Some(value)) => (StatusCode::OK, Json(ApiResponse::success(value))).into_response(),
+        Ok(None) => {
+            ErrorResponse::new(ErrorCode::NotFound, format!("Not found: {}", path)).into_response()
+        }
+        Err(e) => {
+            error!("Reference data fetch failed for {}: {}", path, e);
+            e.into_response()
+        }
+    }
+}
+
+/// List all sets (proxied from Scryfall and cached in memory)
+#[utoipa::path(
+    get,
+    path = "/sets",
+    tag = "cards",
+    responses(
+        (status = 200, description = "Scryfall set list", body = serde_json::Value),
+        (status = 502, description = "Upstream Scryfall failure", body = ErrorResponse)
+    )
+)]
+pub async fn list_sets(State(state): State<AppState>) -> impl IntoResponse {
+    reference_data_response(&state, "sets", "/sets").await
+}
+
+/// Get a single set by code (proxied from Scryfall and cached in memory)
+#[utoipa::path(
+    get,
+    path = "/sets/{code}",
+    tag = "cards",
+    params(
+        ("code" = String, Path, description = "Set code (e.g. \"lea\")")
+    ),
+    responses(
+        (status = 200, description = "Scryfall set object", body = serde_json::Value),
+        (status = 404, description = "Set not found", body = ErrorResponse),
+        (status = 502, description = "Upstream Scryfall failure", body = ErrorResponse)
+    )
+)]
+pub async fn get_set(State(state): State<AppState>, Path(code): Path<String>) -> impl IntoResponse {
+    if !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return ErrorResponse::validation_error("Invalid set code").into_response();
+    }
+    let path = format!("/sets/{}", code.to_lowercase());
+    reference_data_response(&state, "sets", &path).await
+}
+
+/// Get a Scryfall catalog, e.g. `card-names` or `creature-types` (proxied and cached)
+#[utoipa::path(
+    get,
+    path = "/catalog/{name}",
+    tag = "cards",
+    params(
+        ("name" = String, Path, description = "Catalog name (e.g. \"creature-types\")")
+    ),
+    responses(
+        (status = 200, description = "Scryfall catalog object", body = serde_json::Value),
+        (status = 404, description = "Catalog not found", body = ErrorResponse),
+        (status = 502, description = "Upstream Scryfall failure", body = ErrorResponse)
+    )
+)]
+pub async fn get_catalog(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c == '-') {
+        return ErrorResponse::validation_error("Invalid catalog name").into_response();
+    }
+    let path = format!("/catalog/{}", name);
+    reference_data_response(&state, "catalog", &path).await
+}
+
+/// List all card symbols (proxied from Scryfall and cached in memory)
+#[utoipa::path(
+    get,
+    path = "/symbology",
+    tag = "cards",
+    responses(
+        (status = 200, description = "Scryfall card symbol list", body = serde_json::Value),
+        (status = 502, description = "Upstream Scryfall failure", body = ErrorResponse)
+    )
+)]
+pub async fn get_symbology(State(state): State<AppState>) -> impl IntoResponse {
+    reference_data_response(&state, "symbology", "/symbology").await
+}
+
+/// Get a card by name (fuzzy or exact)
+#[utoipa::path(
+    get,
+    path = "/cards/named",
+    tag = "cards",
+    params(NamedParams),
+    responses(
+        (status = 200, description = "Card found", body = CardResponse),
+        (status = 400, description = "Bad request - must provide fuzzy or exact parameter, or invalid lang", body = CardResponse),
+        (status = 404, description = "Card not found", body = CardResponse),
+        (status = 500, description = "Internal server error", body = CardResponse)
+    )
+)]
+pub async fn get_card_by_name(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<NamedParams>,
+) -> impl IntoResponse {
+    let format = match state
+        .response_format
+        .negotiate(params.format.as_deref(), &headers)
+    {
+        Ok(format) => format,
+        Err(value) => return invalid_format(value),
+    };
+
+    let (name, fuzzy) = if let Some(name) = params.fuzzy {
+        (name, true)
+    } else if let Some(name) = params.exact {
+        (name, false)
+    } else {
+        return format.error(ErrorResponse::validation_error(
+            "Must provide either 'fuzzy' or 'exact' parameter",
+        ));
+    };
+
+    let lang = match params.lang.as_deref() {
+        Some(value) => match parse_lang_code(value) {
+            Some(lang) => Some(lang),
+            None => {
+                return format.error(ErrorResponse::validation_error(format!(
+                    "Invalid lang '{}': expected a Scryfall language code such as 'en' or 'ja'",
+                    value
+                )))
+            }
+        },
+        None => None,
+    };
+
+    info!(
+        "Get card by name: name='{}', fuzzy={}, lang={:?}",
+        name, fuzzy, lang
+    );
+
+    let include_tokens = params.include_tokens.unwrap_or(false);
+    let lookup =
+        state
+            .cache_manager
+            .search_by_name_with(&name, fuzzy, lang.as_deref(), include_tokens);
+    match lookup.await {
+        Ok(Some(card)) if format == ResponseFormat::Scryfall => {
+            info!("Found card: {}", card.name);
+            let hint = CacheHint::card(CachedRoute::Named, &card);
+            hint.attach(compat::card_response(card))
+        }
+        Ok(Some(card)) => {
+            info!("Found card: {}", card.name);
+            let hint = CacheHint::card(CachedRoute::Named, &card);
+            hint.attach((StatusCode::OK, Json(ApiResponse::success(card))))
+        }
+        Ok(None) => {
+            info!("Card not found: {}", name);
+            format.error(ErrorResponse::card_not_found(name))
+        }
+        Err(e) => {
+            error!("Get card by name failed: {}", e);
+            format.error(e)
+        }
+    }
+}
+
+/// Get cache statistics
+#[utoipa::path(
+    get,
+    path = "/stats",
+    tag = "statistics",
+    responses(
+        (status = 200, description = "Cache statistics", body = StatsResponse),
+        (status = 500, description = "Internal server error", body = StatsResponse)
+    )
+)]
+pub async fn get_stats(State(state): State<AppState>) -> impl IntoResponse {
+    info!("Stats request");
+
+    match state.cache_manager.get_stats().await {
+        Ok(stats) => {
+            info!("Stats: {:?}", stats);
+            (StatusCode::OK, Json(ApiResponse::success(stats))).into_response()
+        }
+        Err(e) => {
+            error!("Get stats failed: {}", e);
+            ErrorResponse::database_error(format!("Failed to retrieve stats: {}", e))
+                .into_response()
+        }
+    }
+}
+
+/// Admin: compiled features and optional subsystems
+#[utoipa::path(
+    get,
+    path = "/admin/features",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Compiled features and runtime subsystems", body = FeaturesResponse)
+    )
+)]
+pub async fn admin_features(State(state): State<AppState>) -> impl IntoResponse {
+    let mut subsystems = std::collections::BTreeMap::new();
+
+    let database = match state.cache_manager.test_database_connection().await {
+        Ok(()) => "ok".to_string(),
+        Err(e) => format!("error: {}", e),
+    };
+    subsystems.insert("database".to_string(), database);
+
+    let redis = if !cfg!(feature = "redis_cache") {
+        "not_compiled".to_string()
+    } else if !state.cache_manager.redis_enabled() {
+        "disabled".to_string()
+    } else {
+        match state.cache_manager.test_redis_connection().await {
+            Ok(()) => "ok".to_string(),
+            Err(e) => format!("error: {}", e),
+        }
+    };
+    subsystems.insert("redis".to_string(), redis);
+
+    subsystems.insert(
+        "bulk_data".to_string(),
+        state.startup.state().as_str().to_string(),
+    );
+
+    let enabled = |on: bool| if on { "enabled" } else { "disabled" }.to_string();
+    subsystems.insert(
+        "bulk_refresh".to_string(),
+        enabled(state.bulk_refresh.enabled),
+    );
+    subsystems.insert(
+        "graphql_column_projection".to_string(),
+        enabled(state.graphql.column_projection),
+    );
+
+    let report = FeaturesReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        target_arch: std::env::consts::ARCH.to_string(),
+        target_os: std::env::consts::OS.to_string(),
+        compiled_features: features::compiled_features()
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        database_backend: features::database_backend().to_string(),
+        tls_backend: features::tls_backend().to_string(),
+        subsystems,
+    };
+
+    (StatusCode::OK, Json(ApiResponse::success(report))).into_response()
+}
+
+/// Status of the circuit breaker guarding each Scryfall endpoint
+#[utoipa::path(
+    get,
+    path = "/admin/circuit-breakers",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Circuit breaker state per Scryfall endpoint", body = CircuitBreakersResponse)
+    )
+)]
+pub async fn admin_circuit_breakers(State(state): State<AppState>) -> impl IntoResponse {
+    let metrics = state.cache_manager.circuit_breakers().metrics().await;
+    (StatusCode::OK, Json(ApiResponse::success(metrics))).into_response()
+}
+
+/// Force-close a single circuit breaker
+#[utoipa::path(
+    post,
+    path = "/admin/circuit-breakers/{name}/reset",
+    tag = "admin",
+    params(
+        ("name" = String, Path, description = "Circuit breaker name (e.g. \"cards_search\")")
+    ),
+    responses(
+        (status = 200, description = "Circuit breaker reset", body = CircuitBreakerResponse),
+        (status = 404, description = "Unknown circuit breaker", body = CircuitBreakerResponse)
+    )
+)]
+pub async fn admin_reset_circuit_breaker(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let Some(breaker) = state.cache_manager.circuit_breakers().find(&name) else {
+        return ErrorResponse::new(
+            ErrorCode::NotFound,
+            format!("Unknown circuit breaker: {}", name),
+        )
+        .into_response();
+    };
+
+    breaker.reset().await;
+    info!("Circuit breaker '{}' reset via admin API", name);
+
+    let metrics = breaker.metrics().await;
+    (StatusCode::OK, Json(ApiResponse::success(metrics))).into_response()
+}
+
+/// Get the query complexity limits currently enforced
+#[utoipa::path(
+    get,
+    path = "/api/admin/config/query-limits",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Current query limits", body = QueryLimitsResponse)
+    )
+)]
+pub async fn admin_get_query_limits(State(state): State<AppState>) -> impl IntoResponse {
+    let limits = state.query_validator.limits();
+    (StatusCode::OK, Json(ApiResponse::success(&*limits))).into_response()
+}
+
+/// Change query complexity limits without a restart. Omitted fields keep their value;
+/// changes last until the process restarts.
+#[utoipa::path(
+    put,
+    path = "/api/admin/config/query-limits",
+    tag = "admin",
+    request_body = QueryLimitsUpdate,
+    responses(
+        (status = 200, description = "Updated query limits", body = QueryLimitsResponse),
+        (status = 400, description = "Invalid limit", body = ErrorResponse)
+    )
+)]
+pub async fn admin_update_query_limits(
+    State(state): State<AppState>,
+    Json(update): Json<QueryLimitsUpdate>,
+) -> impl IntoResponse {
+    let runtime = state.query_validator.runtime_limits();
+    let (previous, limits) = match runtime.update(&update) {
+        Ok(changed) => changed,
+        Err(e) => return ErrorResponse::validation_error(e.to_string()).into_response(),
+    };
+    info!(
+        target: "audit",
+        action = "update_query_limits",
+        previous = ?previous,
+        current = ?limits,
+        "Query limits changed via admin API"
+    );
+
+    (StatusCode::OK, Json(ApiResponse::success(&*limits))).into_response()
+}
+
+/// List cached search queries
+#[utoipa::path(
+    get,
+    path = "/admin/cache/queries",
+    tag = "admin",
+    params(QueryCacheListParams),
+    responses(
+        (status = 200, description = "Query cache entries, most recently cached first", body = QueryCacheEntriesResponse),
+        (status = 503, description = "Database error", body = QueryCacheEntriesResponse)
+    )
+)]
+pub async fn admin_list_cached_queries(
+    State(state): State<AppState>,
+    Query(params): Query<QueryCacheListParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+
+    match state.cache_manager.list_cached_queries(limit).await {
+        Ok(entries) => (StatusCode::OK, Json(ApiResponse::success(entries))).into_response(),
+        Err(e) => {
+            error!("Failed to list query cache: {}", e);
+            ErrorResponse::database_error(format!("Failed to list query cache: {}", e))
+                .into_response()
+        }
+    }
+}
+
+/// Evict one cached search query by its hash
+#[utoipa::path(
+    delete,
+    path = "/admin/cache/queries/{hash}",
+    tag = "admin",
+    params(
+        ("hash" = String, Path, description = "Query hash, as listed by GET /admin/cache/queries")
+    ),
+    responses(
+        (status = 200, description = "Query evicted", body = QueryCachePurgeResponse),
+        (status = 404, description = "Query not cached", body = QueryCachePurgeResponse),
+        (status = 503, description = "Database error", body = QueryCachePurgeResponse)
+    )
+)]
+pub async fn admin_evict_cached_query(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+) -> impl IntoResponse {
+    match state.cache_manager.evict_cached_query(&hash).await {
+        Ok((in_database, in_redis)) => {
+            info!("Evicted cached query {} via admin API", hash);
+            let purge = QueryCachePurge {
+                database_entries: in_database as u64,
+                redis_entries: in_redis as u64,
+            };
+            (StatusCode::OK, Json(ApiResponse::success(purge))).into_response()
+        }
+        Err(e @ ServiceError::NotFound(_)) => e.into_response(),
+        Err(e) => {
+            error!("Failed to evict cached query {}: {}", hash, e);
+            e.into_response()
+        }
+    }
+}
+
+/// Evict every cached search query
+#[utoipa::path(
+    delete,
+    path = "/admin/cache/queries",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Query cache cleared", body = QueryCachePurgeResponse),
+        (status = 503, description = "Database error", body = QueryCachePurgeResponse)
+    )
+)]
+pub async fn admin_clear_cached_queries(State(state): State<AppState>) -> impl IntoResponse {
+    match state.cache_manager.clear_cached_queries().await {
+        Ok((database_entries, redis_entries)) => {
+            let purge = QueryCachePurge {
+                database_entries,
+                redis_entries,
+            };
+            (StatusCode::OK, Json(ApiResponse::success(purge))).into_response()
+        }
+        Err(e) => {
+            error!("Failed to clear query cache: {}", e);
+            ErrorResponse::database_error(format!("Failed to clear query cache: {}", e))
+                .into_response()
+        }
+    }
+}
+
+/// Cache warm-up status and last run statistics
+#[utoipa::path(
+    get,
+    path = "/admin/warmup",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Warm-up configuration and last run", body = WarmupStatusResponse)
+    )
+)]
+pub async fn admin_warmup_status(State(state): State<AppState>) -> impl IntoResponse {
+    let status = WarmupStatus {
+        configured_queries: state.warmer.queries().len(),
+        last_run: state.warmer.last_run(),
+    };
+    (StatusCode::OK, Json(ApiResponse::success(status))).into_response()
+}
+
+/// Run the cache warm-up queries now
+#[utoipa::path(
+    post,
+    path = "/admin/warmup",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Warm-up completed", body = WarmupStatusResponse),
+        (status = 400, description = "No warm-up queries configured", body = WarmupStatusResponse)
+    )
+)]
+pub async fn admin_warmup(State(state): State<AppState>) -> impl IntoResponse {
+    if state.warmer.queries().is_empty() {
+        return ServiceError::Validation(
+            "No warm-up queries configured (set WARMUP_QUERIES_FILE)".to_string(),
+        )
+        .into_response();
+    }
+
+    info!("Admin warm-up request");
+    let run = state.warmer.run().await;
+    let status = WarmupStatus {
+        configured_queries: run.queries,
+        last_run: Some(run),
+    };
+    (StatusCode::OK, Json(ApiResponse::success(status))).into_response()
+}
+
+/// Export the local card database
+#[utoipa::path(
+    get,
+    path = "/admin/export",
+    tag = "admin",
+    params(ExportParams),
+    responses(
+        (status = 200, description = "Streamed card export (NDJSON or CSV)", body = String),
+        (status = 400, description = "Invalid format or query", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn admin_export(
+    State(state): State<AppState>,
+    Query(params): Query<ExportParams>,
+) -> impl IntoResponse {
+    info!(
+        "Export request: format={:?}, query={:?}",
+        params.format, params.q
+    );
+
+    let format = match params.format.as_deref() {
+        None => ExportFormat::Ndjson,
+        Some(value) => match ExportFormat::parse(value) {
+            Some(format) => format,
+            None => {
+                return ErrorResponse::validation_error(format!(
+                    "Unsupported export format '{}' (expected 'ndjson' or 'csv')",
+                    value
+                ))
+                .into_response();
+            }
+        },
+    };
+
+    let query = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    if let Some(query) = query {
+        if let Err(e) = state.query_validator.validate_query_string(query) {
+            return ErrorResponse::validation_error(e.to_string()).into_response();
+        }
+        match QueryParser::parse(query) {
+            Ok(ast) => {
+                if let Err(e) = state.query_validator.validate_ast(&ast) {
+                    return ErrorResponse::validation_error(e.to_string()).into_response();
+                }
+            }
+            Err(e) => {
+                return ErrorResponse::query_parse_error(&e).into_response();
+            }
+        }
+    }
+
+    let cards = match state.cache_manager.export_cards(query) {
+        Ok(cards) => cards,
+        Err(e) => {
+            error!("Export failed: {}", e);
+            return ErrorResponse::database_error(format!("Failed to export cards: {}", e))
+                .into_response();
+        }
+    };
+
+    let preamble = futures::stream::iter(format.header().map(Ok::<_, anyhow::Error>));
+    let rows = cards.map(move |card| {
+        card.map(|card| format.encode(&card)).map_err(|e| {
+            error!("Export stream failed: {}", e);
+            e
+        })
+    });
+    let body = axum::body::Body::from_stream(preamble.chain(rows));
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.file_name()),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Snapshot the local card database for `/admin/restore` on another deployment
+#[utoipa::path(
+    post,
+    path = "/admin/backup",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Streamed snapshot: a JSON array of raw Scryfall card objects", body = String, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ReloadResponse)
+    )
+)]
+pub async fn admin_backup(State(state): State<AppState>) -> impl IntoResponse {
+    info!("Admin backup request");
+
+    // One query, so the snapshot is consistent even while cards are being written
+    let cards = match state.cache_manager.export_cards(None) {
+        Ok(cards) => cards,
+        Err(e) => {
+            error!("Backup failed: {}", e);
+            return ErrorResponse::database_error(format!("Failed to back up cards: {}", e))
+                .into_response();
+        }
+    };
+
+    // A snapshot cut short by an error is left without its closing bracket, so
+    // restoring it fails rather than loading part of the cards
+    let mut separator = "";
+    let rows = cards.map(move |card| {
+        card.map(|card| {
+            let row = format!("{}{}\n", separator, card.raw_json);
+            separator = ",";
+            row
+        })
+        .map_err(|e| {
+            error!("Backup stream failed: {}", e);
+            e
+        })
+    });
+    let open = futures::stream::iter([Ok::<_, anyhow::Error>("[\n".to_string())]);
+    let close = futures::stream::iter([Ok::<_, anyhow::Error>("]\n".to_string())]);
+    let body = axum::body::Body::from_stream(open.chain(rows).chain(close));
+
+    let file_name = format!(
+        "scryfall-cache-backup-{}.json",
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Load a snapshot from `/admin/backup` (or a Scryfall bulk data file), so a deployment
+/// can be moved without downloading bulk data from Scryfall again
+#[utoipa::path(
+    post,
+    path = "/admin/restore",
+    tag = "admin",
+    request_body(
+        content = String,
+        content_type = "application/json",
+        description = "Snapshot from /admin/backup: a JSON array of Scryfall card objects, optionally gzip-compressed"
+    ),
+    responses(
+        (status = 200, description = "Snapshot restored", body = RestoreResponse),
+        (status = 400, description = "Empty or malformed snapshot", body = RestoreResponse),
+        (status = 409, description = "A bulk data load or restore is already running", body = RestoreResponse),
+        (status = 500, description = "Restore failed", body = RestoreResponse)
+    )
+)]
+pub async fn admin_restore(
+    State(state): State<AppState>,
+    body: axum::body::Body,
+) -> impl IntoResponse {
+    info!("Admin restore request");
+
+    match state.bulk_loader.restore(body.into_data_stream()).await {
+        Ok(import) => (StatusCode::OK, Json(ApiResponse::success(import))).into_response(),
+        Err(e) => {
+            error!("Restore failed: {:#}", e);
+            ServiceError::from(e).into_response()
+        }
+    }
+}
+
+/// Scan the card table for corrupt rows (unparseable or mismatched `raw_json`, blank
+/// names, missing oracle IDs, orphan face data) and re-fetch those cards from Scryfall
+#[utoipa::path(
+    post,
+    path = "/admin/repair",
+    tag = "admin",
+    params(RepairParams),
+    responses(
+        (status = 200, description = "Scan and repair summary", body = RepairResponse),
+        (status = 403, description = "Read-only replica", body = RepairResponse),
+        (status = 500, description = "Scan failed", body = RepairResponse)
+    )
+)]
+pub async fn admin_repair(
+    State(state): State<AppState>,
+    Query(params): Query<RepairParams>,
+) -> impl IntoResponse {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_REPAIR_LIMIT)
+        .clamp(1, MAX_REPAIR_LIMIT);
+    info!("Admin repair request: limit={}", limit);
+
+    match state.cache_manager.repair_cards(limit).await {
+        Ok(report) => (StatusCode::OK, Json(ApiResponse::success(report))).into_response(),
+        Err(e) => {
+            error!("Card repair failed: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+/// Force reload bulk data
+#[utoipa::path(
+    post,
+    path = "/admin/reload",
+    tag = "admin",
     responses(
         (status = 200, description = "Bulk data reload completed", body = ReloadResponse),
+        (status = 409, description = "A bulk data load or restore is already running", body = ReloadResponse),
         (status = 500, description = "Reload failed", body = ReloadResponse)
     )
 )]
@@ -963,29 +3423,8 @@ pub async fn admin_reload(State(state): State<AppState>) -> impl IntoResponse {
                 .into_response()
         }
         Err(e) => {
-            error!("Bulk data reload failed: {}", e);
-
-            // Map error type to appropriate error code
-            let error_message = e.to_string();
-            if error_message.contains("scryfall")
-                || error_message.contains("download")
-                || error_message.contains("HTTP")
-            {
-                ErrorResponse::new(
-                    crate::errors::codes::ErrorCode::ScryfallApiError,
-                    format!("Failed to download bulk data: {}", e),
-                )
-                .into_response()
-            } else if error_message.contains("database") || error_message.contains("connection") {
-                ErrorResponse::database_error(format!(
-                    "Failed to load bulk data into database: {}",
-                    e
-                ))
-                .into_response()
-            } else {
-                ErrorResponse::internal_error(format!("Bulk data reload failed: {}", e))
-                    .into_response()
-            }
+            error!("Bulk data reload failed: {:#}", e);
+            ServiceError::from(e).into_response()
         }
     }
 }
@@ -1004,43 +3443,56 @@ pub async fn admin_reload(State(state): State<AppState>) -> impl IntoResponse {
 )]
 pub async fn autocomplete_cards(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(params): Query<AutocompleteParams>,
 ) -> impl IntoResponse {
-    let prefix = params.q.trim();
-
-    // Return empty results for very short queries
-    if prefix.len() < 2 {
-        return (
+    let format = match state
+        .response_format
+        .negotiate(params.format.as_deref(), &headers)
+    {
+        Ok(format) => format,
+        Err(value) => return invalid_format(value),
+    };
+    let catalog = |names: Vec<String>| match format {
+        ResponseFormat::Native => (
             StatusCode::OK,
             Json(AutocompleteResponse {
                 object: "catalog".to_string(),
-                data: Vec::new(),
+                data: names,
             }),
         )
-            .into_response();
+            .into_response(),
+        ResponseFormat::Scryfall => {
+            (StatusCode::OK, Json(ScryfallCatalog::new(names))).into_response()
+        }
+    };
+
+    let prefix = params.q.trim();
+
+    // Return empty results for very short queries
+    if prefix.len() < 2 {
+        return catalog(Vec::new());
     }
 
     info!("Autocomplete request: prefix='{}'", prefix);
 
-    match state.cache_manager.autocomplete(prefix).await {
+    let options =
+        AutocompleteOptions::new(params.limit, params.include_extras, params.exclude_digital);
+    match state.cache_manager.autocomplete(prefix, &options).await {
         Ok(names) => {
             info!(
                 "Autocomplete returned {} names for prefix '{}'",
                 names.len(),
                 prefix
             );
-            (
-                StatusCode::OK,
-                Json(AutocompleteResponse {
-                    object: "catalog".to_string(),
-                    data: names,
-                }),
-            )
-                .into_response()
+            catalog(names)
         }
         Err(e) => {
             error!("Autocomplete failed: {}", e);
-            ErrorResponse::database_error(format!("Autocomplete failed: {}", e)).into_response()
+            format.error(ErrorResponse::database_error(format!(
+                "Autocomplete failed: {}",
+                e
+            )))
         }
     }
 }
@@ -1058,5 +3510,7 @@ pub fn graphql_schema_provider(state: &AppState) -> crate::graphql::GraphQLSchem
 
 /// GraphQL Playground handler
 pub async fn graphql_playground() -> Html<String> {
-    Html(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
+    Html(playground_source(
+        GraphQLPlaygroundConfig::new("/graphql").subscription_endpoint("/graphql/ws"),
+    ))
 }
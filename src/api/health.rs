@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use crate::config::Settings;
+
+/// Which dependency checks `/health/ready` runs besides the database
+#[derive(Debug, Clone)]
+pub struct HealthConfig {
+    /// PING Redis (reported as `disabled` when Redis isn't configured)
+    pub check_redis: bool,
+    /// Report the state of each Scryfall circuit breaker
+    pub check_circuit_breakers: bool,
+    /// Probe whether the Scryfall API is reachable
+    pub check_upstream: bool,
+    /// How long an upstream probe result is reused before probing again
+    pub upstream_cache_ttl: Duration,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            check_redis: true,
+            check_circuit_breakers: true,
+            check_upstream: false,
+            upstream_cache_ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+impl HealthConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            check_redis: env_flag(settings, "HEALTH_CHECK_REDIS", defaults.check_redis),
+            check_circuit_breakers: env_flag(
+                settings,
+                "HEALTH_CHECK_CIRCUIT_BREAKERS",
+                defaults.check_circuit_breakers,
+            ),
+            check_upstream: env_flag(settings, "HEALTH_CHECK_UPSTREAM", defaults.check_upstream),
+            upstream_cache_ttl: settings
+                .var("HEALTH_UPSTREAM_CACHE_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.upstream_cache_ttl),
+        }
+    }
+}
+
+fn env_flag(settings: &Settings, name: &str, default: bool) -> bool {
+    settings
+        .var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_probe_off_by_default() {
+        let config = HealthConfig::default();
+        assert!(config.check_redis);
+        assert!(config.check_circuit_breakers);
+        assert!(!config.check_upstream);
+        assert_eq!(config.upstream_cache_ttl, Duration::from_secs(60));
+    }
+}
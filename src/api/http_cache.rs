@@ -0,0 +1,230 @@
+//! HTTP caching headers, so CDNs and browsers in front of the service can cache card
+//! lookups and searches. Handlers mark cacheable responses with a `CacheHint`; the
+//! middleware turns it into `Cache-Control`, `Last-Modified` and `Age` headers using the
+//! TTL configured for the route.
+
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{AGE, CACHE_CONTROL, LAST_MODIFIED, VARY},
+        HeaderName, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDateTime;
+use std::time::Duration;
+
+use crate::api::handlers::AppState;
+use crate::config::Settings;
+use crate::models::card::Card;
+
+/// `Cache-Control` lifetimes per kind of route; a zero lifetime sends no caching headers
+#[derive(Debug, Clone)]
+pub struct HttpCacheConfig {
+    pub enabled: bool,
+    /// Lookups of one printing: by id, set and collector number, or external id
+    pub card_max_age: Duration,
+    /// `/cards/named`, whose answer changes when new printings are released
+    pub named_max_age: Duration,
+    /// `/cards/search`
+    pub search_max_age: Duration,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            card_max_age: Duration::from_secs(24 * 3600),
+            named_max_age: Duration::from_secs(3600),
+            search_max_age: Duration::from_secs(300),
+        }
+    }
+}
+
+impl HttpCacheConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        let seconds = |name: &str, default: Duration| {
+            settings
+                .var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+        Self {
+            enabled: settings
+                .var("HTTP_CACHE_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            card_max_age: seconds("HTTP_CACHE_CARD_MAX_AGE_SECONDS", defaults.card_max_age),
+            named_max_age: seconds("HTTP_CACHE_NAMED_MAX_AGE_SECONDS", defaults.named_max_age),
+            search_max_age: seconds("HTTP_CACHE_SEARCH_MAX_AGE_SECONDS", defaults.search_max_age),
+        }
+    }
+
+    fn max_age(&self, route: CachedRoute) -> Duration {
+        match route {
+            CachedRoute::Card => self.card_max_age,
+            CachedRoute::Named => self.named_max_age,
+            CachedRoute::Search => self.search_max_age,
+        }
+    }
+}
+
+/// Kind of cacheable route, selecting its TTL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachedRoute {
+    Card,
+    Named,
+    Search,
+}
+
+/// Marks a response as cacheable. Carried as a response extension from the handler to
+/// `http_cache_middleware`.
+#[derive(Debug, Clone)]
+pub struct CacheHint {
+    route: CachedRoute,
+    /// When the newest card in the response was stored (UTC)
+    last_modified: Option<NaiveDateTime>,
+}
+
+impl CacheHint {
+    pub fn card(route: CachedRoute, card: &Card) -> Self {
+        Self::cards(route, std::slice::from_ref(card))
+    }
+
+    pub fn cards(route: CachedRoute, cards: &[Card]) -> Self {
+        Self {
+            route,
+            last_modified: cards.iter().filter_map(|card| card.updated_at).max(),
+        }
+    }
+
+    /// Attach the hint to `response`
+    pub fn attach(self, response: impl IntoResponse) -> Response {
+        let mut response = response.into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Add caching headers to successful GET responses carrying a `CacheHint`.
+///
+/// `Age` is how long ago the data was stored, i.e. fetched from Scryfall; `max-age`
+/// includes it, so downstream caches keep the response for the route's TTL from now.
+pub async fn http_cache_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let cacheable_method = matches!(*request.method(), Method::GET | Method::HEAD);
+    let mut response = next.run(request).await;
+
+    let Some(hint) = response.extensions_mut().remove::<CacheHint>() else {
+        return response;
+    };
+    if !state.http_cache.enabled
+        || !cacheable_method
+        || response.status() != StatusCode::OK
+        || response.headers().contains_key(CACHE_CONTROL)
+    {
+        return response;
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    for (name, value) in cache_headers(&state.http_cache, &hint, now) {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
+
+fn cache_headers(
+    config: &HttpCacheConfig,
+    hint: &CacheHint,
+    now: NaiveDateTime,
+) -> Vec<(HeaderName, String)> {
+    let ttl = config.max_age(hint.route).as_secs();
+    if ttl == 0 {
+        return Vec::new();
+    }
+
+    let mut headers = match hint.last_modified {
+        Some(last_modified) => {
+            let age = (now - last_modified).num_seconds().max(0) as u64;
+            vec![
+                (CACHE_CONTROL, format!("public, max-age={}", age + ttl)),
+                (
+                    LAST_MODIFIED,
+                    last_modified
+                        .format("%a, %d %b %Y %H:%M:%S GMT")
+                        .to_string(),
+                ),
+                (AGE, age.to_string()),
+            ]
+        }
+        None => vec![(CACHE_CONTROL, format!("public, max-age={}", ttl))],
+    };
+    // Named lookups and searches pick their envelope from the Accept profile
+    if hint.route != CachedRoute::Card {
+        headers.push((VARY, "accept".to_string()));
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_headers() {
+        let config = HttpCacheConfig::default();
+        let stored = chrono::NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let now = stored + chrono::Duration::seconds(90);
+
+        let hint = CacheHint {
+            route: CachedRoute::Card,
+            last_modified: Some(stored),
+        };
+        let headers = cache_headers(&config, &hint, now);
+        assert_eq!(
+            headers,
+            vec![
+                (CACHE_CONTROL, "public, max-age=86490".to_string()),
+                (LAST_MODIFIED, "Fri, 01 Mar 2024 12:00:00 GMT".to_string()),
+                (AGE, "90".to_string()),
+            ]
+        );
+
+        // Cards fetched straight from Scryfall have no storage time
+        let hint = CacheHint {
+            route: CachedRoute::Search,
+            last_modified: None,
+        };
+        assert_eq!(
+            cache_headers(&config, &hint, now),
+            vec![
+                (CACHE_CONTROL, "public, max-age=300".to_string()),
+                (VARY, "accept".to_string()),
+            ]
+        );
+
+        let uncached = HttpCacheConfig {
+            named_max_age: Duration::ZERO,
+            ..config
+        };
+        let hint = CacheHint {
+            route: CachedRoute::Named,
+            last_modified: Some(stored),
+        };
+        assert!(cache_headers(&uncached, &hint, now).is_empty());
+    }
+}
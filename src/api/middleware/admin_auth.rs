@@ -0,0 +1,66 @@
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+        HeaderValue,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::handlers::AppState;
+use crate::errors::{ErrorCode, ErrorResponse};
+
+/// Require `Authorization: Bearer <ADMIN_TOKEN>` when an admin token is configured.
+/// Layered only on the admin routes and `/metrics`, so it checks every request it sees.
+pub async fn admin_auth_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = state.admin_token.as_deref() else {
+        return next.run(request).await;
+    };
+
+    let presented = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if presented.is_some_and(|token| tokens_match(token.trim(), expected)) {
+        return next.run(request).await;
+    }
+
+    let mut response = ErrorResponse::new(
+        ErrorCode::Unauthorized,
+        "Admin token missing or not recognised",
+    )
+    .into_response();
+    response
+        .headers_mut()
+        .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
+
+/// Compare tokens in time independent of where they first differ
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3cres", "s3cret"));
+        assert!(!tokens_match("s3cret-and-more", "s3cret"));
+        assert!(!tokens_match("", "s3cret"));
+    }
+}
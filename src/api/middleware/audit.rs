@@ -0,0 +1,161 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Instant;
+
+use super::read_only::is_admin_mutation;
+use super::tenant::API_KEY_HEADER;
+use crate::api::handlers::AppState;
+use crate::errors::ErrorResponse;
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::utils::hash::hash_query;
+
+/// Largest admin request body buffered so it can be recorded
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Admin endpoints taking file uploads; their bodies are passed through unrecorded
+const UPLOAD_PATHS: &[&str] = &["/admin/restore"];
+
+/// Hex digits of the API key hash that identify an actor
+const ACTOR_FINGERPRINT_LENGTH: usize = 12;
+
+/// Record every admin mutation (anything but GET/HEAD/OPTIONS under `/admin` or
+/// `/api/admin`) in the admin audit log: who sent it, its parameters and its outcome
+pub async fn audit_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let audit_log = &state.audit_log;
+    if !audit_log.enabled() || !is_admin_mutation(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let start = Instant::now();
+    let headers = request.headers();
+    let actor = actor(headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()));
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+
+    let max_parameters_bytes = audit_log.max_parameters_bytes();
+    let (request, parameters) = if UPLOAD_PATHS.contains(&path.as_str()) {
+        let parameters = parameters(query.as_deref(), &[], max_parameters_bytes);
+        (request, parameters)
+    } else {
+        let (parts, body) = request.into_parts();
+        let body = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+            Ok(body) => body,
+            Err(e) => {
+                return ErrorResponse::validation_error(format!(
+                    "Failed to read request body: {}",
+                    e
+                ))
+                .into_response()
+            }
+        };
+        let parameters = parameters(query.as_deref(), &body, max_parameters_bytes);
+        (Request::from_parts(parts, Body::from(body)), parameters)
+    };
+
+    let response = next.run(request).await;
+
+    let status = response.status();
+    audit_log
+        .record(AdminAuditEntry {
+            actor,
+            method,
+            path,
+            parameters,
+            status: status.as_u16() as i32,
+            outcome: outcome(status).to_string(),
+            duration_ms: start.elapsed().as_millis() as i64,
+            created_at: None,
+        })
+        .await;
+
+    response
+}
+
+/// Who sent a request: a fingerprint of its API key (never the key itself), or
+/// `anonymous` without one
+fn actor(api_key: Option<&str>) -> String {
+    match api_key {
+        Some(key) => format!("key:{}", &hash_query(key)[..ACTOR_FINGERPRINT_LENGTH]),
+        None => "anonymous".to_string(),
+    }
+}
+
+/// Query string (`?...`) and request body, separated by a space and truncated to
+/// `max_bytes`
+fn parameters(query: Option<&str>, body: &[u8], max_bytes: usize) -> Option<String> {
+    let body = String::from_utf8_lossy(body);
+    let mut parameters = [
+        query.filter(|q| !q.is_empty()).map(|q| format!("?{}", q)),
+        Some(body.trim().to_string()).filter(|b| !b.is_empty()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    if parameters.len() > max_bytes {
+        let mut end = max_bytes;
+        while !parameters.is_char_boundary(end) {
+            end -= 1;
+        }
+        parameters.truncate(end);
+    }
+    Some(parameters).filter(|p| !p.is_empty())
+}
+
+fn outcome(status: StatusCode) -> &'static str {
+    if status.is_client_error() || status.is_server_error() {
+        "failure"
+    } else {
+        "success"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actor_hides_api_key() {
+        let actor = actor(Some("secret-key"));
+        assert!(actor.starts_with("key:"));
+        assert_eq!(actor.len(), 4 + ACTOR_FINGERPRINT_LENGTH);
+        assert!(!actor.contains("secret"));
+        assert_eq!(super::actor(None), "anonymous");
+    }
+
+    #[test]
+    fn test_parameters() {
+        assert_eq!(parameters(None, b"", 100), None);
+        assert_eq!(
+            parameters(Some("limit=5"), b"", 100).as_deref(),
+            Some("?limit=5")
+        );
+        assert_eq!(
+            parameters(Some("force=true"), b" {\"a\":1}\n", 100).as_deref(),
+            Some("?force=true {\"a\":1}")
+        );
+        assert_eq!(
+            parameters(None, "{\"name\":\"é\"}".as_bytes(), 10).as_deref(),
+            Some("{\"name\":\"")
+        );
+    }
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(outcome(StatusCode::OK), "success");
+        assert_eq!(outcome(StatusCode::ACCEPTED), "success");
+        assert_eq!(outcome(StatusCode::FORBIDDEN), "failure");
+        assert_eq!(outcome(StatusCode::INTERNAL_SERVER_ERROR), "failure");
+    }
+}
@@ -0,0 +1,262 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+use serde_json::json;
+
+use crate::api::handlers::{AppState, MAX_COLLECTION_IDENTIFIERS};
+use crate::config::BatchConfig;
+use crate::errors::{ErrorCode, ErrorResponse};
+
+/// Guard the batch endpoints against hostile payloads before they are deserialized.
+///
+/// The body is read frame by frame and scanned as it arrives: the request is rejected
+/// with 413 as soon as it exceeds `max_body_bytes`, nests deeper than `max_json_depth`,
+/// or its item array (e.g. `ids`) grows past the endpoint's batch limit.
+pub async fn body_limit_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(max_items) = batch_item_limit(request.method(), request.uri().path(), &state.batch)
+    else {
+        return next.run(request).await;
+    };
+    let max_bytes = state.batch.max_body_bytes;
+
+    let declared = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if declared.is_some_and(|len| len > max_bytes) {
+        return PayloadError::TooLarge { max: max_bytes }.into_response();
+    }
+
+    let (parts, body) = request.into_parts();
+    let mut stream = body.into_data_stream();
+    let mut scanner = JsonScanner::new(state.batch.max_json_depth, max_items);
+    let mut buffer = Vec::with_capacity(declared.unwrap_or(0));
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                return ErrorResponse::validation_error(format!(
+                    "Failed to read request body: {}",
+                    e
+                ))
+                .into_response()
+            }
+        };
+        if buffer.len() + chunk.len() > max_bytes {
+            return PayloadError::TooLarge { max: max_bytes }.into_response();
+        }
+        if let Err(e) = scanner.feed(&chunk) {
+            return e.into_response();
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+
+    next.run(Request::from_parts(parts, Body::from(buffer))).await
+}
+
+/// Item limit for a batch endpoint, or `None` for requests that are not guarded
+fn batch_item_limit(method: &Method, path: &str, batch: &BatchConfig) -> Option<usize> {
+    if method != Method::POST {
+        return None;
+    }
+    // The collection endpoint is also mounted under the optional Scryfall prefix
+    let path = path.strip_prefix("/scryfall").unwrap_or(path);
+    match path {
+        "/cards/batch" => Some(batch.max_ids),
+        "/cards/named/batch" => Some(batch.max_names),
+        "/queries/batch" => Some(batch.max_queries),
+        "/cards/collection" => Some(MAX_COLLECTION_IDENTIFIERS),
+        // Collection imports: /collections/{id}/cards
+        path if path.starts_with("/collections/") && path.ends_with("/cards") => {
+            Some(batch.max_ids)
+        }
+        _ => None,
+    }
+}
+
+/// Reasons a batch request body is rejected before deserialization
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PayloadError {
+    TooLarge { max: usize },
+    TooDeep { max: usize },
+    TooManyItems { max: usize },
+}
+
+impl IntoResponse for PayloadError {
+    fn into_response(self) -> Response {
+        let (message, details) = match self {
+            Self::TooLarge { max } => (
+                format!("Request body exceeds {} bytes", max),
+                json!({ "limit": "body_bytes", "max": max }),
+            ),
+            Self::TooDeep { max } => (
+                format!("JSON nesting exceeds depth {}", max),
+                json!({ "limit": "json_depth", "max": max }),
+            ),
+            Self::TooManyItems { max } => (
+                format!("Too many batch items (max {})", max),
+                json!({ "limit": "items", "max": max }),
+            ),
+        };
+        ErrorResponse::with_details(ErrorCode::PayloadTooLarge, message, details).into_response()
+    }
+}
+
+/// Incremental JSON structure scanner. It does not validate the document (the
+/// deserializer does that afterwards); it only tracks nesting depth and counts the
+/// elements of arrays that are direct fields of the top-level object.
+struct JsonScanner {
+    max_depth: usize,
+    max_items: usize,
+    /// Open containers: `Some(items)` for arrays, `None` for objects
+    stack: Vec<Option<usize>>,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonScanner {
+    fn new(max_depth: usize, max_items: usize) -> Self {
+        Self {
+            max_depth,
+            max_items,
+            stack: Vec::new(),
+            in_string: false,
+            escaped: false,
+        }
+    }
+
+    fn feed(&mut self, bytes: &[u8]) -> Result<(), PayloadError> {
+        for &byte in bytes {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+            match byte {
+                b' ' | b'\t' | b'\n' | b'\r' | b':' => {}
+                b']' | b'}' => {
+                    self.stack.pop();
+                }
+                b',' => {
+                    if let Some(Some(items)) = self.stack.last_mut() {
+                        *items += 1;
+                    }
+                    self.check_items()?;
+                }
+                _ => {
+                    // First byte of an array's first element
+                    if let Some(Some(items)) = self.stack.last_mut() {
+                        *items = (*items).max(1);
+                    }
+                    match byte {
+                        b'"' => self.in_string = true,
+                        b'[' | b'{' => {
+                            self.stack.push((byte == b'[').then_some(0));
+                            if self.stack.len() > self.max_depth {
+                                return Err(PayloadError::TooDeep {
+                                    max: self.max_depth,
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Item arrays sit at depth 2 (`{"ids": [...]}`)
+    fn check_items(&self) -> Result<(), PayloadError> {
+        match self.stack.as_slice() {
+            [None, Some(items)] if *items > self.max_items => Err(PayloadError::TooManyItems {
+                max: self.max_items,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(body: &str, max_depth: usize, max_items: usize) -> Result<(), PayloadError> {
+        JsonScanner::new(max_depth, max_items).feed(body.as_bytes())
+    }
+
+    #[test]
+    fn test_scanner_counts_top_level_items() {
+        assert_eq!(scan(r#"{"ids": ["a", "b", "c"]}"#, 8, 3), Ok(()));
+        assert_eq!(
+            scan(r#"{"ids": ["a", "b", "c", "d"]}"#, 8, 3),
+            Err(PayloadError::TooManyItems { max: 3 })
+        );
+        // Commas inside strings and nested objects are not items
+        assert_eq!(
+            scan(
+                r#"{"queries": [{"id": "a,b", "query": "c:r,g"}, {"x": [1, 2, 3, 4]}]}"#,
+                8,
+                2
+            ),
+            Ok(())
+        );
+        assert_eq!(scan(r#"{"names": ["a\",b"]}"#, 8, 1), Ok(()));
+    }
+
+    #[test]
+    fn test_scanner_rejects_deep_nesting() {
+        assert_eq!(scan(r#"{"a": [[1]]}"#, 3, 10), Ok(()));
+        assert_eq!(
+            scan(r#"{"a": [[[1]]]}"#, 3, 10),
+            Err(PayloadError::TooDeep { max: 3 })
+        );
+        assert_eq!(scan("\"[[[[[[\"", 3, 10), Ok(()));
+    }
+
+    #[test]
+    fn test_scanner_across_chunks() {
+        let mut scanner = JsonScanner::new(8, 3);
+        scanner.feed(br#"{"ids": ["a", "b"#).unwrap();
+        scanner.feed(br#"c", "d,"#).unwrap();
+        assert_eq!(
+            scanner.feed(br#"", "e"]}"#),
+            Err(PayloadError::TooManyItems { max: 3 })
+        );
+    }
+
+    #[test]
+    fn test_batch_item_limit() {
+        let batch = BatchConfig::default();
+        assert_eq!(
+            batch_item_limit(&Method::POST, "/cards/batch", &batch),
+            Some(batch.max_ids)
+        );
+        assert_eq!(
+            batch_item_limit(&Method::POST, "/scryfall/cards/collection", &batch),
+            Some(MAX_COLLECTION_IDENTIFIERS)
+        );
+        assert_eq!(
+            batch_item_limit(&Method::POST, "/collections/binder/cards", &batch),
+            Some(batch.max_ids)
+        );
+        assert_eq!(batch_item_limit(&Method::GET, "/cards/batch", &batch), None);
+        assert_eq!(batch_item_limit(&Method::POST, "/graphql", &batch), None);
+    }
+}
@@ -0,0 +1,27 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use tokio::time::Instant;
+
+use crate::api::handlers::AppState;
+use crate::deadline::{self, TIMEOUT_HEADER};
+
+/// Give each request a time budget (the configured default, or the client's
+/// `X-Request-Timeout-Ms` up to the configured maximum). Cache lookups and upstream
+/// Scryfall fetches stop with a 504 `TIMEOUT` error once it runs out.
+pub async fn deadline_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let header = request
+        .headers()
+        .get(TIMEOUT_HEADER)
+        .and_then(|value| value.to_str().ok());
+    match state.request_timeout.budget(header) {
+        Some(budget) => deadline::scope(Instant::now() + budget, next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
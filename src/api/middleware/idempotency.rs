@@ -0,0 +1,344 @@
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use super::tenant::API_KEY_HEADER;
+use crate::api::handlers::AppState;
+use crate::cache::manager::CacheManager;
+use crate::errors::{ErrorCode, ErrorResponse};
+use crate::models::idempotency::IdempotencyRecord;
+
+/// Request header carrying the client's idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Response header marking a response replayed from the idempotency store
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Longest idempotency key accepted
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Largest request body buffered to fingerprint a request (the JSON extractor's limit)
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Batch endpoints accepting an idempotency key, besides the admin POSTs
+const BATCH_PATHS: &[&str] = &["/cards/batch", "/cards/named/batch", "/queries/batch"];
+
+/// Admin endpoints taking file uploads, which are too large to fingerprint
+const UPLOAD_PATHS: &[&str] = &["/admin/restore"];
+
+/// Answer POSTs to the admin and batch endpoints that repeat an `Idempotency-Key` with
+/// the response to the first request, so retried reloads and big batch queries don't
+/// redo their work.
+///
+/// The first request with a key claims it; its response is stored for
+/// `IDEMPOTENCY_TTL_SECONDS` (in Redis when configured, the database otherwise) unless it
+/// is a 5xx or 409 (e.g. a reload already running), which releases the key so a retry
+/// runs again. So does a request that never produces a response, because the client
+/// went away or the handler panicked. Keys are scoped to the caller's credentials and the
+/// endpoint. Repeating a key while its request is still running is rejected with 409,
+/// and repeating it for a different request with 422.
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let config = &state.idempotency;
+    if !config.enabled
+        || state.read_only
+        || !is_idempotent_route(request.method(), request.uri().path())
+    {
+        return next.run(request).await;
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str().map(str::trim) {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+        _ => {
+            return ErrorResponse::validation_error(format!(
+                "Idempotency-Key must be 1 to {} visible ASCII characters",
+                MAX_KEY_LENGTH
+            ))
+            .into_response()
+        }
+    };
+    let scope = idempotency_scope(request.headers(), request.uri().path(), &key);
+
+    let (parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            return ErrorResponse::validation_error(format!("Failed to read request body: {}", e))
+                .into_response()
+        }
+    };
+    let request_hash =
+        request_fingerprint(&parts.method, parts.uri.path(), parts.uri.query(), &body);
+    let request = Request::from_parts(parts, Body::from(body));
+
+    let cache = &state.cache_manager;
+    match cache
+        .claim_idempotency_key(&scope, &request_hash, config.ttl_seconds)
+        .await
+    {
+        Ok(None) => {}
+        Ok(Some(record)) => return replay(&key, &request_hash, record),
+        Err(e) => {
+            // An unavailable store shouldn't take the endpoints down with it
+            warn!(
+                "Idempotency store unavailable, running request without it: {}",
+                e
+            );
+            return next.run(request).await;
+        }
+    }
+    let claim = Claim::new(cache.clone(), scope);
+
+    let response = next.run(request).await;
+
+    // Failures and conflicts may be transient, and streamed or oversized bodies can't be
+    // kept: let retries of those run again
+    let storable = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|len| len <= config.max_response_bytes as u64);
+    let status = response.status();
+    if status.is_server_error() || status == StatusCode::CONFLICT || !storable {
+        claim.release().await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, config.max_response_bytes).await {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to read response for idempotency key: {}", e);
+            claim.release().await;
+            return ErrorResponse::new(ErrorCode::InternalError, "Failed to read response")
+                .into_response();
+        }
+    };
+    let record = IdempotencyRecord {
+        request_hash,
+        status: Some(parts.status.as_u16()),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    };
+    claim.complete(&record).await;
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// A claimed idempotency key. Dropping it before its response is stored or the key is
+/// released (the request future was dropped, or the handler panicked) releases the key
+/// in the background, so retries don't see a request in progress until the key expires.
+struct Claim {
+    cache: Arc<CacheManager>,
+    scope: String,
+    settled: bool,
+}
+
+impl Claim {
+    fn new(cache: Arc<CacheManager>, scope: String) -> Self {
+        Self {
+            cache,
+            scope,
+            settled: false,
+        }
+    }
+
+    /// Store `record` for replaying to retries
+    async fn complete(mut self, record: &IdempotencyRecord) {
+        self.settled = true;
+        if let Err(e) = self
+            .cache
+            .complete_idempotency_key(&self.scope, record)
+            .await
+        {
+            error!("Failed to store idempotent response: {}", e);
+        }
+    }
+
+    /// Drop the key without a response, so a retry runs again
+    async fn release(mut self) {
+        self.settled = true;
+        release(&self.cache, &self.scope).await;
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if self.settled {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            // The key expires after IDEMPOTENCY_TTL_SECONDS instead
+            return;
+        };
+        let cache = self.cache.clone();
+        let scope = std::mem::take(&mut self.scope);
+        runtime.spawn(async move { release(&cache, &scope).await });
+    }
+}
+
+async fn release(cache: &CacheManager, scope: &str) {
+    if let Err(e) = cache.release_idempotency_key(scope).await {
+        error!("Failed to release idempotency key: {}", e);
+    }
+}
+
+/// Whether `method` and `path` accept an idempotency key: POSTs to the admin endpoints
+/// (except uploads) and to the batch endpoints
+pub(crate) fn is_idempotent_route(method: &Method, path: &str) -> bool {
+    if method != Method::POST {
+        return false;
+    }
+    let admin = ["/admin/", "/api/admin/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix));
+    (admin && !UPLOAD_PATHS.contains(&path)) || BATCH_PATHS.contains(&path)
+}
+
+/// Storage key of idempotency key `key`: a hash of it, the endpoint and the caller's
+/// credentials, so callers can't read each other's responses by guessing keys
+pub fn idempotency_scope(headers: &HeaderMap, path: &str, key: &str) -> String {
+    let mut hasher = Sha256::new();
+    for credential in [header::AUTHORIZATION.as_str(), API_KEY_HEADER] {
+        hasher.update(headers.get(credential).map_or(&b""[..], |v| v.as_bytes()));
+        hasher.update([0]);
+    }
+    hasher.update(path.as_bytes());
+    hasher.update([0]);
+    hasher.update(key.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Fingerprint of a request, to tell retries from a different request reusing a key
+pub fn request_fingerprint(
+    method: &Method,
+    path: &str,
+    query: Option<&str>,
+    body: &[u8],
+) -> String {
+    let mut hasher = Sha256::new();
+    for part in [method.as_str(), path, query.unwrap_or("")] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+/// Response to a request whose key was already claimed
+fn replay(key: &str, request_hash: &str, record: IdempotencyRecord) -> Response {
+    if record.request_hash != request_hash {
+        return ErrorResponse::new(
+            ErrorCode::IdempotencyKeyMismatch,
+            format!(
+                "Idempotency key '{}' was already used for a different request",
+                key
+            ),
+        )
+        .into_response();
+    }
+    let Some(status) = record.status.and_then(|s| StatusCode::from_u16(s).ok()) else {
+        return ErrorResponse::new(
+            ErrorCode::IdempotencyConflict,
+            format!(
+                "A request with idempotency key '{}' is still in progress",
+                key
+            ),
+        )
+        .into_response();
+    };
+
+    let mut response = (status, record.body).into_response();
+    let headers = response.headers_mut();
+    match record
+        .content_type
+        .and_then(|v| HeaderValue::from_str(&v).ok())
+    {
+        Some(content_type) => headers.insert(header::CONTENT_TYPE, content_type),
+        None => headers.remove(header::CONTENT_TYPE),
+    };
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_idempotent_route() {
+        assert!(is_idempotent_route(&Method::POST, "/admin/reload"));
+        assert!(is_idempotent_route(&Method::POST, "/admin/repair"));
+        assert!(is_idempotent_route(&Method::POST, "/queries/batch"));
+        assert!(is_idempotent_route(&Method::POST, "/cards/batch"));
+        assert!(!is_idempotent_route(&Method::POST, "/admin/restore"));
+        assert!(!is_idempotent_route(
+            &Method::DELETE,
+            "/admin/cache/queries"
+        ));
+        assert!(!is_idempotent_route(&Method::GET, "/cards/search"));
+        assert!(!is_idempotent_route(&Method::POST, "/saved-searches"));
+    }
+
+    #[test]
+    fn test_scope_separates_callers_and_endpoints() {
+        let mut headers = HeaderMap::new();
+        let anonymous = idempotency_scope(&headers, "/admin/reload", "retry-1");
+        assert_eq!(
+            anonymous,
+            idempotency_scope(&headers, "/admin/reload", "retry-1")
+        );
+        assert_ne!(
+            anonymous,
+            idempotency_scope(&headers, "/admin/repair", "retry-1")
+        );
+
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("tenant-a"));
+        assert_ne!(
+            anonymous,
+            idempotency_scope(&headers, "/admin/reload", "retry-1")
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let hash = request_fingerprint(&Method::POST, "/admin/reload", None, b"");
+        assert_ne!(
+            hash,
+            request_fingerprint(&Method::POST, "/admin/reload", Some("limit=5"), b"")
+        );
+
+        let pending = replay("k", &hash, IdempotencyRecord::pending(hash.clone()));
+        assert_eq!(pending.status(), StatusCode::CONFLICT);
+
+        let done = IdempotencyRecord {
+            status: Some(200),
+            content_type: Some("application/json".to_string()),
+            body: b"{\"success\":true}".to_vec(),
+            ..IdempotencyRecord::pending(hash.clone())
+        };
+        let response = replay("k", &hash, done.clone());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+
+        let mismatch = replay("k", "other", done);
+        assert_eq!(mismatch.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
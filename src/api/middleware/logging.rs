@@ -1,9 +1,11 @@
 use axum::{extract::Request, middleware::Next, response::Response};
 use std::time::Instant;
-use tracing::{info, warn};
+use tracing::{field, info, info_span, warn, Instrument};
 use uuid::Uuid;
 
-/// Middleware to log all HTTP requests and responses with structured data
+/// Middleware to log all HTTP requests and responses with structured data. Requests run
+/// in a `request` span whose `status`, `latency_ms`, `query_hash` and `cache_tier` fields
+/// are filled in as the request is served, so every log line of a request carries them.
 pub async fn logging_middleware(request: Request, next: Next) -> Response {
     let start = Instant::now();
     let request_id = Uuid::new_v4().to_string();
@@ -29,12 +31,26 @@ pub async fn logging_middleware(request: Request, next: Next) -> Response {
         "Incoming request"
     );
 
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %method,
+        path = %path,
+        status = field::Empty,
+        latency_ms = field::Empty,
+        query_hash = field::Empty,
+        cache_tier = field::Empty,
+    );
+
     // Process request
-    let response = next.run(request).await;
+    let response = next.run(request).instrument(span.clone()).await;
 
     // Calculate duration
     let duration = start.elapsed();
     let status = response.status();
+    span.record("status", status.as_u16());
+    span.record("latency_ms", duration.as_millis() as u64);
+    let _entered = span.enter();
 
     // Log response
     if status.is_success() {
@@ -1,3 +1,17 @@
+pub mod admin_auth;
+pub mod audit;
+pub mod body_limit;
+pub mod deadline;
+pub mod idempotency;
 pub mod logging;
+pub mod read_only;
+pub mod tenant;
 
+pub use admin_auth::admin_auth_middleware;
+pub use audit::audit_middleware;
+pub use body_limit::body_limit_middleware;
+pub use deadline::deadline_middleware;
+pub use idempotency::idempotency_middleware;
 pub use logging::logging_middleware;
+pub use read_only::read_only_middleware;
+pub use tenant::tenant_middleware;
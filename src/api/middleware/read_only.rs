@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::handlers::AppState;
+use crate::errors::{ErrorCode, ErrorResponse};
+
+/// Reject admin mutations (anything but GET/HEAD/OPTIONS under `/admin` or `/api/admin`)
+/// with 403 when the service runs read-only
+pub async fn read_only_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if state.read_only && is_admin_mutation(request.method(), request.uri().path()) {
+        return ErrorResponse::new(
+            ErrorCode::ReadOnly,
+            "Service is read-only: admin mutations are disabled",
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+pub(super) fn is_admin_mutation(method: &Method, path: &str) -> bool {
+    let admin = ["/admin/", "/api/admin/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix));
+    admin && !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_admin_mutation() {
+        assert!(is_admin_mutation(&Method::POST, "/admin/reload"));
+        assert!(is_admin_mutation(&Method::DELETE, "/admin/cache/queries"));
+        assert!(is_admin_mutation(
+            &Method::PUT,
+            "/api/admin/config/query-limits"
+        ));
+        assert!(!is_admin_mutation(&Method::GET, "/admin/cache/queries"));
+        assert!(!is_admin_mutation(&Method::GET, "/api/admin/imports"));
+        assert!(!is_admin_mutation(&Method::POST, "/cards/batch"));
+        assert!(!is_admin_mutation(&Method::POST, "/graphql"));
+    }
+}
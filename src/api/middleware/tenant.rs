@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Request, State},
+    http::{header::RETRY_AFTER, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::handlers::AppState;
+use crate::errors::{ErrorCode, ErrorResponse};
+use crate::tenant::{self, TenantError};
+
+/// Header carrying a tenant API key
+pub(super) const API_KEY_HEADER: &str = "x-api-key";
+
+/// Assign each request to a tenant (from its API key or tenant header), enforce the
+/// tenant's rate limit and serve the request in that tenant's scope, so searches use the
+/// tenant's query caches. Health checks and metrics are left alone.
+pub async fn tenant_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if !state.tenants.enabled() || path.starts_with("/health") || path == "/metrics" {
+        return next.run(request).await;
+    }
+
+    let headers = request.headers();
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let resolved = state
+        .tenants
+        .resolve(header(API_KEY_HEADER), header(state.tenants.header()));
+    let tenant = match resolved {
+        Ok(tenant) => tenant,
+        Err(e @ (TenantError::UnknownApiKey | TenantError::ApiKeyRequired(_))) => {
+            return ErrorResponse::new(ErrorCode::Unauthorized, e.to_string()).into_response();
+        }
+        Err(e) => return ErrorResponse::validation_error(e.to_string()).into_response(),
+    };
+
+    if let Err(retry_after) = tenant.admit() {
+        let message = format!("Rate limit exceeded for tenant '{}'", tenant.id());
+        let mut response = ErrorResponse::new(ErrorCode::RateLimited, message).into_response();
+        let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(seconds));
+        return response;
+    }
+
+    tenant::scope(tenant, next.run(request)).await
+}
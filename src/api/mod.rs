@@ -1,4 +1,10 @@
+pub mod compat;
+pub mod cors;
+pub mod export;
 pub mod handlers;
+pub mod health;
+pub mod http_cache;
 pub mod middleware;
 pub mod openapi;
+pub mod parquet;
 pub mod routes;
@@ -1,15 +1,50 @@
-use utoipa::OpenApi;
+use axum::http::Method;
+use serde_json::json;
+use utoipa::openapi::path::PathItemType;
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+};
+use utoipa::openapi::{ContentBuilder, Ref, RefOr, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
 
 use crate::api::handlers::{
-    AdminOverview, AdminOverviewResponse, AutocompleteParams, AutocompleteResponse,
-    BatchCardsData, BatchCardsRequest, BatchCardsResponse, BatchNamedData, BatchNamedRequest,
-    BatchNamedResponse, BatchNamedResult, BatchQueriesData, BatchQueriesRequest,
-    BatchQueriesResponse, BatchQueryItem, BatchQueryResult, CardListResponse, CardResponse,
-    NamedParams, PaginatedCardData, ReloadResponse, SearchParams, StatsResponse,
+    AdminAuditParams, AdminAuditResponse, AdminOverview, AdminOverviewResponse, AutocompleteParams,
+    AutocompleteResponse, BatchCardsData, BatchCardsRequest, BatchCardsResponse, BatchNamedData,
+    BatchNamedRequest, BatchNamedResponse, BatchNamedResult, BatchQueriesData, BatchQueriesRequest,
+    BatchQueriesResponse, BatchQueryItem, BatchQueryResult, BulkImportErrorParams,
+    BulkImportErrors, BulkImportErrorsResponse, BulkImportListParams, BulkImportsResponse,
+    CardFacesResponse, CardListResponse, CardResponse, CircuitBreakerResponse,
+    CircuitBreakersResponse, CollectionCardInput, CollectionCardsRequest, CollectionData,
+    CollectionIdentifier, CollectionRequest, CollectionResponse, CollectionUpsertData,
+    CollectionUpsertResponse, CollectionValueParams, CollectionValueResponse, ExportParams,
+    FeaturesReport, FeaturesResponse, NamedParams, PaginatedCardData, ParquetParams,
+    QueryCacheEntriesResponse, QueryCacheListParams, QueryCachePurge, QueryCachePurgeResponse,
+    QueryLimitsResponse, RandomParams, RelatedCardsResponse, ReloadResponse, RenderParams,
+    RenderedCardResponse, RepairParams, RepairResponse, RestoreResponse, SampleData, SampleParams,
+    SampleResponse, SavedSearchListResponse, SavedSearchNewData, SavedSearchNewResponse,
+    SavedSearchRequest, SavedSearchResponse, SchemaVersionResponse, SearchParams, StatsResponse,
+    TenantStatsResponse, TopQueriesParams, TopQueriesResponse, WarmupStatus, WarmupStatusResponse,
 };
-use crate::cache::manager::CacheStats;
+use crate::api::middleware::idempotency::is_idempotent_route;
+use crate::cache::analytics::TopQuery;
+use crate::cache::manager::{BulkImportStats, CacheStats, TierStats};
+use crate::cache::redis::RedisStats;
+use crate::cache::warmup::WarmupRun;
+use crate::circuit_breaker::{CircuitBreakerMetrics, CircuitState};
+use crate::collections::CollectionValue;
 use crate::errors::{ErrorCode, ErrorDetail, ErrorResponse};
-use crate::models::card::Card;
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::models::bulk_import::{BulkImport, BulkImportError};
+use crate::models::card::{Card, CardFace, CardPart, RelatedCard};
+use crate::models::collection::Finish;
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::{IntegrityIssue, RepairReport};
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::{AppliedMigration, SchemaVersion};
+use crate::render::{RenderFormat, RenderedCard, RenderedFace};
+use crate::scryfall::preview::{PreviewPollRun, PreviewPollStatus};
+use crate::query::{QueryLimits, QueryLimitsUpdate};
+use crate::tenant::TenantStats;
 
 #[derive(OpenApi)]
 #[openapi(
@@ -21,11 +56,28 @@ use crate::models::card::Card;
             name = "Scryfall Cache API",
         )
     ),
+    servers(
+        (url = "/", description = "This instance"),
+    ),
+    // The API key is only checked when tenants are enabled
+    security(
+        (),
+        ("api_key" = []),
+    ),
+    modifiers(&SecurityAddon, &ErrorResponsesAddon),
     paths(
         crate::api::handlers::health,
         crate::api::handlers::health_live,
         crate::api::handlers::health_ready,
         crate::api::handlers::admin_stats_overview,
+        crate::api::handlers::admin_top_queries,
+        crate::api::handlers::admin_list_imports,
+        crate::api::handlers::admin_import_errors,
+        crate::api::handlers::admin_audit_log,
+        crate::api::handlers::admin_tenant_stats,
+        crate::api::handlers::admin_schema_version,
+        crate::api::handlers::admin_get_query_limits,
+        crate::api::handlers::admin_update_query_limits,
         crate::api::handlers::search_cards,
         crate::api::handlers::batch_get_cards,
         crate::api::handlers::batch_get_cards_by_name,
@@ -33,19 +85,91 @@ use crate::models::card::Card;
         crate::api::handlers::get_card_by_name,
         crate::api::handlers::autocomplete_cards,
         crate::api::handlers::get_card,
+        crate::api::handlers::get_card_by_set_and_number,
+        crate::api::handlers::get_card_by_multiverse_id,
+        crate::api::handlers::get_card_by_arena_id,
+        crate::api::handlers::get_card_by_mtgo_id,
+        crate::api::handlers::get_card_faces,
+        crate::api::handlers::get_related_cards,
+        crate::api::handlers::get_card_rendered,
+        crate::api::handlers::get_card_sample,
+        crate::api::handlers::search_cards_parquet,
+        crate::api::handlers::get_random_card,
+        crate::api::handlers::get_card_collection,
+        crate::api::handlers::upsert_collection_cards,
+        crate::api::handlers::get_collection_value,
+        crate::api::handlers::create_saved_search,
+        crate::api::handlers::list_saved_searches,
+        crate::api::handlers::delete_saved_search,
+        crate::api::handlers::get_saved_search_new_cards,
+        crate::api::handlers::list_sets,
+        crate::api::handlers::get_set,
+        crate::api::handlers::get_catalog,
+        crate::api::handlers::get_symbology,
         crate::api::handlers::get_stats,
         crate::api::handlers::admin_reload,
+        crate::api::handlers::admin_export,
+        crate::api::handlers::admin_backup,
+        crate::api::handlers::admin_restore,
+        crate::api::handlers::admin_repair,
+        crate::api::handlers::admin_features,
+        crate::api::handlers::admin_circuit_breakers,
+        crate::api::handlers::admin_reset_circuit_breaker,
+        crate::api::handlers::admin_list_cached_queries,
+        crate::api::handlers::admin_evict_cached_query,
+        crate::api::handlers::admin_clear_cached_queries,
+        crate::api::handlers::admin_warmup_status,
+        crate::api::handlers::admin_warmup,
     ),
     components(
         schemas(
             Card,
+            CardFace,
+            CardFacesResponse,
+            CardPart,
+            RelatedCard,
+            RelatedCardsResponse,
+            RenderParams,
+            RenderFormat,
+            RenderedFace,
+            RenderedCard,
+            RenderedCardResponse,
+            SampleParams,
+            SampleData,
+            SampleResponse,
+            ParquetParams,
+            IntegrityIssue,
+            RepairReport,
+            RepairParams,
+            RepairResponse,
             AdminOverview,
             AdminOverviewResponse,
+            PreviewPollStatus,
+            PreviewPollRun,
+            TopQueriesParams,
+            TopQuery,
+            TopQueriesResponse,
+            BulkImportListParams,
+            BulkImport,
+            BulkImportsResponse,
+            BulkImportErrorParams,
+            BulkImportError,
+            BulkImportErrors,
+            BulkImportErrorsResponse,
+            AdminAuditParams,
+            AdminAuditEntry,
+            AdminAuditResponse,
+            TenantStats,
+            TenantStatsResponse,
+            AppliedMigration,
+            SchemaVersion,
+            SchemaVersionResponse,
             CardResponse,
             CardListResponse,
             PaginatedCardData,
             StatsResponse,
             ReloadResponse,
+            RestoreResponse,
             AutocompleteResponse,
             BatchCardsRequest,
             BatchCardsData,
@@ -59,10 +183,50 @@ use crate::models::card::Card;
             BatchQueriesRequest,
             BatchQueriesData,
             BatchQueriesResponse,
+            CollectionIdentifier,
+            CollectionRequest,
+            CollectionData,
+            CollectionResponse,
+            Finish,
+            CollectionCardInput,
+            CollectionCardsRequest,
+            CollectionUpsertData,
+            CollectionUpsertResponse,
+            CollectionValueParams,
+            CollectionValue,
+            CollectionValueResponse,
+            SavedSearch,
+            SavedSearchRequest,
+            SavedSearchResponse,
+            SavedSearchListResponse,
+            SavedSearchNewData,
+            SavedSearchNewResponse,
             CacheStats,
+            RedisStats,
+            TierStats,
+            BulkImportStats,
             SearchParams,
             NamedParams,
             AutocompleteParams,
+            RandomParams,
+            ExportParams,
+            FeaturesReport,
+            FeaturesResponse,
+            CircuitBreakerMetrics,
+            CircuitState,
+            CircuitBreakersResponse,
+            CircuitBreakerResponse,
+            QueryCacheListParams,
+            QueryCacheEntry,
+            QueryCachePurge,
+            QueryCacheEntriesResponse,
+            QueryCachePurgeResponse,
+            QueryLimits,
+            QueryLimitsUpdate,
+            QueryLimitsResponse,
+            WarmupRun,
+            WarmupStatus,
+            WarmupStatusResponse,
             ErrorResponse,
             ErrorDetail,
             ErrorCode,
@@ -70,9 +234,209 @@ use crate::models::card::Card;
     ),
     tags(
         (name = "health", description = "Health check endpoints"),
-        (name = "cards", description = "Card search and retrieval endpoints"),
+        (name = "cards", description = "Card search and retrieval endpoints, including batch lookups"),
+        (name = "collections", description = "Owned card quantities per tenant, valued from cached prices"),
+        (name = "saved-searches", description = "Saved searches re-evaluated after every bulk import to report newly matching cards"),
         (name = "statistics", description = "Cache statistics and metrics"),
-        (name = "admin", description = "Administrative endpoints"),
+        (name = "admin", description = "Administrative endpoints. Mutations are recorded in the audit log and rejected on read-only replicas."),
     )
 )]
 pub struct ApiDoc;
+
+/// Name of the API key security scheme
+const API_KEY_SCHEME: &str = "api_key";
+
+/// Name of the admin bearer token security scheme
+const ADMIN_TOKEN_SCHEME: &str = "admin_token";
+
+/// Declares the `X-API-Key` header that assigns requests to tenants, and the bearer
+/// token guarding the admin endpoints
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            API_KEY_SCHEME,
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "x-api-key",
+                "Tenant API key (see TENANT_API_KEYS). Unknown keys are rejected with 401.",
+            ))),
+        );
+        components.add_security_scheme(
+            ADMIN_TOKEN_SCHEME,
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "Admin token (see ADMIN_TOKEN), required by the admin endpoints and \
+                         /metrics when set",
+                    ))
+                    .build(),
+            ),
+        );
+
+        // Each check applies only when configured, so none is mandatory
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            if !(path.starts_with("/admin/") || path.starts_with("/api/admin/")) {
+                continue;
+            }
+            for operation in item.operations.values_mut() {
+                operation.security = Some(vec![
+                    SecurityRequirement::default(),
+                    SecurityRequirement::new(API_KEY_SCHEME, Vec::<String>::new()),
+                    SecurityRequirement::new(ADMIN_TOKEN_SCHEME, Vec::<String>::new()),
+                ]);
+            }
+        }
+    }
+}
+
+/// Shared error responses: name, error code and example message
+const ERROR_RESPONSES: &[(&str, ErrorCode, &str)] = &[
+    ("BadRequest", ErrorCode::ValidationError, "Invalid request parameters"),
+    ("Unauthorized", ErrorCode::Unauthorized, "Unknown API key"),
+    (
+        "ReadOnly",
+        ErrorCode::ReadOnly,
+        "Service is read-only: admin mutations are disabled",
+    ),
+    ("NotFound", ErrorCode::CardNotFound, "Card not found"),
+    (
+        "PayloadTooLarge",
+        ErrorCode::PayloadTooLarge,
+        "Request body exceeds 1048576 bytes",
+    ),
+    (
+        "RateLimited",
+        ErrorCode::RateLimited,
+        "Rate limit exceeded for tenant 'acme'",
+    ),
+    ("InternalError", ErrorCode::InternalError, "Internal server error"),
+    (
+        "ServiceUnavailable",
+        ErrorCode::DatabaseError,
+        "Database error: connection refused",
+    ),
+    ("Timeout", ErrorCode::Timeout, "Request timed out during search"),
+    (
+        "IdempotencyConflict",
+        ErrorCode::IdempotencyConflict,
+        "A request with idempotency key 'reload-42' is still in progress",
+    ),
+    (
+        "IdempotencyKeyMismatch",
+        ErrorCode::IdempotencyKeyMismatch,
+        "Idempotency key 'reload-42' was already used for a different request",
+    ),
+];
+
+/// Adds the `ErrorResponse` envelope as shared responses under `components/responses`,
+/// and references the ones any operation can return: 401 and 429 from the tenant
+/// middleware everywhere, 403 from read-only replicas on admin mutations, and 409 and 422
+/// from the idempotency middleware on admin and batch POSTs
+struct ErrorResponsesAddon;
+
+impl Modify for ErrorResponsesAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        for (name, code, message) in ERROR_RESPONSES {
+            let example = json!({
+                "success": false,
+                "error": {
+                    "code": code,
+                    "message": message,
+                    "request_id": "00000000-0000-0000-0000-000000000000",
+                },
+            });
+            let response = ResponseBuilder::new()
+                .description(format!("{} ({})", code, code.status_code()))
+                .content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(Ref::from_schema_name("ErrorResponse"))
+                        .example(Some(example))
+                        .build(),
+                )
+                .build();
+            components
+                .responses
+                .insert(name.to_string(), RefOr::T(response));
+        }
+
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            let admin = path.starts_with("/admin/") || path.starts_with("/api/admin/");
+            for (method, operation) in item.operations.iter_mut() {
+                let mut shared = vec![("401", "Unauthorized"), ("429", "RateLimited")];
+                if admin && !matches!(method, PathItemType::Get | PathItemType::Head) {
+                    shared.push(("403", "ReadOnly"));
+                }
+                if matches!(method, PathItemType::Post) && is_idempotent_route(&Method::POST, path)
+                {
+                    shared.push(("409", "IdempotencyConflict"));
+                    shared.push(("422", "IdempotencyKeyMismatch"));
+                }
+                for (status, name) in shared {
+                    operation
+                        .responses
+                        .responses
+                        .entry(status.to_string())
+                        .or_insert_with(|| {
+                            RefOr::Ref(Ref::new(format!("#/components/responses/{}", name)))
+                        });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_security_and_error_components() {
+        let doc = ApiDoc::openapi();
+        let components = doc.components.as_ref().unwrap();
+        assert!(components.security_schemes.contains_key(API_KEY_SCHEME));
+        assert!(components.security_schemes.contains_key(ADMIN_TOKEN_SCHEME));
+        for (name, _, _) in ERROR_RESPONSES {
+            assert!(components.responses.contains_key(*name), "missing {}", name);
+        }
+        assert!(!doc.servers.as_ref().unwrap().is_empty());
+
+        let value = serde_json::to_value(&doc).unwrap();
+        let reload = &value["paths"]["/admin/reload"]["post"]["responses"];
+        assert_eq!(reload["403"]["$ref"], "#/components/responses/ReadOnly");
+        assert_eq!(reload["429"]["$ref"], "#/components/responses/RateLimited");
+        let reload_security = &value["paths"]["/admin/reload"]["post"]["security"];
+        assert!(reload_security[2][ADMIN_TOKEN_SCHEME].is_array());
+        let search = &value["paths"]["/cards/search"]["get"]["responses"];
+        assert_eq!(search["401"]["$ref"], "#/components/responses/Unauthorized");
+        assert!(search["403"].is_null());
+        let batch = &value["paths"]["/queries/batch"]["post"]["responses"];
+        assert_eq!(
+            batch["409"]["$ref"],
+            "#/components/responses/IdempotencyConflict"
+        );
+        assert_eq!(
+            batch["422"]["$ref"],
+            "#/components/responses/IdempotencyKeyMismatch"
+        );
+    }
+
+    #[test]
+    fn test_openapi_batch_examples() {
+        let value = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        for path in [
+            "/cards/batch",
+            "/cards/named/batch",
+            "/queries/batch",
+            "/cards/collection",
+            "/collections/{id}/cards",
+        ] {
+            let body = &value["paths"][path]["post"]["requestBody"]["content"]["application/json"];
+            assert!(!body["example"].is_null(), "missing example for {}", path);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Parquet encoding of card result sets for `/cards/search.parquet` (`parquet_export`
+//! feature). Cards are projected onto a flat schema of the main card columns and written
+//! one row group per batch, so large exports stream instead of being buffered.
+
+use futures::stream::BoxStream;
+
+use crate::db::CardStream;
+
+/// Cards per Arrow record batch (and Parquet row group)
+pub const BATCH_ROWS: usize = 8192;
+
+/// MIME type of Parquet files
+pub const CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+/// Stream of encoded Parquet file bytes
+pub type ParquetStream = BoxStream<'static, anyhow::Result<Vec<u8>>>;
+
+#[cfg(feature = "parquet_export")]
+pub use encoder::{encode_stream, record_batch, schema, ParquetEncoder};
+
+/// Without the `parquet_export` feature there is no encoder; callers should check
+/// `cfg!(feature = "parquet_export")` before querying.
+#[cfg(not(feature = "parquet_export"))]
+pub fn encode_stream(_cards: CardStream) -> anyhow::Result<ParquetStream> {
+    anyhow::bail!("Parquet export not compiled (missing parquet_export feature)")
+}
+
+#[cfg(feature = "parquet_export")]
+mod encoder {
+    use arrow_array::builder::{ListBuilder, StringBuilder};
+    use arrow_array::types::Date32Type;
+    use arrow_array::{ArrayRef, Date32Array, Float64Array, Int32Array, RecordBatch, StringArray};
+    use arrow_schema::{DataType, Field, Schema, SchemaRef};
+    use futures::stream::StreamExt;
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+    use tracing::error;
+
+    use super::{CardStream, ParquetStream, BATCH_ROWS};
+    use crate::models::card::Card;
+
+    fn text(name: &str, nullable: bool) -> Field {
+        Field::new(name, DataType::Utf8, nullable)
+    }
+
+    fn text_list(name: &str) -> Field {
+        Field::new_list(name, Field::new("item", DataType::Utf8, true), true)
+    }
+
+    fn float(name: &str) -> Field {
+        Field::new(name, DataType::Float64, true)
+    }
+
+    fn int(name: &str) -> Field {
+        Field::new(name, DataType::Int32, true)
+    }
+
+    /// Flat schema of the exported card columns
+    pub fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            text("id", false),
+            text("oracle_id", true),
+            text("name", false),
+            text("mana_cost", true),
+            float("cmc"),
+            text("type_line", true),
+            text("oracle_text", true),
+            text_list("colors"),
+            text_list("color_identity"),
+            text_list("keywords"),
+            text("set_code", true),
+            text("set_name", true),
+            text("collector_number", true),
+            text("rarity", true),
+            text("power", true),
+            text("toughness", true),
+            text("loyalty", true),
+            float("power_num"),
+            float("toughness_num"),
+            float("loyalty_num"),
+            float("price_usd"),
+            float("price_eur"),
+            float("price_tix"),
+            int("edhrec_rank"),
+            int("penny_rank"),
+            Field::new("released_at", DataType::Date32, true),
+            text("lang", true),
+            text("layout", true),
+        ]))
+    }
+
+    fn strings<'a, S: AsRef<str>>(
+        cards: &'a [Card],
+        value: impl Fn(&'a Card) -> Option<S>,
+    ) -> ArrayRef {
+        Arc::new(cards.iter().map(value).collect::<StringArray>())
+    }
+
+    fn string_lists(cards: &[Card], value: impl Fn(&Card) -> Option<&Vec<String>>) -> ArrayRef {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for card in cards {
+            match value(card) {
+                Some(values) => {
+                    for v in values {
+                        builder.values().append_value(v);
+                    }
+                    builder.append(true);
+                }
+                None => builder.append(false),
+            }
+        }
+        Arc::new(builder.finish())
+    }
+
+    fn floats(cards: &[Card], value: impl Fn(&Card) -> Option<f64>) -> ArrayRef {
+        Arc::new(cards.iter().map(value).collect::<Float64Array>())
+    }
+
+    fn ints(cards: &[Card], value: impl Fn(&Card) -> Option<i32>) -> ArrayRef {
+        Arc::new(cards.iter().map(value).collect::<Int32Array>())
+    }
+
+    /// Project `cards` onto `schema()`
+    pub fn record_batch(cards: &[Card]) -> anyhow::Result<RecordBatch> {
+        let columns = vec![
+            strings(cards, |c| Some(c.id.to_string())),
+            strings(cards, |c| c.oracle_id.map(|id| id.to_string())),
+            strings(cards, |c| Some(c.name.as_str())),
+            strings(cards, |c| c.mana_cost.as_deref()),
+            floats(cards, |c| c.cmc),
+            strings(cards, |c| c.type_line.as_deref()),
+            strings(cards, |c| c.oracle_text.as_deref()),
+            string_lists(cards, |c| c.colors.as_ref()),
+            string_lists(cards, |c| c.color_identity.as_ref()),
+            string_lists(cards, |c| c.keywords.as_ref()),
+            strings(cards, |c| c.set_code.as_deref()),
+            strings(cards, |c| c.set_name.as_deref()),
+            strings(cards, |c| c.collector_number.as_deref()),
+            strings(cards, |c| c.rarity.as_deref()),
+            strings(cards, |c| c.power.as_deref()),
+            strings(cards, |c| c.toughness.as_deref()),
+            strings(cards, |c| c.loyalty.as_deref()),
+            floats(cards, |c| c.power_num),
+            floats(cards, |c| c.toughness_num),
+            floats(cards, |c| c.loyalty_num),
+            floats(cards, |c| c.price_usd),
+            floats(cards, |c| c.price_eur),
+            floats(cards, |c| c.price_tix),
+            ints(cards, |c| c.edhrec_rank),
+            ints(cards, |c| c.penny_rank),
+            Arc::new(
+                cards
+                    .iter()
+                    .map(|c| c.released_at.map(Date32Type::from_naive_date))
+                    .collect::<Date32Array>(),
+            ),
+            strings(cards, |c| c.lang.as_deref()),
+            strings(cards, |c| c.layout.as_deref()),
+        ];
+        Ok(RecordBatch::try_new(schema(), columns)?)
+    }
+
+    /// In-memory sink the writer appends to; drained after every row group
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl SharedBuffer {
+        fn take(&self) -> Vec<u8> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Incremental Parquet writer that hands back the bytes of each finished row group
+    pub struct ParquetEncoder {
+        writer: ArrowWriter<SharedBuffer>,
+        buffer: SharedBuffer,
+    }
+
+    impl ParquetEncoder {
+        pub fn new() -> anyhow::Result<Self> {
+            let buffer = SharedBuffer::default();
+            let props = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            let writer = ArrowWriter::try_new(buffer.clone(), schema(), Some(props))?;
+            Ok(Self { writer, buffer })
+        }
+
+        /// Write `cards` as one row group and return the bytes produced so far
+        pub fn write(&mut self, cards: &[Card]) -> anyhow::Result<Vec<u8>> {
+            self.writer.write(&record_batch(cards)?)?;
+            self.writer.flush()?;
+            Ok(self.buffer.take())
+        }
+
+        /// Write the footer and return the remaining bytes
+        pub fn finish(self) -> anyhow::Result<Vec<u8>> {
+            self.writer.close()?;
+            Ok(self.buffer.take())
+        }
+    }
+
+    /// Encode `cards` as a Parquet file, one row group per `BATCH_ROWS` cards. An error
+    /// ends the stream, leaving a truncated file without a footer.
+    pub fn encode_stream(cards: CardStream) -> anyhow::Result<ParquetStream> {
+        let encoder = ParquetEncoder::new()?;
+        let chunks = cards.chunks(BATCH_ROWS);
+
+        let stream = futures::stream::unfold(Some((encoder, chunks)), |state| async move {
+            let (mut encoder, mut chunks) = state?;
+            let Some(chunk) = chunks.next().await else {
+                return Some((encoder.finish(), None));
+            };
+            let encoded = chunk
+                .into_iter()
+                .collect::<anyhow::Result<Vec<Card>>>()
+                .and_then(|cards| encoder.write(&cards));
+            match encoded {
+                Ok(bytes) => Some((Ok(bytes), Some((encoder, chunks)))),
+                Err(e) => {
+                    error!("Parquet export stream failed: {}", e);
+                    Some((Err(e), None))
+                }
+            }
+        });
+        Ok(stream.boxed())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use axum::body::Bytes;
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        fn card(name: &str, cmc: f64) -> Card {
+            Card::from_scryfall_json(serde_json::json!({
+                "id": uuid::Uuid::new_v4(),
+                "name": name,
+                "cmc": cmc,
+                "colors": ["R"],
+                "released_at": "2010-07-16",
+            }))
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_encode_stream_round_trips_cards() {
+            let cards: Vec<anyhow::Result<Card>> =
+                vec![Ok(card("Lightning Bolt", 1.0)), Ok(card("Shock", 1.0))];
+            let stream = encode_stream(futures::stream::iter(cards).boxed()).unwrap();
+            let bytes: Vec<u8> = stream.map(|chunk| chunk.unwrap()).concat().await;
+            assert!(bytes.starts_with(b"PAR1") && bytes.ends_with(b"PAR1"));
+
+            let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(bytes))
+                .unwrap()
+                .build()
+                .unwrap();
+            let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+            assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+            assert_eq!(batches[0].schema().fields().len(), schema().fields().len());
+            let names = batches[0]
+                .column_by_name("name")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            assert_eq!(names.value(0), "Lightning Bolt");
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use axum::{
     middleware,
-    routing::{get, post},
+    response::Redirect,
+    routing::{delete, get, post},
     Json,
     Router,
 };
 use tower_http::{
     compression::CompressionLayer,
-    cors::{Any, CorsLayer},
     services::{ServeDir, ServeFile},
     trace::TraceLayer,
 };
@@ -14,72 +14,238 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use super::handlers::{
-    admin_reload, admin_stats_overview, autocomplete_cards, batch_execute_queries, batch_get_cards,
-    batch_get_cards_by_name, get_card, get_card_by_name, get_stats, graphql_playground, health,
-    health_live, health_ready, search_cards, AppState,
+    admin_audit_log, admin_backup, admin_circuit_breakers, admin_clear_cached_queries,
+    admin_evict_cached_query, admin_export, admin_features, admin_get_query_limits,
+    admin_import_errors, admin_list_cached_queries, admin_list_imports, admin_reload, admin_repair,
+    admin_reset_circuit_breaker, admin_restore, admin_schema_version, admin_stats_overview,
+    admin_tenant_stats, admin_top_queries, admin_update_query_limits, admin_warmup,
+    admin_warmup_status, autocomplete_cards, batch_execute_queries, batch_get_cards,
+    batch_get_cards_by_name, create_saved_search, delete_saved_search, get_card,
+    get_card_by_arena_id, get_card_by_mtgo_id, get_card_by_multiverse_id, get_card_by_name,
+    get_card_by_set_and_number, get_card_collection, get_card_faces, get_card_rendered,
+    get_card_sample, get_catalog, get_collection_value, get_random_card, get_related_cards,
+    get_saved_search_new_cards, get_set, get_stats, get_symbology, graphql_playground, health,
+    health_live, health_ready, list_saved_searches, list_sets, search_cards, search_cards_parquet,
+    upsert_collection_cards, AppState,
 };
+use super::http_cache::http_cache_middleware;
 use async_graphql::{Request as GraphQLRequest, Response as GraphQLResponse};
-use super::middleware::logging_middleware;
+use async_graphql_axum::GraphQLSubscription;
+use super::middleware::{
+    admin_auth_middleware, audit_middleware, body_limit_middleware, deadline_middleware,
+    idempotency_middleware, logging_middleware, read_only_middleware, tenant_middleware,
+};
 use super::openapi::ApiDoc;
 use crate::metrics;
 
 pub fn create_router(state: AppState) -> Router {
-    // Configure CORS
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
-
     // Clone GraphQL schema for extension layer
     let graphql_schema = state.graphql_schema.clone();
 
-    Router::new()
+    let mut router = Router::new()
         // Health check
-        .route("/health", get(health))
-        .route("/health/live", get(health_live))
-        .route("/health/ready", get(health_ready))
+        .merge(health_routes())
         // GraphQL endpoint
         .route(
             "/graphql",
             get(graphql_playground).post(graphql_query_handler),
         )
+        // GraphQL subscriptions over WebSocket
+        .route_service(
+            "/graphql/ws",
+            GraphQLSubscription::new(state.graphql_schema.clone()),
+        )
         // Backward-compatible playground route
         .route("/graphql/playground", get(graphql_playground))
         // Add GraphQL schema as extension for the /graphql route
         .layer(axum::Extension(graphql_schema))
-        // Admin API endpoints (for web UI)
-        .route("/api/admin/stats/overview", get(admin_stats_overview))
-        // Card search endpoints
-        .route("/cards/search", get(search_cards))
-        .route("/cards/named", get(get_card_by_name))
+        // Card search endpoints (Scryfall-compatible paths) plus batch extensions
+        .merge(scryfall_compat_routes())
         .route("/cards/named/batch", post(batch_get_cards_by_name))
-        .route("/cards/autocomplete", get(autocomplete_cards))
-        .route("/cards/:id", get(get_card))
         .route("/cards/batch", post(batch_get_cards))
+        .route("/cards/sample", get(get_card_sample))
+        .route("/cards/search.parquet", get(search_cards_parquet))
+        .route("/cards/:id/faces", get(get_card_faces))
+        .route("/cards/:id/related", get(get_related_cards))
+        .route("/cards/:id/rendered", get(get_card_rendered))
         .route("/queries/batch", post(batch_execute_queries))
+        // User collections
+        .route("/collections/:id/cards", post(upsert_collection_cards))
+        .route("/collections/:id/value", get(get_collection_value))
+        // Saved searches, re-evaluated after every bulk reload
+        .route(
+            "/saved-searches",
+            get(list_saved_searches).post(create_saved_search),
+        )
+        .route("/saved-searches/:id", delete(delete_saved_search))
+        .route("/saved-searches/:id/new", get(get_saved_search_new_cards))
         // Stats endpoint
-        .route("/stats", get(get_stats))
+        .route("/stats", get(get_stats));
+
+    // Metrics and admin endpoints, unless they have their own listener
+    if state.admin_port.is_none() {
+        router = router.merge(admin_routes(&state));
+    }
+
+    // OpenAPI document, browsable with Swagger UI at /docs unless disabled
+    if state.swagger_ui {
+        router = router
+            .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
+            .route("/api-docs", get(|| async { Redirect::permanent("/docs/") }));
+    } else {
+        router = router.route(
+            "/api-docs/openapi.json",
+            get(|| async { Json(ApiDoc::openapi()) }),
+        );
+    }
+
+    // Optionally mirror the Scryfall-compatible routes under /scryfall so generic Scryfall
+    // client libraries can be pointed at this service by changing only their base URL
+    if state.scryfall_compat_prefix {
+        router = router.nest("/scryfall", scryfall_compat_routes());
+    }
+
+    with_middleware(router, state)
+}
+
+/// Router for the admin listener (`ADMIN_PORT`): health checks, metrics, the admin API
+/// and the admin panel
+pub fn create_admin_router(state: AppState) -> Router {
+    let router = health_routes().merge(admin_routes(&state));
+    with_middleware(router, state)
+}
+
+/// Apply the middleware stack shared by the API and admin listeners
+fn with_middleware(router: Router<AppState>, state: AppState) -> Router {
+    // Configure CORS
+    let cors = state.cors.layer();
+
+    router
+        // Turn handlers' cache hints into Cache-Control, Last-Modified and Age headers
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            http_cache_middleware,
+        ))
+        // Give each request a time budget enforced by the cache tiers
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            deadline_middleware,
+        ))
+        // Replay the stored response to admin and batch POSTs repeating an Idempotency-Key
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            idempotency_middleware,
+        ))
+        // Bound batch request bodies before they are deserialized
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            body_limit_middleware,
+        ))
+        // Reject admin mutations on read-only replicas
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            read_only_middleware,
+        ))
+        // Record admin mutations (including those rejected as read-only) in the audit log
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            audit_middleware,
+        ))
+        // Assign requests to tenants and apply per-tenant rate limits
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            tenant_middleware,
+        ))
+        // Add middleware (order matters: compression -> logging -> metrics -> cors -> trace)
+        .layer(CompressionLayer::new())
+        .layer(middleware::from_fn(logging_middleware))
+        .layer(middleware::from_fn(metrics::middleware::track_metrics))
+        .layer(cors)
+        .layer(TraceLayer::new_for_http())
+        // Add shared state
+        .with_state(state)
+}
+
+/// Liveness and readiness checks, served on both listeners
+fn health_routes() -> Router<AppState> {
+    Router::new()
+        .route("/health", get(health))
+        .route("/health/live", get(health_live))
+        .route("/health/ready", get(health_ready))
+}
+
+/// Metrics, admin API and admin panel. Everything but the panel's static files requires
+/// the admin token when one is configured.
+fn admin_routes(state: &AppState) -> Router<AppState> {
+    Router::new()
         // Metrics endpoint (Prometheus)
         .route("/metrics", get(metrics::metrics_handler))
+        // Admin API endpoints (for web UI)
+        .route("/api/admin/stats/overview", get(admin_stats_overview))
+        .route("/api/admin/stats/top-queries", get(admin_top_queries))
+        .route("/api/admin/stats/tenants", get(admin_tenant_stats))
+        .route("/api/admin/schema/version", get(admin_schema_version))
+        .route("/api/admin/imports", get(admin_list_imports))
+        .route("/api/admin/imports/:id/errors", get(admin_import_errors))
+        .route("/api/admin/audit", get(admin_audit_log))
+        .route(
+            "/api/admin/config/query-limits",
+            get(admin_get_query_limits).put(admin_update_query_limits),
+        )
         // Admin endpoints
         .route("/admin/reload", post(admin_reload))
+        .route("/admin/export", get(admin_export))
+        .route("/admin/backup", post(admin_backup))
+        .route("/admin/restore", post(admin_restore))
+        .route("/admin/repair", post(admin_repair))
+        .route("/admin/features", get(admin_features))
+        .route("/admin/circuit-breakers", get(admin_circuit_breakers))
+        .route(
+            "/admin/circuit-breakers/:name/reset",
+            post(admin_reset_circuit_breaker),
+        )
+        .route(
+            "/admin/cache/queries",
+            get(admin_list_cached_queries).delete(admin_clear_cached_queries),
+        )
+        .route(
+            "/admin/cache/queries/:hash",
+            delete(admin_evict_cached_query),
+        )
+        .route("/admin/warmup", get(admin_warmup_status).post(admin_warmup))
+        // Check the admin token on the routes above only
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            admin_auth_middleware,
+        ))
         // Admin panel (static files). Build the frontend into admin-panel/dist.
-        // Note: /admin/reload remains an API endpoint and should take precedence.
+        // Note: the /admin/* API routes above should take precedence.
         .nest_service(
             "/admin",
             ServeDir::new("admin-panel/dist")
                 .not_found_service(ServeFile::new("admin-panel/dist/index.html")),
         )
-        // OpenAPI documentation
-        .merge(SwaggerUi::new("/api-docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
-        // Add middleware (order matters: compression -> logging -> metrics -> cors -> trace)
-        .layer(CompressionLayer::new())
-        .layer(middleware::from_fn(logging_middleware))
-        .layer(middleware::from_fn(metrics::middleware::track_metrics))
-        .layer(cors)
-        .layer(TraceLayer::new_for_http())
-        // Add shared state
-        .with_state(state)
+}
+
+/// Routes that mirror Scryfall's own API paths and parameters
+fn scryfall_compat_routes() -> Router<AppState> {
+    Router::new()
+        .route("/cards/search", get(search_cards))
+        .route("/cards/named", get(get_card_by_name))
+        .route("/cards/autocomplete", get(autocomplete_cards))
+        .route("/cards/random", get(get_random_card))
+        .route("/cards/collection", post(get_card_collection))
+        .route("/cards/:id", get(get_card))
+        .route("/cards/multiverse/:id", get(get_card_by_multiverse_id))
+        .route("/cards/arena/:id", get(get_card_by_arena_id))
+        .route("/cards/mtgo/:id", get(get_card_by_mtgo_id))
+        // Set code + collector number. The first segment shares the `:id` name because
+        // the router requires one parameter name per position (see `/cards/:id/faces`).
+        .route("/cards/:id/:number", get(get_card_by_set_and_number))
+        .route("/sets", get(list_sets))
+        .route("/sets/:code", get(get_set))
+        .route("/catalog/:name", get(get_catalog))
+        .route("/symbology", get(get_symbology))
 }
 
 /// GraphQL query handler
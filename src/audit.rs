@@ -0,0 +1,107 @@
+use tracing::{error, info};
+
+use crate::config::Settings;
+use crate::db::Database;
+use crate::errors::ServiceResult;
+use crate::models::admin_audit::AdminAuditEntry;
+
+#[derive(Debug, Clone)]
+pub struct AuditConfig {
+    /// Record admin mutations in the `admin_audit_log` table
+    pub enabled: bool,
+    /// Longest query string plus request body kept per entry
+    pub max_parameters_bytes: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_parameters_bytes: 4096,
+        }
+    }
+}
+
+impl AuditConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: settings
+                .var("ADMIN_AUDIT_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            max_parameters_bytes: settings
+                .var("ADMIN_AUDIT_MAX_PARAMETERS_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_parameters_bytes),
+        }
+    }
+}
+
+/// Durable trail of admin mutations (reloads, cache purges, config changes)
+pub struct AdminAuditLog {
+    db: Database,
+    config: AuditConfig,
+    read_only: bool,
+}
+
+impl AdminAuditLog {
+    pub fn new(db: Database, config: AuditConfig) -> Self {
+        Self {
+            db,
+            config,
+            read_only: false,
+        }
+    }
+
+    /// Replica mode: entries are only logged on the `audit` tracing target, not stored
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn max_parameters_bytes(&self) -> usize {
+        self.config.max_parameters_bytes
+    }
+
+    /// Log `entry` on the `audit` tracing target and store it. Failing to store an entry
+    /// is logged rather than failing the request it describes.
+    pub async fn record(&self, entry: AdminAuditEntry) {
+        info!(
+            target: "audit",
+            actor = %entry.actor,
+            method = %entry.method,
+            path = %entry.path,
+            status = entry.status,
+            outcome = %entry.outcome,
+            duration_ms = entry.duration_ms,
+            "Admin request"
+        );
+
+        if self.read_only {
+            return;
+        }
+        if let Err(e) = self.db.record_admin_audit(&entry).await {
+            error!(
+                "Failed to record admin audit entry for {} {}: {}",
+                entry.method, entry.path, e
+            );
+        }
+    }
+
+    /// Most recent entries, newest first, optionally only those of `actor`
+    pub async fn recent(
+        &self,
+        limit: i64,
+        actor: Option<&str>,
+    ) -> ServiceResult<Vec<AdminAuditEntry>> {
+        self.db.list_admin_audit(limit, actor).await
+    }
+}
@@ -1,8 +1,31 @@
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
+use crate::config::Settings;
 use crate::scryfall::bulk_loader::BulkLoader;
+use crate::scryfall::incremental::IncrementalSync;
+
+/// How scheduled refreshes update local data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshMode {
+    /// Re-download the full bulk data file when upstream has changed
+    Full,
+    /// Fetch newly released printings since the last sync, with a full refresh
+    /// once the bulk data is stale or when the sync fails
+    Incremental,
+}
+
+impl RefreshMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "full" => Some(RefreshMode::Full),
+            "incremental" => Some(RefreshMode::Incremental),
+            _ => None,
+        }
+    }
+}
 
 /// Configuration for bulk data refresh job
 #[derive(Debug, Clone)]
@@ -11,6 +34,10 @@ pub struct BulkRefreshConfig {
     pub enabled: bool,
     /// Interval between refresh checks (hours)
     pub check_interval_hours: u64,
+    /// Full or incremental refresh
+    pub mode: RefreshMode,
+    /// Days before the last sync that incremental searches start from
+    pub incremental_lookback_days: u32,
 }
 
 impl Default for BulkRefreshConfig {
@@ -18,22 +45,36 @@ impl Default for BulkRefreshConfig {
         Self {
             enabled: true,
             check_interval_hours: 720, // 30 days (monthly)
+            mode: RefreshMode::Full,
+            incremental_lookback_days: 7,
         }
     }
 }
 
 impl BulkRefreshConfig {
-    /// Load configuration from environment variables
-    pub fn from_env() -> Self {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
         Self {
-            enabled: std::env::var("BULK_REFRESH_ENABLED")
+            enabled: settings
+                .var("BULK_REFRESH_ENABLED")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(true),
-            check_interval_hours: std::env::var("BULK_REFRESH_INTERVAL_HOURS")
+            check_interval_hours: settings
+                .var("BULK_REFRESH_INTERVAL_HOURS")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(720), // Default: 30 days
+            mode: settings
+                .var("BULK_REFRESH_MODE")
+                .ok()
+                .and_then(|v| RefreshMode::parse(&v))
+                .unwrap_or(RefreshMode::Full),
+            incremental_lookback_days: settings
+                .var("BULK_REFRESH_INCREMENTAL_LOOKBACK_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(7),
         }
     }
 }
@@ -48,15 +89,27 @@ impl BulkRefreshConfig {
 /// 2. Only download if upstream data actually changed
 /// 3. Fall back to time-based refresh if check fails
 ///
+/// With `SCRYFALL_BULK_CHECK_UPSTREAM=false` only the time-based refresh runs.
+///
+/// In incremental mode each tick first runs an incremental sync. That only picks up
+/// new printings: existing cards' prices, legalities and oracle text change only with a
+/// full refresh. So after a successful sync the full refresh strategy above still runs
+/// once the last full import is older than `SCRYFALL_CACHE_TTL_HOURS`, and straight
+/// away if the sync fails.
+///
 /// # Arguments
 /// * `bulk_loader` - Shared BulkLoader instance
-/// * `config` - Refresh configuration (interval, etc.)
+/// * `incremental` - Incremental sync used when `config.mode` is `Incremental`
+/// * `config` - Refresh configuration (interval, mode, etc.)
+/// * `shutdown` - Stops the job; a refresh in progress stops after its current batch
 ///
 /// # Returns
 /// tokio::task::JoinHandle that can be awaited or aborted
 pub fn start_bulk_refresh_job(
     bulk_loader: Arc<BulkLoader>,
+    incremental: Arc<IncrementalSync>,
     config: BulkRefreshConfig,
+    shutdown: CancellationToken,
 ) -> tokio::task::JoinHandle<()> {
     if !config.enabled {
         info!("Bulk data refresh job is disabled");
@@ -77,13 +130,47 @@ pub fn start_bulk_refresh_job(
         ticker.tick().await;
 
         loop {
-            ticker.tick().await;
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.cancelled() => {
+                    info!("Bulk data refresh job stopped");
+                    return;
+                }
+            }
 
             info!(
                 "Scheduled bulk data refresh check (interval: {} hours)...",
                 config.check_interval_hours
             );
 
+            if config.mode == RefreshMode::Incremental {
+                match incremental.sync().await {
+                    Ok(run) => {
+                        info!(
+                            "Scheduled incremental sync completed: {} cards updated",
+                            run.cards_updated
+                        );
+                        match bulk_loader.is_stale().await {
+                            Ok(true) => info!("Bulk data is stale, checking for a full refresh..."),
+                            Ok(false) => continue,
+                            Err(e) => {
+                                error!("Failed to check bulk data staleness: {}", e);
+                                continue;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!("Incremental sync failed: {}", e);
+                        info!("Falling back to full refresh check...");
+                    }
+                }
+            }
+
+            if !bulk_loader.checks_upstream() {
+                refresh_if_stale(&bulk_loader).await;
+                continue;
+            }
+
             // First, check if Scryfall's data has actually updated
             match bulk_loader.check_upstream_updated().await {
                 Ok(true) => {
@@ -103,28 +190,31 @@ pub fn start_bulk_refresh_job(
                 Err(e) => {
                     error!("Failed to check upstream bulk data status: {}", e);
                     info!("Falling back to time-based refresh check...");
-
-                    // Fall back to time-based check
-                    match bulk_loader.should_load().await {
-                        Ok(true) => {
-                            info!("Time-based refresh triggered (fallback mode)");
-                            if let Err(e) = bulk_loader.load().await {
-                                error!("Fallback bulk data refresh failed: {}", e);
-                            }
-                        }
-                        Ok(false) => {
-                            info!("Time-based check: bulk data is still fresh");
-                        }
-                        Err(e) => {
-                            error!("Failed to check if bulk data should load: {}", e);
-                        }
-                    }
+                    refresh_if_stale(&bulk_loader).await;
                 }
             }
         }
     })
 }
 
+/// Reload bulk data if it is older than `SCRYFALL_CACHE_TTL_HOURS`, without asking Scryfall
+async fn refresh_if_stale(bulk_loader: &BulkLoader) {
+    match bulk_loader.is_stale().await {
+        Ok(true) => {
+            info!("Time-based refresh triggered");
+            if let Err(e) = bulk_loader.load().await {
+                error!("Time-based bulk data refresh failed: {}", e);
+            }
+        }
+        Ok(false) => {
+            info!("Time-based check: bulk data is still fresh");
+        }
+        Err(e) => {
+            error!("Failed to check if bulk data should load: {}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,9 +229,17 @@ mod tests {
     #[test]
     fn test_monthly_interval() {
         let config = BulkRefreshConfig {
-            enabled: true,
             check_interval_hours: 720,
+            ..BulkRefreshConfig::default()
         };
         assert_eq!(config.check_interval_hours / 24, 30); // 30 days
     }
+
+    #[test]
+    fn test_refresh_mode_parse() {
+        assert_eq!(RefreshMode::parse("full"), Some(RefreshMode::Full));
+        assert_eq!(RefreshMode::parse(" Incremental "), Some(RefreshMode::Incremental));
+        assert_eq!(RefreshMode::parse("delta"), None);
+        assert_eq!(BulkRefreshConfig::default().mode, RefreshMode::Full);
+    }
 }
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use crate::cache::manager::CacheManager;
+use crate::config::Settings;
+use crate::metrics::registry::{CACHE_CLEANUP_DELETED, CACHE_CLEANUP_DELETED_TOTAL};
+
+/// Configuration for the query cache cleanup job
+#[derive(Debug, Clone)]
+pub struct CacheCleanupConfig {
+    /// Whether background cleanup is enabled
+    pub enabled: bool,
+    /// Interval between cleanup runs (minutes)
+    pub interval_minutes: u64,
+    /// Entries not read for this many hours are pruned even if not yet expired
+    pub max_idle_hours: i32,
+    /// Also remove Redis autocomplete keys that have no expiry
+    pub prune_redis_autocomplete: bool,
+}
+
+impl Default for CacheCleanupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: 60,
+            max_idle_hours: 168, // 7 days
+            prune_redis_autocomplete: false,
+        }
+    }
+}
+
+impl CacheCleanupConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: settings
+                .var("CACHE_CLEANUP_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            interval_minutes: settings
+                .var("CACHE_CLEANUP_INTERVAL_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&minutes| minutes > 0)
+                .unwrap_or(defaults.interval_minutes),
+            max_idle_hours: settings
+                .var("CACHE_CLEANUP_MAX_IDLE_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_idle_hours),
+            prune_redis_autocomplete: settings
+                .var("CACHE_CLEANUP_REDIS_AUTOCOMPLETE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.prune_redis_autocomplete),
+        }
+    }
+}
+
+/// Start background query cache cleanup job
+///
+/// Each run deletes database query cache rows that have expired or have not been
+/// read for `config.max_idle_hours`, and optionally Redis autocomplete keys left
+/// without an expiry. The number of entries removed is logged and exported as
+/// the `cache_cleanup_deleted` gauge and `cache_cleanup_deleted_total` counter.
+/// Expired idempotency keys are deleted from the database on the same schedule.
+/// The job exits when `shutdown` is cancelled.
+///
+/// # Returns
+/// tokio::task::JoinHandle that can be awaited or aborted
+pub fn start_cache_cleanup_job(
+    cache_manager: Arc<CacheManager>,
+    config: CacheCleanupConfig,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    if !config.enabled {
+        info!("Query cache cleanup job is disabled");
+        return tokio::spawn(async {});
+    }
+
+    info!(
+        "Starting query cache cleanup job: running every {} minutes",
+        config.interval_minutes
+    );
+
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(config.interval_minutes * 60));
+
+        // Skip the first tick (happens immediately)
+        ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.cancelled() => {
+                    info!("Query cache cleanup job stopped");
+                    return;
+                }
+            }
+
+            match cache_manager
+                .clean_expired_queries(config.max_idle_hours, config.prune_redis_autocomplete)
+                .await
+            {
+                Ok((database, redis)) => {
+                    record_deleted("database", database);
+                    record_deleted("redis", redis);
+                    info!(
+                        "Query cache cleanup removed {} database entries and {} Redis keys",
+                        database, redis
+                    );
+                }
+                Err(e) => {
+                    error!("Query cache cleanup failed: {}", e);
+                }
+            }
+
+            match cache_manager.clean_expired_idempotency_keys().await {
+                Ok(0) => {}
+                Ok(deleted) => info!("Removed {} expired idempotency keys", deleted),
+                Err(e) => error!("Idempotency key cleanup failed: {}", e),
+            }
+        }
+    })
+}
+
+fn record_deleted(tier: &str, deleted: u64) {
+    CACHE_CLEANUP_DELETED
+        .with_label_values(&[tier])
+        .set(deleted as i64);
+    CACHE_CLEANUP_DELETED_TOTAL
+        .with_label_values(&[tier])
+        .inc_by(deleted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = CacheCleanupConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.interval_minutes, 60);
+        assert_eq!(config.max_idle_hours, 168);
+        assert!(!config.prune_redis_autocomplete);
+    }
+}
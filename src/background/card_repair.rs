@@ -0,0 +1,125 @@
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use crate::cache::manager::CacheManager;
+use crate::config::Settings;
+
+/// Most cards repaired in one run, unless configured otherwise
+pub const DEFAULT_REPAIR_LIMIT: usize = 1000;
+
+/// Most cards `POST /admin/repair` re-fetches in one request
+pub const MAX_REPAIR_LIMIT: usize = 10_000;
+
+/// Configuration for the card integrity repair job
+#[derive(Debug, Clone)]
+pub struct CardRepairConfig {
+    /// Whether the scheduled repair is enabled
+    pub enabled: bool,
+    /// Interval between runs (hours)
+    pub interval_hours: u64,
+    /// Most cards re-fetched in one run
+    pub max_cards: usize,
+}
+
+impl Default for CardRepairConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 168, // weekly
+            max_cards: DEFAULT_REPAIR_LIMIT,
+        }
+    }
+}
+
+impl CardRepairConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: settings
+                .var("CARD_REPAIR_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            interval_hours: settings
+                .var("CARD_REPAIR_INTERVAL_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&hours| hours > 0)
+                .unwrap_or(defaults.interval_hours),
+            max_cards: settings
+                .var("CARD_REPAIR_MAX_CARDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&cards| cards > 0)
+                .unwrap_or(defaults.max_cards),
+        }
+    }
+}
+
+/// Start background card repair job
+///
+/// Each run scans the card table for rows that fail an integrity check and re-fetches
+/// up to `config.max_cards` of them from Scryfall (see `CacheManager::repair_cards`).
+/// The job exits when `shutdown` is cancelled.
+///
+/// # Returns
+/// tokio::task::JoinHandle that can be awaited or aborted
+pub fn start_card_repair_job(
+    cache_manager: Arc<CacheManager>,
+    config: CardRepairConfig,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    if !config.enabled {
+        info!("Card repair job is disabled");
+        return tokio::spawn(async {});
+    }
+
+    info!(
+        "Starting card repair job: running every {} hours",
+        config.interval_hours
+    );
+
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(config.interval_hours * 3600));
+
+        // Skip the first tick (happens immediately)
+        ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.cancelled() => {
+                    info!("Card repair job stopped");
+                    return;
+                }
+            }
+
+            match cache_manager.repair_cards(config.max_cards).await {
+                Ok(report) => {
+                    if let Some(e) = report.error {
+                        error!("Card repair could not re-fetch cards: {}", e);
+                    }
+                }
+                Err(e) => {
+                    error!("Card repair failed: {}", e);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = CardRepairConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.interval_hours, 168);
+        assert_eq!(config.max_cards, DEFAULT_REPAIR_LIMIT);
+    }
+}
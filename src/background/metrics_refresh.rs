@@ -0,0 +1,99 @@
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::cache::manager::CacheManager;
+use crate::config::Settings;
+
+/// Configuration for the metrics refresh job
+#[derive(Debug, Clone)]
+pub struct MetricsRefreshConfig {
+    /// Whether the gauges are refreshed in the background
+    pub enabled: bool,
+    /// Interval between refreshes (seconds)
+    pub interval_seconds: u64,
+}
+
+impl Default for MetricsRefreshConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_seconds: 60,
+        }
+    }
+}
+
+impl MetricsRefreshConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: settings
+                .var("METRICS_REFRESH_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            interval_seconds: settings
+                .var("METRICS_REFRESH_INTERVAL_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&seconds| seconds > 0)
+                .unwrap_or(defaults.interval_seconds),
+        }
+    }
+}
+
+/// Start background metrics refresh job
+///
+/// Sets the `cards_total`, `queries_cached_total` and `cache_size_bytes` gauges right
+/// away and then every `config.interval_seconds`. Counting rows and sizing the cache
+/// is too costly to do per request, so these gauges lag by up to one interval.
+/// The job exits when `shutdown` is cancelled.
+///
+/// # Returns
+/// tokio::task::JoinHandle that can be awaited or aborted
+pub fn start_metrics_refresh_job(
+    cache_manager: Arc<CacheManager>,
+    config: MetricsRefreshConfig,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    if !config.enabled {
+        info!("Metrics refresh job is disabled");
+        return tokio::spawn(async {});
+    }
+
+    info!(
+        "Starting metrics refresh job: running every {} seconds",
+        config.interval_seconds
+    );
+
+    tokio::spawn(async move {
+        // The first tick happens immediately, so the gauges are set at startup
+        let mut ticker = interval(Duration::from_secs(config.interval_seconds));
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.cancelled() => {
+                    info!("Metrics refresh job stopped");
+                    return;
+                }
+            }
+
+            cache_manager.refresh_metrics().await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = MetricsRefreshConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.interval_seconds, 60);
+    }
+}
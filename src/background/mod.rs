@@ -1,3 +1,23 @@
 pub mod bulk_refresh;
+pub mod cache_cleanup;
+pub mod card_repair;
+pub mod metrics_refresh;
+pub mod preview_poll;
+pub mod saved_searches;
+pub mod shutdown;
+pub mod startup;
+pub mod warmup;
+pub mod webhooks;
+pub mod writer;
 
 pub use bulk_refresh::start_bulk_refresh_job;
+pub use cache_cleanup::{start_cache_cleanup_job, CacheCleanupConfig};
+pub use card_repair::{start_card_repair_job, CardRepairConfig};
+pub use metrics_refresh::{start_metrics_refresh_job, MetricsRefreshConfig};
+pub use preview_poll::start_preview_poll_job;
+pub use saved_searches::start_saved_search_job;
+pub use shutdown::wait_for_jobs;
+pub use startup::{start_initial_load, StartupConfig, StartupStatus};
+pub use warmup::start_warmup_job;
+pub use webhooks::start_webhook_job;
+pub use writer::{CardWriter, CardWriterConfig};
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::scryfall::preview::PreviewPoller;
+
+/// Start background preview poll job
+///
+/// Fetches cards released or previewed within the configured lookback window every
+/// `interval_minutes` and upserts them, so newly previewed cards show up without waiting
+/// for the next bulk load. Disabled unless `PREVIEW_POLL_ENABLED` is set, and on
+/// read-only replicas. The first poll runs immediately. The job exits when `shutdown` is
+/// cancelled, after finishing a poll in progress.
+///
+/// # Returns
+/// tokio::task::JoinHandle that can be awaited or aborted
+pub fn start_preview_poll_job(
+    poller: Arc<PreviewPoller>,
+    read_only: bool,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    if !poller.config().enabled || read_only {
+        info!("Preview poll job is disabled");
+        return tokio::spawn(async {});
+    }
+
+    let interval_minutes = poller.config().interval_minutes;
+    info!(
+        "Starting preview poll job: fetching cards from the last {} days every {} minutes",
+        poller.config().lookback_days,
+        interval_minutes
+    );
+
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(interval_minutes * 60));
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.cancelled() => {
+                    info!("Preview poll job stopped");
+                    return;
+                }
+            }
+            poller.poll().await;
+        }
+    })
+}
@@ -0,0 +1,50 @@
+use futures::StreamExt;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::cache::manager::CacheManager;
+use crate::events::{self, CacheEvent};
+
+/// Start background saved search evaluation job
+///
+/// Evaluates every saved search after each successful bulk reload, recording the cards
+/// that match a search for the first time and publishing a `saved_search_matched` event
+/// per search that gained matches (delivered to webhooks listing it in `WEBHOOK_EVENTS`).
+/// Does nothing on read-only replicas, which never import bulk data. The job exits when
+/// `shutdown` is cancelled, after finishing an evaluation in progress.
+///
+/// # Returns
+/// tokio::task::JoinHandle that can be awaited or aborted
+pub fn start_saved_search_job(
+    cache_manager: Arc<CacheManager>,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    if cache_manager.read_only() {
+        info!("Saved search evaluation is disabled (read-only mode)");
+        return tokio::spawn(async {});
+    }
+
+    // Subscribe before spawning so no reload completed after startup is missed
+    let mut events = Box::pin(events::stream());
+    tokio::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                event = events.next() => event,
+                _ = shutdown.cancelled() => None,
+            };
+            let Some(event) = event else {
+                break;
+            };
+            if !matches!(event, CacheEvent::BulkReloadCompleted { .. }) {
+                continue;
+            }
+            match cache_manager.evaluate_saved_searches().await {
+                Ok(0) => {}
+                Ok(evaluated) => info!("Evaluated {} saved searches after bulk reload", evaluated),
+                Err(e) => warn!("Saved search evaluation failed: {}", e),
+            }
+        }
+        info!("Saved search evaluation stopped");
+    })
+}
@@ -0,0 +1,46 @@
+use tokio::task::JoinHandle;
+use tokio::time::{timeout_at, Duration, Instant};
+use tracing::{info, warn};
+
+/// Wait for background jobs to exit after shutdown was requested
+///
+/// Jobs get `timeout` in total to finish what they are doing (an import finishes its
+/// current batch, the card writer flushes its queue). Jobs still running after that are
+/// aborted so the process can exit.
+pub async fn wait_for_jobs(jobs: Vec<(&'static str, JoinHandle<()>)>, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    for (name, mut handle) in jobs {
+        match timeout_at(deadline, &mut handle).await {
+            Ok(Ok(())) => info!("Background job '{}' stopped", name),
+            Ok(Err(e)) => warn!("Background job '{}' failed: {}", name, e),
+            Err(_) => {
+                warn!(
+                    "Background job '{}' did not stop within {:?}, aborting",
+                    name, timeout
+                );
+                handle.abort();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_for_jobs_gives_up_on_stuck_jobs() {
+        let finished = tokio::spawn(async {});
+        let stuck = tokio::spawn(std::future::pending::<()>());
+
+        let waited = tokio::time::timeout(
+            Duration::from_secs(5),
+            wait_for_jobs(
+                vec![("finished", finished), ("stuck", stuck)],
+                Duration::from_millis(50),
+            ),
+        )
+        .await;
+        assert!(waited.is_ok());
+    }
+}
@@ -0,0 +1,220 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+use crate::config::Settings;
+use crate::scryfall::bulk_loader::BulkLoader;
+
+/// Configuration for the initial (startup) bulk data load
+#[derive(Debug, Clone, Default)]
+pub struct StartupConfig {
+    /// Report ready after this long even if the initial load is still running,
+    /// serving requests through the Scryfall API fallback in the meantime.
+    /// `None` keeps readiness off until the load finishes (or fails).
+    pub ready_grace: Option<Duration>,
+}
+
+impl StartupConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            ready_grace: settings
+                .var("STARTUP_READY_GRACE_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs),
+        }
+    }
+}
+
+/// Progress of the initial bulk data load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitialLoadState {
+    /// Checking whether a load is needed
+    Pending,
+    /// No local data yet; bulk load in progress
+    Loading,
+    /// Existing local data is served while a stale dataset is reloaded
+    Refreshing,
+    /// Local data is available
+    Ready,
+    /// Initial load failed; requests fall back to the Scryfall API
+    Failed,
+}
+
+impl InitialLoadState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InitialLoadState::Pending => "pending",
+            InitialLoadState::Loading => "loading",
+            InitialLoadState::Refreshing => "refreshing",
+            InitialLoadState::Ready => "ready",
+            InitialLoadState::Failed => "failed",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => InitialLoadState::Loading,
+            2 => InitialLoadState::Refreshing,
+            3 => InitialLoadState::Ready,
+            4 => InitialLoadState::Failed,
+            _ => InitialLoadState::Pending,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            InitialLoadState::Pending => 0,
+            InitialLoadState::Loading => 1,
+            InitialLoadState::Refreshing => 2,
+            InitialLoadState::Ready => 3,
+            InitialLoadState::Failed => 4,
+        }
+    }
+}
+
+/// Shared startup status consulted by the readiness endpoint
+#[derive(Debug)]
+pub struct StartupStatus {
+    state: AtomicU8,
+    started_at: Instant,
+    ready_grace: Option<Duration>,
+}
+
+impl StartupStatus {
+    pub fn new(config: &StartupConfig) -> Self {
+        Self {
+            state: AtomicU8::new(InitialLoadState::Pending.to_u8()),
+            started_at: Instant::now(),
+            ready_grace: config.ready_grace,
+        }
+    }
+
+    /// Status for contexts that skip the initial load entirely (e.g. tests)
+    pub fn ready() -> Self {
+        let status = Self::new(&StartupConfig::default());
+        status.set(InitialLoadState::Ready);
+        status
+    }
+
+    pub fn state(&self) -> InitialLoadState {
+        InitialLoadState::from_u8(self.state.load(Ordering::Acquire))
+    }
+
+    pub fn set(&self, state: InitialLoadState) {
+        self.state.store(state.to_u8(), Ordering::Release);
+    }
+
+    /// Whether the service should receive traffic
+    pub fn is_ready(&self) -> bool {
+        match self.state() {
+            InitialLoadState::Ready | InitialLoadState::Refreshing | InitialLoadState::Failed => {
+                true
+            }
+            InitialLoadState::Pending | InitialLoadState::Loading => self
+                .ready_grace
+                .map(|grace| self.started_at.elapsed() >= grace)
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Run the initial bulk data load in the background so the HTTP server can start
+/// (and answer health checks) immediately. A read-only loader never loads; it serves
+/// the data already in the database, or the API fallback if there is none.
+pub fn start_initial_load(
+    bulk_loader: Arc<BulkLoader>,
+    status: Arc<StartupStatus>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        if bulk_loader.read_only() {
+            if bulk_loader.has_data().await.unwrap_or(false) {
+                info!("Read-only mode: serving existing bulk data");
+                status.set(InitialLoadState::Ready);
+                bulk_loader.notify_data_ready();
+            } else {
+                warn!("Read-only mode: no bulk data in the database, relying on API fallback");
+                status.set(InitialLoadState::Failed);
+            }
+            return;
+        }
+
+        let should_load = match bulk_loader.should_load().await {
+            Ok(v) => v,
+            Err(e) => {
+                error!("Failed to determine bulk load status: {}", e);
+                status.set(InitialLoadState::Failed);
+                return;
+            }
+        };
+
+        if !should_load {
+            info!("Bulk data is up to date, skipping load");
+            status.set(InitialLoadState::Ready);
+            bulk_loader.notify_data_ready();
+            return;
+        }
+
+        let has_data = bulk_loader.has_data().await.unwrap_or(false);
+        status.set(if has_data {
+            InitialLoadState::Refreshing
+        } else {
+            InitialLoadState::Loading
+        });
+
+        info!("Loading bulk data in background...");
+        match bulk_loader.load().await {
+            Ok(()) => {
+                info!("Initial bulk data load completed");
+                status.set(InitialLoadState::Ready);
+            }
+            Err(e) => {
+                error!("Failed to load bulk data: {}", e);
+                if has_data {
+                    error!("Continuing with existing bulk data");
+                    status.set(InitialLoadState::Ready);
+                    bulk_loader.notify_data_ready();
+                } else {
+                    error!("Continuing without bulk data - will rely on API fallback");
+                    status.set(InitialLoadState::Failed);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loading_is_not_ready_without_grace() {
+        let status = StartupStatus::new(&StartupConfig::default());
+        status.set(InitialLoadState::Loading);
+        assert!(!status.is_ready());
+
+        status.set(InitialLoadState::Ready);
+        assert!(status.is_ready());
+    }
+
+    #[test]
+    fn test_grace_period_allows_fallback_serving() {
+        let status = StartupStatus::new(&StartupConfig {
+            ready_grace: Some(Duration::ZERO),
+        });
+        status.set(InitialLoadState::Loading);
+        assert!(status.is_ready());
+    }
+
+    #[test]
+    fn test_failed_and_refreshing_are_ready() {
+        let status = StartupStatus::new(&StartupConfig::default());
+        status.set(InitialLoadState::Failed);
+        assert!(status.is_ready());
+        status.set(InitialLoadState::Refreshing);
+        assert!(status.is_ready());
+        assert_eq!(status.state().as_str(), "refreshing");
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::cache::warmup::CacheWarmer;
+use crate::scryfall::bulk_loader::BulkLoader;
+
+/// Start background cache warm-up job
+///
+/// Runs the configured warm-up queries whenever local card data becomes available:
+/// once after startup (whether or not the initial bulk load ran) and again after
+/// every bulk reload. Does nothing when no warm-up queries are configured. The job
+/// exits when `shutdown` is cancelled, after finishing a warm-up run in progress.
+///
+/// # Returns
+/// tokio::task::JoinHandle that can be awaited or aborted
+pub fn start_warmup_job(
+    warmer: Arc<CacheWarmer>,
+    bulk_loader: Arc<BulkLoader>,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    if warmer.queries().is_empty() {
+        info!("Cache warm-up is disabled (no warm-up queries configured)");
+        return tokio::spawn(async {});
+    }
+
+    info!(
+        "Starting cache warm-up job with {} queries",
+        warmer.queries().len()
+    );
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = bulk_loader.data_ready() => {}
+                _ = shutdown.cancelled() => {
+                    info!("Cache warm-up job stopped");
+                    return;
+                }
+            }
+            info!("Local card data is ready, warming query cache...");
+            warmer.run().await;
+        }
+    })
+}
@@ -0,0 +1,61 @@
+use futures::StreamExt;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::events::{self, webhooks::WebhookDispatcher};
+
+/// Start background webhook delivery job
+///
+/// Forwards every event on the event bus that the dispatcher is configured for (by
+/// default `bulk_reload_completed`, published after any successful bulk reload:
+/// scheduled, admin-triggered or at startup) to the configured webhook URLs. Each
+/// event is delivered on its own task so a slow endpoint doesn't hold up later events.
+/// The job stops taking new events when `shutdown` is cancelled and waits for
+/// deliveries already in progress.
+///
+/// # Returns
+/// tokio::task::JoinHandle that can be awaited or aborted
+pub fn start_webhook_job(
+    dispatcher: Arc<WebhookDispatcher>,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    if dispatcher.config().urls.is_empty() {
+        info!("Webhook notifications are disabled (no WEBHOOK_URLS configured)");
+        return tokio::spawn(async {});
+    }
+
+    info!(
+        "Starting webhook notifications to {} URLs for events: {}",
+        dispatcher.config().urls.len(),
+        dispatcher.config().events.join(", ")
+    );
+
+    // Subscribe before spawning so no event published after startup is missed
+    let mut events = Box::pin(events::stream());
+    tokio::spawn(async move {
+        let mut deliveries = JoinSet::new();
+        loop {
+            let event = tokio::select! {
+                event = events.next() => event,
+                _ = shutdown.cancelled() => None,
+            };
+            let Some(event) = event else {
+                break;
+            };
+            if !dispatcher.config().wants(&event) {
+                continue;
+            }
+            let dispatcher = dispatcher.clone();
+            deliveries.spawn(async move {
+                dispatcher.dispatch(&event).await;
+            });
+            // Reap finished deliveries so the set doesn't grow without bound
+            while deliveries.try_join_next().is_some() {}
+        }
+
+        while deliveries.join_next().await.is_some() {}
+        info!("Webhook notifications stopped");
+    })
+}
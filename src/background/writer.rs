@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::cache::policy::CachePolicy;
+use crate::cache::redis::RedisCache;
+use crate::config::Settings;
+use crate::db::Database;
+use crate::errors::ServiceResult;
+use crate::metrics::registry::{
+    CARD_WRITER_BATCH_CARDS, CARD_WRITER_FLUSHES_TOTAL, CARD_WRITER_QUEUE_DEPTH,
+    CARD_WRITER_SUBMISSIONS_TOTAL,
+};
+use crate::models::card::Card;
+
+/// Configuration for the write-behind card writer
+#[derive(Debug, Clone)]
+pub struct CardWriterConfig {
+    /// Persist on the background writer and answer requests immediately
+    pub enabled: bool,
+    /// Submissions allowed to wait for the writer before callers go back to writing inline
+    pub queue_size: usize,
+    /// Flush as soon as this many distinct cards are pending
+    pub batch_size: usize,
+    /// Flush pending cards at least this often (milliseconds)
+    pub flush_interval_ms: u64,
+}
+
+impl Default for CardWriterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            queue_size: 64,
+            batch_size: 500,
+            flush_interval_ms: 250,
+        }
+    }
+}
+
+impl CardWriterConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: settings
+                .var("CARD_WRITER_ASYNC")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            queue_size: settings
+                .var("CARD_WRITER_QUEUE_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&size| size > 0)
+                .unwrap_or(defaults.queue_size),
+            batch_size: settings
+                .var("CARD_WRITER_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&size| size > 0)
+                .unwrap_or(defaults.batch_size),
+            flush_interval_ms: settings
+                .var("CARD_WRITER_FLUSH_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&ms| ms > 0)
+                .unwrap_or(defaults.flush_interval_ms),
+        }
+    }
+}
+
+/// Search whose results are cached once its cards are stored
+#[derive(Debug, Clone)]
+pub struct CachedSearch {
+    pub query: String,
+    /// Query cache key, already scoped to `tenant_id`
+    pub query_hash: String,
+    pub tenant_id: String,
+    pub policy: CachePolicy,
+}
+
+/// Cards fetched from Scryfall that still need to be written to the database
+/// (and, for a cached search, to the query caches and Redis)
+#[derive(Debug)]
+pub struct Writeback {
+    pub cards: Vec<Card>,
+    pub search: Option<CachedSearch>,
+}
+
+/// Write-behind writer for cards fetched from the Scryfall API.
+///
+/// Submissions go through a bounded queue to one background task, which merges them
+/// into batches deduplicated by card id and flushes a batch once it reaches
+/// `batch_size` cards or `flush_interval_ms` has passed, so a burst of cache misses
+/// costs a few large transactions instead of many tiny ones. When the queue is full
+/// (or the writer is disabled) the caller writes inline, so bursts slow down instead
+/// of buffering without limit.
+pub struct CardWriter {
+    db: Database,
+    redis: Option<RedisCache>,
+    queue: Option<mpsc::Sender<Writeback>>,
+    /// Tells the background task to flush what is queued and exit
+    stop: CancellationToken,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl CardWriter {
+    /// Create a writer, spawning its background task when `config.enabled` is set
+    pub fn new(db: Database, redis: Option<RedisCache>, config: &CardWriterConfig) -> Self {
+        let stop = CancellationToken::new();
+        let (queue, task) = if config.enabled {
+            info!(
+                "Starting card writer: batches of up to {} cards, flushed every {}ms",
+                config.batch_size, config.flush_interval_ms
+            );
+            let (tx, rx) = mpsc::channel(config.queue_size);
+            let task = tokio::spawn(run_writer(
+                db.clone(),
+                redis.clone(),
+                rx,
+                config.clone(),
+                stop.clone(),
+            ));
+            (Some(tx), Some(task))
+        } else {
+            (None, None)
+        };
+
+        Self {
+            db,
+            redis,
+            queue,
+            stop,
+            task: Mutex::new(task),
+        }
+    }
+
+    /// Flush queued cards and stop the background task. Later submissions are
+    /// written inline.
+    pub async fn shutdown(&self) {
+        self.stop.cancel();
+        let task = self.task.lock().unwrap().take();
+        if let Some(task) = task {
+            if let Err(e) = task.await {
+                warn!("Card writer task failed: {}", e);
+            }
+        }
+    }
+
+    /// Persist `writeback`, on the background writer if the queue has room
+    pub async fn submit(&self, writeback: Writeback) -> ServiceResult<()> {
+        let writeback = match &self.queue {
+            Some(queue) => match queue.try_send(writeback) {
+                Ok(()) => {
+                    CARD_WRITER_QUEUE_DEPTH.inc();
+                    CARD_WRITER_SUBMISSIONS_TOTAL
+                        .with_label_values(&["queued"])
+                        .inc();
+                    return Ok(());
+                }
+                Err(TrySendError::Full(writeback)) | Err(TrySendError::Closed(writeback)) => {
+                    debug!("Card writer queue unavailable, writing inline");
+                    writeback
+                }
+            },
+            None => writeback,
+        };
+
+        CARD_WRITER_SUBMISSIONS_TOTAL
+            .with_label_values(&["inline"])
+            .inc();
+        let mut batch = PendingBatch::default();
+        batch.add(writeback);
+        batch.flush(&self.db, self.redis.as_ref()).await
+    }
+}
+
+/// Cards and searches merged from submissions since the last flush
+#[derive(Debug, Default)]
+struct PendingBatch {
+    /// Pending cards by id; a later submission of the same card replaces the earlier one
+    cards: HashMap<Uuid, Card>,
+    /// Searches to cache once their cards are stored, by query hash
+    searches: HashMap<String, (CachedSearch, Vec<Uuid>)>,
+}
+
+impl PendingBatch {
+    fn add(&mut self, writeback: Writeback) {
+        if let Some(search) = writeback.search {
+            let card_ids = writeback.cards.iter().map(|c| c.id).collect();
+            self.searches
+                .insert(search.query_hash.clone(), (search, card_ids));
+        }
+        for card in writeback.cards {
+            self.cards.insert(card.id, card);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cards.is_empty() && self.searches.is_empty()
+    }
+
+    /// Write the cards to the database in one batch, then cache the searches
+    async fn flush(self, db: &Database, redis: Option<&RedisCache>) -> ServiceResult<()> {
+        let cards: Vec<Card> = self.cards.into_values().collect();
+        db.insert_cards_batch(&cards).await?;
+
+        for (search, card_ids) in self.searches.values() {
+            store_query_results(
+                db,
+                redis,
+                &search.tenant_id,
+                &search.query,
+                &search.query_hash,
+                card_ids,
+                &search.policy,
+            )
+            .await;
+        }
+        if let Some(redis) = redis.filter(|_| !self.searches.is_empty()) {
+            redis.set_cards(&cards).await.ok();
+        }
+
+        Ok(())
+    }
+}
+
+/// Merge queued submissions into batches until every sender is dropped or `stop` is
+/// cancelled
+async fn run_writer(
+    db: Database,
+    redis: Option<RedisCache>,
+    mut rx: mpsc::Receiver<Writeback>,
+    config: CardWriterConfig,
+    stop: CancellationToken,
+) {
+    let mut ticker = interval(Duration::from_millis(config.flush_interval_ms));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut batch = PendingBatch::default();
+
+    loop {
+        tokio::select! {
+            received = rx.recv() => match received {
+                Some(writeback) => {
+                    CARD_WRITER_QUEUE_DEPTH.dec();
+                    batch.add(writeback);
+                    if batch.len() < config.batch_size {
+                        continue;
+                    }
+                }
+                None => {
+                    flush(std::mem::take(&mut batch), &db, redis.as_ref()).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => {}
+            _ = stop.cancelled() => {
+                // Refuse new submissions and drain the ones already queued
+                rx.close();
+                while let Some(writeback) = rx.recv().await {
+                    CARD_WRITER_QUEUE_DEPTH.dec();
+                    batch.add(writeback);
+                }
+                flush(std::mem::take(&mut batch), &db, redis.as_ref()).await;
+                info!("Card writer stopped");
+                return;
+            }
+        }
+
+        flush(std::mem::take(&mut batch), &db, redis.as_ref()).await;
+    }
+}
+
+/// Flush a batch from the background writer, recording the outcome
+async fn flush(batch: PendingBatch, db: &Database, redis: Option<&RedisCache>) {
+    if batch.is_empty() {
+        return;
+    }
+
+    let count = batch.len();
+    CARD_WRITER_BATCH_CARDS.observe(count as f64);
+    match batch.flush(db, redis).await {
+        Ok(()) => {
+            debug!("Card writer stored {} cards", count);
+            CARD_WRITER_FLUSHES_TOTAL
+                .with_label_values(&["success"])
+                .inc();
+        }
+        Err(e) => {
+            warn!("Card writer failed to store {} cards: {}", count, e);
+            CARD_WRITER_FLUSHES_TOTAL
+                .with_label_values(&["failure"])
+                .inc();
+        }
+    }
+}
+
+/// Store search results of `tenant_id` under `query_hash` in the cache tiers enabled
+/// by `policy`
+pub(crate) async fn store_query_results(
+    db: &Database,
+    redis: Option<&RedisCache>,
+    tenant_id: &str,
+    query: &str,
+    query_hash: &str,
+    card_ids: &[Uuid],
+    policy: &CachePolicy,
+) {
+    // Store in database query cache
+    if policy.database {
+        db.store_query_cache(tenant_id, query_hash, query, card_ids, policy.ttl_hours())
+            .await
+            .ok();
+    }
+
+    // Store in Redis cache
+    if policy.redis {
+        if let Some(redis) = redis {
+            redis
+                .set_query_results(query_hash, card_ids, policy.ttl)
+                .await
+                .ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(id: &str, name: &str) -> Card {
+        Card::from_scryfall_json(serde_json::json!({ "id": id, "name": name })).unwrap()
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = CardWriterConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.batch_size, 500);
+        assert_eq!(config.flush_interval_ms, 250);
+    }
+
+    #[test]
+    fn test_batch_dedupes_cards_by_id() {
+        let id = "550c74d4-1fcb-406a-b02a-639a760a4380";
+        let mut batch = PendingBatch::default();
+        batch.add(Writeback {
+            cards: vec![card(id, "Old Name")],
+            search: None,
+        });
+        batch.add(Writeback {
+            cards: vec![
+                card(id, "New Name"),
+                card("a3fb7228-e76b-4e96-a40e-20b5fed75685", "Sol Ring"),
+            ],
+            search: None,
+        });
+
+        assert_eq!(batch.len(), 2);
+        let id = Uuid::parse_str(id).unwrap();
+        assert_eq!(batch.cards[&id].name, "New Name");
+    }
+}
@@ -0,0 +1,577 @@
+//! Load test harness for a running scryfall-cache instance.
+//!
+//! Replays a seeded, scripted mix of searches, card lookups, autocomplete and batch
+//! requests at a fixed rate, then reports latency percentiles per request kind and the
+//! cache hit ratio (from `/metrics`) over the run. The same seed and scenario always
+//! produce the same request sequence, so runs are comparable across builds.
+//!
+//! ```bash
+//! cargo run --release --features loadtest --bin loadtest -- \
+//!     --target http://localhost:8080 --scenario mixed --rps 50 --duration 60
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+const USAGE: &str = "\
+Usage: loadtest [OPTIONS]
+
+Options:
+  --target <URL>         Instance to test (default: http://localhost:8080)
+  --scenario <NAME>      mixed, search, lookup, autocomplete or batch (default: mixed)
+  --rps <N>              Requests per second (default: 20)
+  --duration <SECS>      Run length in seconds (default: 30)
+  --concurrency <N>      Max requests in flight (default: 64)
+  --seed <N>             Seed for the request sequence (default: 42)
+  --json                 Print the report as JSON";
+
+const SEARCH_QUERIES: &[&str] = &[
+    "t:creature",
+    "c:red cmc:<=2",
+    "o:draw t:instant",
+    "c:u t:instant",
+    "name:bolt",
+    "t:legendary t:creature c:g",
+    "cmc:>=5 t:dragon",
+    "o:\"enters the battlefield\"",
+    "t:artifact cmc:<=3",
+    "c:w t:enchantment",
+];
+
+const NAMES: &[&str] = &[
+    "Lightning Bolt",
+    "Sol Ring",
+    "Counterspell",
+    "Llanowar Elves",
+    "Swords to Plowshares",
+    "Dark Ritual",
+    "Giant Growth",
+    "Brainstorm",
+];
+
+const PREFIXES: &[&str] = &[
+    "light", "sol", "counter", "llan", "sword", "dark", "giant", "brain", "ser", "wrath",
+];
+
+/// Size of each `/cards/batch` request
+const BATCH_SIZE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum RequestKind {
+    Search,
+    Lookup,
+    Named,
+    Autocomplete,
+    Batch,
+}
+
+impl RequestKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RequestKind::Search => "search",
+            RequestKind::Lookup => "lookup",
+            RequestKind::Named => "named",
+            RequestKind::Autocomplete => "autocomplete",
+            RequestKind::Batch => "batch",
+        }
+    }
+}
+
+/// Relative weights of each request kind in a scenario
+fn scenario_weights(scenario: &str) -> Option<Vec<(RequestKind, u32)>> {
+    let weights = match scenario {
+        "mixed" => vec![
+            (RequestKind::Search, 40),
+            (RequestKind::Lookup, 20),
+            (RequestKind::Named, 10),
+            (RequestKind::Autocomplete, 20),
+            (RequestKind::Batch, 10),
+        ],
+        "search" => vec![(RequestKind::Search, 1)],
+        "lookup" => vec![(RequestKind::Lookup, 1), (RequestKind::Named, 1)],
+        "autocomplete" => vec![(RequestKind::Autocomplete, 1)],
+        "batch" => vec![(RequestKind::Batch, 1)],
+        _ => return None,
+    };
+    Some(weights)
+}
+
+/// Small deterministic PRNG (xorshift64*) so request sequences are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Options {
+    target: String,
+    scenario: String,
+    rps: u32,
+    duration: Duration,
+    concurrency: usize,
+    seed: u64,
+    json: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut options = Options {
+            target: "http://localhost:8080".to_string(),
+            scenario: "mixed".to_string(),
+            rps: 20,
+            duration: Duration::from_secs(30),
+            concurrency: 64,
+            seed: 42,
+            json: false,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => {
+                    options.json = true;
+                    continue;
+                }
+                "--help" | "-h" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                _ => {}
+            }
+            let value = args
+                .next()
+                .with_context(|| format!("missing value for {}", arg))?;
+            match arg.as_str() {
+                "--target" => options.target = value.trim_end_matches('/').to_string(),
+                "--scenario" => options.scenario = value,
+                "--rps" => options.rps = value.parse().context("--rps must be a number")?,
+                "--duration" => {
+                    options.duration =
+                        Duration::from_secs(value.parse().context("--duration must be seconds")?)
+                }
+                "--concurrency" => {
+                    options.concurrency = value.parse().context("--concurrency must be a number")?
+                }
+                "--seed" => options.seed = value.parse().context("--seed must be a number")?,
+                other => bail!("unknown argument '{}'", other),
+            }
+        }
+
+        if options.rps == 0 {
+            bail!("--rps must be greater than 0");
+        }
+        if scenario_weights(&options.scenario).is_none() {
+            bail!(
+                "unknown scenario '{}' (expected mixed, search, lookup, autocomplete or batch)",
+                options.scenario
+            );
+        }
+        Ok(options)
+    }
+}
+
+/// One scripted request
+#[derive(Debug, Clone)]
+struct Step {
+    kind: RequestKind,
+    method: reqwest::Method,
+    path: String,
+    body: Option<Value>,
+}
+
+/// Build the full request sequence for a run up front, so it only depends on the seed
+fn build_script(options: &Options, card_ids: &[String]) -> Vec<Step> {
+    let weights = scenario_weights(&options.scenario).unwrap_or_default();
+    let total_weight: u32 = weights.iter().map(|(_, w)| w).sum();
+    let count = options.rps as u64 * options.duration.as_secs().max(1);
+    let mut rng = Rng::new(options.seed);
+
+    (0..count)
+        .map(|_| {
+            let mut roll = rng.below(total_weight as usize) as u32;
+            let kind = weights
+                .iter()
+                .find(|(_, weight)| {
+                    if roll < *weight {
+                        true
+                    } else {
+                        roll -= weight;
+                        false
+                    }
+                })
+                .map(|(kind, _)| *kind)
+                .unwrap_or(RequestKind::Search);
+
+            // Lookups need IDs from the bootstrap search; fall back to a name lookup
+            let kind = match kind {
+                RequestKind::Lookup | RequestKind::Batch if card_ids.is_empty() => {
+                    RequestKind::Named
+                }
+                kind => kind,
+            };
+
+            let get = |path: String| Step {
+                kind,
+                method: reqwest::Method::GET,
+                path,
+                body: None,
+            };
+            match kind {
+                RequestKind::Search => get(format!(
+                    "/cards/search?q={}&page_size=50",
+                    urlencoding::encode(rng.pick(SEARCH_QUERIES))
+                )),
+                RequestKind::Lookup => get(format!("/cards/{}", rng.pick(card_ids))),
+                RequestKind::Named => get(format!(
+                    "/cards/named?exact={}",
+                    urlencoding::encode(rng.pick(NAMES))
+                )),
+                RequestKind::Autocomplete => get(format!(
+                    "/cards/autocomplete?q={}",
+                    urlencoding::encode(rng.pick(PREFIXES))
+                )),
+                RequestKind::Batch => {
+                    let ids: Vec<&String> = (0..BATCH_SIZE).map(|_| rng.pick(card_ids)).collect();
+                    Step {
+                        kind,
+                        method: reqwest::Method::POST,
+                        path: "/cards/batch".to_string(),
+                        body: Some(json!({ "ids": ids })),
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// Card IDs from the target, used for ID lookups and batch requests
+async fn bootstrap_card_ids(client: &reqwest::Client, target: &str) -> Result<Vec<String>> {
+    let url = format!("{}/cards/search?q=t:creature&page_size=500", target);
+    let body: Value = client
+        .get(&url)
+        .send()
+        .await
+        .context("failed to reach target")?
+        .json()
+        .await
+        .context("invalid search response")?;
+
+    let mut ids: Vec<String> = body["data"]["data"]
+        .as_array()
+        .map(|cards| {
+            cards
+                .iter()
+                .filter_map(|card| card["id"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    // Sort so the script doesn't depend on the target's result order
+    ids.sort();
+    Ok(ids)
+}
+
+/// Cache hit/miss counters per tier, scraped from `/metrics`
+#[derive(Debug, Default, Clone)]
+struct CacheCounters {
+    hits: BTreeMap<String, f64>,
+    misses: BTreeMap<String, f64>,
+}
+
+impl CacheCounters {
+    fn parse(metrics: &str) -> Self {
+        let mut counters = CacheCounters::default();
+        for line in metrics.lines() {
+            let (map, rest) = if let Some(rest) = line.strip_prefix("cache_hits_total{") {
+                (&mut counters.hits, rest)
+            } else if let Some(rest) = line.strip_prefix("cache_misses_total{") {
+                (&mut counters.misses, rest)
+            } else {
+                continue;
+            };
+
+            let Some((labels, value)) = rest.split_once('}') else {
+                continue;
+            };
+            let tier = labels
+                .split(',')
+                .find_map(|label| label.strip_prefix("tier="))
+                .map(|tier| tier.trim_matches('"').to_string());
+            if let (Some(tier), Ok(value)) = (tier, value.trim().parse::<f64>()) {
+                map.insert(tier, value);
+            }
+        }
+        counters
+    }
+
+    /// Hit ratio per tier for the counts accumulated since `before`
+    fn hit_ratios_since(&self, before: &CacheCounters) -> BTreeMap<String, f64> {
+        let delta = |now: &BTreeMap<String, f64>, then: &BTreeMap<String, f64>, tier: &str| {
+            now.get(tier).copied().unwrap_or(0.0) - then.get(tier).copied().unwrap_or(0.0)
+        };
+
+        self.hits
+            .keys()
+            .chain(self.misses.keys())
+            .filter_map(|tier| {
+                let hits = delta(&self.hits, &before.hits, tier);
+                let misses = delta(&self.misses, &before.misses, tier);
+                (hits + misses > 0.0).then(|| (tier.clone(), hits / (hits + misses)))
+            })
+            .collect()
+    }
+}
+
+async fn scrape_cache_counters(client: &reqwest::Client, target: &str) -> CacheCounters {
+    match client.get(format!("{}/metrics", target)).send().await {
+        Ok(response) => CacheCounters::parse(&response.text().await.unwrap_or_default()),
+        Err(_) => CacheCounters::default(),
+    }
+}
+
+/// Latency samples and error count for one request kind
+#[derive(Debug, Default)]
+struct KindStats {
+    latencies: Vec<Duration>,
+    errors: usize,
+}
+
+/// Value at percentile `p` (0-100) of sorted samples, nearest-rank
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let options = Options::parse(std::env::args().skip(1))?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+
+    let card_ids = bootstrap_card_ids(&client, &options.target).await?;
+    let script = build_script(&options, &card_ids);
+    eprintln!(
+        "Running '{}' against {}: {} requests at {} rps (seed {}, {} card ids)",
+        options.scenario,
+        options.target,
+        script.len(),
+        options.rps,
+        options.seed,
+        card_ids.len()
+    );
+
+    let before = scrape_cache_counters(&client, &options.target).await;
+    let semaphore = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / options.rps as f64));
+    let mut tasks = JoinSet::new();
+    let started = Instant::now();
+
+    // Open-loop: requests are issued on schedule regardless of how fast earlier ones finish
+    for step in script {
+        interval.tick().await;
+        let permit = semaphore.clone().acquire_owned().await?;
+        let client = client.clone();
+        let url = format!("{}{}", options.target, step.path);
+        tasks.spawn(async move {
+            let mut request = client.request(step.method, &url);
+            if let Some(body) = &step.body {
+                request = request.json(body);
+            }
+            let start = Instant::now();
+            let ok = match request.send().await {
+                Ok(response) => {
+                    let ok = response.status().is_success();
+                    // Include body transfer in the measured latency
+                    let _ = response.bytes().await;
+                    ok
+                }
+                Err(_) => false,
+            };
+            drop(permit);
+            (step.kind, start.elapsed(), ok)
+        });
+    }
+
+    let mut stats: BTreeMap<RequestKind, KindStats> = BTreeMap::new();
+    while let Some(result) = tasks.join_next().await {
+        let (kind, latency, ok) = result?;
+        let entry = stats.entry(kind).or_default();
+        entry.latencies.push(latency);
+        if !ok {
+            entry.errors += 1;
+        }
+    }
+    let elapsed = started.elapsed();
+    let after = scrape_cache_counters(&client, &options.target).await;
+    let hit_ratios = after.hit_ratios_since(&before);
+
+    let mut all = Vec::new();
+    for kind_stats in stats.values_mut() {
+        kind_stats.latencies.sort();
+        all.extend_from_slice(&kind_stats.latencies);
+    }
+    all.sort();
+    let kinds: Vec<(&str, &KindStats)> = stats
+        .iter()
+        .map(|(kind, kind_stats)| (kind.as_str(), kind_stats))
+        .collect();
+    let total_errors: usize = stats.values().map(|s| s.errors).sum();
+
+    if options.json {
+        let summary = |latencies: &[Duration], errors: usize| {
+            json!({
+                "requests": latencies.len(),
+                "errors": errors,
+                "p50_ms": millis(percentile(latencies, 50.0)),
+                "p90_ms": millis(percentile(latencies, 90.0)),
+                "p99_ms": millis(percentile(latencies, 99.0)),
+                "max_ms": millis(latencies.last().copied().unwrap_or_default()),
+            })
+        };
+        let by_kind: serde_json::Map<String, Value> = kinds
+            .iter()
+            .map(|(name, s)| (name.to_string(), summary(&s.latencies, s.errors)))
+            .collect();
+        let report = json!({
+            "scenario": options.scenario,
+            "seed": options.seed,
+            "target_rps": options.rps,
+            "achieved_rps": all.len() as f64 / elapsed.as_secs_f64(),
+            "overall": summary(&all, total_errors),
+            "by_kind": by_kind,
+            "cache_hit_ratio": hit_ratios,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "{:<14} {:>8} {:>7} {:>9} {:>9} {:>9} {:>9}",
+        "kind", "requests", "errors", "p50 ms", "p90 ms", "p99 ms", "max ms"
+    );
+    let row = |name: &str, latencies: &[Duration], errors: usize| {
+        println!(
+            "{:<14} {:>8} {:>7} {:>9.1} {:>9.1} {:>9.1} {:>9.1}",
+            name,
+            latencies.len(),
+            errors,
+            millis(percentile(latencies, 50.0)),
+            millis(percentile(latencies, 90.0)),
+            millis(percentile(latencies, 99.0)),
+            millis(latencies.last().copied().unwrap_or_default()),
+        );
+    };
+    for (name, kind_stats) in &kinds {
+        row(name, &kind_stats.latencies, kind_stats.errors);
+    }
+    row("overall", &all, total_errors);
+
+    println!(
+        "\nAchieved {:.1} rps over {:.1}s",
+        all.len() as f64 / elapsed.as_secs_f64(),
+        elapsed.as_secs_f64()
+    );
+    if hit_ratios.is_empty() {
+        println!("Cache hit ratio: unavailable (no cache activity in /metrics)");
+    } else {
+        for (tier, ratio) in &hit_ratios {
+            println!("Cache hit ratio [{}]: {:.1}%", tier, ratio * 100.0);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str]) -> Options {
+        Options::parse(args.iter().map(|a| a.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_script_is_reproducible() {
+        let opts = options(&["--rps", "10", "--duration", "2", "--seed", "7"]);
+        let ids = vec!["a".to_string(), "b".to_string()];
+
+        let first: Vec<String> = build_script(&opts, &ids)
+            .into_iter()
+            .map(|s| s.path)
+            .collect();
+        let second: Vec<String> = build_script(&opts, &ids)
+            .into_iter()
+            .map(|s| s.path)
+            .collect();
+        assert_eq!(first.len(), 20);
+        assert_eq!(first, second);
+
+        let other_seed = options(&["--rps", "10", "--duration", "2", "--seed", "8"]);
+        let third: Vec<String> = build_script(&other_seed, &ids)
+            .into_iter()
+            .map(|s| s.path)
+            .collect();
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn test_options_reject_unknown_scenario() {
+        assert!(Options::parse(["--scenario", "nope"].iter().map(|a| a.to_string())).is_err());
+        assert!(Options::parse(["--rps", "0"].iter().map(|a| a.to_string())).is_err());
+    }
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&samples, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&samples, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_cache_hit_ratio_from_metrics() {
+        let before = CacheCounters::parse(
+            "cache_hits_total{tier=\"database\"} 10\ncache_misses_total{tier=\"database\"} 10\n",
+        );
+        let after = CacheCounters::parse(
+            "# HELP cache_hits_total Total cache hits\n\
+             cache_hits_total{tier=\"database\"} 40\n\
+             cache_misses_total{tier=\"database\"} 20\n\
+             cache_misses_total{tier=\"api\"} 5\n",
+        );
+
+        let ratios = after.hit_ratios_since(&before);
+        assert_eq!(ratios["database"], 0.75);
+        assert_eq!(ratios["api"], 0.0);
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::utils::hash::hash_query;
+
+/// Default number of distinct queries tracked
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// How often a search query was seen, for deciding what to warm or index
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct TopQuery {
+    pub query_hash: String,
+    pub query_text: String,
+    /// Times the query was searched. May be overestimated for queries that replaced
+    /// an evicted entry (by at most the evicted entry's count).
+    pub count: u64,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Bounded in-memory frequency table of search queries.
+///
+/// Uses the space-saving algorithm: once `capacity` distinct queries are tracked, a new
+/// query replaces the least frequent one and inherits its count, so frequent queries are
+/// never lost while memory stays bounded.
+pub struct QueryAnalytics {
+    entries: Mutex<HashMap<String, TopQuery>>,
+    capacity: usize,
+}
+
+impl QueryAnalytics {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Count one search for `query`
+    pub fn record(&self, query: &str) {
+        let query_hash = hash_query(query);
+        let now = Utc::now();
+        let mut entries = self.entries.lock().unwrap();
+
+        if let Some(entry) = entries.get_mut(&query_hash) {
+            entry.count += 1;
+            entry.last_seen = now;
+            return;
+        }
+
+        let mut count = 1;
+        if entries.len() >= self.capacity {
+            let least_frequent = entries
+                .iter()
+                .min_by_key(|(_, entry)| (entry.count, entry.last_seen))
+                .map(|(hash, _)| hash.clone());
+            if let Some(evicted) = least_frequent.and_then(|hash| entries.remove(&hash)) {
+                count += evicted.count;
+            }
+        }
+
+        entries.insert(
+            query_hash.clone(),
+            TopQuery {
+                query_hash,
+                query_text: query.to_string(),
+                count,
+                last_seen: now,
+            },
+        );
+    }
+
+    /// The `limit` most frequent queries, most frequent first
+    pub fn top(&self, limit: usize) -> Vec<TopQuery> {
+        let mut queries: Vec<TopQuery> = self.entries.lock().unwrap().values().cloned().collect();
+        queries.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| b.last_seen.cmp(&a.last_seen))
+        });
+        queries.truncate(limit);
+        queries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_queries_ordered_by_count() {
+        let analytics = QueryAnalytics::new(10);
+        for query in ["c:red", "t:goblin", "c:red", "o:draw", "c:red", "t:goblin"] {
+            analytics.record(query);
+        }
+
+        let top = analytics.top(2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].query_text, "c:red");
+        assert_eq!(top[0].count, 3);
+        assert_eq!(top[0].query_hash, hash_query("c:red"));
+        assert_eq!(top[1].query_text, "t:goblin");
+        assert_eq!(top[1].count, 2);
+    }
+
+    #[test]
+    fn test_capacity_evicts_least_frequent() {
+        let analytics = QueryAnalytics::new(2);
+        analytics.record("c:red");
+        analytics.record("c:red");
+        analytics.record("c:red");
+        analytics.record("t:goblin");
+        analytics.record("o:draw");
+
+        let top = analytics.top(10);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].query_text, "c:red");
+        // Replaced "t:goblin" and inherited its count
+        assert_eq!(top[1].query_text, "o:draw");
+        assert_eq!(top[1].count, 2);
+    }
+}
@@ -0,0 +1,227 @@
+//! Encoding of values stored in Redis. Cards carry their full Scryfall JSON, so entries are
+//! large; MessagePack and compression make them smaller at some CPU cost.
+//!
+//! Entries written with the default (JSON, uncompressed) are plain JSON, as they have
+//! always been. Any other combination is prefixed with a three-byte header naming the
+//! encoding and compression, so entries are decoded correctly whatever the current
+//! configuration is, and changing it never requires flushing Redis.
+
+use anyhow::{bail, Result};
+
+/// First header byte. Never the first byte of a JSON entry (`{`, `[` or `"`).
+#[cfg(feature = "redis_cache")]
+const HEADER_MARKER: u8 = 0xC5;
+
+/// Serialization format of Redis cache entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheEncoding {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl CacheEncoding {
+    /// Parse `REDIS_ENCODING` (`json` or `msgpack`)
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(CacheEncoding::Json),
+            "msgpack" | "messagepack" => Ok(CacheEncoding::MessagePack),
+            other => bail!(
+                "unknown Redis encoding '{}' (expected json or msgpack)",
+                other
+            ),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheEncoding::Json => "json",
+            CacheEncoding::MessagePack => "msgpack",
+        }
+    }
+
+    #[cfg(feature = "redis_cache")]
+    fn tag(&self) -> u8 {
+        match self {
+            CacheEncoding::Json => 0,
+            CacheEncoding::MessagePack => 1,
+        }
+    }
+
+    #[cfg(feature = "redis_cache")]
+    fn from_tag(tag: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(CacheEncoding::Json),
+            1 => Ok(CacheEncoding::MessagePack),
+            _ => bail!("unknown cache entry encoding {}", tag),
+        }
+    }
+}
+
+/// Compression of Redis cache entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheCompression {
+    #[default]
+    None,
+    Zstd,
+    Lz4,
+}
+
+impl CacheCompression {
+    /// Parse `REDIS_COMPRESSION` (`none`, `zstd` or `lz4`)
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" | "" => Ok(CacheCompression::None),
+            "zstd" => Ok(CacheCompression::Zstd),
+            "lz4" => Ok(CacheCompression::Lz4),
+            other => bail!(
+                "unknown Redis compression '{}' (expected none, zstd or lz4)",
+                other
+            ),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheCompression::None => "none",
+            CacheCompression::Zstd => "zstd",
+            CacheCompression::Lz4 => "lz4",
+        }
+    }
+
+    #[cfg(feature = "redis_cache")]
+    fn tag(&self) -> u8 {
+        match self {
+            CacheCompression::None => 0,
+            CacheCompression::Zstd => 1,
+            CacheCompression::Lz4 => 2,
+        }
+    }
+
+    #[cfg(feature = "redis_cache")]
+    fn from_tag(tag: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(CacheCompression::None),
+            1 => Ok(CacheCompression::Zstd),
+            2 => Ok(CacheCompression::Lz4),
+            _ => bail!("unknown cache entry compression {}", tag),
+        }
+    }
+}
+
+/// Serialize `value` for Redis with `encoding` and `compression`
+#[cfg(feature = "redis_cache")]
+pub fn encode<T: serde::Serialize>(
+    value: &T,
+    encoding: CacheEncoding,
+    compression: CacheCompression,
+) -> Result<Vec<u8>> {
+    use anyhow::Context;
+
+    let serialized = match encoding {
+        CacheEncoding::Json => serde_json::to_vec(value).context("JSON encoding failed")?,
+        // Named fields, so `skip_serializing_if` and added fields keep working
+        CacheEncoding::MessagePack => {
+            rmp_serde::to_vec_named(value).context("MessagePack encoding failed")?
+        }
+    };
+    if encoding == CacheEncoding::Json && compression == CacheCompression::None {
+        return Ok(serialized);
+    }
+
+    let payload = match compression {
+        CacheCompression::None => serialized,
+        CacheCompression::Zstd => {
+            zstd::encode_all(serialized.as_slice(), 3).context("zstd compression failed")?
+        }
+        CacheCompression::Lz4 => lz4_flex::compress_prepend_size(&serialized),
+    };
+    let mut bytes = Vec::with_capacity(payload.len() + 3);
+    bytes.extend_from_slice(&[HEADER_MARKER, encoding.tag(), compression.tag()]);
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+/// Deserialize an entry written by `encode` with any encoding and compression, or a plain
+/// JSON entry
+#[cfg(feature = "redis_cache")]
+pub fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    use anyhow::Context;
+
+    let Some((&[HEADER_MARKER, encoding, compression], payload)) = bytes.split_first_chunk::<3>()
+    else {
+        return serde_json::from_slice(bytes).context("JSON decoding failed");
+    };
+
+    let decompressed;
+    let serialized = match CacheCompression::from_tag(compression)? {
+        CacheCompression::None => payload,
+        CacheCompression::Zstd => {
+            decompressed = zstd::decode_all(payload).context("zstd decompression failed")?;
+            decompressed.as_slice()
+        }
+        CacheCompression::Lz4 => {
+            decompressed =
+                lz4_flex::decompress_size_prepended(payload).context("lz4 decompression failed")?;
+            decompressed.as_slice()
+        }
+    };
+    match CacheEncoding::from_tag(encoding)? {
+        CacheEncoding::Json => serde_json::from_slice(serialized).context("JSON decoding failed"),
+        CacheEncoding::MessagePack => {
+            rmp_serde::from_slice(serialized).context("MessagePack decoding failed")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "redis_cache")]
+    #[test]
+    fn test_codec_round_trips() {
+        use crate::models::card::Card;
+
+        let card = Card::from_scryfall_json(serde_json::json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "name": "Sol Ring",
+            "oracle_text": "{T}: Add {C}{C}.",
+            "prices": {"usd": "1.50", "eur": null}
+        }))
+        .unwrap();
+
+        for encoding in [CacheEncoding::Json, CacheEncoding::MessagePack] {
+            for compression in [
+                CacheCompression::None,
+                CacheCompression::Zstd,
+                CacheCompression::Lz4,
+            ] {
+                let bytes = encode(&card, encoding, compression).unwrap();
+                let decoded: Card = decode(&bytes).unwrap();
+                assert_eq!(decoded.id, card.id);
+                assert_eq!(decoded.raw_json, card.raw_json);
+            }
+        }
+
+        // The default is plain JSON, as written before encodings were configurable
+        let plain = encode(&card, CacheEncoding::Json, CacheCompression::None).unwrap();
+        assert_eq!(plain, serde_json::to_vec(&card).unwrap());
+        assert!(decode::<Card>(&[HEADER_MARKER, 9, 0]).is_err());
+    }
+
+    #[test]
+    fn test_parse_codec_settings() {
+        assert_eq!(
+            CacheEncoding::parse("MsgPack").unwrap(),
+            CacheEncoding::MessagePack
+        );
+        assert!(CacheEncoding::parse("bincode").is_err());
+        assert_eq!(CacheCompression::parse("").unwrap(), CacheCompression::None);
+        assert_eq!(
+            CacheCompression::parse("lz4").unwrap(),
+            CacheCompression::Lz4
+        );
+        assert!(CacheCompression::parse("gzip").is_err());
+    }
+}
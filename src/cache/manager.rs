@@ -1,72 +1,719 @@
-use anyhow::Result;
-use std::collections::HashSet;
-use tracing::{debug, info};
+use futures::StreamExt;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn, Span};
 use uuid::Uuid;
 
-use crate::cache::redis::RedisCache;
-use crate::db::Database;
-use crate::metrics::registry::{CACHE_HITS_TOTAL, CACHE_MISSES_TOTAL};
-use crate::models::card::Card;
+use crate::background::writer::{self, CachedSearch, CardWriter, Writeback};
+use crate::cache::analytics::{QueryAnalytics, TopQuery};
+use crate::cache::policy::{CachePolicy, CachePolicyTable, QueryClass};
+use crate::cache::redis::{RedisCache, RedisLock, RedisStats};
+use crate::circuit_breaker::CircuitBreakerRegistry;
+use crate::collections::{self, CollectionValue};
+use crate::config::CacheConfig;
+use crate::db::{AdvisoryLock, AutocompleteOptions, CardProjection, CardStream, Database};
+use crate::deadline;
+use crate::errors::{ServiceError, ServiceResult};
+use crate::events::{self, CacheEvent};
+use crate::metrics::registry::{
+    CACHE_HITS_TOTAL, CACHE_MISSES_TOTAL, CACHE_SIZE_BYTES, CACHE_TIER_DURATION_SECONDS,
+    CARDS_TOTAL, QUERIES_CACHED_TOTAL, SEARCH_TOTAL_DURATION_SECONDS,
+};
+use crate::models::card::{normalize_card_name, Card, ExternalId, RelatedCard};
+use crate::models::collection::CollectionEntry;
+use crate::models::idempotency::IdempotencyRecord;
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::RepairReport;
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::SchemaVersion;
 use crate::query::executor::QueryExecutor;
-use crate::scryfall::client::ScryfallClient;
+use crate::query::{QueryLimits, RuntimeQueryLimits, SearchCursor, SearchOrder, UniqueMode};
+use crate::scryfall::source::CardSource;
+use crate::tenant;
 use crate::utils::hash::hash_query;
 
+/// One page of a paginated search
+#[derive(Debug, Default)]
+pub struct SearchPage {
+    pub cards: Vec<Card>,
+    /// Matching cards across all pages
+    pub total: usize,
+    /// The Scryfall fallback stopped at its page or card cap: `total` is Scryfall's
+    /// count, but only the cards it fetched can be paged through
+    pub truncated: bool,
+}
+
+/// How long proxied Scryfall reference data (sets, catalogs, symbology) is kept in memory
+const REFERENCE_DATA_TTL: Duration = Duration::from_secs(24 * 3600);
+
+/// Most cards a saved search may match; every match is stored to tell new cards apart
+pub const MAX_SAVED_SEARCH_MATCHES: usize = 10_000;
+
 pub struct CacheManager {
     redis: Option<RedisCache>,
     db: Database,
     query_executor: QueryExecutor,
-    scryfall_client: ScryfallClient,
-    query_cache_ttl_hours: i32,
+    /// Where cards missing from the database are fetched from, normally Scryfall
+    card_source: Arc<dyn CardSource>,
+    cache_policies: CachePolicyTable,
+    reference_data: RwLock<HashMap<String, (Instant, serde_json::Value)>>,
+    /// Last Scryfall reachability probe and its outcome
+    upstream_probe: tokio::sync::Mutex<Option<(Instant, Result<(), String>)>>,
+    /// How often each search query is seen
+    query_analytics: QueryAnalytics,
+    /// Write-behind storage for cards fetched from Scryfall
+    writer: CardWriter,
+    /// When this manager was created, for `CacheStats::uptime_seconds`
+    started_at: Instant,
+    /// Bumped by every bulk reload; searches that started under an older generation
+    /// don't store their results
+    generation: AtomicU64,
+    /// Never write to the database; caches are only filled in Redis
+    read_only: bool,
+    /// Query limits shared with the query validator, for `query_timeout_seconds`
+    query_limits: RuntimeQueryLimits,
 }
 
 impl CacheManager {
     pub fn new(
         redis: Option<RedisCache>,
         db: Database,
-        scryfall_client: ScryfallClient,
-        query_cache_ttl_hours: i32,
+        card_source: Arc<dyn CardSource>,
+        config: &CacheConfig,
     ) -> Self {
-        let query_executor = QueryExecutor::new(db.clone());
+        let query_executor =
+            QueryExecutor::new(db.clone()).with_default_unique(config.default_unique);
+        let writer = CardWriter::new(db.clone(), redis.clone(), &config.card_writer);
 
         Self {
             redis,
             db,
             query_executor,
-            scryfall_client,
-            query_cache_ttl_hours,
+            card_source,
+            cache_policies: config.cache_policies.clone(),
+            reference_data: RwLock::new(HashMap::new()),
+            upstream_probe: tokio::sync::Mutex::new(None),
+            query_analytics: QueryAnalytics::new(config.analytics_capacity),
+            writer,
+            started_at: Instant::now(),
+            generation: AtomicU64::new(0),
+            read_only: false,
+            query_limits: RuntimeQueryLimits::new(QueryLimits::default()),
+        }
+    }
+
+    /// Bound searches by the `query_timeout_seconds` of `limits`, so changes made
+    /// through the admin API apply to the next search
+    pub fn with_query_limits(mut self, limits: RuntimeQueryLimits) -> Self {
+        self.query_limits = limits;
+        self
+    }
+
+    /// Serve reads only: cards fetched from Scryfall and search results are not stored
+    /// in the database, and cache administration is rejected with `ReadOnly`
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Whether this manager was created for a read-only replica
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Reject `operation` when running read-only
+    fn ensure_writable(&self, operation: &str) -> ServiceResult<()> {
+        if self.read_only {
+            return Err(ServiceError::ReadOnly(operation.to_string()));
         }
+        Ok(())
     }
 
-    pub async fn test_database_connection(&self) -> Result<()> {
+    pub async fn test_database_connection(&self) -> ServiceResult<()> {
         self.db.test_connection().await
     }
 
-    pub async fn test_redis_connection(&self) -> Result<()> {
+    /// Whether a Redis cache was configured and connected at startup
+    pub fn redis_enabled(&self) -> bool {
+        self.redis.is_some()
+    }
+
+    /// Store cards still queued for the write-behind writer; called once on shutdown
+    pub async fn shutdown(&self) {
+        self.writer.shutdown().await;
+    }
+
+    /// Circuit breakers guarding each Scryfall endpoint
+    pub fn circuit_breakers(&self) -> &CircuitBreakerRegistry {
+        self.card_source.circuit_breakers()
+    }
+
+    pub async fn test_redis_connection(&self) -> ServiceResult<()> {
         if let Some(redis) = &self.redis {
-            redis.test_connection().await
+            Ok(redis.test_connection().await?)
         } else {
             Ok(())
         }
     }
 
+    /// Whether the Scryfall API is reachable. The probe result is reused for `max_age`,
+    /// and concurrent callers wait for a single probe rather than each sending one.
+    pub async fn check_upstream(&self, max_age: Duration) -> Result<(), String> {
+        let mut last_probe = self.upstream_probe.lock().await;
+        if let Some((probed_at, result)) = last_probe.as_ref() {
+            if probed_at.elapsed() < max_age {
+                return result.clone();
+            }
+        }
+
+        let result = self.card_source.probe().await.map_err(|e| e.to_string());
+        *last_probe = Some((Instant::now(), result.clone()));
+        result
+    }
+
+    /// Number of database query cache entries per tenant, ordered by tenant id
+    pub async fn query_cache_entries_by_tenant(&self) -> ServiceResult<Vec<(String, i64)>> {
+        self.db.count_query_cache_by_tenant().await
+    }
+
+    /// Applied database schema migrations and those still pending
+    pub async fn schema_version(&self) -> ServiceResult<SchemaVersion> {
+        self.db.get_schema_version().await
+    }
+
+    /// Entries in the database query cache, most recently cached first
+    pub async fn list_cached_queries(&self, limit: i64) -> ServiceResult<Vec<QueryCacheEntry>> {
+        self.db.list_query_cache(limit).await
+    }
+
+    /// Evict one cached query from the database and Redis query caches.
+    /// Returns whether it was cached in the database and in Redis, or `NotFound`
+    /// if it was cached in neither.
+    pub async fn evict_cached_query(&self, query_hash: &str) -> ServiceResult<(bool, bool)> {
+        self.ensure_writable("cannot evict cached queries")?;
+        let in_database = self.db.delete_query_cache(query_hash).await?;
+        let in_redis = match &self.redis {
+            Some(redis) => redis.delete_query_results(query_hash).await?,
+            None => false,
+        };
+        if !in_database && !in_redis {
+            return Err(ServiceError::NotFound(format!(
+                "Query not cached: {}",
+                query_hash
+            )));
+        }
+        Ok((in_database, in_redis))
+    }
+
+    /// Evict every cached query, returning how many database and Redis entries were removed
+    pub async fn clear_cached_queries(&self) -> ServiceResult<(u64, u64)> {
+        self.ensure_writable("cannot clear cached queries")?;
+        let database = self.db.clear_query_cache().await?;
+        let redis = match &self.redis {
+            Some(redis) => redis.clear_query_results().await?,
+            None => 0,
+        };
+        info!(
+            "Cleared query cache ({} database entries, {} Redis entries)",
+            database, redis
+        );
+        Ok((database, redis))
+    }
+
+    /// Store owned quantities in a collection of the current tenant; quantity 0 removes
+    /// an entry
+    pub async fn upsert_collection_entries(
+        &self,
+        collection_id: &str,
+        entries: &[CollectionEntry],
+    ) -> ServiceResult<()> {
+        self.ensure_writable("cannot modify collections")?;
+        self.db
+            .upsert_collection_entries(&tenant::current_id(), collection_id, entries)
+            .await
+    }
+
+    /// Value a collection of the current tenant in `currency` from the prices of the
+    /// cached cards. Cards missing from the cache count as unpriced rather than being
+    /// fetched from Scryfall.
+    pub async fn collection_value(
+        &self,
+        collection_id: &str,
+        currency: &str,
+    ) -> ServiceResult<CollectionValue> {
+        let entries = self
+            .db
+            .list_collection_entries(&tenant::current_id(), collection_id)
+            .await?;
+        let ids: Vec<Uuid> = entries
+            .iter()
+            .map(|entry| entry.card_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let cards = if ids.is_empty() {
+            Vec::new()
+        } else {
+            self.get_cards_batch(&ids, false).await?.0
+        };
+        Ok(collections::collection_value(
+            collection_id,
+            currency,
+            &entries,
+            &cards,
+        ))
+    }
+
+    /// Register a saved search for the current tenant. The cards it matches now are
+    /// recorded, so only cards added by later bulk imports are reported as new.
+    pub async fn create_saved_search(
+        &self,
+        query: &str,
+        name: Option<String>,
+    ) -> ServiceResult<SavedSearch> {
+        self.ensure_writable("cannot create saved searches")?;
+        let card_ids = self.saved_search_card_ids(query).await?;
+        let search = SavedSearch {
+            id: Uuid::new_v4(),
+            tenant_id: tenant::current_id(),
+            query: query.to_string(),
+            name,
+            runs: 0,
+            new_matches: 0,
+            created_at: Some(chrono::Utc::now().naive_utc()),
+            last_run_at: None,
+        };
+        self.db.insert_saved_search(&search, &card_ids).await?;
+        Ok(search)
+    }
+
+    /// Saved searches of the current tenant, oldest first
+    pub async fn saved_searches(&self) -> ServiceResult<Vec<SavedSearch>> {
+        self.db
+            .list_saved_searches(Some(&tenant::current_id()))
+            .await
+    }
+
+    /// Remove a saved search of the current tenant, returning it, or fail with `NotFound`
+    pub async fn delete_saved_search(&self, id: Uuid) -> ServiceResult<SavedSearch> {
+        self.ensure_writable("cannot delete saved searches")?;
+        let tenant_id = tenant::current_id();
+        let not_found = || ServiceError::NotFound(format!("Saved search not found: {}", id));
+        let search = self
+            .db
+            .get_saved_search(&tenant_id, id)
+            .await?
+            .ok_or_else(not_found)?;
+        if !self.db.delete_saved_search(&tenant_id, id).await? {
+            return Err(not_found());
+        }
+        Ok(search)
+    }
+
+    /// A saved search of the current tenant with the cards that first matched it in its
+    /// latest evaluation, ordered by name. Empty until the first bulk import after the
+    /// search was registered.
+    pub async fn saved_search_new_cards(
+        &self,
+        id: Uuid,
+    ) -> ServiceResult<(SavedSearch, Vec<Card>)> {
+        let search = self
+            .db
+            .get_saved_search(&tenant::current_id(), id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Saved search not found: {}", id)))?;
+        if search.runs == 0 {
+            return Ok((search, Vec::new()));
+        }
+
+        let ids = self.db.list_saved_search_matches(id, search.runs).await?;
+        let mut cards = if ids.is_empty() {
+            Vec::new()
+        } else {
+            self.get_cards_batch(&ids, false).await?.0
+        };
+        cards.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        Ok((search, cards))
+    }
+
+    /// Evaluate the saved searches of every tenant against the local card data, recording
+    /// cards that match a search for the first time and publishing `SavedSearchMatched`
+    /// for each search that gained matches. Searches that now fail or match too many
+    /// cards are skipped. Returns how many searches were evaluated.
+    pub async fn evaluate_saved_searches(&self) -> ServiceResult<usize> {
+        self.ensure_writable("cannot evaluate saved searches")?;
+        let searches = self.db.list_saved_searches(None).await?;
+
+        let mut evaluated = 0;
+        for search in searches {
+            let card_ids = match self.saved_search_card_ids(&search.query).await {
+                Ok(card_ids) => card_ids,
+                Err(e) => {
+                    warn!(
+                        "Skipping saved search {} ({}): {}",
+                        search.id, search.query, e
+                    );
+                    continue;
+                }
+            };
+            let new_card_ids = self
+                .db
+                .record_saved_search_run(search.id, &card_ids)
+                .await?;
+            evaluated += 1;
+
+            if !new_card_ids.is_empty() {
+                info!(
+                    "Saved search {} ({}) has {} new matches",
+                    search.id,
+                    search.query,
+                    new_card_ids.len()
+                );
+                events::publish(CacheEvent::SavedSearchMatched {
+                    id: search.id,
+                    tenant_id: search.tenant_id,
+                    query: search.query,
+                    new_card_ids,
+                });
+            }
+        }
+        Ok(evaluated)
+    }
+
+    /// Ids of the local cards matching a saved search query, failing with `Validation`
+    /// when there are more than `MAX_SAVED_SEARCH_MATCHES`. Every matching printing
+    /// counts, so a new printing of a known card is reported as a new match.
+    async fn saved_search_card_ids(&self, query: &str) -> ServiceResult<Vec<Uuid>> {
+        let cards = self
+            .query_executor
+            .execute_projected(
+                query,
+                Some(MAX_SAVED_SEARCH_MATCHES as i64 + 1),
+                UniqueMode::Prints,
+                &CardProjection::MINIMAL,
+            )
+            .await?;
+        if cards.len() > MAX_SAVED_SEARCH_MATCHES {
+            return Err(ServiceError::Validation(format!(
+                "Saved search query matches more than {} cards",
+                MAX_SAVED_SEARCH_MATCHES
+            )));
+        }
+        Ok(cards.into_iter().map(|card| card.id).collect())
+    }
+
+    /// Current cache generation, bumped by `invalidate_after_reload`
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Drop everything cached before a bulk reload so the new data is served immediately.
+    /// Bumps the cache generation first, so searches still running against the old data
+    /// don't repopulate the caches, then clears the database query cache and every Redis
+    /// key class. Returns how many database and Redis entries were removed.
+    pub async fn invalidate_after_reload(&self) -> ServiceResult<(u64, u64)> {
+        self.ensure_writable("cannot invalidate caches")?;
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let database = self.db.clear_query_cache().await?;
+        let redis = match &self.redis {
+            Some(redis) => redis.invalidate_all().await?,
+            None => 0,
+        };
+        info!(
+            "Invalidated caches after bulk reload (generation {}, {} database entries, {} Redis keys)",
+            generation, database, redis
+        );
+        Ok((database, redis))
+    }
+
+    /// Prune database query cache rows that have expired or gone unread for
+    /// `max_idle_hours`, and optionally Redis autocomplete keys without an expiry.
+    /// Returns how many database and Redis entries were removed.
+    pub async fn clean_expired_queries(
+        &self,
+        max_idle_hours: i32,
+        prune_autocomplete: bool,
+    ) -> ServiceResult<(u64, u64)> {
+        self.ensure_writable("cannot clean cached queries")?;
+        let database = self.db.clean_old_cache_entries(max_idle_hours).await?;
+        let redis = match self.redis.as_ref().filter(|_| prune_autocomplete) {
+            Some(redis) => redis.prune_autocomplete().await?,
+            None => 0,
+        };
+        Ok((database, redis))
+    }
+
+    /// Take cluster-wide lock `name` without waiting, in Redis when it is configured and
+    /// as a database advisory lock otherwise. Returns `None` while another instance holds
+    /// it; the lock is released when the returned guard is dropped.
+    pub async fn try_distributed_lock(&self, name: &str) -> ServiceResult<Option<AdvisoryLock>> {
+        match &self.redis {
+            Some(redis) => Ok(RedisLock::try_acquire(redis, name)
+                .await?
+                .map(AdvisoryLock::new)),
+            None => self.db.try_advisory_lock(name).await,
+        }
+    }
+
+    /// Claim idempotency key `key` for a request hashing to `request_hash` for
+    /// `ttl_seconds`, in Redis when it is configured and in the database otherwise.
+    /// Returns `None` once claimed, or the record already stored under the key.
+    pub async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_hash: &str,
+        ttl_seconds: u64,
+    ) -> ServiceResult<Option<IdempotencyRecord>> {
+        self.ensure_writable("cannot store idempotency keys")?;
+        match &self.redis {
+            Some(redis) => Ok(redis
+                .claim_idempotency_key(key, request_hash, ttl_seconds)
+                .await?),
+            None => {
+                self.db
+                    .claim_idempotency_key(key, request_hash, ttl_seconds as i64)
+                    .await
+            }
+        }
+    }
+
+    /// Store the response of a claimed idempotency key for replaying to retries
+    pub async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        record: &IdempotencyRecord,
+    ) -> ServiceResult<()> {
+        match &self.redis {
+            Some(redis) => Ok(redis.complete_idempotency_key(key, record).await?),
+            None => self.db.complete_idempotency_key(key, record).await,
+        }
+    }
+
+    /// Drop a claimed idempotency key without a response, so a retry runs again
+    pub async fn release_idempotency_key(&self, key: &str) -> ServiceResult<()> {
+        match &self.redis {
+            Some(redis) => Ok(redis.release_idempotency_key(key).await?),
+            None => self.db.release_idempotency_key(key).await,
+        }
+    }
+
+    /// Delete expired idempotency keys from the database (Redis expires its own).
+    /// Returns how many were removed.
+    pub async fn clean_expired_idempotency_keys(&self) -> ServiceResult<u64> {
+        self.ensure_writable("cannot clean idempotency keys")?;
+        self.db.clean_expired_idempotency_keys().await
+    }
+
+    /// Scan the stored cards for integrity issues and re-fetch up to `limit` affected
+    /// cards from Scryfall, overwriting their rows and Redis entries. Cards Scryfall no
+    /// longer knows are left in place and counted as `not_found`. A failed fetch is
+    /// reported in the result rather than as an error, with nothing stored.
+    pub async fn repair_cards(&self, limit: usize) -> ServiceResult<RepairReport> {
+        self.ensure_writable("cannot repair cards")?;
+        let started_at = chrono::Utc::now().naive_utc();
+        let start = Instant::now();
+
+        let issues = self.db.find_integrity_issues(limit as i64).await?;
+        let mut report = RepairReport {
+            started_at,
+            duration_ms: 0,
+            issues_found: issues.len(),
+            repaired: 0,
+            not_found: 0,
+            error: None,
+            issues,
+        };
+
+        if !report.issues.is_empty() {
+            let ids: Vec<Uuid> = report.issues.iter().map(|issue| issue.id).collect();
+            match self.card_source.get_cards_by_ids_collection(&ids).await {
+                Ok(cards) => {
+                    if !cards.is_empty() {
+                        self.db.insert_cards_batch(&cards).await?;
+                        if let Some(redis) = &self.redis {
+                            redis.set_cards(&cards).await.ok();
+                        }
+                    }
+                    for card in &cards {
+                        events::publish(CacheEvent::CardUpdated {
+                            id: card.id,
+                            name: card.name.clone(),
+                        });
+                    }
+                    let fetched: HashSet<Uuid> = cards.iter().map(|card| card.id).collect();
+                    report.repaired = ids.iter().filter(|id| fetched.contains(id)).count();
+                    report.not_found = ids.len() - report.repaired;
+                }
+                Err(e) => {
+                    warn!("Card repair could not re-fetch {} cards: {}", ids.len(), e);
+                    report.error = Some(e.to_string());
+                }
+            }
+        }
+
+        report.duration_ms = start.elapsed().as_millis() as u64;
+        info!(
+            "Card repair found {} cards with issues: {} repaired, {} not found",
+            report.issues_found, report.repaired, report.not_found
+        );
+        Ok(report)
+    }
+
+    /// Caching policy for a search query, chosen by its query class
+    pub fn cache_policy(&self, query: &str) -> (QueryClass, CachePolicy) {
+        let class = QueryClass::classify_query(query);
+        (class, self.cache_policies.get(class))
+    }
+
+    /// Store search results in the cache tiers enabled by `policy`, unless a bulk reload
+    /// has invalidated the caches since the search started under `generation`.
+    /// Read-only managers only store them in Redis.
+    async fn store_query_results(
+        &self,
+        query: &str,
+        query_hash: &str,
+        card_ids: &[Uuid],
+        policy: &CachePolicy,
+        generation: u64,
+    ) {
+        if self.generation() != generation {
+            debug!(
+                "Not caching results computed before a bulk reload: {}",
+                query
+            );
+            return;
+        }
+        let policy = CachePolicy {
+            database: policy.database && !self.read_only,
+            ..*policy
+        };
+        writer::store_query_results(
+            &self.db,
+            self.redis.as_ref(),
+            &tenant::current_id(),
+            query,
+            query_hash,
+            card_ids,
+            &policy,
+        )
+        .await;
+    }
+
+    /// Store cards fetched from Scryfall through the write-behind card writer
+    async fn store_fetched(&self, cards: Vec<Card>) -> ServiceResult<()> {
+        self.write_back(Writeback {
+            cards,
+            search: None,
+        })
+        .await
+    }
+
+    /// Hand `writeback` to the card writer; a no-op when running read-only
+    async fn write_back(&self, writeback: Writeback) -> ServiceResult<()> {
+        if self.read_only {
+            debug!(
+                "Read-only mode: not storing {} fetched cards",
+                writeback.cards.len()
+            );
+            return Ok(());
+        }
+        self.writer.submit(writeback).await
+    }
+
+    /// Most frequently searched queries since startup, most frequent first
+    pub fn top_queries(&self, limit: usize) -> Vec<TopQuery> {
+        self.query_analytics.top(limit)
+    }
+
     /// Search for cards with caching
-    pub async fn search(&self, query: &str, limit: Option<i64>) -> Result<Vec<Card>> {
-        debug!("Cache search for query: {}", query);
+    pub async fn search(&self, query: &str, limit: Option<i64>) -> ServiceResult<Vec<Card>> {
+        self.search_projected(query, limit, &CardProjection::ALL)
+            .await
+    }
+
+    /// Search for cards with caching, reading only the columns selected by `projection`
+    pub async fn search_projected(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+        projection: &CardProjection,
+    ) -> ServiceResult<Vec<Card>> {
+        self.query_analytics.record(query);
+        self.search_timed(query, limit, projection).await
+    }
+
+    /// Search for cards with caching without counting the query in the top-queries
+    /// statistics (used by cache warm-up)
+    pub async fn search_untracked(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+    ) -> ServiceResult<Vec<Card>> {
+        self.search_timed(query, limit, &CardProjection::ALL).await
+    }
+
+    /// Run a search through the cache tiers, recording its total duration
+    async fn search_timed(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+        projection: &CardProjection,
+    ) -> ServiceResult<Vec<Card>> {
+        let start = Instant::now();
+        let search = self.search_tiers(query, limit, projection);
+        let result = self.bounded_search(search).await;
+        record_search(query, &result, start);
+        result.map(|(cards, _)| cards)
+    }
+
+    /// Run a search until the request deadline or `query_timeout_seconds`, whichever
+    /// comes first, failing with `ServiceError::Timeout` after that. The tighter deadline
+    /// also applies to the Scryfall fallback.
+    async fn bounded_search<T, F>(&self, search: F) -> ServiceResult<T>
+    where
+        F: Future<Output = ServiceResult<T>>,
+    {
+        let timeout = Duration::from_secs(self.query_limits.load().query_timeout_seconds);
+        let limit = tokio::time::Instant::now() + timeout;
+        let deadline = deadline::current().map_or(limit, |current| current.min(limit));
+        deadline::scope(deadline, deadline::enforce("search", search)).await
+    }
+
+    /// Walk the cache tiers for a search, returning the cards and the tier that answered
+    async fn search_tiers(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+        projection: &CardProjection,
+    ) -> ServiceResult<(Vec<Card>, &'static str)> {
+        let generation = self.generation();
+        let (class, policy) = self.cache_policy(query);
+        debug!(
+            "Cache search for query: {} (class {})",
+            query,
+            class.as_str()
+        );
+        let query_hash = tenant::query_cache_key(query);
 
         // 1. Check Redis cache first (if enabled)
-        if let Some(redis) = &self.redis {
-            if let Ok(Some(card_ids)) = redis.get_query_results(query).await {
+        if let Some(redis) = self.redis.as_ref().filter(|_| policy.redis) {
+            let lookup = timed("search", "redis", redis.get_query_results(&query_hash)).await;
+            if let Ok(Some(card_ids)) = lookup {
                 debug!("Redis cache hit for query: {} ({} IDs)", query, card_ids.len());
 
                 // Try to fetch cards from database
-                match self.db.get_cards_by_ids(&card_ids).await {
+                let fetched = self.db.get_cards_by_ids_projected(&card_ids, projection);
+                match fetched.await {
                     Ok(cards) if !cards.is_empty() => {
                         info!(
                             "Returned {} cards from Redis cache for query: {}",
                             cards.len(),
                             query
                         );
-                        return Ok(cards);
+                        return Ok((cards, "redis"));
                     }
                     _ => {
                         debug!("Redis had IDs but database fetch failed, falling back");
@@ -76,12 +723,21 @@ impl CacheManager {
         }
 
         // 2. Check database query cache
-        let query_hash = hash_query(query);
-        if let Some((card_ids, _total)) = self.db.get_query_cache(&query_hash).await? {
+        let cached = if policy.database && self.read_only {
+            let lookup = self.db.peek_query_cache(&query_hash);
+            timed("search", "query_cache", lookup).await?
+        } else if policy.database {
+            let lookup = self.db.get_query_cache(&query_hash);
+            timed("search", "query_cache", lookup).await?
+        } else {
+            None
+        };
+        if let Some((card_ids, _total)) = cached {
             debug!("Database query cache hit for query: {} ({} IDs)", query, card_ids.len());
 
             // Try to fetch cards from database
-            match self.db.get_cards_by_ids(&card_ids).await {
+            let fetched = self.db.get_cards_by_ids_projected(&card_ids, projection);
+            match fetched.await {
                 Ok(cards) if !cards.is_empty() => {
                     CACHE_HITS_TOTAL.with_label_values(&["query_cache"]).inc();
                     info!(
@@ -91,11 +747,14 @@ impl CacheManager {
                     );
 
                     // Store in Redis for faster access next time
-                    if let Some(redis) = &self.redis {
-                        redis.set_query_results(query, &card_ids).await.ok();
+                    if let Some(redis) = self.redis.as_ref().filter(|_| policy.redis) {
+                        redis
+                            .set_query_results(&query_hash, &card_ids, policy.ttl)
+                            .await
+                            .ok();
                     }
 
-                    return Ok(cards);
+                    return Ok((cards, "query_cache"));
                 }
                 Err(e) => {
                     debug!("Cache fetch failed ({}), falling back to direct query", e);
@@ -111,7 +770,12 @@ impl CacheManager {
         debug!("Cache miss for query: {}", query);
 
         // 3. Try to execute query locally against database
-        match self.query_executor.execute(query, limit).await {
+        let unique = self.query_executor.default_unique();
+        let executed = self
+            .query_executor
+            .execute_projected(query, limit, unique, projection);
+        let executed = timed("search", "database", executed).await;
+        match executed {
             Ok(cards) if !cards.is_empty() => {
                 CACHE_HITS_TOTAL.with_label_values(&["database"]).inc();
                 info!(
@@ -122,19 +786,10 @@ impl CacheManager {
 
                 // Store in both caches
                 let card_ids: Vec<Uuid> = cards.iter().map(|c| c.id).collect();
+                self.store_query_results(query, &query_hash, &card_ids, &policy, generation)
+                    .await;
 
-                // Store in database query cache
-                self.db
-                    .store_query_cache(&query_hash, &card_ids, self.query_cache_ttl_hours)
-                    .await
-                    .ok();
-
-                // Store in Redis cache
-                if let Some(redis) = &self.redis {
-                    redis.set_query_results(query, &card_ids).await.ok();
-                }
-
-                Ok(cards)
+                Ok((cards, "database"))
             }
             Ok(cards) => {
                 CACHE_MISSES_TOTAL.with_label_values(&["database"]).inc();
@@ -145,27 +800,24 @@ impl CacheManager {
                     query
                 );
                 info!("Querying Scryfall API for: {}", query);
-                let cards = self.scryfall_client.search_cards(query).await?;
+                let upstream = upstream_query(query, self.query_executor.default_unique());
+                let fetched = self.card_source.search_cards(&upstream);
+                let cards = timed("search", "api", fetched).await?.cards;
 
                 if !cards.is_empty() {
                     CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
-                    // Store cards in database
-                    self.db.insert_cards_batch(&cards).await?;
-
-                    // Store in both caches
-                    let card_ids: Vec<Uuid> = cards.iter().map(|c| c.id).collect();
-
-                    // Store in database query cache
-                    self.db
-                        .store_query_cache(&query_hash, &card_ids, self.query_cache_ttl_hours)
-                        .await
-                        .ok();
-
-                    // Store in Redis cache
-                    if let Some(redis) = &self.redis {
-                        redis.set_query_results(query, &card_ids).await.ok();
-                        redis.set_cards(&cards).await.ok();
-                    }
+                    // Store cards and cache the results without holding up the response
+                    let search = CachedSearch {
+                        query: query.to_string(),
+                        query_hash: query_hash.clone(),
+                        tenant_id: tenant::current_id(),
+                        policy,
+                    };
+                    self.write_back(Writeback {
+                        cards: cards.clone(),
+                        search: Some(search),
+                    })
+                    .await?;
 
                     info!(
                         "Returned {} cards from Scryfall API for query: {}",
@@ -176,34 +828,31 @@ impl CacheManager {
                     CACHE_MISSES_TOTAL.with_label_values(&["api"]).inc();
                 }
 
-                Ok(cards)
+                Ok((project_cards(cards, projection), "api"))
             }
             Err(e) => {
                 CACHE_MISSES_TOTAL.with_label_values(&["database"]).inc();
                 // Query executor failed with an error
                 debug!("Query executor error for query '{}': {}", query, e);
                 info!("Querying Scryfall API for: {}", query);
-                let cards = self.scryfall_client.search_cards(query).await?;
+                let upstream = upstream_query(query, self.query_executor.default_unique());
+                let fetched = self.card_source.search_cards(&upstream);
+                let cards = timed("search", "api", fetched).await?.cards;
 
                 if !cards.is_empty() {
                     CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
-                    // Store cards in database
-                    self.db.insert_cards_batch(&cards).await?;
-
-                    // Store in both caches
-                    let card_ids: Vec<Uuid> = cards.iter().map(|c| c.id).collect();
-
-                    // Store in database query cache
-                    self.db
-                        .store_query_cache(&query_hash, &card_ids, self.query_cache_ttl_hours)
-                        .await
-                        .ok();
-
-                    // Store in Redis cache
-                    if let Some(redis) = &self.redis {
-                        redis.set_query_results(query, &card_ids).await.ok();
-                        redis.set_cards(&cards).await.ok();
-                    }
+                    // Store cards and cache the results without holding up the response
+                    let search = CachedSearch {
+                        query: query.to_string(),
+                        query_hash: query_hash.clone(),
+                        tenant_id: tenant::current_id(),
+                        policy,
+                    };
+                    self.write_back(Writeback {
+                        cards: cards.clone(),
+                        search: Some(search),
+                    })
+                    .await?;
 
                     info!(
                         "Returned {} cards from Scryfall API for query: {}",
@@ -214,32 +863,86 @@ impl CacheManager {
                     CACHE_MISSES_TOTAL.with_label_values(&["api"]).inc();
                 }
 
-                Ok(cards)
+                Ok((project_cards(cards, projection), "api"))
             }
         }
     }
 
-    /// Search for cards with pagination (optimized - fetches only requested page)
+    /// Search for cards with pagination (optimized - fetches only requested page).
+    /// When `after` is set, the page starts after the cursor and `page` is ignored.
     pub async fn search_paginated(
         &self,
         query: &str,
         page: usize,
         page_size: usize,
-    ) -> Result<(Vec<Card>, usize)> {
+        after: Option<&SearchCursor>,
+    ) -> ServiceResult<SearchPage> {
+        self.search_paginated_projected(
+            query,
+            None,
+            SearchOrder::default(),
+            page,
+            page_size,
+            after,
+            &CardProjection::ALL,
+        )
+        .await
+    }
+
+    /// Same as `search_paginated`, deduplicating matches by `unique` (the configured
+    /// default when `None`), sorting them by `order` and reading only the columns selected
+    /// by `projection`. `after` requires the default order.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_paginated_projected(
+        &self,
+        query: &str,
+        unique: Option<UniqueMode>,
+        order: SearchOrder,
+        page: usize,
+        page_size: usize,
+        after: Option<&SearchCursor>,
+        projection: &CardProjection,
+    ) -> ServiceResult<SearchPage> {
         debug!(
             "Cache paginated search for query: {} (page {}, page_size {})",
             query, page, page_size
         );
 
+        // Count each search once, not once per page
+        if page <= 1 && after.is_none() {
+            self.query_analytics.record(query);
+        }
+
+        let start = Instant::now();
+        let unique = unique.unwrap_or(self.query_executor.default_unique());
+        let search =
+            self.search_paginated_tiers(query, unique, order, page, page_size, after, projection);
+        let result = self.bounded_search(search).await;
+        record_search(query, &result, start);
+        result.map(|(page, _)| page)
+    }
+
+    /// Answer a paginated search from the database, falling back to Scryfall. Returns the
+    /// page and the tier that answered.
+    #[allow(clippy::too_many_arguments)]
+    async fn search_paginated_tiers(
+        &self,
+        query: &str,
+        unique: UniqueMode,
+        order: SearchOrder,
+        page: usize,
+        page_size: usize,
+        after: Option<&SearchCursor>,
+        projection: &CardProjection,
+    ) -> ServiceResult<(SearchPage, &'static str)> {
         // For paginated queries, we can't rely on query_cache as easily
         // since it stores all card IDs but pagination happens at query level
         // Instead, we directly use the paginated query executor
 
-        match self
+        let executed = self
             .query_executor
-            .execute_paginated(query, page, page_size)
-            .await
-        {
+            .execute_paginated_projected(query, unique, order, page, page_size, after, projection);
+        match timed("search", "database", executed).await {
             Ok((cards, total)) => {
                 if !cards.is_empty() || total > 0 {
                     CACHE_HITS_TOTAL.with_label_values(&["database"]).inc();
@@ -250,98 +953,174 @@ impl CacheManager {
                         page,
                         total.div_ceil(page_size)
                     );
-                    Ok((cards, total))
+                    let results = SearchPage {
+                        cards,
+                        total,
+                        truncated: false,
+                    };
+                    Ok((results, "database"))
                 } else {
                     CACHE_MISSES_TOTAL.with_label_values(&["database"]).inc();
                     // Query returned no results - fall back to Scryfall API
                     debug!("Local query returned no results, querying Scryfall API");
-                    info!("Querying Scryfall API for: {}", query);
-                    let cards = self.scryfall_client.search_cards(query).await?;
-
-                    if !cards.is_empty() {
-                        CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
-                        // Store cards in database
-                        self.db.insert_cards_batch(&cards).await?;
-                        info!(
-                            "Stored {} cards from Scryfall API for query: {}",
-                            cards.len(),
-                            query
-                        );
-                    } else {
-                        CACHE_MISSES_TOTAL.with_label_values(&["api"]).inc();
-                    }
-
-                    // For Scryfall API fallback, apply pagination in-memory
-                    // since we fetched all results
-                    let total = cards.len();
-                    let start = (page.saturating_sub(1)) * page_size;
-                    let end = (start + page_size).min(total);
-
-                    let paginated_cards = if start < total {
-                        cards[start..end].to_vec()
-                    } else {
-                        Vec::new()
-                    };
-
-                    Ok((paginated_cards, total))
+                    let upstream = self.search_upstream_page(
+                        query, unique, order, page, page_size, after, projection,
+                    );
+                    Ok((upstream.await?, "api"))
                 }
             }
             Err(e) => {
                 CACHE_MISSES_TOTAL.with_label_values(&["database"]).inc();
                 // Query executor failed - fall back to Scryfall API
                 debug!("Query executor error: {}", e);
-                info!("Querying Scryfall API for: {}", query);
-                let cards = self.scryfall_client.search_cards(query).await?;
+                let upstream = self
+                    .search_upstream_page(query, unique, order, page, page_size, after, projection);
+                Ok((upstream.await?, "api"))
+            }
+        }
+    }
 
-                if !cards.is_empty() {
-                    CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
-                    // Store cards in database
-                    self.db.insert_cards_batch(&cards).await?;
-                    info!(
-                        "Stored {} cards from Scryfall API for query: {}",
-                        cards.len(),
-                        query
-                    );
-                } else {
-                    CACHE_MISSES_TOTAL.with_label_values(&["api"]).inc();
-                }
+    /// Fetch a search from Scryfall, store the cards and paginate them in memory
+    #[allow(clippy::too_many_arguments)]
+    async fn search_upstream_page(
+        &self,
+        query: &str,
+        unique: UniqueMode,
+        order: SearchOrder,
+        page: usize,
+        page_size: usize,
+        after: Option<&SearchCursor>,
+        projection: &CardProjection,
+    ) -> ServiceResult<SearchPage> {
+        info!("Querying Scryfall API for: {}", query);
+        // Ask Scryfall for the order too, so a capped fetch keeps the cards that sort first
+        let mut upstream = upstream_query(query, unique);
+        if let Some(keywords) = order.upstream_keywords() {
+            upstream = format!("{} {}", upstream, keywords);
+        }
+        let fetched = self.card_source.search_cards(&upstream);
+        let results = timed("search", "api", fetched).await?;
 
-                // Apply pagination in-memory
-                let total = cards.len();
-                let start = (page.saturating_sub(1)) * page_size;
-                let end = (start + page_size).min(total);
+        if !results.cards.is_empty() {
+            CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
+            // Store cards in database without holding up the response
+            self.store_fetched(results.cards.clone()).await?;
+            info!(
+                "Fetched {} cards from Scryfall API for query: {}",
+                results.cards.len(),
+                query
+            );
+        } else {
+            CACHE_MISSES_TOTAL.with_label_values(&["api"]).inc();
+        }
 
-                let paginated_cards = if start < total {
-                    cards[start..end].to_vec()
-                } else {
-                    Vec::new()
-                };
+        // We fetched every page we are going to, so paginate in memory. When the fetch
+        // was truncated, report Scryfall's total rather than the number of cards fetched;
+        // pages past the fetched cards come back empty.
+        let cards = project_cards(results.cards, projection);
+        let (cards, fetched) = paginate_in_memory(cards, order, page, page_size, after);
+        let total = fetched.max(results.total_cards);
+        Ok(SearchPage {
+            cards,
+            total,
+            truncated: results.truncated,
+        })
+    }
+
+    /// Pick a random locally stored card, optionally matching a Scryfall query
+    pub async fn random_card(&self, query: Option<&str>) -> ServiceResult<Option<Card>> {
+        Ok(self.query_executor.random(query).await?)
+    }
+
+    /// Draw a reproducible sample of up to `n` locally stored cards matching `query` (or of
+    /// every card), with the number of matches. Bypasses the caches and the Scryfall API.
+    pub async fn sample_cards(
+        &self,
+        query: Option<&str>,
+        n: usize,
+        seed: u64,
+    ) -> ServiceResult<(Vec<Card>, usize)> {
+        Ok(self.query_executor.sample(query, n as i64, seed).await?)
+    }
 
-                Ok((paginated_cards, total))
+    /// Get Scryfall reference data that isn't part of the card table (sets, catalogs,
+    /// symbology). Fetched from the API on first use and kept in memory for a day.
+    pub async fn get_reference_data(
+        &self,
+        endpoint: &'static str,
+        path: &str,
+    ) -> ServiceResult<Option<serde_json::Value>> {
+        if let Ok(cache) = self.reference_data.read() {
+            if let Some((fetched_at, value)) = cache.get(path) {
+                if fetched_at.elapsed() < REFERENCE_DATA_TTL {
+                    debug!("Reference data cache hit: {}", path);
+                    return Ok(Some(value.clone()));
+                }
             }
         }
+
+        let value = self.card_source.get_json(endpoint, path).await?;
+        if let (Some(value), Ok(mut cache)) = (&value, self.reference_data.write()) {
+            cache.insert(path.to_string(), (Instant::now(), value.clone()));
+        }
+        Ok(value)
+    }
+
+    /// Stream all locally stored cards matching `query` (or every card) straight from
+    /// the database. Bypasses the caches and the Scryfall API fallback.
+    pub fn export_cards(&self, query: Option<&str>) -> ServiceResult<CardStream> {
+        Ok(self.query_executor.stream(query)?)
+    }
+
+    /// Stream every locally stored card matching a search, deduplicated and ordered like
+    /// `search_paginated`. Reads the database only: no query cache or Scryfall fallback.
+    pub fn stream_search(
+        &self,
+        query: &str,
+        unique: Option<UniqueMode>,
+        order: SearchOrder,
+        limit: Option<i64>,
+    ) -> ServiceResult<CardStream> {
+        self.query_analytics.record(query);
+        let unique = unique.unwrap_or(self.query_executor.default_unique());
+        Ok(self
+            .query_executor
+            .stream_search(query, unique, order, limit)?)
     }
 
     /// Get a card by ID with caching
-    pub async fn get_card(&self, id: Uuid) -> Result<Option<Card>> {
+    pub async fn get_card(&self, id: Uuid) -> ServiceResult<Option<Card>> {
+        self.get_card_projected(id, &CardProjection::ALL).await
+    }
+
+    /// Get a card by ID with caching, reading only the projected columns from the database.
+    /// Cards read with a partial projection are not written back to Redis.
+    pub async fn get_card_projected(
+        &self,
+        id: Uuid,
+        projection: &CardProjection,
+    ) -> ServiceResult<Option<Card>> {
         debug!("Cache get card by ID: {}", id);
 
         // 1. Check Redis cache first (if enabled)
         if let Some(redis) = &self.redis {
-            if let Ok(Some(card)) = redis.get_card(id).await {
+            if let Ok(Some(card)) = timed("get_card", "redis", redis.get_card(id)).await {
                 debug!("Found card in Redis cache: {}", card.name);
                 return Ok(Some(card));
             }
         }
 
         // 2. Check local database
-        if let Ok(Some(card)) = self.db.get_card_by_id(id).await {
+        let lookup = self.db.get_card_by_id_projected(id, projection);
+        if let Ok(Some(card)) = timed("get_card", "database", lookup).await {
             CACHE_HITS_TOTAL.with_label_values(&["database"]).inc();
             debug!("Found card in local database: {}", card.name);
 
-            // Store in Redis for faster access next time
-            if let Some(redis) = &self.redis {
-                redis.set_card(&card).await.ok();
+            // Store in Redis for faster access next time (full rows only)
+            if projection.is_full() {
+                if let Some(redis) = &self.redis {
+                    redis.set_card(&card).await.ok();
+                }
             }
 
             return Ok(Some(card));
@@ -351,11 +1130,16 @@ impl CacheManager {
 
         // 3. Fall back to Scryfall API
         debug!("Card not in database, querying Scryfall API");
-        if let Some(card) = self.scryfall_client.get_card_by_id(id).await? {
+        let fetched = timed("get_card", "api", self.card_source.get_card_by_id(id)).await?;
+        if let Some(card) = fetched {
             CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
 
             // Store in database
-            self.db.insert_cards_batch(&[card.clone()]).await?;
+            self.store_fetched(vec![card.clone()]).await?;
+            events::publish(CacheEvent::CardUpdated {
+                id: card.id,
+                name: card.name.clone(),
+            });
 
             // Store in Redis cache
             if let Some(redis) = &self.redis {
@@ -370,12 +1154,141 @@ impl CacheManager {
         Ok(None)
     }
 
+    /// Cards linked to card `id` through Scryfall's `all_parts` (the tokens it makes, its
+    /// meld pair, combo pieces), resolved like `get_cards_batch` with missing cards fetched
+    /// from Scryfall. `None` if the card doesn't exist.
+    pub async fn get_related_cards(&self, id: Uuid) -> ServiceResult<Option<Vec<RelatedCard>>> {
+        let Some(card) = self.get_card(id).await? else {
+            return Ok(None);
+        };
+
+        // A card just fetched from Scryfall may not have been written back yet
+        let mut parts = self.db.list_card_relations(id).await?;
+        if parts.is_empty() {
+            parts = card.related_parts();
+        }
+        if parts.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let ids: Vec<Uuid> = parts.iter().map(|part| part.id).collect();
+        let (cards, _missing) = self.get_cards_batch(&ids, true).await?;
+        let mut by_id: HashMap<Uuid, Card> = cards.into_iter().map(|c| (c.id, c)).collect();
+        Ok(Some(
+            parts
+                .into_iter()
+                .map(|part| RelatedCard {
+                    card: by_id.remove(&part.id),
+                    part,
+                })
+                .collect(),
+        ))
+    }
+
+    /// Get a printing by set code and collector number, falling back to the Scryfall API
+    pub async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>> {
+        debug!(
+            "Cache get card by set and number: {}/{}",
+            set_code, collector_number
+        );
+
+        let lookup = self
+            .db
+            .get_card_by_set_and_number(set_code, collector_number);
+        if let Some(card) = timed("get_card", "database", lookup).await? {
+            CACHE_HITS_TOTAL.with_label_values(&["database"]).inc();
+            debug!("Found card in local database: {}", card.name);
+            return Ok(Some(card));
+        }
+
+        CACHE_MISSES_TOTAL.with_label_values(&["database"]).inc();
+
+        debug!("Card not in database, querying Scryfall API");
+        let fetched = self
+            .card_source
+            .get_card_by_set_and_number(set_code, collector_number);
+        if let Some(card) = timed("get_card", "api", fetched).await? {
+            CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
+            self.cache_fetched_card(&card).await?;
+            return Ok(Some(card));
+        }
+
+        CACHE_MISSES_TOTAL.with_label_values(&["api"]).inc();
+        Ok(None)
+    }
+
+    /// Get a card by its multiverse, MTG Arena or MTGO id, falling back to the Scryfall API
+    pub async fn get_card_by_external_id(
+        &self,
+        external_id: ExternalId,
+    ) -> ServiceResult<Option<Card>> {
+        debug!("Cache get card by external id: {}", external_id);
+
+        let lookup = self.db.get_card_by_external_id(external_id);
+        if let Some(card) = timed("get_card", "database", lookup).await? {
+            CACHE_HITS_TOTAL.with_label_values(&["database"]).inc();
+            debug!("Found card in local database: {}", card.name);
+            return Ok(Some(card));
+        }
+
+        CACHE_MISSES_TOTAL.with_label_values(&["database"]).inc();
+
+        debug!("Card not in database, querying Scryfall API");
+        let fetched = self.card_source.get_card_by_external_id(external_id);
+        if let Some(card) = timed("get_card", "api", fetched).await? {
+            CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
+            self.cache_fetched_card(&card).await?;
+            return Ok(Some(card));
+        }
+
+        CACHE_MISSES_TOTAL.with_label_values(&["api"]).inc();
+        Ok(None)
+    }
+
+    /// Store a card fetched by a single-card API lookup and announce it
+    async fn cache_fetched_card(&self, card: &Card) -> ServiceResult<()> {
+        self.store_fetched(vec![card.clone()]).await?;
+        events::publish(CacheEvent::CardUpdated {
+            id: card.id,
+            name: card.name.clone(),
+        });
+        if let Some(redis) = &self.redis {
+            redis.set_card(card).await.ok();
+        }
+        info!("Fetched and cached card from Scryfall: {}", card.name);
+        Ok(())
+    }
+
     /// Search by card name with caching
-    pub async fn search_by_name(&self, name: &str, fuzzy: bool) -> Result<Option<Card>> {
-        debug!("Cache search by name: {} (fuzzy={})", name, fuzzy);
+    pub async fn search_by_name(&self, name: &str, fuzzy: bool) -> ServiceResult<Option<Card>> {
+        self.search_by_name_with(name, fuzzy, None, false).await
+    }
 
-        // Try local database first
-        let cards = self.db.search_cards_by_name(name, 1).await?;
+    /// Same as `search_by_name`, but with `lang` returns the printing in that language
+    /// (matching its localized printed name as well as the English name), and with
+    /// `include_tokens` lets tokens and emblems match
+    pub async fn search_by_name_with(
+        &self,
+        name: &str,
+        fuzzy: bool,
+        lang: Option<&str>,
+        include_tokens: bool,
+    ) -> ServiceResult<Option<Card>> {
+        debug!(
+            "Cache search by name: {} (fuzzy={}, lang={:?}, include_tokens={})",
+            name, fuzzy, lang, include_tokens
+        );
+
+        // Try local database first. Split cards are found by their full name or by either
+        // half, like on Scryfall (`Fire`, `Fire // Ice` and `Fire//Ice` all match).
+        let cards = self
+            .db
+            .search_cards_by_name(&normalize_card_name(name), lang, include_tokens, 1)
+            .await?;
         if let Some(card) = cards.first() {
             CACHE_HITS_TOTAL.with_label_values(&["database"]).inc();
             debug!("Found card in local database: {}", card.name);
@@ -386,10 +1299,23 @@ impl CacheManager {
 
         // Fall back to Scryfall API
         debug!("Card not in database, querying Scryfall API");
-        if let Some(card) = self.scryfall_client.get_card_by_name(name, fuzzy).await? {
+        let fetched = match lang {
+            Some(lang) => {
+                self.card_source
+                    .get_card_by_name_localized(name, fuzzy, lang)
+                    .await?
+            }
+            None => self.card_source.get_card_by_name(name, fuzzy).await?,
+        };
+        let fetched = fetched.filter(|card| include_tokens || !card.is_token_or_emblem());
+        if let Some(card) = fetched {
             CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
             // Store in database
-            self.db.insert_cards_batch(&[card.clone()]).await?;
+            self.store_fetched(vec![card.clone()]).await?;
+            events::publish(CacheEvent::CardUpdated {
+                id: card.id,
+                name: card.name.clone(),
+            });
             info!("Fetched and cached card from Scryfall: {}", card.name);
             return Ok(Some(card));
         }
@@ -398,10 +1324,128 @@ impl CacheManager {
         Ok(None)
     }
 
+    /// Resolve many card names at once, returning one entry per name in request order.
+    /// - Looks each name up in the local DB, `parallelism` at a time.
+    /// - Resolves the misses with chunked /cards/collection requests and stores them.
+    /// - Falls back to per-name /cards/named lookups for names the collection endpoint
+    ///   could not resolve when `fuzzy` is set (it only matches exact names), or when the
+    ///   collection request itself failed.
+    pub async fn search_by_names_batch(
+        &self,
+        names: &[String],
+        fuzzy: bool,
+        parallelism: usize,
+    ) -> ServiceResult<Vec<Option<Card>>> {
+        let local: Vec<(usize, ServiceResult<Vec<Card>>)> =
+            futures::stream::iter(names.iter().enumerate())
+                .map(|(idx, name)| async move {
+                    (
+                        idx,
+                        self.db
+                            .search_cards_by_name(&normalize_card_name(name), None, false, 1)
+                            .await,
+                    )
+                })
+                .buffer_unordered(parallelism)
+                .collect()
+                .await;
+
+        let mut results: Vec<Option<Card>> = vec![None; names.len()];
+        for (idx, cards) in local {
+            results[idx] = cards?.into_iter().next();
+        }
+
+        let hits = results.iter().filter(|card| card.is_some()).count() as u64;
+        CACHE_HITS_TOTAL
+            .with_label_values(&["database"])
+            .inc_by(hits);
+        CACHE_MISSES_TOTAL
+            .with_label_values(&["database"])
+            .inc_by(names.len() as u64 - hits);
+
+        let mut missing: Vec<String> = Vec::new();
+        for (name, card) in names.iter().zip(&results) {
+            if card.is_none() && !missing.iter().any(|m| m.eq_ignore_ascii_case(name)) {
+                missing.push(name.clone());
+            }
+        }
+        if missing.is_empty() {
+            return Ok(results);
+        }
+
+        debug!(
+            "Resolving {} card names via Scryfall /cards/collection",
+            missing.len()
+        );
+        let collection_failed = match self
+            .card_source
+            .get_cards_by_names_collection(&missing)
+            .await
+        {
+            Ok(fetched) => {
+                let fetched: Vec<Card> = fetched
+                    .into_iter()
+                    .filter(|card| !card.is_token_or_emblem())
+                    .collect();
+                if fetched.is_empty() {
+                    CACHE_MISSES_TOTAL.with_label_values(&["api"]).inc();
+                } else {
+                    CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
+                    self.store_fetched(fetched.clone()).await?;
+                    for card in &fetched {
+                        events::publish(CacheEvent::CardUpdated {
+                            id: card.id,
+                            name: card.name.clone(),
+                        });
+                    }
+                }
+                for (name, slot) in names.iter().zip(results.iter_mut()) {
+                    if slot.is_none() {
+                        *slot = fetched.iter().find(|card| card.matches_name(name)).cloned();
+                    }
+                }
+                false
+            }
+            Err(e) => {
+                warn!("Scryfall collection lookup by name failed: {}", e);
+                true
+            }
+        };
+
+        if fuzzy || collection_failed {
+            let unresolved: Vec<usize> = (0..names.len())
+                .filter(|&idx| results[idx].is_none())
+                .collect();
+            let fallback: Vec<(usize, Option<Card>)> = futures::stream::iter(unresolved)
+                .map(|idx| async move {
+                    let card = match self.search_by_name(&names[idx], fuzzy).await {
+                        Ok(card) => card,
+                        Err(e) => {
+                            error!("Batch named lookup failed: {}", e);
+                            None
+                        }
+                    };
+                    (idx, card)
+                })
+                .buffer_unordered(parallelism)
+                .collect()
+                .await;
+            for (idx, card) in fallback {
+                results[idx] = card;
+            }
+        }
+
+        Ok(results)
+    }
+
     /// Autocomplete card names by prefix (case-insensitive)
-    /// Returns up to 20 card names that start with the given prefix
-    pub async fn autocomplete(&self, prefix: &str) -> Result<Vec<String>> {
-        debug!("Autocomplete request: prefix='{}'", prefix);
+    /// Returns up to `options.limit` card names that start with the given prefix
+    pub async fn autocomplete(
+        &self,
+        prefix: &str,
+        options: &AutocompleteOptions,
+    ) -> ServiceResult<Vec<String>> {
+        debug!("Autocomplete request: prefix='{}' ({:?})", prefix, options);
 
         if prefix.len() < 2 {
             // Don't autocomplete for very short queries to avoid returning too many results
@@ -409,19 +1453,20 @@ impl CacheManager {
         }
 
         // 1. Check Redis cache first (if enabled)
+        let cache_key = options.cache_key(prefix);
         if let Some(redis) = &self.redis {
-            if let Ok(Some(names)) = redis.get_autocomplete(prefix).await {
+            if let Ok(Some(names)) = redis.get_autocomplete(&cache_key).await {
                 debug!("Autocomplete Redis cache hit for prefix '{}'", prefix);
                 return Ok(names);
             }
         }
 
         // 2. Query the database for matching card names
-        let names = self.db.autocomplete_card_names(prefix, 20).await?;
+        let names = self.db.autocomplete_card_names(prefix, options).await?;
 
         // Store in Redis for faster access next time
         if let Some(redis) = &self.redis {
-            redis.set_autocomplete(prefix, &names).await.ok();
+            redis.set_autocomplete(&cache_key, &names).await.ok();
         }
 
         info!(
@@ -433,22 +1478,63 @@ impl CacheManager {
     }
 
     /// Fetch multiple cards by IDs in one call.
-    /// - Reads from the local DB first.
+    /// - Reads from Redis (one MGET per chunk), then the local DB for the rest.
     /// - Optionally fetches missing cards from Scryfall using /cards/collection (chunked) and stores them.
     /// Returns (cards_in_request_order, missing_ids_unique).
     pub async fn get_cards_batch(
         &self,
         ids: &[Uuid],
         fetch_missing: bool,
-    ) -> Result<(Vec<Card>, Vec<Uuid>)> {
+    ) -> ServiceResult<(Vec<Card>, Vec<Uuid>)> {
+        self.get_cards_batch_projected(ids, fetch_missing, &CardProjection::ALL)
+            .await
+    }
+
+    /// Same as `get_cards_batch`, but reads only the projected columns from the database.
+    /// Cards fetched from Scryfall are always complete.
+    pub async fn get_cards_batch_projected(
+        &self,
+        ids: &[Uuid],
+        fetch_missing: bool,
+        projection: &CardProjection,
+    ) -> ServiceResult<(Vec<Card>, Vec<Uuid>)> {
         if ids.is_empty() {
             return Ok((Vec::new(), Vec::new()));
         }
 
-        let found_cards = self.db.get_cards_by_ids(ids).await?;
-        let mut by_id = std::collections::HashMap::with_capacity(found_cards.len());
-        for card in found_cards {
-            by_id.insert(card.id, card);
+        let mut by_id = std::collections::HashMap::with_capacity(ids.len());
+        if let Some(redis) = &self.redis {
+            match redis.get_cards(ids).await {
+                Ok(cards) => {
+                    for mut card in cards {
+                        projection.apply(&mut card);
+                        by_id.insert(card.id, card);
+                    }
+                }
+                Err(e) => warn!("Redis batch card lookup failed: {}", e),
+            }
+        }
+
+        let uncached: Vec<Uuid> = ids
+            .iter()
+            .filter(|id| !by_id.contains_key(*id))
+            .copied()
+            .collect();
+        if !uncached.is_empty() {
+            let found_cards = self
+                .db
+                .get_cards_by_ids_projected(&uncached, projection)
+                .await?;
+            // Only complete cards go into Redis, since cached cards are served to any
+            // projection
+            if let Some(redis) = self.redis.as_ref().filter(|_| projection.is_full()) {
+                if !found_cards.is_empty() {
+                    redis.set_cards(&found_cards).await.ok();
+                }
+            }
+            for card in found_cards {
+                by_id.insert(card.id, card);
+            }
         }
 
         let mut missing_ids = Vec::new();
@@ -467,13 +1553,13 @@ impl CacheManager {
 
         if fetch_missing && !missing_ids.is_empty() {
             let fetched = self
-                .scryfall_client
+                .card_source
                 .get_cards_by_ids_collection(&missing_ids)
                 .await?;
 
             if !fetched.is_empty() {
                 CACHE_HITS_TOTAL.with_label_values(&["api"]).inc();
-                self.db.insert_cards_batch(&fetched).await?;
+                self.store_fetched(fetched.clone()).await?;
                 for card in fetched {
                     by_id.insert(card.id, card);
                 }
@@ -497,22 +1583,232 @@ impl CacheManager {
     }
 
     /// Get cache statistics
-    pub async fn get_stats(&self) -> Result<CacheStats> {
+    pub async fn get_stats(&self) -> ServiceResult<CacheStats> {
         let total_cards = self.db.get_card_count().await?;
         let total_cache_entries = self.db.get_cache_entry_count().await?;
+        let total_cache_hits = self.db.get_query_cache_hit_count().await?;
+
+        let bulk_import = BulkImportStats {
+            loaded: self.db.check_bulk_data_loaded().await?,
+            last_import: self.db.get_last_bulk_import().await?,
+        };
+
+        let redis = match &self.redis {
+            Some(redis) => match redis.get_stats().await {
+                Ok(stats) => Some(stats),
+                Err(e) => {
+                    warn!("Failed to read Redis stats: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let tiers = CACHE_TIERS
+            .iter()
+            .map(|tier| {
+                TierStats::new(
+                    tier,
+                    CACHE_HITS_TOTAL.with_label_values(&[*tier]).get(),
+                    CACHE_MISSES_TOTAL.with_label_values(&[*tier]).get(),
+                )
+            })
+            .collect();
 
         Ok(CacheStats {
             total_cards,
             total_cache_entries,
+            total_cache_hits,
+            redis,
+            tiers,
+            bulk_import,
+            uptime_seconds: self.started_at.elapsed().as_secs(),
+            cache_generation: self.generation(),
         })
     }
+
+    /// Update the card count, cached query count and cache size gauges. Each is read
+    /// separately, so one failing source leaves only its own gauge stale.
+    pub async fn refresh_metrics(&self) {
+        match self.db.get_card_count().await {
+            Ok(count) => CARDS_TOTAL.set(count),
+            Err(e) => warn!("Failed to count cards for metrics: {}", e),
+        }
+        match self.db.get_cache_entry_count().await {
+            Ok(count) => QUERIES_CACHED_TOTAL.set(count),
+            Err(e) => warn!("Failed to count query cache entries for metrics: {}", e),
+        }
+        match self.db.get_database_size_bytes().await {
+            Ok(bytes) => CACHE_SIZE_BYTES.with_label_values(&["database"]).set(bytes),
+            Err(e) => warn!("Failed to read database size for metrics: {}", e),
+        }
+        if let Some(redis) = &self.redis {
+            match redis.memory_used_bytes().await {
+                Ok(bytes) => CACHE_SIZE_BYTES
+                    .with_label_values(&["redis"])
+                    .set(bytes as i64),
+                Err(e) => warn!("Failed to read Redis memory usage for metrics: {}", e),
+            }
+        }
+    }
+}
+
+/// Record a search's outcome: the tenant's search counters, the request span and the
+/// total duration by the tier that answered (`error` if it failed)
+fn record_search<T>(query: &str, result: &ServiceResult<(T, &'static str)>, start: Instant) {
+    let tier = result.as_ref().map_or("error", |(_, tier)| *tier);
+    tenant::record_search(tier);
+    // Fill in the request span opened by the logging middleware; with several searches
+    // per request (batch queries, GraphQL) the last one wins
+    let span = Span::current();
+    span.record("query_hash", hash_query(query).as_str());
+    span.record("cache_tier", tier);
+    SEARCH_TOTAL_DURATION_SECONDS
+        .with_label_values(&[tier])
+        .observe(start.elapsed().as_secs_f64());
+}
+
+/// Await a single cache tier lookup, recording its latency under `operation` and `tier`
+async fn timed<F: Future>(operation: &str, tier: &str, lookup: F) -> F::Output {
+    let _timer = CACHE_TIER_DURATION_SECONDS
+        .with_label_values(&[operation, tier])
+        .start_timer();
+    lookup.await
+}
+
+/// Blank the columns `projection` omits on cards fetched in full from Scryfall, so
+/// fallback results look like the ones read from the database
+fn project_cards(mut cards: Vec<Card>, projection: &CardProjection) -> Vec<Card> {
+    if !projection.is_full() {
+        for card in &mut cards {
+            projection.apply(card);
+        }
+    }
+    cards
+}
+
+/// Search sent to Scryfall for a local `query`. Scryfall deduplicates by card unless told
+/// otherwise, so the mode is always sent; the query is parenthesized so the keyword
+/// applies to every `or` branch.
+fn upstream_query(query: &str, unique: UniqueMode) -> String {
+    format!("({}) unique:{}", query, unique.as_str())
+}
+
+/// Paginate a fully-fetched result set (Scryfall fallback), mirroring the
+/// ordering and cursor semantics of the local query executor.
+/// Returns the requested page and the total number of results.
+fn paginate_in_memory(
+    mut cards: Vec<Card>,
+    order: SearchOrder,
+    page: usize,
+    page_size: usize,
+    after: Option<&SearchCursor>,
+) -> (Vec<Card>, usize) {
+    let total = cards.len();
+
+    if order != SearchOrder::default() {
+        cards.sort_by(|a, b| order.compare(a, b));
+    }
+
+    if let Some(cursor) = after {
+        cards.sort_by(|a, b| order.compare(a, b));
+        let paginated_cards = cards
+            .into_iter()
+            .filter(|card| cursor.is_before(card))
+            .take(page_size)
+            .collect();
+        return (paginated_cards, total);
+    }
+
+    let start = (page.saturating_sub(1)) * page_size;
+    let end = (start + page_size).min(total);
+
+    let paginated_cards = if start < total {
+        cards[start..end].to_vec()
+    } else {
+        Vec::new()
+    };
+
+    (paginated_cards, total)
 }
 
 /// Cache statistics
-#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct CacheStats {
     /// Total number of cards in the database
     pub total_cards: i64,
     /// Total number of cached query results
     pub total_cache_entries: i64,
+    /// Total number of hits served from the database query cache
+    pub total_cache_hits: i64,
+    /// Redis keyspace hits and misses; absent when Redis is not configured or unreachable
+    pub redis: Option<RedisStats>,
+    /// Hits and misses per cache tier since startup, in lookup order
+    pub tiers: Vec<TierStats>,
+    /// Bulk data import status
+    pub bulk_import: BulkImportStats,
+    /// Seconds since the service started
+    pub uptime_seconds: u64,
+    /// Bulk reloads that have invalidated the caches since startup
+    pub cache_generation: u64,
+}
+
+/// Cache tiers reported in `CacheStats::tiers`, in the order lookups try them
+const CACHE_TIERS: &[&str] = &["query_cache", "redis", "database", "api"];
+
+/// Hit/miss counters of one cache tier
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct TierStats {
+    /// Tier name (`query_cache`, `redis`, `database` or `api`)
+    pub tier: String,
+    pub hits: u64,
+    pub misses: u64,
+    /// Hits as a percentage of lookups (0 when there were none)
+    pub hit_rate: f64,
+}
+
+impl TierStats {
+    fn new(tier: &str, hits: u64, misses: u64) -> Self {
+        Self {
+            tier: tier.to_string(),
+            hits,
+            misses,
+            hit_rate: hit_rate(hits, misses),
+        }
+    }
+}
+
+/// Bulk data import status
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct BulkImportStats {
+    /// Whether the database holds any cards
+    pub loaded: bool,
+    /// When the last bulk import finished
+    pub last_import: Option<chrono::NaiveDateTime>,
+}
+
+/// `hits` as a percentage of `hits + misses`
+pub fn hit_rate(hits: u64, misses: u64) -> f64 {
+    let total = hits + misses;
+    if total > 0 {
+        (hits as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tier_stats() {
+        assert_eq!(hit_rate(0, 0), 0.0);
+        assert_eq!(hit_rate(3, 1), 75.0);
+
+        let stats = TierStats::new("redis", 1, 3);
+        assert_eq!(stats.tier, "redis");
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+        assert_eq!(stats.hit_rate, 25.0);
+    }
 }
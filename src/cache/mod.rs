@@ -1,2 +1,6 @@
+pub mod analytics;
+pub mod codec;
 pub mod manager;
+pub mod policy;
 pub mod redis;
+pub mod warmup;
@@ -0,0 +1,254 @@
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+
+use crate::query::parser::QueryNode;
+use crate::query::QueryParser;
+
+/// Fields whose results depend on card prices, which change daily
+const PRICE_FIELDS: &[&str] = &["usd", "eur", "tix"];
+
+/// Fields matching rules text, which only changes with errata
+const ORACLE_TEXT_FIELDS: &[&str] = &["name", "oracle", "oracle_text", "type", "type_line"];
+
+/// Class of a search query, used to pick its caching policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryClass {
+    /// Only name filters (e.g. `name:bolt`)
+    Name,
+    /// Only name/oracle text/type line filters (e.g. `o:draw t:instant`)
+    OracleText,
+    /// Any price filter (e.g. `usd:<1`)
+    Price,
+    /// Everything else
+    Search,
+}
+
+impl QueryClass {
+    pub const ALL: [QueryClass; 4] = [
+        QueryClass::Name,
+        QueryClass::OracleText,
+        QueryClass::Price,
+        QueryClass::Search,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueryClass::Name => "name",
+            QueryClass::OracleText => "oracle_text",
+            QueryClass::Price => "price",
+            QueryClass::Search => "search",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|class| class.as_str() == value)
+    }
+
+    /// Classify a parsed query from the fields it filters on
+    pub fn classify(ast: &QueryNode) -> Self {
+        let mut fields = Vec::new();
+        collect_fields(ast, &mut fields);
+
+        if fields.iter().any(|f| PRICE_FIELDS.contains(f)) {
+            QueryClass::Price
+        } else if fields.iter().all(|f| *f == "name") {
+            QueryClass::Name
+        } else if fields.iter().all(|f| ORACLE_TEXT_FIELDS.contains(f)) {
+            QueryClass::OracleText
+        } else {
+            QueryClass::Search
+        }
+    }
+
+    /// Classify a raw query string. Queries that don't parse are treated as broad searches.
+    pub fn classify_query(query: &str) -> Self {
+        QueryParser::parse(query)
+            .map(|ast| Self::classify(&ast))
+            .unwrap_or(QueryClass::Search)
+    }
+}
+
+fn collect_fields<'a>(node: &'a QueryNode, fields: &mut Vec<&'a str>) {
+    match node {
+        QueryNode::And(children) | QueryNode::Or(children) => {
+            for child in children {
+                collect_fields(child, fields);
+            }
+        }
+        QueryNode::Not(child) => collect_fields(child, fields),
+        QueryNode::Filter(filter) => fields.push(filter.field.as_str()),
+    }
+}
+
+/// TTL and cache tiers used for one query class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    pub ttl: Duration,
+    /// Store results in Redis (when Redis is enabled)
+    pub redis: bool,
+    /// Store results in the database query cache
+    pub database: bool,
+}
+
+impl CachePolicy {
+    /// TTL for the database query cache, which has hour granularity
+    pub fn ttl_hours(&self) -> i32 {
+        self.ttl.as_secs().div_ceil(3600).max(1) as i32
+    }
+
+    /// Parse `<ttl>[:<tiers>]`, e.g. `15m:redis`, `3d:redis+db` or `1h`
+    fn parse(spec: &str) -> Result<Self> {
+        let (ttl, tiers) = spec.split_once(':').unwrap_or((spec, "redis+db"));
+        let ttl = parse_duration(ttl.trim())?;
+
+        let mut policy = CachePolicy {
+            ttl,
+            redis: false,
+            database: false,
+        };
+        for tier in tiers.split('+').map(str::trim) {
+            match tier {
+                "redis" => policy.redis = true,
+                "db" | "database" => policy.database = true,
+                "none" => {}
+                other => bail!(
+                    "unknown cache tier '{}' (expected redis, db or none)",
+                    other
+                ),
+            }
+        }
+        Ok(policy)
+    }
+}
+
+/// Parse a duration like `30s`, `15m`, `24h` or `7d`
+fn parse_duration(value: &str) -> Result<Duration> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .with_context(|| format!("invalid cache TTL '{}'", value))?;
+
+    let seconds = match unit {
+        "s" => amount,
+        "m" => amount * 60,
+        "h" | "" => amount * 3600,
+        "d" => amount * 86_400,
+        _ => bail!(
+            "invalid cache TTL unit in '{}' (expected s, m, h or d)",
+            value
+        ),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Caching policy for each query class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachePolicyTable {
+    pub name: CachePolicy,
+    pub oracle_text: CachePolicy,
+    pub price: CachePolicy,
+    pub search: CachePolicy,
+}
+
+impl CachePolicyTable {
+    /// Default policies: rules text rarely changes so caches for days, prices change daily
+    /// so only live briefly in Redis, and broad searches use `search_ttl_hours`.
+    pub fn with_search_ttl(search_ttl_hours: u32) -> Self {
+        let both = |ttl| CachePolicy {
+            ttl,
+            redis: true,
+            database: true,
+        };
+
+        Self {
+            name: both(Duration::from_secs(7 * 86_400)),
+            oracle_text: both(Duration::from_secs(3 * 86_400)),
+            price: CachePolicy {
+                ttl: Duration::from_secs(15 * 60),
+                redis: true,
+                database: false,
+            },
+            search: both(Duration::from_secs(search_ttl_hours as u64 * 3600)),
+        }
+    }
+
+    /// Apply overrides from a comma-separated list of `<class>=<ttl>[:<tiers>]`,
+    /// e.g. `price=5m:redis,oracle_text=7d`
+    pub fn with_overrides(mut self, spec: &str) -> Result<Self> {
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (class, policy) = entry
+                .split_once('=')
+                .with_context(|| format!("invalid cache policy '{}'", entry))?;
+            let class = QueryClass::parse(class.trim())
+                .with_context(|| format!("unknown query class '{}'", class.trim()))?;
+            let policy = CachePolicy::parse(policy)
+                .with_context(|| format!("invalid cache policy for '{}'", class.as_str()))?;
+            *self.get_mut(class) = policy;
+        }
+        Ok(self)
+    }
+
+    pub fn get(&self, class: QueryClass) -> CachePolicy {
+        match class {
+            QueryClass::Name => self.name,
+            QueryClass::OracleText => self.oracle_text,
+            QueryClass::Price => self.price,
+            QueryClass::Search => self.search,
+        }
+    }
+
+    fn get_mut(&mut self, class: QueryClass) -> &mut CachePolicy {
+        match class {
+            QueryClass::Name => &mut self.name,
+            QueryClass::OracleText => &mut self.oracle_text,
+            QueryClass::Price => &mut self.price,
+            QueryClass::Search => &mut self.search,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_queries() {
+        assert_eq!(QueryClass::classify_query("name:bolt"), QueryClass::Name);
+        assert_eq!(QueryClass::classify_query("bolt"), QueryClass::Name);
+        assert_eq!(
+            QueryClass::classify_query("o:draw t:instant"),
+            QueryClass::OracleText
+        );
+        assert_eq!(
+            QueryClass::classify_query("t:creature usd:<1"),
+            QueryClass::Price
+        );
+        assert_eq!(
+            QueryClass::classify_query("c:red cmc:<=2"),
+            QueryClass::Search
+        );
+    }
+
+    #[test]
+    fn test_policy_overrides() {
+        let table = CachePolicyTable::with_search_ttl(24)
+            .with_overrides("price=5m:none, oracle_text=7d:db")
+            .unwrap();
+
+        assert_eq!(table.price.ttl, Duration::from_secs(300));
+        assert!(!table.price.redis && !table.price.database);
+        assert_eq!(table.oracle_text.ttl_hours(), 7 * 24);
+        assert!(!table.oracle_text.redis && table.oracle_text.database);
+        assert_eq!(table.search.ttl_hours(), 24);
+
+        assert!(CachePolicyTable::with_search_ttl(24)
+            .with_overrides("prices=5m")
+            .is_err());
+        assert!(CachePolicyTable::with_search_ttl(24)
+            .with_overrides("price=5w")
+            .is_err());
+    }
+}
@@ -4,10 +4,15 @@ use std::time::Duration;
 use tracing::{debug, error, warn};
 use uuid::Uuid;
 
+use crate::cache::codec::{CacheCompression, CacheEncoding};
 use crate::metrics::registry::{CACHE_HITS_TOTAL, CACHE_MISSES_TOTAL};
 use crate::models::card::Card;
-use crate::utils::hash::hash_query;
+use crate::models::idempotency::IdempotencyRecord;
 
+#[cfg(feature = "redis_cache")]
+use crate::cache::codec;
+#[cfg(feature = "redis_cache")]
+use crate::metrics::registry::REDIS_ENTRY_SIZE_BYTES;
 #[cfg(feature = "redis_cache")]
 use redis::{aio::ConnectionManager, AsyncCommands, Client};
 
@@ -15,16 +20,46 @@ use redis::{aio::ConnectionManager, AsyncCommands, Client};
 #[derive(Debug, Clone)]
 pub struct RedisConfig {
     pub url: String,
-    pub ttl_seconds: u64,
+    /// Namespace prepended to every key (`staging` -> `staging:card:<id>`), so several
+    /// environments can share one Redis instance. Empty for unprefixed keys.
+    pub key_prefix: String,
+    /// TTL of cached cards
+    pub card_ttl_seconds: u64,
+    /// Upper bound on the TTL of cached query results, which otherwise follow the query's
+    /// cache policy
+    pub query_ttl_seconds: Option<u64>,
+    /// TTL of cached autocomplete results
+    pub autocomplete_ttl_seconds: u64,
     pub max_value_size_mb: usize,
+    /// Serialization format of new entries; entries in any format are read
+    pub encoding: CacheEncoding,
+    /// Compression of new entries
+    pub compression: CacheCompression,
 }
 
 impl Default for RedisConfig {
     fn default() -> Self {
         Self {
             url: "redis://localhost:6379".to_string(),
-            ttl_seconds: 3600, // 1 hour
+            key_prefix: String::new(),
+            card_ttl_seconds: 3600, // 1 hour
+            query_ttl_seconds: None,
+            autocomplete_ttl_seconds: 600, // 10 minutes
             max_value_size_mb: 10,
+            encoding: CacheEncoding::Json,
+            compression: CacheCompression::None,
+        }
+    }
+}
+
+#[cfg(feature = "redis_cache")]
+impl RedisConfig {
+    /// Full key for `id` in a key class (`card`, `query`, `autocomplete`)
+    fn key(&self, class: &str, id: impl std::fmt::Display) -> String {
+        if self.key_prefix.is_empty() {
+            format!("{}:{}", class, id)
+        } else {
+            format!("{}:{}:{}", self.key_prefix, class, id)
         }
     }
 }
@@ -45,8 +80,17 @@ impl<T> CacheEntry<T> {
     }
 }
 
+/// Keys per MGET / pipeline / UNLINK in the multi-key operations
+#[cfg(feature = "redis_cache")]
+const BATCH_CHUNK_SIZE: usize = 500;
+
+/// Classes of keys this cache writes, each under `<prefix>:<class>:`
+#[cfg(feature = "redis_cache")]
+const KEY_CLASSES: &[&str] = &["card", "query", "autocomplete"];
+
 /// Redis cache client wrapper
 #[cfg(feature = "redis_cache")]
+#[derive(Clone)]
 pub struct RedisCache {
     client: ConnectionManager,
     config: RedisConfig,
@@ -81,19 +125,23 @@ impl RedisCache {
         Ok(())
     }
 
-    /// Get query results from cache
-    pub async fn get_query_results(&self, query: &str) -> Result<Option<Vec<Uuid>>> {
-        let key = format!("query:{}", hash_query(query));
+    /// Get query results from cache by query cache key (see `tenant::query_cache_key`)
+    pub async fn get_query_results(&self, query_hash: &str) -> Result<Option<Vec<Uuid>>> {
+        let key = self.config.key("query", query_hash);
 
         match self.get_value::<Vec<Uuid>>(&key).await {
             Ok(Some(card_ids)) => {
                 CACHE_HITS_TOTAL.with_label_values(&["redis"]).inc();
-                debug!("Redis cache hit for query: {} ({} IDs)", query, card_ids.len());
+                debug!(
+                    "Redis cache hit for query {} ({} IDs)",
+                    query_hash,
+                    card_ids.len()
+                );
                 Ok(Some(card_ids))
             }
             Ok(None) => {
                 CACHE_MISSES_TOTAL.with_label_values(&["redis"]).inc();
-                debug!("Redis cache miss for query: {}", query);
+                debug!("Redis cache miss for query {}", query_hash);
                 Ok(None)
             }
             Err(e) => {
@@ -104,16 +152,43 @@ impl RedisCache {
         }
     }
 
-    /// Store query results in cache
-    pub async fn set_query_results(&self, query: &str, card_ids: &[Uuid]) -> Result<()> {
-        let key = format!("query:{}", hash_query(query));
+    /// Store query results under a query cache key for `ttl`
+    pub async fn set_query_results(
+        &self,
+        query_hash: &str,
+        card_ids: &[Uuid],
+        ttl: Duration,
+    ) -> Result<()> {
+        let key = self.config.key("query", query_hash);
         let card_ids_vec: Vec<Uuid> = card_ids.to_vec();
-        self.set_value(&key, &card_ids_vec, Some(self.config.ttl_seconds)).await
+        let ttl = match self.config.query_ttl_seconds {
+            Some(max) => ttl.as_secs().min(max),
+            None => ttl.as_secs(),
+        };
+        self.set_value("query", &key, &card_ids_vec, Some(ttl.max(1)))
+            .await
+    }
+
+    /// Remove cached results for one query by its hash
+    pub async fn delete_query_results(&self, query_hash: &str) -> Result<bool> {
+        let mut conn = self.client.clone();
+        let deleted: u64 = conn
+            .del(self.config.key("query", query_hash))
+            .await
+            .context("Failed to delete query results from Redis")?;
+        Ok(deleted > 0)
+    }
+
+    /// Remove all cached query results, leaving cards and autocomplete entries in place
+    pub async fn clear_query_results(&self) -> Result<u64> {
+        let deleted = self.unlink_matching(&self.config.key("query", "*")).await?;
+        debug!("Removed {} cached queries from Redis", deleted);
+        Ok(deleted)
     }
 
     /// Get a card by ID
     pub async fn get_card(&self, id: Uuid) -> Result<Option<Card>> {
-        let key = format!("card:{}", id);
+        let key = self.config.key("card", id);
 
         match self.get_value::<Card>(&key).await {
             Ok(Some(card)) => {
@@ -135,60 +210,284 @@ impl RedisCache {
 
     /// Store a card in cache
     pub async fn set_card(&self, card: &Card) -> Result<()> {
-        let key = format!("card:{}", card.id);
-        self.set_value(&key, card, Some(self.config.ttl_seconds)).await
+        let key = self.config.key("card", card.id);
+        self.set_value("card", &key, card, Some(self.config.card_ttl_seconds))
+            .await
     }
 
-    /// Get multiple cards by IDs
+    /// Get multiple cards by IDs with one MGET per chunk of keys.
+    /// Cards that are missing or fail to deserialize are left out.
     pub async fn get_cards(&self, ids: &[Uuid]) -> Result<Vec<Card>> {
-        let mut cards = Vec::new();
-
-        for id in ids {
-            if let Some(card) = self.get_card(*id).await? {
-                cards.push(card);
+        let mut conn = self.client.clone();
+        let mut cards = Vec::with_capacity(ids.len());
+
+        for chunk in ids.chunks(BATCH_CHUNK_SIZE) {
+            let keys: Vec<String> = chunk.iter().map(|id| self.config.key("card", id)).collect();
+            // MGET always replies with an array, even for a single key
+            let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET")
+                .arg(&keys)
+                .query_async(&mut conn)
+                .await
+                .context("Failed to get cards from Redis")?;
+
+            for (key, value) in keys.iter().zip(values) {
+                let Some(bytes) = value else { continue };
+                match codec::decode::<CacheEntry<Card>>(&bytes) {
+                    Ok(entry) => cards.push(entry.data),
+                    Err(e) => warn!("Failed to deserialize cached {}: {}", key, e),
+                }
             }
         }
 
+        let hits = cards.len() as u64;
+        CACHE_HITS_TOTAL.with_label_values(&["redis"]).inc_by(hits);
+        CACHE_MISSES_TOTAL
+            .with_label_values(&["redis"])
+            .inc_by(ids.len() as u64 - hits);
+        debug!("Redis cache hit for {} of {} cards", hits, ids.len());
+
         Ok(cards)
     }
 
-    /// Store multiple cards in cache
+    /// Store multiple cards in cache with one pipelined round trip per chunk
     pub async fn set_cards(&self, cards: &[Card]) -> Result<()> {
-        for card in cards {
-            // Best effort - don't fail if one card fails to cache
-            if let Err(e) = self.set_card(card).await {
-                warn!("Failed to cache card {}: {}", card.id, e);
+        let mut conn = self.client.clone();
+
+        for chunk in cards.chunks(BATCH_CHUNK_SIZE) {
+            let mut pipe = redis::pipe();
+            for card in chunk {
+                let key = self.config.key("card", card.id);
+                // Best effort - skip cards that can't be encoded
+                match self.encode("card", &key, card) {
+                    Ok(Some(bytes)) => {
+                        pipe.set_ex(key, bytes, self.config.card_ttl_seconds)
+                            .ignore();
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("Failed to cache card {}: {}", card.id, e),
+                }
             }
+            pipe.query_async::<_, ()>(&mut conn)
+                .await
+                .context("Failed to store cards in Redis")?;
         }
+
         Ok(())
     }
 
-    /// Get autocomplete results
-    pub async fn get_autocomplete(&self, prefix: &str) -> Result<Option<Vec<String>>> {
-        let key = format!("autocomplete:{}", prefix.to_lowercase());
+    /// Get autocomplete results cached under `cache_key` (see `AutocompleteOptions::cache_key`)
+    pub async fn get_autocomplete(&self, cache_key: &str) -> Result<Option<Vec<String>>> {
+        let key = self.config.key("autocomplete", cache_key);
         self.get_value::<Vec<String>>(&key).await
     }
 
-    /// Store autocomplete results
-    pub async fn set_autocomplete(&self, prefix: &str, names: &[String]) -> Result<()> {
-        let key = format!("autocomplete:{}", prefix.to_lowercase());
+    /// Store autocomplete results under `cache_key`
+    pub async fn set_autocomplete(&self, cache_key: &str, names: &[String]) -> Result<()> {
+        let key = self.config.key("autocomplete", cache_key);
         let names_vec: Vec<String> = names.to_vec();
-        // Autocomplete results expire faster (10 minutes)
-        self.set_value(&key, &names_vec, Some(600)).await
+        self.set_value(
+            "autocomplete",
+            &key,
+            &names_vec,
+            Some(self.config.autocomplete_ttl_seconds),
+        )
+        .await
+    }
+
+    /// Remove autocomplete entries that have no expiry (written before TTLs were set)
+    pub async fn prune_autocomplete(&self) -> Result<u64> {
+        let mut conn = self.client.clone();
+        let keys: Vec<String> = {
+            let mut iter = conn
+                .scan_match::<_, String>(self.config.key("autocomplete", "*"))
+                .await
+                .context("Failed to scan Redis autocomplete keys")?;
+            let mut keys = Vec::new();
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            keys
+        };
+
+        let mut deleted = 0;
+        for key in keys {
+            let ttl: i64 = conn
+                .ttl(&key)
+                .await
+                .context("Failed to read Redis key TTL")?;
+            // -1 = key exists without an expiry; -2 = already gone
+            if ttl == -1 {
+                let count: u64 = conn
+                    .del(&key)
+                    .await
+                    .context("Failed to delete autocomplete key from Redis")?;
+                deleted += count;
+            }
+        }
+
+        debug!("Removed {} stale autocomplete keys from Redis", deleted);
+        Ok(deleted)
     }
 
-    /// Invalidate all caches (e.g., after bulk data reload)
-    pub async fn invalidate_all(&self) -> Result<()> {
+    /// Claim idempotency key `key` for a request hashing to `request_hash` until
+    /// `ttl_seconds` from now (`SET NX`), or return the record already stored under it.
+    /// Idempotency keys are not a cache key class, so reloads don't drop them.
+    pub async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_hash: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<IdempotencyRecord>> {
+        let key = self.config.key("idempotency", key);
+        let pending = codec::encode(
+            &CacheEntry::new(IdempotencyRecord::pending(request_hash)),
+            self.config.encoding,
+            self.config.compression,
+        )
+        .context("Failed to serialize idempotency record")?;
+
         let mut conn = self.client.clone();
-        redis::cmd("FLUSHDB")
-            .query_async::<_, ()>(&mut conn)
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(pending)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl_seconds)
+            .query_async(&mut conn)
             .await
-            .context("Failed to flush Redis database")?;
+            .context("Failed to claim idempotency key in Redis")?;
+        if claimed.is_some() {
+            return Ok(None);
+        }
+        self.get_value::<IdempotencyRecord>(&key).await
+    }
 
-        debug!("Redis cache invalidated");
+    /// Store the response of a claimed idempotency key, keeping its TTL. A response over
+    /// the size limit releases the key instead.
+    pub async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        record: &IdempotencyRecord,
+    ) -> Result<()> {
+        let full_key = self.config.key("idempotency", key);
+        let Some(bytes) = self.encode("idempotency", &full_key, record)? else {
+            return self.release_idempotency_key(key).await;
+        };
+
+        let mut conn = self.client.clone();
+        redis::cmd("SET")
+            .arg(&full_key)
+            .arg(bytes)
+            .arg("XX")
+            .arg("KEEPTTL")
+            .query_async::<_, Option<String>>(&mut conn)
+            .await
+            .context("Failed to store idempotent response in Redis")?;
+        Ok(())
+    }
+
+    /// Drop an idempotency key, so the next request with it runs again
+    pub async fn release_idempotency_key(&self, key: &str) -> Result<()> {
+        let mut conn = self.client.clone();
+        let _: u64 = conn
+            .del(self.config.key("idempotency", key))
+            .await
+            .context("Failed to release idempotency key in Redis")?;
+        Ok(())
+    }
+
+    /// Take lock `name` for `ttl_seconds` if nobody holds it, tagged with `token` so only
+    /// this holder can extend or release it
+    pub async fn try_lock(&self, name: &str, token: &str, ttl_seconds: u64) -> Result<bool> {
+        let mut conn = self.client.clone();
+        let locked: Option<String> = redis::cmd("SET")
+            .arg(self.config.key("lock", name))
+            .arg(token)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl_seconds)
+            .query_async(&mut conn)
+            .await
+            .context("Failed to take lock in Redis")?;
+        Ok(locked.is_some())
+    }
+
+    /// Reset the TTL of lock `name` to `ttl_seconds`, if `token` still holds it
+    pub async fn extend_lock(&self, name: &str, token: &str, ttl_seconds: u64) -> Result<bool> {
+        let mut conn = self.client.clone();
+        let extended: i64 = redis::Script::new(
+            r"if redis.call('GET', KEYS[1]) == ARGV[1] then
+                return redis.call('EXPIRE', KEYS[1], ARGV[2])
+            end
+            return 0",
+        )
+        .key(self.config.key("lock", name))
+        .arg(token)
+        .arg(ttl_seconds)
+        .invoke_async(&mut conn)
+        .await
+        .context("Failed to extend lock in Redis")?;
+        Ok(extended == 1)
+    }
+
+    /// Release lock `name`, if `token` still holds it
+    pub async fn release_lock(&self, name: &str, token: &str) -> Result<()> {
+        let mut conn = self.client.clone();
+        let _: i64 = redis::Script::new(
+            r"if redis.call('GET', KEYS[1]) == ARGV[1] then
+                return redis.call('DEL', KEYS[1])
+            end
+            return 0",
+        )
+        .key(self.config.key("lock", name))
+        .arg(token)
+        .invoke_async(&mut conn)
+        .await
+        .context("Failed to release lock in Redis")?;
         Ok(())
     }
 
+    /// Invalidate all caches (e.g., after bulk data reload) by removing every card, query
+    /// and autocomplete key in this instance's namespace. Other keys in the Redis database,
+    /// including other environments' namespaces, are left alone.
+    pub async fn invalidate_all(&self) -> Result<u64> {
+        let mut deleted = 0;
+        for class in KEY_CLASSES {
+            deleted += self.unlink_matching(&self.config.key(class, "*")).await?;
+        }
+
+        debug!("Redis cache invalidated ({} keys removed)", deleted);
+        Ok(deleted)
+    }
+
+    /// Remove every key matching `pattern`, found with SCAN (which doesn't block the server
+    /// like KEYS) and removed with UNLINK (which frees memory in the background)
+    async fn unlink_matching(&self, pattern: &str) -> Result<u64> {
+        let mut conn = self.client.clone();
+        let keys: Vec<String> = {
+            let mut iter = conn
+                .scan_match::<_, String>(pattern)
+                .await
+                .with_context(|| format!("Failed to scan Redis keys matching {}", pattern))?;
+            let mut keys = Vec::new();
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            keys
+        };
+
+        let mut deleted = 0;
+        for chunk in keys.chunks(BATCH_CHUNK_SIZE) {
+            let count: u64 = redis::cmd("UNLINK")
+                .arg(chunk)
+                .query_async(&mut conn)
+                .await
+                .context("Failed to unlink keys from Redis")?;
+            deleted += count;
+        }
+        Ok(deleted)
+    }
+
     /// Get cache stats
     pub async fn get_stats(&self) -> Result<RedisStats> {
         let mut conn = self.client.clone();
@@ -211,70 +510,98 @@ impl RedisCache {
             }
         }
 
-        let total = hits + misses;
-        let hit_rate = if total > 0 {
-            (hits as f64 / total as f64) * 100.0
-        } else {
-            0.0
-        };
-
         Ok(RedisStats {
             hits,
             misses,
-            hit_rate,
+            hit_rate: crate::cache::manager::hit_rate(hits, misses),
         })
     }
 
+    /// Memory used by the Redis server (`used_memory`), shared with any other keyspace on it
+    pub async fn memory_used_bytes(&self) -> Result<u64> {
+        let mut conn = self.client.clone();
+
+        let info: String = redis::cmd("INFO")
+            .arg("memory")
+            .query_async(&mut conn)
+            .await
+            .context("Failed to get Redis memory usage")?;
+
+        info.lines()
+            .find_map(|line| line.strip_prefix("used_memory:"))
+            .and_then(|value| value.trim().parse().ok())
+            .context("Redis INFO memory has no used_memory field")
+    }
+
     /// Generic get value from cache
     async fn get_value<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<Option<T>> {
         let mut conn = self.client.clone();
 
-        let value: Option<String> = conn
+        let value: Option<Vec<u8>> = conn
             .get(key)
             .await
             .context("Failed to get value from Redis")?;
 
         match value {
-            Some(json) => {
-                let entry: CacheEntry<T> = serde_json::from_str(&json)
-                    .context("Failed to deserialize cache entry")?;
+            Some(bytes) => {
+                let entry: CacheEntry<T> =
+                    codec::decode(&bytes).context("Failed to deserialize cache entry")?;
                 Ok(Some(entry.data))
             }
             None => Ok(None),
         }
     }
 
-    /// Generic set value in cache
-    async fn set_value<T: Serialize>(
+    /// Serialize a value as a cache entry of key `class` with the configured encoding and
+    /// compression, or `None` if it exceeds the size limit
+    fn encode<T: Serialize>(
         &self,
+        class: &str,
         key: &str,
         value: &T,
-        ttl_seconds: Option<u64>,
-    ) -> Result<()> {
+    ) -> Result<Option<Vec<u8>>> {
         let entry = CacheEntry::new(value);
-        let json = serde_json::to_string(&entry)
+        let bytes = codec::encode(&entry, self.config.encoding, self.config.compression)
             .context("Failed to serialize cache entry")?;
+        REDIS_ENTRY_SIZE_BYTES
+            .with_label_values(&[class])
+            .observe(bytes.len() as f64);
 
         // Check size limit
-        let size_mb = json.len() / (1024 * 1024);
+        let size_mb = bytes.len() / (1024 * 1024);
         if size_mb > self.config.max_value_size_mb {
             warn!(
                 "Cache value too large ({} MB > {} MB limit), skipping: {}",
                 size_mb, self.config.max_value_size_mb, key
             );
-            return Ok(());
+            return Ok(None);
         }
 
+        Ok(Some(bytes))
+    }
+
+    /// Generic set value in cache
+    async fn set_value<T: Serialize>(
+        &self,
+        class: &str,
+        key: &str,
+        value: &T,
+        ttl_seconds: Option<u64>,
+    ) -> Result<()> {
+        let Some(bytes) = self.encode(class, key, value)? else {
+            return Ok(());
+        };
+
         let mut conn = self.client.clone();
 
         match ttl_seconds {
             Some(ttl) => {
-                conn.set_ex::<_, _, ()>(key, json, ttl)
+                conn.set_ex::<_, _, ()>(key, bytes, ttl)
                     .await
                     .context("Failed to set value in Redis with TTL")?;
             }
             None => {
-                conn.set::<_, _, ()>(key, json)
+                conn.set::<_, _, ()>(key, bytes)
                     .await
                     .context("Failed to set value in Redis")?;
             }
@@ -285,15 +612,82 @@ impl RedisCache {
 }
 
 /// Redis cache statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct RedisStats {
     pub hits: u64,
     pub misses: u64,
+    /// Hits as a percentage of lookups (0 when there were none)
     pub hit_rate: f64,
 }
 
+/// How long a Redis lock outlives a holder that stops renewing it (e.g. one that crashed)
+pub const LOCK_TTL_SECONDS: u64 = 60;
+
+/// Lock taken with `RedisCache::try_lock`, renewed in the background and released when
+/// dropped
+pub struct RedisLock {
+    redis: RedisCache,
+    name: String,
+    token: String,
+    renewal: tokio::task::JoinHandle<()>,
+}
+
+impl RedisLock {
+    /// Take lock `name` without waiting. Returns `None` while another holder has it.
+    pub async fn try_acquire(redis: &RedisCache, name: &str) -> Result<Option<Self>> {
+        let token = Uuid::new_v4().to_string();
+        if !redis.try_lock(name, &token, LOCK_TTL_SECONDS).await? {
+            return Ok(None);
+        }
+
+        let renewal = {
+            let (redis, name, token) = (redis.clone(), name.to_string(), token.clone());
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(LOCK_TTL_SECONDS / 3));
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    match redis.extend_lock(&name, &token, LOCK_TTL_SECONDS).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            error!("Redis lock '{}' expired while held", name);
+                            return;
+                        }
+                        Err(e) => warn!("Failed to renew Redis lock '{}': {}", name, e),
+                    }
+                }
+            })
+        };
+        Ok(Some(Self {
+            redis: redis.clone(),
+            name: name.to_string(),
+            token,
+            renewal,
+        }))
+    }
+}
+
+impl Drop for RedisLock {
+    fn drop(&mut self) {
+        self.renewal.abort();
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            // The lock expires after LOCK_TTL_SECONDS instead
+            return;
+        };
+        let redis = self.redis.clone();
+        let name = std::mem::take(&mut self.name);
+        let token = std::mem::take(&mut self.token);
+        runtime.spawn(async move {
+            if let Err(e) = redis.release_lock(&name, &token).await {
+                warn!("Failed to release Redis lock '{}': {}", name, e);
+            }
+        });
+    }
+}
+
 /// Stub implementation when Redis is not enabled
 #[cfg(not(feature = "redis_cache"))]
+#[derive(Clone)]
 pub struct RedisCache;
 
 #[cfg(not(feature = "redis_cache"))]
@@ -306,14 +700,27 @@ impl RedisCache {
         Ok(())
     }
 
-    pub async fn get_query_results(&self, _query: &str) -> Result<Option<Vec<Uuid>>> {
+    pub async fn get_query_results(&self, _query_hash: &str) -> Result<Option<Vec<Uuid>>> {
         Ok(None)
     }
 
-    pub async fn set_query_results(&self, _query: &str, _card_ids: &[Uuid]) -> Result<()> {
+    pub async fn set_query_results(
+        &self,
+        _query_hash: &str,
+        _card_ids: &[Uuid],
+        _ttl: Duration,
+    ) -> Result<()> {
         Ok(())
     }
 
+    pub async fn delete_query_results(&self, _query_hash: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    pub async fn clear_query_results(&self) -> Result<u64> {
+        Ok(0)
+    }
+
     pub async fn get_card(&self, _id: Uuid) -> Result<Option<Card>> {
         Ok(None)
     }
@@ -330,18 +737,55 @@ impl RedisCache {
         Ok(())
     }
 
-    pub async fn get_autocomplete(&self, _prefix: &str) -> Result<Option<Vec<String>>> {
+    pub async fn get_autocomplete(&self, _cache_key: &str) -> Result<Option<Vec<String>>> {
         Ok(None)
     }
 
-    pub async fn set_autocomplete(&self, _prefix: &str, _names: &[String]) -> Result<()> {
+    pub async fn set_autocomplete(&self, _cache_key: &str, _names: &[String]) -> Result<()> {
         Ok(())
     }
 
-    pub async fn invalidate_all(&self) -> Result<()> {
+    pub async fn prune_autocomplete(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    pub async fn claim_idempotency_key(
+        &self,
+        _key: &str,
+        _request_hash: &str,
+        _ttl_seconds: u64,
+    ) -> Result<Option<IdempotencyRecord>> {
+        Ok(None)
+    }
+
+    pub async fn complete_idempotency_key(
+        &self,
+        _key: &str,
+        _record: &IdempotencyRecord,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub async fn release_idempotency_key(&self, _key: &str) -> Result<()> {
+        Ok(())
+    }
+
+    pub async fn try_lock(&self, _name: &str, _token: &str, _ttl_seconds: u64) -> Result<bool> {
+        Ok(true)
+    }
+
+    pub async fn extend_lock(&self, _name: &str, _token: &str, _ttl_seconds: u64) -> Result<bool> {
+        Ok(true)
+    }
+
+    pub async fn release_lock(&self, _name: &str, _token: &str) -> Result<()> {
         Ok(())
     }
 
+    pub async fn invalidate_all(&self) -> Result<u64> {
+        Ok(0)
+    }
+
     pub async fn get_stats(&self) -> Result<RedisStats> {
         Ok(RedisStats {
             hits: 0,
@@ -349,4 +793,130 @@ impl RedisCache {
             hit_rate: 0.0,
         })
     }
+
+    pub async fn memory_used_bytes(&self) -> Result<u64> {
+        Ok(0)
+    }
+}
+
+/// These tests need a Redis server at `TEST_REDIS_URL` (default `redis://localhost:6379`).
+/// Each test works in its own key namespace.
+#[cfg(all(test, feature = "redis_cache"))]
+mod tests {
+    use super::*;
+
+    /// Configuration for the server at `TEST_REDIS_URL`, under a namespace of its own
+    fn test_config() -> RedisConfig {
+        RedisConfig {
+            url: std::env::var("TEST_REDIS_URL").unwrap_or_else(|_| RedisConfig::default().url),
+            key_prefix: format!("test-{}", Uuid::new_v4()),
+            ..RedisConfig::default()
+        }
+    }
+
+    async fn test_cache() -> RedisCache {
+        RedisCache::new(test_config()).await.unwrap()
+    }
+
+    fn card(name: &str) -> Card {
+        Card::from_scryfall_json(serde_json::json!({
+            "id": Uuid::new_v4().to_string(),
+            "name": name,
+            "set": "lea",
+            "collector_number": "161",
+            "lang": "en"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_key() {
+        let mut config = RedisConfig::default();
+        assert_eq!(config.key("card", "abc"), "card:abc");
+        config.key_prefix = "staging".to_string();
+        assert_eq!(config.key("query", "abc"), "staging:query:abc");
+    }
+
+    #[tokio::test]
+    async fn test_ttl_per_key_class() {
+        let cache = RedisCache::new(RedisConfig {
+            card_ttl_seconds: 300,
+            query_ttl_seconds: Some(60),
+            autocomplete_ttl_seconds: 120,
+            ..test_config()
+        })
+        .await
+        .unwrap();
+        let card = card("Lightning Bolt");
+        cache.set_card(&card).await.unwrap();
+        cache
+            .set_query_results("c:r", &[card.id], Duration::from_secs(3600))
+            .await
+            .unwrap();
+        cache
+            .set_autocomplete("light", &["Lightning Bolt".to_string()])
+            .await
+            .unwrap();
+
+        // Query results are capped at REDIS_QUERY_TTL_SECONDS despite their one-hour policy
+        let mut conn = cache.client.clone();
+        for (key, max) in [
+            (cache.config.key("card", card.id), 300),
+            (cache.config.key("query", "c:r"), 60),
+            (cache.config.key("autocomplete", "light"), 120),
+        ] {
+            let ttl: i64 = conn.ttl(&key).await.unwrap();
+            assert!(ttl > 0 && ttl <= max, "{} expires in {}s", key, ttl);
+        }
+
+        cache.invalidate_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_and_get_cards_in_chunks() {
+        let cache = test_cache().await;
+        let cards: Vec<Card> = (0..BATCH_CHUNK_SIZE + 1)
+            .map(|i| card(&format!("Card {}", i)))
+            .collect();
+        cache.set_cards(&cards).await.unwrap();
+
+        // Corrupt entries and missing cards are left out
+        let corrupt = Uuid::new_v4();
+        let mut conn = cache.client.clone();
+        conn.set::<_, _, ()>(cache.config.key("card", corrupt), "not a card")
+            .await
+            .unwrap();
+
+        let mut ids: Vec<Uuid> = cards.iter().map(|card| card.id).collect();
+        ids.extend([corrupt, Uuid::new_v4()]);
+        let found = cache.get_cards(&ids).await.unwrap();
+        assert_eq!(found.len(), cards.len());
+        assert!(found.iter().zip(&cards).all(|(a, b)| a.id == b.id));
+
+        cache.invalidate_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_all_keeps_other_namespaces() {
+        let cache = test_cache().await;
+        let other = test_cache().await;
+        for cache in [&cache, &other] {
+            cache.set_card(&card("Lightning Bolt")).await.unwrap();
+            cache
+                .set_query_results("c:r", &[Uuid::new_v4()], Duration::from_secs(60))
+                .await
+                .unwrap();
+            cache
+                .set_autocomplete("light", &["Lightning Bolt".to_string()])
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(cache.invalidate_all().await.unwrap(), 3);
+        assert_eq!(cache.get_query_results("c:r").await.unwrap(), None);
+        assert_eq!(cache.get_autocomplete("light").await.unwrap(), None);
+
+        assert!(other.get_query_results("c:r").await.unwrap().is_some());
+        assert_eq!(other.invalidate_all().await.unwrap(), 3);
+    }
 }
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tracing::{info, warn};
+
+use crate::cache::manager::CacheManager;
+use crate::config::Settings;
+use crate::errors::ServiceError;
+use crate::metrics::registry::CACHE_WARMUP_DURATION_SECONDS;
+use crate::utils::hash::hash_query;
+
+/// Configuration for cache warm-up
+#[derive(Debug, Clone, Default)]
+pub struct WarmupConfig {
+    /// Queries executed on each warm-up run, in order
+    pub queries: Vec<String>,
+}
+
+impl WarmupConfig {
+    /// Load configuration from `settings`. `WARMUP_QUERIES_FILE` points at a
+    /// file with one query per line; blank lines and `#` comments are ignored.
+    pub fn from_settings(settings: &Settings) -> Self {
+        let Some(path) = settings.var("WARMUP_QUERIES_FILE").ok().map(PathBuf::from) else {
+            return Self::default();
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Self {
+                queries: parse_queries(&contents),
+            },
+            Err(e) => {
+                warn!(
+                    "Failed to read warm-up queries from {}: {}",
+                    path.display(),
+                    e
+                );
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Parse a warm-up query list: one query per line, skipping blanks and `#` comments
+pub fn parse_queries(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Outcome of a warm-up run
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct WarmupRun {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Number of configured queries
+    pub queries: usize,
+    /// Queries executed and cached successfully
+    pub warmed: usize,
+    pub failed: usize,
+}
+
+/// Pre-populates the query cache (database and Redis) with a fixed list of popular queries
+pub struct CacheWarmer {
+    cache_manager: Arc<CacheManager>,
+    queries: Vec<String>,
+    last_run: RwLock<Option<WarmupRun>>,
+    /// Serializes runs so a manual trigger doesn't overlap a scheduled one
+    running: tokio::sync::Mutex<()>,
+}
+
+impl CacheWarmer {
+    pub fn new(cache_manager: Arc<CacheManager>, config: WarmupConfig) -> Self {
+        Self {
+            cache_manager,
+            queries: config.queries,
+            last_run: RwLock::new(None),
+            running: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn queries(&self) -> &[String] {
+        &self.queries
+    }
+
+    /// Result of the most recent run, if any
+    pub fn last_run(&self) -> Option<WarmupRun> {
+        self.last_run.read().unwrap().clone()
+    }
+
+    /// Re-run every configured query. Each query's cached results are evicted first so
+    /// entries computed before a bulk reload are replaced rather than served again.
+    pub async fn run(&self) -> WarmupRun {
+        let _running = self.running.lock().await;
+        let started_at = Utc::now();
+        let start = Instant::now();
+        let mut warmed = 0;
+        let mut failed = 0;
+
+        for query in &self.queries {
+            let evicted = self.cache_manager.evict_cached_query(&hash_query(query));
+            match evicted.await {
+                Ok(_) | Err(ServiceError::NotFound(_)) => {}
+                Err(e) => warn!("Failed to evict warm-up query '{}': {}", query, e),
+            }
+
+            match self.cache_manager.search_untracked(query, None).await {
+                Ok(_) => warmed += 1,
+                Err(e) => {
+                    warn!("Warm-up query '{}' failed: {}", query, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        let duration = start.elapsed();
+        CACHE_WARMUP_DURATION_SECONDS.set(duration.as_secs_f64());
+        info!(
+            "Cache warm-up completed: {} of {} queries warmed in {:.2}s",
+            warmed,
+            self.queries.len(),
+            duration.as_secs_f64()
+        );
+
+        let run = WarmupRun {
+            started_at,
+            duration_ms: duration.as_millis() as u64,
+            queries: self.queries.len(),
+            warmed,
+            failed,
+        };
+        *self.last_run.write().unwrap() = Some(run.clone());
+        run
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_queries() {
+        let contents = "# staples\nc:red t:instant\n\n  sol ring  \n#commander\nt:legendary";
+        assert_eq!(
+            parse_queries(contents),
+            vec!["c:red t:instant", "sol ring", "t:legendary"]
+        );
+        assert!(parse_queries("").is_empty());
+    }
+}
@@ -1,11 +1,20 @@
+mod registry;
 mod state;
 
+use serde::Serialize;
 use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tracing::{info, warn};
 
+use crate::config::Settings;
+use crate::events::{self, CacheEvent};
+use crate::metrics::registry::{
+    CIRCUIT_BREAKER_FAILURES_TOTAL, CIRCUIT_BREAKER_STATE, CIRCUIT_BREAKER_TRANSITIONS_TOTAL,
+};
+
+pub use registry::CircuitBreakerRegistry;
 pub use state::{CircuitState, CircuitStateData};
 
 #[derive(Debug, Clone)]
@@ -28,23 +37,27 @@ impl Default for CircuitBreakerConfig {
 }
 
 impl CircuitBreakerConfig {
-    pub fn from_env() -> Self {
+    pub fn from_settings(settings: &Settings) -> Self {
         Self {
-            failure_threshold: std::env::var("CIRCUIT_BREAKER_FAILURE_THRESHOLD")
+            failure_threshold: settings
+                .var("CIRCUIT_BREAKER_FAILURE_THRESHOLD")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(5),
-            success_threshold: std::env::var("CIRCUIT_BREAKER_SUCCESS_THRESHOLD")
+            success_threshold: settings
+                .var("CIRCUIT_BREAKER_SUCCESS_THRESHOLD")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(2),
             timeout: Duration::from_secs(
-                std::env::var("CIRCUIT_BREAKER_TIMEOUT_SECONDS")
+                settings
+                    .var("CIRCUIT_BREAKER_TIMEOUT_SECONDS")
                     .ok()
                     .and_then(|v| v.parse().ok())
                     .unwrap_or(60),
             ),
-            half_open_max_requests: std::env::var("CIRCUIT_BREAKER_HALF_OPEN_REQUESTS")
+            half_open_max_requests: settings
+                .var("CIRCUIT_BREAKER_HALF_OPEN_REQUESTS")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(3),
@@ -70,6 +83,10 @@ impl CircuitBreaker {
             "Initialized circuit breaker"
         );
 
+        CIRCUIT_BREAKER_STATE
+            .with_label_values(&[&name_str])
+            .set(CircuitState::Closed.as_metric_value());
+
         Self {
             name: name_str,
             config,
@@ -77,13 +94,17 @@ impl CircuitBreaker {
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub async fn state(&self) -> CircuitState {
         let mut state = self.state.lock().await;
 
         // Check if we should transition from Open to HalfOpen
         if state.should_attempt_reset(self.config.timeout) {
             info!(name = %self.name, "Circuit breaker transitioning to half-open");
-            state.state = CircuitState::HalfOpen;
+            self.transition(&mut state, CircuitState::HalfOpen);
             state.reset();
         }
 
@@ -144,7 +165,7 @@ impl CircuitBreaker {
                 state.success_count += 1;
                 if state.success_count >= self.config.success_threshold {
                     info!(name = %self.name, "Circuit breaker closing after successful recovery");
-                    state.state = CircuitState::Closed;
+                    self.transition(&mut state, CircuitState::Closed);
                     state.reset();
                 }
             }
@@ -159,6 +180,9 @@ impl CircuitBreaker {
         let mut state = self.state.lock().await;
         state.failure_count += 1;
         state.last_failure_time = Some(std::time::Instant::now());
+        CIRCUIT_BREAKER_FAILURES_TOTAL
+            .with_label_values(&[&self.name])
+            .inc();
 
         match state.state {
             CircuitState::Closed => {
@@ -168,12 +192,12 @@ impl CircuitBreaker {
                         failures = state.failure_count,
                         "Circuit breaker opening due to failures"
                     );
-                    state.state = CircuitState::Open;
+                    self.transition(&mut state, CircuitState::Open);
                 }
             }
             CircuitState::HalfOpen => {
                 warn!(name = %self.name, "Circuit breaker reopening after failed recovery attempt");
-                state.state = CircuitState::Open;
+                self.transition(&mut state, CircuitState::Open);
                 state.reset();
             }
             CircuitState::Open => {
@@ -182,6 +206,36 @@ impl CircuitBreaker {
         }
     }
 
+    /// Move to `to`, recording the transition in Prometheus
+    fn transition(&self, state: &mut CircuitStateData, to: CircuitState) {
+        let from = state.state;
+        state.state = to;
+        if from == to {
+            return;
+        }
+
+        CIRCUIT_BREAKER_TRANSITIONS_TOTAL
+            .with_label_values(&[&self.name, from.as_str(), to.as_str()])
+            .inc();
+        CIRCUIT_BREAKER_STATE
+            .with_label_values(&[&self.name])
+            .set(to.as_metric_value());
+        events::publish(CacheEvent::CircuitBreakerStateChanged {
+            name: self.name.clone(),
+            from,
+            to,
+        });
+    }
+
+    /// Force the breaker closed, clearing failure history
+    pub async fn reset(&self) {
+        let mut state = self.state.lock().await;
+        info!(name = %self.name, previous = ?state.state, "Circuit breaker manually reset");
+        self.transition(&mut state, CircuitState::Closed);
+        state.last_failure_time = None;
+        state.reset();
+    }
+
     pub async fn metrics(&self) -> CircuitBreakerMetrics {
         let state = self.state.lock().await;
         CircuitBreakerMetrics {
@@ -217,7 +271,7 @@ impl<E: std::error::Error + 'static> std::error::Error for CircuitBreakerError<E
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct CircuitBreakerMetrics {
     pub name: String,
     pub state: CircuitState,
@@ -324,4 +378,65 @@ mod tests {
 
         assert_eq!(cb.state().await, CircuitState::Closed);
     }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_manual_reset_closes() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            success_threshold: 1,
+            timeout: Duration::from_secs(60),
+            half_open_max_requests: 1,
+        };
+
+        let cb = CircuitBreaker::new("test", config);
+        let _ = cb.call(async { Err::<(), _>("failure") }).await;
+        assert_eq!(cb.state().await, CircuitState::Open);
+
+        cb.reset().await;
+        assert_eq!(cb.state().await, CircuitState::Closed);
+        assert_eq!(cb.metrics().await.failure_count, 0);
+        assert!(cb.call(async { Ok::<_, &str>(()) }).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_exports_metrics() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            success_threshold: 1,
+            timeout: Duration::from_secs(60),
+            half_open_max_requests: 1,
+        };
+
+        let name = "test_metrics_export";
+        let cb = CircuitBreaker::new(name, config);
+        let state_gauge = CIRCUIT_BREAKER_STATE.with_label_values(&[name]);
+        assert_eq!(state_gauge.get(), 0);
+
+        for _ in 0..2 {
+            let _ = cb.call(async { Err::<(), _>("failure") }).await;
+        }
+
+        assert_eq!(state_gauge.get(), 1);
+        assert_eq!(
+            CIRCUIT_BREAKER_FAILURES_TOTAL
+                .with_label_values(&[name])
+                .get(),
+            2
+        );
+        assert_eq!(
+            CIRCUIT_BREAKER_TRANSITIONS_TOTAL
+                .with_label_values(&[name, "closed", "open"])
+                .get(),
+            1
+        );
+
+        cb.reset().await;
+        assert_eq!(state_gauge.get(), 0);
+        assert_eq!(
+            CIRCUIT_BREAKER_TRANSITIONS_TOTAL
+                .with_label_values(&[name, "open", "closed"])
+                .get(),
+            1
+        );
+    }
 }
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use super::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerMetrics};
+
+/// Named circuit breakers sharing one configuration, so each upstream endpoint
+/// trips independently of the others
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerRegistry {
+    config: CircuitBreakerConfig,
+    breakers: Arc<RwLock<BTreeMap<String, Arc<CircuitBreaker>>>>,
+}
+
+impl CircuitBreakerRegistry {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            breakers: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+
+    /// Get the breaker for `name`, creating it on first use
+    pub fn get(&self, name: &str) -> Arc<CircuitBreaker> {
+        if let Some(breaker) = self.find(name) {
+            return breaker;
+        }
+
+        let mut breakers = self.breakers.write().unwrap();
+        breakers
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(CircuitBreaker::new(name, self.config.clone())))
+            .clone()
+    }
+
+    /// Get the breaker for `name` if it exists
+    pub fn find(&self, name: &str) -> Option<Arc<CircuitBreaker>> {
+        self.breakers.read().unwrap().get(name).cloned()
+    }
+
+    /// All registered breakers, ordered by name
+    pub fn all(&self) -> Vec<Arc<CircuitBreaker>> {
+        self.breakers.read().unwrap().values().cloned().collect()
+    }
+
+    /// Current metrics of every registered breaker, ordered by name
+    pub async fn metrics(&self) -> Vec<CircuitBreakerMetrics> {
+        let mut metrics = Vec::new();
+        for breaker in self.all() {
+            metrics.push(breaker.metrics().await);
+        }
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::CircuitState;
+
+    #[tokio::test]
+    async fn test_registry_breakers_are_independent() {
+        let registry = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..CircuitBreakerConfig::default()
+        });
+
+        let search = registry.get("cards_search");
+        let _ = search.call(async { Err::<(), _>("failure") }).await;
+
+        assert!(Arc::ptr_eq(&search, &registry.get("cards_search")));
+        assert_eq!(search.state().await, CircuitState::Open);
+        assert_eq!(
+            registry.get("cards_named").state().await,
+            CircuitState::Closed
+        );
+
+        let names: Vec<String> = registry
+            .metrics()
+            .await
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(names, vec!["cards_named", "cards_search"]);
+        assert!(registry.find("sets").is_none());
+    }
+}
@@ -0,0 +1,503 @@
+//! Typed client for this service's REST API (`client` feature), so Rust consumers don't
+//! hand-roll reqwest calls. It shares the server's `Card`, `ErrorResponse` and admin models
+//! and only uses reqwest and serde; nothing here touches the database, caches or router.
+//!
+//! Requests always ask for the native response envelope, whatever the server's
+//! `RESPONSE_FORMAT` default is.
+
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub use crate::cache::analytics::TopQuery;
+pub use crate::cache::manager::CacheStats;
+pub use crate::errors::{ErrorCode, ErrorDetail, ErrorResponse};
+pub use crate::models::admin_audit::AdminAuditEntry;
+pub use crate::models::bulk_import::BulkImport;
+pub use crate::models::card::{Card, CardFace, RelatedCard};
+pub use crate::models::repair::RepairReport;
+pub use crate::models::schema_version::SchemaVersion;
+
+/// Error returned by `ScryfallCacheClient`
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The request could not be sent or its body could not be decoded
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The service answered with its structured error envelope
+    #[error("{status}: {} ({:?})", .error.message, .error.code)]
+    Api {
+        status: StatusCode,
+        error: ErrorDetail,
+    },
+    /// The service (or a proxy in front of it) answered with an error that isn't the
+    /// structured envelope
+    #[error("{status}: {body}")]
+    Unexpected { status: StatusCode, body: String },
+}
+
+impl ClientError {
+    /// HTTP status of the response, if one was received
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Http(e) => e.status(),
+            ClientError::Api { status, .. } | ClientError::Unexpected { status, .. } => {
+                Some(*status)
+            }
+        }
+    }
+
+    /// Structured error code, when the service sent one
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            ClientError::Api { error, .. } => Some(error.code),
+            _ => None,
+        }
+    }
+}
+
+pub type ClientResult<T> = Result<T, ClientError>;
+
+/// Native success envelope: `{"success": true, "data": ...}`
+#[derive(Debug, Deserialize)]
+struct Envelope<T> {
+    data: Option<T>,
+    error: Option<ErrorDetail>,
+}
+
+/// One page of `/cards/search` results
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchPage {
+    pub data: Vec<Card>,
+    /// Matches across all pages
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub total_pages: usize,
+    pub has_more: bool,
+    /// Pass as `SearchOptions::cursor` to fetch the next page
+    pub next_cursor: Option<String>,
+    /// Scryfall had more matches than the service was configured to fetch
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Optional `/cards/search` parameters; see the API docs for their defaults
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Omit `raw_json` and `card_faces` from each card
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<bool>,
+    /// `cards`, `prints` or `art`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique: Option<String>,
+    /// `name`, `released`, `usd`, `eur`, `tix`, `edhrec` or `penny`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    /// `auto`, `asc` or `desc`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+}
+
+/// A reproducible sample from `/cards/sample`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CardSample {
+    /// Pass again to draw the same cards
+    pub seed: u64,
+    /// Cards matching the query
+    pub total: usize,
+    pub data: Vec<Card>,
+}
+
+/// Result of `/cards/batch`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchCards {
+    /// Found cards, in request order
+    pub cards: Vec<Card>,
+    pub missing_ids: Vec<Uuid>,
+}
+
+/// One requested name of `/cards/named/batch`
+#[derive(Debug, Clone, Deserialize)]
+pub struct NamedResult {
+    pub name: String,
+    pub card: Option<Card>,
+}
+
+/// Result of `/cards/named/batch`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchNamed {
+    pub results: Vec<NamedResult>,
+    pub not_found: Vec<String>,
+}
+
+/// Cached query entries evicted by `DELETE /admin/cache/queries`
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueryCachePurge {
+    pub database_entries: u64,
+    pub redis_entries: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Catalog {
+    data: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct BatchCardsBody<'a> {
+    ids: &'a [Uuid],
+    fetch_missing: bool,
+}
+
+#[derive(Serialize)]
+struct BatchNamedBody<'a> {
+    names: &'a [String],
+    fuzzy: bool,
+}
+
+const NATIVE: (&str, &str) = ("format", "native");
+
+/// Client for a running instance of the service
+#[derive(Debug, Clone)]
+pub struct ScryfallCacheClient {
+    http: reqwest::Client,
+    base_url: String,
+    admin_token: Option<String>,
+}
+
+impl ScryfallCacheClient {
+    /// Client for the service at `base_url`, e.g. `http://localhost:8080`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Client sending its requests through `http`, for custom timeouts, proxies or TLS
+    pub fn with_http_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Self {
+            http,
+            base_url,
+            admin_token: None,
+        }
+    }
+
+    /// Send `token` as the bearer token of admin requests (`ADMIN_TOKEN` on the server)
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http.request(method, self.url(path))
+    }
+
+    fn admin(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.request(method, path);
+        match &self.admin_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Send `request` and decode the `data` of its native envelope
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> ClientResult<T> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await?;
+            return Err(api_error(status, body));
+        }
+        let envelope: Envelope<T> = response.json().await?;
+        match (envelope.data, envelope.error) {
+            (Some(data), _) => Ok(data),
+            (None, Some(error)) => Err(ClientError::Api { status, error }),
+            (None, None) => Err(ClientError::Unexpected {
+                status,
+                body: "response has neither data nor error".to_string(),
+            }),
+        }
+    }
+
+    /// Like `send`, but a 404 is `None`
+    async fn send_optional<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> ClientResult<Option<T>> {
+        match self.send(request).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// `GET` any endpoint answering with the native envelope, for those without a
+    /// typed method
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &(impl Serialize + ?Sized),
+    ) -> ClientResult<T> {
+        self.send(self.request(Method::GET, path).query(query))
+            .await
+    }
+
+    /// Search cards with a Scryfall query, e.g. `c:red t:creature` (`GET /cards/search`)
+    pub async fn search(&self, q: &str, options: &SearchOptions) -> ClientResult<SearchPage> {
+        let request = self
+            .request(Method::GET, "/cards/search")
+            .query(&[("q", q), NATIVE])
+            .query(options);
+        self.send(request).await
+    }
+
+    /// Card by Scryfall id; `None` when neither the cache nor Scryfall knows it
+    /// (`GET /cards/{id}`)
+    pub async fn get_card(&self, id: Uuid) -> ClientResult<Option<Card>> {
+        self.send_optional(self.request(Method::GET, &format!("/cards/{}", id)))
+            .await
+    }
+
+    /// Card by exact (case-insensitive) name (`GET /cards/named?exact=`)
+    pub async fn get_card_by_name(&self, name: &str) -> ClientResult<Option<Card>> {
+        let request = self
+            .request(Method::GET, "/cards/named")
+            .query(&[("exact", name), NATIVE]);
+        self.send_optional(request).await
+    }
+
+    /// Card by fuzzy name, e.g. `light bolt` (`GET /cards/named?fuzzy=`)
+    pub async fn get_card_by_fuzzy_name(&self, name: &str) -> ClientResult<Option<Card>> {
+        let request = self
+            .request(Method::GET, "/cards/named")
+            .query(&[("fuzzy", name), NATIVE]);
+        self.send_optional(request).await
+    }
+
+    /// Printing by set code and collector number (`GET /cards/{set}/{number}`)
+    pub async fn get_card_by_set_and_number(
+        &self,
+        set: &str,
+        number: &str,
+    ) -> ClientResult<Option<Card>> {
+        let path = format!("/cards/{}/{}", set, number);
+        self.send_optional(self.request(Method::GET, &path)).await
+    }
+
+    /// Random card, optionally restricted by a query (`GET /cards/random`)
+    pub async fn random_card(&self, q: Option<&str>) -> ClientResult<Card> {
+        let request = self
+            .request(Method::GET, "/cards/random")
+            .query(&[("q", q)]);
+        self.send(request).await
+    }
+
+    /// Up to `n` matching cards drawn with `seed` (random when `None`) (`GET /cards/sample`)
+    pub async fn sample(
+        &self,
+        q: Option<&str>,
+        n: Option<usize>,
+        seed: Option<u64>,
+    ) -> ClientResult<CardSample> {
+        let request = self
+            .request(Method::GET, "/cards/sample")
+            .query(&[("q", q)])
+            .query(&[("n", n)])
+            .query(&[("seed", seed)]);
+        self.send(request).await
+    }
+
+    /// Card names starting with `q` (`GET /cards/autocomplete`)
+    pub async fn autocomplete(&self, q: &str, limit: Option<i64>) -> ClientResult<Vec<String>> {
+        let request = self
+            .request(Method::GET, "/cards/autocomplete")
+            .query(&[("q", q), NATIVE])
+            .query(&[("limit", limit)]);
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await?;
+            return Err(api_error(status, body));
+        }
+        Ok(response.json::<Catalog>().await?.data)
+    }
+
+    /// Cards by id, in request order; `fetch_missing` fetches uncached ones from
+    /// Scryfall (`POST /cards/batch`)
+    pub async fn batch_get_cards(
+        &self,
+        ids: &[Uuid],
+        fetch_missing: bool,
+    ) -> ClientResult<BatchCards> {
+        let body = BatchCardsBody { ids, fetch_missing };
+        self.send(self.request(Method::POST, "/cards/batch").json(&body))
+            .await
+    }
+
+    /// Cards by name, exact or fuzzy (`POST /cards/named/batch`)
+    pub async fn batch_get_cards_by_name(
+        &self,
+        names: &[String],
+        fuzzy: bool,
+    ) -> ClientResult<BatchNamed> {
+        let body = BatchNamedBody { names, fuzzy };
+        self.send(self.request(Method::POST, "/cards/named/batch").json(&body))
+            .await
+    }
+
+    /// Faces of a card, empty for single-faced cards (`GET /cards/{id}/faces`)
+    pub async fn card_faces(&self, id: Uuid) -> ClientResult<Option<Vec<CardFace>>> {
+        let path = format!("/cards/{}/faces", id);
+        self.send_optional(self.request(Method::GET, &path)).await
+    }
+
+    /// Tokens, meld parts and combo pieces linked to a card (`GET /cards/{id}/related`)
+    pub async fn related_cards(&self, id: Uuid) -> ClientResult<Option<Vec<RelatedCard>>> {
+        let path = format!("/cards/{}/related", id);
+        self.send_optional(self.request(Method::GET, &path)).await
+    }
+
+    /// Cache statistics (`GET /stats`)
+    pub async fn stats(&self) -> ClientResult<CacheStats> {
+        self.send(self.request(Method::GET, "/stats")).await
+    }
+
+    /// Force a bulk data reload; returns once it completes (`POST /admin/reload`)
+    pub async fn reload(&self) -> ClientResult<String> {
+        self.send(self.admin(Method::POST, "/admin/reload")).await
+    }
+
+    /// Scan up to `limit` stored cards for corrupt rows and re-fetch them
+    /// (`POST /admin/repair`)
+    pub async fn repair(&self, limit: Option<usize>) -> ClientResult<RepairReport> {
+        let request = self
+            .admin(Method::POST, "/admin/repair")
+            .query(&[("limit", limit)]);
+        self.send(request).await
+    }
+
+    /// Evict every cached query result (`DELETE /admin/cache/queries`)
+    pub async fn clear_query_cache(&self) -> ClientResult<QueryCachePurge> {
+        self.send(self.admin(Method::DELETE, "/admin/cache/queries"))
+            .await
+    }
+
+    /// Most frequent search queries (`GET /api/admin/stats/top-queries`)
+    pub async fn top_queries(&self, limit: Option<usize>) -> ClientResult<Vec<TopQuery>> {
+        let request = self
+            .admin(Method::GET, "/api/admin/stats/top-queries")
+            .query(&[("limit", limit)]);
+        self.send(request).await
+    }
+
+    /// Bulk import history, newest first (`GET /api/admin/imports`)
+    pub async fn imports(&self, limit: Option<i64>) -> ClientResult<Vec<BulkImport>> {
+        let request = self
+            .admin(Method::GET, "/api/admin/imports")
+            .query(&[("limit", limit)]);
+        self.send(request).await
+    }
+
+    /// Admin audit log, newest first, optionally of one actor (`GET /api/admin/audit`)
+    pub async fn audit_log(
+        &self,
+        limit: Option<i64>,
+        actor: Option<&str>,
+    ) -> ClientResult<Vec<AdminAuditEntry>> {
+        let request = self
+            .admin(Method::GET, "/api/admin/audit")
+            .query(&[("limit", limit)])
+            .query(&[("actor", actor)]);
+        self.send(request).await
+    }
+
+    /// Applied and pending database migrations (`GET /api/admin/schema/version`)
+    pub async fn schema_version(&self) -> ClientResult<SchemaVersion> {
+        self.send(self.admin(Method::GET, "/api/admin/schema/version"))
+            .await
+    }
+}
+
+/// Error for a non-success response with body `body`
+fn api_error(status: StatusCode, body: String) -> ClientError {
+    match serde_json::from_str::<ErrorResponse>(&body) {
+        Ok(response) => ClientError::Api {
+            status,
+            error: response.error,
+        },
+        Err(_) => ClientError::Unexpected { status, body },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_trailing_slash() {
+        let client = ScryfallCacheClient::new("http://localhost:8080/");
+        assert_eq!(client.base_url(), "http://localhost:8080");
+        assert_eq!(
+            client.url("/cards/search"),
+            "http://localhost:8080/cards/search"
+        );
+    }
+
+    #[test]
+    fn test_search_options_query() {
+        let options = SearchOptions {
+            page: Some(2),
+            order: Some("usd".to_string()),
+            ..Default::default()
+        };
+        let request = ScryfallCacheClient::new("http://localhost:8080")
+            .request(Method::GET, "/cards/search")
+            .query(&[("q", "c:red"), NATIVE])
+            .query(&options)
+            .query(&[("limit", None::<i64>)])
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("q=c%3Ared&format=native&page=2&order=usd")
+        );
+    }
+
+    #[test]
+    fn test_admin_token_sent_as_bearer() {
+        let client = ScryfallCacheClient::new("http://localhost:8080").with_admin_token("s3cret");
+        let request = client.admin(Method::POST, "/admin/reload").build().unwrap();
+        assert_eq!(request.headers()["authorization"], "Bearer s3cret");
+    }
+
+    #[test]
+    fn test_api_error_decoding() {
+        let body = serde_json::to_string(&ErrorResponse::card_not_found("abc")).unwrap();
+        let error = api_error(StatusCode::NOT_FOUND, body);
+        assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(error.code(), Some(ErrorCode::CardNotFound));
+
+        let error = api_error(
+            StatusCode::BAD_GATEWAY,
+            "<html>bad gateway</html>".to_string(),
+        );
+        assert!(matches!(error, ClientError::Unexpected { .. }));
+        assert_eq!(error.code(), None);
+    }
+}
@@ -0,0 +1,159 @@
+//! User collections: owned quantities of printings, stored per tenant, valued with the
+//! prices of the cached cards.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::card::Card;
+use crate::models::collection::{CollectionEntry, Finish};
+
+/// Longest accepted collection id
+const MAX_COLLECTION_ID_LENGTH: usize = 64;
+
+/// Currencies a collection can be valued in
+pub const CURRENCIES: &[&str] = &["usd", "eur", "tix"];
+
+/// Collection ids are 1-64 ASCII letters, digits, `-` or `_`
+pub fn is_valid_collection_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_COLLECTION_ID_LENGTH
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Key of the price of `finish` in Scryfall's `prices` object (`usd_foil`). MTGO has a
+/// single `tix` price for every finish, and Scryfall has no etched EUR price.
+fn price_key(currency: &str, finish: Finish) -> Option<String> {
+    match (currency, finish) {
+        ("tix", _) => Some("tix".to_string()),
+        ("eur", Finish::Etched) => None,
+        (currency, Finish::Nonfoil) => Some(currency.to_string()),
+        (currency, finish) => Some(format!("{}_{}", currency, finish.as_str())),
+    }
+}
+
+/// Price of one copy of `card` in `finish`, or `None` when Scryfall has no price for it
+fn unit_price(card: &Card, currency: &str, finish: Finish) -> Option<f64> {
+    let key = price_key(currency, finish)?;
+    card.prices
+        .as_ref()?
+        .get(&key)?
+        .as_str()?
+        .parse::<f64>()
+        .ok()
+}
+
+/// Value of a collection, from the prices of the cached cards
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CollectionValue {
+    pub collection_id: String,
+    /// `usd`, `eur` or `tix`
+    pub currency: String,
+    /// Sum of quantity times price over the priced entries, rounded to cents
+    pub total: f64,
+    /// Distinct printing and finish entries
+    pub entries: usize,
+    /// Copies owned
+    pub quantity: i64,
+    /// Copies without a price in `currency`, including cards missing from the cache
+    pub unpriced_quantity: i64,
+}
+
+/// Value `entries` in `currency` using the prices of `cards`
+pub fn collection_value(
+    collection_id: &str,
+    currency: &str,
+    entries: &[CollectionEntry],
+    cards: &[Card],
+) -> CollectionValue {
+    let cards: HashMap<Uuid, &Card> = cards.iter().map(|card| (card.id, card)).collect();
+    let mut total = 0.0;
+    let mut quantity = 0;
+    let mut unpriced_quantity = 0;
+
+    for entry in entries {
+        let copies = i64::from(entry.quantity);
+        quantity += copies;
+        let price = cards
+            .get(&entry.card_id)
+            .and_then(|card| unit_price(card, currency, entry.finish));
+        match price {
+            Some(price) => total += price * copies as f64,
+            None => unpriced_quantity += copies,
+        }
+    }
+
+    CollectionValue {
+        collection_id: collection_id.to_string(),
+        currency: currency.to_string(),
+        total: (total * 100.0).round() / 100.0,
+        entries: entries.len(),
+        quantity,
+        unpriced_quantity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(prices: serde_json::Value) -> Card {
+        Card::from_scryfall_json(serde_json::json!({
+            "id": Uuid::new_v4().to_string(),
+            "name": "Sol Ring",
+            "prices": prices
+        }))
+        .unwrap()
+    }
+
+    fn entry(card: &Card, finish: Finish, quantity: i32) -> CollectionEntry {
+        CollectionEntry {
+            card_id: card.id,
+            finish,
+            quantity,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_collection_value() {
+        let ring = card(serde_json::json!({"usd": "1.50", "usd_foil": "10.00", "eur": "1.20"}));
+        let bolt = card(serde_json::json!({"usd": null, "tix": "0.02"}));
+        let entries = vec![
+            entry(&ring, Finish::Nonfoil, 4),
+            entry(&ring, Finish::Foil, 1),
+            entry(&bolt, Finish::Nonfoil, 2),
+            CollectionEntry {
+                card_id: Uuid::nil(),
+                finish: Finish::Nonfoil,
+                quantity: 3,
+                updated_at: None,
+            },
+        ];
+        let cards = vec![ring, bolt];
+
+        let usd = collection_value("binder", "usd", &entries, &cards);
+        assert_eq!(usd.total, 16.0);
+        assert_eq!(usd.entries, 4);
+        assert_eq!(usd.quantity, 10);
+        assert_eq!(usd.unpriced_quantity, 5);
+
+        let eur = collection_value("binder", "eur", &entries, &cards);
+        assert_eq!(eur.total, 4.8);
+        assert_eq!(eur.unpriced_quantity, 6);
+
+        let tix = collection_value("binder", "tix", &entries, &cards);
+        assert_eq!(tix.total, 0.04);
+    }
+
+    #[test]
+    fn test_collection_ids() {
+        assert!(is_valid_collection_id("my-binder_2"));
+        assert!(!is_valid_collection_id(""));
+        assert!(!is_valid_collection_id("a/b"));
+        assert!(!is_valid_collection_id(&"x".repeat(65)));
+    }
+}
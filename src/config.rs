@@ -1,5 +1,32 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
 use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::api::compat::ResponseFormat;
+use crate::api::cors::CorsConfig;
+use crate::api::health::HealthConfig;
+use crate::api::http_cache::HttpCacheConfig;
+use crate::audit::AuditConfig;
+use crate::background::bulk_refresh::BulkRefreshConfig;
+use crate::background::{
+    CacheCleanupConfig, CardRepairConfig, CardWriterConfig, MetricsRefreshConfig, StartupConfig,
+};
+use crate::cache::codec::{CacheCompression, CacheEncoding};
+use crate::cache::policy::CachePolicyTable;
+use crate::cache::warmup::WarmupConfig;
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::deadline::RequestTimeoutConfig;
+use crate::events::webhooks::WebhookConfig;
+use crate::graphql::GraphQLConfig;
+use crate::models::card::parse_lang_code;
+use crate::query::{QueryLimits, UniqueMode};
+use crate::scryfall::hedge::HedgeConfig;
+use crate::scryfall::preview::PreviewPollConfig;
+use crate::scryfall::retry::RetryConfig;
+use crate::scryfall::upstream::{parse_upstreams, UpstreamConfig};
+use crate::tenant::TenantConfig;
+use crate::tls::TlsConfig;
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -7,11 +34,31 @@ pub struct Config {
     pub server: ServerConfig,
     pub scryfall: ScryfallConfig,
     pub cache: CacheConfig,
+    pub query_limits: QueryLimits,
+    pub batch: BatchConfig,
+    pub idempotency: IdempotencyConfig,
+    pub jobs: JobsConfig,
+    pub health: HealthConfig,
+    pub graphql: GraphQLConfig,
+    pub webhooks: WebhookConfig,
+    pub tenants: TenantConfig,
+    pub audit: AuditConfig,
+    pub request_timeout: RequestTimeoutConfig,
+    pub cors: CorsConfig,
+    pub http_cache: HttpCacheConfig,
 }
 
 #[derive(Debug, Clone)]
 pub struct DatabaseConfig {
     pub url: String,
+    /// Database file used by the SQLite backend
+    pub sqlite_path: String,
+    /// Cards written per transaction by SQLite bulk imports
+    pub sqlite_import_batch_size: usize,
+    /// Cards per multi-row INSERT statement in SQLite bulk imports (1 inserts row by row)
+    pub sqlite_import_rows_per_statement: usize,
+    /// Record per-query metrics and connection pool gauges
+    pub metrics_enabled: bool,
     pub max_connections: u32,
     pub min_connections: u32,
     pub acquire_timeout_ms: u64,
@@ -24,94 +71,866 @@ pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub instance_id: String,
+    /// Secret used to sign pagination cursors. Must be shared by all instances behind a
+    /// load balancer; if unset a random per-process secret is used.
+    pub cursor_secret: Option<String>,
+    /// Also mount the Scryfall-compatible routes under `/scryfall`
+    pub scryfall_compat_prefix: bool,
+    /// Default envelope for the Scryfall-compatible card endpoints
+    pub response_format: ResponseFormat,
+    /// How long background jobs get to stop after the server shuts down
+    pub shutdown_timeout_seconds: u64,
+    /// Replica mode: never write to the database (no migrations, bulk loads, cache
+    /// writes or admin mutations); reads are still served from the database and the API
+    pub read_only: bool,
+    /// Serve Swagger UI at `/docs` (the OpenAPI document is always served)
+    pub swagger_ui: bool,
+    /// Serve `/metrics`, the admin API and the admin panel on this port instead of the API
+    /// port (health checks are served on both)
+    pub admin_port: Option<u16>,
+    /// Bearer token required for `/metrics` and the admin API; unset leaves them open
+    pub admin_token: Option<String>,
+    /// Serve HTTPS on the API port with this certificate; unset serves plain HTTP
+    pub tls: Option<TlsConfig>,
+    /// Serve the gRPC API (`grpc` feature) on this port; unset disables it
+    pub grpc_port: Option<u16>,
+}
+
+/// Log output format. Read from `LOG_FORMAT` before the rest of the configuration so
+/// startup messages use it too; it can't be set from a config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per line, with the fields of the current request span
+    Json,
+}
+
+impl LogFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pretty" | "text" => Some(LogFormat::Pretty),
+            "json" => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Format from `LOG_FORMAT`, `Pretty` if unset or unrecognised
+    pub fn from_env() -> Self {
+        env::var("LOG_FORMAT")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ScryfallConfig {
     pub rate_limit_per_second: u32,
-    pub bulk_data_type: String,
+    /// Bulk data types to import, in order (e.g. `oracle_cards`, `default_cards`, `all_cards`)
+    pub bulk_data_types: Vec<String>,
+    /// Languages imported from bulk data (e.g. `en`, `ja`); empty imports every language.
+    /// Mainly useful with `all_cards`, which holds every localized printing.
+    pub bulk_languages: Vec<String>,
     pub cache_ttl_hours: u32,
+    /// Before reloading stale bulk data, ask Scryfall whether it has published newer data
+    /// since the last import, and skip the download if not
+    pub bulk_check_upstream: bool,
+    /// Directory bulk data files are downloaded to before being imported
+    pub bulk_download_dir: PathBuf,
+    /// Hold a cluster-wide lock (in Redis when configured, a PostgreSQL advisory lock
+    /// otherwise) while loading bulk data, so instances sharing a database don't import
+    /// at the same time
+    pub bulk_distributed_lock: bool,
+    /// Circuit breaker settings, one breaker per Scryfall endpoint
+    pub circuit_breaker: CircuitBreakerConfig,
+    pub retry: RetryConfig,
+    /// Second attempts for lookups slower than usual
+    pub hedge: HedgeConfig,
+    /// Most result pages a search follows upstream before stopping (0 = unlimited)
+    pub search_max_pages: usize,
+    /// Most cards a search collects upstream before stopping (0 = unlimited)
+    pub search_max_cards: usize,
+    /// Servers answering Scryfall API requests, in priority order (e.g. an internal mirror
+    /// before api.scryfall.com); empty means Scryfall's own API at `rate_limit_per_second`
+    pub upstreams: Vec<UpstreamConfig>,
 }
 
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
     pub query_cache_ttl_hours: u32,
+    /// TTL and cache tiers per query class; the `search` class defaults to `query_cache_ttl_hours`
+    pub cache_policies: CachePolicyTable,
     pub query_cache_max_size: usize,
     pub redis: Option<RedisConfig>,
+    /// Write-behind storage of cards fetched from Scryfall
+    pub card_writer: CardWriterConfig,
+    /// Distinct search queries tracked for the top-queries report
+    pub analytics_capacity: usize,
+    /// Deduplication of searches that don't pass `unique`
+    pub default_unique: UniqueMode,
+}
+
+/// Limits for the batch endpoints
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    pub max_ids: usize,
+    pub max_names: usize,
+    pub max_queries: usize,
+    /// Lookups run concurrently per batch request (1-32)
+    pub parallelism: usize,
+    /// Largest request body accepted by the batch endpoints
+    pub max_body_bytes: usize,
+    /// Deepest JSON nesting accepted by the batch endpoints
+    pub max_json_depth: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_ids: 1000,
+            max_names: 50,
+            max_queries: 10,
+            parallelism: 4,
+            max_body_bytes: 1024 * 1024,
+            max_json_depth: 16,
+        }
+    }
+}
+
+impl BatchConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_ids: env_or(settings, "BATCH_MAX_IDS", defaults.max_ids),
+            max_names: env_or(settings, "BATCH_MAX_NAMES", defaults.max_names),
+            max_queries: env_or(settings, "BATCH_MAX_QUERIES", defaults.max_queries),
+            parallelism: env_or(settings, "BATCH_PARALLELISM", defaults.parallelism).clamp(1, 32),
+            max_body_bytes: env_or(settings, "BATCH_MAX_BODY_BYTES", defaults.max_body_bytes)
+                .max(1),
+            max_json_depth: env_or(settings, "BATCH_MAX_JSON_DEPTH", defaults.max_json_depth)
+                .max(2),
+        }
+    }
+}
+
+/// `Idempotency-Key` support on the admin and batch POST endpoints
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    /// Replay stored responses to requests repeating a key; when false the header is
+    /// ignored
+    pub enabled: bool,
+    /// How long a key and its response are kept
+    pub ttl_seconds: u64,
+    /// Largest response kept for replay; retries of bigger ones run again
+    pub max_response_bytes: usize,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_seconds: 3600,
+            max_response_bytes: 4 * 1024 * 1024,
+        }
+    }
+}
+
+impl IdempotencyConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: env_or(settings, "IDEMPOTENCY_ENABLED", defaults.enabled),
+            ttl_seconds: env_or(settings, "IDEMPOTENCY_TTL_SECONDS", defaults.ttl_seconds).max(1),
+            max_response_bytes: env_or(
+                settings,
+                "IDEMPOTENCY_MAX_RESPONSE_BYTES",
+                defaults.max_response_bytes,
+            ),
+        }
+    }
+}
+
+/// Background jobs and startup behaviour
+#[derive(Debug, Clone, Default)]
+pub struct JobsConfig {
+    pub bulk_refresh: BulkRefreshConfig,
+    pub cache_cleanup: CacheCleanupConfig,
+    pub card_repair: CardRepairConfig,
+    pub metrics_refresh: MetricsRefreshConfig,
+    pub warmup: WarmupConfig,
+    pub startup: StartupConfig,
+    pub preview_poll: PreviewPollConfig,
 }
 
 #[derive(Debug, Clone)]
 pub struct RedisConfig {
     pub url: String,
-    pub ttl_seconds: u64,
+    /// Namespace prepended to every key, for sharing one instance between environments
+    pub key_prefix: String,
+    pub card_ttl_seconds: u64,
+    /// Upper bound on query result TTLs; unset to follow each query's cache policy
+    pub query_ttl_seconds: Option<u64>,
+    pub autocomplete_ttl_seconds: u64,
     pub max_value_size_mb: usize,
+    /// Serialization format of new entries (`json` or `msgpack`)
+    pub encoding: CacheEncoding,
+    /// Compression of new entries (`none`, `zstd` or `lz4`)
+    pub compression: CacheCompression,
     pub enabled: bool,
 }
 
+/// Configuration values by environment variable name, layered from the config file, the
+/// environment and command-line flags. Later layers override earlier ones.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    values: HashMap<String, String>,
+}
+
+impl Settings {
+    /// The process environment
+    pub fn from_env() -> Self {
+        let mut settings = Self::default();
+        settings.extend(env::vars());
+        settings
+    }
+
+    /// Layer `values` over the current ones
+    pub fn extend<K: Into<String>, V: Into<String>>(
+        &mut self,
+        values: impl IntoIterator<Item = (K, V)>,
+    ) {
+        self.values.extend(
+            values
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into())),
+        );
+    }
+
+    /// Value of `name`, with the same errors as [`std::env::var`] so components can read
+    /// settings exactly as they read the environment
+    pub fn var(&self, name: &str) -> Result<String, env::VarError> {
+        self.values
+            .get(name)
+            .cloned()
+            .ok_or(env::VarError::NotPresent)
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Settings {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(values: I) -> Self {
+        let mut settings = Self::default();
+        settings.extend(values);
+        settings
+    }
+}
+
+/// Settings that can be given in a config file or as a command-line flag, by
+/// `section.key`, with the environment variable each one stands for.
+///
+/// File and flag values are layered with the environment into a [`Settings`] map under
+/// these variable names, which every component reads its settings from.
+const SETTINGS: &[(&str, &str)] = &[
+    ("server.host", "API_HOST"),
+    ("server.port", "API_PORT"),
+    ("server.instance_id", "INSTANCE_ID"),
+    ("server.cursor_secret", "CURSOR_SECRET"),
+    ("server.scryfall_compat_prefix", "SCRYFALL_COMPAT_PREFIX"),
+    (
+        "server.shutdown_timeout_seconds",
+        "SHUTDOWN_TIMEOUT_SECONDS",
+    ),
+    (
+        "server.scryfall_compat_responses",
+        "SCRYFALL_COMPAT_RESPONSES",
+    ),
+    ("server.read_only", "READ_ONLY"),
+    ("server.swagger_ui", "SWAGGER_UI_ENABLED"),
+    ("server.admin_port", "ADMIN_PORT"),
+    ("server.admin_token", "ADMIN_TOKEN"),
+    ("server.tls_cert_path", "TLS_CERT_PATH"),
+    ("server.tls_key_path", "TLS_KEY_PATH"),
+    ("server.tls_redirect_http_port", "TLS_REDIRECT_HTTP_PORT"),
+    ("server.grpc_port", "GRPC_PORT"),
+    ("cors.enabled", "CORS_ENABLED"),
+    ("cors.allowed_origins", "CORS_ALLOWED_ORIGINS"),
+    ("cors.allowed_methods", "CORS_ALLOWED_METHODS"),
+    ("cors.allowed_headers", "CORS_ALLOWED_HEADERS"),
+    ("cors.allow_credentials", "CORS_ALLOW_CREDENTIALS"),
+    ("cors.max_age_seconds", "CORS_MAX_AGE_SECONDS"),
+    ("http_cache.enabled", "HTTP_CACHE_ENABLED"),
+    (
+        "http_cache.card_max_age_seconds",
+        "HTTP_CACHE_CARD_MAX_AGE_SECONDS",
+    ),
+    (
+        "http_cache.named_max_age_seconds",
+        "HTTP_CACHE_NAMED_MAX_AGE_SECONDS",
+    ),
+    (
+        "http_cache.search_max_age_seconds",
+        "HTTP_CACHE_SEARCH_MAX_AGE_SECONDS",
+    ),
+    ("database.url", "DATABASE_URL"),
+    ("database.sqlite_path", "SQLITE_PATH"),
+    (
+        "database.sqlite_import_batch_size",
+        "SQLITE_IMPORT_BATCH_SIZE",
+    ),
+    (
+        "database.sqlite_import_rows_per_statement",
+        "SQLITE_IMPORT_ROWS_PER_STATEMENT",
+    ),
+    ("database.metrics_enabled", "DATABASE_METRICS_ENABLED"),
+    ("database.max_connections", "DATABASE_MAX_CONNECTIONS"),
+    ("database.min_connections", "DATABASE_MIN_CONNECTIONS"),
+    ("database.acquire_timeout_ms", "DATABASE_ACQUIRE_TIMEOUT_MS"),
+    (
+        "database.idle_timeout_seconds",
+        "DATABASE_IDLE_TIMEOUT_SECONDS",
+    ),
+    (
+        "database.max_lifetime_seconds",
+        "DATABASE_MAX_LIFETIME_SECONDS",
+    ),
+    (
+        "scryfall.rate_limit_per_second",
+        "SCRYFALL_RATE_LIMIT_PER_SECOND",
+    ),
+    ("scryfall.bulk_data_type", "SCRYFALL_BULK_DATA_TYPE"),
+    ("scryfall.bulk_languages", "SCRYFALL_BULK_LANGUAGES"),
+    ("scryfall.cache_ttl_hours", "SCRYFALL_CACHE_TTL_HOURS"),
+    (
+        "scryfall.bulk_check_upstream",
+        "SCRYFALL_BULK_CHECK_UPSTREAM",
+    ),
+    ("scryfall.bulk_download_dir", "SCRYFALL_BULK_DOWNLOAD_DIR"),
+    (
+        "scryfall.bulk_distributed_lock",
+        "SCRYFALL_BULK_DISTRIBUTED_LOCK",
+    ),
+    ("scryfall.max_retries", "SCRYFALL_MAX_RETRIES"),
+    (
+        "scryfall.retry_budget_seconds",
+        "SCRYFALL_RETRY_BUDGET_SECONDS",
+    ),
+    ("scryfall.throttle_seconds", "SCRYFALL_THROTTLE_SECONDS"),
+    ("scryfall.search_max_pages", "SCRYFALL_SEARCH_MAX_PAGES"),
+    ("scryfall.search_max_cards", "SCRYFALL_SEARCH_MAX_CARDS"),
+    ("scryfall.upstreams", "SCRYFALL_UPSTREAMS"),
+    ("scryfall.hedge_enabled", "SCRYFALL_HEDGE_ENABLED"),
+    ("scryfall.hedge_percentile", "SCRYFALL_HEDGE_PERCENTILE"),
+    ("scryfall.hedge_min_delay_ms", "SCRYFALL_HEDGE_MIN_DELAY_MS"),
+    ("scryfall.hedge_max_delay_ms", "SCRYFALL_HEDGE_MAX_DELAY_MS"),
+    ("cache.query_cache_ttl_hours", "QUERY_CACHE_TTL_HOURS"),
+    ("cache.query_cache_max_size", "QUERY_CACHE_MAX_SIZE"),
+    ("cache.query_cache_policy", "QUERY_CACHE_POLICY"),
+    ("cache.analytics_capacity", "QUERY_ANALYTICS_CAPACITY"),
+    ("cache.default_unique", "SEARCH_DEFAULT_UNIQUE"),
+    ("redis.enabled", "REDIS_ENABLED"),
+    ("redis.url", "REDIS_URL"),
+    ("redis.ttl_seconds", "REDIS_TTL_SECONDS"),
+    ("redis.key_prefix", "REDIS_KEY_PREFIX"),
+    ("redis.card_ttl_seconds", "REDIS_CARD_TTL_SECONDS"),
+    ("redis.query_ttl_seconds", "REDIS_QUERY_TTL_SECONDS"),
+    (
+        "redis.autocomplete_ttl_seconds",
+        "REDIS_AUTOCOMPLETE_TTL_SECONDS",
+    ),
+    ("redis.max_value_size_mb", "REDIS_MAX_VALUE_SIZE_MB"),
+    ("redis.encoding", "REDIS_ENCODING"),
+    ("redis.compression", "REDIS_COMPRESSION"),
+    (
+        "circuit_breaker.failure_threshold",
+        "CIRCUIT_BREAKER_FAILURE_THRESHOLD",
+    ),
+    (
+        "circuit_breaker.success_threshold",
+        "CIRCUIT_BREAKER_SUCCESS_THRESHOLD",
+    ),
+    (
+        "circuit_breaker.timeout_seconds",
+        "CIRCUIT_BREAKER_TIMEOUT_SECONDS",
+    ),
+    (
+        "circuit_breaker.half_open_requests",
+        "CIRCUIT_BREAKER_HALF_OPEN_REQUESTS",
+    ),
+    ("query_limits.max_length", "QUERY_MAX_LENGTH"),
+    ("query_limits.max_nesting", "QUERY_MAX_NESTING"),
+    ("query_limits.max_or_clauses", "QUERY_MAX_OR_CLAUSES"),
+    ("query_limits.max_results", "QUERY_MAX_RESULTS"),
+    ("query_limits.timeout_seconds", "QUERY_TIMEOUT_SECONDS"),
+    ("query_limits.max_regex_length", "QUERY_MAX_REGEX_LENGTH"),
+    ("batch.max_ids", "BATCH_MAX_IDS"),
+    ("batch.max_names", "BATCH_MAX_NAMES"),
+    ("batch.max_queries", "BATCH_MAX_QUERIES"),
+    ("batch.parallelism", "BATCH_PARALLELISM"),
+    ("batch.max_body_bytes", "BATCH_MAX_BODY_BYTES"),
+    ("batch.max_json_depth", "BATCH_MAX_JSON_DEPTH"),
+    ("idempotency.enabled", "IDEMPOTENCY_ENABLED"),
+    ("idempotency.ttl_seconds", "IDEMPOTENCY_TTL_SECONDS"),
+    (
+        "idempotency.max_response_bytes",
+        "IDEMPOTENCY_MAX_RESPONSE_BYTES",
+    ),
+    ("jobs.bulk_refresh.enabled", "BULK_REFRESH_ENABLED"),
+    (
+        "jobs.bulk_refresh.interval_hours",
+        "BULK_REFRESH_INTERVAL_HOURS",
+    ),
+    ("jobs.bulk_refresh.mode", "BULK_REFRESH_MODE"),
+    (
+        "jobs.bulk_refresh.incremental_lookback_days",
+        "BULK_REFRESH_INCREMENTAL_LOOKBACK_DAYS",
+    ),
+    ("jobs.cache_cleanup.enabled", "CACHE_CLEANUP_ENABLED"),
+    (
+        "jobs.cache_cleanup.interval_minutes",
+        "CACHE_CLEANUP_INTERVAL_MINUTES",
+    ),
+    (
+        "jobs.cache_cleanup.max_idle_hours",
+        "CACHE_CLEANUP_MAX_IDLE_HOURS",
+    ),
+    (
+        "jobs.cache_cleanup.redis_autocomplete",
+        "CACHE_CLEANUP_REDIS_AUTOCOMPLETE",
+    ),
+    ("jobs.card_repair.enabled", "CARD_REPAIR_ENABLED"),
+    (
+        "jobs.card_repair.interval_hours",
+        "CARD_REPAIR_INTERVAL_HOURS",
+    ),
+    ("jobs.card_repair.max_cards", "CARD_REPAIR_MAX_CARDS"),
+    ("jobs.metrics_refresh.enabled", "METRICS_REFRESH_ENABLED"),
+    (
+        "jobs.metrics_refresh.interval_seconds",
+        "METRICS_REFRESH_INTERVAL_SECONDS",
+    ),
+    ("jobs.card_writer.async", "CARD_WRITER_ASYNC"),
+    ("jobs.card_writer.queue_size", "CARD_WRITER_QUEUE_SIZE"),
+    ("jobs.card_writer.batch_size", "CARD_WRITER_BATCH_SIZE"),
+    (
+        "jobs.card_writer.flush_interval_ms",
+        "CARD_WRITER_FLUSH_INTERVAL_MS",
+    ),
+    ("jobs.warmup.queries_file", "WARMUP_QUERIES_FILE"),
+    ("jobs.preview_poll.enabled", "PREVIEW_POLL_ENABLED"),
+    (
+        "jobs.preview_poll.interval_minutes",
+        "PREVIEW_POLL_INTERVAL_MINUTES",
+    ),
+    (
+        "jobs.preview_poll.lookback_days",
+        "PREVIEW_POLL_LOOKBACK_DAYS",
+    ),
+    (
+        "jobs.preview_poll.rate_limit_per_second",
+        "PREVIEW_POLL_RATE_LIMIT_PER_SECOND",
+    ),
+    ("startup.ready_grace_seconds", "STARTUP_READY_GRACE_SECONDS"),
+    ("health.check_redis", "HEALTH_CHECK_REDIS"),
+    (
+        "health.check_circuit_breakers",
+        "HEALTH_CHECK_CIRCUIT_BREAKERS",
+    ),
+    ("health.check_upstream", "HEALTH_CHECK_UPSTREAM"),
+    (
+        "health.upstream_cache_seconds",
+        "HEALTH_UPSTREAM_CACHE_SECONDS",
+    ),
+    ("graphql.column_projection", "GRAPHQL_COLUMN_PROJECTION"),
+    ("webhooks.urls", "WEBHOOK_URLS"),
+    ("webhooks.events", "WEBHOOK_EVENTS"),
+    ("webhooks.secret", "WEBHOOK_SECRET"),
+    ("webhooks.timeout_seconds", "WEBHOOK_TIMEOUT_SECONDS"),
+    ("webhooks.max_retries", "WEBHOOK_MAX_RETRIES"),
+    ("tenants.enabled", "TENANTS_ENABLED"),
+    ("tenants.header", "TENANT_HEADER"),
+    ("tenants.api_keys", "TENANT_API_KEYS"),
+    (
+        "tenants.rate_limit_per_minute",
+        "TENANT_RATE_LIMIT_PER_MINUTE",
+    ),
+    ("tenants.rate_limits", "TENANT_RATE_LIMITS"),
+    ("tenants.max_tenants", "TENANT_MAX_TENANTS"),
+    ("audit.enabled", "ADMIN_AUDIT_ENABLED"),
+    (
+        "audit.max_parameters_bytes",
+        "ADMIN_AUDIT_MAX_PARAMETERS_BYTES",
+    ),
+    ("request_timeout.default_ms", "REQUEST_TIMEOUT_MS"),
+    ("request_timeout.max_ms", "REQUEST_TIMEOUT_MAX_MS"),
+];
+
+/// Config files looked for in the working directory when none is given
+const DEFAULT_CONFIG_FILES: &[&str] = &["config.toml", "config.yaml", "config.yml"];
+
+/// Environment variable naming the config file
+const CONFIG_FILE_VAR: &str = "CONFIG_FILE";
+
+/// Parsed command-line arguments
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    /// `--config <path>`
+    pub config_file: Option<PathBuf>,
+    /// `--section.key=value` flags, as (environment variable, value)
+    pub overrides: Vec<(&'static str, String)>,
+}
+
+impl CliArgs {
+    /// Parse `--config <path>` and `--section.key <value>` flags (either flag also
+    /// accepts the `--flag=value` form)
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                bail!("Unexpected argument '{}'", arg);
+            };
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .with_context(|| format!("Missing value for --{}", flag))?;
+                    (flag.to_string(), value)
+                }
+            };
+
+            if name == "config" {
+                parsed.config_file = Some(PathBuf::from(value));
+            } else {
+                let var =
+                    setting_var(&name).with_context(|| format!("Unknown setting --{}", name))?;
+                parsed.overrides.push((var, value));
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Environment variable for a `section.key` setting
+fn setting_var(key: &str) -> Option<&'static str> {
+    SETTINGS
+        .iter()
+        .find(|(setting, _)| *setting == key)
+        .map(|(_, var)| *var)
+}
+
+/// Read a TOML or YAML config file (chosen by extension) as (environment variable, value)
+/// pairs
+pub fn read_config_file(path: &Path) -> Result<Vec<(&'static str, String)>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    parse_config(&text, extension)
+        .with_context(|| format!("Invalid config file {}", path.display()))
+}
+
+/// Parse config file contents in the format named by `extension` (`toml`, `yaml`, `yml`)
+fn parse_config(text: &str, extension: &str) -> Result<Vec<(&'static str, String)>> {
+    let document: serde_json::Value = match extension {
+        "toml" => toml::from_str(text)?,
+        "yaml" | "yml" => serde_yaml::from_str(text)?,
+        other => bail!(
+            "Unsupported config file format '{}' (use .toml or .yaml)",
+            other
+        ),
+    };
+
+    let mut values = Vec::new();
+    flatten_settings("", &document, &mut values);
+    values
+        .into_iter()
+        .map(|(key, value)| {
+            let var = setting_var(&key).with_context(|| format!("Unknown setting '{}'", key))?;
+            Ok((var, value))
+        })
+        .collect()
+}
+
+/// Flatten nested sections into `section.key` pairs. Lists become comma-separated values
+/// (e.g. `bulk_data_type = ["oracle_cards", "default_cards"]`).
+fn flatten_settings(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, String)>) {
+    use serde_json::Value;
+
+    let scalar = |value: &Value| match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_settings(&key, value, out);
+            }
+        }
+        Value::Array(items) => {
+            let joined: Vec<String> = items.iter().map(scalar).collect();
+            out.push((prefix.to_string(), joined.join(",")));
+        }
+        Value::Null => {}
+        other => out.push((prefix.to_string(), scalar(other))),
+    }
+}
+
 impl Config {
+    /// Load configuration from, in increasing priority: a config file, the environment
+    /// (including `.env`), and command-line flags.
+    ///
+    /// The config file is `--config <path>`, else `$CONFIG_FILE`, else the first of
+    /// `config.toml`/`config.yaml`/`config.yml` in the working directory, if any.
+    pub fn load(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        dotenvy::dotenv().ok();
+
+        let cli = CliArgs::parse(args)?;
+        let config_file = cli
+            .config_file
+            .clone()
+            .or_else(|| env::var_os(CONFIG_FILE_VAR).map(PathBuf::from))
+            .or_else(|| {
+                DEFAULT_CONFIG_FILES
+                    .iter()
+                    .map(PathBuf::from)
+                    .find(|path| path.is_file())
+            });
+
+        let mut settings = Settings::default();
+        if let Some(path) = config_file {
+            settings.extend(read_config_file(&path)?);
+        }
+        settings.extend(env::vars());
+        settings.extend(cli.overrides);
+
+        Self::from_settings(&settings)
+    }
+
+    /// Load configuration from the environment (including `.env`) alone
     pub fn from_env() -> Result<Self> {
         // Load .env file if it exists
         dotenvy::dotenv().ok();
 
+        Self::from_settings(&Settings::from_env())
+    }
+
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        let query_cache_ttl_hours = settings
+            .var("QUERY_CACHE_TTL_HOURS")
+            .unwrap_or_else(|_| "24".to_string())
+            .parse()
+            .context("QUERY_CACHE_TTL_HOURS must be a valid number")?;
+        let scryfall_rate_limit = settings
+            .var("SCRYFALL_RATE_LIMIT_PER_SECOND")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .context("SCRYFALL_RATE_LIMIT_PER_SECOND must be a valid number")?;
+        let port: u16 = settings
+            .var("API_PORT")
+            .unwrap_or_else(|_| "8080".to_string())
+            .parse()
+            .context("API_PORT must be a valid port number")?;
+        let admin_port: Option<u16> = settings
+            .var("ADMIN_PORT")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|v| v.parse())
+            .transpose()
+            .context("ADMIN_PORT must be a valid port number")?;
+        if admin_port == Some(port) {
+            bail!("ADMIN_PORT must differ from API_PORT ({})", port);
+        }
+        let grpc_port: Option<u16> = settings
+            .var("GRPC_PORT")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|v| v.parse())
+            .transpose()
+            .context("GRPC_PORT must be a valid port number")?;
+        if let Some(grpc_port) = grpc_port {
+            if !cfg!(feature = "grpc") {
+                bail!("GRPC_PORT is set but gRPC support is not compiled (missing grpc feature)");
+            }
+            if grpc_port == port || Some(grpc_port) == admin_port {
+                bail!(
+                    "GRPC_PORT ({}) must differ from API_PORT and ADMIN_PORT",
+                    grpc_port
+                );
+            }
+        }
+        let tls = TlsConfig::from_settings(settings)?;
+        if let Some(redirect_port) = tls.as_ref().and_then(|tls| tls.redirect_http_port) {
+            if redirect_port == port || Some(redirect_port) == admin_port {
+                bail!(
+                    "TLS_REDIRECT_HTTP_PORT ({}) must differ from API_PORT and ADMIN_PORT",
+                    redirect_port
+                );
+            }
+        }
+
         Ok(Config {
             database: DatabaseConfig {
-                url: env::var("DATABASE_URL").context("DATABASE_URL must be set")?,
-                max_connections: env::var("DATABASE_MAX_CONNECTIONS")
+                url: settings
+                    .var("DATABASE_URL")
+                    .context("DATABASE_URL must be set")?,
+                max_connections: settings
+                    .var("DATABASE_MAX_CONNECTIONS")
                     .unwrap_or_else(|_| "10".to_string())
                     .parse()
                     .context("DATABASE_MAX_CONNECTIONS must be a valid number")?,
-                min_connections: env::var("DATABASE_MIN_CONNECTIONS")
+                min_connections: settings
+                    .var("DATABASE_MIN_CONNECTIONS")
                     .unwrap_or_else(|_| "0".to_string())
                     .parse()
                     .context("DATABASE_MIN_CONNECTIONS must be a valid number")?,
-                acquire_timeout_ms: env::var("DATABASE_ACQUIRE_TIMEOUT_MS")
+                acquire_timeout_ms: settings
+                    .var("DATABASE_ACQUIRE_TIMEOUT_MS")
                     .unwrap_or_else(|_| "30000".to_string())
                     .parse()
                     .context("DATABASE_ACQUIRE_TIMEOUT_MS must be a valid number")?,
-                idle_timeout_seconds: env::var("DATABASE_IDLE_TIMEOUT_SECONDS")
+                idle_timeout_seconds: settings
+                    .var("DATABASE_IDLE_TIMEOUT_SECONDS")
                     .unwrap_or_else(|_| "600".to_string())
                     .parse()
                     .context("DATABASE_IDLE_TIMEOUT_SECONDS must be a valid number")?,
-                max_lifetime_seconds: env::var("DATABASE_MAX_LIFETIME_SECONDS")
+                max_lifetime_seconds: settings
+                    .var("DATABASE_MAX_LIFETIME_SECONDS")
                     .unwrap_or_else(|_| "1800".to_string())
                     .parse()
                     .context("DATABASE_MAX_LIFETIME_SECONDS must be a valid number")?,
+                sqlite_path: settings
+                    .var("SQLITE_PATH")
+                    .unwrap_or_else(|_| "./data/scryfall-cache.db".to_string()),
+                sqlite_import_batch_size: env_or::<usize>(
+                    settings,
+                    "SQLITE_IMPORT_BATCH_SIZE",
+                    5000,
+                )
+                .max(1),
+                sqlite_import_rows_per_statement: env_or::<usize>(
+                    settings,
+                    "SQLITE_IMPORT_ROWS_PER_STATEMENT",
+                    1,
+                )
+                .max(1),
+                metrics_enabled: env_or(settings, "DATABASE_METRICS_ENABLED", true),
             },
             server: ServerConfig {
-                host: env::var("API_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-                port: env::var("API_PORT")
-                    .unwrap_or_else(|_| "8080".to_string())
-                    .parse()
-                    .context("API_PORT must be a valid port number")?,
+                host: settings
+                    .var("API_HOST")
+                    .unwrap_or_else(|_| "0.0.0.0".to_string()),
+                port,
                 // Used only for debugging/observability. If unset, fall back to HOSTNAME if
                 // present (e.g. Docker/Kubernetes), otherwise "unknown".
-                instance_id: env::var("INSTANCE_ID")
-                    .or_else(|_| env::var("HOSTNAME"))
+                instance_id: settings
+                    .var("INSTANCE_ID")
+                    .or_else(|_| settings.var("HOSTNAME"))
                     .unwrap_or_else(|_| "unknown".to_string()),
+                cursor_secret: settings.var("CURSOR_SECRET").ok().filter(|s| !s.is_empty()),
+                scryfall_compat_prefix: settings
+                    .var("SCRYFALL_COMPAT_PREFIX")
+                    .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+                    .unwrap_or(false),
+                response_format: ResponseFormat::from_settings(settings),
+                shutdown_timeout_seconds: settings
+                    .var("SHUTDOWN_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .context("SHUTDOWN_TIMEOUT_SECONDS must be a valid number")?,
+                read_only: env_or(settings, "READ_ONLY", false),
+                swagger_ui: env_or(settings, "SWAGGER_UI_ENABLED", true),
+                admin_port,
+                admin_token: settings.var("ADMIN_TOKEN").ok().filter(|s| !s.is_empty()),
+                tls,
+                grpc_port,
             },
             scryfall: ScryfallConfig {
-                rate_limit_per_second: env::var("SCRYFALL_RATE_LIMIT_PER_SECOND")
-                    .unwrap_or_else(|_| "10".to_string())
-                    .parse()
-                    .context("SCRYFALL_RATE_LIMIT_PER_SECOND must be a valid number")?,
-                bulk_data_type: env::var("SCRYFALL_BULK_DATA_TYPE")
-                    .unwrap_or_else(|_| "default_cards".to_string()),
-                cache_ttl_hours: env::var("SCRYFALL_CACHE_TTL_HOURS")
+                rate_limit_per_second: scryfall_rate_limit,
+                bulk_data_types: parse_bulk_data_types(
+                    &settings
+                        .var("SCRYFALL_BULK_DATA_TYPE")
+                        .unwrap_or_else(|_| "default_cards".to_string()),
+                )?,
+                bulk_languages: parse_bulk_languages(
+                    &settings.var("SCRYFALL_BULK_LANGUAGES").unwrap_or_default(),
+                )?,
+                cache_ttl_hours: settings
+                    .var("SCRYFALL_CACHE_TTL_HOURS")
                     .unwrap_or_else(|_| "24".to_string())
                     .parse()
                     .context("SCRYFALL_CACHE_TTL_HOURS must be a valid number")?,
+                bulk_check_upstream: env_or(settings, "SCRYFALL_BULK_CHECK_UPSTREAM", true),
+                bulk_download_dir: settings
+                    .var("SCRYFALL_BULK_DOWNLOAD_DIR")
+                    .ok()
+                    .filter(|v| !v.trim().is_empty())
+                    .map(PathBuf::from)
+                    .unwrap_or_else(env::temp_dir),
+                bulk_distributed_lock: env_or(settings, "SCRYFALL_BULK_DISTRIBUTED_LOCK", false),
+                circuit_breaker: CircuitBreakerConfig::from_settings(settings),
+                retry: RetryConfig::from_settings(settings),
+                hedge: HedgeConfig::from_settings(settings),
+                search_max_pages: env_or(settings, "SCRYFALL_SEARCH_MAX_PAGES", 20),
+                search_max_cards: env_or(settings, "SCRYFALL_SEARCH_MAX_CARDS", 3500),
+                upstreams: parse_upstreams(
+                    &settings.var("SCRYFALL_UPSTREAMS").unwrap_or_default(),
+                    scryfall_rate_limit,
+                )?,
             },
             cache: CacheConfig {
-                query_cache_ttl_hours: env::var("QUERY_CACHE_TTL_HOURS")
-                    .unwrap_or_else(|_| "24".to_string())
-                    .parse()
-                    .context("QUERY_CACHE_TTL_HOURS must be a valid number")?,
-                query_cache_max_size: env::var("QUERY_CACHE_MAX_SIZE")
+                query_cache_ttl_hours,
+                cache_policies: CachePolicyTable::with_search_ttl(query_cache_ttl_hours)
+                    .with_overrides(&settings.var("QUERY_CACHE_POLICY").unwrap_or_default())
+                    .context("QUERY_CACHE_POLICY is invalid")?,
+                query_cache_max_size: settings
+                    .var("QUERY_CACHE_MAX_SIZE")
                     .unwrap_or_else(|_| "10000".to_string())
                     .parse()
                     .context("QUERY_CACHE_MAX_SIZE must be a valid number")?,
-                redis: Self::redis_config_from_env(),
+                redis: Self::redis_config(settings)?,
+                card_writer: CardWriterConfig::from_settings(settings),
+                analytics_capacity: env_or(
+                    settings,
+                    "QUERY_ANALYTICS_CAPACITY",
+                    crate::cache::analytics::DEFAULT_CAPACITY,
+                ),
+                default_unique: UniqueMode::from_settings(settings),
+            },
+            query_limits: QueryLimits::from_settings(settings),
+            batch: BatchConfig::from_settings(settings),
+            idempotency: IdempotencyConfig::from_settings(settings),
+            jobs: JobsConfig {
+                bulk_refresh: BulkRefreshConfig::from_settings(settings),
+                cache_cleanup: CacheCleanupConfig::from_settings(settings),
+                card_repair: CardRepairConfig::from_settings(settings),
+                metrics_refresh: MetricsRefreshConfig::from_settings(settings),
+                warmup: WarmupConfig::from_settings(settings),
+                startup: StartupConfig::from_settings(settings),
+                preview_poll: PreviewPollConfig::from_settings(settings),
             },
+            health: HealthConfig::from_settings(settings),
+            graphql: GraphQLConfig::from_settings(settings),
+            webhooks: WebhookConfig::from_settings(settings),
+            tenants: TenantConfig::from_settings(settings),
+            audit: AuditConfig::from_settings(settings),
+            request_timeout: RequestTimeoutConfig::from_settings(settings),
+            cors: CorsConfig::from_settings(settings)?,
+            http_cache: HttpCacheConfig::from_settings(settings),
         })
     }
 
@@ -119,30 +938,108 @@ impl Config {
         format!("{}:{}", self.server.host, self.server.port)
     }
 
-    fn redis_config_from_env() -> Option<RedisConfig> {
+    /// Address of the admin listener, if `ADMIN_PORT` is set
+    pub fn admin_address(&self) -> Option<String> {
+        self.server
+            .admin_port
+            .map(|port| format!("{}:{}", self.server.host, port))
+    }
+
+    /// Address of the gRPC listener, if `GRPC_PORT` is set
+    pub fn grpc_address(&self) -> Option<String> {
+        self.server
+            .grpc_port
+            .map(|port| format!("{}:{}", self.server.host, port))
+    }
+
+    fn redis_config(settings: &Settings) -> Result<Option<RedisConfig>> {
         // Redis is optional - only enabled if REDIS_ENABLED=true
-        let enabled = env::var("REDIS_ENABLED")
+        let enabled = settings
+            .var("REDIS_ENABLED")
             .unwrap_or_else(|_| "false".to_string())
             .parse::<bool>()
             .unwrap_or(false);
 
         if !enabled {
-            return None;
+            return Ok(None);
         }
 
-        Some(RedisConfig {
-            url: env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
-            ttl_seconds: env::var("REDIS_TTL_SECONDS")
-                .unwrap_or_else(|_| "3600".to_string())
-                .parse()
-                .unwrap_or(3600),
-            max_value_size_mb: env::var("REDIS_MAX_VALUE_SIZE_MB")
+        // REDIS_TTL_SECONDS predates the per-class TTLs and still sets the card TTL
+        let ttl_seconds = env_or(settings, "REDIS_TTL_SECONDS", 3600);
+
+        Ok(Some(RedisConfig {
+            url: settings
+                .var("REDIS_URL")
+                .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+            key_prefix: settings
+                .var("REDIS_KEY_PREFIX")
+                .map(|prefix| prefix.trim_end_matches(':').to_string())
+                .unwrap_or_default(),
+            card_ttl_seconds: env_or(settings, "REDIS_CARD_TTL_SECONDS", ttl_seconds),
+            query_ttl_seconds: settings
+                .var("REDIS_QUERY_TTL_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            autocomplete_ttl_seconds: env_or(settings, "REDIS_AUTOCOMPLETE_TTL_SECONDS", 600),
+            max_value_size_mb: settings
+                .var("REDIS_MAX_VALUE_SIZE_MB")
                 .unwrap_or_else(|_| "10".to_string())
                 .parse()
                 .unwrap_or(10),
+            encoding: CacheEncoding::parse(
+                &settings
+                    .var("REDIS_ENCODING")
+                    .unwrap_or_else(|_| "json".to_string()),
+            )
+            .context("REDIS_ENCODING is invalid")?,
+            compression: CacheCompression::parse(
+                &settings.var("REDIS_COMPRESSION").unwrap_or_default(),
+            )
+            .context("REDIS_COMPRESSION is invalid")?,
             enabled: true,
-        })
+        }))
+    }
+}
+
+/// Parse an optional setting, falling back to `default` when unset or invalid
+fn env_or<T: std::str::FromStr>(settings: &Settings, name: &str, default: T) -> T {
+    settings
+        .var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Parse a comma-separated list of bulk data types, dropping blanks and duplicates
+fn parse_bulk_data_types(value: &str) -> Result<Vec<String>> {
+    let mut types: Vec<String> = Vec::new();
+    for bulk_type in value.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !types.iter().any(|t| t == bulk_type) {
+            types.push(bulk_type.to_string());
+        }
+    }
+
+    if types.is_empty() {
+        anyhow::bail!("SCRYFALL_BULK_DATA_TYPE must list at least one bulk data type");
     }
+    Ok(types)
+}
+
+/// Parse a comma-separated list of language codes to import; empty means every language
+fn parse_bulk_languages(value: &str) -> Result<Vec<String>> {
+    let mut languages: Vec<String> = Vec::new();
+    for code in value.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        let lang = parse_lang_code(code).with_context(|| {
+            format!(
+                "SCRYFALL_BULK_LANGUAGES contains invalid language code '{}'",
+                code
+            )
+        })?;
+        if !languages.contains(&lang) {
+            languages.push(lang);
+        }
+    }
+    Ok(languages)
 }
 
 #[cfg(test)]
@@ -159,24 +1056,201 @@ mod tests {
                 acquire_timeout_ms: 30_000,
                 idle_timeout_seconds: 600,
                 max_lifetime_seconds: 1800,
+                sqlite_path: "./data/scryfall-cache.db".to_string(),
+                sqlite_import_batch_size: 5000,
+                sqlite_import_rows_per_statement: 1,
+                metrics_enabled: true,
             },
             server: ServerConfig {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
                 instance_id: "test-instance".to_string(),
+                cursor_secret: None,
+                scryfall_compat_prefix: false,
+                response_format: ResponseFormat::Native,
+                shutdown_timeout_seconds: 30,
+                read_only: false,
+                swagger_ui: true,
+                admin_port: None,
+                admin_token: None,
+                tls: None,
+                grpc_port: None,
             },
             scryfall: ScryfallConfig {
                 rate_limit_per_second: 10,
-                bulk_data_type: "default_cards".to_string(),
+                bulk_data_types: vec!["default_cards".to_string()],
+                bulk_languages: Vec::new(),
                 cache_ttl_hours: 24,
+                bulk_check_upstream: true,
+                bulk_download_dir: env::temp_dir(),
+                bulk_distributed_lock: false,
+                circuit_breaker: CircuitBreakerConfig::default(),
+                retry: RetryConfig::default(),
+                hedge: HedgeConfig::default(),
+                search_max_pages: 20,
+                search_max_cards: 3500,
+                upstreams: Vec::new(),
             },
             cache: CacheConfig {
                 query_cache_ttl_hours: 24,
+                cache_policies: CachePolicyTable::with_search_ttl(24),
                 query_cache_max_size: 10000,
                 redis: None,
+                card_writer: CardWriterConfig::default(),
+                analytics_capacity: 1000,
+                default_unique: UniqueMode::Cards,
             },
+            query_limits: QueryLimits::default(),
+            batch: BatchConfig::default(),
+            idempotency: IdempotencyConfig::default(),
+            jobs: JobsConfig::default(),
+            health: HealthConfig::default(),
+            graphql: GraphQLConfig::default(),
+            webhooks: WebhookConfig::default(),
+            tenants: TenantConfig::default(),
+            audit: AuditConfig::default(),
+            request_timeout: RequestTimeoutConfig::default(),
+            cors: CorsConfig::default(),
+            http_cache: HttpCacheConfig::default(),
         };
 
         assert_eq!(config.server_address(), "127.0.0.1:3000");
     }
+
+    #[test]
+    fn test_parse_toml_config() {
+        let text = r#"
+            [server]
+            port = 9000
+
+            [scryfall]
+            bulk_data_type = ["oracle_cards", "default_cards"]
+
+            [jobs.bulk_refresh]
+            enabled = false
+        "#;
+
+        let mut values = parse_config(text, "toml").unwrap();
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                ("API_PORT", "9000".to_string()),
+                ("BULK_REFRESH_ENABLED", "false".to_string()),
+                (
+                    "SCRYFALL_BULK_DATA_TYPE",
+                    "oracle_cards,default_cards".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml_config() {
+        let text = "circuit_breaker:\n  failure_threshold: 3\nbatch:\n  max_ids: 500\n";
+        let mut values = parse_config(text, "yaml").unwrap();
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                ("BATCH_MAX_IDS", "500".to_string()),
+                ("CIRCUIT_BREAKER_FAILURE_THRESHOLD", "3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_config_rejects_unknown_settings() {
+        let err = parse_config("[server]\nprot = 9000\n", "toml").unwrap_err();
+        assert!(err.to_string().contains("server.prot"));
+        assert!(parse_config("port: 1", "ini").is_err());
+    }
+
+    #[test]
+    fn test_parse_cli_args() {
+        let args = [
+            "--config",
+            "prod.toml",
+            "--server.port=9000",
+            "--batch.max_ids",
+            "50",
+        ];
+        let cli = CliArgs::parse(args.iter().map(|a| a.to_string())).unwrap();
+        assert_eq!(cli.config_file, Some(PathBuf::from("prod.toml")));
+        assert_eq!(
+            cli.overrides,
+            vec![
+                ("API_PORT", "9000".to_string()),
+                ("BATCH_MAX_IDS", "50".to_string())
+            ]
+        );
+
+        assert!(CliArgs::parse(["--server.prot=1".to_string()]).is_err());
+        assert!(CliArgs::parse(["--server.port".to_string()]).is_err());
+        assert!(CliArgs::parse(["serve".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_settings_layering() {
+        let file = parse_config(
+            r#"
+            [server]
+            port = 9000
+            host = "127.0.0.1"
+
+            [batch]
+            max_ids = 50
+            "#,
+            "toml",
+        )
+        .unwrap();
+        let env = [
+            ("DATABASE_URL", "postgresql://localhost/test"),
+            ("API_PORT", "9100"),
+            ("BATCH_MAX_IDS", "60"),
+        ];
+        let cli = CliArgs::parse(["--batch.max_ids=70".to_string()]).unwrap();
+
+        let mut settings = Settings::default();
+        settings.extend(file);
+        settings.extend(env);
+        settings.extend(cli.overrides);
+
+        let config = Config::from_settings(&settings).unwrap();
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.server.port, 9100);
+        assert_eq!(config.batch.max_ids, 70);
+        assert!(settings.var("REDIS_ENABLED").is_err());
+
+        let missing_database = Settings::from_iter([("API_PORT", "9100")]);
+        assert!(Config::from_settings(&missing_database).is_err());
+    }
+
+    #[test]
+    fn test_parse_bulk_data_types() {
+        assert_eq!(
+            parse_bulk_data_types(" oracle_cards, default_cards,,oracle_cards ").unwrap(),
+            vec!["oracle_cards", "default_cards"]
+        );
+        assert!(parse_bulk_data_types(" , ").is_err());
+    }
+
+    #[test]
+    fn test_parse_bulk_languages() {
+        assert_eq!(
+            parse_bulk_languages("EN, ja,,en").unwrap(),
+            vec!["en", "ja"]
+        );
+        assert!(parse_bulk_languages("").unwrap().is_empty());
+        assert!(parse_bulk_languages("english").is_err());
+    }
+
+    #[test]
+    fn test_parse_log_format() {
+        assert_eq!(LogFormat::parse("json"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse(" JSON "), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("pretty"), Some(LogFormat::Pretty));
+        assert_eq!(LogFormat::parse("xml"), None);
+        assert_eq!(LogFormat::default(), LogFormat::Pretty);
+    }
 }
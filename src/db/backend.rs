@@ -1,68 +1,471 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt};
 use std::any::Any;
+use std::time::Duration;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
-use crate::models::card::Card;
+use crate::db::projection::CardProjection;
+use crate::errors::ServiceResult;
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::models::bulk_import::{BulkImport, BulkImportError};
+use crate::models::card::{Card, CardPart, ExternalId};
+use crate::models::collection::CollectionEntry;
+use crate::models::idempotency::IdempotencyRecord;
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::IntegrityIssue;
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::SchemaVersion;
+use crate::models::sync::SyncRun;
+
+/// Cards per bulk import batch for backends that don't tune it
+pub const DEFAULT_IMPORT_BATCH_SIZE: usize = 500;
+
+/// Stream of cards read incrementally from the database
+pub type CardStream = BoxStream<'static, Result<Card>>;
+
+/// Number of rows buffered between a streaming query and its consumer.
+/// A slow consumer stops the database read once the buffer is full.
+pub const STREAM_BUFFER_ROWS: usize = 256;
+
+/// Adapt the receiving end of a row channel into a `CardStream`
+pub fn channel_stream(rx: mpsc::Receiver<Result<Card>>) -> CardStream {
+    futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    })
+    .boxed()
+}
+
+/// Connection pool usage reported by a backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// Connections checked out of the pool
+    pub active: u32,
+    /// Open connections waiting in the pool
+    pub idle: u32,
+}
+
+/// SQL dialect of a backend, for callers that build their own queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    Postgres,
+    Sqlite,
+}
+
+/// Cluster-wide lock taken with `try_advisory_lock`, held until dropped
+pub struct AdvisoryLock {
+    _guard: Box<dyn Any + Send>,
+}
+
+impl AdvisoryLock {
+    /// Lock released when `guard` is dropped
+    pub fn new(guard: impl Any + Send) -> Self {
+        Self {
+            _guard: Box::new(guard),
+        }
+    }
+}
+
+/// Default number of names returned by autocomplete
+pub const DEFAULT_AUTOCOMPLETE_LIMIT: i64 = 20;
+
+/// Most names a single autocomplete lookup may return
+pub const MAX_AUTOCOMPLETE_LIMIT: i64 = 100;
+
+/// Options of an autocomplete lookup, mirroring Scryfall's `/cards/autocomplete`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutocompleteOptions {
+    /// Most names to return, between 1 and `MAX_AUTOCOMPLETE_LIMIT`
+    pub limit: i64,
+    /// Also suggest extras (see `EXTRA_LAYOUTS`)
+    pub include_extras: bool,
+    /// Leave out names only printed in digital-only products
+    pub exclude_digital: bool,
+}
+
+impl Default for AutocompleteOptions {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_AUTOCOMPLETE_LIMIT,
+            include_extras: false,
+            exclude_digital: false,
+        }
+    }
+}
+
+impl AutocompleteOptions {
+    /// Options with `limit` clamped to `1..=MAX_AUTOCOMPLETE_LIMIT`
+    pub fn new(limit: Option<i64>, include_extras: bool, exclude_digital: bool) -> Self {
+        Self {
+            limit: limit
+                .unwrap_or(DEFAULT_AUTOCOMPLETE_LIMIT)
+                .clamp(1, MAX_AUTOCOMPLETE_LIMIT),
+            include_extras,
+            exclude_digital,
+        }
+    }
+
+    /// Cache key of the names matching `prefix` with these options. The prefix comes last
+    /// so keys of different options can't collide.
+    pub fn cache_key(&self, prefix: &str) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.limit,
+            self.include_extras as u8,
+            self.exclude_digital as u8,
+            prefix.to_lowercase()
+        )
+    }
+}
 
 /// Database backend trait for abstracting PostgreSQL and SQLite
 #[async_trait]
 pub trait DatabaseBackend: Send + Sync {
     /// Insert a batch of cards into the database
-    async fn insert_cards_batch(&self, cards: &[Card]) -> Result<()>;
+    async fn insert_cards_batch(&self, cards: &[Card]) -> ServiceResult<()>;
+
+    /// Start a bulk reload: create an empty `cards_staging` table for
+    /// `insert_cards_staging`, replacing any left over from an interrupted load
+    async fn begin_staging(&self) -> ServiceResult<()>;
+
+    /// Insert a batch of cards into the staging table
+    async fn insert_cards_staging(&self, cards: &[Card]) -> ServiceResult<()>;
+
+    /// Cards a bulk import passes to each `insert_cards_staging` call
+    fn import_batch_size(&self) -> usize {
+        DEFAULT_IMPORT_BATCH_SIZE
+    }
+
+    /// Atomically replace the live cards with the staged ones, keeping live cards that
+    /// weren't staged, and drop the staging table
+    async fn commit_staging(&self) -> ServiceResult<()>;
+
+    /// Drop the staging table without touching the live cards
+    async fn discard_staging(&self) -> ServiceResult<()>;
+
+    /// Take the lock `name` shared by every instance using this database, without
+    /// waiting. Returns `None` while another holder has it.
+    async fn try_advisory_lock(&self, name: &str) -> ServiceResult<Option<AdvisoryLock>>;
 
     /// Get a card by ID
-    async fn get_card_by_id(&self, id: Uuid) -> Result<Option<Card>>;
+    async fn get_card_by_id(&self, id: Uuid) -> ServiceResult<Option<Card>>;
+
+    /// Get a printing by set code and collector number. When several languages share
+    /// the printing, the English one is returned.
+    async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>>;
+
+    /// Get a card by its multiverse, MTG Arena or MTGO id
+    async fn get_card_by_external_id(&self, external_id: ExternalId)
+        -> ServiceResult<Option<Card>>;
 
     /// Get multiple cards by IDs
-    async fn get_cards_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Card>>;
+    async fn get_cards_by_ids(&self, ids: &[Uuid]) -> ServiceResult<Vec<Card>>;
+
+    /// Cards linked to card `id` through Scryfall's `all_parts`, as recorded at import
+    async fn list_card_relations(&self, id: Uuid) -> ServiceResult<Vec<CardPart>>;
+
+    /// Stored cards that fail an integrity check (bad `raw_json`, blank name, missing
+    /// oracle ID, face data the card JSON doesn't have), at most `limit`
+    async fn find_integrity_issues(&self, limit: i64) -> ServiceResult<Vec<IntegrityIssue>>;
 
-    /// Search cards by name (fuzzy search)
-    async fn search_cards_by_name(&self, name: &str, limit: i64) -> Result<Vec<Card>>;
+    /// Get a card by ID, reading only the columns selected by `projection`.
+    /// Backends that don't support projection fall back to a full read.
+    async fn get_card_by_id_projected(
+        &self,
+        id: Uuid,
+        _projection: &CardProjection,
+    ) -> ServiceResult<Option<Card>> {
+        self.get_card_by_id(id).await
+    }
+
+    /// Get multiple cards by IDs, reading only the columns selected by `projection`.
+    /// Backends that don't support projection fall back to a full read.
+    async fn get_cards_by_ids_projected(
+        &self,
+        ids: &[Uuid],
+        _projection: &CardProjection,
+    ) -> ServiceResult<Vec<Card>> {
+        self.get_cards_by_ids(ids).await
+    }
+
+    /// SQL dialect that `execute_raw_query`, `count_query` and `stream_raw_query` expect
+    fn dialect(&self) -> SqlDialect;
+
+    /// SELECT column list reading only the columns selected by `projection`, for
+    /// callers that build their own card queries (e.g. search pages).
+    /// Backends that don't support projection select every column.
+    fn card_columns(&self, _projection: &CardProjection) -> String {
+        "*".to_string()
+    }
+
+    /// Search cards by name (fuzzy search).
+    ///
+    /// With `lang`, only printings in that language match and their localized
+    /// `printed_name` is searched too; without it, English printings sort first.
+    /// Tokens and emblems only match when `include_tokens` is set.
+    async fn search_cards_by_name(
+        &self,
+        name: &str,
+        lang: Option<&str>,
+        include_tokens: bool,
+        limit: i64,
+    ) -> ServiceResult<Vec<Card>>;
 
     /// Autocomplete card names by prefix (case-insensitive)
-    /// Returns up to `limit` card names that start with the given prefix, sorted alphabetically
-    async fn autocomplete_card_names(&self, prefix: &str, limit: i64) -> Result<Vec<String>>;
+    /// Returns up to `options.limit` card names that start with the given prefix, sorted
+    /// alphabetically
+    async fn autocomplete_card_names(
+        &self,
+        prefix: &str,
+        options: &AutocompleteOptions,
+    ) -> ServiceResult<Vec<String>>;
 
-    /// Store a query result in the cache
+    /// Store a query result in the cache of `tenant_id`
     async fn store_query_cache(
         &self,
+        tenant_id: &str,
         query_hash: &str,
+        query_text: &str,
         card_ids: &[Uuid],
         ttl_hours: i32,
-    ) -> Result<()>;
+    ) -> ServiceResult<()>;
 
     /// Get cached query results
-    async fn get_query_cache(&self, query_hash: &str) -> Result<Option<(Vec<Uuid>, i32)>>;
+    async fn get_query_cache(&self, query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>>;
+
+    /// Get cached query results without recording the hit, for read-only deployments
+    async fn peek_query_cache(&self, query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>>;
+
+    /// List query cache entries, most recently cached first
+    async fn list_query_cache(&self, limit: i64) -> ServiceResult<Vec<QueryCacheEntry>>;
+
+    /// Delete one query cache entry, returning whether it existed
+    async fn delete_query_cache(&self, query_hash: &str) -> ServiceResult<bool>;
 
-    /// Record a bulk import operation
-    async fn record_bulk_import(&self, total_cards: i32, source: &str) -> Result<()>;
+    /// Delete every query cache entry, returning how many were removed
+    async fn clear_query_cache(&self) -> ServiceResult<u64>;
+
+    /// Record an imported bulk data file in the import history, returning its id
+    async fn record_bulk_import(&self, import: &BulkImport) -> ServiceResult<i32>;
+
+    /// List recorded bulk imports, most recent first
+    async fn list_bulk_imports(&self, limit: i64) -> ServiceResult<Vec<BulkImport>>;
+
+    /// Record the entries of an import that failed to parse
+    async fn record_bulk_import_errors(
+        &self,
+        import_id: i32,
+        errors: &[BulkImportError],
+    ) -> ServiceResult<()>;
+
+    /// Recorded parse failures of an import in file order, or `None` if there is no such
+    /// import
+    async fn list_bulk_import_errors(
+        &self,
+        import_id: i32,
+    ) -> ServiceResult<Option<Vec<BulkImportError>>>;
+
+    /// Record an admin mutation in the admin audit log
+    async fn record_admin_audit(&self, entry: &AdminAuditEntry) -> ServiceResult<()>;
+
+    /// List admin audit log entries, most recent first, optionally only those of `actor`
+    async fn list_admin_audit(
+        &self,
+        limit: i64,
+        actor: Option<&str>,
+    ) -> ServiceResult<Vec<AdminAuditEntry>>;
+
+    /// Claim idempotency key `key` for a request hashing to `request_hash`, expiring
+    /// `ttl_seconds` from now. Returns `None` once claimed, or the unexpired record already
+    /// stored under the key; expired records are replaced.
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_hash: &str,
+        ttl_seconds: i64,
+    ) -> ServiceResult<Option<IdempotencyRecord>>;
+
+    /// Store the response of a claimed idempotency key, keeping its expiry
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        record: &IdempotencyRecord,
+    ) -> ServiceResult<()>;
+
+    /// Drop an idempotency key, so the next request with it runs again
+    async fn release_idempotency_key(&self, key: &str) -> ServiceResult<()>;
+
+    /// Delete expired idempotency keys, returning how many were removed
+    async fn clean_expired_idempotency_keys(&self) -> ServiceResult<u64>;
+
+    /// Store owned quantities in collection `collection_id` of `tenant_id`, replacing the
+    /// stored quantity of each printing and finish. Entries with quantity 0 are removed.
+    async fn upsert_collection_entries(
+        &self,
+        tenant_id: &str,
+        collection_id: &str,
+        entries: &[CollectionEntry],
+    ) -> ServiceResult<()>;
+
+    /// Entries of collection `collection_id` of `tenant_id`
+    async fn list_collection_entries(
+        &self,
+        tenant_id: &str,
+        collection_id: &str,
+    ) -> ServiceResult<Vec<CollectionEntry>>;
+
+    /// Register `search`, recording `card_ids` as the matches it has before its first run
+    async fn insert_saved_search(&self, search: &SavedSearch, card_ids: &[Uuid])
+        -> ServiceResult<()>;
+
+    /// Saved searches of `tenant_id`, or of every tenant when `None`, oldest first
+    async fn list_saved_searches(&self, tenant_id: Option<&str>)
+        -> ServiceResult<Vec<SavedSearch>>;
+
+    /// Saved search `id` of `tenant_id`
+    async fn get_saved_search(&self, tenant_id: &str, id: Uuid)
+        -> ServiceResult<Option<SavedSearch>>;
+
+    /// Remove saved search `id` of `tenant_id` and its matches; returns whether it existed
+    async fn delete_saved_search(&self, tenant_id: &str, id: Uuid) -> ServiceResult<bool>;
+
+    /// Record an evaluation of saved search `id` that matched `card_ids`: bumps its run
+    /// count and returns the ids that had never matched it before
+    async fn record_saved_search_run(&self, id: Uuid, card_ids: &[Uuid])
+        -> ServiceResult<Vec<Uuid>>;
+
+    /// Cards that first matched saved search `id` in evaluation `run`
+    async fn list_saved_search_matches(&self, id: Uuid, run: i32) -> ServiceResult<Vec<Uuid>>;
+
+    /// Record that the given cards were present in a bulk data file of `bulk_type`
+    async fn record_card_provenance(&self, bulk_type: &str, card_ids: &[Uuid])
+        -> ServiceResult<()>;
 
     /// Clean old cache entries
-    async fn clean_old_cache_entries(&self, hours: i32) -> Result<u64>;
+    async fn clean_old_cache_entries(&self, hours: i32) -> ServiceResult<u64>;
 
     /// Test database connection
-    async fn test_connection(&self) -> Result<()>;
+    async fn test_connection(&self) -> ServiceResult<()>;
 
     /// Execute a raw SQL query and return cards
     /// This is primarily for PostgreSQL; SQLite support may be limited
-    async fn execute_raw_query(&self, sql: &str, params: &[String]) -> Result<Vec<Card>>;
+    async fn execute_raw_query(&self, sql: &str, params: &[String]) -> ServiceResult<Vec<Card>>;
+
+    /// Execute a raw SQL query and stream the resulting cards row by row instead of
+    /// collecting the whole result set in memory.
+    fn stream_raw_query(&self, _sql: String, _params: Vec<String>) -> CardStream {
+        futures::stream::once(async {
+            Err(anyhow::anyhow!("Streaming queries are not supported by this backend"))
+        })
+        .boxed()
+    }
 
     /// Execute a COUNT query and return the result
-    async fn count_query(&self, sql: &str, params: &[String]) -> Result<usize>;
+    async fn count_query(&self, sql: &str, params: &[String]) -> ServiceResult<usize>;
+
+    /// `execute_raw_query` for queries whose cost the caller can't bound up front (regex
+    /// searches), cancelled with `ServiceError::Timeout` once they run for `timeout`.
+    /// Backends that evaluate such queries in bounded time run them as is.
+    async fn execute_raw_query_with_timeout(
+        &self,
+        sql: &str,
+        params: &[String],
+        _timeout: Duration,
+    ) -> ServiceResult<Vec<Card>> {
+        self.execute_raw_query(sql, params).await
+    }
+
+    /// `stream_raw_query` cancelled once it runs for `timeout`, like
+    /// `execute_raw_query_with_timeout`
+    fn stream_raw_query_with_timeout(
+        &self,
+        sql: String,
+        params: Vec<String>,
+        _timeout: Duration,
+    ) -> CardStream {
+        self.stream_raw_query(sql, params)
+    }
+
+    /// `count_query` cancelled once it runs for `timeout`, like
+    /// `execute_raw_query_with_timeout`
+    async fn count_query_with_timeout(
+        &self,
+        sql: &str,
+        params: &[String],
+        _timeout: Duration,
+    ) -> ServiceResult<usize> {
+        self.count_query(sql, params).await
+    }
 
     /// Check if bulk data is loaded (count of cards > 0)
-    async fn check_bulk_data_loaded(&self) -> Result<bool>;
+    async fn check_bulk_data_loaded(&self) -> ServiceResult<bool>;
 
     /// Get the timestamp of the last bulk import
-    async fn get_last_bulk_import(&self) -> Result<Option<chrono::NaiveDateTime>>;
+    async fn get_last_bulk_import(&self) -> ServiceResult<Option<chrono::NaiveDateTime>>;
+
+    /// Append an incremental sync run to the sync ledger
+    async fn record_sync(&self, run: &SyncRun) -> ServiceResult<()>;
+
+    /// Get the start time of the most recent successful incremental sync
+    async fn get_last_sync(&self) -> ServiceResult<Option<chrono::NaiveDateTime>>;
 
     /// Get the total count of cards in the database
-    async fn get_card_count(&self) -> Result<i64>;
+    async fn get_card_count(&self) -> ServiceResult<i64>;
 
     /// Get the total count of query cache entries
-    async fn get_cache_entry_count(&self) -> Result<i64>;
+    async fn get_cache_entry_count(&self) -> ServiceResult<i64>;
+
+    /// Get the on-disk size of the database in bytes
+    async fn get_database_size_bytes(&self) -> ServiceResult<i64>;
+
+    /// Number of query cache entries per tenant, ordered by tenant id
+    async fn count_query_cache_by_tenant(&self) -> ServiceResult<Vec<(String, i64)>>;
+
+    /// Get the total number of hits across query cache entries
+    async fn get_query_cache_hit_count(&self) -> ServiceResult<i64>;
+
+    /// Applied schema migrations, compared with those this build knows about
+    async fn get_schema_version(&self) -> ServiceResult<SchemaVersion>;
+
+    /// Current connection pool usage, for backends whose pool reports it
+    fn pool_status(&self) -> Option<PoolStatus> {
+        None
+    }
 
     /// Return self as Any for downcasting
     fn as_any(&self) -> &dyn Any;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autocomplete_options_limit_is_bounded() {
+        assert_eq!(AutocompleteOptions::new(None, false, false).limit, 20);
+        assert_eq!(AutocompleteOptions::new(Some(5), false, false).limit, 5);
+        assert_eq!(AutocompleteOptions::new(Some(0), false, false).limit, 1);
+        assert_eq!(
+            AutocompleteOptions::new(Some(10_000), false, false).limit,
+            MAX_AUTOCOMPLETE_LIMIT
+        );
+        assert_eq!(
+            AutocompleteOptions::new(None, false, false),
+            AutocompleteOptions::default()
+        );
+    }
+
+    #[test]
+    fn test_autocomplete_cache_key() {
+        let default = AutocompleteOptions::default();
+        assert_eq!(default.cache_key("Light"), "20:0:0:light");
+        let options = AutocompleteOptions::new(Some(50), true, true);
+        assert_eq!(options.cache_key("light"), "50:1:1:light");
+        assert_ne!(default.cache_key("1:1:light"), options.cache_key("light"));
+    }
+}
@@ -1,158 +1,659 @@
-use anyhow::Result;
 use async_trait::async_trait;
+use futures::StreamExt;
 use std::any::Any;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-use crate::db::{Database, DatabaseBackend};
-use crate::metrics::registry::{DATABASE_QUERIES_TOTAL, DATABASE_QUERY_DURATION_SECONDS};
-use crate::models::card::Card;
+use crate::db::{
+    AdvisoryLock, AutocompleteOptions, CardProjection, CardStream, Database, DatabaseBackend,
+    PoolStatus, SqlDialect,
+};
+use crate::errors::ServiceResult;
+use crate::metrics::registry::{
+    DATABASE_CONNECTIONS_ACTIVE, DATABASE_CONNECTIONS_IDLE, DATABASE_QUERIES_TOTAL,
+    DATABASE_QUERY_DURATION_SECONDS,
+};
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::models::bulk_import::{BulkImport, BulkImportError};
+use crate::models::card::{Card, CardPart, ExternalId};
+use crate::models::collection::CollectionEntry;
+use crate::models::idempotency::IdempotencyRecord;
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::IntegrityIssue;
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::SchemaVersion;
+use crate::models::sync::SyncRun;
 
 /// A thin wrapper around a DatabaseBackend that records basic Prometheus metrics
-/// for query counts and durations.
+/// for query counts and durations, labelled with the backend name, and keeps the
+/// connection pool gauges current for backends that report pool usage.
 ///
 /// This keeps performance instrumentation centralized and avoids sprinkling
 /// timing code across all backend implementations.
 pub struct InstrumentedDatabase {
     inner: Database,
+    /// `backend` label value (`postgres` or `sqlite`)
+    backend: &'static str,
 }
 
 impl InstrumentedDatabase {
-    pub fn new(inner: Database) -> Self {
-        Self { inner }
+    pub fn new(inner: Database, backend: &'static str) -> Self {
+        Self { inner, backend }
     }
 
     fn observe(&self, query_type: &'static str, start: Instant) {
-        let seconds = start.elapsed().as_secs_f64();
-        DATABASE_QUERIES_TOTAL
-            .with_label_values(&[query_type])
-            .inc();
-        DATABASE_QUERY_DURATION_SECONDS
-            .with_label_values(&[query_type])
-            .observe(seconds);
+        record_query(self.backend, query_type, start);
+        if let Some(status) = self.inner.pool_status() {
+            DATABASE_CONNECTIONS_ACTIVE.set(status.active as i64);
+            DATABASE_CONNECTIONS_IDLE.set(status.idle as i64);
+        }
     }
 }
 
+fn record_query(backend: &'static str, query_type: &'static str, start: Instant) {
+    let seconds = start.elapsed().as_secs_f64();
+    DATABASE_QUERIES_TOTAL
+        .with_label_values(&[backend, query_type])
+        .inc();
+    DATABASE_QUERY_DURATION_SECONDS
+        .with_label_values(&[backend, query_type])
+        .observe(seconds);
+}
+
 #[async_trait]
 impl DatabaseBackend for InstrumentedDatabase {
-    async fn insert_cards_batch(&self, cards: &[Card]) -> Result<()> {
+    async fn insert_cards_batch(&self, cards: &[Card]) -> ServiceResult<()> {
         let start = Instant::now();
         let res = self.inner.insert_cards_batch(cards).await;
         self.observe("insert", start);
         res
     }
 
-    async fn get_card_by_id(&self, id: Uuid) -> Result<Option<Card>> {
+    async fn begin_staging(&self) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.begin_staging().await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn insert_cards_staging(&self, cards: &[Card]) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.insert_cards_staging(cards).await;
+        self.observe("insert", start);
+        res
+    }
+
+    fn import_batch_size(&self) -> usize {
+        self.inner.import_batch_size()
+    }
+
+    async fn commit_staging(&self) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.commit_staging().await;
+        self.observe("update", start);
+        res
+    }
+
+    async fn discard_staging(&self) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.discard_staging().await;
+        self.observe("delete", start);
+        res
+    }
+
+    async fn try_advisory_lock(&self, name: &str) -> ServiceResult<Option<AdvisoryLock>> {
+        let start = Instant::now();
+        let res = self.inner.try_advisory_lock(name).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_card_by_id(&self, id: Uuid) -> ServiceResult<Option<Card>> {
         let start = Instant::now();
         let res = self.inner.get_card_by_id(id).await;
         self.observe("select", start);
         res
     }
 
-    async fn get_cards_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Card>> {
+    async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>> {
+        let start = Instant::now();
+        let res = self
+            .inner
+            .get_card_by_set_and_number(set_code, collector_number)
+            .await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_card_by_external_id(
+        &self,
+        external_id: ExternalId,
+    ) -> ServiceResult<Option<Card>> {
+        let start = Instant::now();
+        let res = self.inner.get_card_by_external_id(external_id).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_cards_by_ids(&self, ids: &[Uuid]) -> ServiceResult<Vec<Card>> {
         let start = Instant::now();
         let res = self.inner.get_cards_by_ids(ids).await;
         self.observe("select", start);
         res
     }
 
-    async fn search_cards_by_name(&self, name: &str, limit: i64) -> Result<Vec<Card>> {
+    async fn list_card_relations(&self, id: Uuid) -> ServiceResult<Vec<CardPart>> {
+        let start = Instant::now();
+        let res = self.inner.list_card_relations(id).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn find_integrity_issues(&self, limit: i64) -> ServiceResult<Vec<IntegrityIssue>> {
+        let start = Instant::now();
+        let res = self.inner.find_integrity_issues(limit).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_card_by_id_projected(
+        &self,
+        id: Uuid,
+        projection: &CardProjection,
+    ) -> ServiceResult<Option<Card>> {
+        let start = Instant::now();
+        let res = self.inner.get_card_by_id_projected(id, projection).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_cards_by_ids_projected(
+        &self,
+        ids: &[Uuid],
+        projection: &CardProjection,
+    ) -> ServiceResult<Vec<Card>> {
+        let start = Instant::now();
+        let res = self.inner.get_cards_by_ids_projected(ids, projection).await;
+        self.observe("select", start);
+        res
+    }
+
+    fn dialect(&self) -> SqlDialect {
+        self.inner.dialect()
+    }
+
+    fn card_columns(&self, projection: &CardProjection) -> String {
+        self.inner.card_columns(projection)
+    }
+
+    async fn search_cards_by_name(
+        &self,
+        name: &str,
+        lang: Option<&str>,
+        include_tokens: bool,
+        limit: i64,
+    ) -> ServiceResult<Vec<Card>> {
         let start = Instant::now();
-        let res = self.inner.search_cards_by_name(name, limit).await;
+        let res = self
+            .inner
+            .search_cards_by_name(name, lang, include_tokens, limit)
+            .await;
         self.observe("select", start);
         res
     }
 
-    async fn autocomplete_card_names(&self, prefix: &str, limit: i64) -> Result<Vec<String>> {
+    async fn autocomplete_card_names(
+        &self,
+        prefix: &str,
+        options: &AutocompleteOptions,
+    ) -> ServiceResult<Vec<String>> {
         let start = Instant::now();
-        let res = self.inner.autocomplete_card_names(prefix, limit).await;
+        let res = self.inner.autocomplete_card_names(prefix, options).await;
         self.observe("select", start);
         res
     }
 
-    async fn store_query_cache(&self, query_hash: &str, card_ids: &[Uuid], ttl_hours: i32) -> Result<()> {
+    async fn store_query_cache(
+        &self,
+        tenant_id: &str,
+        query_hash: &str,
+        query_text: &str,
+        card_ids: &[Uuid],
+        ttl_hours: i32,
+    ) -> ServiceResult<()> {
         let start = Instant::now();
         let res = self
             .inner
-            .store_query_cache(query_hash, card_ids, ttl_hours)
+            .store_query_cache(tenant_id, query_hash, query_text, card_ids, ttl_hours)
             .await;
         // Upsert/write
         self.observe("insert", start);
         res
     }
 
-    async fn get_query_cache(&self, query_hash: &str) -> Result<Option<(Vec<Uuid>, i32)>> {
+    async fn get_query_cache(&self, query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>> {
         let start = Instant::now();
         let res = self.inner.get_query_cache(query_hash).await;
         self.observe("select", start);
         res
     }
 
-    async fn record_bulk_import(&self, total_cards: i32, source: &str) -> Result<()> {
+    async fn peek_query_cache(&self, query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>> {
+        let start = Instant::now();
+        let res = self.inner.peek_query_cache(query_hash).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn list_query_cache(&self, limit: i64) -> ServiceResult<Vec<QueryCacheEntry>> {
+        let start = Instant::now();
+        let res = self.inner.list_query_cache(limit).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn delete_query_cache(&self, query_hash: &str) -> ServiceResult<bool> {
+        let start = Instant::now();
+        let res = self.inner.delete_query_cache(query_hash).await;
+        self.observe("delete", start);
+        res
+    }
+
+    async fn clear_query_cache(&self) -> ServiceResult<u64> {
+        let start = Instant::now();
+        let res = self.inner.clear_query_cache().await;
+        self.observe("delete", start);
+        res
+    }
+
+    async fn record_bulk_import(&self, import: &BulkImport) -> ServiceResult<i32> {
+        let start = Instant::now();
+        let res = self.inner.record_bulk_import(import).await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn list_bulk_imports(&self, limit: i64) -> ServiceResult<Vec<BulkImport>> {
+        let start = Instant::now();
+        let res = self.inner.list_bulk_imports(limit).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn record_bulk_import_errors(
+        &self,
+        import_id: i32,
+        errors: &[BulkImportError],
+    ) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self
+            .inner
+            .record_bulk_import_errors(import_id, errors)
+            .await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn list_bulk_import_errors(
+        &self,
+        import_id: i32,
+    ) -> ServiceResult<Option<Vec<BulkImportError>>> {
+        let start = Instant::now();
+        let res = self.inner.list_bulk_import_errors(import_id).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn record_admin_audit(&self, entry: &AdminAuditEntry) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.record_admin_audit(entry).await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn list_admin_audit(
+        &self,
+        limit: i64,
+        actor: Option<&str>,
+    ) -> ServiceResult<Vec<AdminAuditEntry>> {
+        let start = Instant::now();
+        let res = self.inner.list_admin_audit(limit, actor).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_hash: &str,
+        ttl_seconds: i64,
+    ) -> ServiceResult<Option<IdempotencyRecord>> {
+        let start = Instant::now();
+        let res = self
+            .inner
+            .claim_idempotency_key(key, request_hash, ttl_seconds)
+            .await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        record: &IdempotencyRecord,
+    ) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.complete_idempotency_key(key, record).await;
+        self.observe("update", start);
+        res
+    }
+
+    async fn release_idempotency_key(&self, key: &str) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.release_idempotency_key(key).await;
+        self.observe("delete", start);
+        res
+    }
+
+    async fn clean_expired_idempotency_keys(&self) -> ServiceResult<u64> {
+        let start = Instant::now();
+        let res = self.inner.clean_expired_idempotency_keys().await;
+        self.observe("delete", start);
+        res
+    }
+
+    async fn upsert_collection_entries(
+        &self,
+        tenant_id: &str,
+        collection_id: &str,
+        entries: &[CollectionEntry],
+    ) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self
+            .inner
+            .upsert_collection_entries(tenant_id, collection_id, entries)
+            .await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn list_collection_entries(
+        &self,
+        tenant_id: &str,
+        collection_id: &str,
+    ) -> ServiceResult<Vec<CollectionEntry>> {
+        let start = Instant::now();
+        let res = self
+            .inner
+            .list_collection_entries(tenant_id, collection_id)
+            .await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn insert_saved_search(
+        &self,
+        search: &SavedSearch,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.insert_saved_search(search, card_ids).await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn list_saved_searches(
+        &self,
+        tenant_id: Option<&str>,
+    ) -> ServiceResult<Vec<SavedSearch>> {
+        let start = Instant::now();
+        let res = self.inner.list_saved_searches(tenant_id).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_saved_search(
+        &self,
+        tenant_id: &str,
+        id: Uuid,
+    ) -> ServiceResult<Option<SavedSearch>> {
+        let start = Instant::now();
+        let res = self.inner.get_saved_search(tenant_id, id).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn delete_saved_search(&self, tenant_id: &str, id: Uuid) -> ServiceResult<bool> {
+        let start = Instant::now();
+        let res = self.inner.delete_saved_search(tenant_id, id).await;
+        self.observe("delete", start);
+        res
+    }
+
+    async fn record_saved_search_run(
+        &self,
+        id: Uuid,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<Vec<Uuid>> {
+        let start = Instant::now();
+        let res = self.inner.record_saved_search_run(id, card_ids).await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn list_saved_search_matches(&self, id: Uuid, run: i32) -> ServiceResult<Vec<Uuid>> {
+        let start = Instant::now();
+        let res = self.inner.list_saved_search_matches(id, run).await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn record_card_provenance(
+        &self,
+        bulk_type: &str,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<()> {
         let start = Instant::now();
-        let res = self.inner.record_bulk_import(total_cards, source).await;
+        let res = self.inner.record_card_provenance(bulk_type, card_ids).await;
         self.observe("insert", start);
         res
     }
 
-    async fn clean_old_cache_entries(&self, hours: i32) -> Result<u64> {
+    async fn clean_old_cache_entries(&self, hours: i32) -> ServiceResult<u64> {
         let start = Instant::now();
         let res = self.inner.clean_old_cache_entries(hours).await;
         self.observe("delete", start);
         res
     }
 
-    async fn test_connection(&self) -> Result<()> {
+    async fn test_connection(&self) -> ServiceResult<()> {
         let start = Instant::now();
         let res = self.inner.test_connection().await;
         self.observe("select", start);
         res
     }
 
-    async fn execute_raw_query(&self, sql: &str, params: &[String]) -> Result<Vec<Card>> {
+    async fn execute_raw_query(&self, sql: &str, params: &[String]) -> ServiceResult<Vec<Card>> {
         let start = Instant::now();
         let res = self.inner.execute_raw_query(sql, params).await;
         self.observe("select", start);
         res
     }
 
-    async fn count_query(&self, sql: &str, params: &[String]) -> Result<usize> {
+    fn stream_raw_query(&self, sql: String, params: Vec<String>) -> CardStream {
+        let start = Instant::now();
+        let backend = self.backend;
+        // Recorded once the stream has been fully consumed
+        let finished = futures::stream::once(async move {
+            record_query(backend, "select", start);
+        })
+        .filter_map(|_| async { None });
+        self.inner.stream_raw_query(sql, params).chain(finished).boxed()
+    }
+
+    async fn count_query(&self, sql: &str, params: &[String]) -> ServiceResult<usize> {
         let start = Instant::now();
         let res = self.inner.count_query(sql, params).await;
         self.observe("select", start);
         res
     }
 
-    async fn check_bulk_data_loaded(&self) -> Result<bool> {
+    async fn execute_raw_query_with_timeout(
+        &self,
+        sql: &str,
+        params: &[String],
+        timeout: Duration,
+    ) -> ServiceResult<Vec<Card>> {
+        let start = Instant::now();
+        let res = self
+            .inner
+            .execute_raw_query_with_timeout(sql, params, timeout)
+            .await;
+        self.observe("select", start);
+        res
+    }
+
+    fn stream_raw_query_with_timeout(
+        &self,
+        sql: String,
+        params: Vec<String>,
+        timeout: Duration,
+    ) -> CardStream {
+        let start = Instant::now();
+        let backend = self.backend;
+        // Recorded once the stream has been fully consumed
+        let finished = futures::stream::once(async move {
+            record_query(backend, "select", start);
+        })
+        .filter_map(|_| async { None });
+        self.inner
+            .stream_raw_query_with_timeout(sql, params, timeout)
+            .chain(finished)
+            .boxed()
+    }
+
+    async fn count_query_with_timeout(
+        &self,
+        sql: &str,
+        params: &[String],
+        timeout: Duration,
+    ) -> ServiceResult<usize> {
+        let start = Instant::now();
+        let res = self
+            .inner
+            .count_query_with_timeout(sql, params, timeout)
+            .await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn check_bulk_data_loaded(&self) -> ServiceResult<bool> {
         let start = Instant::now();
         let res = self.inner.check_bulk_data_loaded().await;
         self.observe("select", start);
         res
     }
 
-    async fn get_last_bulk_import(&self) -> Result<Option<chrono::NaiveDateTime>> {
+    async fn get_last_bulk_import(&self) -> ServiceResult<Option<chrono::NaiveDateTime>> {
         let start = Instant::now();
         let res = self.inner.get_last_bulk_import().await;
         self.observe("select", start);
         res
     }
 
-    async fn get_card_count(&self) -> Result<i64> {
+    async fn record_sync(&self, run: &SyncRun) -> ServiceResult<()> {
+        let start = Instant::now();
+        let res = self.inner.record_sync(run).await;
+        self.observe("insert", start);
+        res
+    }
+
+    async fn get_last_sync(&self) -> ServiceResult<Option<chrono::NaiveDateTime>> {
+        let start = Instant::now();
+        let res = self.inner.get_last_sync().await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_card_count(&self) -> ServiceResult<i64> {
         let start = Instant::now();
         let res = self.inner.get_card_count().await;
         self.observe("select", start);
         res
     }
 
-    async fn get_cache_entry_count(&self) -> Result<i64> {
+    async fn get_cache_entry_count(&self) -> ServiceResult<i64> {
         let start = Instant::now();
         let res = self.inner.get_cache_entry_count().await;
         self.observe("select", start);
         res
     }
 
+    async fn get_database_size_bytes(&self) -> ServiceResult<i64> {
+        let start = Instant::now();
+        let res = self.inner.get_database_size_bytes().await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn count_query_cache_by_tenant(&self) -> ServiceResult<Vec<(String, i64)>> {
+        let start = Instant::now();
+        let res = self.inner.count_query_cache_by_tenant().await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_query_cache_hit_count(&self) -> ServiceResult<i64> {
+        let start = Instant::now();
+        let res = self.inner.get_query_cache_hit_count().await;
+        self.observe("select", start);
+        res
+    }
+
+    async fn get_schema_version(&self) -> ServiceResult<SchemaVersion> {
+        let start = Instant::now();
+        let res = self.inner.get_schema_version().await;
+        self.observe("select", start);
+        res
+    }
+
+    fn pool_status(&self) -> Option<PoolStatus> {
+        self.inner.pool_status()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self.inner.as_any()
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::TestDb;
+    use crate::db::SqlDialect;
+
+    #[tokio::test]
+    async fn test_records_queries_by_backend() {
+        let status = PoolStatus { active: 3, idle: 2 };
+        let inner = TestDb::new(SqlDialect::Postgres).with_pool_status(status);
+        let db = InstrumentedDatabase::new(std::sync::Arc::new(inner), "instrumented_test");
+        let queries = || {
+            DATABASE_QUERIES_TOTAL
+                .with_label_values(&["instrumented_test", "select"])
+                .get()
+        };
+
+        assert!(!db.check_bulk_data_loaded().await.unwrap());
+        // Failed queries are counted too
+        assert!(db.get_card_by_id(Uuid::nil()).await.is_err());
+        assert_eq!(queries(), 2);
+        assert_eq!(db.pool_status(), Some(status));
+        assert_eq!(DATABASE_CONNECTIONS_ACTIVE.get(), 3);
+        assert_eq!(DATABASE_CONNECTIONS_IDLE.get(), 2);
+    }
+}
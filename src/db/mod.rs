@@ -1,7 +1,11 @@
 pub mod backend;
 mod instrumented;
+pub mod projection;
 pub mod schema;
 
+#[cfg(test)]
+pub(crate) mod testing;
+
 #[cfg(feature = "postgres")]
 pub mod postgres;
 
@@ -17,7 +21,10 @@ pub use postgres::PostgresBackend;
 #[cfg(feature = "sqlite")]
 pub use sqlite::SqliteBackend;
 
-pub use backend::DatabaseBackend;
+pub use backend::{
+    AdvisoryLock, AutocompleteOptions, CardStream, DatabaseBackend, PoolStatus, SqlDialect,
+};
+pub use projection::CardProjection;
 use instrumented::InstrumentedDatabase;
 
 /// Database connection type - polymorphic over backends
@@ -30,18 +37,30 @@ pub async fn init_database(config: &crate::config::DatabaseConfig) -> Result<Dat
     let pool = postgres::connection::create_pool(config).await?;
     postgres::connection::test_connection(&pool).await?;
     let backend = PostgresBackend::new(pool);
-    let base: Database = Arc::new(backend) as Database;
-    Ok(Arc::new(InstrumentedDatabase::new(base)) as Database)
+    Ok(instrument(Arc::new(backend), "postgres", config))
 }
 
 #[cfg(feature = "sqlite")]
 pub async fn init_database(config: &crate::config::DatabaseConfig) -> Result<Database> {
     tracing::info!("Initializing SQLite backend");
-    let database_path =
-        std::env::var("SQLITE_PATH").unwrap_or_else(|_| "./data/scryfall-cache.db".to_string());
+    let pool = sqlite::connection::create_pool(&config.sqlite_path)?;
+    let backend = SqliteBackend::new(pool)?.with_import_tuning(
+        config.sqlite_import_batch_size,
+        config.sqlite_import_rows_per_statement,
+    );
+    Ok(instrument(Arc::new(backend), "sqlite", config))
+}
 
-    let pool = sqlite::connection::create_pool(&database_path)?;
-    let backend = SqliteBackend::new(pool)?;
-    let base: Database = Arc::new(backend) as Database;
-    Ok(Arc::new(InstrumentedDatabase::new(base)) as Database)
+/// Wrap `backend` to record query metrics, unless disabled by `DATABASE_METRICS_ENABLED`
+fn instrument(
+    backend: Database,
+    name: &'static str,
+    config: &crate::config::DatabaseConfig,
+) -> Database {
+    if config.metrics_enabled {
+        Arc::new(InstrumentedDatabase::new(backend, name))
+    } else {
+        tracing::info!("Database query metrics are disabled");
+        backend
+    }
 }
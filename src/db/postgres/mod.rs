@@ -1,13 +1,26 @@
 pub mod connection;
 pub mod queries;
 
-use anyhow::Result;
 use async_trait::async_trait;
 use sqlx::PgPool;
+use std::time::Duration;
 use uuid::Uuid;
 
-use crate::db::backend::DatabaseBackend;
-use crate::models::card::Card;
+use crate::db::backend::{
+    AdvisoryLock, AutocompleteOptions, CardStream, DatabaseBackend, PoolStatus, SqlDialect,
+};
+use crate::db::projection::CardProjection;
+use crate::errors::{ServiceError, ServiceResult};
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::models::bulk_import::{BulkImport, BulkImportError};
+use crate::models::card::{Card, CardPart, ExternalId};
+use crate::models::collection::CollectionEntry;
+use crate::models::idempotency::IdempotencyRecord;
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::IntegrityIssue;
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::SchemaVersion;
+use crate::models::sync::SyncRun;
 
 pub struct PostgresBackend {
     pool: PgPool,
@@ -23,75 +36,481 @@ impl PostgresBackend {
     }
 }
 
+/// Report a query cancelled by its statement timeout as `ServiceError::Timeout`
+fn timeout_error(error: anyhow::Error) -> ServiceError {
+    if queries::is_statement_cancelled(&error) {
+        ServiceError::Timeout("database query".to_string())
+    } else {
+        ServiceError::Db(error)
+    }
+}
+
 #[async_trait]
 impl DatabaseBackend for PostgresBackend {
-    async fn insert_cards_batch(&self, cards: &[Card]) -> Result<()> {
-        queries::insert_cards_batch(&self.pool, cards).await
+    async fn insert_cards_batch(&self, cards: &[Card]) -> ServiceResult<()> {
+        queries::insert_cards_batch(&self.pool, cards)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn begin_staging(&self) -> ServiceResult<()> {
+        queries::begin_staging(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn insert_cards_staging(&self, cards: &[Card]) -> ServiceResult<()> {
+        queries::insert_cards_staging(&self.pool, cards)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn commit_staging(&self) -> ServiceResult<()> {
+        queries::swap_staging(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn discard_staging(&self) -> ServiceResult<()> {
+        queries::discard_staging(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn try_advisory_lock(&self, name: &str) -> ServiceResult<Option<AdvisoryLock>> {
+        let lock = queries::try_advisory_lock(&self.pool, name)
+            .await
+            .map_err(ServiceError::Db)?;
+        Ok(lock.map(AdvisoryLock::new))
+    }
+
+    async fn get_card_by_id(&self, id: Uuid) -> ServiceResult<Option<Card>> {
+        queries::get_card_by_id(&self.pool, id)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>> {
+        queries::get_card_by_set_and_number(&self.pool, set_code, collector_number)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn get_card_by_external_id(
+        &self,
+        external_id: ExternalId,
+    ) -> ServiceResult<Option<Card>> {
+        queries::get_card_by_external_id(&self.pool, external_id)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn get_cards_by_ids(&self, ids: &[Uuid]) -> ServiceResult<Vec<Card>> {
+        queries::get_cards_by_ids(&self.pool, ids)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn list_card_relations(&self, id: Uuid) -> ServiceResult<Vec<CardPart>> {
+        queries::list_card_relations(&self.pool, id)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn find_integrity_issues(&self, limit: i64) -> ServiceResult<Vec<IntegrityIssue>> {
+        queries::find_integrity_issues(&self.pool, limit)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn get_card_by_id_projected(
+        &self,
+        id: Uuid,
+        projection: &CardProjection,
+    ) -> ServiceResult<Option<Card>> {
+        queries::get_card_by_id_projected(&self.pool, id, projection)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn get_card_by_id(&self, id: Uuid) -> Result<Option<Card>> {
-        queries::get_card_by_id(&self.pool, id).await
+    async fn get_cards_by_ids_projected(
+        &self,
+        ids: &[Uuid],
+        projection: &CardProjection,
+    ) -> ServiceResult<Vec<Card>> {
+        queries::get_cards_by_ids_projected(&self.pool, ids, projection)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    fn dialect(&self) -> SqlDialect {
+        SqlDialect::Postgres
     }
 
-    async fn get_cards_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Card>> {
-        queries::get_cards_by_ids(&self.pool, ids).await
+    fn card_columns(&self, projection: &CardProjection) -> String {
+        queries::projected_columns(projection)
     }
 
-    async fn search_cards_by_name(&self, name: &str, limit: i64) -> Result<Vec<Card>> {
-        queries::search_cards_by_name(&self.pool, name, limit).await
+    async fn search_cards_by_name(
+        &self,
+        name: &str,
+        lang: Option<&str>,
+        include_tokens: bool,
+        limit: i64,
+    ) -> ServiceResult<Vec<Card>> {
+        queries::search_cards_by_name(&self.pool, name, lang, include_tokens, limit)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn autocomplete_card_names(&self, prefix: &str, limit: i64) -> Result<Vec<String>> {
-        queries::autocomplete_card_names(&self.pool, prefix, limit).await
+    async fn autocomplete_card_names(
+        &self,
+        prefix: &str,
+        options: &AutocompleteOptions,
+    ) -> ServiceResult<Vec<String>> {
+        queries::autocomplete_card_names(&self.pool, prefix, options)
+            .await
+            .map_err(ServiceError::Db)
     }
 
     async fn store_query_cache(
         &self,
+        tenant_id: &str,
         query_hash: &str,
+        query_text: &str,
         card_ids: &[Uuid],
         ttl_hours: i32,
-    ) -> Result<()> {
-        queries::store_query_cache(&self.pool, query_hash, card_ids, ttl_hours).await
+    ) -> ServiceResult<()> {
+        queries::store_query_cache(
+            &self.pool, tenant_id, query_hash, query_text, card_ids, ttl_hours,
+        )
+        .await
+        .map_err(ServiceError::Db)
+    }
+
+    async fn get_query_cache(&self, query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>> {
+        queries::get_query_cache(&self.pool, query_hash)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn peek_query_cache(&self, query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>> {
+        queries::peek_query_cache(&self.pool, query_hash)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn list_query_cache(&self, limit: i64) -> ServiceResult<Vec<QueryCacheEntry>> {
+        queries::list_query_cache(&self.pool, limit)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn delete_query_cache(&self, query_hash: &str) -> ServiceResult<bool> {
+        queries::delete_query_cache(&self.pool, query_hash)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn clear_query_cache(&self) -> ServiceResult<u64> {
+        queries::clear_query_cache(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn record_bulk_import(&self, import: &BulkImport) -> ServiceResult<i32> {
+        queries::record_bulk_import(&self.pool, import)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn list_bulk_imports(&self, limit: i64) -> ServiceResult<Vec<BulkImport>> {
+        queries::list_bulk_imports(&self.pool, limit)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn record_bulk_import_errors(
+        &self,
+        import_id: i32,
+        errors: &[BulkImportError],
+    ) -> ServiceResult<()> {
+        queries::record_bulk_import_errors(&self.pool, import_id, errors)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn list_bulk_import_errors(
+        &self,
+        import_id: i32,
+    ) -> ServiceResult<Option<Vec<BulkImportError>>> {
+        queries::list_bulk_import_errors(&self.pool, import_id)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn record_admin_audit(&self, entry: &AdminAuditEntry) -> ServiceResult<()> {
+        queries::record_admin_audit(&self.pool, entry)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn list_admin_audit(
+        &self,
+        limit: i64,
+        actor: Option<&str>,
+    ) -> ServiceResult<Vec<AdminAuditEntry>> {
+        queries::list_admin_audit(&self.pool, limit, actor)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_hash: &str,
+        ttl_seconds: i64,
+    ) -> ServiceResult<Option<IdempotencyRecord>> {
+        queries::claim_idempotency_key(&self.pool, key, request_hash, ttl_seconds)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        record: &IdempotencyRecord,
+    ) -> ServiceResult<()> {
+        queries::complete_idempotency_key(&self.pool, key, record)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn release_idempotency_key(&self, key: &str) -> ServiceResult<()> {
+        queries::release_idempotency_key(&self.pool, key)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn clean_expired_idempotency_keys(&self) -> ServiceResult<u64> {
+        queries::clean_expired_idempotency_keys(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn upsert_collection_entries(
+        &self,
+        tenant_id: &str,
+        collection_id: &str,
+        entries: &[CollectionEntry],
+    ) -> ServiceResult<()> {
+        queries::upsert_collection_entries(&self.pool, tenant_id, collection_id, entries)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn list_collection_entries(
+        &self,
+        tenant_id: &str,
+        collection_id: &str,
+    ) -> ServiceResult<Vec<CollectionEntry>> {
+        queries::list_collection_entries(&self.pool, tenant_id, collection_id)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn insert_saved_search(
+        &self,
+        search: &SavedSearch,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<()> {
+        queries::insert_saved_search(&self.pool, search, card_ids)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn list_saved_searches(
+        &self,
+        tenant_id: Option<&str>,
+    ) -> ServiceResult<Vec<SavedSearch>> {
+        queries::list_saved_searches(&self.pool, tenant_id)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn get_saved_search(
+        &self,
+        tenant_id: &str,
+        id: Uuid,
+    ) -> ServiceResult<Option<SavedSearch>> {
+        queries::get_saved_search(&self.pool, tenant_id, id)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn delete_saved_search(&self, tenant_id: &str, id: Uuid) -> ServiceResult<bool> {
+        queries::delete_saved_search(&self.pool, tenant_id, id)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn record_saved_search_run(
+        &self,
+        id: Uuid,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<Vec<Uuid>> {
+        queries::record_saved_search_run(&self.pool, id, card_ids)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn list_saved_search_matches(&self, id: Uuid, run: i32) -> ServiceResult<Vec<Uuid>> {
+        queries::list_saved_search_matches(&self.pool, id, run)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn record_card_provenance(
+        &self,
+        bulk_type: &str,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<()> {
+        queries::record_card_provenance(&self.pool, bulk_type, card_ids)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn clean_old_cache_entries(&self, hours: i32) -> ServiceResult<u64> {
+        queries::clean_old_cache_entries(&self.pool, hours)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn test_connection(&self) -> ServiceResult<()> {
+        connection::test_connection(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn execute_raw_query(&self, sql: &str, params: &[String]) -> ServiceResult<Vec<Card>> {
+        queries::execute_raw_query(&self.pool, sql, params)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    fn stream_raw_query(&self, sql: String, params: Vec<String>) -> CardStream {
+        queries::stream_raw_query(self.pool.clone(), sql, params)
+    }
+
+    async fn count_query(&self, sql: &str, params: &[String]) -> ServiceResult<usize> {
+        queries::count_query(&self.pool, sql, params)
+            .await
+            .map_err(ServiceError::Db)
+    }
+
+    async fn execute_raw_query_with_timeout(
+        &self,
+        sql: &str,
+        params: &[String],
+        timeout: Duration,
+    ) -> ServiceResult<Vec<Card>> {
+        queries::execute_raw_query_with_timeout(&self.pool, sql, params, timeout)
+            .await
+            .map_err(timeout_error)
+    }
+
+    fn stream_raw_query_with_timeout(
+        &self,
+        sql: String,
+        params: Vec<String>,
+        timeout: Duration,
+    ) -> CardStream {
+        queries::stream_raw_query_with_timeout(self.pool.clone(), sql, params, timeout)
+    }
+
+    async fn count_query_with_timeout(
+        &self,
+        sql: &str,
+        params: &[String],
+        timeout: Duration,
+    ) -> ServiceResult<usize> {
+        queries::count_query_with_timeout(&self.pool, sql, params, timeout)
+            .await
+            .map_err(timeout_error)
+    }
+
+    async fn check_bulk_data_loaded(&self) -> ServiceResult<bool> {
+        queries::check_bulk_data_loaded(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn get_query_cache(&self, query_hash: &str) -> Result<Option<(Vec<Uuid>, i32)>> {
-        queries::get_query_cache(&self.pool, query_hash).await
+    async fn get_last_bulk_import(&self) -> ServiceResult<Option<chrono::NaiveDateTime>> {
+        queries::get_last_bulk_import(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn record_bulk_import(&self, total_cards: i32, source: &str) -> Result<()> {
-        queries::record_bulk_import(&self.pool, total_cards, source).await
+    async fn record_sync(&self, run: &SyncRun) -> ServiceResult<()> {
+        queries::record_sync(&self.pool, run)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn clean_old_cache_entries(&self, hours: i32) -> Result<u64> {
-        queries::clean_old_cache_entries(&self.pool, hours).await
+    async fn get_last_sync(&self) -> ServiceResult<Option<chrono::NaiveDateTime>> {
+        queries::get_last_sync(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn test_connection(&self) -> Result<()> {
-        connection::test_connection(&self.pool).await
+    async fn get_card_count(&self) -> ServiceResult<i64> {
+        queries::get_card_count(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn execute_raw_query(&self, sql: &str, params: &[String]) -> Result<Vec<Card>> {
-        queries::execute_raw_query(&self.pool, sql, params).await
+    async fn get_cache_entry_count(&self) -> ServiceResult<i64> {
+        queries::get_cache_entry_count(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn count_query(&self, sql: &str, params: &[String]) -> Result<usize> {
-        queries::count_query(&self.pool, sql, params).await
+    async fn get_database_size_bytes(&self) -> ServiceResult<i64> {
+        queries::get_database_size_bytes(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn check_bulk_data_loaded(&self) -> Result<bool> {
-        queries::check_bulk_data_loaded(&self.pool).await
+    async fn count_query_cache_by_tenant(&self) -> ServiceResult<Vec<(String, i64)>> {
+        queries::count_query_cache_by_tenant(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn get_last_bulk_import(&self) -> Result<Option<chrono::NaiveDateTime>> {
-        queries::get_last_bulk_import(&self.pool).await
+    async fn get_query_cache_hit_count(&self) -> ServiceResult<i64> {
+        queries::get_query_cache_hit_count(&self.pool)
+            .await
+            .map_err(ServiceError::Db)
     }
 
-    async fn get_card_count(&self) -> Result<i64> {
-        queries::get_card_count(&self.pool).await
+    async fn get_schema_version(&self) -> ServiceResult<SchemaVersion> {
+        let applied = queries::list_applied_migrations(&self.pool)
+            .await
+            .map_err(ServiceError::Db)?;
+        let known = crate::db::schema::MIGRATOR.iter().map(|m| m.version);
+        Ok(SchemaVersion::new("postgres", known, applied))
     }
 
-    async fn get_cache_entry_count(&self) -> Result<i64> {
-        queries::get_cache_entry_count(&self.pool).await
+    fn pool_status(&self) -> Option<PoolStatus> {
+        let idle = self.pool.num_idle() as u32;
+        Some(PoolStatus {
+            active: self.pool.size().saturating_sub(idle),
+            idle,
+        })
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
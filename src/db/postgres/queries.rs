@@ -1,11 +1,31 @@
 use anyhow::{Context, Result};
-use sqlx::{PgPool, Postgres, QueryBuilder};
+use futures::StreamExt;
+use sqlx::{Connection, PgConnection, PgPool, Postgres, QueryBuilder};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::warn;
 use uuid::Uuid;
 
-use crate::models::card::Card;
+use crate::db::backend::{channel_stream, AutocompleteOptions, CardStream, STREAM_BUFFER_ROWS};
+use crate::db::projection::CardProjection;
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::models::bulk_import::{BulkImport, BulkImportError};
+use crate::models::card::{Card, CardPart, ExternalId, EXTRA_LAYOUTS};
+use crate::models::collection::{CollectionEntry, Finish};
+use crate::models::idempotency::IdempotencyRecord;
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::IntegrityIssue;
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::AppliedMigration;
+use crate::models::sync::SyncRun;
 
 /// Insert a batch of cards into the database
 pub async fn insert_cards_batch(pool: &PgPool, cards: &[Card]) -> Result<()> {
+    insert_cards_into(pool, "cards", cards).await
+}
+
+/// Upsert a batch of cards into `table` (`cards` or `cards_staging`)
+async fn insert_cards_into(pool: &PgPool, table: &str, cards: &[Card]) -> Result<()> {
     if cards.is_empty() {
         return Ok(());
     }
@@ -15,16 +35,21 @@ pub async fn insert_cards_batch(pool: &PgPool, cards: &[Card]) -> Result<()> {
     // Insert in chunks to avoid enormous SQL statements while still reducing per-row overhead.
     const CHUNK_SIZE: usize = 250;
     for chunk in cards.chunks(CHUNK_SIZE) {
-        let mut builder = QueryBuilder::<Postgres>::new(
+        let mut builder = QueryBuilder::<Postgres>::new(format!(
             r#"
-            INSERT INTO cards (
+            INSERT INTO {table} (
                 id, oracle_id, name, mana_cost, cmc, type_line, oracle_text,
                 colors, color_identity, set_code, set_name, collector_number,
                 rarity, power, toughness, loyalty, keywords, prices, image_uris,
-                card_faces, legalities, released_at, raw_json
+                card_faces, legalities, released_at, raw_json, lang,
+                printed_name, printed_type_line, printed_text, layout,
+                multiverse_ids, arena_id, mtgo_id, mtgo_foil_id,
+                power_num, toughness_num, loyalty_num,
+                price_usd, price_eur, price_tix, face_names,
+                edhrec_rank, penny_rank
             )
-            "#,
-        );
+            "#
+        ));
 
         builder.push_values(chunk, |mut b, card| {
             b.push_bind(card.id)
@@ -49,7 +74,25 @@ pub async fn insert_cards_batch(pool: &PgPool, cards: &[Card]) -> Result<()> {
                 .push_bind(&card.card_faces)
                 .push_bind(&card.legalities)
                 .push_bind(card.released_at)
-                .push_bind(&card.raw_json);
+                .push_bind(&card.raw_json)
+                .push_bind(&card.lang)
+                .push_bind(&card.printed_name)
+                .push_bind(&card.printed_type_line)
+                .push_bind(&card.printed_text)
+                .push_bind(&card.layout)
+                .push_bind(&card.multiverse_ids)
+                .push_bind(card.arena_id)
+                .push_bind(card.mtgo_id)
+                .push_bind(card.mtgo_foil_id)
+                .push_bind(card.power_num)
+                .push_bind(card.toughness_num)
+                .push_bind(card.loyalty_num)
+                .push_bind(card.price_usd)
+                .push_bind(card.price_eur)
+                .push_bind(card.price_tix)
+                .push_bind(&card.face_names)
+                .push_bind(card.edhrec_rank)
+                .push_bind(card.penny_rank);
         });
 
         builder.push(
@@ -77,6 +120,24 @@ pub async fn insert_cards_batch(pool: &PgPool, cards: &[Card]) -> Result<()> {
                 legalities = EXCLUDED.legalities,
                 released_at = EXCLUDED.released_at,
                 raw_json = EXCLUDED.raw_json,
+                lang = EXCLUDED.lang,
+                printed_name = EXCLUDED.printed_name,
+                printed_type_line = EXCLUDED.printed_type_line,
+                printed_text = EXCLUDED.printed_text,
+                layout = EXCLUDED.layout,
+                multiverse_ids = EXCLUDED.multiverse_ids,
+                arena_id = EXCLUDED.arena_id,
+                mtgo_id = EXCLUDED.mtgo_id,
+                mtgo_foil_id = EXCLUDED.mtgo_foil_id,
+                power_num = EXCLUDED.power_num,
+                toughness_num = EXCLUDED.toughness_num,
+                loyalty_num = EXCLUDED.loyalty_num,
+                price_usd = EXCLUDED.price_usd,
+                price_eur = EXCLUDED.price_eur,
+                price_tix = EXCLUDED.price_tix,
+                face_names = EXCLUDED.face_names,
+                edhrec_rank = EXCLUDED.edhrec_rank,
+                penny_rank = EXCLUDED.penny_rank,
                 updated_at = NOW()
             "#,
         );
@@ -88,6 +149,8 @@ pub async fn insert_cards_batch(pool: &PgPool, cards: &[Card]) -> Result<()> {
             .context("Failed to insert card batch")?;
     }
 
+    replace_card_relations(&mut transaction, cards).await?;
+
     transaction
         .commit()
         .await
@@ -96,6 +159,165 @@ pub async fn insert_cards_batch(pool: &PgPool, cards: &[Card]) -> Result<()> {
     Ok(())
 }
 
+/// Replace the `card_relations` rows of `cards` with the links in their `all_parts`
+async fn replace_card_relations(
+    transaction: &mut sqlx::Transaction<'_, Postgres>,
+    cards: &[Card],
+) -> Result<()> {
+    let ids: Vec<Uuid> = cards.iter().map(|card| card.id).collect();
+    sqlx::query("DELETE FROM card_relations WHERE card_id = ANY($1)")
+        .bind(&ids)
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to clear card relations")?;
+
+    let relations: Vec<(Uuid, CardPart)> = cards
+        .iter()
+        .flat_map(|card| {
+            card.related_parts()
+                .into_iter()
+                .map(move |part| (card.id, part))
+        })
+        .collect();
+    // Five bound parameters per row, well under PostgreSQL's limit of 65535
+    for chunk in relations.chunks(1000) {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO card_relations (card_id, related_id, component, name, type_line) ",
+        );
+        builder.push_values(chunk, |mut b, (card_id, part)| {
+            b.push_bind(card_id)
+                .push_bind(part.id)
+                .push_bind(&part.component)
+                .push_bind(&part.name)
+                .push_bind(&part.type_line);
+        });
+        builder.push(" ON CONFLICT DO NOTHING");
+        builder
+            .build()
+            .execute(&mut **transaction)
+            .await
+            .context("Failed to insert card relations")?;
+    }
+    Ok(())
+}
+
+/// Create an empty `cards_staging` table with the same columns and primary key as
+/// `cards`, dropping any left behind by an interrupted load. Secondary indexes are
+/// built later by `swap_staging`, so the bulk import doesn't have to maintain them.
+pub async fn begin_staging(pool: &PgPool) -> Result<()> {
+    for sql in [
+        "DROP TABLE IF EXISTS cards_staging",
+        "CREATE TABLE cards_staging (LIKE cards INCLUDING DEFAULTS INCLUDING CONSTRAINTS INCLUDING GENERATED)",
+        "ALTER TABLE cards_staging ADD PRIMARY KEY (id)",
+    ] {
+        sqlx::query(sql)
+            .execute(pool)
+            .await
+            .context("Failed to create cards_staging table")?;
+    }
+    Ok(())
+}
+
+/// Insert a batch of cards into `cards_staging`
+pub async fn insert_cards_staging(pool: &PgPool, cards: &[Card]) -> Result<()> {
+    insert_cards_into(pool, "cards_staging", cards).await
+}
+
+/// Replace `cards` with `cards_staging` in one transaction.
+///
+/// The secondary indexes of `cards` are first rebuilt on the staging table under
+/// `<name>_staging` names. Then, holding a lock that blocks writers (readers keep seeing
+/// the old table), cards missing from the staged data (such as ones fetched from the
+/// Scryfall API) are carried over, the tables are swapped by renaming, and the indexes,
+/// primary key and `updated_at` trigger take the names the migrations gave them.
+pub async fn swap_staging(pool: &PgPool) -> Result<()> {
+    let indexes: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT indexname, indexdef FROM pg_indexes
+        WHERE schemaname = current_schema() AND tablename = 'cards' AND indexname <> 'cards_pkey'
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list cards indexes")?;
+
+    for (name, definition) in &indexes {
+        let sql = staged_index_sql(name, definition)
+            .with_context(|| format!("Unexpected definition for index {}", name))?;
+        sqlx::query(&sql)
+            .execute(pool)
+            .await
+            .with_context(|| format!("Failed to build staging index for {}", name))?;
+    }
+
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+    let mut statements = vec![
+        "LOCK TABLE cards IN EXCLUSIVE MODE".to_string(),
+        r#"
+        INSERT INTO cards_staging
+        SELECT c.* FROM cards c
+        WHERE NOT EXISTS (SELECT 1 FROM cards_staging s WHERE s.id = c.id)
+        "#
+        .to_string(),
+        "ALTER TABLE cards RENAME TO cards_old".to_string(),
+        "ALTER TABLE cards_staging RENAME TO cards".to_string(),
+        "DROP TABLE cards_old".to_string(),
+        "ALTER TABLE cards RENAME CONSTRAINT cards_staging_pkey TO cards_pkey".to_string(),
+    ];
+    statements.extend(
+        indexes
+            .iter()
+            .map(|(name, _)| format!("ALTER INDEX {}_staging RENAME TO {}", name, name)),
+    );
+    statements.push(
+        r#"
+        CREATE TRIGGER update_cards_updated_at
+            BEFORE UPDATE ON cards
+            FOR EACH ROW
+            EXECUTE FUNCTION update_updated_at_column()
+        "#
+        .to_string(),
+    );
+    for sql in &statements {
+        sqlx::query(sql)
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to swap in cards_staging")?;
+    }
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit staging swap")?;
+
+    sqlx::query("ANALYZE cards")
+        .execute(pool)
+        .await
+        .context("Failed to analyze cards")?;
+    Ok(())
+}
+
+/// Drop `cards_staging` after a failed load, leaving `cards` untouched
+pub async fn discard_staging(pool: &PgPool) -> Result<()> {
+    sqlx::query("DROP TABLE IF EXISTS cards_staging")
+        .execute(pool)
+        .await
+        .context("Failed to drop cards_staging table")?;
+    Ok(())
+}
+
+/// Rewrite a `cards` index definition from `pg_indexes` to build the same index on
+/// `cards_staging` as `<name>_staging`
+fn staged_index_sql(name: &str, definition: &str) -> Option<String> {
+    let (head, tail) = definition.split_once(" ON ")?;
+    let head = head.strip_suffix(name)?;
+    let (table, rest) = tail.split_once(' ')?;
+    let table = table.strip_suffix("cards")?;
+    Some(format!(
+        "{}{}_staging ON {}cards_staging {}",
+        head, name, table, rest
+    ))
+}
+
 /// Get a card by ID
 pub async fn get_card_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Card>> {
     let card = sqlx::query_as::<_, Card>("SELECT * FROM cards WHERE id = $1")
@@ -107,6 +329,112 @@ pub async fn get_card_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Card>> {
     Ok(card)
 }
 
+/// Cards linked to card `id` through `all_parts`, grouped by how they are linked
+pub async fn list_card_relations(pool: &PgPool, id: Uuid) -> Result<Vec<CardPart>> {
+    let rows: Vec<(Uuid, String, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT related_id, component, name, type_line
+        FROM card_relations
+        WHERE card_id = $1
+        ORDER BY component, name, related_id
+        "#,
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list card relations")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, component, name, type_line)| CardPart {
+            id,
+            component,
+            name,
+            type_line,
+        })
+        .collect())
+}
+
+/// Cards whose stored row is inconsistent, first issue per card, at most `limit`
+pub async fn find_integrity_issues(pool: &PgPool, limit: i64) -> Result<Vec<IntegrityIssue>> {
+    let rows: Vec<(Uuid, String, String)> = sqlx::query_as(
+        r#"
+        SELECT id, name, issue
+        FROM (
+            SELECT id, COALESCE(name, '') AS name,
+                CASE
+                    WHEN jsonb_typeof(raw_json) IS DISTINCT FROM 'object' THEN 'invalid_raw_json'
+                    WHEN raw_json->>'id' IS DISTINCT FROM id::text THEN 'id_mismatch'
+                    WHEN name IS NULL OR btrim(name) = '' THEN 'missing_name'
+                    WHEN oracle_id IS NULL AND layout IS DISTINCT FROM 'reversible_card'
+                        THEN 'missing_oracle_id'
+                    WHEN card_faces IS NOT NULL
+                        AND (jsonb_typeof(card_faces) <> 'array' OR raw_json->'card_faces' IS NULL)
+                        THEN 'orphan_card_faces'
+                END AS issue
+            FROM cards
+        ) checked
+        WHERE issue IS NOT NULL
+        ORDER BY id
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to scan cards for integrity issues")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, name, issue)| IntegrityIssue { id, name, issue })
+        .collect())
+}
+
+/// Get a printing by set code and collector number (uses idx_cards_set_collector)
+pub async fn get_card_by_set_and_number(
+    pool: &PgPool,
+    set_code: &str,
+    collector_number: &str,
+) -> Result<Option<Card>> {
+    let card = sqlx::query_as::<_, Card>(
+        r#"
+        SELECT * FROM cards
+        WHERE set_code = $1 AND collector_number = $2
+        ORDER BY lang IS DISTINCT FROM 'en'
+        LIMIT 1
+        "#,
+    )
+    .bind(set_code.to_lowercase())
+    .bind(collector_number)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to fetch card by set and collector number")?;
+
+    Ok(card)
+}
+
+/// Get a card by one of its external ids (uses the GIN index on multiverse_ids and
+/// the btree indexes on the Arena/MTGO ids)
+pub async fn get_card_by_external_id(
+    pool: &PgPool,
+    external_id: ExternalId,
+) -> Result<Option<Card>> {
+    let condition = match external_id {
+        ExternalId::Multiverse(_) => "multiverse_ids @> ARRAY[$1]",
+        ExternalId::Arena(_) => "arena_id = $1",
+        ExternalId::Mtgo(_) => "(mtgo_id = $1 OR mtgo_foil_id = $1)",
+    };
+    let sql = format!("SELECT * FROM cards WHERE {} LIMIT 1", condition);
+
+    let card = sqlx::query_as::<_, Card>(&sql)
+        .bind(external_id.value())
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch card by external id")?;
+
+    Ok(card)
+}
+
 /// Get multiple cards by IDs
 pub async fn get_cards_by_ids(pool: &PgPool, ids: &[Uuid]) -> Result<Vec<Card>> {
     if ids.is_empty() {
@@ -124,18 +452,85 @@ pub async fn get_cards_by_ids(pool: &PgPool, ids: &[Uuid]) -> Result<Vec<Card>>
     Ok(cards)
 }
 
+/// SELECT column list for `projection`, with typed literals for omitted JSON columns
+pub fn projected_columns(projection: &CardProjection) -> String {
+    projection.select_list("NULL::jsonb", "'{}'::jsonb")
+}
+
+/// Get a card by ID, reading only the projected columns
+pub async fn get_card_by_id_projected(
+    pool: &PgPool,
+    id: Uuid,
+    projection: &CardProjection,
+) -> Result<Option<Card>> {
+    let sql = format!(
+        "SELECT {} FROM cards WHERE id = $1",
+        projected_columns(projection)
+    );
+
+    let card = sqlx::query_as::<_, Card>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch projected card by ID")?;
+
+    Ok(card)
+}
+
+/// Get multiple cards by IDs, reading only the projected columns
+pub async fn get_cards_by_ids_projected(
+    pool: &PgPool,
+    ids: &[Uuid],
+    projection: &CardProjection,
+) -> Result<Vec<Card>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql = format!(
+        "SELECT {} FROM cards WHERE id = ANY($1)",
+        projected_columns(projection)
+    );
+
+    let cards = sqlx::query_as::<_, Card>(&sql)
+        .bind(ids)
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch projected cards by IDs")?;
+
+    Ok(cards)
+}
+
 /// Search cards by name (fuzzy match)
-pub async fn search_cards_by_name(pool: &PgPool, name: &str, limit: i64) -> Result<Vec<Card>> {
+pub async fn search_cards_by_name(
+    pool: &PgPool,
+    name: &str,
+    lang: Option<&str>,
+    include_tokens: bool,
+    limit: i64,
+) -> Result<Vec<Card>> {
     let cards = sqlx::query_as::<_, Card>(
         r#"
         SELECT * FROM cards
-        WHERE to_tsvector('english', name) @@ plainto_tsquery('english', $1)
-        ORDER BY name
+        WHERE (
+            to_tsvector('english', name) @@ plainto_tsquery('english', $1)
+            OR face_names @> ARRAY[LOWER($1)]
+            OR ($3::text IS NOT NULL AND LOWER(printed_name) = LOWER($1))
+        )
+        AND ($3::text IS NULL OR lang = $3)
+        AND ($4 OR layout IS NULL OR layout NOT IN ('token', 'double_faced_token', 'emblem'))
+        ORDER BY
+            LOWER(name) = LOWER($1) DESC,
+            COALESCE(face_names @> ARRAY[LOWER($1)], false) DESC,
+            lang IS DISTINCT FROM 'en',
+            name
         LIMIT $2
         "#,
     )
     .bind(name)
     .bind(limit)
+    .bind(lang)
+    .bind(include_tokens)
     .fetch_all(pool)
     .await
     .context("Failed to search cards by name")?;
@@ -148,26 +543,34 @@ pub async fn search_cards_by_name(pool: &PgPool, name: &str, limit: i64) -> Resu
 pub async fn autocomplete_card_names(
     pool: &PgPool,
     prefix: &str,
-    limit: i64,
+    options: &AutocompleteOptions,
 ) -> Result<Vec<String>> {
     // Use ILIKE for case-insensitive prefix matching
     // The idx_cards_name GIN index can be used for prefix searches in PostgreSQL
     let pattern = format!("{}%", prefix);
 
-    let names: Vec<(String,)> = sqlx::query_as(
-        r#"
-        SELECT DISTINCT name
-        FROM cards
-        WHERE name ILIKE $1
-        ORDER BY name
-        LIMIT $2
-        "#,
-    )
-    .bind(&pattern)
-    .bind(limit)
-    .fetch_all(pool)
-    .await
-    .context("Failed to autocomplete card names")?;
+    let mut conditions = vec!["name ILIKE $1".to_string()];
+    if !options.include_extras {
+        let layouts: Vec<String> = EXTRA_LAYOUTS.iter().map(|l| format!("'{}'", l)).collect();
+        conditions.push(format!(
+            "(layout IS NULL OR layout NOT IN ({}))",
+            layouts.join(", ")
+        ));
+    }
+    if options.exclude_digital {
+        conditions.push("NOT COALESCE((raw_json->>'digital')::boolean, false)".to_string());
+    }
+    let sql = format!(
+        "SELECT DISTINCT name FROM cards WHERE {} ORDER BY name LIMIT $2",
+        conditions.join(" AND ")
+    );
+
+    let names: Vec<(String,)> = sqlx::query_as(&sql)
+        .bind(&pattern)
+        .bind(options.limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to autocomplete card names")?;
 
     Ok(names.into_iter().map(|(name,)| name).collect())
 }
@@ -175,23 +578,31 @@ pub async fn autocomplete_card_names(
 /// Store query cache entry
 pub async fn store_query_cache(
     pool: &PgPool,
+    tenant_id: &str,
     query_hash: &str,
+    query_text: &str,
     card_ids: &[Uuid],
-    _ttl_hours: i32,
+    ttl_hours: i32,
 ) -> Result<()> {
     sqlx::query(
         r#"
-        INSERT INTO query_cache (query_hash, query_text, result_ids, total_cards)
-        VALUES ($1, '', $2, $3)
+        INSERT INTO query_cache
+            (query_hash, query_text, result_ids, total_cards, expires_at, tenant_id)
+        VALUES ($1, $2, $3, $4, NOW() + INTERVAL '1 hour' * $5, $6)
         ON CONFLICT (query_hash) DO UPDATE SET
+            query_text = EXCLUDED.query_text,
             result_ids = EXCLUDED.result_ids,
             total_cards = EXCLUDED.total_cards,
+            expires_at = EXCLUDED.expires_at,
             last_accessed = NOW()
         "#,
     )
     .bind(query_hash)
+    .bind(query_text)
     .bind(card_ids)
     .bind(card_ids.len() as i32)
+    .bind(ttl_hours)
+    .bind(tenant_id)
     .execute(pool)
     .await
     .context("Failed to store query cache")?;
@@ -204,8 +615,8 @@ pub async fn get_query_cache(pool: &PgPool, query_hash: &str) -> Result<Option<(
     let result: Option<(Vec<Uuid>, i32)> = sqlx::query_as(
         r#"
         UPDATE query_cache
-        SET last_accessed = NOW()
-        WHERE query_hash = $1
+        SET last_accessed = NOW(), hit_count = hit_count + 1
+        WHERE query_hash = $1 AND (expires_at IS NULL OR expires_at > NOW())
         RETURNING result_ids, total_cards
         "#,
     )
@@ -217,19 +628,764 @@ pub async fn get_query_cache(pool: &PgPool, query_hash: &str) -> Result<Option<(
     Ok(result)
 }
 
-/// Record bulk data import
-pub async fn record_bulk_import(pool: &PgPool, total_cards: i32, source: &str) -> Result<()> {
+/// Get cached query results without updating the hit count or access time
+pub async fn peek_query_cache(pool: &PgPool, query_hash: &str) -> Result<Option<(Vec<Uuid>, i32)>> {
+    let result: Option<(Vec<Uuid>, i32)> = sqlx::query_as(
+        r#"
+        SELECT result_ids, total_cards
+        FROM query_cache
+        WHERE query_hash = $1 AND (expires_at IS NULL OR expires_at > NOW())
+        "#,
+    )
+    .bind(query_hash)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to peek query cache")?;
+
+    Ok(result)
+}
+
+/// List query cache entries, most recently cached first
+pub async fn list_query_cache(pool: &PgPool, limit: i64) -> Result<Vec<QueryCacheEntry>> {
+    let rows: Vec<(
+        String,
+        String,
+        i32,
+        i64,
+        Option<chrono::NaiveDateTime>,
+        i64,
+        Option<chrono::NaiveDateTime>,
+        String,
+    )> = sqlx::query_as(
+        r#"
+        SELECT query_hash, query_text, total_cards, hit_count, created_at,
+               COALESCE(EXTRACT(EPOCH FROM (NOW() - created_at))::BIGINT, 0),
+               expires_at, tenant_id
+        FROM query_cache
+        ORDER BY created_at DESC NULLS LAST
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list query cache")?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                query_hash,
+                query_text,
+                total_cards,
+                hit_count,
+                created_at,
+                age_seconds,
+                expires_at,
+                tenant_id,
+            )| {
+                QueryCacheEntry {
+                    query_hash,
+                    tenant_id,
+                    query_text,
+                    result_count: total_cards as i64,
+                    hit_count,
+                    created_at,
+                    age_seconds,
+                    expires_at,
+                }
+            },
+        )
+        .collect())
+}
+
+/// Delete one query cache entry
+pub async fn delete_query_cache(pool: &PgPool, query_hash: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM query_cache WHERE query_hash = $1")
+        .bind(query_hash)
+        .execute(pool)
+        .await
+        .context("Failed to delete query cache entry")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete every query cache entry
+pub async fn clear_query_cache(pool: &PgPool) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM query_cache")
+        .execute(pool)
+        .await
+        .context("Failed to clear query cache")?;
+
+    Ok(result.rows_affected())
+}
+
+/// Record bulk data import, returning its id
+pub async fn record_bulk_import(pool: &PgPool, import: &BulkImport) -> Result<i32> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO bulk_data_metadata
+            (bulk_type, download_uri, updated_at, total_cards, file_size_bytes, duration_ms, failed_cards)
+        VALUES ($1, $2, NOW(), $3, $4, $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(&import.bulk_type)
+    .bind(&import.source)
+    .bind(import.total_cards)
+    .bind(import.file_size_bytes)
+    .bind(import.duration_ms)
+    .bind(import.failed_cards)
+    .fetch_one(pool)
+    .await
+    .context("Failed to record bulk import")
+}
+
+/// List recorded bulk imports, most recent first
+pub async fn list_bulk_imports(pool: &PgPool, limit: i64) -> Result<Vec<BulkImport>> {
+    let rows: Vec<(
+        i32,
+        String,
+        String,
+        i32,
+        i32,
+        i64,
+        i64,
+        Option<chrono::NaiveDateTime>,
+    )> = sqlx::query_as(
+        r#"
+        SELECT id, bulk_type, download_uri, total_cards, failed_cards, file_size_bytes, duration_ms,
+               imported_at
+        FROM bulk_data_metadata
+        ORDER BY imported_at DESC NULLS LAST, id DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list bulk imports")?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                bulk_type,
+                source,
+                total_cards,
+                failed_cards,
+                file_size_bytes,
+                duration_ms,
+                imported_at,
+            )| BulkImport {
+                id: Some(id),
+                bulk_type,
+                source,
+                total_cards,
+                failed_cards,
+                file_size_bytes,
+                duration_ms,
+                imported_at,
+            },
+        )
+        .collect())
+}
+
+/// Record the entries of an import that failed to parse
+pub async fn record_bulk_import_errors(
+    pool: &PgPool,
+    import_id: i32,
+    errors: &[BulkImportError],
+) -> Result<()> {
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder = QueryBuilder::<Postgres>::new(
+        "INSERT INTO bulk_import_errors (import_id, position, card_id, card_name, category, message) ",
+    );
+    builder.push_values(errors, |mut row, error| {
+        row.push_bind(import_id)
+            .push_bind(error.position)
+            .push_bind(&error.card_id)
+            .push_bind(&error.card_name)
+            .push_bind(&error.category)
+            .push_bind(&error.message);
+    });
+    builder
+        .build()
+        .execute(pool)
+        .await
+        .context("Failed to record bulk import errors")?;
+
+    Ok(())
+}
+
+/// Recorded parse failures of an import in file order, or `None` if there is no such import
+pub async fn list_bulk_import_errors(
+    pool: &PgPool,
+    import_id: i32,
+) -> Result<Option<Vec<BulkImportError>>> {
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM bulk_data_metadata WHERE id = $1)")
+            .bind(import_id)
+            .fetch_one(pool)
+            .await
+            .context("Failed to look up bulk import")?;
+    if !exists {
+        return Ok(None);
+    }
+
+    let rows: Vec<(i64, Option<String>, Option<String>, String, String)> = sqlx::query_as(
+        r#"
+        SELECT position, card_id, card_name, category, message
+        FROM bulk_import_errors
+        WHERE import_id = $1
+        ORDER BY position
+        "#,
+    )
+    .bind(import_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list bulk import errors")?;
+
+    Ok(Some(
+        rows.into_iter()
+            .map(
+                |(position, card_id, card_name, category, message)| BulkImportError {
+                    position,
+                    card_id,
+                    card_name,
+                    category,
+                    message,
+                },
+            )
+            .collect(),
+    ))
+}
+
+/// Session advisory lock held on a connection detached from the pool, so the lock
+/// doesn't take a pooled connection or keep a transaction open. Dropping it unlocks and
+/// closes the connection; a lost connection releases the lock with it.
+pub struct PgAdvisoryLock {
+    conn: Option<PgConnection>,
+    name: String,
+}
+
+impl Drop for PgAdvisoryLock {
+    fn drop(&mut self) {
+        let Some(mut conn) = self.conn.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            // Dropping the connection ends the session, which releases the lock
+            return;
+        };
+        let name = std::mem::take(&mut self.name);
+        runtime.spawn(async move {
+            if let Err(e) = sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+                .bind(&name)
+                .execute(&mut conn)
+                .await
+            {
+                warn!("Failed to release advisory lock '{}': {}", name, e);
+            }
+            let _ = conn.close().await;
+        });
+    }
+}
+
+/// Take session advisory lock `name` without waiting, on a dedicated connection that
+/// holds it until the returned lock is dropped
+pub async fn try_advisory_lock(pool: &PgPool, name: &str) -> Result<Option<PgAdvisoryLock>> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .context("Failed to open advisory lock connection")?
+        .detach();
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtext($1))")
+        .bind(name)
+        .fetch_one(&mut conn)
+        .await
+        .context("Failed to take advisory lock")?;
+    if !locked {
+        let _ = conn.close().await;
+        return Ok(None);
+    }
+
+    Ok(Some(PgAdvisoryLock {
+        conn: Some(conn),
+        name: name.to_string(),
+    }))
+}
+
+/// Claim an idempotency key, or return the unexpired record already stored under it
+pub async fn claim_idempotency_key(
+    pool: &PgPool,
+    key: &str,
+    request_hash: &str,
+    ttl_seconds: i64,
+) -> Result<Option<IdempotencyRecord>> {
+    // Expired rows are taken over in place, so a claim is a single atomic statement
+    let claimed = sqlx::query(
+        r#"
+        INSERT INTO idempotency_keys (key, request_hash, expires_at)
+        VALUES ($1, $2, NOW() + INTERVAL '1 second' * $3)
+        ON CONFLICT (key) DO UPDATE SET
+            request_hash = EXCLUDED.request_hash,
+            status = NULL,
+            content_type = NULL,
+            body = NULL,
+            created_at = NOW(),
+            expires_at = EXCLUDED.expires_at
+        WHERE idempotency_keys.expires_at < NOW()
+        "#,
+    )
+    .bind(key)
+    .bind(request_hash)
+    .bind(ttl_seconds)
+    .execute(pool)
+    .await
+    .context("Failed to claim idempotency key")?
+    .rows_affected();
+    if claimed > 0 {
+        return Ok(None);
+    }
+
+    let row: Option<(String, Option<i32>, Option<String>, Option<Vec<u8>>)> = sqlx::query_as(
+        "SELECT request_hash, status, content_type, body FROM idempotency_keys WHERE key = $1",
+    )
+    .bind(key)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to read idempotency key")?;
+
+    Ok(row.map(
+        |(request_hash, status, content_type, body)| IdempotencyRecord {
+            request_hash,
+            status: status.map(|s| s as u16),
+            content_type,
+            body: body.unwrap_or_default(),
+        },
+    ))
+}
+
+/// Store the response of a claimed idempotency key
+pub async fn complete_idempotency_key(
+    pool: &PgPool,
+    key: &str,
+    record: &IdempotencyRecord,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE idempotency_keys
+        SET status = $2, content_type = $3, body = $4
+        WHERE key = $1 AND request_hash = $5
+        "#,
+    )
+    .bind(key)
+    .bind(record.status.map(i32::from))
+    .bind(&record.content_type)
+    .bind(&record.body)
+    .bind(&record.request_hash)
+    .execute(pool)
+    .await
+    .context("Failed to store idempotent response")?;
+
+    Ok(())
+}
+
+/// Delete an idempotency key
+pub async fn release_idempotency_key(pool: &PgPool, key: &str) -> Result<()> {
+    sqlx::query("DELETE FROM idempotency_keys WHERE key = $1")
+        .bind(key)
+        .execute(pool)
+        .await
+        .context("Failed to release idempotency key")?;
+
+    Ok(())
+}
+
+/// Delete expired idempotency keys
+pub async fn clean_expired_idempotency_keys(pool: &PgPool) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at < NOW()")
+        .execute(pool)
+        .await
+        .context("Failed to clean expired idempotency keys")?;
+
+    Ok(result.rows_affected())
+}
+
+/// Record an admin mutation in the admin audit log
+pub async fn record_admin_audit(pool: &PgPool, entry: &AdminAuditEntry) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO admin_audit_log
+            (actor, method, path, parameters, status, outcome, duration_ms)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(&entry.actor)
+    .bind(&entry.method)
+    .bind(&entry.path)
+    .bind(&entry.parameters)
+    .bind(entry.status)
+    .bind(&entry.outcome)
+    .bind(entry.duration_ms)
+    .execute(pool)
+    .await
+    .context("Failed to record admin audit entry")?;
+
+    Ok(())
+}
+
+/// List admin audit log entries, most recent first, optionally only those of `actor`
+pub async fn list_admin_audit(
+    pool: &PgPool,
+    limit: i64,
+    actor: Option<&str>,
+) -> Result<Vec<AdminAuditEntry>> {
+    let rows: Vec<(
+        String,
+        String,
+        String,
+        Option<String>,
+        i32,
+        String,
+        i64,
+        Option<chrono::NaiveDateTime>,
+    )> = sqlx::query_as(
+        r#"
+        SELECT actor, method, path, parameters, status, outcome, duration_ms, created_at
+        FROM admin_audit_log
+        WHERE $2::TEXT IS NULL OR actor = $2
+        ORDER BY created_at DESC NULLS LAST, id DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .bind(actor)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list admin audit log")?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(actor, method, path, parameters, status, outcome, duration_ms, created_at)| {
+                AdminAuditEntry {
+                    actor,
+                    method,
+                    path,
+                    parameters,
+                    status,
+                    outcome,
+                    duration_ms,
+                    created_at,
+                }
+            },
+        )
+        .collect())
+}
+
+/// Store owned quantities in a collection in one transaction; quantity 0 removes an entry
+pub async fn upsert_collection_entries(
+    pool: &PgPool,
+    tenant_id: &str,
+    collection_id: &str,
+    entries: &[CollectionEntry],
+) -> Result<()> {
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+
+    for entry in entries {
+        if entry.quantity > 0 {
+            sqlx::query(
+                r#"
+                INSERT INTO collection_cards (tenant_id, collection_id, card_id, finish, quantity)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (tenant_id, collection_id, card_id, finish) DO UPDATE SET
+                    quantity = EXCLUDED.quantity,
+                    updated_at = NOW()
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(collection_id)
+            .bind(entry.card_id)
+            .bind(entry.finish.as_str())
+            .bind(entry.quantity)
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to store collection entry")?;
+        } else {
+            sqlx::query(
+                r#"
+                DELETE FROM collection_cards
+                WHERE tenant_id = $1 AND collection_id = $2 AND card_id = $3 AND finish = $4
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(collection_id)
+            .bind(entry.card_id)
+            .bind(entry.finish.as_str())
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to remove collection entry")?;
+        }
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit transaction")?;
+    Ok(())
+}
+
+/// Entries of a collection, ordered by card id and finish
+pub async fn list_collection_entries(
+    pool: &PgPool,
+    tenant_id: &str,
+    collection_id: &str,
+) -> Result<Vec<CollectionEntry>> {
+    let rows: Vec<(Uuid, String, i32, Option<chrono::NaiveDateTime>)> = sqlx::query_as(
+        r#"
+        SELECT card_id, finish, quantity, updated_at
+        FROM collection_cards
+        WHERE tenant_id = $1 AND collection_id = $2
+        ORDER BY card_id, finish
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(collection_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list collection entries")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(card_id, finish, quantity, updated_at)| CollectionEntry {
+            card_id,
+            finish: Finish::parse(&finish).unwrap_or_default(),
+            quantity,
+            updated_at,
+        })
+        .collect())
+}
+
+/// Columns of `saved_searches`, in the order `SavedSearchRow` holds them
+const SAVED_SEARCH_COLUMNS: &str =
+    "id, tenant_id, query, name, runs, new_matches, created_at, last_run_at";
+
+type SavedSearchRow = (
+    Uuid,
+    String,
+    String,
+    Option<String>,
+    i32,
+    i32,
+    Option<chrono::NaiveDateTime>,
+    Option<chrono::NaiveDateTime>,
+);
+
+fn saved_search_from_row(row: SavedSearchRow) -> SavedSearch {
+    let (id, tenant_id, query, name, runs, new_matches, created_at, last_run_at) = row;
+    SavedSearch {
+        id,
+        tenant_id,
+        query,
+        name,
+        runs,
+        new_matches,
+        created_at,
+        last_run_at,
+    }
+}
+
+/// Register a saved search with the matches it has before its first run (run 0)
+pub async fn insert_saved_search(
+    pool: &PgPool,
+    search: &SavedSearch,
+    card_ids: &[Uuid],
+) -> Result<()> {
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+
+    sqlx::query("INSERT INTO saved_searches (id, tenant_id, query, name) VALUES ($1, $2, $3, $4)")
+        .bind(search.id)
+        .bind(&search.tenant_id)
+        .bind(&search.query)
+        .bind(&search.name)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to insert saved search")?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO saved_search_matches (saved_search_id, card_id, first_run)
+        SELECT $1, card_id, 0 FROM UNNEST($2::uuid[]) AS card_id
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(search.id)
+    .bind(card_ids)
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to record saved search matches")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit transaction")?;
+    Ok(())
+}
+
+/// Saved searches of one tenant, or of every tenant, oldest first
+pub async fn list_saved_searches(
+    pool: &PgPool,
+    tenant_id: Option<&str>,
+) -> Result<Vec<SavedSearch>> {
+    let rows: Vec<SavedSearchRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM saved_searches WHERE $1::text IS NULL OR tenant_id = $1 ORDER BY created_at, id",
+        SAVED_SEARCH_COLUMNS
+    ))
+    .bind(tenant_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list saved searches")?;
+
+    Ok(rows.into_iter().map(saved_search_from_row).collect())
+}
+
+/// Get a saved search of a tenant
+pub async fn get_saved_search(
+    pool: &PgPool,
+    tenant_id: &str,
+    id: Uuid,
+) -> Result<Option<SavedSearch>> {
+    let row: Option<SavedSearchRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM saved_searches WHERE tenant_id = $1 AND id = $2",
+        SAVED_SEARCH_COLUMNS
+    ))
+    .bind(tenant_id)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to get saved search")?;
+
+    Ok(row.map(saved_search_from_row))
+}
+
+/// Remove a saved search of a tenant and its matches, returning whether it existed
+pub async fn delete_saved_search(pool: &PgPool, tenant_id: &str, id: Uuid) -> Result<bool> {
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+
+    let deleted = sqlx::query("DELETE FROM saved_searches WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(id)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to delete saved search")?
+        .rows_affected()
+        > 0;
+    if deleted {
+        sqlx::query("DELETE FROM saved_search_matches WHERE saved_search_id = $1")
+            .bind(id)
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to delete saved search matches")?;
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit transaction")?;
+    Ok(deleted)
+}
+
+/// Record an evaluation of a saved search, returning the cards that matched it for the
+/// first time. Returns nothing if the search was deleted in the meantime.
+pub async fn record_saved_search_run(
+    pool: &PgPool,
+    id: Uuid,
+    card_ids: &[Uuid],
+) -> Result<Vec<Uuid>> {
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+
+    let run: Option<(i32,)> = sqlx::query_as(
+        "UPDATE saved_searches SET runs = runs + 1, last_run_at = NOW() WHERE id = $1 RETURNING runs",
+    )
+    .bind(id)
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to update saved search")?;
+    let Some((run,)) = run else {
+        return Ok(Vec::new());
+    };
+
+    let new_ids: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        INSERT INTO saved_search_matches (saved_search_id, card_id, first_run)
+        SELECT $1, card_id, $2 FROM UNNEST($3::uuid[]) AS card_id
+        ON CONFLICT DO NOTHING
+        RETURNING card_id
+        "#,
+    )
+    .bind(id)
+    .bind(run)
+    .bind(card_ids)
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to record saved search matches")?;
+
+    sqlx::query("UPDATE saved_searches SET new_matches = $2 WHERE id = $1")
+        .bind(id)
+        .bind(new_ids.len() as i32)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to update saved search")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit transaction")?;
+    Ok(new_ids.into_iter().map(|(id,)| id).collect())
+}
+
+/// Cards that first matched a saved search in the given run
+pub async fn list_saved_search_matches(pool: &PgPool, id: Uuid, run: i32) -> Result<Vec<Uuid>> {
+    let rows: Vec<(Uuid,)> = sqlx::query_as(
+        "SELECT card_id FROM saved_search_matches WHERE saved_search_id = $1 AND first_run = $2",
+    )
+    .bind(id)
+    .bind(run)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list saved search matches")?;
+
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+/// Record which bulk data type the given cards were imported from
+pub async fn record_card_provenance(
+    pool: &PgPool,
+    bulk_type: &str,
+    card_ids: &[Uuid],
+) -> Result<()> {
+    if card_ids.is_empty() {
+        return Ok(());
+    }
+
     sqlx::query(
         r#"
-        INSERT INTO bulk_data_metadata (bulk_type, download_uri, updated_at, total_cards, file_size_bytes)
-        VALUES ('unknown', $1, NOW(), $2, 0)
+        INSERT INTO card_provenance (card_id, bulk_type)
+        SELECT UNNEST($1::uuid[]), $2
+        ON CONFLICT (card_id, bulk_type) DO UPDATE SET imported_at = NOW()
         "#,
     )
-    .bind(source)
-    .bind(total_cards)
+    .bind(card_ids)
+    .bind(bulk_type)
     .execute(pool)
     .await
-    .context("Failed to record bulk import")?;
+    .context("Failed to record card provenance")?;
 
     Ok(())
 }
@@ -239,7 +1395,8 @@ pub async fn clean_old_cache_entries(pool: &PgPool, hours: i32) -> Result<u64> {
     let result = sqlx::query(
         r#"
         DELETE FROM query_cache
-        WHERE last_accessed < NOW() - INTERVAL '1 hour' * $1
+        WHERE expires_at < NOW()
+           OR last_accessed < NOW() - INTERVAL '1 hour' * $1
         "#,
     )
     .bind(hours)
@@ -267,6 +1424,32 @@ pub async fn execute_raw_query(pool: &PgPool, sql: &str, params: &[String]) -> R
     Ok(cards)
 }
 
+/// Execute a raw SQL query, streaming rows from the server as they are read.
+/// The query runs on its own task and stops as soon as the consumer goes away.
+pub fn stream_raw_query(pool: PgPool, sql: String, params: Vec<String>) -> CardStream {
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER_ROWS);
+
+    tokio::spawn(async move {
+        let mut query_builder = sqlx::query_as::<_, Card>(&sql);
+
+        // Bind all parameters
+        for param in &params {
+            query_builder = query_builder.bind(param.clone());
+        }
+
+        let mut rows = query_builder.fetch(&pool);
+        while let Some(row) = rows.next().await {
+            let failed = row.is_err();
+            let item = row.context("Failed to stream query results");
+            if tx.send(item).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    channel_stream(rx)
+}
+
 /// Execute a COUNT query and return the result
 pub async fn count_query(pool: &PgPool, sql: &str, params: &[String]) -> Result<usize> {
     let mut query_builder = sqlx::query_scalar::<_, i64>(sql);
@@ -284,6 +1467,118 @@ pub async fn count_query(pool: &PgPool, sql: &str, params: &[String]) -> Result<
     Ok(count as usize)
 }
 
+/// Begin a transaction whose statements are cancelled once they run for `timeout`
+async fn begin_with_timeout(
+    pool: &PgPool,
+    timeout: Duration,
+) -> Result<sqlx::Transaction<'static, Postgres>> {
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+    sqlx::query("SELECT set_config('statement_timeout', $1, true)")
+        .bind(timeout.as_millis().max(1).to_string())
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to set statement timeout")?;
+    Ok(transaction)
+}
+
+/// `execute_raw_query` cancelled by the server once it runs for `timeout`
+pub async fn execute_raw_query_with_timeout(
+    pool: &PgPool,
+    sql: &str,
+    params: &[String],
+    timeout: Duration,
+) -> Result<Vec<Card>> {
+    let mut transaction = begin_with_timeout(pool, timeout).await?;
+    let mut query_builder = sqlx::query_as::<_, Card>(sql);
+
+    // Bind all parameters
+    for param in params {
+        query_builder = query_builder.bind(param.clone());
+    }
+
+    let cards = query_builder
+        .fetch_all(&mut *transaction)
+        .await
+        .context("Failed to execute raw query")?;
+    transaction.commit().await?;
+
+    Ok(cards)
+}
+
+/// `stream_raw_query` cancelled by the server once it runs for `timeout`
+pub fn stream_raw_query_with_timeout(
+    pool: PgPool,
+    sql: String,
+    params: Vec<String>,
+    timeout: Duration,
+) -> CardStream {
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER_ROWS);
+
+    tokio::spawn(async move {
+        let mut transaction = match begin_with_timeout(&pool, timeout).await {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                let _ = tx.send(Err(e)).await;
+                return;
+            }
+        };
+        let mut query_builder = sqlx::query_as::<_, Card>(&sql);
+
+        // Bind all parameters
+        for param in &params {
+            query_builder = query_builder.bind(param.clone());
+        }
+
+        let mut rows = query_builder.fetch(&mut *transaction);
+        while let Some(row) = rows.next().await {
+            let failed = row.is_err();
+            let item = row.context("Failed to stream query results");
+            if tx.send(item).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    channel_stream(rx)
+}
+
+/// `count_query` cancelled by the server once it runs for `timeout`
+pub async fn count_query_with_timeout(
+    pool: &PgPool,
+    sql: &str,
+    params: &[String],
+    timeout: Duration,
+) -> Result<usize> {
+    let mut transaction = begin_with_timeout(pool, timeout).await?;
+    let mut query_builder = sqlx::query_scalar::<_, i64>(sql);
+
+    // Bind all parameters
+    for param in params {
+        query_builder = query_builder.bind(param.clone());
+    }
+
+    let count = query_builder
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to execute COUNT query")?;
+    transaction.commit().await?;
+
+    Ok(count as usize)
+}
+
+/// SQLSTATE of a statement cancelled by the server (`query_canceled`)
+const QUERY_CANCELED: &str = "57014";
+
+/// Whether `error` is a statement the server cancelled, e.g. for running past its
+/// `statement_timeout`
+pub fn is_statement_cancelled(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<sqlx::Error>())
+        .filter_map(|e| e.as_database_error())
+        .any(|e| e.code().as_deref() == Some(QUERY_CANCELED))
+}
+
 /// Check if bulk data is loaded
 pub async fn check_bulk_data_loaded(pool: &PgPool) -> Result<bool> {
     let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM cards")
@@ -306,6 +1601,39 @@ pub async fn get_last_bulk_import(pool: &PgPool) -> Result<Option<chrono::NaiveD
     Ok(result.map(|r| r.0))
 }
 
+/// Append an incremental sync run to the sync ledger
+pub async fn record_sync(pool: &PgPool, run: &SyncRun) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO sync_ledger (started_at, completed_at, since, cards_updated, status, error)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(run.started_at)
+    .bind(run.completed_at)
+    .bind(run.since)
+    .bind(run.cards_updated)
+    .bind(run.status.as_str())
+    .bind(run.error.as_deref())
+    .execute(pool)
+    .await
+    .context("Failed to record sync run")?;
+
+    Ok(())
+}
+
+/// Get the start time of the most recent successful incremental sync
+pub async fn get_last_sync(pool: &PgPool) -> Result<Option<chrono::NaiveDateTime>> {
+    let result: Option<(chrono::NaiveDateTime,)> = sqlx::query_as(
+        "SELECT started_at FROM sync_ledger WHERE status = 'success' ORDER BY started_at DESC LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to get last sync timestamp")?;
+
+    Ok(result.map(|r| r.0))
+}
+
 /// Get the total count of cards in the database
 pub async fn get_card_count(pool: &PgPool) -> Result<i64> {
     let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM cards")
@@ -316,6 +1644,54 @@ pub async fn get_card_count(pool: &PgPool) -> Result<i64> {
     Ok(result.0)
 }
 
+/// Get the total number of hits across query cache entries
+pub async fn get_query_cache_hit_count(pool: &PgPool) -> Result<i64> {
+    let result: (i64,) =
+        sqlx::query_as("SELECT COALESCE(SUM(hit_count), 0)::BIGINT FROM query_cache")
+            .fetch_one(pool)
+            .await
+            .context("Failed to get query cache hit count")?;
+
+    Ok(result.0)
+}
+
+/// Migrations recorded in `_sqlx_migrations`, in version order. Empty if the table
+/// doesn't exist yet (a replica whose primary hasn't run the migrations).
+pub async fn list_applied_migrations(pool: &PgPool) -> Result<Vec<AppliedMigration>> {
+    let (exists,): (bool,) = sqlx::query_as("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await
+        .context("Failed to look up _sqlx_migrations")?;
+    if !exists {
+        return Ok(Vec::new());
+    }
+
+    let rows: Vec<(i64, String, chrono::DateTime<chrono::Utc>, i64)> = sqlx::query_as(
+        r#"
+        SELECT version, description, installed_on, execution_time
+        FROM _sqlx_migrations
+        WHERE success
+        ORDER BY version
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list applied migrations")?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(version, description, installed_on, execution_time)| AppliedMigration {
+                version,
+                description,
+                installed_on: Some(installed_on.naive_utc()),
+                // sqlx records nanoseconds
+                execution_time_ms: execution_time / 1_000_000,
+            },
+        )
+        .collect())
+}
+
 /// Get the total count of query cache entries
 pub async fn get_cache_entry_count(pool: &PgPool) -> Result<i64> {
     let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM query_cache")
@@ -325,3 +1701,55 @@ pub async fn get_cache_entry_count(pool: &PgPool) -> Result<i64> {
 
     Ok(result.0)
 }
+
+/// Get the on-disk size of the current database, including indexes and TOAST data
+pub async fn get_database_size_bytes(pool: &PgPool) -> Result<i64> {
+    let result: (i64,) = sqlx::query_as("SELECT pg_database_size(current_database())")
+        .fetch_one(pool)
+        .await
+        .context("Failed to get database size")?;
+
+    Ok(result.0)
+}
+
+/// Count query cache entries per tenant, ordered by tenant id
+pub async fn count_query_cache_by_tenant(pool: &PgPool) -> Result<Vec<(String, i64)>> {
+    sqlx::query_as(
+        "SELECT tenant_id, COUNT(*) FROM query_cache GROUP BY tenant_id ORDER BY tenant_id",
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to count query cache entries by tenant")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staged_index_sql() {
+        assert_eq!(
+            staged_index_sql(
+                "idx_cards_set_code",
+                "CREATE INDEX idx_cards_set_code ON public.cards USING btree (set_code)"
+            )
+            .as_deref(),
+            Some("CREATE INDEX idx_cards_set_code_staging ON public.cards_staging USING btree (set_code)")
+        );
+        assert_eq!(
+            staged_index_sql(
+                "idx_cards_printed_name",
+                "CREATE INDEX idx_cards_printed_name ON cards USING btree (lower(printed_name))"
+            )
+            .as_deref(),
+            Some("CREATE INDEX idx_cards_printed_name_staging ON cards_staging USING btree (lower(printed_name))")
+        );
+        assert_eq!(
+            staged_index_sql(
+                "idx_other",
+                "CREATE INDEX idx_other ON public.sets USING btree (code)"
+            ),
+            None
+        );
+    }
+}
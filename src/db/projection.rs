@@ -0,0 +1,197 @@
+use crate::models::card::Card;
+
+/// Column projection for card reads.
+///
+/// Lets callers that only need a subset of card data (e.g. GraphQL queries that never
+/// touch `raw_json`) skip the heavy JSON columns. Omitted columns are still returned by
+/// the backend so row mapping keeps working, but as `NULL` (or `{}` for the non-nullable
+/// `raw_json`) instead of the stored value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CardProjection {
+    pub raw_json: bool,
+    pub prices: bool,
+    pub image_uris: bool,
+    pub card_faces: bool,
+    pub legalities: bool,
+}
+
+/// Columns that are always read regardless of projection
+const BASE_COLUMNS: &[&str] = &[
+    "id",
+    "oracle_id",
+    "name",
+    "mana_cost",
+    "cmc",
+    "type_line",
+    "oracle_text",
+    "colors",
+    "color_identity",
+    "set_code",
+    "set_name",
+    "collector_number",
+    "rarity",
+    "power",
+    "toughness",
+    "loyalty",
+    "power_num",
+    "toughness_num",
+    "loyalty_num",
+    "price_usd",
+    "price_eur",
+    "price_tix",
+    "edhrec_rank",
+    "penny_rank",
+    "keywords",
+    "face_names",
+    "released_at",
+    "lang",
+    "printed_name",
+    "printed_type_line",
+    "printed_text",
+    "layout",
+    "multiverse_ids",
+    "arena_id",
+    "mtgo_id",
+    "mtgo_foil_id",
+    "created_at",
+    "updated_at",
+];
+
+impl Default for CardProjection {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl CardProjection {
+    /// Read every column (equivalent to `SELECT *`)
+    pub const ALL: Self = Self {
+        raw_json: true,
+        prices: true,
+        image_uris: true,
+        card_faces: true,
+        legalities: true,
+    };
+
+    /// Read only the scalar/text columns, skipping every JSON column
+    pub const MINIMAL: Self = Self {
+        raw_json: false,
+        prices: false,
+        image_uris: false,
+        card_faces: false,
+        legalities: false,
+    };
+
+    /// Everything except the heavy per-printing JSON (`raw_json`, `card_faces`), for
+    /// list endpoints that return many cards per page
+    pub const SUMMARY: Self = Self {
+        raw_json: false,
+        card_faces: false,
+        ..Self::ALL
+    };
+
+    /// Whether this projection reads every column
+    pub fn is_full(&self) -> bool {
+        *self == Self::ALL
+    }
+
+    /// Build a SELECT column list for this projection.
+    ///
+    /// `null_json` is substituted for omitted nullable JSON columns and `empty_json`
+    /// for an omitted `raw_json`, so each backend can supply correctly typed literals.
+    pub fn select_list(&self, null_json: &str, empty_json: &str) -> String {
+        if self.is_full() {
+            return "*".to_string();
+        }
+
+        let mut columns: Vec<String> = BASE_COLUMNS.iter().map(|c| c.to_string()).collect();
+
+        for (column, included) in [
+            ("prices", self.prices),
+            ("image_uris", self.image_uris),
+            ("card_faces", self.card_faces),
+            ("legalities", self.legalities),
+        ] {
+            if included {
+                columns.push(column.to_string());
+            } else {
+                columns.push(format!("{} AS {}", null_json, column));
+            }
+        }
+
+        if self.raw_json {
+            columns.push("raw_json".to_string());
+        } else {
+            columns.push(format!("{} AS raw_json", empty_json));
+        }
+
+        columns.join(", ")
+    }
+
+    /// Blank the columns this projection omits on a card that was read in full
+    /// (e.g. fetched from Scryfall), so it matches cards read from the database
+    pub fn apply(&self, card: &mut Card) {
+        if !self.raw_json {
+            card.raw_json = serde_json::json!({});
+        }
+        for (value, included) in [
+            (&mut card.prices, self.prices),
+            (&mut card.image_uris, self.image_uris),
+            (&mut card.card_faces, self.card_faces),
+            (&mut card.legalities, self.legalities),
+        ] {
+            if !included {
+                *value = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_projection_selects_star() {
+        assert_eq!(CardProjection::ALL.select_list("NULL", "'{}'"), "*");
+    }
+
+    #[test]
+    fn test_minimal_projection_replaces_json_columns() {
+        let list = CardProjection::MINIMAL.select_list("NULL::jsonb", "'{}'::jsonb");
+        assert!(list.contains("NULL::jsonb AS prices"));
+        assert!(list.contains("'{}'::jsonb AS raw_json"));
+        assert!(list.starts_with("id, oracle_id, name"));
+    }
+
+    #[test]
+    fn test_summary_projection_skips_raw_json_and_faces() {
+        let list = CardProjection::SUMMARY.select_list("NULL", "'{}'");
+        assert!(list.contains("'{}' AS raw_json"));
+        assert!(list.contains("NULL AS card_faces"));
+        assert!(list.contains(", prices,"));
+
+        let mut card = Card::from_scryfall_json(serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Delver of Secrets",
+            "prices": {"usd": "0.10"},
+            "card_faces": [{"name": "Delver of Secrets"}, {"name": "Insectile Aberration"}]
+        }))
+        .unwrap();
+        CardProjection::SUMMARY.apply(&mut card);
+        assert_eq!(card.raw_json, serde_json::json!({}));
+        assert!(card.card_faces.is_none());
+        assert!(card.prices.is_some());
+    }
+
+    #[test]
+    fn test_partial_projection_keeps_selected_columns() {
+        let projection = CardProjection {
+            prices: true,
+            ..CardProjection::MINIMAL
+        };
+        let list = projection.select_list("NULL", "'{}'");
+        assert!(list.contains(", prices,"));
+        assert!(list.contains("NULL AS image_uris"));
+    }
+}
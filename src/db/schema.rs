@@ -1,106 +1,32 @@
 #[cfg(feature = "postgres")]
 use anyhow::{Context, Result};
 #[cfg(feature = "postgres")]
+use sqlx::migrate::Migrator;
+#[cfg(feature = "postgres")]
 use sqlx::PgPool;
 #[cfg(feature = "postgres")]
 use tracing::info;
 
+/// Migrations under `migrations/`, embedded at build time. Applied versions are recorded
+/// in `_sqlx_migrations`, so each file runs once per database.
 #[cfg(feature = "postgres")]
-const MIGRATION_SQL: &str = concat!(
-    include_str!("../../migrations/001_initial_schema.sql"),
-    "\n",
-    include_str!("../../migrations/002_fix_cmc_type.sql"),
-    "\n",
-    include_str!("../../migrations/003_add_performance_indexes.sql"),
-);
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
 #[cfg(feature = "postgres")]
 pub async fn run_migrations(pool: &PgPool) -> Result<()> {
     info!("Running database migrations...");
 
-    // Split the migration SQL into individual statements
-    // We need to handle this carefully because of function definitions with semicolons
-    let statements = split_sql_statements(MIGRATION_SQL);
-
-    for (i, statement) in statements.iter().enumerate() {
-        let trimmed = statement.trim();
-        if trimmed.is_empty() || trimmed.starts_with("--") {
-            continue;
-        }
-
-        sqlx::query(trimmed).execute(pool).await.with_context(|| {
-            format!(
-                "Failed to execute migration statement {}: {}",
-                i + 1,
-                &trimmed[..trimmed.len().min(100)]
-            )
-        })?;
-    }
+    // Databases created before migrations were versioned have no _sqlx_migrations table
+    // and get every file applied once; the files are idempotent, so that only fills gaps
+    MIGRATOR
+        .run(pool)
+        .await
+        .context("Failed to apply database migrations")?;
 
     info!("Database migrations completed successfully");
     Ok(())
 }
 
-#[cfg(feature = "postgres")]
-fn split_sql_statements(sql: &str) -> Vec<String> {
-    let mut statements = Vec::new();
-    let mut current = String::new();
-    let mut in_function = false;
-    let mut dollar_quote_tag: Option<String> = None;
-
-    for line in sql.lines() {
-        let trimmed = line.trim();
-
-        // Skip comments
-        if trimmed.starts_with("--") {
-            continue;
-        }
-
-        // Check for function start
-        if trimmed.contains("CREATE OR REPLACE FUNCTION") || trimmed.contains("CREATE FUNCTION") {
-            in_function = true;
-        }
-
-        // Check for dollar quotes ($$)
-        if let Some(pos) = trimmed.find("$$") {
-            if dollar_quote_tag.is_none() {
-                // Extract the tag between $ symbols if any (e.g., $body$)
-                let tag = if pos > 0 && trimmed[..pos].ends_with('$') {
-                    "$$".to_string()
-                } else {
-                    "$$".to_string()
-                };
-                dollar_quote_tag = Some(tag);
-            } else {
-                dollar_quote_tag = None;
-            }
-        }
-
-        current.push_str(line);
-        current.push('\n');
-
-        // End statement on semicolon if we're not in a function or dollar quote
-        if trimmed.ends_with(';') && !in_function && dollar_quote_tag.is_none() {
-            statements.push(current.trim().to_string());
-            current.clear();
-        }
-
-        // Check for function end
-        if in_function && trimmed.contains("language") && trimmed.ends_with(';') {
-            in_function = false;
-            statements.push(current.trim().to_string());
-            current.clear();
-        }
-    }
-
-    // Add any remaining content
-    if !current.trim().is_empty() {
-        statements.push(current.trim().to_string());
-    }
-
-    statements
-}
-
 #[cfg(feature = "postgres")]
 pub async fn check_bulk_data_loaded(pool: &PgPool) -> Result<bool> {
     let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM cards")
@@ -135,11 +61,31 @@ pub async fn get_last_bulk_import(pool: &PgPool) -> Result<Option<chrono::NaiveD
 
 #[cfg(all(test, feature = "postgres"))]
 mod tests {
-    use super::MIGRATION_SQL;
+    use super::MIGRATOR;
+
+    /// Every migration file, in version order
+    fn migration_sql() -> String {
+        MIGRATOR
+            .iter()
+            .map(|migration| migration.sql.as_ref())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn migrations_are_numbered_in_order() {
+        let versions: Vec<i64> = MIGRATOR.iter().map(|migration| migration.version).collect();
+        assert_eq!(versions.first(), Some(&1));
+        assert!(
+            versions.windows(2).all(|pair| pair[1] == pair[0] + 1),
+            "Migration versions have gaps: {:?}",
+            versions
+        );
+    }
 
     #[test]
     fn migration_sql_includes_phase_2_indexes() {
-        let sql = MIGRATION_SQL;
+        let sql = migration_sql();
         assert!(
             sql.contains("idx_cards_colors_type"),
             "Missing composite colors/type index"
@@ -160,10 +106,122 @@ mod tests {
 
     #[test]
     fn migration_sql_includes_cmc_type_fix() {
-        let sql = MIGRATION_SQL;
+        let sql = migration_sql();
         assert!(
             sql.contains("ALTER TABLE cards ALTER COLUMN cmc TYPE DOUBLE PRECISION"),
             "Missing CMC type fix migration"
         );
     }
+
+    #[test]
+    fn migration_sql_includes_sync_ledger() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS sync_ledger"),
+            "Missing sync ledger table"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_card_provenance() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS card_provenance"),
+            "Missing card provenance table"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_query_cache_expiry() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("ALTER TABLE query_cache ADD COLUMN IF NOT EXISTS expires_at"),
+            "Missing query cache expiry column"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_query_cache_hit_count() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("ALTER TABLE query_cache ADD COLUMN IF NOT EXISTS hit_count"),
+            "Missing query cache hit count column"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_bulk_import_stats() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("ALTER TABLE bulk_data_metadata ADD COLUMN IF NOT EXISTS duration_ms"),
+            "Missing bulk import duration column"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_query_cache_tenant() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("ALTER TABLE query_cache ADD COLUMN IF NOT EXISTS tenant_id"),
+            "Missing query cache tenant column"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_admin_audit_log() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS admin_audit_log"),
+            "Missing admin_audit_log table"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_collections() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS collection_cards"),
+            "Missing collection_cards table"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_saved_searches() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS saved_searches"),
+            "Missing saved_searches table"
+        );
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS saved_search_matches"),
+            "Missing saved_search_matches table"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_bulk_import_errors() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS bulk_import_errors"),
+            "Missing bulk_import_errors table"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_card_relations() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS card_relations"),
+            "Missing card_relations table"
+        );
+    }
+
+    #[test]
+    fn migration_sql_includes_idempotency_keys() {
+        let sql = migration_sql();
+        assert!(
+            sql.contains("CREATE TABLE IF NOT EXISTS idempotency_keys"),
+            "Missing idempotency_keys table"
+        );
+    }
 }
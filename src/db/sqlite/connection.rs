@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::params;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{params, Connection};
+use sha2::{Digest, Sha256};
 use std::path::Path;
 use std::time::Duration;
 
@@ -15,7 +17,11 @@ pub fn create_pool(database_path: &str) -> Result<SqlitePool> {
         std::fs::create_dir_all(parent).context("Failed to create database directory")?;
     }
 
-    let manager = SqliteConnectionManager::file(database_path);
+    let manager = SqliteConnectionManager::file(database_path).with_init(|conn| {
+        // WAL lets searches read while a bulk import writes
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        register_functions(conn)
+    });
 
     Pool::builder()
         .max_size(15) // SQLite doesn't handle as many connections as Postgres
@@ -24,6 +30,33 @@ pub fn create_pool(database_path: &str) -> Result<SqlitePool> {
         .context("Failed to create SQLite connection pool")
 }
 
+/// SQL functions SQLite lacks. `regexp` backs the `X REGEXP Y` operator used by regex
+/// searches; the compiled pattern is cached per statement. `sha256` returns the digest of
+/// its text argument as a blob, like PostgreSQL's, and orders seeded samples.
+fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    conn.create_scalar_function(
+        "regexp",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let regex = ctx.get_or_create_aux(0, |pattern| {
+                crate::query::pattern::compile_pattern(pattern.as_str()?)
+            })?;
+            let text: Option<String> = ctx.get(1)?;
+            Ok(text.is_some_and(|text| regex.is_match(&text)))
+        },
+    )?;
+    conn.create_scalar_function(
+        "sha256",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let text: Option<String> = ctx.get(0)?;
+            Ok(text.map(|text| Sha256::digest(text.as_bytes()).to_vec()))
+        },
+    )
+}
+
 pub fn test_connection(pool: &SqlitePool) -> Result<()> {
     let conn = pool.get().context("Failed to get connection from pool")?;
     conn.query_row("SELECT 1", params![], |_| Ok(()))
@@ -32,8 +65,13 @@ pub fn test_connection(pool: &SqlitePool) -> Result<()> {
 }
 
 pub fn init_schema(pool: &SqlitePool) -> Result<()> {
-    let conn = pool.get().context("Failed to get connection from pool")?;
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    super::migrations::run_migrations(&mut conn)
+}
 
+/// Schema as of the first versioned migration. Every step checks what already exists,
+/// so it also brings databases created before migrations were versioned up to date.
+pub(super) fn baseline_schema(conn: &Connection) -> Result<()> {
     // Create cards table
     conn.execute(
         r#"
@@ -61,6 +99,24 @@ pub fn init_schema(pool: &SqlitePool) -> Result<()> {
             legalities TEXT,
             released_at TEXT,
             raw_json TEXT NOT NULL,
+            lang TEXT,
+            printed_name TEXT,
+            printed_type_line TEXT,
+            printed_text TEXT,
+            layout TEXT,
+            multiverse_ids TEXT,
+            arena_id INTEGER,
+            mtgo_id INTEGER,
+            mtgo_foil_id INTEGER,
+            power_num REAL,
+            toughness_num REAL,
+            loyalty_num REAL,
+            price_usd REAL,
+            price_eur REAL,
+            price_tix REAL,
+            face_names TEXT,
+            edhrec_rank INTEGER,
+            penny_rank INTEGER,
             created_at TEXT DEFAULT CURRENT_TIMESTAMP,
             updated_at TEXT DEFAULT CURRENT_TIMESTAMP
         )
@@ -69,34 +125,385 @@ pub fn init_schema(pool: &SqlitePool) -> Result<()> {
     )
     .context("Failed to create cards table")?;
 
-    // Create query_cache table
+    // Databases created before localized printings were stored lack the language columns.
+    // Add them and backfill from the stored Scryfall JSON.
+    let has_lang: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('cards') WHERE name = 'lang'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect cards table")?;
+    if has_lang == 0 {
+        for column in ["lang", "printed_name", "printed_type_line", "printed_text"] {
+            let sql = format!("ALTER TABLE cards ADD COLUMN {} TEXT", column);
+            conn.execute(&sql, params![])
+                .with_context(|| format!("Failed to add cards.{} column", column))?;
+        }
+        conn.execute(
+            r#"
+            UPDATE cards SET
+                lang = COALESCE(json_extract(raw_json, '$.lang'), 'en'),
+                printed_name = json_extract(raw_json, '$.printed_name'),
+                printed_type_line = json_extract(raw_json, '$.printed_type_line'),
+                printed_text = json_extract(raw_json, '$.printed_text')
+            "#,
+            params![],
+        )
+        .context("Failed to backfill card language columns")?;
+    }
+
+    // Likewise for the layout column used by is:token / is:emblem
+    let has_layout: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('cards') WHERE name = 'layout'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect cards table")?;
+    if has_layout == 0 {
+        conn.execute("ALTER TABLE cards ADD COLUMN layout TEXT", params![])
+            .context("Failed to add cards.layout column")?;
+        conn.execute(
+            "UPDATE cards SET layout = COALESCE(json_extract(raw_json, '$.layout'), 'normal')",
+            params![],
+        )
+        .context("Failed to backfill card layout column")?;
+    }
+
+    // And for the external id columns used by the multiverse/arena/mtgo lookups
+    let has_arena_id: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('cards') WHERE name = 'arena_id'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect cards table")?;
+    if has_arena_id == 0 {
+        conn.execute(
+            "ALTER TABLE cards ADD COLUMN multiverse_ids TEXT",
+            params![],
+        )
+        .context("Failed to add cards.multiverse_ids column")?;
+        for column in ["arena_id", "mtgo_id", "mtgo_foil_id"] {
+            let sql = format!("ALTER TABLE cards ADD COLUMN {} INTEGER", column);
+            conn.execute(&sql, params![])
+                .with_context(|| format!("Failed to add cards.{} column", column))?;
+        }
+        conn.execute(
+            r#"
+            UPDATE cards SET
+                multiverse_ids = json_extract(raw_json, '$.multiverse_ids'),
+                arena_id = json_extract(raw_json, '$.arena_id'),
+                mtgo_id = json_extract(raw_json, '$.mtgo_id'),
+                mtgo_foil_id = json_extract(raw_json, '$.mtgo_foil_id')
+            "#,
+            params![],
+        )
+        .context("Failed to backfill card external id columns")?;
+    }
+
+    // And for the numeric power/toughness/loyalty used by comparisons. Older databases
+    // had generated power_num/toughness_num columns; those are now written on import,
+    // so drop the generated ones (and their indexes) and add plain columns instead.
+    // Generated columns are hidden from pragma_table_info, hence pragma_table_xinfo.
+    for column in ["power", "toughness", "loyalty"] {
+        let generated: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_xinfo('cards') WHERE name = ?1 AND hidden IN (2, 3)",
+                params![format!("{}_num", column)],
+                |row| row.get(0),
+            )
+            .context("Failed to inspect cards table")?;
+        if generated > 0 {
+            conn.execute(
+                &format!("DROP INDEX IF EXISTS idx_cards_{}_num", column),
+                params![],
+            )
+            .with_context(|| format!("Failed to drop {}_num index", column))?;
+            conn.execute(
+                &format!("ALTER TABLE cards DROP COLUMN {}_num", column),
+                params![],
+            )
+            .with_context(|| format!("Failed to drop generated cards.{}_num column", column))?;
+        }
+
+        let has_column: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('cards') WHERE name = ?1",
+                params![format!("{}_num", column)],
+                |row| row.get(0),
+            )
+            .context("Failed to inspect cards table")?;
+        if has_column == 0 {
+            conn.execute(
+                &format!("ALTER TABLE cards ADD COLUMN {}_num REAL", column),
+                params![],
+            )
+            .with_context(|| format!("Failed to add cards.{}_num column", column))?;
+            let sql = format!(
+                "UPDATE cards SET {column}_num = CAST({column} AS REAL) WHERE {column} GLOB '[0-9]*' AND {column} NOT GLOB '*[^0-9.]*'"
+            );
+            conn.execute(&sql, params![])
+                .with_context(|| format!("Failed to backfill cards.{}_num column", column))?;
+        }
+    }
+
+    // And for the numeric prices used by usd/eur/tix filters
+    for currency in ["usd", "eur", "tix"] {
+        let has_column: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('cards') WHERE name = ?1",
+                params![format!("price_{}", currency)],
+                |row| row.get(0),
+            )
+            .context("Failed to inspect cards table")?;
+        if has_column == 0 {
+            conn.execute(
+                &format!("ALTER TABLE cards ADD COLUMN price_{} REAL", currency),
+                params![],
+            )
+            .with_context(|| format!("Failed to add cards.price_{} column", currency))?;
+            let sql = format!(
+                "UPDATE cards SET price_{currency} = CAST(json_extract(prices, '$.{currency}') AS REAL) WHERE json_extract(prices, '$.{currency}') IS NOT NULL"
+            );
+            conn.execute(&sql, params![])
+                .with_context(|| format!("Failed to backfill cards.price_{} column", currency))?;
+        }
+    }
+
+    // And for the face names used to look up split and adventure cards by one half
+    let has_face_names: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('cards') WHERE name = 'face_names'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect cards table")?;
+    if has_face_names == 0 {
+        conn.execute("ALTER TABLE cards ADD COLUMN face_names TEXT", params![])
+            .context("Failed to add cards.face_names column")?;
+        conn.execute(
+            r#"
+            UPDATE cards SET face_names = (
+                SELECT json_group_array(LOWER(json_extract(face.value, '$.name')))
+                FROM json_each(cards.card_faces) AS face
+            )
+            WHERE json_type(card_faces) = 'array'
+            "#,
+            params![],
+        )
+        .context("Failed to backfill cards.face_names column")?;
+    }
+
+    // And for the popularity ranks used by order=edhrec and order=penny
+    for column in ["edhrec_rank", "penny_rank"] {
+        let has_column: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('cards') WHERE name = ?1",
+                params![column],
+                |row| row.get(0),
+            )
+            .context("Failed to inspect cards table")?;
+        if has_column == 0 {
+            conn.execute(
+                &format!("ALTER TABLE cards ADD COLUMN {} INTEGER", column),
+                params![],
+            )
+            .with_context(|| format!("Failed to add cards.{} column", column))?;
+            let sql = format!(
+                "UPDATE cards SET {column} = json_extract(raw_json, '$.{column}') WHERE json_type(raw_json, '$.{column}') = 'integer'"
+            );
+            conn.execute(&sql, params![])
+                .with_context(|| format!("Failed to backfill cards.{} column", column))?;
+        }
+    }
+
+    // Older databases used a SQLite-specific query_cache layout (card_ids/ttl_hours).
+    // Cached results are disposable, so drop it and recreate with the shared layout.
+    let has_legacy_layout: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('query_cache') WHERE name = 'card_ids'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect query_cache table")?;
+    if has_legacy_layout > 0 {
+        conn.execute("DROP TABLE query_cache", params![])
+            .context("Failed to drop legacy query_cache table")?;
+    }
+
+    // Create query_cache table (same columns as the PostgreSQL schema)
     conn.execute(
         r#"
         CREATE TABLE IF NOT EXISTS query_cache (
             query_hash TEXT PRIMARY KEY,
-            card_ids TEXT NOT NULL,
-            ttl_hours INTEGER NOT NULL,
+            query_text TEXT NOT NULL,
+            result_ids TEXT NOT NULL,
+            total_cards INTEGER NOT NULL,
+            hit_count INTEGER NOT NULL DEFAULT 0,
             created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-            expires_at TEXT NOT NULL
+            last_accessed TEXT DEFAULT CURRENT_TIMESTAMP,
+            expires_at TEXT,
+            tenant_id TEXT NOT NULL DEFAULT 'default'
         )
         "#,
         params![],
     )
     .context("Failed to create query_cache table")?;
 
-    // Create bulk_imports table
+    // Entries cached before tenants existed belong to the default tenant
+    let has_tenant_id: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('query_cache') WHERE name = 'tenant_id'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect query_cache table")?;
+    if has_tenant_id == 0 {
+        conn.execute(
+            "ALTER TABLE query_cache ADD COLUMN tenant_id TEXT NOT NULL DEFAULT 'default'",
+            params![],
+        )
+        .context("Failed to add query_cache.tenant_id column")?;
+    }
+
+    // Create bulk_data_metadata table (same columns as the PostgreSQL schema)
     conn.execute(
         r#"
-        CREATE TABLE IF NOT EXISTS bulk_imports (
+        CREATE TABLE IF NOT EXISTS bulk_data_metadata (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
+            bulk_type TEXT NOT NULL,
+            download_uri TEXT NOT NULL,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            imported_at TEXT DEFAULT CURRENT_TIMESTAMP,
             total_cards INTEGER NOT NULL,
-            source TEXT NOT NULL,
-            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            file_size_bytes INTEGER NOT NULL DEFAULT 0,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            failed_cards INTEGER NOT NULL DEFAULT 0
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create bulk_data_metadata table")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bulk_data_imported_at ON bulk_data_metadata(imported_at DESC)",
+        params![],
+    )
+    .context("Failed to create bulk_data_metadata index")?;
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS bulk_import_errors (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            import_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            card_id TEXT,
+            card_name TEXT,
+            category TEXT NOT NULL,
+            message TEXT NOT NULL
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create bulk_import_errors table")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bulk_import_errors_import_id ON bulk_import_errors(import_id)",
+        params![],
+    )
+    .context("Failed to create bulk_import_errors index")?;
+
+    // Card links from Scryfall's all_parts (tokens, meld pairs, combo pieces). Databases
+    // created before the table existed get it filled from the stored card JSON.
+    let has_relations: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'card_relations'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect card_relations table")?;
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS card_relations (
+            card_id TEXT NOT NULL,
+            related_id TEXT NOT NULL,
+            component TEXT NOT NULL,
+            name TEXT NOT NULL,
+            type_line TEXT,
+            PRIMARY KEY (card_id, related_id)
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create card_relations table")?;
+    if has_relations == 0 {
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO card_relations (card_id, related_id, component, name, type_line)
+            SELECT cards.id, json_extract(part.value, '$.id'), json_extract(part.value, '$.component'),
+                json_extract(part.value, '$.name'), json_extract(part.value, '$.type_line')
+            FROM cards, json_each(cards.raw_json, '$.all_parts') AS part
+            WHERE json_extract(part.value, '$.id') IS NOT NULL
+              AND json_extract(part.value, '$.id') <> cards.id
+              AND json_extract(part.value, '$.component') IS NOT NULL
+              AND json_extract(part.value, '$.name') IS NOT NULL
+            "#,
+            params![],
+        )
+        .context("Failed to backfill card_relations table")?;
+    }
+
+    // Older databases recorded imports in a SQLite-specific bulk_imports table; carry its
+    // history over to the shared layout
+    let has_legacy_imports: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'bulk_imports'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect bulk_imports table")?;
+    if has_legacy_imports > 0 {
+        conn.execute(
+            r#"
+            INSERT INTO bulk_data_metadata (bulk_type, download_uri, updated_at, imported_at, total_cards)
+            SELECT 'unknown', source, created_at, created_at, total_cards FROM bulk_imports
+            "#,
+            params![],
+        )
+        .context("Failed to migrate bulk_imports rows")?;
+        conn.execute("DROP TABLE bulk_imports", params![])
+            .context("Failed to drop legacy bulk_imports table")?;
+    }
+
+    // Create sync_ledger table (incremental sync runs)
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS sync_ledger (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            started_at TEXT NOT NULL,
+            completed_at TEXT NOT NULL,
+            since TEXT NOT NULL,
+            cards_updated INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            error TEXT
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create sync_ledger table")?;
+
+    // Create card_provenance table (bulk data types each card was imported from)
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS card_provenance (
+            card_id TEXT NOT NULL,
+            bulk_type TEXT NOT NULL,
+            imported_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (card_id, bulk_type)
         )
         "#,
         params![],
     )
-    .context("Failed to create bulk_imports table")?;
+    .context("Failed to create card_provenance table")?;
 
     // Create indexes for performance
     // Note: SQLite doesn't support GIN indexes like PostgreSQL, so we use standard B-tree indexes
@@ -166,6 +573,40 @@ pub fn init_schema(pool: &SqlitePool) -> Result<()> {
     )
     .context("Failed to create set_collector composite index")?;
 
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_cards_lang ON cards(lang)",
+        params![],
+    )
+    .context("Failed to create lang index")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_cards_layout ON cards(layout)",
+        params![],
+    )
+    .context("Failed to create layout index")?;
+
+    for column in [
+        "arena_id",
+        "mtgo_id",
+        "mtgo_foil_id",
+        "power_num",
+        "toughness_num",
+        "loyalty_num",
+        "price_usd",
+        "price_eur",
+        "price_tix",
+        "edhrec_rank",
+        "penny_rank",
+        "released_at",
+    ] {
+        let sql = format!(
+            "CREATE INDEX IF NOT EXISTS idx_cards_{0} ON cards({0})",
+            column
+        );
+        conn.execute(&sql, params![])
+            .with_context(|| format!("Failed to create {} index", column))?;
+    }
+
     // Query cache index
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_query_cache_expires ON query_cache(expires_at)",
@@ -173,5 +614,144 @@ pub fn init_schema(pool: &SqlitePool) -> Result<()> {
     )
     .context("Failed to create expires_at index")?;
 
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_query_cache_last_accessed ON query_cache(last_accessed)",
+        params![],
+    )
+    .context("Failed to create last_accessed index")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_query_cache_tenant_id ON query_cache(tenant_id)",
+        params![],
+    )
+    .context("Failed to create tenant_id index")?;
+
+    // Create admin_audit_log table (same columns as the PostgreSQL schema)
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS admin_audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            actor TEXT NOT NULL,
+            method TEXT NOT NULL,
+            path TEXT NOT NULL,
+            parameters TEXT,
+            status INTEGER NOT NULL,
+            outcome TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create admin_audit_log table")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_admin_audit_log_created_at ON admin_audit_log(created_at DESC)",
+        params![],
+    )
+    .context("Failed to create admin_audit_log created_at index")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_admin_audit_log_actor ON admin_audit_log(actor)",
+        params![],
+    )
+    .context("Failed to create admin_audit_log actor index")?;
+
+    // Create collection_cards table (same columns as the PostgreSQL schema)
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS collection_cards (
+            tenant_id TEXT NOT NULL DEFAULT 'default',
+            collection_id TEXT NOT NULL,
+            card_id TEXT NOT NULL,
+            finish TEXT NOT NULL DEFAULT 'nonfoil',
+            quantity INTEGER NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (tenant_id, collection_id, card_id, finish)
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create collection_cards table")?;
+
+    // Create saved_searches and saved_search_matches tables (same columns as the
+    // PostgreSQL schema)
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS saved_searches (
+            id TEXT PRIMARY KEY,
+            tenant_id TEXT NOT NULL DEFAULT 'default',
+            query TEXT NOT NULL,
+            name TEXT,
+            runs INTEGER NOT NULL DEFAULT 0,
+            new_matches INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            last_run_at TEXT
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create saved_searches table")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_saved_searches_tenant_id ON saved_searches(tenant_id)",
+        params![],
+    )
+    .context("Failed to create saved_searches tenant_id index")?;
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS saved_search_matches (
+            saved_search_id TEXT NOT NULL,
+            card_id TEXT NOT NULL,
+            first_run INTEGER NOT NULL,
+            PRIMARY KEY (saved_search_id, card_id)
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create saved_search_matches table")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_saved_search_matches_run ON saved_search_matches(saved_search_id, first_run)",
+        params![],
+    )
+    .context("Failed to create saved_search_matches run index")?;
+
+    Ok(())
+}
+
+/// Responses replayed for retried requests with an `Idempotency-Key` (same columns as
+/// the PostgreSQL schema)
+pub(super) fn idempotency_keys(conn: &Connection) -> Result<()> {
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS idempotency_keys (
+            key TEXT PRIMARY KEY,
+            request_hash TEXT NOT NULL,
+            status INTEGER,
+            content_type TEXT,
+            body BLOB,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            expires_at TEXT NOT NULL
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create idempotency_keys table")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires_at ON idempotency_keys(expires_at)",
+        params![],
+    )
+    .context("Failed to create idempotency_keys expires_at index")?;
+
+    Ok(())
+}
+
+/// Index serving cursor pagination, which orders by `(name, id)` and resumes after the
+/// last `(name, id)` seen
+pub(super) fn name_id_index(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_cards_name_id ON cards(name, id)",
+        params![],
+    )
+    .context("Failed to create name/id index")?;
+
     Ok(())
 }
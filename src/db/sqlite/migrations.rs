@@ -0,0 +1,190 @@
+//! Versioned schema migrations for the SQLite backend, the counterpart of the
+//! `sqlx::migrate!` runner used for PostgreSQL. Applied versions are recorded in
+//! `schema_migrations`, so each step runs once per database.
+
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, TransactionBehavior};
+use std::time::Instant;
+use tracing::info;
+
+use super::connection;
+
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// Migrations in version order. Add new steps at the end and never change one that has
+/// shipped: databases that recorded its version won't run it again.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "baseline schema",
+        apply: connection::baseline_schema,
+    },
+    Migration {
+        version: 2,
+        description: "idempotency keys",
+        apply: connection::idempotency_keys,
+    },
+    Migration {
+        version: 3,
+        description: "name/id keyset index",
+        apply: connection::name_id_index,
+    },
+];
+
+/// Apply every migration the database hasn't recorded, each in its own transaction
+pub fn run_migrations(conn: &mut Connection) -> Result<()> {
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            installed_on TEXT DEFAULT CURRENT_TIMESTAMP,
+            execution_time_ms INTEGER NOT NULL DEFAULT 0
+        )
+        "#,
+        params![],
+    )
+    .context("Failed to create schema_migrations table")?;
+
+    // Like sqlx, refuse to run against a database migrated by a newer build
+    let latest = MIGRATIONS.last().map_or(0, |m| m.version);
+    let recorded: Option<i64> = conn
+        .query_row(
+            "SELECT MAX(version) FROM schema_migrations",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to read schema_migrations")?;
+    if let Some(recorded) = recorded.filter(|&v| v > latest) {
+        bail!(
+            "Database schema is at version {}, newer than this build's {}",
+            recorded,
+            latest
+        );
+    }
+
+    for migration in MIGRATIONS {
+        // IMMEDIATE takes the write lock before the version check, so two processes
+        // opening the same file can't both apply a step
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .context("Failed to begin migration transaction")?;
+        let applied: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM schema_migrations WHERE version = ?1)",
+                params![migration.version],
+                |row| row.get(0),
+            )
+            .context("Failed to read schema_migrations")?;
+        if applied {
+            continue;
+        }
+
+        info!(
+            "Applying SQLite migration {} ({})",
+            migration.version, migration.description
+        );
+        let start = Instant::now();
+        (migration.apply)(&tx).with_context(|| {
+            format!(
+                "Failed to apply migration {} ({})",
+                migration.version, migration.description
+            )
+        })?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, description, execution_time_ms) VALUES (?1, ?2, ?3)",
+            params![
+                migration.version,
+                migration.description,
+                start.elapsed().as_millis() as i64
+            ],
+        )
+        .context("Failed to record migration")?;
+        tx.commit().context("Failed to commit migration")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_numbered_in_order() {
+        let versions: Vec<i64> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(versions.first(), Some(&1));
+        assert!(versions.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    }
+
+    #[test]
+    fn run_migrations_records_versions_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        run_migrations(&mut conn).unwrap();
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM schema_migrations", params![], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    fn baseline_converts_legacy_bulk_imports() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE bulk_imports (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                total_cards INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO bulk_imports (total_cards, source, created_at)
+            VALUES (42, 'https://example.com/cards.json', '2024-01-02 03:04:05');
+            "#,
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+
+        let (total_cards, source, imported_at): (i64, String, String) = conn
+            .query_row(
+                "SELECT total_cards, download_uri, imported_at FROM bulk_data_metadata",
+                params![],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(total_cards, 42);
+        assert_eq!(source, "https://example.com/cards.json");
+        assert_eq!(imported_at, "2024-01-02 03:04:05");
+
+        let legacy_tables: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'bulk_imports'",
+                params![],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(legacy_tables, 0);
+    }
+
+    #[test]
+    fn run_migrations_rejects_newer_schema() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO schema_migrations (version, description) VALUES (?1, 'future')",
+            params![MIGRATIONS.len() as i64 + 1],
+        )
+        .unwrap();
+
+        assert!(run_migrations(&mut conn).is_err());
+    }
+}
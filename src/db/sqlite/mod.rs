@@ -1,23 +1,55 @@
 pub mod connection;
+mod migrations;
 pub mod queries;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
-use crate::db::backend::DatabaseBackend;
+use crate::db::backend::{
+    channel_stream, AdvisoryLock, AutocompleteOptions, CardStream, DatabaseBackend, SqlDialect,
+    DEFAULT_IMPORT_BATCH_SIZE, STREAM_BUFFER_ROWS,
+};
+use crate::db::projection::CardProjection;
 use crate::db::sqlite::connection::SqlitePool;
-use crate::models::card::Card;
+use crate::errors::{ServiceError, ServiceResult};
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::models::bulk_import::{BulkImport, BulkImportError};
+use crate::models::card::{Card, CardPart, ExternalId};
+use crate::models::collection::CollectionEntry;
+use crate::models::idempotency::IdempotencyRecord;
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::IntegrityIssue;
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::SchemaVersion;
+use crate::models::sync::SyncRun;
 
 pub struct SqliteBackend {
     pool: SqlitePool,
+    /// Cards written per transaction by bulk imports
+    import_batch_size: usize,
+    /// Cards per INSERT statement in bulk imports
+    rows_per_statement: usize,
 }
 
 impl SqliteBackend {
     pub fn new(pool: SqlitePool) -> Result<Self> {
         // Initialize schema on creation
         connection::init_schema(&pool)?;
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            import_batch_size: DEFAULT_IMPORT_BATCH_SIZE,
+            rows_per_statement: 1,
+        })
+    }
+
+    /// Write bulk imports `batch_size` cards per transaction, `rows_per_statement` cards
+    /// per INSERT
+    pub fn with_import_tuning(mut self, batch_size: usize, rows_per_statement: usize) -> Self {
+        self.import_batch_size = batch_size.max(1);
+        self.rows_per_statement = rows_per_statement.clamp(1, queries::MAX_ROWS_PER_STATEMENT);
+        self
     }
 
     pub fn pool(&self) -> &SqlitePool {
@@ -25,112 +57,469 @@ impl SqliteBackend {
     }
 }
 
+/// Run a blocking SQLite call on the blocking thread pool
+async fn run_blocking<T, F>(f: F) -> ServiceResult<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ServiceError::Db(e.into()))?
+        .map_err(ServiceError::Db)
+}
+
 #[async_trait]
 impl DatabaseBackend for SqliteBackend {
-    async fn insert_cards_batch(&self, cards: &[Card]) -> Result<()> {
+    async fn insert_cards_batch(&self, cards: &[Card]) -> ServiceResult<()> {
         let pool = self.pool.clone();
         let cards = cards.to_vec(); // Clone to move into spawn_blocking
-        tokio::task::spawn_blocking(move || queries::insert_cards_batch(&pool, &cards)).await?
+        run_blocking(move || queries::insert_cards_batch(&pool, &cards)).await
     }
 
-    async fn get_card_by_id(&self, id: Uuid) -> Result<Option<Card>> {
+    async fn begin_staging(&self) -> ServiceResult<()> {
         let pool = self.pool.clone();
-        tokio::task::spawn_blocking(move || queries::get_card_by_id(&pool, id)).await?
+        run_blocking(move || queries::begin_staging(&pool)).await
+    }
+
+    async fn insert_cards_staging(&self, cards: &[Card]) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let cards = cards.to_vec();
+        let rows_per_statement = self.rows_per_statement;
+        run_blocking(move || queries::insert_cards_staging(&pool, &cards, rows_per_statement)).await
+    }
+
+    fn import_batch_size(&self) -> usize {
+        self.import_batch_size
     }
 
-    async fn get_cards_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Card>> {
+    async fn commit_staging(&self) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::merge_staging(&pool)).await
+    }
+
+    async fn discard_staging(&self) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::discard_staging(&pool)).await
+    }
+
+    async fn try_advisory_lock(&self, _name: &str) -> ServiceResult<Option<AdvisoryLock>> {
+        // A SQLite database belongs to a single instance, whose loads are already
+        // serialized in-process
+        Ok(Some(AdvisoryLock::new(())))
+    }
+
+    async fn get_card_by_id(&self, id: Uuid) -> ServiceResult<Option<Card>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::get_card_by_id(&pool, id)).await
+    }
+
+    async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>> {
+        let pool = self.pool.clone();
+        let set_code = set_code.to_string();
+        let collector_number = collector_number.to_string();
+        run_blocking(move || {
+            queries::get_card_by_set_and_number(&pool, &set_code, &collector_number)
+        })
+        .await
+    }
+
+    async fn get_card_by_external_id(
+        &self,
+        external_id: ExternalId,
+    ) -> ServiceResult<Option<Card>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::get_card_by_external_id(&pool, external_id)).await
+    }
+
+    async fn get_cards_by_ids(&self, ids: &[Uuid]) -> ServiceResult<Vec<Card>> {
         let pool = self.pool.clone();
         let ids = ids.to_vec();
-        tokio::task::spawn_blocking(move || queries::get_cards_by_ids(&pool, &ids)).await?
+        run_blocking(move || queries::get_cards_by_ids(&pool, &ids)).await
     }
 
-    async fn search_cards_by_name(&self, name: &str, limit: i64) -> Result<Vec<Card>> {
+    async fn list_card_relations(&self, id: Uuid) -> ServiceResult<Vec<CardPart>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::list_card_relations(&pool, id)).await
+    }
+
+    async fn find_integrity_issues(&self, limit: i64) -> ServiceResult<Vec<IntegrityIssue>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::find_integrity_issues(&pool, limit)).await
+    }
+
+    async fn get_card_by_id_projected(
+        &self,
+        id: Uuid,
+        projection: &CardProjection,
+    ) -> ServiceResult<Option<Card>> {
+        let pool = self.pool.clone();
+        let projection = *projection;
+        run_blocking(move || queries::get_card_by_id_projected(&pool, id, &projection)).await
+    }
+
+    async fn get_cards_by_ids_projected(
+        &self,
+        ids: &[Uuid],
+        projection: &CardProjection,
+    ) -> ServiceResult<Vec<Card>> {
+        let pool = self.pool.clone();
+        let ids = ids.to_vec();
+        let projection = *projection;
+        run_blocking(move || queries::get_cards_by_ids_projected(&pool, &ids, &projection)).await
+    }
+
+    fn dialect(&self) -> SqlDialect {
+        SqlDialect::Sqlite
+    }
+
+    fn card_columns(&self, projection: &CardProjection) -> String {
+        queries::projected_columns(projection)
+    }
+
+    async fn search_cards_by_name(
+        &self,
+        name: &str,
+        lang: Option<&str>,
+        include_tokens: bool,
+        limit: i64,
+    ) -> ServiceResult<Vec<Card>> {
         let pool = self.pool.clone();
         let name = name.to_string();
-        tokio::task::spawn_blocking(move || queries::search_cards_by_name(&pool, &name, limit))
-            .await?
+        let lang = lang.map(str::to_string);
+        run_blocking(move || {
+            queries::search_cards_by_name(&pool, &name, lang.as_deref(), include_tokens, limit)
+        })
+        .await
     }
 
-    async fn autocomplete_card_names(&self, prefix: &str, limit: i64) -> Result<Vec<String>> {
+    async fn autocomplete_card_names(
+        &self,
+        prefix: &str,
+        options: &AutocompleteOptions,
+    ) -> ServiceResult<Vec<String>> {
         let pool = self.pool.clone();
         let prefix = prefix.to_string();
-        tokio::task::spawn_blocking(move || queries::autocomplete_card_names(&pool, &prefix, limit))
-            .await?
+        let options = *options;
+        run_blocking(move || queries::autocomplete_card_names(&pool, &prefix, &options)).await
     }
 
     async fn store_query_cache(
         &self,
+        tenant_id: &str,
         query_hash: &str,
+        query_text: &str,
         card_ids: &[Uuid],
         ttl_hours: i32,
-    ) -> Result<()> {
+    ) -> ServiceResult<()> {
         let pool = self.pool.clone();
+        let tenant_id = tenant_id.to_string();
         let query_hash = query_hash.to_string();
+        let query_text = query_text.to_string();
         let card_ids = card_ids.to_vec(); // Clone to move
-        tokio::task::spawn_blocking(move || {
-            queries::store_query_cache(&pool, &query_hash, &card_ids, ttl_hours)
+        run_blocking(move || {
+            queries::store_query_cache(
+                &pool,
+                &tenant_id,
+                &query_hash,
+                &query_text,
+                &card_ids,
+                ttl_hours,
+            )
         })
-        .await?
+        .await
     }
 
-    async fn get_query_cache(&self, query_hash: &str) -> Result<Option<(Vec<Uuid>, i32)>> {
+    async fn get_query_cache(&self, query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>> {
         let pool = self.pool.clone();
         let query_hash = query_hash.to_string();
-        tokio::task::spawn_blocking(move || queries::get_query_cache(&pool, &query_hash)).await?
+        run_blocking(move || queries::get_query_cache(&pool, &query_hash)).await
     }
 
-    async fn record_bulk_import(&self, total_cards: i32, source: &str) -> Result<()> {
+    async fn peek_query_cache(&self, query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>> {
         let pool = self.pool.clone();
-        let source = source.to_string();
-        tokio::task::spawn_blocking(move || {
-            queries::record_bulk_import(&pool, total_cards, &source)
+        let query_hash = query_hash.to_string();
+        run_blocking(move || queries::peek_query_cache(&pool, &query_hash)).await
+    }
+
+    async fn list_query_cache(&self, limit: i64) -> ServiceResult<Vec<QueryCacheEntry>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::list_query_cache(&pool, limit)).await
+    }
+
+    async fn delete_query_cache(&self, query_hash: &str) -> ServiceResult<bool> {
+        let pool = self.pool.clone();
+        let query_hash = query_hash.to_string();
+        run_blocking(move || queries::delete_query_cache(&pool, &query_hash)).await
+    }
+
+    async fn clear_query_cache(&self) -> ServiceResult<u64> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::clear_query_cache(&pool)).await
+    }
+
+    async fn record_bulk_import(&self, import: &BulkImport) -> ServiceResult<i32> {
+        let pool = self.pool.clone();
+        let import = import.clone();
+        run_blocking(move || queries::record_bulk_import(&pool, &import)).await
+    }
+
+    async fn list_bulk_imports(&self, limit: i64) -> ServiceResult<Vec<BulkImport>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::list_bulk_imports(&pool, limit)).await
+    }
+
+    async fn record_bulk_import_errors(
+        &self,
+        import_id: i32,
+        errors: &[BulkImportError],
+    ) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let errors = errors.to_vec();
+        run_blocking(move || queries::record_bulk_import_errors(&pool, import_id, &errors)).await
+    }
+
+    async fn list_bulk_import_errors(
+        &self,
+        import_id: i32,
+    ) -> ServiceResult<Option<Vec<BulkImportError>>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::list_bulk_import_errors(&pool, import_id)).await
+    }
+
+    async fn record_admin_audit(&self, entry: &AdminAuditEntry) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let entry = entry.clone();
+        run_blocking(move || queries::record_admin_audit(&pool, &entry)).await
+    }
+
+    async fn list_admin_audit(
+        &self,
+        limit: i64,
+        actor: Option<&str>,
+    ) -> ServiceResult<Vec<AdminAuditEntry>> {
+        let pool = self.pool.clone();
+        let actor = actor.map(str::to_string);
+        run_blocking(move || queries::list_admin_audit(&pool, limit, actor.as_deref())).await
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_hash: &str,
+        ttl_seconds: i64,
+    ) -> ServiceResult<Option<IdempotencyRecord>> {
+        let pool = self.pool.clone();
+        let key = key.to_string();
+        let request_hash = request_hash.to_string();
+        run_blocking(move || {
+            queries::claim_idempotency_key(&pool, &key, &request_hash, ttl_seconds)
+        })
+        .await
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        record: &IdempotencyRecord,
+    ) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let key = key.to_string();
+        let record = record.clone();
+        run_blocking(move || queries::complete_idempotency_key(&pool, &key, &record)).await
+    }
+
+    async fn release_idempotency_key(&self, key: &str) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let key = key.to_string();
+        run_blocking(move || queries::release_idempotency_key(&pool, &key)).await
+    }
+
+    async fn clean_expired_idempotency_keys(&self) -> ServiceResult<u64> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::clean_expired_idempotency_keys(&pool)).await
+    }
+
+    async fn upsert_collection_entries(
+        &self,
+        tenant_id: &str,
+        collection_id: &str,
+        entries: &[CollectionEntry],
+    ) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let tenant_id = tenant_id.to_string();
+        let collection_id = collection_id.to_string();
+        let entries = entries.to_vec();
+        run_blocking(move || {
+            queries::upsert_collection_entries(&pool, &tenant_id, &collection_id, &entries)
         })
-        .await?
+        .await
+    }
+
+    async fn list_collection_entries(
+        &self,
+        tenant_id: &str,
+        collection_id: &str,
+    ) -> ServiceResult<Vec<CollectionEntry>> {
+        let pool = self.pool.clone();
+        let tenant_id = tenant_id.to_string();
+        let collection_id = collection_id.to_string();
+        run_blocking(move || queries::list_collection_entries(&pool, &tenant_id, &collection_id))
+            .await
+    }
+
+    async fn insert_saved_search(
+        &self,
+        search: &SavedSearch,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let search = search.clone();
+        let card_ids = card_ids.to_vec();
+        run_blocking(move || queries::insert_saved_search(&pool, &search, &card_ids)).await
+    }
+
+    async fn list_saved_searches(
+        &self,
+        tenant_id: Option<&str>,
+    ) -> ServiceResult<Vec<SavedSearch>> {
+        let pool = self.pool.clone();
+        let tenant_id = tenant_id.map(str::to_string);
+        run_blocking(move || queries::list_saved_searches(&pool, tenant_id.as_deref())).await
+    }
+
+    async fn get_saved_search(
+        &self,
+        tenant_id: &str,
+        id: Uuid,
+    ) -> ServiceResult<Option<SavedSearch>> {
+        let pool = self.pool.clone();
+        let tenant_id = tenant_id.to_string();
+        run_blocking(move || queries::get_saved_search(&pool, &tenant_id, id)).await
+    }
+
+    async fn delete_saved_search(&self, tenant_id: &str, id: Uuid) -> ServiceResult<bool> {
+        let pool = self.pool.clone();
+        let tenant_id = tenant_id.to_string();
+        run_blocking(move || queries::delete_saved_search(&pool, &tenant_id, id)).await
+    }
+
+    async fn record_saved_search_run(
+        &self,
+        id: Uuid,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<Vec<Uuid>> {
+        let pool = self.pool.clone();
+        let card_ids = card_ids.to_vec();
+        run_blocking(move || queries::record_saved_search_run(&pool, id, &card_ids)).await
     }
 
-    async fn clean_old_cache_entries(&self, hours: i32) -> Result<u64> {
+    async fn list_saved_search_matches(&self, id: Uuid, run: i32) -> ServiceResult<Vec<Uuid>> {
         let pool = self.pool.clone();
-        tokio::task::spawn_blocking(move || queries::clean_old_cache_entries(&pool, hours)).await?
+        run_blocking(move || queries::list_saved_search_matches(&pool, id, run)).await
     }
 
-    async fn test_connection(&self) -> Result<()> {
+    async fn record_card_provenance(
+        &self,
+        bulk_type: &str,
+        card_ids: &[Uuid],
+    ) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let bulk_type = bulk_type.to_string();
+        let card_ids = card_ids.to_vec();
+        run_blocking(move || queries::record_card_provenance(&pool, &bulk_type, &card_ids)).await
+    }
+
+    async fn clean_old_cache_entries(&self, hours: i32) -> ServiceResult<u64> {
         let pool = self.pool.clone();
-        tokio::task::spawn_blocking(move || connection::test_connection(&pool)).await?
+        run_blocking(move || queries::clean_old_cache_entries(&pool, hours)).await
     }
 
-    async fn execute_raw_query(&self, sql: &str, params: &[String]) -> Result<Vec<Card>> {
+    async fn test_connection(&self) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        run_blocking(move || connection::test_connection(&pool)).await
+    }
+
+    async fn execute_raw_query(&self, sql: &str, params: &[String]) -> ServiceResult<Vec<Card>> {
         let pool = self.pool.clone();
         let sql = sql.to_string();
         let params = params.to_vec();
-        tokio::task::spawn_blocking(move || queries::execute_raw_query(&pool, &sql, &params))
-            .await?
+        run_blocking(move || queries::execute_raw_query(&pool, &sql, &params)).await
+    }
+
+    fn stream_raw_query(&self, sql: String, params: Vec<String>) -> CardStream {
+        let pool = self.pool.clone();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER_ROWS);
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = queries::stream_raw_query(&pool, &sql, &params, &tx) {
+                let _ = tx.blocking_send(Err(e));
+            }
+        });
+        channel_stream(rx)
     }
 
-    async fn count_query(&self, sql: &str, params: &[String]) -> Result<usize> {
+    async fn count_query(&self, sql: &str, params: &[String]) -> ServiceResult<usize> {
         let pool = self.pool.clone();
         let sql = sql.to_string();
         let params = params.to_vec();
-        tokio::task::spawn_blocking(move || queries::count_query(&pool, &sql, &params)).await?
+        run_blocking(move || queries::count_query(&pool, &sql, &params)).await
+    }
+
+    async fn check_bulk_data_loaded(&self) -> ServiceResult<bool> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::check_bulk_data_loaded(&pool)).await
+    }
+
+    async fn get_last_bulk_import(&self) -> ServiceResult<Option<chrono::NaiveDateTime>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::get_last_bulk_import(&pool)).await
+    }
+
+    async fn record_sync(&self, run: &SyncRun) -> ServiceResult<()> {
+        let pool = self.pool.clone();
+        let run = run.clone();
+        run_blocking(move || queries::record_sync(&pool, &run)).await
+    }
+
+    async fn get_last_sync(&self) -> ServiceResult<Option<chrono::NaiveDateTime>> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::get_last_sync(&pool)).await
+    }
+
+    async fn get_card_count(&self) -> ServiceResult<i64> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::get_card_count(&pool)).await
+    }
+
+    async fn get_cache_entry_count(&self) -> ServiceResult<i64> {
+        let pool = self.pool.clone();
+        run_blocking(move || queries::get_cache_entry_count(&pool)).await
     }
 
-    async fn check_bulk_data_loaded(&self) -> Result<bool> {
+    async fn get_database_size_bytes(&self) -> ServiceResult<i64> {
         let pool = self.pool.clone();
-        tokio::task::spawn_blocking(move || queries::check_bulk_data_loaded(&pool)).await?
+        run_blocking(move || queries::get_database_size_bytes(&pool)).await
     }
 
-    async fn get_last_bulk_import(&self) -> Result<Option<chrono::NaiveDateTime>> {
+    async fn count_query_cache_by_tenant(&self) -> ServiceResult<Vec<(String, i64)>> {
         let pool = self.pool.clone();
-        tokio::task::spawn_blocking(move || queries::get_last_bulk_import(&pool)).await?
+        run_blocking(move || queries::count_query_cache_by_tenant(&pool)).await
     }
 
-    async fn get_card_count(&self) -> Result<i64> {
+    async fn get_query_cache_hit_count(&self) -> ServiceResult<i64> {
         let pool = self.pool.clone();
-        tokio::task::spawn_blocking(move || queries::get_card_count(&pool)).await?
+        run_blocking(move || queries::get_query_cache_hit_count(&pool)).await
     }
 
-    async fn get_cache_entry_count(&self) -> Result<i64> {
+    async fn get_schema_version(&self) -> ServiceResult<SchemaVersion> {
         let pool = self.pool.clone();
-        tokio::task::spawn_blocking(move || queries::get_cache_entry_count(&pool)).await?
+        let applied = run_blocking(move || queries::list_applied_migrations(&pool)).await?;
+        let known = migrations::MIGRATIONS.iter().map(|m| m.version);
+        Ok(SchemaVersion::new("sqlite", known, applied))
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
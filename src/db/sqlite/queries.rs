@@ -1,120 +1,354 @@
 use anyhow::{Context, Result};
-use rusqlite::{params, OptionalExtension};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use crate::db::backend::AutocompleteOptions;
+use crate::db::projection::CardProjection;
 use crate::db::sqlite::connection::SqlitePool;
-use crate::models::card::Card;
+use crate::models::admin_audit::AdminAuditEntry;
+use crate::models::bulk_import::{BulkImport, BulkImportError};
+use crate::models::card::{Card, CardPart, ExternalId, EXTRA_LAYOUTS};
+use crate::models::collection::{CollectionEntry, Finish};
+use crate::models::idempotency::IdempotencyRecord;
+use crate::models::query_cache::QueryCacheEntry;
+use crate::models::repair::IntegrityIssue;
+use crate::models::saved_search::SavedSearch;
+use crate::models::schema_version::AppliedMigration;
+use crate::models::sync::SyncRun;
+
+/// Format of `CURRENT_TIMESTAMP` values, used for all stored timestamps
+const SQLITE_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Columns written by card inserts, in the order `card_values` returns them
+const CARD_INSERT_COLUMNS: &str = r#"
+    id, oracle_id, name, mana_cost, cmc, type_line, oracle_text,
+    colors, color_identity, set_code, set_name, collector_number,
+    rarity, power, toughness, loyalty, keywords, prices, image_uris,
+    card_faces, legalities, released_at, raw_json, lang,
+    printed_name, printed_type_line, printed_text, layout,
+    multiverse_ids, arena_id, mtgo_id, mtgo_foil_id,
+    power_num, toughness_num, loyalty_num,
+    price_usd, price_eur, price_tix, face_names,
+    edhrec_rank, penny_rank
+"#;
+
+/// Number of columns in `CARD_INSERT_COLUMNS`
+const CARD_INSERT_COLUMN_COUNT: usize = 41;
+
+/// Largest multi-row insert, keeping a statement under SQLite's 32766 bound parameters
+pub const MAX_ROWS_PER_STATEMENT: usize = 32766 / CARD_INSERT_COLUMN_COUNT;
+
+/// Conflict clause shared by card inserts: a card already stored is overwritten
+const CARD_UPSERT: &str = r#"
+    ON CONFLICT(id) DO UPDATE SET
+        oracle_id = excluded.oracle_id,
+        name = excluded.name,
+        mana_cost = excluded.mana_cost,
+        cmc = excluded.cmc,
+        type_line = excluded.type_line,
+        oracle_text = excluded.oracle_text,
+        colors = excluded.colors,
+        color_identity = excluded.color_identity,
+        set_code = excluded.set_code,
+        set_name = excluded.set_name,
+        collector_number = excluded.collector_number,
+        rarity = excluded.rarity,
+        power = excluded.power,
+        toughness = excluded.toughness,
+        loyalty = excluded.loyalty,
+        keywords = excluded.keywords,
+        prices = excluded.prices,
+        image_uris = excluded.image_uris,
+        card_faces = excluded.card_faces,
+        legalities = excluded.legalities,
+        released_at = excluded.released_at,
+        raw_json = excluded.raw_json,
+        lang = excluded.lang,
+        printed_name = excluded.printed_name,
+        printed_type_line = excluded.printed_type_line,
+        printed_text = excluded.printed_text,
+        layout = excluded.layout,
+        multiverse_ids = excluded.multiverse_ids,
+        arena_id = excluded.arena_id,
+        mtgo_id = excluded.mtgo_id,
+        mtgo_foil_id = excluded.mtgo_foil_id,
+        power_num = excluded.power_num,
+        toughness_num = excluded.toughness_num,
+        loyalty_num = excluded.loyalty_num,
+        price_usd = excluded.price_usd,
+        price_eur = excluded.price_eur,
+        price_tix = excluded.price_tix,
+        face_names = excluded.face_names,
+        edhrec_rank = excluded.edhrec_rank,
+        penny_rank = excluded.penny_rank,
+        updated_at = CURRENT_TIMESTAMP
+"#;
 
 /// Insert a batch of cards into the database
 pub fn insert_cards_batch(pool: &SqlitePool, cards: &[Card]) -> Result<()> {
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    insert_cards_into(&mut conn, "cards", cards, 1)
+}
+
+/// Upsert a batch of cards into `table` (`cards` or `cards_staging`) in one transaction,
+/// `rows_per_statement` cards per INSERT. Statements are prepared through the
+/// connection's statement cache, so later batches on the same connection reuse them.
+fn insert_cards_into(
+    conn: &mut Connection,
+    table: &str,
+    cards: &[Card],
+    rows_per_statement: usize,
+) -> Result<()> {
     if cards.is_empty() {
         return Ok(());
     }
+    let rows_per_statement = rows_per_statement.clamp(1, MAX_ROWS_PER_STATEMENT);
 
-    let mut conn = pool.get().context("Failed to get connection from pool")?;
     let tx = conn.transaction().context("Failed to begin transaction")?;
+    {
+        let mut chunks = cards.chunks_exact(rows_per_statement);
+        let mut stmt = tx
+            .prepare_cached(&insert_sql(table, rows_per_statement))
+            .context("Failed to prepare card insert")?;
+        for chunk in &mut chunks {
+            stmt.execute(params_from_iter(chunk_values(chunk)?))
+                .context("Failed to insert card")?;
+        }
+
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            tx.prepare_cached(&insert_sql(table, rest.len()))
+                .context("Failed to prepare card insert")?
+                .execute(params_from_iter(chunk_values(rest)?))
+                .context("Failed to insert card")?;
+        }
+    }
+    replace_card_relations(&tx, cards)?;
+
+    tx.commit().context("Failed to commit transaction")?;
+    Ok(())
+}
+
+/// Replace the `card_relations` rows of `cards` with the links in their `all_parts`
+fn replace_card_relations(conn: &Connection, cards: &[Card]) -> Result<()> {
+    let mut delete = conn
+        .prepare_cached("DELETE FROM card_relations WHERE card_id = ?1")
+        .context("Failed to prepare card relation delete")?;
+    let mut insert = conn
+        .prepare_cached(
+            "INSERT OR IGNORE INTO card_relations (card_id, related_id, component, name, type_line) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .context("Failed to prepare card relation insert")?;
 
     for card in cards {
-        let colors_json = card
-            .colors
-            .as_ref()
-            .map(|v| serde_json::to_string(v).unwrap_or_default());
-        let color_identity_json = card
-            .color_identity
-            .as_ref()
-            .map(|v| serde_json::to_string(v).unwrap_or_default());
-        let keywords_json = card
-            .keywords
-            .as_ref()
-            .map(|v| serde_json::to_string(v).unwrap_or_default());
-        let prices_json = card
-            .prices
-            .as_ref()
-            .map(|v| serde_json::to_string(v).unwrap_or_default());
-        let image_uris_json = card
-            .image_uris
-            .as_ref()
-            .map(|v| serde_json::to_string(v).unwrap_or_default());
-        let card_faces_json = card
-            .card_faces
-            .as_ref()
-            .map(|v| serde_json::to_string(v).unwrap_or_default());
-        let legalities_json = card
-            .legalities
+        let card_id = card.id.to_string();
+        delete
+            .execute(params![card_id])
+            .context("Failed to clear card relations")?;
+        for part in card.related_parts() {
+            insert
+                .execute(params![
+                    card_id,
+                    part.id.to_string(),
+                    part.component,
+                    part.name,
+                    part.type_line
+                ])
+                .context("Failed to insert card relation")?;
+        }
+    }
+    Ok(())
+}
+
+/// Upsert statement for `rows` cards into `table`
+fn insert_sql(table: &str, rows: usize) -> String {
+    let row = format!("({})", vec!["?"; CARD_INSERT_COLUMN_COUNT].join(", "));
+    format!(
+        "INSERT INTO {table} ({CARD_INSERT_COLUMNS}) VALUES {} {CARD_UPSERT}",
+        vec![row; rows].join(", ")
+    )
+}
+
+/// Bound values for a multi-row insert of `cards`
+fn chunk_values(cards: &[Card]) -> Result<Vec<Value>> {
+    let mut values = Vec::with_capacity(cards.len() * CARD_INSERT_COLUMN_COUNT);
+    for card in cards {
+        values.extend(card_values(card)?);
+    }
+    Ok(values)
+}
+
+/// Column values of `card`, in `CARD_INSERT_COLUMNS` order. List and object columns are
+/// stored as JSON text.
+fn card_values(card: &Card) -> Result<[Value; CARD_INSERT_COLUMN_COUNT]> {
+    fn json<T: serde::Serialize>(value: &Option<T>) -> Value {
+        value
             .as_ref()
-            .map(|v| serde_json::to_string(v).unwrap_or_default());
-        let raw_json =
-            serde_json::to_string(&card.raw_json).context("Failed to serialize raw_json")?;
+            .map(|v| serde_json::to_string(v).unwrap_or_default())
+            .into()
+    }
+    let raw_json = serde_json::to_string(&card.raw_json).context("Failed to serialize raw_json")?;
+
+    Ok([
+        card.id.to_string().into(),
+        card.oracle_id.map(|u| u.to_string()).into(),
+        card.name.clone().into(),
+        card.mana_cost.clone().into(),
+        card.cmc.into(),
+        card.type_line.clone().into(),
+        card.oracle_text.clone().into(),
+        json(&card.colors),
+        json(&card.color_identity),
+        card.set_code.clone().into(),
+        card.set_name.clone().into(),
+        card.collector_number.clone().into(),
+        card.rarity.clone().into(),
+        card.power.clone().into(),
+        card.toughness.clone().into(),
+        card.loyalty.clone().into(),
+        json(&card.keywords),
+        json(&card.prices),
+        json(&card.image_uris),
+        json(&card.card_faces),
+        json(&card.legalities),
+        card.released_at.map(|d| d.to_string()).into(),
+        raw_json.into(),
+        card.lang.clone().into(),
+        card.printed_name.clone().into(),
+        card.printed_type_line.clone().into(),
+        card.printed_text.clone().into(),
+        card.layout.clone().into(),
+        json(&card.multiverse_ids),
+        card.arena_id.into(),
+        card.mtgo_id.into(),
+        card.mtgo_foil_id.into(),
+        card.power_num.into(),
+        card.toughness_num.into(),
+        card.loyalty_num.into(),
+        card.price_usd.into(),
+        card.price_eur.into(),
+        card.price_tix.into(),
+        json(&card.face_names),
+        card.edhrec_rank.into(),
+        card.penny_rank.into(),
+    ])
+}
 
-        tx.execute(
-            r#"
-            INSERT INTO cards (
-                id, oracle_id, name, mana_cost, cmc, type_line, oracle_text,
-                colors, color_identity, set_code, set_name, collector_number,
-                rarity, power, toughness, loyalty, keywords, prices, image_uris,
-                card_faces, legalities, released_at, raw_json
-            ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
-                ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23
+/// Run `f` with `synchronous = NORMAL`, restoring the previous setting afterwards. In WAL
+/// mode this skips an fsync per transaction; a crash can lose the last few transactions
+/// but can't corrupt the database, and an interrupted bulk import is discarded anyway.
+fn with_import_pragmas<T>(
+    conn: &mut Connection,
+    f: impl FnOnce(&mut Connection) -> Result<T>,
+) -> Result<T> {
+    let previous: i64 = conn
+        .pragma_query_value(None, "synchronous", |row| row.get(0))
+        .context("Failed to read synchronous pragma")?;
+    conn.pragma_update(None, "synchronous", "NORMAL")
+        .context("Failed to set synchronous pragma")?;
+    let result = f(conn);
+    conn.pragma_update(None, "synchronous", previous)
+        .context("Failed to restore synchronous pragma")?;
+    result
+}
+
+/// Create an empty `cards_staging` table from the `cards` table definition, dropping any
+/// left behind by an interrupted load
+pub fn begin_staging(pool: &SqlitePool) -> Result<()> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let definition: String = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'cards'",
+            params![],
+            |row| row.get(0),
+        )
+        .context("Failed to read cards table definition")?;
+    let definition = staged_table_sql(&definition).context("Unexpected cards table definition")?;
+
+    conn.execute("DROP TABLE IF EXISTS cards_staging", params![])
+        .context("Failed to drop leftover cards_staging table")?;
+    conn.execute(&definition, params![])
+        .context("Failed to create cards_staging table")?;
+    Ok(())
+}
+
+/// Insert a batch of cards into `cards_staging`
+pub fn insert_cards_staging(
+    pool: &SqlitePool,
+    cards: &[Card],
+    rows_per_statement: usize,
+) -> Result<()> {
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    with_import_pragmas(&mut conn, |conn| {
+        insert_cards_into(conn, "cards_staging", cards, rows_per_statement)
+    })
+}
+
+/// Upsert every staged card into `cards` and drop `cards_staging`, in one transaction so
+/// readers see either the old or the new data. Cards missing from the staged data (such
+/// as ones fetched from the Scryfall API) are left in place.
+pub fn merge_staging(pool: &SqlitePool) -> Result<()> {
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+
+    let columns: Vec<String> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT name FROM pragma_table_info('cards_staging') WHERE name NOT IN ('created_at', 'updated_at')",
             )
-            ON CONFLICT(id) DO UPDATE SET
-                oracle_id = excluded.oracle_id,
-                name = excluded.name,
-                mana_cost = excluded.mana_cost,
-                cmc = excluded.cmc,
-                type_line = excluded.type_line,
-                oracle_text = excluded.oracle_text,
-                colors = excluded.colors,
-                color_identity = excluded.color_identity,
-                set_code = excluded.set_code,
-                set_name = excluded.set_name,
-                collector_number = excluded.collector_number,
-                rarity = excluded.rarity,
-                power = excluded.power,
-                toughness = excluded.toughness,
-                loyalty = excluded.loyalty,
-                keywords = excluded.keywords,
-                prices = excluded.prices,
-                image_uris = excluded.image_uris,
-                card_faces = excluded.card_faces,
-                legalities = excluded.legalities,
-                released_at = excluded.released_at,
-                raw_json = excluded.raw_json,
-                updated_at = CURRENT_TIMESTAMP
-            "#,
-            params![
-                card.id.to_string(),
-                card.oracle_id.map(|u| u.to_string()),
-                &card.name,
-                &card.mana_cost,
-                card.cmc,
-                &card.type_line,
-                &card.oracle_text,
-                colors_json,
-                color_identity_json,
-                &card.set_code,
-                &card.set_name,
-                &card.collector_number,
-                &card.rarity,
-                &card.power,
-                &card.toughness,
-                &card.loyalty,
-                keywords_json,
-                prices_json,
-                image_uris_json,
-                card_faces_json,
-                legalities_json,
-                card.released_at.map(|d| d.to_string()),
-                raw_json,
-            ],
-        )
-        .context("Failed to insert card")?;
-    }
+            .context("Failed to prepare cards_staging column listing")?;
+        let rows = stmt
+            .query_map(params![], |row| row.get(0))
+            .context("Failed to list cards_staging columns")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read cards_staging column")?
+    };
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|c| c.as_str() != "id")
+        .map(|c| format!("{c} = excluded.{c}"))
+        .collect();
+    let columns = columns.join(", ");
+    // `WHERE true` keeps SQLite from parsing ON CONFLICT as a join constraint
+    let sql = format!(
+        r#"
+        INSERT INTO cards ({columns})
+        SELECT {columns} FROM cards_staging WHERE true
+        ON CONFLICT(id) DO UPDATE SET {}, updated_at = CURRENT_TIMESTAMP
+        "#,
+        updates.join(", ")
+    );
+
+    with_import_pragmas(&mut conn, |conn| {
+        let tx = conn.transaction().context("Failed to begin transaction")?;
+        tx.execute(&sql, params![])
+            .context("Failed to merge cards_staging into cards")?;
+        tx.execute("DROP TABLE cards_staging", params![])
+            .context("Failed to drop cards_staging table")?;
+        tx.commit().context("Failed to commit staging merge")
+    })
+}
 
-    tx.commit().context("Failed to commit transaction")?;
+/// Drop `cards_staging` after a failed load, leaving `cards` untouched
+pub fn discard_staging(pool: &SqlitePool) -> Result<()> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+    conn.execute("DROP TABLE IF EXISTS cards_staging", params![])
+        .context("Failed to drop cards_staging table")?;
     Ok(())
 }
 
+/// Rename the table in a `CREATE TABLE cards (...)` statement to `cards_staging`
+fn staged_table_sql(definition: &str) -> Option<String> {
+    let (head, rest) = definition.split_once('(')?;
+    let name = head.trim_end().rsplit(' ').next()?;
+    if name.trim_matches(|c| c == '"' || c == '`' || c == '[' || c == ']') != "cards" {
+        return None;
+    }
+    Some(format!("CREATE TABLE cards_staging ({}", rest))
+}
+
 /// Get a card by ID
 pub fn get_card_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Card>> {
     let conn = pool.get().context("Failed to get connection from pool")?;
@@ -131,6 +365,151 @@ pub fn get_card_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Card>> {
     Ok(card)
 }
 
+/// Cards linked to card `id` through `all_parts`, grouped by how they are linked
+pub fn list_card_relations(pool: &SqlitePool, id: Uuid) -> Result<Vec<CardPart>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+        SELECT related_id, component, name, type_line
+        FROM card_relations
+        WHERE card_id = ?1
+        ORDER BY component, name, related_id
+        "#,
+        )
+        .context("Failed to prepare card relation listing")?;
+
+    let rows = stmt
+        .query_map(params![id.to_string()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .context("Failed to list card relations")?;
+
+    let mut parts = Vec::new();
+    for row in rows {
+        let (related_id, component, name, type_line) =
+            row.context("Failed to read card relation")?;
+        let Ok(id) = Uuid::parse_str(&related_id) else {
+            continue;
+        };
+        parts.push(CardPart {
+            id,
+            component,
+            name,
+            type_line,
+        });
+    }
+    Ok(parts)
+}
+
+/// Cards whose stored row is inconsistent, first issue per card, at most `limit`.
+/// CASE branches are evaluated in order, so `json_type` only sees valid JSON.
+pub fn find_integrity_issues(pool: &SqlitePool, limit: i64) -> Result<Vec<IntegrityIssue>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+        SELECT id, name, issue
+        FROM (
+            SELECT id, COALESCE(name, '') AS name,
+                CASE
+                    WHEN raw_json IS NULL OR json_valid(raw_json) = 0 THEN 'invalid_raw_json'
+                    WHEN json_type(raw_json) <> 'object' THEN 'invalid_raw_json'
+                    WHEN json_extract(raw_json, '$.id') IS NOT id THEN 'id_mismatch'
+                    WHEN name IS NULL OR trim(name) = '' THEN 'missing_name'
+                    WHEN oracle_id IS NULL AND layout IS NOT 'reversible_card'
+                        THEN 'missing_oracle_id'
+                    WHEN card_faces IS NOT NULL AND json_valid(card_faces) = 0
+                        THEN 'orphan_card_faces'
+                    WHEN card_faces IS NOT NULL
+                        AND (json_type(card_faces) <> 'array'
+                            OR json_type(raw_json, '$.card_faces') IS NULL)
+                        THEN 'orphan_card_faces'
+                END AS issue
+            FROM cards
+        )
+        WHERE issue IS NOT NULL
+        ORDER BY id
+        LIMIT ?1
+        "#,
+        )
+        .context("Failed to prepare integrity scan")?;
+
+    let rows = stmt
+        .query_map(params![limit], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .context("Failed to scan cards for integrity issues")?;
+
+    let mut issues = Vec::new();
+    for row in rows {
+        let (id, name, issue) = row.context("Failed to read integrity issue")?;
+        let Ok(id) = Uuid::parse_str(&id) else {
+            continue;
+        };
+        issues.push(IntegrityIssue { id, name, issue });
+    }
+    Ok(issues)
+}
+
+/// Get a printing by set code and collector number (uses idx_cards_set_collector)
+pub fn get_card_by_set_and_number(
+    pool: &SqlitePool,
+    set_code: &str,
+    collector_number: &str,
+) -> Result<Option<Card>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let card = conn
+        .query_row(
+            r#"
+            SELECT * FROM cards
+            WHERE set_code = ?1 AND collector_number = ?2
+            ORDER BY COALESCE(lang, 'en') != 'en'
+            LIMIT 1
+            "#,
+            params![set_code.to_lowercase(), collector_number],
+            row_to_card,
+        )
+        .optional()
+        .context("Failed to fetch card by set and collector number")?;
+
+    Ok(card)
+}
+
+/// Get a card by one of its external ids. Multiverse lookups scan the JSON array
+/// column; Arena and MTGO lookups use their indexes.
+pub fn get_card_by_external_id(pool: &SqlitePool, external_id: ExternalId) -> Result<Option<Card>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let condition = match external_id {
+        ExternalId::Multiverse(_) => {
+            "EXISTS (SELECT 1 FROM json_each(cards.multiverse_ids) WHERE json_each.value = ?1)"
+        }
+        ExternalId::Arena(_) => "arena_id = ?1",
+        ExternalId::Mtgo(_) => "(mtgo_id = ?1 OR mtgo_foil_id = ?1)",
+    };
+    let sql = format!("SELECT * FROM cards WHERE {} LIMIT 1", condition);
+
+    let card = conn
+        .query_row(&sql, params![external_id.value()], row_to_card)
+        .optional()
+        .context("Failed to fetch card by external id")?;
+
+    Ok(card)
+}
+
 /// Get multiple cards by IDs
 pub fn get_cards_by_ids(pool: &SqlitePool, ids: &[Uuid]) -> Result<Vec<Card>> {
     if ids.is_empty() {
@@ -159,17 +538,100 @@ pub fn get_cards_by_ids(pool: &SqlitePool, ids: &[Uuid]) -> Result<Vec<Card>> {
     Ok(cards)
 }
 
+/// SELECT column list for `projection`, with literals for omitted JSON columns
+pub fn projected_columns(projection: &CardProjection) -> String {
+    projection.select_list("NULL", "'{}'")
+}
+
+/// Get a card by ID, reading only the projected columns
+pub fn get_card_by_id_projected(
+    pool: &SqlitePool,
+    id: Uuid,
+    projection: &CardProjection,
+) -> Result<Option<Card>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+    let sql = format!(
+        "SELECT {} FROM cards WHERE id = ?1",
+        projected_columns(projection)
+    );
+
+    let card = conn
+        .query_row(&sql, params![id.to_string()], row_to_card)
+        .optional()
+        .context("Failed to fetch projected card by ID")?;
+
+    Ok(card)
+}
+
+/// Get multiple cards by IDs, reading only the projected columns
+pub fn get_cards_by_ids_projected(
+    pool: &SqlitePool,
+    ids: &[Uuid],
+    projection: &CardProjection,
+) -> Result<Vec<Card>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conn = pool.get().context("Failed to get connection from pool")?;
+    let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let query = format!(
+        "SELECT {} FROM cards WHERE id IN ({})",
+        projected_columns(projection),
+        placeholders
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .context("Failed to prepare statement")?;
+    let id_strings: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+
+    let cards = stmt
+        .query_map(rusqlite::params_from_iter(id_strings.iter()), row_to_card)
+        .context("Failed to query projected cards")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to map rows to cards")?;
+
+    Ok(cards)
+}
+
 /// Search cards by name (fuzzy search)
-pub fn search_cards_by_name(pool: &SqlitePool, name: &str, limit: i64) -> Result<Vec<Card>> {
+pub fn search_cards_by_name(
+    pool: &SqlitePool,
+    name: &str,
+    lang: Option<&str>,
+    include_tokens: bool,
+    limit: i64,
+) -> Result<Vec<Card>> {
     let conn = pool.get().context("Failed to get connection from pool")?;
     let search_pattern = format!("%{}%", name);
 
     let mut stmt = conn
-        .prepare("SELECT * FROM cards WHERE name LIKE ?1 COLLATE NOCASE LIMIT ?2")
+        .prepare(
+            r#"
+            SELECT * FROM cards
+            WHERE (
+                name LIKE ?1 COLLATE NOCASE
+                OR EXISTS (SELECT 1 FROM json_each(cards.face_names) WHERE value = LOWER(?5))
+                OR (?3 IS NOT NULL AND printed_name LIKE ?1 COLLATE NOCASE)
+            )
+            AND (?3 IS NULL OR lang = ?3)
+            AND (?4 OR layout IS NULL OR layout NOT IN ('token', 'double_faced_token', 'emblem'))
+            ORDER BY
+                name = ?5 COLLATE NOCASE DESC,
+                EXISTS (SELECT 1 FROM json_each(cards.face_names) WHERE value = LOWER(?5)) DESC,
+                COALESCE(lang, 'en') != 'en',
+                name
+            LIMIT ?2
+            "#,
+        )
         .context("Failed to prepare statement")?;
 
     let cards = stmt
-        .query_map(params![search_pattern, limit], row_to_card)
+        .query_map(
+            params![search_pattern, limit, lang, include_tokens, name],
+            row_to_card,
+        )
         .context("Failed to query cards")?
         .collect::<Result<Vec<_>, _>>()
         .context("Failed to map rows to cards")?;
@@ -179,16 +641,34 @@ pub fn search_cards_by_name(pool: &SqlitePool, name: &str, limit: i64) -> Result
 
 /// Autocomplete card names by prefix (case-insensitive)
 /// Returns distinct card names that start with the given prefix, sorted alphabetically
-pub fn autocomplete_card_names(pool: &SqlitePool, prefix: &str, limit: i64) -> Result<Vec<String>> {
+pub fn autocomplete_card_names(
+    pool: &SqlitePool,
+    prefix: &str,
+    options: &AutocompleteOptions,
+) -> Result<Vec<String>> {
     let conn = pool.get().context("Failed to get connection from pool")?;
     let search_pattern = format!("{}%", prefix);
 
-    let mut stmt = conn.prepare(
-        "SELECT DISTINCT name FROM cards WHERE name LIKE ?1 COLLATE NOCASE ORDER BY name LIMIT ?2"
-    ).context("Failed to prepare statement")?;
+    let mut conditions = vec!["name LIKE ?1 COLLATE NOCASE".to_string()];
+    if !options.include_extras {
+        let layouts: Vec<String> = EXTRA_LAYOUTS.iter().map(|l| format!("'{}'", l)).collect();
+        conditions.push(format!(
+            "(layout IS NULL OR layout NOT IN ({}))",
+            layouts.join(", ")
+        ));
+    }
+    if options.exclude_digital {
+        conditions.push("COALESCE(json_extract(raw_json, '$.digital'), 0) = 0".to_string());
+    }
+    let sql = format!(
+        "SELECT DISTINCT name FROM cards WHERE {} ORDER BY name LIMIT ?2",
+        conditions.join(" AND ")
+    );
+
+    let mut stmt = conn.prepare(&sql).context("Failed to prepare statement")?;
 
     let names = stmt
-        .query_map(params![search_pattern, limit], |row| {
+        .query_map(params![search_pattern, options.limit], |row| {
             row.get::<_, String>(0)
         })
         .context("Failed to query card names")?
@@ -201,69 +681,803 @@ pub fn autocomplete_card_names(pool: &SqlitePool, prefix: &str, limit: i64) -> R
 /// Store a query result in the cache
 pub fn store_query_cache(
     pool: &SqlitePool,
+    tenant_id: &str,
     query_hash: &str,
+    query_text: &str,
     card_ids: &[Uuid],
     ttl_hours: i32,
 ) -> Result<()> {
     let conn = pool.get().context("Failed to get connection from pool")?;
-    let card_ids_json = serde_json::to_string(card_ids).context("Failed to serialize card IDs")?;
+    let result_ids_json =
+        serde_json::to_string(card_ids).context("Failed to serialize card IDs")?;
 
     conn.execute(
         r#"
-        INSERT INTO query_cache (query_hash, card_ids, ttl_hours, expires_at)
-        VALUES (?1, ?2, ?3, datetime('now', '+' || ?3 || ' hours'))
+        INSERT INTO query_cache
+            (query_hash, query_text, result_ids, total_cards, expires_at, tenant_id)
+        VALUES (?1, ?2, ?3, ?4, datetime('now', '+' || ?5 || ' hours'), ?6)
         ON CONFLICT(query_hash) DO UPDATE SET
-            card_ids = excluded.card_ids,
-            ttl_hours = excluded.ttl_hours,
-            created_at = CURRENT_TIMESTAMP,
-            expires_at = datetime('now', '+' || excluded.ttl_hours || ' hours')
+            query_text = excluded.query_text,
+            result_ids = excluded.result_ids,
+            total_cards = excluded.total_cards,
+            expires_at = excluded.expires_at,
+            last_accessed = CURRENT_TIMESTAMP
         "#,
-        params![query_hash, card_ids_json, ttl_hours],
+        params![
+            query_hash,
+            query_text,
+            result_ids_json,
+            card_ids.len() as i64,
+            ttl_hours,
+            tenant_id
+        ],
     )
     .context("Failed to store query cache")?;
 
     Ok(())
 }
 
-/// Get cached query results
+/// Get cached query results, counting the hit
 pub fn get_query_cache(pool: &SqlitePool, query_hash: &str) -> Result<Option<(Vec<Uuid>, i32)>> {
     let conn = pool.get().context("Failed to get connection from pool")?;
 
     let result = conn
         .query_row(
             r#"
-        SELECT card_ids, ttl_hours FROM query_cache
-        WHERE query_hash = ?1 AND expires_at > datetime('now')
+        UPDATE query_cache
+        SET last_accessed = CURRENT_TIMESTAMP, hit_count = hit_count + 1
+        WHERE query_hash = ?1 AND (expires_at IS NULL OR expires_at > datetime('now'))
+        RETURNING result_ids, total_cards
         "#,
             params![query_hash],
             |row| {
-                let card_ids_json: String = row.get(0)?;
-                let ttl_hours: i32 = row.get(1)?;
-                Ok((card_ids_json, ttl_hours))
+                let result_ids_json: String = row.get(0)?;
+                let total_cards: i32 = row.get(1)?;
+                Ok((result_ids_json, total_cards))
             },
         )
         .optional()
         .context("Failed to fetch query cache")?;
 
-    if let Some((card_ids_json, ttl_hours)) = result {
+    if let Some((result_ids_json, total_cards)) = result {
         let card_ids: Vec<Uuid> =
-            serde_json::from_str(&card_ids_json).context("Failed to deserialize card IDs")?;
-        Ok(Some((card_ids, ttl_hours)))
+            serde_json::from_str(&result_ids_json).context("Failed to deserialize card IDs")?;
+        Ok(Some((card_ids, total_cards)))
     } else {
         Ok(None)
     }
 }
 
-/// Record a bulk import operation
-pub fn record_bulk_import(pool: &SqlitePool, total_cards: i32, source: &str) -> Result<()> {
+/// Get cached query results without updating the hit count or access time
+pub fn peek_query_cache(pool: &SqlitePool, query_hash: &str) -> Result<Option<(Vec<Uuid>, i32)>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let result = conn
+        .query_row(
+            r#"
+        SELECT result_ids, total_cards
+        FROM query_cache
+        WHERE query_hash = ?1 AND (expires_at IS NULL OR expires_at > datetime('now'))
+        "#,
+            params![query_hash],
+            |row| {
+                let result_ids_json: String = row.get(0)?;
+                let total_cards: i32 = row.get(1)?;
+                Ok((result_ids_json, total_cards))
+            },
+        )
+        .optional()
+        .context("Failed to peek query cache")?;
+
+    match result {
+        Some((result_ids_json, total_cards)) => {
+            let card_ids: Vec<Uuid> =
+                serde_json::from_str(&result_ids_json).context("Failed to deserialize card IDs")?;
+            Ok(Some((card_ids, total_cards)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// List query cache entries, most recently cached first
+pub fn list_query_cache(pool: &SqlitePool, limit: i64) -> Result<Vec<QueryCacheEntry>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+        SELECT query_hash, query_text, total_cards, hit_count, created_at,
+               CAST(strftime('%s', 'now') - strftime('%s', created_at) AS INTEGER),
+               expires_at, tenant_id
+        FROM query_cache
+        ORDER BY created_at DESC
+        LIMIT ?1
+        "#,
+        )
+        .context("Failed to prepare query cache listing")?;
+
+    let parse_timestamp = |value: Option<String>| {
+        value.and_then(|v| chrono::NaiveDateTime::parse_from_str(&v, SQLITE_TIMESTAMP_FORMAT).ok())
+    };
+
+    let rows = stmt
+        .query_map(params![limit], |row| {
+            Ok(QueryCacheEntry {
+                query_hash: row.get(0)?,
+                tenant_id: row.get(7)?,
+                query_text: row.get(1)?,
+                result_count: row.get(2)?,
+                hit_count: row.get(3)?,
+                created_at: parse_timestamp(row.get(4)?),
+                age_seconds: row.get::<_, Option<i64>>(5)?.unwrap_or(0),
+                expires_at: parse_timestamp(row.get(6)?),
+            })
+        })
+        .context("Failed to list query cache")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read query cache entry")
+}
+
+/// Delete one query cache entry
+pub fn delete_query_cache(pool: &SqlitePool, query_hash: &str) -> Result<bool> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let deleted = conn
+        .execute(
+            "DELETE FROM query_cache WHERE query_hash = ?1",
+            params![query_hash],
+        )
+        .context("Failed to delete query cache entry")?;
+
+    Ok(deleted > 0)
+}
+
+/// Delete every query cache entry
+pub fn clear_query_cache(pool: &SqlitePool) -> Result<u64> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let deleted = conn
+        .execute("DELETE FROM query_cache", params![])
+        .context("Failed to clear query cache")?;
+
+    Ok(deleted as u64)
+}
+
+/// Record a bulk import operation, returning its id
+pub fn record_bulk_import(pool: &SqlitePool, import: &BulkImport) -> Result<i32> {
     let conn = pool.get().context("Failed to get connection from pool")?;
 
     conn.execute(
-        "INSERT INTO bulk_imports (total_cards, source) VALUES (?1, ?2)",
-        params![total_cards, source],
+        r#"
+        INSERT INTO bulk_data_metadata
+            (bulk_type, download_uri, total_cards, file_size_bytes, duration_ms, failed_cards)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        params![
+            import.bulk_type,
+            import.source,
+            import.total_cards,
+            import.file_size_bytes,
+            import.duration_ms,
+            import.failed_cards
+        ],
     )
     .context("Failed to record bulk import")?;
 
+    Ok(conn.last_insert_rowid() as i32)
+}
+
+/// List recorded bulk imports, most recent first
+pub fn list_bulk_imports(pool: &SqlitePool, limit: i64) -> Result<Vec<BulkImport>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+        SELECT id, bulk_type, download_uri, total_cards, failed_cards, file_size_bytes,
+               duration_ms, imported_at
+        FROM bulk_data_metadata
+        ORDER BY imported_at DESC, id DESC
+        LIMIT ?1
+        "#,
+        )
+        .context("Failed to prepare bulk import listing")?;
+
+    let rows = stmt
+        .query_map(params![limit], |row| {
+            let imported_at: Option<String> = row.get(7)?;
+            Ok(BulkImport {
+                id: row.get(0)?,
+                bulk_type: row.get(1)?,
+                source: row.get(2)?,
+                total_cards: row.get(3)?,
+                failed_cards: row.get(4)?,
+                file_size_bytes: row.get(5)?,
+                duration_ms: row.get(6)?,
+                imported_at: imported_at.and_then(|v| {
+                    chrono::NaiveDateTime::parse_from_str(&v, SQLITE_TIMESTAMP_FORMAT).ok()
+                }),
+            })
+        })
+        .context("Failed to list bulk imports")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read bulk import")
+}
+
+/// Record the entries of an import that failed to parse
+pub fn record_bulk_import_errors(
+    pool: &SqlitePool,
+    import_id: i32,
+    errors: &[BulkImportError],
+) -> Result<()> {
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    let tx = conn.transaction().context("Failed to begin transaction")?;
+    {
+        let mut stmt = tx
+            .prepare(
+                r#"
+            INSERT INTO bulk_import_errors
+                (import_id, position, card_id, card_name, category, message)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            )
+            .context("Failed to prepare bulk import error insert")?;
+        for error in errors {
+            stmt.execute(params![
+                import_id,
+                error.position,
+                error.card_id,
+                error.card_name,
+                error.category,
+                error.message
+            ])
+            .context("Failed to record bulk import error")?;
+        }
+    }
+    tx.commit().context("Failed to commit bulk import errors")?;
+
+    Ok(())
+}
+
+/// Recorded parse failures of an import in file order, or `None` if there is no such import
+pub fn list_bulk_import_errors(
+    pool: &SqlitePool,
+    import_id: i32,
+) -> Result<Option<Vec<BulkImportError>>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS (SELECT 1 FROM bulk_data_metadata WHERE id = ?1)",
+            params![import_id],
+            |row| row.get(0),
+        )
+        .context("Failed to look up bulk import")?;
+    if !exists {
+        return Ok(None);
+    }
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+        SELECT position, card_id, card_name, category, message
+        FROM bulk_import_errors
+        WHERE import_id = ?1
+        ORDER BY position
+        "#,
+        )
+        .context("Failed to prepare bulk import error listing")?;
+
+    let rows = stmt
+        .query_map(params![import_id], |row| {
+            Ok(BulkImportError {
+                position: row.get(0)?,
+                card_id: row.get(1)?,
+                card_name: row.get(2)?,
+                category: row.get(3)?,
+                message: row.get(4)?,
+            })
+        })
+        .context("Failed to list bulk import errors")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map(Some)
+        .context("Failed to read bulk import error")
+}
+
+/// Claim an idempotency key, or return the unexpired record already stored under it
+pub fn claim_idempotency_key(
+    pool: &SqlitePool,
+    key: &str,
+    request_hash: &str,
+    ttl_seconds: i64,
+) -> Result<Option<IdempotencyRecord>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    // Expired rows are taken over in place, so a claim is a single atomic statement
+    let claimed = conn
+        .execute(
+            r#"
+            INSERT INTO idempotency_keys (key, request_hash, expires_at)
+            VALUES (?1, ?2, datetime('now', '+' || ?3 || ' seconds'))
+            ON CONFLICT (key) DO UPDATE SET
+                request_hash = excluded.request_hash,
+                status = NULL,
+                content_type = NULL,
+                body = NULL,
+                created_at = CURRENT_TIMESTAMP,
+                expires_at = excluded.expires_at
+            WHERE idempotency_keys.expires_at < datetime('now')
+            "#,
+            params![key, request_hash, ttl_seconds],
+        )
+        .context("Failed to claim idempotency key")?;
+    if claimed > 0 {
+        return Ok(None);
+    }
+
+    conn.query_row(
+        "SELECT request_hash, status, content_type, body FROM idempotency_keys WHERE key = ?1",
+        params![key],
+        |row| {
+            let status: Option<i64> = row.get(1)?;
+            let body: Option<Vec<u8>> = row.get(3)?;
+            Ok(IdempotencyRecord {
+                request_hash: row.get(0)?,
+                status: status.map(|s| s as u16),
+                content_type: row.get(2)?,
+                body: body.unwrap_or_default(),
+            })
+        },
+    )
+    .optional()
+    .context("Failed to read idempotency key")
+}
+
+/// Store the response of a claimed idempotency key
+pub fn complete_idempotency_key(
+    pool: &SqlitePool,
+    key: &str,
+    record: &IdempotencyRecord,
+) -> Result<()> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    conn.execute(
+        r#"
+        UPDATE idempotency_keys
+        SET status = ?2, content_type = ?3, body = ?4
+        WHERE key = ?1 AND request_hash = ?5
+        "#,
+        params![
+            key,
+            record.status,
+            record.content_type,
+            record.body,
+            record.request_hash
+        ],
+    )
+    .context("Failed to store idempotent response")?;
+
+    Ok(())
+}
+
+/// Delete an idempotency key
+pub fn release_idempotency_key(pool: &SqlitePool, key: &str) -> Result<()> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    conn.execute("DELETE FROM idempotency_keys WHERE key = ?1", params![key])
+        .context("Failed to release idempotency key")?;
+
+    Ok(())
+}
+
+/// Delete expired idempotency keys
+pub fn clean_expired_idempotency_keys(pool: &SqlitePool) -> Result<u64> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let deleted = conn
+        .execute(
+            "DELETE FROM idempotency_keys WHERE expires_at < datetime('now')",
+            params![],
+        )
+        .context("Failed to clean expired idempotency keys")?;
+
+    Ok(deleted as u64)
+}
+
+/// Record an admin mutation in the admin audit log
+pub fn record_admin_audit(pool: &SqlitePool, entry: &AdminAuditEntry) -> Result<()> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    conn.execute(
+        r#"
+        INSERT INTO admin_audit_log
+            (actor, method, path, parameters, status, outcome, duration_ms)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "#,
+        params![
+            entry.actor,
+            entry.method,
+            entry.path,
+            entry.parameters,
+            entry.status,
+            entry.outcome,
+            entry.duration_ms
+        ],
+    )
+    .context("Failed to record admin audit entry")?;
+
+    Ok(())
+}
+
+/// List admin audit log entries, most recent first, optionally only those of `actor`
+pub fn list_admin_audit(
+    pool: &SqlitePool,
+    limit: i64,
+    actor: Option<&str>,
+) -> Result<Vec<AdminAuditEntry>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+        SELECT actor, method, path, parameters, status, outcome, duration_ms, created_at
+        FROM admin_audit_log
+        WHERE ?2 IS NULL OR actor = ?2
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?1
+        "#,
+        )
+        .context("Failed to prepare admin audit log listing")?;
+
+    let rows = stmt
+        .query_map(params![limit, actor], |row| {
+            let created_at: Option<String> = row.get(7)?;
+            Ok(AdminAuditEntry {
+                actor: row.get(0)?,
+                method: row.get(1)?,
+                path: row.get(2)?,
+                parameters: row.get(3)?,
+                status: row.get(4)?,
+                outcome: row.get(5)?,
+                duration_ms: row.get(6)?,
+                created_at: created_at.and_then(|v| {
+                    chrono::NaiveDateTime::parse_from_str(&v, SQLITE_TIMESTAMP_FORMAT).ok()
+                }),
+            })
+        })
+        .context("Failed to list admin audit log")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read admin audit log")
+}
+
+/// Store owned quantities in a collection in one transaction; quantity 0 removes an entry
+pub fn upsert_collection_entries(
+    pool: &SqlitePool,
+    tenant_id: &str,
+    collection_id: &str,
+    entries: &[CollectionEntry],
+) -> Result<()> {
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    let tx = conn.transaction().context("Failed to begin transaction")?;
+
+    for entry in entries {
+        if entry.quantity > 0 {
+            tx.execute(
+                r#"
+                INSERT INTO collection_cards (tenant_id, collection_id, card_id, finish, quantity)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT(tenant_id, collection_id, card_id, finish) DO UPDATE SET
+                    quantity = excluded.quantity,
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+                params![
+                    tenant_id,
+                    collection_id,
+                    entry.card_id.to_string(),
+                    entry.finish.as_str(),
+                    entry.quantity
+                ],
+            )
+            .context("Failed to store collection entry")?;
+        } else {
+            tx.execute(
+                "DELETE FROM collection_cards
+                 WHERE tenant_id = ?1 AND collection_id = ?2 AND card_id = ?3 AND finish = ?4",
+                params![
+                    tenant_id,
+                    collection_id,
+                    entry.card_id.to_string(),
+                    entry.finish.as_str()
+                ],
+            )
+            .context("Failed to remove collection entry")?;
+        }
+    }
+
+    tx.commit().context("Failed to commit transaction")?;
+    Ok(())
+}
+
+/// Entries of a collection, ordered by card id and finish
+pub fn list_collection_entries(
+    pool: &SqlitePool,
+    tenant_id: &str,
+    collection_id: &str,
+) -> Result<Vec<CollectionEntry>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+        SELECT card_id, finish, quantity, updated_at
+        FROM collection_cards
+        WHERE tenant_id = ?1 AND collection_id = ?2
+        ORDER BY card_id, finish
+        "#,
+        )
+        .context("Failed to prepare collection listing")?;
+
+    let rows = stmt
+        .query_map(params![tenant_id, collection_id], |row| {
+            let card_id: String = row.get(0)?;
+            let finish: String = row.get(1)?;
+            let updated_at: Option<String> = row.get(3)?;
+            Ok((card_id, finish, row.get::<_, i32>(2)?, updated_at))
+        })
+        .context("Failed to list collection entries")?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        let (card_id, finish, quantity, updated_at) =
+            row.context("Failed to read collection entry")?;
+        let Ok(card_id) = Uuid::parse_str(&card_id) else {
+            continue;
+        };
+        entries.push(CollectionEntry {
+            card_id,
+            finish: Finish::parse(&finish).unwrap_or_default(),
+            quantity,
+            updated_at: updated_at.and_then(|v| {
+                chrono::NaiveDateTime::parse_from_str(&v, SQLITE_TIMESTAMP_FORMAT).ok()
+            }),
+        });
+    }
+    Ok(entries)
+}
+
+/// Columns of `saved_searches`, in the order `saved_search_from_row` reads them
+const SAVED_SEARCH_COLUMNS: &str =
+    "id, tenant_id, query, name, runs, new_matches, created_at, last_run_at";
+
+fn saved_search_from_row(row: &rusqlite::Row) -> rusqlite::Result<(String, SavedSearch)> {
+    let parse_timestamp = |value: Option<String>| {
+        value.and_then(|v| chrono::NaiveDateTime::parse_from_str(&v, SQLITE_TIMESTAMP_FORMAT).ok())
+    };
+    let id: String = row.get(0)?;
+    Ok((
+        id,
+        SavedSearch {
+            id: Uuid::nil(),
+            tenant_id: row.get(1)?,
+            query: row.get(2)?,
+            name: row.get(3)?,
+            runs: row.get(4)?,
+            new_matches: row.get(5)?,
+            created_at: parse_timestamp(row.get(6)?),
+            last_run_at: parse_timestamp(row.get(7)?),
+        },
+    ))
+}
+
+/// Read saved searches from `rows`, skipping any whose id is not a UUID
+fn collect_saved_searches(
+    rows: impl Iterator<Item = rusqlite::Result<(String, SavedSearch)>>,
+) -> Result<Vec<SavedSearch>> {
+    let mut searches = Vec::new();
+    for row in rows {
+        let (id, mut search) = row.context("Failed to read saved search")?;
+        if let Ok(id) = Uuid::parse_str(&id) {
+            search.id = id;
+            searches.push(search);
+        }
+    }
+    Ok(searches)
+}
+
+/// Insert `card_ids` as matches of a saved search first seen in `run`, returning the
+/// ids that were not matches already
+fn insert_saved_search_matches(
+    tx: &rusqlite::Transaction,
+    id: Uuid,
+    run: i32,
+    card_ids: &[Uuid],
+) -> Result<Vec<Uuid>> {
+    let mut stmt = tx
+        .prepare_cached(
+            "INSERT OR IGNORE INTO saved_search_matches (saved_search_id, card_id, first_run)
+             VALUES (?1, ?2, ?3)",
+        )
+        .context("Failed to prepare saved search match insert")?;
+
+    let mut new_ids = Vec::new();
+    for card_id in card_ids {
+        let inserted = stmt
+            .execute(params![id.to_string(), card_id.to_string(), run])
+            .context("Failed to record saved search match")?;
+        if inserted > 0 {
+            new_ids.push(*card_id);
+        }
+    }
+    Ok(new_ids)
+}
+
+/// Register a saved search with the matches it has before its first run (run 0)
+pub fn insert_saved_search(
+    pool: &SqlitePool,
+    search: &SavedSearch,
+    card_ids: &[Uuid],
+) -> Result<()> {
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    let tx = conn.transaction().context("Failed to begin transaction")?;
+
+    tx.execute(
+        "INSERT INTO saved_searches (id, tenant_id, query, name) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            search.id.to_string(),
+            search.tenant_id,
+            search.query,
+            search.name
+        ],
+    )
+    .context("Failed to insert saved search")?;
+    insert_saved_search_matches(&tx, search.id, 0, card_ids)?;
+
+    tx.commit().context("Failed to commit transaction")?;
+    Ok(())
+}
+
+/// Saved searches of one tenant, or of every tenant, oldest first
+pub fn list_saved_searches(pool: &SqlitePool, tenant_id: Option<&str>) -> Result<Vec<SavedSearch>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM saved_searches WHERE ?1 IS NULL OR tenant_id = ?1 ORDER BY created_at, id",
+            SAVED_SEARCH_COLUMNS
+        ))
+        .context("Failed to prepare saved search listing")?;
+    let rows = stmt
+        .query_map(params![tenant_id], saved_search_from_row)
+        .context("Failed to list saved searches")?;
+
+    collect_saved_searches(rows)
+}
+
+/// Get a saved search of a tenant
+pub fn get_saved_search(
+    pool: &SqlitePool,
+    tenant_id: &str,
+    id: Uuid,
+) -> Result<Option<SavedSearch>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM saved_searches WHERE tenant_id = ?1 AND id = ?2",
+            SAVED_SEARCH_COLUMNS
+        ))
+        .context("Failed to prepare saved search lookup")?;
+    let rows = stmt
+        .query_map(params![tenant_id, id.to_string()], saved_search_from_row)
+        .context("Failed to get saved search")?;
+
+    Ok(collect_saved_searches(rows)?.into_iter().next())
+}
+
+/// Remove a saved search of a tenant and its matches, returning whether it existed
+pub fn delete_saved_search(pool: &SqlitePool, tenant_id: &str, id: Uuid) -> Result<bool> {
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    let tx = conn.transaction().context("Failed to begin transaction")?;
+
+    let deleted = tx
+        .execute(
+            "DELETE FROM saved_searches WHERE tenant_id = ?1 AND id = ?2",
+            params![tenant_id, id.to_string()],
+        )
+        .context("Failed to delete saved search")?
+        > 0;
+    if deleted {
+        tx.execute(
+            "DELETE FROM saved_search_matches WHERE saved_search_id = ?1",
+            params![id.to_string()],
+        )
+        .context("Failed to delete saved search matches")?;
+    }
+
+    tx.commit().context("Failed to commit transaction")?;
+    Ok(deleted)
+}
+
+/// Record an evaluation of a saved search, returning the cards that matched it for the
+/// first time. Returns nothing if the search was deleted in the meantime.
+pub fn record_saved_search_run(
+    pool: &SqlitePool,
+    id: Uuid,
+    card_ids: &[Uuid],
+) -> Result<Vec<Uuid>> {
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    let tx = conn.transaction().context("Failed to begin transaction")?;
+
+    let run: Option<i32> = tx
+        .query_row(
+            "UPDATE saved_searches SET runs = runs + 1, last_run_at = CURRENT_TIMESTAMP
+             WHERE id = ?1 RETURNING runs",
+            params![id.to_string()],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("Failed to update saved search")?;
+    let Some(run) = run else {
+        return Ok(Vec::new());
+    };
+
+    let new_ids = insert_saved_search_matches(&tx, id, run, card_ids)?;
+    tx.execute(
+        "UPDATE saved_searches SET new_matches = ?2 WHERE id = ?1",
+        params![id.to_string(), new_ids.len() as i32],
+    )
+    .context("Failed to update saved search")?;
+
+    tx.commit().context("Failed to commit transaction")?;
+    Ok(new_ids)
+}
+
+/// Cards that first matched a saved search in the given run
+pub fn list_saved_search_matches(pool: &SqlitePool, id: Uuid, run: i32) -> Result<Vec<Uuid>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT card_id FROM saved_search_matches WHERE saved_search_id = ?1 AND first_run = ?2",
+        )
+        .context("Failed to prepare saved search match listing")?;
+    let rows = stmt
+        .query_map(params![id.to_string(), run], |row| row.get::<_, String>(0))
+        .context("Failed to list saved search matches")?;
+
+    let mut card_ids = Vec::new();
+    for row in rows {
+        let card_id = row.context("Failed to read saved search match")?;
+        if let Ok(card_id) = Uuid::parse_str(&card_id) {
+            card_ids.push(card_id);
+        }
+    }
+    Ok(card_ids)
+}
+
+/// Record which bulk data type the given cards were imported from
+pub fn record_card_provenance(pool: &SqlitePool, bulk_type: &str, card_ids: &[Uuid]) -> Result<()> {
+    if card_ids.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = pool.get().context("Failed to get connection from pool")?;
+    let tx = conn.transaction().context("Failed to begin transaction")?;
+
+    for id in card_ids {
+        tx.execute(
+            "INSERT INTO card_provenance (card_id, bulk_type) VALUES (?1, ?2)
+             ON CONFLICT(card_id, bulk_type) DO UPDATE SET imported_at = CURRENT_TIMESTAMP",
+            params![id.to_string(), bulk_type],
+        )
+        .context("Failed to record card provenance")?;
+    }
+
+    tx.commit().context("Failed to commit transaction")?;
     Ok(())
 }
 
@@ -273,7 +1487,8 @@ pub fn clean_old_cache_entries(pool: &SqlitePool, hours: i32) -> Result<u64> {
 
     let deleted = conn
         .execute(
-            "DELETE FROM query_cache WHERE expires_at < datetime('now', '-' || ?1 || ' hours')",
+            "DELETE FROM query_cache WHERE expires_at < datetime('now') \
+             OR last_accessed < datetime('now', '-' || ?1 || ' hours')",
             params![hours],
         )
         .context("Failed to clean old cache entries")?;
@@ -300,6 +1515,9 @@ fn row_to_card(row: &rusqlite::Row) -> rusqlite::Result<Card> {
     let keywords_json: Option<String> = row.get("keywords")?;
     let keywords = keywords_json.and_then(|s| serde_json::from_str(&s).ok());
 
+    let face_names_json: Option<String> = row.get("face_names")?;
+    let face_names = face_names_json.and_then(|s| serde_json::from_str(&s).ok());
+
     let prices_json: Option<String> = row.get("prices")?;
     let prices = prices_json.and_then(|s| serde_json::from_str(&s).ok());
 
@@ -312,6 +1530,9 @@ fn row_to_card(row: &rusqlite::Row) -> rusqlite::Result<Card> {
     let legalities_json: Option<String> = row.get("legalities")?;
     let legalities = legalities_json.and_then(|s| serde_json::from_str(&s).ok());
 
+    let multiverse_ids_json: Option<String> = row.get("multiverse_ids")?;
+    let multiverse_ids = multiverse_ids_json.and_then(|s| serde_json::from_str(&s).ok());
+
     let raw_json_str: String = row.get("raw_json")?;
     let raw_json = serde_json::from_str(&raw_json_str).map_err(|e| {
         rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
@@ -346,13 +1567,31 @@ fn row_to_card(row: &rusqlite::Row) -> rusqlite::Result<Card> {
         power: row.get("power")?,
         toughness: row.get("toughness")?,
         loyalty: row.get("loyalty")?,
+        power_num: row.get("power_num")?,
+        toughness_num: row.get("toughness_num")?,
+        loyalty_num: row.get("loyalty_num")?,
+        price_usd: row.get("price_usd")?,
+        price_eur: row.get("price_eur")?,
+        price_tix: row.get("price_tix")?,
+        edhrec_rank: row.get("edhrec_rank")?,
+        penny_rank: row.get("penny_rank")?,
         keywords,
+        face_names,
         prices,
         image_uris,
         card_faces,
         legalities,
         released_at,
         raw_json,
+        lang: row.get("lang")?,
+        printed_name: row.get("printed_name")?,
+        printed_type_line: row.get("printed_type_line")?,
+        printed_text: row.get("printed_text")?,
+        layout: row.get("layout")?,
+        multiverse_ids,
+        arena_id: row.get("arena_id")?,
+        mtgo_id: row.get("mtgo_id")?,
+        mtgo_foil_id: row.get("mtgo_foil_id")?,
         created_at,
         updated_at,
     })
@@ -381,6 +1620,34 @@ pub fn execute_raw_query(pool: &SqlitePool, sql: &str, params: &[String]) -> Res
     Ok(cards)
 }
 
+/// Execute a raw SQL query, sending each row to `tx` as it is read.
+/// Stops early (without error) once the receiver has been dropped.
+pub fn stream_raw_query(
+    pool: &SqlitePool,
+    sql: &str,
+    params: &[String],
+    tx: &mpsc::Sender<Result<Card>>,
+) -> Result<()> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(sql)
+        .context("Failed to prepare SQL statement")?;
+
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), row_to_card)
+        .context("Failed to execute query")?;
+
+    for row in rows {
+        let item = row.context("Failed to parse query results");
+        if tx.blocking_send(item).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
 /// Execute a COUNT query and return the result
 pub fn count_query(pool: &SqlitePool, sql: &str, params: &[String]) -> Result<usize> {
     let conn = pool.get().context("Failed to get connection from pool")?;
@@ -421,7 +1688,7 @@ pub fn get_last_bulk_import(pool: &SqlitePool) -> Result<Option<chrono::NaiveDat
         .context("Failed to get last bulk import timestamp")?;
 
     if let Some(timestamp_str) = result {
-        let dt = chrono::NaiveDateTime::parse_from_str(&timestamp_str, "%Y-%m-%d %H:%M:%S")
+        let dt = chrono::NaiveDateTime::parse_from_str(&timestamp_str, SQLITE_TIMESTAMP_FORMAT)
             .context("Failed to parse timestamp")?;
         Ok(Some(dt))
     } else {
@@ -429,6 +1696,48 @@ pub fn get_last_bulk_import(pool: &SqlitePool) -> Result<Option<chrono::NaiveDat
     }
 }
 
+/// Append an incremental sync run to the sync ledger
+pub fn record_sync(pool: &SqlitePool, run: &SyncRun) -> Result<()> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    conn.execute(
+        "INSERT INTO sync_ledger (started_at, completed_at, since, cards_updated, status, error)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            run.started_at.format(SQLITE_TIMESTAMP_FORMAT).to_string(),
+            run.completed_at.format(SQLITE_TIMESTAMP_FORMAT).to_string(),
+            run.since.format(SQLITE_TIMESTAMP_FORMAT).to_string(),
+            run.cards_updated,
+            run.status.as_str(),
+            run.error,
+        ],
+    )
+    .context("Failed to record sync run")?;
+
+    Ok(())
+}
+
+/// Get the start time of the most recent successful incremental sync
+pub fn get_last_sync(pool: &SqlitePool) -> Result<Option<chrono::NaiveDateTime>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let result: Option<String> = conn
+        .query_row(
+            "SELECT started_at FROM sync_ledger WHERE status = 'success' ORDER BY started_at DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("Failed to get last sync timestamp")?;
+
+    result
+        .map(|s| {
+            chrono::NaiveDateTime::parse_from_str(&s, SQLITE_TIMESTAMP_FORMAT)
+                .context("Failed to parse timestamp")
+        })
+        .transpose()
+}
+
 /// Get the total count of cards in the database
 pub fn get_card_count(pool: &SqlitePool) -> Result<i64> {
     let conn = pool.get().context("Failed to get connection from pool")?;
@@ -450,3 +1759,141 @@ pub fn get_cache_entry_count(pool: &SqlitePool) -> Result<i64> {
 
     Ok(count)
 }
+
+/// Get the size of the database file (pages in use and free pages, excluding the WAL)
+pub fn get_database_size_bytes(pool: &SqlitePool) -> Result<i64> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let size: i64 = conn
+        .query_row(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+            [],
+            |row| row.get(0),
+        )
+        .context("Failed to get database size")?;
+
+    Ok(size)
+}
+
+/// Count query cache entries per tenant, ordered by tenant id
+pub fn count_query_cache_by_tenant(pool: &SqlitePool) -> Result<Vec<(String, i64)>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT tenant_id, COUNT(*) FROM query_cache GROUP BY tenant_id ORDER BY tenant_id",
+        )
+        .context("Failed to prepare query cache tenant count")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Failed to count query cache entries by tenant")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read query cache tenant count")
+}
+
+/// Get the total number of hits across query cache entries
+pub fn get_query_cache_hit_count(pool: &SqlitePool) -> Result<i64> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let count: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(hit_count), 0) FROM query_cache",
+            [],
+            |row| row.get(0),
+        )
+        .context("Failed to get query cache hit count")?;
+
+    Ok(count)
+}
+
+/// Migrations recorded in `schema_migrations`, in version order
+pub fn list_applied_migrations(pool: &SqlitePool) -> Result<Vec<AppliedMigration>> {
+    let conn = pool.get().context("Failed to get connection from pool")?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT version, description, installed_on, execution_time_ms FROM schema_migrations ORDER BY version",
+        )
+        .context("Failed to prepare applied migration listing")?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            let installed_on: Option<String> = row.get(2)?;
+            Ok(AppliedMigration {
+                version: row.get(0)?,
+                description: row.get(1)?,
+                installed_on: installed_on.and_then(|v| {
+                    chrono::NaiveDateTime::parse_from_str(&v, SQLITE_TIMESTAMP_FORMAT).ok()
+                }),
+                execution_time_ms: row.get(3)?,
+            })
+        })
+        .context("Failed to list applied migrations")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read applied migrations")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::sqlite::migrations::run_migrations;
+
+    fn card(id: u128, name: &str) -> Card {
+        Card::from_scryfall_json(serde_json::json!({
+            "id": Uuid::from_u128(id).to_string(),
+            "name": name,
+            "set": "lea",
+            "collector_number": id.to_string(),
+            "lang": "en"
+        }))
+        .unwrap()
+    }
+
+    fn card_names(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT name FROM cards ORDER BY id").unwrap();
+        let names = stmt.query_map(params![], |row| row.get(0)).unwrap();
+        names.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn insert_sql_binds_every_column_of_every_row() {
+        let sql = insert_sql("cards_staging", 3);
+        assert!(sql.starts_with("INSERT INTO cards_staging ("));
+        assert_eq!(sql.matches('?').count(), 3 * CARD_INSERT_COLUMN_COUNT);
+        assert_eq!(
+            card_values(&card(1, "Sol Ring")).unwrap().len(),
+            CARD_INSERT_COLUMN_COUNT
+        );
+    }
+
+    #[test]
+    fn insert_cards_in_multi_row_statements() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+
+        // Five cards two per statement leave one for a single-row statement
+        let cards: Vec<Card> = (1..=5).map(|i| card(i, &format!("Card {}", i))).collect();
+        insert_cards_into(&mut conn, "cards", &cards, 2).unwrap();
+        assert_eq!(
+            card_names(&conn),
+            ["Card 1", "Card 2", "Card 3", "Card 4", "Card 5"]
+        );
+
+        // Cards already stored are overwritten
+        let renamed = [card(2, "Sol Ring"), card(6, "Black Lotus")];
+        insert_cards_into(&mut conn, "cards", &renamed, MAX_ROWS_PER_STATEMENT + 1).unwrap();
+        assert_eq!(
+            card_names(&conn),
+            [
+                "Card 1",
+                "Sol Ring",
+                "Card 3",
+                "Card 4",
+                "Card 5",
+                "Black Lotus"
+            ]
+        );
+    }
+}
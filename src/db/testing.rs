@@ -0,0 +1,367 @@
+//! Database stub for unit tests.
+
+use async_trait::async_trait;
+use std::any::Any;
+use uuid::Uuid;
+
+use crate::db::{PoolStatus, SqlDialect};
+use crate::errors::{ServiceError, ServiceResult};
+use crate::models::card::{Card, ExternalId};
+
+/// Backend answering every query with an error, for tests that only build SQL or
+/// exercise logic in front of the database. The bulk import status and pool usage are the
+/// exceptions: they report no cards and no pool unless set.
+#[derive(Debug)]
+pub(crate) struct TestDb {
+    dialect: SqlDialect,
+    last_bulk_import: Option<chrono::NaiveDateTime>,
+    pool_status: Option<PoolStatus>,
+}
+
+impl TestDb {
+    pub(crate) fn new(dialect: SqlDialect) -> Self {
+        Self {
+            dialect,
+            last_bulk_import: None,
+            pool_status: None,
+        }
+    }
+
+    /// Report cards loaded by a bulk import at `imported_at`
+    pub(crate) fn with_bulk_import(mut self, imported_at: chrono::NaiveDateTime) -> Self {
+        self.last_bulk_import = Some(imported_at);
+        self
+    }
+
+    /// Report `status` as the connection pool usage
+    pub(crate) fn with_pool_status(mut self, status: PoolStatus) -> Self {
+        self.pool_status = Some(status);
+        self
+    }
+}
+
+fn not_implemented() -> ServiceError {
+    ServiceError::Internal(anyhow::anyhow!("not implemented"))
+}
+
+#[async_trait]
+impl crate::db::DatabaseBackend for TestDb {
+    async fn insert_cards_batch(&self, _cards: &[Card]) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn begin_staging(&self) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn insert_cards_staging(&self, _cards: &[Card]) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn commit_staging(&self) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn discard_staging(&self) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn try_advisory_lock(
+        &self,
+        _name: &str,
+    ) -> ServiceResult<Option<crate::db::AdvisoryLock>> {
+        Err(not_implemented())
+    }
+
+    async fn get_card_by_id(&self, _id: Uuid) -> ServiceResult<Option<Card>> {
+        Err(not_implemented())
+    }
+
+    async fn get_card_by_set_and_number(
+        &self,
+        _set_code: &str,
+        _collector_number: &str,
+    ) -> ServiceResult<Option<Card>> {
+        Err(not_implemented())
+    }
+
+    async fn get_card_by_external_id(
+        &self,
+        _external_id: ExternalId,
+    ) -> ServiceResult<Option<Card>> {
+        Err(not_implemented())
+    }
+
+    async fn get_cards_by_ids(&self, _ids: &[Uuid]) -> ServiceResult<Vec<Card>> {
+        Err(not_implemented())
+    }
+
+    async fn list_card_relations(
+        &self,
+        _id: Uuid,
+    ) -> ServiceResult<Vec<crate::models::card::CardPart>> {
+        Err(not_implemented())
+    }
+
+    async fn search_cards_by_name(
+        &self,
+        _name: &str,
+        _lang: Option<&str>,
+        _include_tokens: bool,
+        _limit: i64,
+    ) -> ServiceResult<Vec<Card>> {
+        Err(not_implemented())
+    }
+
+    async fn autocomplete_card_names(
+        &self,
+        _prefix: &str,
+        _options: &crate::db::AutocompleteOptions,
+    ) -> ServiceResult<Vec<String>> {
+        Err(not_implemented())
+    }
+
+    async fn store_query_cache(
+        &self,
+        _tenant_id: &str,
+        _query_hash: &str,
+        _query_text: &str,
+        _card_ids: &[Uuid],
+        _ttl_hours: i32,
+    ) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn get_query_cache(&self, _query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>> {
+        Err(not_implemented())
+    }
+
+    async fn peek_query_cache(&self, _query_hash: &str) -> ServiceResult<Option<(Vec<Uuid>, i32)>> {
+        Err(not_implemented())
+    }
+
+    async fn list_query_cache(
+        &self,
+        _limit: i64,
+    ) -> ServiceResult<Vec<crate::models::query_cache::QueryCacheEntry>> {
+        Err(not_implemented())
+    }
+
+    async fn delete_query_cache(&self, _query_hash: &str) -> ServiceResult<bool> {
+        Err(not_implemented())
+    }
+
+    async fn clear_query_cache(&self) -> ServiceResult<u64> {
+        Err(not_implemented())
+    }
+
+    async fn record_bulk_import(
+        &self,
+        _import: &crate::models::bulk_import::BulkImport,
+    ) -> ServiceResult<i32> {
+        Err(not_implemented())
+    }
+
+    async fn list_bulk_imports(
+        &self,
+        _limit: i64,
+    ) -> ServiceResult<Vec<crate::models::bulk_import::BulkImport>> {
+        Err(not_implemented())
+    }
+
+    async fn record_bulk_import_errors(
+        &self,
+        _import_id: i32,
+        _errors: &[crate::models::bulk_import::BulkImportError],
+    ) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn list_bulk_import_errors(
+        &self,
+        _import_id: i32,
+    ) -> ServiceResult<Option<Vec<crate::models::bulk_import::BulkImportError>>> {
+        Err(not_implemented())
+    }
+
+    async fn record_admin_audit(
+        &self,
+        _entry: &crate::models::admin_audit::AdminAuditEntry,
+    ) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn list_admin_audit(
+        &self,
+        _limit: i64,
+        _actor: Option<&str>,
+    ) -> ServiceResult<Vec<crate::models::admin_audit::AdminAuditEntry>> {
+        Err(not_implemented())
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        _key: &str,
+        _request_hash: &str,
+        _ttl_seconds: i64,
+    ) -> ServiceResult<Option<crate::models::idempotency::IdempotencyRecord>> {
+        Err(not_implemented())
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        _key: &str,
+        _record: &crate::models::idempotency::IdempotencyRecord,
+    ) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn release_idempotency_key(&self, _key: &str) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn clean_expired_idempotency_keys(&self) -> ServiceResult<u64> {
+        Err(not_implemented())
+    }
+
+    async fn upsert_collection_entries(
+        &self,
+        _tenant_id: &str,
+        _collection_id: &str,
+        _entries: &[crate::models::collection::CollectionEntry],
+    ) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn list_collection_entries(
+        &self,
+        _tenant_id: &str,
+        _collection_id: &str,
+    ) -> ServiceResult<Vec<crate::models::collection::CollectionEntry>> {
+        Err(not_implemented())
+    }
+
+    async fn insert_saved_search(
+        &self,
+        _search: &crate::models::saved_search::SavedSearch,
+        _card_ids: &[Uuid],
+    ) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn list_saved_searches(
+        &self,
+        _tenant_id: Option<&str>,
+    ) -> ServiceResult<Vec<crate::models::saved_search::SavedSearch>> {
+        Err(not_implemented())
+    }
+
+    async fn get_saved_search(
+        &self,
+        _tenant_id: &str,
+        _id: Uuid,
+    ) -> ServiceResult<Option<crate::models::saved_search::SavedSearch>> {
+        Err(not_implemented())
+    }
+
+    async fn delete_saved_search(&self, _tenant_id: &str, _id: Uuid) -> ServiceResult<bool> {
+        Err(not_implemented())
+    }
+
+    async fn record_saved_search_run(
+        &self,
+        _id: Uuid,
+        _card_ids: &[Uuid],
+    ) -> ServiceResult<Vec<Uuid>> {
+        Err(not_implemented())
+    }
+
+    async fn list_saved_search_matches(&self, _id: Uuid, _run: i32) -> ServiceResult<Vec<Uuid>> {
+        Err(not_implemented())
+    }
+
+    async fn record_card_provenance(
+        &self,
+        _bulk_type: &str,
+        _card_ids: &[Uuid],
+    ) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn clean_old_cache_entries(&self, _hours: i32) -> ServiceResult<u64> {
+        Err(not_implemented())
+    }
+
+    async fn test_connection(&self) -> ServiceResult<()> {
+        Ok(())
+    }
+
+    async fn execute_raw_query(&self, _sql: &str, _params: &[String]) -> ServiceResult<Vec<Card>> {
+        Err(not_implemented())
+    }
+
+    async fn count_query(&self, _sql: &str, _params: &[String]) -> ServiceResult<usize> {
+        Err(not_implemented())
+    }
+
+    async fn check_bulk_data_loaded(&self) -> ServiceResult<bool> {
+        Ok(self.last_bulk_import.is_some())
+    }
+
+    async fn get_last_bulk_import(&self) -> ServiceResult<Option<chrono::NaiveDateTime>> {
+        Ok(self.last_bulk_import)
+    }
+
+    async fn record_sync(&self, _run: &crate::models::sync::SyncRun) -> ServiceResult<()> {
+        Err(not_implemented())
+    }
+
+    async fn get_last_sync(&self) -> ServiceResult<Option<chrono::NaiveDateTime>> {
+        Err(not_implemented())
+    }
+
+    async fn get_card_count(&self) -> ServiceResult<i64> {
+        Err(not_implemented())
+    }
+
+    async fn get_cache_entry_count(&self) -> ServiceResult<i64> {
+        Err(not_implemented())
+    }
+
+    async fn get_database_size_bytes(&self) -> ServiceResult<i64> {
+        Err(not_implemented())
+    }
+
+    async fn count_query_cache_by_tenant(&self) -> ServiceResult<Vec<(String, i64)>> {
+        Err(not_implemented())
+    }
+
+    async fn get_query_cache_hit_count(&self) -> ServiceResult<i64> {
+        Err(not_implemented())
+    }
+
+    async fn get_schema_version(
+        &self,
+    ) -> ServiceResult<crate::models::schema_version::SchemaVersion> {
+        Err(not_implemented())
+    }
+
+    async fn find_integrity_issues(
+        &self,
+        _limit: i64,
+    ) -> ServiceResult<Vec<crate::models::repair::IntegrityIssue>> {
+        Err(not_implemented())
+    }
+
+    fn pool_status(&self) -> Option<PoolStatus> {
+        self.pool_status
+    }
+
+    fn dialect(&self) -> SqlDialect {
+        self.dialect
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,133 @@
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::config::Settings;
+use crate::errors::{ServiceError, ServiceResult};
+
+/// Header a client can send to pick its own time budget, in milliseconds
+pub const TIMEOUT_HEADER: &str = "x-request-timeout-ms";
+
+tokio::task_local! {
+    /// When the request being served must be answered by, set by the deadline middleware
+    static DEADLINE: Instant;
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestTimeoutConfig {
+    /// Time budget of a request without a timeout header, in milliseconds; 0 disables it
+    pub default_ms: u64,
+    /// Largest budget a client may ask for with the timeout header, in milliseconds
+    pub max_ms: u64,
+}
+
+impl Default for RequestTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            default_ms: 25_000,
+            max_ms: 60_000,
+        }
+    }
+}
+
+impl RequestTimeoutConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            default_ms: settings
+                .var("REQUEST_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.default_ms),
+            max_ms: settings
+                .var("REQUEST_TIMEOUT_MAX_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_ms),
+        }
+    }
+
+    /// Budget of a request: the header value (capped at `max_ms`) when it is a positive
+    /// number, else the default. `None` means the request runs without a deadline.
+    pub fn budget(&self, header: Option<&str>) -> Option<Duration> {
+        let requested = header
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|ms| *ms > 0);
+        match requested {
+            Some(ms) => Some(Duration::from_millis(ms.min(self.max_ms.max(1)))),
+            None if self.default_ms > 0 => Some(Duration::from_millis(self.default_ms)),
+            None => None,
+        }
+    }
+}
+
+/// Run `future` as a request that must finish by `deadline`
+pub async fn scope<F: Future>(deadline: Instant, future: F) -> F::Output {
+    DEADLINE.scope(deadline, future).await
+}
+
+/// Deadline of the request being served, if any
+pub fn current() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// Whether the request being served has run out of time
+pub fn expired() -> bool {
+    current().is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Run `future` within the current request's deadline. When the deadline passes first,
+/// the future is dropped (cancelling any upstream requests it is making) and
+/// `ServiceError::Timeout` is returned. Outside a deadline scope it runs unbounded.
+pub async fn enforce<T, F>(operation: &str, future: F) -> ServiceResult<T>
+where
+    F: Future<Output = ServiceResult<T>>,
+{
+    match current() {
+        Some(deadline) => tokio::time::timeout_at(deadline, future)
+            .await
+            .unwrap_or_else(|_| Err(ServiceError::Timeout(operation.to_string()))),
+        None => future.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_from_header() {
+        let config = RequestTimeoutConfig {
+            default_ms: 1000,
+            max_ms: 5000,
+        };
+        assert_eq!(config.budget(None), Some(Duration::from_millis(1000)));
+        assert_eq!(config.budget(Some("250")), Some(Duration::from_millis(250)));
+        assert_eq!(config.budget(Some("90000")), Some(Duration::from_millis(5000)));
+        assert_eq!(config.budget(Some("soon")), Some(Duration::from_millis(1000)));
+        assert_eq!(config.budget(Some("0")), Some(Duration::from_millis(1000)));
+
+        let disabled = RequestTimeoutConfig {
+            default_ms: 0,
+            max_ms: 5000,
+        };
+        assert_eq!(disabled.budget(None), None);
+        assert_eq!(disabled.budget(Some("100")), Some(Duration::from_millis(100)));
+    }
+
+    #[tokio::test]
+    async fn test_enforce_times_out() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        };
+        let deadline = Instant::now() + Duration::from_millis(10);
+        let result = scope(deadline, enforce("search", slow)).await;
+        assert!(matches!(result, Err(ServiceError::Timeout(_))));
+        assert!(!expired());
+
+        let fast = async { Ok(1) };
+        assert_eq!(enforce("search", fast).await.unwrap(), 1);
+    }
+}
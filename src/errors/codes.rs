@@ -14,6 +14,10 @@ pub enum ErrorCode {
     #[serde(rename = "CARD_NOT_FOUND")]
     CardNotFound,
 
+    /// Requested resource (other than a card) not found
+    #[serde(rename = "NOT_FOUND")]
+    NotFound,
+
     /// Database connection or query error
     #[serde(rename = "DATABASE_ERROR")]
     DatabaseError,
@@ -26,6 +30,38 @@ pub enum ErrorCode {
     #[serde(rename = "VALIDATION_ERROR")]
     ValidationError,
 
+    /// Write operation rejected because the service runs in read-only mode
+    #[serde(rename = "READ_ONLY")]
+    ReadOnly,
+
+    /// API key not recognised
+    #[serde(rename = "UNAUTHORIZED")]
+    Unauthorized,
+
+    /// Request body too large or too deeply nested
+    #[serde(rename = "PAYLOAD_TOO_LARGE")]
+    PayloadTooLarge,
+
+    /// Request ran past its time budget
+    #[serde(rename = "TIMEOUT")]
+    Timeout,
+
+    /// Tenant exceeded its request rate limit
+    #[serde(rename = "RATE_LIMITED")]
+    RateLimited,
+
+    /// Idempotency key reused while its request is in flight
+    #[serde(rename = "IDEMPOTENCY_CONFLICT")]
+    IdempotencyConflict,
+
+    /// Idempotency key reused for a different request
+    #[serde(rename = "IDEMPOTENCY_KEY_MISMATCH")]
+    IdempotencyKeyMismatch,
+
+    /// Bulk data load or restore rejected because another one is running
+    #[serde(rename = "RELOAD_IN_PROGRESS")]
+    ReloadInProgress,
+
     /// Internal server error
     #[serde(rename = "INTERNAL_ERROR")]
     InternalError,
@@ -36,9 +72,18 @@ impl fmt::Display for ErrorCode {
         match self {
             Self::InvalidQuery => write!(f, "INVALID_QUERY"),
             Self::CardNotFound => write!(f, "CARD_NOT_FOUND"),
+            Self::NotFound => write!(f, "NOT_FOUND"),
             Self::DatabaseError => write!(f, "DATABASE_ERROR"),
             Self::ScryfallApiError => write!(f, "SCRYFALL_API_ERROR"),
             Self::ValidationError => write!(f, "VALIDATION_ERROR"),
+            Self::ReadOnly => write!(f, "READ_ONLY"),
+            Self::Unauthorized => write!(f, "UNAUTHORIZED"),
+            Self::PayloadTooLarge => write!(f, "PAYLOAD_TOO_LARGE"),
+            Self::Timeout => write!(f, "TIMEOUT"),
+            Self::RateLimited => write!(f, "RATE_LIMITED"),
+            Self::IdempotencyConflict => write!(f, "IDEMPOTENCY_CONFLICT"),
+            Self::IdempotencyKeyMismatch => write!(f, "IDEMPOTENCY_KEY_MISMATCH"),
+            Self::ReloadInProgress => write!(f, "RELOAD_IN_PROGRESS"),
             Self::InternalError => write!(f, "INTERNAL_ERROR"),
         }
     }
@@ -50,9 +95,18 @@ impl ErrorCode {
         match self {
             Self::InvalidQuery => 400,
             Self::CardNotFound => 404,
+            Self::NotFound => 404,
             Self::DatabaseError => 503,
             Self::ScryfallApiError => 502,
             Self::ValidationError => 400,
+            Self::ReadOnly => 403,
+            Self::Unauthorized => 401,
+            Self::PayloadTooLarge => 413,
+            Self::Timeout => 504,
+            Self::RateLimited => 429,
+            Self::IdempotencyConflict => 409,
+            Self::IdempotencyKeyMismatch => 422,
+            Self::ReloadInProgress => 409,
             Self::InternalError => 500,
         }
     }
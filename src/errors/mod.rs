@@ -2,6 +2,8 @@
 
 pub mod codes;
 pub mod response;
+pub mod service;
 
 pub use codes::ErrorCode;
 pub use response::{ErrorDetail, ErrorResponse};
+pub use service::{ServiceError, ServiceResult};
@@ -9,6 +9,8 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use super::codes::ErrorCode;
+use crate::query::cursor::CursorError;
+use crate::query::parser::ParseError;
 
 /// Structured error response
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -93,6 +95,28 @@ impl ErrorResponse {
         Self::new(ErrorCode::InvalidQuery, message)
     }
 
+    /// Invalid query error for a parser failure. When the parser reported a location,
+    /// it is included in `details` (position, length, token, expected).
+    pub fn query_parse_error(error: &anyhow::Error) -> Self {
+        let message = format!("Query parse error: {}", error);
+        match error
+            .downcast_ref::<ParseError>()
+            .and_then(|e| serde_json::to_value(e).ok())
+        {
+            Some(details) => Self::with_details(ErrorCode::InvalidQuery, message, details),
+            None => Self::invalid_query(message),
+        }
+    }
+
+    /// Rejected pagination cursor, with the reason (e.g. `stale`) in `details`
+    pub fn invalid_cursor(error: CursorError) -> Self {
+        Self::with_details(
+            ErrorCode::ValidationError,
+            error.to_string(),
+            serde_json::json!({ "reason": error.reason() }),
+        )
+    }
+
     pub fn card_not_found(card_id: impl Into<String>) -> Self {
         Self::new(
             ErrorCode::CardNotFound,
@@ -140,6 +164,20 @@ mod tests {
         assert!(err.error.details.is_some());
     }
 
+    #[test]
+    fn test_query_parse_error_includes_location() {
+        let parse_err = crate::query::QueryParser::parse("c:red or").unwrap_err();
+        let err = ErrorResponse::query_parse_error(&parse_err);
+        assert_eq!(err.error.code, ErrorCode::InvalidQuery);
+        let details = err.error.details.unwrap();
+        assert_eq!(details["position"], 8);
+        assert_eq!(details["token"], Value::Null);
+        assert_eq!(details["expected"], json!(["filter", "("]));
+
+        let other = ErrorResponse::query_parse_error(&anyhow::anyhow!("boom"));
+        assert!(other.error.details.is_none());
+    }
+
     #[test]
     fn test_helper_methods() {
         let err = ErrorResponse::card_not_found("abc123");
@@ -147,6 +185,13 @@ mod tests {
         assert!(err.error.message.contains("abc123"));
     }
 
+    #[test]
+    fn test_invalid_cursor_reason() {
+        let err = ErrorResponse::invalid_cursor(CursorError::Stale);
+        assert_eq!(err.error.code, ErrorCode::ValidationError);
+        assert_eq!(err.error.details.unwrap()["reason"], "stale");
+    }
+
     // ========== HTTP STATUS CODE TESTS ==========
 
     #[test]
@@ -155,9 +200,15 @@ mod tests {
         assert_eq!(ErrorCode::InvalidQuery.status_code(), 400);
         assert_eq!(ErrorCode::ValidationError.status_code(), 400);
         assert_eq!(ErrorCode::CardNotFound.status_code(), 404);
+        assert_eq!(ErrorCode::NotFound.status_code(), 404);
+        assert_eq!(ErrorCode::PayloadTooLarge.status_code(), 413);
+        assert_eq!(ErrorCode::Timeout.status_code(), 504);
         assert_eq!(ErrorCode::InternalError.status_code(), 500);
         assert_eq!(ErrorCode::ScryfallApiError.status_code(), 502);
         assert_eq!(ErrorCode::DatabaseError.status_code(), 503);
+        assert_eq!(ErrorCode::IdempotencyConflict.status_code(), 409);
+        assert_eq!(ErrorCode::IdempotencyKeyMismatch.status_code(), 422);
+        assert_eq!(ErrorCode::ReloadInProgress.status_code(), 409);
     }
 
     #[test]
@@ -0,0 +1,157 @@
+use axum::response::{IntoResponse, Response};
+
+use super::codes::ErrorCode;
+use super::response::ErrorResponse;
+use crate::query::parser::ParseError;
+
+/// Result type returned by the cache manager, Scryfall client and database backends
+pub type ServiceResult<T> = std::result::Result<T, ServiceError>;
+
+/// Failure of a service operation, classified by where it went wrong so handlers can
+/// pick an error code without inspecting messages
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    /// Database connection or query failure
+    #[error("Database error: {0:#}")]
+    Db(anyhow::Error),
+
+    /// Scryfall API request failed or returned an error status
+    #[error("Scryfall API error: {0:#}")]
+    ScryfallApi(anyhow::Error),
+
+    /// Request rejected because the circuit breaker for this Scryfall endpoint is open
+    #[error("Scryfall API unavailable: circuit breaker for {0} is open")]
+    CircuitOpen(String),
+
+    /// Caller input was rejected
+    #[error("{0}")]
+    Validation(String),
+
+    /// Requested resource does not exist
+    #[error("{0}")]
+    NotFound(String),
+
+    /// Write rejected because the service runs in read-only mode
+    #[error("Service is read-only: {0}")]
+    ReadOnly(String),
+
+    /// A bulk data load or restore is already running, on this instance or another
+    #[error("Bulk data load already in progress {0}")]
+    ReloadInProgress(String),
+
+    /// The request's time budget ran out during this operation
+    #[error("Request timed out during {0}")]
+    Timeout(String),
+
+    /// Anything else (cache tier failures, bugs)
+    #[error("{0:#}")]
+    Internal(anyhow::Error),
+}
+
+impl ServiceError {
+    /// Error code reported to API clients
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Db(_) => ErrorCode::DatabaseError,
+            Self::ScryfallApi(_) | Self::CircuitOpen(_) => ErrorCode::ScryfallApiError,
+            Self::Validation(_) => ErrorCode::ValidationError,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::ReadOnly(_) => ErrorCode::ReadOnly,
+            Self::Timeout(_) => ErrorCode::Timeout,
+            Self::ReloadInProgress(_) => ErrorCode::ReloadInProgress,
+            Self::Internal(_) => ErrorCode::InternalError,
+        }
+    }
+}
+
+/// Recover the typed error from an `anyhow` chain (e.g. after `.context(...)`), treating
+/// query parse failures as validation errors and anything unrecognised as internal
+impl From<anyhow::Error> for ServiceError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<ServiceError>() {
+            Ok(service_error) => service_error,
+            Err(error) if error.downcast_ref::<ParseError>().is_some() => {
+                Self::Validation(format!("{:#}", error))
+            }
+            Err(error) => Self::Internal(error),
+        }
+    }
+}
+
+impl From<ServiceError> for ErrorResponse {
+    fn from(error: ServiceError) -> Self {
+        ErrorResponse::new(error.code(), error.to_string())
+    }
+}
+
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        ErrorResponse::from(self).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_service_error_codes() {
+        assert_eq!(
+            ServiceError::Db(anyhow::anyhow!("pool timed out")).code(),
+            ErrorCode::DatabaseError
+        );
+        assert_eq!(
+            ServiceError::CircuitOpen("cards_search".to_string()).code(),
+            ErrorCode::ScryfallApiError
+        );
+        assert_eq!(
+            ServiceError::NotFound("gone".to_string()).code(),
+            ErrorCode::NotFound
+        );
+        assert_eq!(
+            ServiceError::ReadOnly("bulk reload".to_string()).code(),
+            ErrorCode::ReadOnly
+        );
+        assert_eq!(
+            ServiceError::Timeout("search".to_string()).code(),
+            ErrorCode::Timeout
+        );
+        assert_eq!(
+            ServiceError::ReloadInProgress("on another instance".to_string()).code(),
+            ErrorCode::ReloadInProgress
+        );
+    }
+
+    #[test]
+    fn test_service_error_survives_context() {
+        let result = Err::<(), _>(ServiceError::Db(anyhow::anyhow!("pool timed out")))
+            .context("Failed to load");
+        let error = ServiceError::from(result.unwrap_err());
+        assert!(matches!(error, ServiceError::Db(_)));
+
+        let parse_error = crate::query::QueryParser::parse("c:red or").unwrap_err();
+        assert!(matches!(
+            ServiceError::from(parse_error),
+            ServiceError::Validation(_)
+        ));
+
+        assert!(matches!(
+            ServiceError::from(anyhow::anyhow!("boom")),
+            ServiceError::Internal(_)
+        ));
+    }
+
+    #[test]
+    fn test_service_error_into_response() {
+        let response =
+            ServiceError::ScryfallApi(anyhow::anyhow!("500 Internal Server Error")).into_response();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_GATEWAY);
+
+        let response = ServiceError::Db(anyhow::anyhow!("connection refused")).into_response();
+        assert_eq!(
+            response.status(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+}
@@ -0,0 +1,132 @@
+pub mod webhooks;
+
+use futures::Stream;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::circuit_breaker::CircuitState;
+
+/// Events buffered per subscriber before the slowest one starts missing events
+const EVENT_BUFFER: usize = 1024;
+
+/// Something that happened inside the service that clients may want to react to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CacheEvent {
+    /// A bulk data import started
+    BulkReloadStarted { bulk_types: Vec<String> },
+    /// A bulk data import finished successfully
+    BulkReloadCompleted {
+        cards_imported: usize,
+        duration_ms: u64,
+    },
+    /// A bulk data import failed
+    BulkReloadFailed { error: String },
+    /// A Scryfall circuit breaker changed state
+    CircuitBreakerStateChanged {
+        name: String,
+        from: CircuitState,
+        to: CircuitState,
+    },
+    /// A card was written to the local database outside of a bulk import
+    CardUpdated { id: Uuid, name: String },
+    /// A saved search matched cards it had never matched before, after a bulk import
+    SavedSearchMatched {
+        id: Uuid,
+        tenant_id: String,
+        query: String,
+        new_card_ids: Vec<Uuid>,
+    },
+}
+
+impl CacheEvent {
+    /// Event type name, as used in the serialized `type` tag
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CacheEvent::BulkReloadStarted { .. } => "bulk_reload_started",
+            CacheEvent::BulkReloadCompleted { .. } => "bulk_reload_completed",
+            CacheEvent::BulkReloadFailed { .. } => "bulk_reload_failed",
+            CacheEvent::CircuitBreakerStateChanged { .. } => "circuit_breaker_state_changed",
+            CacheEvent::CardUpdated { .. } => "card_updated",
+            CacheEvent::SavedSearchMatched { .. } => "saved_search_matched",
+        }
+    }
+}
+
+lazy_static! {
+    static ref EVENTS: broadcast::Sender<CacheEvent> = broadcast::channel(EVENT_BUFFER).0;
+}
+
+/// Publish an event to every current subscriber. Events published while nobody is
+/// subscribed are dropped.
+pub fn publish(event: CacheEvent) {
+    let _ = EVENTS.send(event);
+}
+
+/// Receive events published from now on
+pub fn subscribe() -> broadcast::Receiver<CacheEvent> {
+    EVENTS.subscribe()
+}
+
+/// Events published from now on as a stream. A subscriber that falls more than
+/// `EVENT_BUFFER` events behind skips the events it missed rather than ending.
+pub fn stream() -> impl Stream<Item = CacheEvent> {
+    futures::stream::unfold(subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Event subscriber lagged, skipped {} events", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_published_events() {
+        let mut events = Box::pin(stream());
+        let id = Uuid::new_v4();
+        publish(CacheEvent::CardUpdated {
+            id,
+            name: "Lightning Bolt".to_string(),
+        });
+
+        // Other tests may publish concurrently; wait for ours
+        loop {
+            if let Some(CacheEvent::CardUpdated { id: got, .. }) = events.next().await {
+                if got == id {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_event_serializes_with_type_tag() {
+        let event = CacheEvent::CircuitBreakerStateChanged {
+            name: "cards_search".to_string(),
+            from: CircuitState::Closed,
+            to: CircuitState::Open,
+        };
+        assert_eq!(event.kind(), "circuit_breaker_state_changed");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "type": "circuit_breaker_state_changed",
+                "name": "cards_search",
+                "from": "closed",
+                "to": "open",
+            })
+        );
+    }
+}
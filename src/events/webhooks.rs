@@ -0,0 +1,261 @@
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::config::Settings;
+use crate::events::CacheEvent;
+use crate::metrics::registry::{WEBHOOK_DELIVERIES_TOTAL, WEBHOOK_DELIVERY_ATTEMPTS_TOTAL};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header carrying the event type of a webhook delivery
+pub const EVENT_HEADER: &str = "X-Scryfall-Cache-Event";
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>` when a secret is configured
+pub const SIGNATURE_HEADER: &str = "X-Scryfall-Cache-Signature";
+
+/// Webhook notification configuration
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Endpoints every matching event is POSTed to
+    pub urls: Vec<String>,
+    /// Shared secret used to sign request bodies (unsigned when unset)
+    pub secret: Option<String>,
+    /// Event types delivered (see `CacheEvent::kind`)
+    pub events: Vec<String>,
+    /// Retries per URL after the first failed attempt
+    pub max_retries: u32,
+    /// Per-attempt request timeout
+    pub timeout: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            secret: None,
+            events: vec!["bulk_reload_completed".to_string()],
+            max_retries: 3,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            urls: settings
+                .var("WEBHOOK_URLS")
+                .map(|v| split_list(&v))
+                .unwrap_or_default(),
+            secret: settings
+                .var("WEBHOOK_SECRET")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            events: settings
+                .var("WEBHOOK_EVENTS")
+                .map(|v| split_list(&v))
+                .ok()
+                .filter(|events| !events.is_empty())
+                .unwrap_or(defaults.events),
+            max_retries: settings
+                .var("WEBHOOK_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_retries),
+            timeout: settings
+                .var("WEBHOOK_TIMEOUT_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+        }
+    }
+
+    /// Whether `event` should be delivered
+    pub fn wants(&self, event: &CacheEvent) -> bool {
+        self.events.iter().any(|kind| kind == event.kind())
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Body of a webhook request: the event fields plus delivery metadata
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    #[serde(flatten)]
+    event: &'a CacheEvent,
+    instance_id: &'a str,
+    sent_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// `sha256=<hex HMAC-SHA256>` of `body` keyed with `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// POSTs events to the configured webhook URLs, retrying failed deliveries
+pub struct WebhookDispatcher {
+    http_client: reqwest::Client,
+    config: WebhookConfig,
+    instance_id: String,
+}
+
+impl WebhookDispatcher {
+    pub fn new(config: WebhookConfig, instance_id: String) -> Self {
+        let http_client = reqwest::Client::builder()
+            .user_agent("scryfall-cache/0.1.0")
+            .timeout(config.timeout)
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            http_client,
+            config,
+            instance_id,
+        }
+    }
+
+    pub fn config(&self) -> &WebhookConfig {
+        &self.config
+    }
+
+    /// Deliver `event` to every configured URL, returning how many deliveries succeeded
+    pub async fn dispatch(&self, event: &CacheEvent) -> usize {
+        let payload = WebhookPayload {
+            event,
+            instance_id: &self.instance_id,
+            sent_at: chrono::Utc::now(),
+        };
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize webhook payload: {}", e);
+                return 0;
+            }
+        };
+        let signature = self.config.secret.as_deref().map(|s| sign(s, &body));
+
+        let deliveries = self
+            .config
+            .urls
+            .iter()
+            .map(|url| self.deliver(url, event.kind(), &body, signature.as_deref()));
+        let delivered = futures::future::join_all(deliveries)
+            .await
+            .into_iter()
+            .filter(|ok| *ok)
+            .count();
+
+        info!(
+            "Delivered {} webhook to {} of {} URLs",
+            event.kind(),
+            delivered,
+            self.config.urls.len()
+        );
+        delivered
+    }
+
+    /// POST `body` to one URL, retrying network errors, 429s and 5xx responses with
+    /// exponential backoff
+    async fn deliver(&self, url: &str, kind: &str, body: &[u8], signature: Option<&str>) -> bool {
+        for attempt in 0..=self.config.max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_secs(1 << (attempt - 1).min(6))).await;
+            }
+
+            let mut request = self
+                .http_client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, kind)
+                .body(body.to_vec());
+            if let Some(signature) = signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+
+            let retryable = match request.send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    WEBHOOK_DELIVERY_ATTEMPTS_TOTAL
+                        .with_label_values(&[status.as_str()])
+                        .inc();
+                    if status.is_success() {
+                        debug!("Webhook {} delivered to {}", kind, url);
+                        WEBHOOK_DELIVERIES_TOTAL
+                            .with_label_values(&["success"])
+                            .inc();
+                        return true;
+                    }
+                    warn!("Webhook {} to {} returned {}", kind, url, status);
+                    status.is_server_error() || status.as_u16() == 429
+                }
+                Err(e) => {
+                    WEBHOOK_DELIVERY_ATTEMPTS_TOTAL
+                        .with_label_values(&["error"])
+                        .inc();
+                    warn!("Webhook {} to {} failed: {}", kind, url, e);
+                    true
+                }
+            };
+
+            if !retryable {
+                break;
+            }
+        }
+
+        WEBHOOK_DELIVERIES_TOTAL
+            .with_label_values(&["failure"])
+            .inc();
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_matches_known_hmac() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_default_config_only_wants_reload_completion() {
+        let config = WebhookConfig::default();
+        assert!(config.urls.is_empty());
+        assert!(config.wants(&CacheEvent::BulkReloadCompleted {
+            cards_imported: 10,
+            duration_ms: 5,
+        }));
+        assert!(!config.wants(&CacheEvent::BulkReloadStarted {
+            bulk_types: vec!["default_cards".to_string()],
+        }));
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(
+            split_list(" https://a.example/hook, ,https://b.example/hook "),
+            vec!["https://a.example/hook", "https://b.example/hook"]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use async_graphql::dataloader::Loader;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::cache::manager::CacheManager;
+use crate::db::CardProjection;
+use crate::errors::ServiceError;
+use crate::models::card::Card;
+
+/// A card lookup: the card ID plus the columns the requesting selection set needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CardKey {
+    pub id: Uuid,
+    pub projection: CardProjection,
+}
+
+/// Batches card-by-ID lookups issued while resolving a GraphQL request into
+/// `CacheManager::get_cards_batch_projected` calls, one per distinct projection.
+/// Cards missing locally are fetched from Scryfall, as `get_card` would.
+pub struct CardLoader {
+    cache_manager: Arc<CacheManager>,
+}
+
+impl CardLoader {
+    pub fn new(cache_manager: Arc<CacheManager>) -> Self {
+        Self { cache_manager }
+    }
+}
+
+impl Loader<CardKey> for CardLoader {
+    type Value = Card;
+    type Error = Arc<ServiceError>;
+
+    async fn load(&self, keys: &[CardKey]) -> Result<HashMap<CardKey, Card>, Self::Error> {
+        let mut by_projection: HashMap<CardProjection, Vec<Uuid>> = HashMap::new();
+        for key in keys {
+            let ids = by_projection.entry(key.projection).or_default();
+            ids.push(key.id);
+        }
+        debug!(
+            "Loading {} cards in {} batches",
+            keys.len(),
+            by_projection.len()
+        );
+
+        let mut cards = HashMap::with_capacity(keys.len());
+        for (projection, ids) in by_projection {
+            let (found, _missing) = self
+                .cache_manager
+                .get_cards_batch_projected(&ids, true, &projection)
+                .await
+                .map_err(Arc::new)?;
+            for card in found {
+                let key = CardKey {
+                    id: card.id,
+                    projection,
+                };
+                cards.insert(key, card);
+            }
+        }
+
+        Ok(cards)
+    }
+}
@@ -1,5 +1,7 @@
+pub mod loader;
 pub mod schema;
 pub mod types;
 
-pub use schema::{create_schema, GraphQLSchema};
+pub use loader::{CardKey, CardLoader};
+pub use schema::{create_schema, GraphQLConfig, GraphQLSchema};
 pub use types::*;
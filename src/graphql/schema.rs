@@ -1,10 +1,78 @@
+use async_graphql::dataloader::DataLoader;
 use async_graphql::*;
+use futures::future::ready;
+use futures::{Stream, StreamExt};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::cache::manager::CacheManager;
+use crate::config::Settings;
+use crate::db::{AutocompleteOptions, CardProjection};
+use crate::events::{self, CacheEvent};
+use crate::graphql::loader::{CardKey, CardLoader};
 use crate::graphql::types::*;
-use crate::scryfall::bulk_loader::BulkLoader;
+use crate::models::card::parse_lang_code;
+
+/// GraphQL layer configuration
+#[derive(Debug, Clone)]
+pub struct GraphQLConfig {
+    /// Push the requested card fields down to the database so heavy JSON columns
+    /// are only read when the selection set asks for them
+    pub column_projection: bool,
+}
+
+impl Default for GraphQLConfig {
+    fn default() -> Self {
+        Self {
+            column_projection: true,
+        }
+    }
+}
+
+impl GraphQLConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            column_projection: settings
+                .var("GRAPHQL_COLUMN_PROJECTION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
+        }
+    }
+}
+
+/// Work out which card columns a selection set needs.
+///
+/// `CardType` never exposes `raw_json`, so it is always skipped; the remaining JSON
+/// columns are only read when a field backed by them is selected.
+fn card_projection(ctx: &Context<'_>, card_selection: Lookahead<'_>) -> CardProjection {
+    let enabled = ctx
+        .data::<GraphQLConfig>()
+        .map(|c| c.column_projection)
+        .unwrap_or(false);
+    if !enabled {
+        return CardProjection::ALL;
+    }
+
+    CardProjection {
+        prices: card_selection.field("prices").exists()
+            || card_selection.field("usdPrice").exists()
+            || card_selection.field("usdFoilPrice").exists(),
+        image_uris: card_selection.field("imageUris").exists(),
+        legalities: card_selection.field("legalities").exists(),
+        card_faces: card_selection.field("faces").exists(),
+        ..CardProjection::MINIMAL
+    }
+}
+
+/// Validate an optional `lang` argument (Scryfall language code)
+fn parse_lang_arg(lang: Option<String>) -> Result<Option<String>> {
+    lang.map(|lang| {
+        parse_lang_code(&lang).ok_or_else(|| Error::new(format!("Invalid lang: {}", lang)))
+    })
+    .transpose()
+}
 
 /// GraphQL Query root
 pub struct Query;
@@ -13,30 +81,68 @@ pub struct Query;
 impl Query {
     /// Get a card by its ID
     async fn card(&self, ctx: &Context<'_>, id: ID) -> Result<Option<CardType>> {
-        let cache_manager = ctx.data::<Arc<CacheManager>>()?;
+        // Batched with other `card` lookups resolving concurrently (e.g. aliased fields)
+        let loader = ctx.data::<DataLoader<CardLoader>>()?;
 
         let card_id = Uuid::parse_str(&id.0)
             .map_err(|e| Error::new(format!("Invalid UUID: {}", e)))?;
 
-        let card = cache_manager
-            .get_card(card_id)
+        let key = CardKey {
+            id: card_id,
+            projection: card_projection(ctx, ctx.look_ahead()),
+        };
+
+        let card = loader
+            .load_one(key)
             .await
             .map_err(|e| Error::new(format!("Failed to fetch card: {}", e)))?;
 
         Ok(card.map(CardType::from))
     }
 
-    /// Get a card by name (fuzzy or exact match)
+    /// Get a card by name (fuzzy or exact match), optionally the printing in a given
+    /// language (Scryfall language code, e.g. "ja"). Tokens and emblems are skipped
+    /// unless `includeTokens` is set.
     async fn card_by_name(
         &self,
         ctx: &Context<'_>,
         name: String,
         #[graphql(default = true)] fuzzy: bool,
+        lang: Option<String>,
+        #[graphql(default = false)] include_tokens: bool,
+    ) -> Result<Option<CardType>> {
+        let cache_manager = ctx.data::<Arc<CacheManager>>()?;
+        let lang = parse_lang_arg(lang)?;
+
+        let card = cache_manager
+            .search_by_name_with(&name, fuzzy, lang.as_deref(), include_tokens)
+            .await
+            .map_err(|e| Error::new(format!("Failed to search by name: {}", e)))?;
+
+        Ok(card.map(CardType::from))
+    }
+
+    /// Get a card by name like REST `/cards/named`: pass exactly one of `exact`
+    /// (case-insensitive) or `fuzzy`. `lang` and `includeTokens` work as in `cardByName`.
+    async fn card_named(
+        &self,
+        ctx: &Context<'_>,
+        exact: Option<String>,
+        fuzzy: Option<String>,
+        lang: Option<String>,
+        #[graphql(default = false)] include_tokens: bool,
     ) -> Result<Option<CardType>> {
         let cache_manager = ctx.data::<Arc<CacheManager>>()?;
 
+        let (name, fuzzy) = match (exact, fuzzy) {
+            (Some(name), None) => (name, false),
+            (None, Some(name)) => (name, true),
+            _ => return Err(Error::new("Provide exactly one of 'exact' or 'fuzzy'")),
+        };
+        let lang = parse_lang_arg(lang)?;
+
         let card = cache_manager
-            .search_by_name(&name, fuzzy)
+            .search_by_name_with(&name, fuzzy, lang.as_deref(), include_tokens)
             .await
             .map_err(|e| Error::new(format!("Failed to search by name: {}", e)))?;
 
@@ -51,21 +157,31 @@ impl Query {
         #[graphql(default = 100)] limit: i64,
     ) -> Result<Vec<CardType>> {
         let cache_manager = ctx.data::<Arc<CacheManager>>()?;
+        let projection = card_projection(ctx, ctx.look_ahead());
 
         let cards = cache_manager
-            .search(&query, Some(limit))
+            .search_projected(&query, Some(limit), &projection)
             .await
             .map_err(|e| Error::new(format!("Failed to search cards: {}", e)))?;
 
         Ok(cards.into_iter().map(CardType::from).collect())
     }
 
-    /// Autocomplete card names by prefix
-    async fn autocomplete(&self, ctx: &Context<'_>, prefix: String) -> Result<Vec<String>> {
+    /// Autocomplete card names by prefix: up to `limit` names (default 20, max 100),
+    /// leaving out extras (tokens, emblems, planes...) unless `include_extras` is set
+    async fn autocomplete(
+        &self,
+        ctx: &Context<'_>,
+        prefix: String,
+        #[graphql(default = 20)] limit: i64,
+        #[graphql(default = false)] include_extras: bool,
+        #[graphql(default = false)] exclude_digital: bool,
+    ) -> Result<Vec<String>> {
         let cache_manager = ctx.data::<Arc<CacheManager>>()?;
+        let options = AutocompleteOptions::new(Some(limit), include_extras, exclude_digital);
 
         let names = cache_manager
-            .autocomplete(&prefix)
+            .autocomplete(&prefix, &options)
             .await
             .map_err(|e| Error::new(format!("Failed to autocomplete: {}", e)))?;
 
@@ -92,8 +208,10 @@ impl Query {
 
         let ids = ids?;
 
+        let projection = card_projection(ctx, ctx.look_ahead().field("cards"));
+
         let (cards, missing_ids) = cache_manager
-            .get_cards_batch(&ids, input.fetch_missing)
+            .get_cards_batch_projected(&ids, input.fetch_missing, &projection)
             .await
             .map_err(|e| Error::new(format!("Failed to fetch batch cards: {}", e)))?;
 
@@ -112,45 +230,108 @@ impl Query {
             .await
             .map_err(|e| Error::new(format!("Failed to get stats: {}", e)))?;
 
-        Ok(CacheStatsType {
-            total_cards: stats.total_cards,
-            total_cache_entries: stats.total_cache_entries,
-        })
+        Ok(stats.into())
     }
 }
 
-/// GraphQL Mutation root
-pub struct Mutation;
+/// GraphQL Subscription root, backed by the service event bus
+pub struct Subscription;
 
-#[Object]
-impl Mutation {
-    /// Manually trigger a bulk data reload from Scryfall
-    async fn reload_bulk_data(&self, ctx: &Context<'_>) -> Result<BulkDataReloadType> {
-        let bulk_loader = ctx.data::<Arc<BulkLoader>>()?;
-
-        match bulk_loader.load().await {
-            Ok(_) => Ok(BulkDataReloadType {
-                success: true,
-                message: "Bulk data reloaded successfully".to_string(),
-            }),
-            Err(e) => Ok(BulkDataReloadType {
-                success: false,
-                message: format!("Failed to reload bulk data: {}", e),
-            }),
-        }
+#[Subscription]
+impl Subscription {
+    /// Bulk data reloads as they start, complete or fail
+    async fn bulk_reload(&self) -> impl Stream<Item = BulkReloadEventType> {
+        events::stream().filter_map(|event| {
+            ready(match event {
+                CacheEvent::BulkReloadStarted { bulk_types } => Some(BulkReloadEventType {
+                    status: BulkReloadStatus::Started,
+                    bulk_types,
+                    cards_imported: None,
+                    duration_ms: None,
+                    error: None,
+                }),
+                CacheEvent::BulkReloadCompleted {
+                    cards_imported,
+                    duration_ms,
+                } => Some(BulkReloadEventType {
+                    status: BulkReloadStatus::Completed,
+                    bulk_types: Vec::new(),
+                    cards_imported: Some(cards_imported as i64),
+                    duration_ms: Some(duration_ms as i64),
+                    error: None,
+                }),
+                CacheEvent::BulkReloadFailed { error } => Some(BulkReloadEventType {
+                    status: BulkReloadStatus::Failed,
+                    bulk_types: Vec::new(),
+                    cards_imported: None,
+                    duration_ms: None,
+                    error: Some(error),
+                }),
+                _ => None,
+            })
+        })
+    }
+
+    /// Circuit breaker state changes, optionally for a single breaker
+    async fn circuit_breaker_state_changed(
+        &self,
+        name: Option<String>,
+    ) -> impl Stream<Item = CircuitBreakerStateChangeType> {
+        events::stream().filter_map(move |event| {
+            ready(match event {
+                CacheEvent::CircuitBreakerStateChanged {
+                    name: breaker,
+                    from,
+                    to,
+                } if name.is_none() || name.as_deref() == Some(breaker.as_str()) => {
+                    Some(CircuitBreakerStateChangeType {
+                        name: breaker,
+                        from: from.as_str().to_string(),
+                        to: to.as_str().to_string(),
+                    })
+                }
+                _ => None,
+            })
+        })
+    }
+
+    /// Cards written outside of bulk imports (incremental sync, Scryfall fallback),
+    /// optionally for a single card
+    async fn card_updated(&self, id: Option<ID>) -> Result<impl Stream<Item = CardUpdatedType>> {
+        let id = id
+            .map(|id| Uuid::parse_str(&id.0))
+            .transpose()
+            .map_err(|e| Error::new(format!("Invalid UUID: {}", e)))?;
+
+        Ok(events::stream().filter_map(move |event| {
+            ready(match event {
+                CacheEvent::CardUpdated { id: card_id, name }
+                    if id.is_none() || id == Some(card_id) =>
+                {
+                    Some(CardUpdatedType {
+                        id: ID(card_id.to_string()),
+                        name,
+                    })
+                }
+                _ => None,
+            })
+        }))
     }
 }
 
-/// GraphQL schema type
-pub type GraphQLSchema = Schema<Query, Mutation, EmptySubscription>;
+/// GraphQL schema type. There are no mutations: the `/graphql` endpoint sits outside the
+/// admin token and audit log, so reloads go through `POST /admin/reload`.
+pub type GraphQLSchema = Schema<Query, EmptyMutation, Subscription>;
 
 /// Create a new GraphQL schema with the given cache manager
-pub fn create_schema(
-    cache_manager: Arc<CacheManager>,
-    bulk_loader: Arc<BulkLoader>,
-) -> GraphQLSchema {
-    Schema::build(Query, Mutation, EmptySubscription)
+pub fn create_schema(cache_manager: Arc<CacheManager>, config: GraphQLConfig) -> GraphQLSchema {
+    // Without a cache the loader only batches concurrent lookups, so sharing it across
+    // requests never serves stale cards
+    let card_loader = DataLoader::new(CardLoader::new(cache_manager.clone()), tokio::spawn);
+
+    Schema::build(Query, EmptyMutation, Subscription)
         .data(cache_manager)
-        .data(bulk_loader)
+        .data(card_loader)
+        .data(config)
         .finish()
 }
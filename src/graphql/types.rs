@@ -1,8 +1,11 @@
 use async_graphql::*;
 use serde_json::Value as JsonValue;
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::models::card::Card as DbCard;
+use crate::cache::manager::{CacheManager, CacheStats};
+use crate::models::card::{Card as DbCard, CardFace, RelatedCard};
+use crate::utils::mana::{parse_mana_cost, ManaSymbol};
 
 /// GraphQL representation of a Magic: The Gathering card
 #[derive(Debug, Clone)]
@@ -25,9 +28,18 @@ pub struct CardType {
     pub loyalty: Option<String>,
     pub keywords: Option<Vec<String>>,
     pub released_at: Option<String>,
+    pub lang: Option<String>,
+    pub printed_name: Option<String>,
+    pub printed_type_line: Option<String>,
+    pub printed_text: Option<String>,
+    pub layout: Option<String>,
+    pub multiverse_ids: Option<Vec<i32>>,
+    pub arena_id: Option<i32>,
+    pub mtgo_id: Option<i32>,
     pub prices_json: Option<JsonValue>,
     pub image_uris_json: Option<JsonValue>,
     pub legalities_json: Option<JsonValue>,
+    pub faces: Vec<CardFaceType>,
 }
 
 #[Object]
@@ -52,6 +64,16 @@ impl CardType {
         &self.mana_cost
     }
 
+    /// Mana cost broken into its distinct symbols
+    async fn mana_cost_parsed(&self) -> Option<Vec<ManaSymbolType>> {
+        self.mana_cost.as_deref().map(|cost| {
+            parse_mana_cost(cost)
+                .into_iter()
+                .map(ManaSymbolType::from)
+                .collect()
+        })
+    }
+
     /// Converted mana cost
     async fn cmc(&self) -> Option<f64> {
         self.cmc
@@ -122,6 +144,51 @@ impl CardType {
         &self.released_at
     }
 
+    /// Faces of a multi-faced card, in printed order (empty for single-faced cards)
+    async fn faces(&self) -> &[CardFaceType] {
+        &self.faces
+    }
+
+    /// Language code of this printing (e.g., "en", "ja")
+    async fn lang(&self) -> &Option<String> {
+        &self.lang
+    }
+
+    /// Localized name as printed (non-English printings)
+    async fn printed_name(&self) -> &Option<String> {
+        &self.printed_name
+    }
+
+    /// Localized type line as printed (non-English printings)
+    async fn printed_type_line(&self) -> &Option<String> {
+        &self.printed_type_line
+    }
+
+    /// Localized rules text as printed (non-English printings)
+    async fn printed_text(&self) -> &Option<String> {
+        &self.printed_text
+    }
+
+    /// Scryfall layout (e.g. "normal", "transform", "token", "emblem")
+    async fn layout(&self) -> &Option<String> {
+        &self.layout
+    }
+
+    /// Gatherer multiverse ids
+    async fn multiverse_ids(&self) -> &Option<Vec<i32>> {
+        &self.multiverse_ids
+    }
+
+    /// MTG Arena id
+    async fn arena_id(&self) -> Option<i32> {
+        self.arena_id
+    }
+
+    /// MTGO catalog id
+    async fn mtgo_id(&self) -> Option<i32> {
+        self.mtgo_id
+    }
+
     /// Get card prices (JSON string)
     async fn prices(&self) -> Option<String> {
         self.prices_json.as_ref().map(|v| v.to_string())
@@ -154,10 +221,30 @@ impl CardType {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string())
     }
+
+    /// Cards linked through Scryfall's `all_parts`: tokens this card creates, its meld
+    /// pair or meld result, and combo pieces
+    async fn related_cards(&self, ctx: &Context<'_>) -> Result<Vec<RelatedCardType>> {
+        let cache_manager = ctx.data::<Arc<CacheManager>>()?;
+        let id =
+            Uuid::parse_str(&self.id.0).map_err(|e| Error::new(format!("Invalid UUID: {}", e)))?;
+
+        let related = cache_manager
+            .get_related_cards(id)
+            .await
+            .map_err(|e| Error::new(format!("Failed to fetch related cards: {}", e)))?;
+
+        Ok(related
+            .unwrap_or_default()
+            .into_iter()
+            .map(RelatedCardType::from)
+            .collect())
+    }
 }
 
 impl From<DbCard> for CardType {
     fn from(card: DbCard) -> Self {
+        let faces = card.faces().into_iter().map(CardFaceType::from).collect();
         Self {
             id: ID(card.id.to_string()),
             oracle_id: card.oracle_id.map(|id| ID(id.to_string())),
@@ -177,9 +264,117 @@ impl From<DbCard> for CardType {
             loyalty: card.loyalty,
             keywords: card.keywords,
             released_at: card.released_at.map(|d| d.to_string()),
+            lang: card.lang,
+            printed_name: card.printed_name,
+            printed_type_line: card.printed_type_line,
+            printed_text: card.printed_text,
+            layout: card.layout,
+            multiverse_ids: card.multiverse_ids,
+            arena_id: card.arena_id,
+            mtgo_id: card.mtgo_id,
             prices_json: card.prices,
             image_uris_json: card.image_uris,
             legalities_json: card.legalities,
+            faces,
+        }
+    }
+}
+
+/// One face of a multi-faced card
+#[derive(Debug, Clone, SimpleObject)]
+pub struct CardFaceType {
+    /// Face name
+    pub name: String,
+    /// Mana cost of this face
+    pub mana_cost: Option<String>,
+    /// Type line of this face
+    pub type_line: Option<String>,
+    /// Oracle rules text of this face
+    pub oracle_text: Option<String>,
+    /// Colors of this face
+    pub colors: Option<Vec<String>>,
+    /// Power (for creature faces)
+    pub power: Option<String>,
+    /// Toughness (for creature faces)
+    pub toughness: Option<String>,
+    /// Loyalty (for planeswalker faces)
+    pub loyalty: Option<String>,
+    /// Flavor text
+    pub flavor_text: Option<String>,
+    /// Image URIs of this face (JSON string), for cards with separately printed faces
+    pub image_uris: Option<String>,
+}
+
+impl From<CardFace> for CardFaceType {
+    fn from(face: CardFace) -> Self {
+        Self {
+            name: face.name,
+            mana_cost: face.mana_cost,
+            type_line: face.type_line,
+            oracle_text: face.oracle_text,
+            colors: face.colors,
+            power: face.power,
+            toughness: face.toughness,
+            loyalty: face.loyalty,
+            flavor_text: face.flavor_text,
+            image_uris: face.image_uris.map(|v| v.to_string()),
+        }
+    }
+}
+
+/// A card linked to another through Scryfall's `all_parts`
+#[derive(Debug, Clone, SimpleObject)]
+pub struct RelatedCardType {
+    /// ID of the linked card
+    pub id: ID,
+    /// How the cards are linked: `token`, `meld_part`, `meld_result` or `combo_piece`
+    pub component: String,
+    /// Name of the linked card
+    pub name: String,
+    /// Type line of the linked card
+    pub type_line: Option<String>,
+    /// The linked card, if it is stored locally or known to Scryfall
+    pub card: Option<CardType>,
+}
+
+impl From<RelatedCard> for RelatedCardType {
+    fn from(related: RelatedCard) -> Self {
+        Self {
+            id: ID(related.part.id.to_string()),
+            component: related.part.component,
+            name: related.part.name,
+            type_line: related.part.type_line,
+            card: related.card.map(CardType::from),
+        }
+    }
+}
+
+/// One distinct symbol of a mana cost
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ManaSymbolType {
+    /// Symbol without braces (e.g., "2", "W", "U/B", "G/P")
+    pub symbol: String,
+    /// How many times the symbol appears in the cost
+    pub count: i32,
+    /// Colors that can pay for the symbol; empty for generic and colorless mana
+    pub colors: Vec<String>,
+    /// Payable with either of two kinds of mana
+    pub hybrid: bool,
+    /// Payable with 2 life
+    pub phyrexian: bool,
+    /// What one occurrence adds to the mana value
+    pub mana_value: f64,
+}
+
+impl From<ManaSymbol> for ManaSymbolType {
+    fn from(symbol: ManaSymbol) -> Self {
+        Self {
+            symbol: symbol.symbol,
+            count: symbol.count as i32,
+            colors: symbol.colors,
+            hybrid: symbol.hybrid,
+            phyrexian: symbol.phyrexian,
+            mana_value: symbol.mana_value,
         }
     }
 }
@@ -192,16 +387,74 @@ pub struct CacheStatsType {
 
     /// Total number of cached query results
     pub total_cache_entries: i64,
+
+    /// Total number of hits served from the database query cache
+    pub total_cache_hits: i64,
+
+    /// Redis keyspace hits and misses; null when Redis is not configured or unreachable
+    pub redis: Option<HitRateType>,
+
+    /// Hits and misses per cache tier since startup, in lookup order
+    pub tiers: Vec<TierStatsType>,
+
+    /// Whether the database holds any cards
+    pub bulk_data_loaded: bool,
+
+    /// When the last bulk import finished
+    pub last_bulk_import: Option<String>,
+
+    /// Seconds since the service started
+    pub uptime_seconds: u64,
 }
 
-/// Bulk data reload result
+/// Hit/miss counters
 #[derive(Debug, Clone, SimpleObject)]
-pub struct BulkDataReloadType {
-    /// Whether the reload was successful
-    pub success: bool,
+pub struct HitRateType {
+    pub hits: u64,
+    pub misses: u64,
+
+    /// Hits as a percentage of lookups
+    pub hit_rate: f64,
+}
 
-    /// Status message
-    pub message: String,
+/// Hit/miss counters of one cache tier
+#[derive(Debug, Clone, SimpleObject)]
+pub struct TierStatsType {
+    /// Tier name (`query_cache`, `redis`, `database` or `api`)
+    pub tier: String,
+    pub hits: u64,
+    pub misses: u64,
+
+    /// Hits as a percentage of lookups
+    pub hit_rate: f64,
+}
+
+impl From<CacheStats> for CacheStatsType {
+    fn from(stats: CacheStats) -> Self {
+        Self {
+            total_cards: stats.total_cards,
+            total_cache_entries: stats.total_cache_entries,
+            total_cache_hits: stats.total_cache_hits,
+            redis: stats.redis.map(|redis| HitRateType {
+                hits: redis.hits,
+                misses: redis.misses,
+                hit_rate: redis.hit_rate,
+            }),
+            tiers: stats
+                .tiers
+                .into_iter()
+                .map(|tier| TierStatsType {
+                    tier: tier.tier,
+                    hits: tier.hits,
+                    misses: tier.misses,
+                    hit_rate: tier.hit_rate,
+                })
+                .collect(),
+            bulk_data_loaded: stats.bulk_import.loaded,
+            last_bulk_import: stats.bulk_import.last_import.map(|t| t.to_string()),
+            uptime_seconds: stats.uptime_seconds,
+        }
+    }
 }
 
 /// Input type for batch card queries
@@ -224,3 +477,53 @@ pub struct BatchCardResult {
     /// IDs that were not found
     pub missing_ids: Vec<ID>,
 }
+
+/// Stage of a bulk data reload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum BulkReloadStatus {
+    Started,
+    Completed,
+    Failed,
+}
+
+/// Bulk data reload progress event
+#[derive(Debug, Clone, SimpleObject)]
+pub struct BulkReloadEventType {
+    /// Which stage of the reload this event reports
+    pub status: BulkReloadStatus,
+
+    /// Bulk data types being imported (started events only)
+    pub bulk_types: Vec<String>,
+
+    /// Distinct cards imported (completed events only)
+    pub cards_imported: Option<i64>,
+
+    /// Import duration in milliseconds (completed events only)
+    pub duration_ms: Option<i64>,
+
+    /// Failure reason (failed events only)
+    pub error: Option<String>,
+}
+
+/// Circuit breaker state change event
+#[derive(Debug, Clone, SimpleObject)]
+pub struct CircuitBreakerStateChangeType {
+    /// Breaker name (the Scryfall endpoint it guards)
+    pub name: String,
+
+    /// Previous state (`closed`, `open` or `half_open`)
+    pub from: String,
+
+    /// New state
+    pub to: String,
+}
+
+/// Card written to the local database outside of a bulk import
+#[derive(Debug, Clone, SimpleObject)]
+pub struct CardUpdatedType {
+    /// Card ID
+    pub id: ID,
+
+    /// Card name
+    pub name: String,
+}
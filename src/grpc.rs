@@ -0,0 +1,339 @@
+//! gRPC API for internal consumers that prefer typed clients and streaming. Serves
+//! `scryfall_cache.v1.CardService` (`proto/scryfall_cache/v1/cards.proto`) on `GRPC_PORT`,
+//! next to the REST and GraphQL APIs and backed by the same `AppState`. Needs the `grpc`
+//! feature.
+
+use anyhow::Result;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+use crate::api::handlers::AppState;
+
+#[cfg(feature = "grpc")]
+pub use service::{proto, CardServiceImpl};
+
+/// Serve the gRPC API on `listener` until `shutdown` is cancelled, then let in-flight
+/// calls finish
+#[cfg(feature = "grpc")]
+pub async fn serve(
+    listener: TcpListener,
+    state: AppState,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    use proto::card_service_server::CardServiceServer;
+    use tonic::transport::{server::TcpIncoming, Server};
+
+    let incoming = TcpIncoming::from_listener(listener, true, None)
+        .map_err(|e| anyhow::anyhow!("Failed to accept gRPC connections: {}", e))?;
+    Server::builder()
+        .add_service(CardServiceServer::new(CardServiceImpl::new(state)))
+        .serve_with_incoming_shutdown(incoming, shutdown.cancelled_owned())
+        .await?;
+    Ok(())
+}
+
+#[cfg(not(feature = "grpc"))]
+pub async fn serve(
+    _listener: TcpListener,
+    _state: AppState,
+    _shutdown: CancellationToken,
+) -> Result<()> {
+    anyhow::bail!("gRPC support not compiled (missing grpc feature)")
+}
+
+#[cfg(feature = "grpc")]
+mod service {
+    use futures::stream::{BoxStream, StreamExt};
+    use tonic::{Request, Response, Status};
+    use tracing::{error, info};
+    use uuid::Uuid;
+
+    use crate::api::handlers::AppState;
+    use crate::cache::manager::CacheStats;
+    use crate::errors::ServiceError;
+    use crate::models::card::Card;
+    use crate::query::{QueryParser, SearchOrder, SortDirection, SortOrder, UniqueMode};
+
+    use proto::card_service_server::CardService;
+    use proto::{
+        BatchGetCardsRequest, BatchGetCardsResponse, GetCardRequest, GetStatsRequest,
+        SearchCardsRequest, Stats, TierStats,
+    };
+
+    /// Messages and service traits generated from `cards.proto`
+    pub mod proto {
+        tonic::include_proto!("scryfall_cache.v1");
+    }
+
+    impl From<Card> for proto::Card {
+        fn from(card: Card) -> Self {
+            Self {
+                id: card.id.to_string(),
+                oracle_id: card.oracle_id.map(|id| id.to_string()),
+                name: card.name,
+                mana_cost: card.mana_cost,
+                cmc: card.cmc,
+                type_line: card.type_line,
+                oracle_text: card.oracle_text,
+                colors: card.colors.unwrap_or_default(),
+                color_identity: card.color_identity.unwrap_or_default(),
+                keywords: card.keywords.unwrap_or_default(),
+                set_code: card.set_code,
+                set_name: card.set_name,
+                collector_number: card.collector_number,
+                rarity: card.rarity,
+                power: card.power,
+                toughness: card.toughness,
+                loyalty: card.loyalty,
+                price_usd: card.price_usd,
+                price_eur: card.price_eur,
+                price_tix: card.price_tix,
+                edhrec_rank: card.edhrec_rank,
+                penny_rank: card.penny_rank,
+                released_at: card.released_at.map(|date| date.to_string()),
+                lang: card.lang,
+                layout: card.layout,
+                raw_json: card.raw_json.to_string(),
+            }
+        }
+    }
+
+    impl From<CacheStats> for Stats {
+        fn from(stats: CacheStats) -> Self {
+            Self {
+                total_cards: stats.total_cards,
+                total_cache_entries: stats.total_cache_entries,
+                total_cache_hits: stats.total_cache_hits,
+                tiers: stats
+                    .tiers
+                    .into_iter()
+                    .map(|tier| TierStats {
+                        tier: tier.tier,
+                        hits: tier.hits,
+                        misses: tier.misses,
+                        hit_rate: tier.hit_rate,
+                    })
+                    .collect(),
+                bulk_data_loaded: stats.bulk_import.loaded,
+                last_import: stats
+                    .bulk_import
+                    .last_import
+                    .map(|at| at.format("%Y-%m-%dT%H:%M:%S").to_string()),
+                uptime_seconds: stats.uptime_seconds,
+                cache_generation: stats.cache_generation,
+            }
+        }
+    }
+
+    /// gRPC status for a service error, matching the HTTP status the REST API answers with
+    fn status(e: ServiceError) -> Status {
+        let message = e.to_string();
+        match e {
+            ServiceError::Validation(_) => Status::invalid_argument(message),
+            ServiceError::NotFound(_) => Status::not_found(message),
+            ServiceError::ReadOnly(_) => Status::failed_precondition(message),
+            ServiceError::Timeout(_) => Status::deadline_exceeded(message),
+            ServiceError::ReloadInProgress(_) => Status::aborted(message),
+            ServiceError::ScryfallApi(_) | ServiceError::CircuitOpen(_) => {
+                Status::unavailable(message)
+            }
+            ServiceError::Db(_) | ServiceError::Internal(_) => Status::internal(message),
+        }
+    }
+
+    fn parse_id(id: &str) -> Result<Uuid, Status> {
+        Uuid::parse_str(id.trim())
+            .map_err(|_| Status::invalid_argument(format!("Invalid card id '{}'", id)))
+    }
+
+    /// `CardService` answered from the shared application state
+    pub struct CardServiceImpl {
+        state: AppState,
+    }
+
+    impl CardServiceImpl {
+        pub fn new(state: AppState) -> Self {
+            Self { state }
+        }
+
+        fn validate_query(&self, query: &str) -> Result<(), Status> {
+            let validator = &self.state.query_validator;
+            validator
+                .validate_query_string(query)
+                .map_err(|e| Status::invalid_argument(e.to_string()))?;
+            let ast =
+                QueryParser::parse(query).map_err(|e| Status::invalid_argument(e.to_string()))?;
+            validator
+                .validate_ast(&ast)
+                .map_err(|e| Status::invalid_argument(e.to_string()))
+        }
+    }
+
+    #[tonic::async_trait]
+    impl CardService for CardServiceImpl {
+        async fn get_card(
+            &self,
+            request: Request<GetCardRequest>,
+        ) -> Result<Response<proto::Card>, Status> {
+            let id = parse_id(&request.get_ref().id)?;
+            info!("gRPC GetCard: {}", id);
+
+            match self
+                .state
+                .cache_manager
+                .get_card(id)
+                .await
+                .map_err(status)?
+            {
+                Some(card) => Ok(Response::new(card.into())),
+                None => Err(Status::not_found(format!("Card not found: {}", id))),
+            }
+        }
+
+        async fn batch_get_cards(
+            &self,
+            request: Request<BatchGetCardsRequest>,
+        ) -> Result<Response<BatchGetCardsResponse>, Status> {
+            let request = request.into_inner();
+            let max_ids = self.state.batch.max_ids;
+            if request.ids.is_empty() {
+                return Err(Status::invalid_argument("ids must not be empty"));
+            }
+            if request.ids.len() > max_ids {
+                return Err(Status::invalid_argument(format!(
+                    "too many ids: {} (max {})",
+                    request.ids.len(),
+                    max_ids
+                )));
+            }
+            let ids = request
+                .ids
+                .iter()
+                .map(|id| parse_id(id))
+                .collect::<Result<Vec<_>, _>>()?;
+            info!("gRPC BatchGetCards: {} ids", ids.len());
+
+            let (cards, missing_ids) = self
+                .state
+                .cache_manager
+                .get_cards_batch(&ids, request.fetch_missing)
+                .await
+                .map_err(status)?;
+            Ok(Response::new(BatchGetCardsResponse {
+                cards: cards.into_iter().map(Into::into).collect(),
+                missing_ids: missing_ids.iter().map(Uuid::to_string).collect(),
+            }))
+        }
+
+        type SearchCardsStream = BoxStream<'static, Result<proto::Card, Status>>;
+
+        async fn search_cards(
+            &self,
+            request: Request<SearchCardsRequest>,
+        ) -> Result<Response<Self::SearchCardsStream>, Status> {
+            let request = request.into_inner();
+            let query = request.q.trim();
+            if query.is_empty() {
+                return Err(Status::invalid_argument("q must not be empty"));
+            }
+            info!("gRPC SearchCards: query='{}'", query);
+            self.validate_query(query)?;
+
+            let unique = match request.unique.as_deref() {
+                None => None,
+                Some(value) => Some(UniqueMode::parse(value).ok_or_else(|| {
+                    Status::invalid_argument(format!(
+                        "Invalid unique mode '{}': expected cards, prints or art",
+                        value
+                    ))
+                })?),
+            };
+            let order = match request.order.as_deref() {
+                None => SortOrder::default(),
+                Some(value) => SortOrder::parse(value).ok_or_else(|| {
+                    Status::invalid_argument(format!(
+                        "Invalid order '{}': expected name, released, usd, eur, tix, edhrec or penny",
+                        value
+                    ))
+                })?,
+            };
+            let dir = match request.dir.as_deref() {
+                None => SortDirection::default(),
+                Some(value) => SortDirection::parse(value).ok_or_else(|| {
+                    Status::invalid_argument(format!(
+                        "Invalid dir '{}': expected auto, asc or desc",
+                        value
+                    ))
+                })?,
+            };
+            let limit = request.limit.filter(|&limit| limit > 0);
+
+            let cards = self
+                .state
+                .cache_manager
+                .stream_search(query, unique, SearchOrder::new(order, dir), limit)
+                .map_err(status)?;
+            let stream = cards.map(|card| {
+                card.map(proto::Card::from).map_err(|e| {
+                    error!("gRPC search stream failed: {}", e);
+                    Status::internal(e.to_string())
+                })
+            });
+            Ok(Response::new(stream.boxed()))
+        }
+
+        async fn get_stats(
+            &self,
+            _request: Request<GetStatsRequest>,
+        ) -> Result<Response<Stats>, Status> {
+            let stats = self.state.cache_manager.get_stats().await.map_err(status)?;
+            Ok(Response::new(stats.into()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_card_conversion() {
+            let card = Card::from_scryfall_json(serde_json::json!({
+                "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+                "name": "Lightning Bolt",
+                "cmc": 1.0,
+                "colors": ["R"],
+                "released_at": "2009-07-17",
+            }))
+            .unwrap();
+
+            let message = proto::Card::from(card);
+            assert_eq!(message.id, "550c74d4-1fcb-406a-b02a-639a760a4380");
+            assert_eq!(message.cmc, Some(1.0));
+            assert_eq!(message.colors, ["R"]);
+            assert!(message.keywords.is_empty());
+            assert_eq!(message.released_at.as_deref(), Some("2009-07-17"));
+            assert!(message.raw_json.contains("Lightning Bolt"));
+        }
+
+        #[test]
+        fn test_status_codes() {
+            let code = |e| status(e).code();
+            assert_eq!(
+                code(ServiceError::Validation("bad".into())),
+                tonic::Code::InvalidArgument
+            );
+            assert_eq!(
+                code(ServiceError::NotFound("gone".into())),
+                tonic::Code::NotFound
+            );
+            assert_eq!(
+                code(ServiceError::CircuitOpen("search".into())),
+                tonic::Code::Unavailable
+            );
+            assert_eq!(
+                code(ServiceError::Db(anyhow::anyhow!("down"))),
+                tonic::Code::Internal
+            );
+        }
+    }
+}
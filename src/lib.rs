@@ -1,14 +1,24 @@
 // Library exports for testing
 pub mod api;
+pub mod audit;
 pub mod background;
 pub mod cache;
 pub mod circuit_breaker;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod collections;
 pub mod config;
 pub mod db;
+pub mod deadline;
 pub mod errors;
+pub mod events;
 pub mod graphql;
+pub mod grpc;
 pub mod metrics;
 pub mod models;
 pub mod query;
+pub mod render;
 pub mod scryfall;
+pub mod tenant;
+pub mod tls;
 pub mod utils;
@@ -1,31 +1,43 @@
 mod api;
+mod audit;
 mod background;
 mod cache;
 mod circuit_breaker;
+mod collections;
 mod config;
 mod db;
+mod deadline;
 mod errors;
+mod events;
 mod graphql;
+mod grpc;
 mod metrics;
 mod models;
 mod query;
+mod render;
 mod scryfall;
+mod tenant;
+mod tls;
 mod utils;
 
 use anyhow::{Context, Result};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
-use tracing::{error, info};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use api::handlers::AppStateInner;
-use api::routes::create_router;
+use api::routes::{create_admin_router, create_router};
 use cache::manager::CacheManager;
 use cache::redis::{RedisCache, RedisConfig};
-use config::Config;
+use config::{Config, LogFormat};
 use graphql::create_schema;
 use scryfall::bulk_loader::BulkLoader;
 use scryfall::client::ScryfallClient;
+use scryfall::incremental::IncrementalSync;
+use scryfall::preview::PreviewPoller;
 
 /// Wait for shutdown signal (SIGTERM or SIGINT)
 async fn shutdown_signal() {
@@ -61,13 +73,22 @@ async fn shutdown_signal() {
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info,scryfall_cache=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let registry = tracing_subscriber::registry().with(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "info,scryfall_cache=debug".into()),
+    );
+    match LogFormat::from_env() {
+        LogFormat::Json => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .flatten_event(true)
+                    .with_current_span(true)
+                    .with_span_list(false),
+            )
+            .init(),
+        LogFormat::Pretty => registry.with(tracing_subscriber::fmt::layer()).init(),
+    }
 
     info!(
         "Starting Scryfall Cache Microservice v{}",
@@ -79,9 +100,20 @@ async fn main() -> Result<()> {
     info!("Metrics registry initialized");
 
     // Load configuration
-    let config = Config::from_env().context("Failed to load configuration")?;
+    let mut config =
+        Config::load(std::env::args().skip(1)).context("Failed to load configuration")?;
     info!("Configuration loaded successfully");
 
+    // Read replicas never write: no bulk refreshes, cache cleanup, card repair or warm-up
+    let read_only = config.server.read_only;
+    if read_only {
+        warn!("Read-only mode: bulk loads, cache writes and admin mutations are disabled");
+        config.jobs.bulk_refresh.enabled = false;
+        config.jobs.cache_cleanup.enabled = false;
+        config.jobs.card_repair.enabled = false;
+        config.jobs.warmup.queries.clear();
+    }
+
     // Initialize database backend
     info!("Connecting to database...");
     let db = db::init_database(&config.database)
@@ -93,10 +125,14 @@ async fn main() -> Result<()> {
         .context("Failed to test database connection")?;
     info!("Database connection established");
 
-    // Run migrations (PostgreSQL only)
+    // Run migrations (PostgreSQL only); replicas rely on the primary having run them
     #[cfg(feature = "postgres")]
     {
-        if let Some(pg_backend) = db.as_any().downcast_ref::<db::PostgresBackend>() {
+        let pg_backend = db
+            .as_any()
+            .downcast_ref::<db::PostgresBackend>()
+            .filter(|_| !read_only);
+        if let Some(pg_backend) = pg_backend {
             db::schema::run_migrations(pg_backend.pool())
                 .await
                 .context("Failed to run database migrations")?;
@@ -109,8 +145,13 @@ async fn main() -> Result<()> {
 
         let redis_config_instance = RedisConfig {
             url: redis_config.url.clone(),
-            ttl_seconds: redis_config.ttl_seconds,
+            key_prefix: redis_config.key_prefix.clone(),
+            card_ttl_seconds: redis_config.card_ttl_seconds,
+            query_ttl_seconds: redis_config.query_ttl_seconds,
+            autocomplete_ttl_seconds: redis_config.autocomplete_ttl_seconds,
             max_value_size_mb: redis_config.max_value_size_mb,
+            encoding: redis_config.encoding,
+            compression: redis_config.compression,
         };
 
         match RedisCache::new(redis_config_instance).await {
@@ -136,71 +177,281 @@ async fn main() -> Result<()> {
     // Initialize Scryfall client
     let scryfall_client = ScryfallClient::new(&config.scryfall);
 
-    // Initialize bulk loader
-    let bulk_loader = BulkLoader::new(db.clone(), config.scryfall.clone());
+    // Load refresh settings (full vs incremental) before the client is handed off
+    let refresh_config = config.jobs.bulk_refresh.clone();
+    let incremental_sync = Arc::new(IncrementalSync::new(
+        db.clone(),
+        scryfall_client.clone(),
+        refresh_config.incremental_lookback_days,
+    ));
 
-    // Load bulk data if needed
-    if bulk_loader.should_load().await? {
-        info!("Loading bulk data...");
-        if let Err(e) = bulk_loader.load().await {
-            error!("Failed to load bulk data: {}", e);
-            error!("Continuing without bulk data - will rely on API fallback");
-        }
-    } else {
-        info!("Bulk data is up to date, skipping load");
-    }
+    // Cancelled on Ctrl+C/SIGTERM so background jobs and bulk imports stop at a safe point
+    let shutdown = CancellationToken::new();
+
+    // Initialize query validator; its limits can be changed at runtime through the admin API
+    let query_validator = query::QueryValidator::new(config.query_limits.clone());
 
     // Initialize cache manager
     let cache_manager = CacheManager::new(
         redis_cache,
         db.clone(),
-        scryfall_client,
-        config.cache.query_cache_ttl_hours as i32,
-    );
-
-    // Initialize query validator
-    let query_validator = query::QueryValidator::new(query::QueryLimits::from_env());
+        Arc::new(scryfall_client),
+        &config.cache,
+    )
+    .with_read_only(read_only)
+    .with_query_limits(query_validator.runtime_limits().clone());
 
     // Wrap cache_manager in Arc for sharing
     let cache_manager_arc = Arc::new(cache_manager);
 
+    // Initialize bulk loader; successful reloads invalidate the caches
+    let bulk_loader = BulkLoader::new(db.clone(), config.scryfall.clone())
+        .with_shutdown(shutdown.clone())
+        .with_cache(cache_manager_arc.clone())
+        .with_read_only(read_only);
+
+    // Admin mutations are recorded in the database (logged only on read-only replicas)
+    let audit_log =
+        audit::AdminAuditLog::new(db.clone(), config.audit.clone()).with_read_only(read_only);
+
+    // Pagination cursors are signed so clients can't forge or replay them
+    let cursor_signer = match config.server.cursor_secret.as_deref() {
+        Some(secret) => query::CursorSigner::new(secret),
+        None => {
+            warn!("CURSOR_SECRET not set; using a random secret (cursors won't survive restarts)");
+            query::CursorSigner::random()
+        }
+    };
+
     // Clone bulk_loader for background job and wrap in Arc
     let bulk_loader_clone = Arc::new(bulk_loader.clone());
 
     // Create GraphQL schema
-    let graphql_schema = create_schema(cache_manager_arc.clone(), bulk_loader_clone.clone());
+    let graphql_schema = create_schema(cache_manager_arc.clone(), config.graphql.clone());
+
+    // Notify downstream services of bulk reloads (subscribed before the initial load starts)
+    let webhooks = events::webhooks::WebhookDispatcher::new(
+        config.webhooks.clone(),
+        config.server.instance_id.clone(),
+    );
+    let webhook_handle = background::start_webhook_job(Arc::new(webhooks), shutdown.clone());
+
+    // Poll Scryfall for newly previewed cards between bulk loads (own rate budget)
+    let preview_poller = Arc::new(PreviewPoller::new(
+        db.clone(),
+        &config.scryfall,
+        config.jobs.preview_poll.clone(),
+    ));
+    let preview_poll_handle =
+        background::start_preview_poll_job(preview_poller.clone(), read_only, shutdown.clone());
+
+    // Re-evaluate saved searches after every bulk reload, including the initial load
+    let saved_search_handle =
+        background::start_saved_search_job(cache_manager_arc.clone(), shutdown.clone());
+
+    // Load bulk data in the background so health endpoints are available immediately
+    let startup_status = Arc::new(background::StartupStatus::new(&config.jobs.startup));
+    let initial_load_handle =
+        background::start_initial_load(bulk_loader_clone.clone(), startup_status.clone());
+
+    // Warm the query cache once local data is available and after every bulk reload
+    let warmer = Arc::new(cache::warmup::CacheWarmer::new(
+        cache_manager_arc.clone(),
+        config.jobs.warmup.clone(),
+    ));
+    let warmup_handle =
+        background::start_warmup_job(warmer.clone(), bulk_loader_clone.clone(), shutdown.clone());
 
     // Create application state
     let state = Arc::new(AppStateInner {
         cache_manager: cache_manager_arc,
         bulk_loader: (*bulk_loader_clone).clone(),
         query_validator,
+        cursor_signer,
         graphql_schema,
         instance_id: config.server.instance_id.clone(),
+        startup: startup_status,
+        health: config.health.clone(),
+        warmer,
+        response_format: config.server.response_format,
+        scryfall_compat_prefix: config.server.scryfall_compat_prefix,
+        swagger_ui: config.server.swagger_ui,
+        cors: config.cors.clone(),
+        http_cache: config.http_cache.clone(),
+        batch: config.batch.clone(),
+        idempotency: config.idempotency.clone(),
+        bulk_refresh: refresh_config.clone(),
+        graphql: config.graphql.clone(),
+        read_only,
+        admin_port: config.server.admin_port,
+        admin_token: config.server.admin_token.clone(),
+        tenants: Arc::new(tenant::TenantRegistry::new(config.tenants.clone())),
+        audit_log: Arc::new(audit_log),
+        preview_poller,
+        request_timeout: config.request_timeout.clone(),
     });
 
     // Start background bulk data refresh job
-    let refresh_config = background::bulk_refresh::BulkRefreshConfig::from_env();
-    let _refresh_handle = background::start_bulk_refresh_job(bulk_loader_clone, refresh_config);
+    let refresh_handle = background::start_bulk_refresh_job(
+        bulk_loader_clone,
+        incremental_sync,
+        refresh_config,
+        shutdown.clone(),
+    );
+
+    // Start background query cache cleanup job
+    let cleanup_handle = background::start_cache_cleanup_job(
+        state.cache_manager.clone(),
+        config.jobs.cache_cleanup.clone(),
+        shutdown.clone(),
+    );
+
+    // Start background card repair job
+    let repair_handle = background::start_card_repair_job(
+        state.cache_manager.clone(),
+        config.jobs.card_repair.clone(),
+        shutdown.clone(),
+    );
+
+    // Start background metrics refresh job
+    let metrics_refresh_handle = background::start_metrics_refresh_job(
+        state.cache_manager.clone(),
+        config.jobs.metrics_refresh.clone(),
+        shutdown.clone(),
+    );
+
+    // Serve the gRPC API on its own port, if configured
+    let grpc_handle = match config.grpc_address() {
+        Some(grpc_addr) => {
+            let grpc_listener = tokio::net::TcpListener::bind(&grpc_addr)
+                .await
+                .context("Failed to bind gRPC server")?;
+            info!("gRPC server listening on {}", grpc_addr);
+            let state = state.clone();
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                if let Err(e) = grpc::serve(grpc_listener, state, shutdown).await {
+                    error!("gRPC server error: {}", e);
+                }
+            })
+        }
+        None => tokio::spawn(async {}),
+    };
 
     // Create router
+    let cache_manager = state.cache_manager.clone();
+    let admin_app = create_admin_router(state.clone());
     let app = create_router(state);
 
+    // Serve metrics and the admin API on their own port, if configured
+    let admin_handle = match config.admin_address() {
+        Some(admin_addr) => {
+            let admin_listener = tokio::net::TcpListener::bind(&admin_addr)
+                .await
+                .context("Failed to bind admin server")?;
+            info!("Admin server listening on {}", admin_addr);
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                if let Err(e) = axum::serve(admin_listener, admin_app)
+                    .with_graceful_shutdown(shutdown.cancelled_owned())
+                    .await
+                {
+                    error!("Admin server error: {}", e);
+                }
+            })
+        }
+        None => tokio::spawn(async {}),
+    };
+    if config.server.admin_port.is_none() && config.server.admin_token.is_none() {
+        warn!("ADMIN_TOKEN not set; /metrics and the admin API are open on the API port");
+    }
+
     // Start server
     let addr = config.server_address();
     info!("Starting server on {}", addr);
 
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .context("Failed to bind server")?;
-
-    info!("Server listening on {}", addr);
+    // Redirect plain HTTP to HTTPS, if configured
+    let redirect_handle = match config
+        .server
+        .tls
+        .as_ref()
+        .and_then(|tls| tls.redirect_http_port)
+    {
+        Some(redirect_port) => {
+            let redirect_addr = format!("{}:{}", config.server.host, redirect_port);
+            let redirect_listener = tokio::net::TcpListener::bind(&redirect_addr)
+                .await
+                .context("Failed to bind HTTP redirect server")?;
+            info!("Redirecting HTTP on {} to HTTPS", redirect_addr);
+            let redirect_app = tls::redirect_router(config.server.port);
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                if let Err(e) = axum::serve(redirect_listener, redirect_app)
+                    .with_graceful_shutdown(shutdown.cancelled_owned())
+                    .await
+                {
+                    error!("HTTP redirect server error: {}", e);
+                }
+            })
+        }
+        None => tokio::spawn(async {}),
+    };
 
-    // Serve with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .context("Server error")?;
+    // Serve with graceful shutdown; background jobs start stopping as soon as the signal
+    // arrives, while in-flight requests finish
+    let served = match &config.server.tls {
+        Some(tls_config) => {
+            tokio::spawn({
+                let shutdown = shutdown.clone();
+                async move {
+                    shutdown_signal().await;
+                    shutdown.cancel();
+                }
+            });
+            tls::serve(&addr, app, tls_config, shutdown.clone()).await
+        }
+        None => {
+            let listener = tokio::net::TcpListener::bind(&addr)
+                .await
+                .context("Failed to bind server")?;
+
+            info!("Server listening on {}", addr);
+
+            axum::serve(listener, app)
+                .with_graceful_shutdown({
+                    let shutdown = shutdown.clone();
+                    async move {
+                        shutdown_signal().await;
+                        shutdown.cancel();
+                    }
+                })
+                .await
+                .map_err(anyhow::Error::from)
+        }
+    };
+    shutdown.cancel();
+
+    background::wait_for_jobs(
+        vec![
+            ("initial_load", initial_load_handle),
+            ("bulk_refresh", refresh_handle),
+            ("cache_cleanup", cleanup_handle),
+            ("card_repair", repair_handle),
+            ("metrics_refresh", metrics_refresh_handle),
+            ("admin_server", admin_handle),
+            ("grpc_server", grpc_handle),
+            ("http_redirect", redirect_handle),
+            ("warmup", warmup_handle),
+            ("webhooks", webhook_handle),
+            ("saved_searches", saved_search_handle),
+            ("preview_poll", preview_poll_handle),
+        ],
+        Duration::from_secs(config.server.shutdown_timeout_seconds),
+    )
+    .await;
+    cache_manager.shutdown().await;
+
+    served.context("Server error")?;
 
     info!("Server shutdown complete");
 
@@ -1,7 +1,8 @@
 use lazy_static::lazy_static;
 use prometheus::{
-    register_gauge, register_histogram_vec, register_int_counter_vec, register_int_gauge, Gauge,
-    HistogramVec, IntCounterVec, IntGauge,
+    register_gauge, register_histogram, register_histogram_vec, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec, Gauge, Histogram, HistogramVec, IntCounterVec,
+    IntGauge, IntGaugeVec,
 };
 
 lazy_static! {
@@ -36,9 +37,82 @@ lazy_static! {
     )
     .unwrap();
 
-    pub static ref CACHE_SIZE_BYTES: IntGauge = register_int_gauge!(
+    pub static ref CACHE_TIER_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
+        "cache_tier_duration_seconds",
+        "Time spent looking up a single cache tier in seconds",
+        &["operation", "tier"],  // operation: search, get_card; tier: redis, query_cache, database, api
+        vec![0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    )
+    .unwrap();
+
+    pub static ref SEARCH_TOTAL_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
+        "search_total_duration_seconds",
+        "End-to-end cached search duration in seconds, by the tier that answered",
+        &["tier"],  // tier: redis, query_cache, database, api, error
+        vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    )
+    .unwrap();
+
+    // Card Writer Metrics (write-behind storage of cards fetched from Scryfall)
+    pub static ref CARD_WRITER_SUBMISSIONS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "card_writer_submissions_total",
+        "Fetched card sets submitted for storage",
+        &["mode"]  // mode: queued (background writer), inline (queue full or writer disabled)
+    )
+    .unwrap();
+
+    pub static ref CARD_WRITER_QUEUE_DEPTH: IntGauge = register_int_gauge!(
+        "card_writer_queue_depth",
+        "Submissions waiting to be merged into a batch by the card writer"
+    )
+    .unwrap();
+
+    pub static ref CARD_WRITER_FLUSHES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "card_writer_flushes_total",
+        "Batches flushed by the background card writer",
+        &["outcome"]  // outcome: success, failure
+    )
+    .unwrap();
+
+    pub static ref REDIS_ENTRY_SIZE_BYTES: HistogramVec = register_histogram_vec!(
+        "redis_entry_size_bytes",
+        "Size of serialized (and compressed) values written to Redis",
+        &["class"],  // class: card, query, autocomplete
+        vec![256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0]
+    )
+    .unwrap();
+
+    pub static ref CARD_WRITER_BATCH_CARDS: Histogram = register_histogram!(
+        "card_writer_batch_cards",
+        "Distinct cards per batch flushed by the background card writer",
+        vec![1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0]
+    )
+    .unwrap();
+
+    pub static ref CACHE_CLEANUP_DELETED: IntGaugeVec = register_int_gauge_vec!(
+        "cache_cleanup_deleted",
+        "Entries removed by the last cache cleanup run",
+        &["tier"]  // tier: database, redis
+    )
+    .unwrap();
+
+    pub static ref CACHE_CLEANUP_DELETED_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "cache_cleanup_deleted_total",
+        "Total entries removed by cache cleanup runs",
+        &["tier"]
+    )
+    .unwrap();
+
+    pub static ref CACHE_WARMUP_DURATION_SECONDS: Gauge = register_gauge!(
+        "cache_warmup_duration_seconds",
+        "Time taken for last cache warm-up run"
+    )
+    .unwrap();
+
+    pub static ref CACHE_SIZE_BYTES: IntGaugeVec = register_int_gauge_vec!(
         "cache_size_bytes",
-        "Current cache size in bytes"
+        "Current cache size in bytes",
+        &["tier"]  // tier: redis, database
     )
     .unwrap();
 
@@ -64,18 +138,76 @@ lazy_static! {
     )
     .unwrap();
 
+    pub static ref SCRYFALL_UPSTREAM_FAILOVERS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "scryfall_upstream_failovers_total",
+        "Requests passed on to the next upstream because an upstream was down",
+        &["upstream"]
+    )
+    .unwrap();
+
+    pub static ref SCRYFALL_HEDGED_REQUESTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "scryfall_hedged_requests_total",
+        "Second attempts sent for Scryfall requests slower than the hedge delay",
+        &["endpoint"]
+    )
+    .unwrap();
+
+    pub static ref SCRYFALL_HEDGE_WINS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "scryfall_hedge_wins_total",
+        "Hedged Scryfall requests answered by the second attempt first",
+        &["endpoint"]
+    )
+    .unwrap();
+
+    // Circuit Breaker Metrics
+    pub static ref CIRCUIT_BREAKER_STATE: IntGaugeVec = register_int_gauge_vec!(
+        "circuit_breaker_state",
+        "Current circuit breaker state (0 = closed, 1 = open, 2 = half-open)",
+        &["name"]
+    )
+    .unwrap();
+
+    pub static ref CIRCUIT_BREAKER_FAILURES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "circuit_breaker_failures_total",
+        "Total failed calls through a circuit breaker",
+        &["name"]
+    )
+    .unwrap();
+
+    pub static ref CIRCUIT_BREAKER_TRANSITIONS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "circuit_breaker_transitions_total",
+        "Total circuit breaker state transitions",
+        &["name", "from", "to"]
+    )
+    .unwrap();
+
+    // Webhook Metrics
+    pub static ref WEBHOOK_DELIVERIES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "webhook_deliveries_total",
+        "Total webhook deliveries per URL, after retries",
+        &["outcome"]  // outcome: success, failure
+    )
+    .unwrap();
+
+    pub static ref WEBHOOK_DELIVERY_ATTEMPTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "webhook_delivery_attempts_total",
+        "Total webhook HTTP attempts, including retries",
+        &["status"]  // HTTP status code, or "error" when no response was received
+    )
+    .unwrap();
+
     // Database Metrics
     pub static ref DATABASE_QUERIES_TOTAL: IntCounterVec = register_int_counter_vec!(
         "database_queries_total",
         "Total database queries",
-        &["query_type"]  // query_type: select, insert, update, delete
+        &["backend", "query_type"]  // backend: postgres, sqlite; query_type: select, insert, update, delete
     )
     .unwrap();
 
     pub static ref DATABASE_QUERY_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
         "database_query_duration_seconds",
         "Database query duration in seconds",
-        &["query_type"],
+        &["backend", "query_type"],
         vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0]
     )
     .unwrap();
@@ -131,10 +263,28 @@ pub fn init_metrics() {
     lazy_static::initialize(&HTTP_REQUEST_DURATION_SECONDS);
     lazy_static::initialize(&CACHE_HITS_TOTAL);
     lazy_static::initialize(&CACHE_MISSES_TOTAL);
+    lazy_static::initialize(&CACHE_TIER_DURATION_SECONDS);
+    lazy_static::initialize(&SEARCH_TOTAL_DURATION_SECONDS);
+    lazy_static::initialize(&CARD_WRITER_SUBMISSIONS_TOTAL);
+    lazy_static::initialize(&CARD_WRITER_QUEUE_DEPTH);
+    lazy_static::initialize(&CARD_WRITER_FLUSHES_TOTAL);
+    lazy_static::initialize(&CARD_WRITER_BATCH_CARDS);
+    lazy_static::initialize(&CACHE_CLEANUP_DELETED);
+    lazy_static::initialize(&CACHE_CLEANUP_DELETED_TOTAL);
+    lazy_static::initialize(&CACHE_WARMUP_DURATION_SECONDS);
     lazy_static::initialize(&CACHE_SIZE_BYTES);
+    lazy_static::initialize(&REDIS_ENTRY_SIZE_BYTES);
     lazy_static::initialize(&SCRYFALL_API_CALLS_TOTAL);
     lazy_static::initialize(&SCRYFALL_API_ERRORS_TOTAL);
     lazy_static::initialize(&SCRYFALL_RATE_LIMIT_WAITS_TOTAL);
+    lazy_static::initialize(&SCRYFALL_UPSTREAM_FAILOVERS_TOTAL);
+    lazy_static::initialize(&SCRYFALL_HEDGED_REQUESTS_TOTAL);
+    lazy_static::initialize(&SCRYFALL_HEDGE_WINS_TOTAL);
+    lazy_static::initialize(&CIRCUIT_BREAKER_STATE);
+    lazy_static::initialize(&CIRCUIT_BREAKER_FAILURES_TOTAL);
+    lazy_static::initialize(&CIRCUIT_BREAKER_TRANSITIONS_TOTAL);
+    lazy_static::initialize(&WEBHOOK_DELIVERIES_TOTAL);
+    lazy_static::initialize(&WEBHOOK_DELIVERY_ATTEMPTS_TOTAL);
     lazy_static::initialize(&DATABASE_QUERIES_TOTAL);
     lazy_static::initialize(&DATABASE_QUERY_DURATION_SECONDS);
     lazy_static::initialize(&DATABASE_CONNECTIONS_ACTIVE);
@@ -0,0 +1,24 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// One admin mutation, as recorded in the admin audit log
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AdminAuditEntry {
+    /// Who made the request: `key:` and a fingerprint of its API key, or `anonymous`
+    pub actor: String,
+    /// HTTP method, e.g. `POST`
+    pub method: String,
+    /// Request path, e.g. `/admin/reload`
+    pub path: String,
+    /// Query string and request body, truncated to `ADMIN_AUDIT_MAX_PARAMETERS_BYTES`
+    pub parameters: Option<String>,
+    /// HTTP status of the response
+    pub status: i32,
+    /// `success` for 1xx-3xx responses, `failure` otherwise
+    pub outcome: String,
+    /// How long the request took
+    pub duration_ms: i64,
+    /// Set by the database when the entry is recorded
+    pub created_at: Option<NaiveDateTime>,
+}
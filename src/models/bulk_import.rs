@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// One imported bulk data file, as recorded in the import history
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkImport {
+    /// Set by the database when the import is recorded
+    pub id: Option<i32>,
+    /// Scryfall bulk data type, e.g. `default_cards`
+    pub bulk_type: String,
+    /// Download URI the file was fetched from
+    pub source: String,
+    /// Cards written to the database
+    pub total_cards: i32,
+    /// Cards in the file that failed to parse
+    pub failed_cards: i32,
+    /// Size of the file as reported by Scryfall
+    pub file_size_bytes: i64,
+    /// How long downloading and importing the file took
+    pub duration_ms: i64,
+    /// Set by the database when the import is recorded
+    pub imported_at: Option<NaiveDateTime>,
+}
+
+/// A bulk data entry that failed to parse into a card during an import
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BulkImportError {
+    /// Position of the entry in the bulk data file
+    pub position: i64,
+    /// The entry's `id`, as given (it may not be a valid UUID)
+    pub card_id: Option<String>,
+    pub card_name: Option<String>,
+    /// `not_an_object`, `missing_id`, `invalid_id`, `missing_name` or `other`
+    pub category: String,
+    pub message: String,
+}
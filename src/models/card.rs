@@ -26,20 +26,239 @@ pub struct Card {
     pub power: Option<String>,
     pub toughness: Option<String>,
     pub loyalty: Option<String>,
+    /// `power` as a number, for comparisons. `None` for values such as `*`, `1+*` or `X`
+    pub power_num: Option<f64>,
+    /// `toughness` as a number, for comparisons. `None` for non-numeric values
+    pub toughness_num: Option<f64>,
+    /// `loyalty` as a number, for comparisons. `None` for non-numeric values
+    pub loyalty_num: Option<f64>,
     pub keywords: Option<Vec<String>>,
+    /// Lowercased names of the card's faces (`["fire", "ice"]`, or an adventure's
+    /// `["bonecrusher giant", "stomp"]`), for looking a card up by one of its halves.
+    /// `None` for single-faced cards.
+    pub face_names: Option<Vec<String>>,
     pub prices: Option<serde_json::Value>,
+    /// `prices.usd` as a number, for price filters
+    pub price_usd: Option<f64>,
+    /// `prices.eur` as a number, for price filters
+    pub price_eur: Option<f64>,
+    /// `prices.tix` as a number, for price filters
+    pub price_tix: Option<f64>,
+    /// Popularity rank on EDHREC (1 = most played), for `order=edhrec`
+    pub edhrec_rank: Option<i32>,
+    /// Popularity rank on Penny Dreadful, for `order=penny`
+    pub penny_rank: Option<i32>,
     pub image_uris: Option<serde_json::Value>,
     pub card_faces: Option<serde_json::Value>,
     pub legalities: Option<serde_json::Value>,
     pub released_at: Option<NaiveDate>,
     pub raw_json: serde_json::Value,
+    /// Language code of this printing (e.g. `en`, `ja`)
+    pub lang: Option<String>,
+    /// Localized name as printed, for non-English printings
+    pub printed_name: Option<String>,
+    /// Localized type line as printed, for non-English printings
+    pub printed_type_line: Option<String>,
+    /// Localized rules text as printed, for non-English printings
+    pub printed_text: Option<String>,
+    /// Scryfall layout (`normal`, `transform`, `token`, `emblem`, ...)
+    pub layout: Option<String>,
+    /// Gatherer multiverse ids of this printing
+    pub multiverse_ids: Option<Vec<i32>>,
+    /// MTG Arena id
+    pub arena_id: Option<i32>,
+    /// MTGO catalog id of the non-foil printing
+    pub mtgo_id: Option<i32>,
+    /// MTGO catalog id of the foil printing
+    pub mtgo_foil_id: Option<i32>,
     #[serde(skip_deserializing)]
     pub created_at: Option<chrono::NaiveDateTime>,
     #[serde(skip_deserializing)]
     pub updated_at: Option<chrono::NaiveDateTime>,
 }
 
+/// One face of a multi-faced card (transform, modal double-faced, split, flip, adventure)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CardFace {
+    pub name: String,
+    pub mana_cost: Option<String>,
+    pub type_line: Option<String>,
+    pub oracle_text: Option<String>,
+    pub colors: Option<Vec<String>>,
+    pub power: Option<String>,
+    pub toughness: Option<String>,
+    pub loyalty: Option<String>,
+    pub flavor_text: Option<String>,
+    /// Per-face images. Only set for cards whose faces are printed on separate sides;
+    /// split and adventure cards share the card-level `image_uris`.
+    pub image_uris: Option<serde_json::Value>,
+}
+
+/// An entry of Scryfall's `all_parts`: another card this one is linked to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CardPart {
+    /// ID of the linked card
+    pub id: Uuid,
+    /// How the cards are linked: `token`, `meld_part`, `meld_result` or `combo_piece`
+    pub component: String,
+    pub name: String,
+    pub type_line: Option<String>,
+}
+
+/// A linked card from `all_parts`, resolved to the full card
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RelatedCard {
+    #[serde(flatten)]
+    pub part: CardPart,
+    /// The linked card; `None` if it is neither stored locally nor known to Scryfall
+    pub card: Option<Card>,
+}
+
+/// Layouts of token cards, matched by `is:token`
+pub const TOKEN_LAYOUTS: &[&str] = &["token", "double_faced_token"];
+
+/// Layout of emblem cards, matched by `is:emblem`
+pub const EMBLEM_LAYOUT: &str = "emblem";
+
+/// Layouts Scryfall counts as extras (tokens, emblems, art cards, planes, schemes and
+/// vanguards), left out of autocomplete unless `include_extras` is set
+pub const EXTRA_LAYOUTS: &[&str] = &[
+    "token",
+    "double_faced_token",
+    "emblem",
+    "art_series",
+    "planar",
+    "scheme",
+    "vanguard",
+];
+
+/// An id from one of the external id spaces Scryfall can look cards up by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalId {
+    /// Gatherer multiverse id (matches any of a card's `multiverse_ids`)
+    Multiverse(i32),
+    /// MTG Arena id
+    Arena(i32),
+    /// MTGO catalog id (matches either `mtgo_id` or `mtgo_foil_id`)
+    Mtgo(i32),
+}
+
+impl ExternalId {
+    /// Path segment Scryfall uses for this id space (`/cards/{kind}/{id}`)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Multiverse(_) => "multiverse",
+            Self::Arena(_) => "arena",
+            Self::Mtgo(_) => "mtgo",
+        }
+    }
+
+    /// The id value
+    pub fn value(&self) -> i32 {
+        match *self {
+            Self::Multiverse(id) | Self::Arena(id) | Self::Mtgo(id) => id,
+        }
+    }
+}
+
+impl std::fmt::Display for ExternalId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.kind(), self.value())
+    }
+}
+
+/// Normalize a Scryfall language code (`en`, `ja`, `zhs`, `ph`, ...) to lowercase.
+/// Returns `None` unless the value is 2-3 ASCII letters.
+pub fn parse_lang_code(value: &str) -> Option<String> {
+    let value = value.trim();
+    let valid = (2..=3).contains(&value.len()) && value.chars().all(|c| c.is_ascii_alphabetic());
+    valid.then(|| value.to_ascii_lowercase())
+}
+
+/// Parse a printed power, toughness or loyalty value (`3`, `-1`, `.5`, `+2`) as a number.
+/// Returns `None` for variable values such as `*`, `1+*`, `X` or `∞`.
+pub fn parse_stat(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let valid = digits.chars().any(|c| c.is_ascii_digit())
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.matches('.').count() <= 1;
+    if valid {
+        value.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Normalize a card name for lookups: trims it and spaces split-card separators the way
+/// Scryfall prints them (`Fire//Ice` -> `Fire // Ice`)
+pub fn normalize_card_name(name: &str) -> String {
+    name.split("//")
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" // ")
+}
+
 impl Card {
+    /// Whether this is a token or an emblem rather than a real card
+    pub fn is_token_or_emblem(&self) -> bool {
+        self.layout
+            .as_deref()
+            .is_some_and(|layout| layout == EMBLEM_LAYOUT || TOKEN_LAYOUTS.contains(&layout))
+    }
+
+    /// Whether `name` is this card's name or, for multi-faced cards, one of its face names
+    /// (`Fire // Ice` matches `Fire`, `Bonecrusher Giant` matches `Stomp`). Case-insensitive.
+    pub fn matches_name(&self, name: &str) -> bool {
+        let name = normalize_card_name(name);
+        self.name.eq_ignore_ascii_case(&name)
+            || self
+                .name
+                .split(" // ")
+                .any(|face| face.eq_ignore_ascii_case(&name))
+            || self
+                .face_names
+                .iter()
+                .flatten()
+                .any(|face| face.eq_ignore_ascii_case(&name))
+    }
+
+    /// Parsed `card_faces`, in printed order. Empty for single-faced cards and for cards
+    /// read with a projection that skipped the column. Malformed faces are ignored.
+    pub fn faces(&self) -> Vec<CardFace> {
+        self.card_faces
+            .as_ref()
+            .and_then(|v| v.as_array())
+            .map(|faces| {
+                faces
+                    .iter()
+                    .filter_map(|face| serde_json::from_value(face.clone()).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Cards linked to this one through `all_parts` (the tokens it makes, its meld pair,
+    /// combo pieces), without this card itself, which Scryfall lists among its own parts.
+    /// Read from `raw_json`, so empty for cards read with a projection that skipped it.
+    pub fn related_parts(&self) -> Vec<CardPart> {
+        let mut parts: Vec<CardPart> = self
+            .raw_json
+            .get("all_parts")
+            .and_then(|v| v.as_array())
+            .map(|parts| {
+                parts
+                    .iter()
+                    .filter_map(|part| serde_json::from_value::<CardPart>(part.clone()).ok())
+                    .filter(|part| part.id != self.id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut seen = std::collections::HashSet::new();
+        parts.retain(|part| seen.insert(part.id));
+        parts
+    }
+
     /// Create a Card from raw Scryfall JSON
     pub fn from_scryfall_json(value: serde_json::Value) -> Result<Self> {
         // Extract fields from the JSON value
@@ -127,6 +346,10 @@ impl Card {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        let power_num = power.as_deref().and_then(parse_stat);
+        let toughness_num = toughness.as_deref().and_then(parse_stat);
+        let loyalty_num = loyalty.as_deref().and_then(parse_stat);
+
         let keywords = value.get("keywords").and_then(|v| v.as_array()).map(|arr| {
             arr.iter()
                 .filter_map(|v| v.as_str().map(|s| s.to_string()))
@@ -134,8 +357,30 @@ impl Card {
         });
 
         let prices = value.get("prices").cloned();
+        let price = |currency: &str| {
+            prices
+                .as_ref()
+                .and_then(|p| p.get(currency))
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .filter(|amount| amount.is_finite())
+        };
+        let price_usd = price("usd");
+        let price_eur = price("eur");
+        let price_tix = price("tix");
         let image_uris = value.get("image_uris").cloned();
         let card_faces = value.get("card_faces").cloned();
+        let face_names = card_faces
+            .as_ref()
+            .and_then(|v| v.as_array())
+            .map(|faces| {
+                faces
+                    .iter()
+                    .filter_map(|face| face.get("name").and_then(|n| n.as_str()))
+                    .map(|name| name.to_lowercase())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|names| !names.is_empty());
         let legalities = value.get("legalities").cloned();
 
         let released_at = value
@@ -143,6 +388,53 @@ impl Card {
             .and_then(|v| v.as_str())
             .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
 
+        let lang = value
+            .get("lang")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let printed_name = value
+            .get("printed_name")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let printed_type_line = value
+            .get("printed_type_line")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let printed_text = value
+            .get("printed_text")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let layout = value
+            .get("layout")
+            .and_then(|v| v.as_str())
+            .unwrap_or("normal")
+            .to_string();
+
+        let multiverse_ids = value
+            .get("multiverse_ids")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_i64().and_then(|id| i32::try_from(id).ok()))
+                    .collect()
+            });
+
+        let int_field = |field: &str| {
+            value
+                .get(field)
+                .and_then(|v| v.as_i64())
+                .and_then(|id| i32::try_from(id).ok())
+        };
+        let arena_id = int_field("arena_id");
+        let mtgo_id = int_field("mtgo_id");
+        let mtgo_foil_id = int_field("mtgo_foil_id");
+        let edhrec_rank = int_field("edhrec_rank");
+        let penny_rank = int_field("penny_rank");
+
         Ok(Card {
             id,
             oracle_id,
@@ -160,13 +452,31 @@ impl Card {
             power,
             toughness,
             loyalty,
+            power_num,
+            toughness_num,
+            loyalty_num,
             keywords,
+            face_names,
             prices,
+            price_usd,
+            price_eur,
+            price_tix,
+            edhrec_rank,
+            penny_rank,
             image_uris,
             card_faces,
             legalities,
             released_at,
             raw_json: value,
+            lang,
+            printed_name,
+            printed_type_line,
+            printed_text,
+            layout: Some(layout),
+            multiverse_ids,
+            arena_id,
+            mtgo_id,
+            mtgo_foil_id,
             created_at: None,
             updated_at: None,
         })
@@ -202,4 +512,213 @@ mod tests {
         assert_eq!(card.mana_cost, Some("{R}".to_string()));
         assert_eq!(card.cmc, Some(1.0));
     }
+
+    #[test]
+    fn test_card_faces_parsed() {
+        let json = serde_json::json!({
+            "id": "6ee6cd34-c117-4d7e-97d1-8f8464bfaac8",
+            "name": "Delver of Secrets // Insectile Aberration",
+            "card_faces": [
+                {
+                    "object": "card_face",
+                    "name": "Delver of Secrets",
+                    "mana_cost": "{U}",
+                    "type_line": "Creature — Human Wizard",
+                    "oracle_text": "At the beginning of your upkeep, look at the top card of your library.",
+                    "colors": ["U"],
+                    "power": "1",
+                    "toughness": "1",
+                    "image_uris": {"normal": "https://cards.scryfall.io/normal/front/delver.jpg"}
+                },
+                {
+                    "object": "card_face",
+                    "name": "Insectile Aberration",
+                    "mana_cost": "",
+                    "type_line": "Creature — Human Insect",
+                    "oracle_text": "Flying",
+                    "power": "3",
+                    "toughness": "2"
+                }
+            ]
+        });
+
+        let card = Card::from_scryfall_json(json).unwrap();
+        let faces = card.faces();
+        assert_eq!(faces.len(), 2);
+        assert_eq!(faces[0].name, "Delver of Secrets");
+        assert!(faces[0].image_uris.is_some());
+        assert_eq!(faces[1].oracle_text.as_deref(), Some("Flying"));
+        assert_eq!(faces[1].colors, None);
+    }
+
+    #[test]
+    fn test_related_parts() {
+        let json = serde_json::json!({
+            "id": "a7ac1d2c-2a8f-4b5e-9f4f-0b1c2d3e4f50",
+            "name": "Bruna, the Fading Light",
+            "all_parts": [
+                {
+                    "object": "related_card",
+                    "id": "a7ac1d2c-2a8f-4b5e-9f4f-0b1c2d3e4f50",
+                    "component": "meld_part",
+                    "name": "Bruna, the Fading Light",
+                    "type_line": "Legendary Creature — Angel Horror"
+                },
+                {
+                    "object": "related_card",
+                    "id": "5a7a49ea-e4d7-4ee6-9ed8-a1d4fa6b8fd3",
+                    "component": "meld_result",
+                    "name": "Brisela, Voice of Nightmares",
+                    "type_line": "Legendary Creature — Eldrazi Angel"
+                },
+                {"object": "related_card", "component": "token", "name": "No id"}
+            ]
+        });
+
+        let card = Card::from_scryfall_json(json).unwrap();
+        let parts = card.related_parts();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].component, "meld_result");
+        assert_eq!(parts[0].name, "Brisela, Voice of Nightmares");
+    }
+
+    #[test]
+    fn test_localized_card_fields() {
+        let json = serde_json::json!({
+            "id": "2bd56f1a-3f4d-4b52-a3b1-6b4b1b2a1c0d",
+            "name": "Lightning Bolt",
+            "lang": "ja",
+            "printed_name": "稲妻",
+            "printed_type_line": "インスタント",
+            "printed_text": "クリーチャー１体かプレイヤー１人を対象とする。稲妻はそれに３点のダメージを与える。"
+        });
+
+        let card = Card::from_scryfall_json(json).unwrap();
+        assert_eq!(card.lang.as_deref(), Some("ja"));
+        assert_eq!(card.printed_name.as_deref(), Some("稲妻"));
+        assert_eq!(card.printed_type_line.as_deref(), Some("インスタント"));
+        assert!(card.printed_text.is_some());
+    }
+
+    #[test]
+    fn test_token_layouts() {
+        let card = |layout: &str| {
+            Card::from_scryfall_json(serde_json::json!({
+                "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+                "name": "Goblin",
+                "layout": layout
+            }))
+            .unwrap()
+        };
+        assert!(card("token").is_token_or_emblem());
+        assert!(card("double_faced_token").is_token_or_emblem());
+        assert!(card("emblem").is_token_or_emblem());
+        assert!(!card("normal").is_token_or_emblem());
+
+        let missing = serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Lightning Bolt"
+        });
+        let card = Card::from_scryfall_json(missing).unwrap();
+        assert_eq!(card.layout.as_deref(), Some("normal"));
+    }
+
+    #[test]
+    fn test_parse_lang_code() {
+        assert_eq!(parse_lang_code(" JA "), Some("ja".to_string()));
+        assert_eq!(parse_lang_code("zhs"), Some("zhs".to_string()));
+        assert_eq!(parse_lang_code("j"), None);
+        assert_eq!(parse_lang_code("ja lang:any"), None);
+    }
+
+    #[test]
+    fn test_parse_stat() {
+        assert_eq!(parse_stat("3"), Some(3.0));
+        assert_eq!(parse_stat("-1"), Some(-1.0));
+        assert_eq!(parse_stat("+2"), Some(2.0));
+        assert_eq!(parse_stat(".5"), Some(0.5));
+        assert_eq!(parse_stat("*"), None);
+        assert_eq!(parse_stat("1+*"), None);
+        assert_eq!(parse_stat("X"), None);
+        assert_eq!(parse_stat("1.2.3"), None);
+
+        let json = serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Tarmogoyf",
+            "power": "*",
+            "toughness": "1+*"
+        });
+        let card = Card::from_scryfall_json(json).unwrap();
+        assert_eq!(card.power_num, None);
+        assert_eq!(card.toughness_num, None);
+        assert_eq!(card.loyalty_num, None);
+    }
+
+    #[test]
+    fn test_prices_parsed_as_numbers() {
+        let json = serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Tarmogoyf",
+            "prices": { "usd": "24.99", "usd_foil": "40.00", "eur": null, "tix": "1.5" }
+        });
+        let card = Card::from_scryfall_json(json).unwrap();
+        assert_eq!(card.price_usd, Some(24.99));
+        assert_eq!(card.price_eur, None);
+        assert_eq!(card.price_tix, Some(1.5));
+    }
+
+    #[test]
+    fn test_single_faced_card_has_no_faces() {
+        let json = serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Lightning Bolt"
+        });
+        assert!(Card::from_scryfall_json(json).unwrap().faces().is_empty());
+    }
+
+    #[test]
+    fn test_matches_name() {
+        let json = serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Fire // Ice"
+        });
+        let card = Card::from_scryfall_json(json).unwrap();
+        assert!(card.matches_name("fire // ice"));
+        assert!(card.matches_name("Ice"));
+        assert!(!card.matches_name("Fire and Ice"));
+        assert!(card.matches_name("Fire//Ice"));
+
+        let adventure = serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Bonecrusher Giant",
+            "layout": "adventure",
+            "card_faces": [{"name": "Bonecrusher Giant"}, {"name": "Stomp"}]
+        });
+        let card = Card::from_scryfall_json(adventure).unwrap();
+        assert_eq!(
+            card.face_names,
+            Some(vec!["bonecrusher giant".to_string(), "stomp".to_string()])
+        );
+        assert!(card.matches_name("stomp"));
+        assert_eq!(normalize_card_name(" Fire//Ice "), "Fire // Ice");
+    }
+
+    #[test]
+    fn test_external_ids() {
+        let json = serde_json::json!({
+            "id": "550c74d4-1fcb-406a-b02a-639a760a4380",
+            "name": "Lightning Bolt",
+            "multiverse_ids": [442130],
+            "arena_id": 70177,
+            "mtgo_id": 67196,
+            "mtgo_foil_id": 67197
+        });
+
+        let card = Card::from_scryfall_json(json).unwrap();
+        assert_eq!(card.multiverse_ids, Some(vec![442130]));
+        assert_eq!(card.arena_id, Some(70177));
+        assert_eq!(card.mtgo_id, Some(67196));
+        assert_eq!(card.mtgo_foil_id, Some(67197));
+        assert_eq!(ExternalId::Mtgo(67197).to_string(), "mtgo/67197");
+    }
 }
@@ -0,0 +1,46 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Finish a printing is owned in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Finish {
+    #[default]
+    Nonfoil,
+    Foil,
+    Etched,
+}
+
+impl Finish {
+    /// Name stored in the database and used in the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Finish::Nonfoil => "nonfoil",
+            Finish::Foil => "foil",
+            Finish::Etched => "etched",
+        }
+    }
+
+    /// Parse a stored finish name
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "nonfoil" => Some(Finish::Nonfoil),
+            "foil" => Some(Finish::Foil),
+            "etched" => Some(Finish::Etched),
+            _ => None,
+        }
+    }
+}
+
+/// Quantity of one printing, in one finish, owned in a collection
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionEntry {
+    pub card_id: Uuid,
+    pub finish: Finish,
+    /// Copies owned; storing 0 removes the entry
+    pub quantity: i32,
+    /// Set by the database when the entry is stored
+    pub updated_at: Option<NaiveDateTime>,
+}
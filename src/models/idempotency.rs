@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// A request claimed under an `Idempotency-Key`, and its response once it has one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    /// Hash of the request's method, path, query string and body. A retry with the same
+    /// key but a different request is rejected rather than answered with this response.
+    pub request_hash: String,
+    /// HTTP status of the stored response; `None` while the first request is in flight
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub body: Vec<u8>,
+}
+
+impl IdempotencyRecord {
+    /// Record of a request that is still being handled
+    pub fn pending(request_hash: impl Into<String>) -> Self {
+        Self {
+            request_hash: request_hash.into(),
+            status: None,
+            content_type: None,
+            body: Vec::new(),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status.is_none()
+    }
+}
@@ -1 +1,10 @@
+pub mod admin_audit;
+pub mod bulk_import;
 pub mod card;
+pub mod collection;
+pub mod idempotency;
+pub mod query_cache;
+pub mod repair;
+pub mod saved_search;
+pub mod schema_version;
+pub mod sync;
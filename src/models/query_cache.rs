@@ -0,0 +1,23 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A cached search result, as listed by the admin cache endpoints
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QueryCacheEntry {
+    /// Hash of the query text, prefixed with `<tenant>:` for tenants other than the
+    /// default one; the key in both the database and Redis query caches
+    pub query_hash: String,
+    /// Tenant whose search cached the entry
+    pub tenant_id: String,
+    /// Original query text (empty for entries cached before it was recorded)
+    pub query_text: String,
+    /// Number of card IDs in the cached result
+    pub result_count: i64,
+    /// Times the entry has been served from the database query cache
+    pub hit_count: i64,
+    pub created_at: Option<NaiveDateTime>,
+    /// Seconds since the entry was cached
+    pub age_seconds: i64,
+    pub expires_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A stored card that fails an integrity check
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IntegrityIssue {
+    pub id: Uuid,
+    /// Stored name (may be empty, which is itself an issue)
+    pub name: String,
+    /// `invalid_raw_json`, `id_mismatch`, `missing_name`, `missing_oracle_id` or
+    /// `orphan_card_faces`. A card with several problems is reported with the first.
+    pub issue: String,
+}
+
+/// Outcome of an integrity scan and repair
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RepairReport {
+    pub started_at: NaiveDateTime,
+    pub duration_ms: u64,
+    /// Cards found with integrity issues (at most the scan limit)
+    pub issues_found: usize,
+    /// Cards re-fetched from Scryfall and stored again
+    pub repaired: usize,
+    /// Cards Scryfall no longer knows; left as they are
+    pub not_found: usize,
+    /// Set when re-fetching failed; no card was stored
+    pub error: Option<String>,
+    /// The cards found, with what was wrong with each
+    pub issues: Vec<IntegrityIssue>,
+}
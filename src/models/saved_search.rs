@@ -0,0 +1,25 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A search query re-evaluated after every bulk import to find cards that newly match it
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    /// Tenant that registered the search; only visible to that tenant
+    #[serde(skip)]
+    pub tenant_id: String,
+    /// Scryfall query, e.g. `set:mkm r:mythic`
+    pub query: String,
+    /// Optional label chosen by the client
+    pub name: Option<String>,
+    /// Evaluations after bulk imports; 0 until the first import after registration
+    pub runs: i32,
+    /// Cards that first matched in the latest evaluation
+    pub new_matches: i32,
+    /// Set by the database when the search is registered
+    pub created_at: Option<NaiveDateTime>,
+    /// When the search was last evaluated
+    pub last_run_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,50 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A migration recorded in the database's version table
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: Option<NaiveDateTime>,
+    /// How long the migration took to apply
+    pub execution_time_ms: i64,
+}
+
+/// Schema version of the database, compared with the migrations this build knows about
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SchemaVersion {
+    /// `postgres` or `sqlite`; each backend numbers its migrations separately
+    pub backend: String,
+    /// Highest applied migration, `None` if none has been applied yet
+    pub current_version: Option<i64>,
+    /// Highest migration this build knows about
+    pub latest_version: i64,
+    /// Migrations this build knows about that the database hasn't applied
+    pub pending: Vec<i64>,
+    /// Applied migrations in version order
+    pub applied: Vec<AppliedMigration>,
+}
+
+impl SchemaVersion {
+    pub fn new(
+        backend: &str,
+        known_versions: impl IntoIterator<Item = i64>,
+        applied: Vec<AppliedMigration>,
+    ) -> Self {
+        let known: Vec<i64> = known_versions.into_iter().collect();
+        let pending = known
+            .iter()
+            .copied()
+            .filter(|version| !applied.iter().any(|m| m.version == *version))
+            .collect();
+        Self {
+            backend: backend.to_string(),
+            current_version: applied.iter().map(|m| m.version).max(),
+            latest_version: known.iter().copied().max().unwrap_or(0),
+            pending,
+            applied,
+        }
+    }
+}
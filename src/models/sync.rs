@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+/// Outcome of an incremental sync run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStatus {
+    Success,
+    Failed,
+}
+
+impl SyncStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncStatus::Success => "success",
+            SyncStatus::Failed => "failed",
+        }
+    }
+}
+
+/// One entry in the sync ledger
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncRun {
+    /// When the run started; becomes the watermark for the next run on success
+    pub started_at: NaiveDateTime,
+    pub completed_at: NaiveDateTime,
+    /// Watermark the run fetched changes from
+    pub since: NaiveDateTime,
+    pub cards_updated: i32,
+    pub status: SyncStatus,
+    pub error: Option<String>,
+}
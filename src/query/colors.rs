@@ -0,0 +1,82 @@
+//! Values of `c:` and `id:` filters: color letters (`wu`), color names (`blue`) and the
+//! guild, shard and wedge nicknames Scryfall accepts (`azorius`, `esper`, `temur`).
+
+/// Color letters in WUBRG order, the order color sets are normalized to
+const WUBRG: [(char, &str); 5] = [('w', "W"), ('u', "U"), ('b', "B"), ('r', "R"), ('g', "G")];
+
+/// Names accepted in place of color letters
+const NAMED_COLORS: &[(&str, &str)] = &[
+    ("white", "w"),
+    ("blue", "u"),
+    ("black", "b"),
+    ("red", "r"),
+    ("green", "g"),
+    ("colorless", "c"),
+    // Guilds
+    ("azorius", "wu"),
+    ("dimir", "ub"),
+    ("rakdos", "br"),
+    ("gruul", "rg"),
+    ("selesnya", "gw"),
+    ("orzhov", "wb"),
+    ("izzet", "ur"),
+    ("golgari", "bg"),
+    ("boros", "rw"),
+    ("simic", "gu"),
+    // Shards
+    ("bant", "gwu"),
+    ("esper", "wub"),
+    ("grixis", "ubr"),
+    ("jund", "brg"),
+    ("naya", "rgw"),
+    // Wedges
+    ("abzan", "wbg"),
+    ("jeskai", "urw"),
+    ("sultai", "bgu"),
+    ("mardu", "rwb"),
+    ("temur", "gur"),
+];
+
+/// Parse a color filter value into color codes in WUBRG order (`esper` -> `["W", "U", "B"]`).
+/// Colorless (`c` or `colorless`) is the empty set. Returns `None` for values that are not
+/// colors, including colorless combined with colors (`cr`).
+pub fn parse_colors(value: &str) -> Option<Vec<&'static str>> {
+    let value = value.trim().to_lowercase();
+    let letters = NAMED_COLORS
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map_or(value.as_str(), |(_, letters)| letters);
+
+    if letters == "c" {
+        return Some(Vec::new());
+    }
+    if letters.is_empty() || !letters.chars().all(|c| WUBRG.iter().any(|(l, _)| *l == c)) {
+        return None;
+    }
+    Some(
+        WUBRG
+            .iter()
+            .filter(|(letter, _)| letters.contains(*letter))
+            .map(|(_, code)| *code)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_colors() {
+        assert_eq!(parse_colors("uw"), Some(vec!["W", "U"]));
+        assert_eq!(parse_colors("GRR"), Some(vec!["R", "G"]));
+        assert_eq!(parse_colors("red"), Some(vec!["R"]));
+        assert_eq!(parse_colors("Esper"), Some(vec!["W", "U", "B"]));
+        assert_eq!(parse_colors("temur"), Some(vec!["U", "R", "G"]));
+        assert_eq!(parse_colors("c"), Some(vec![]));
+        assert_eq!(parse_colors("colorless"), Some(vec![]));
+        assert_eq!(parse_colors("cr"), None);
+        assert_eq!(parse_colors("purple"), None);
+        assert_eq!(parse_colors(""), None);
+    }
+}
@@ -0,0 +1,246 @@
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::models::card::Card;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Keyset cursor for stable iteration over search results.
+///
+/// Search results are ordered by `(name, id)`, so the last card of a page is enough to
+/// resume from: the next page is every match that sorts strictly after it. Unlike
+/// offsets, this stays cheap deep into a result set and doesn't skip or repeat rows
+/// when cards are inserted mid-iteration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchCursor {
+    /// Name of the last card on the previous page
+    #[serde(rename = "n")]
+    pub name: String,
+    /// ID of the last card on the previous page (tie-breaker for duplicate names)
+    #[serde(rename = "i")]
+    pub id: Uuid,
+}
+
+impl SearchCursor {
+    /// Cursor pointing just past the given card
+    pub fn after(card: &Card) -> Self {
+        Self {
+            name: card.name.clone(),
+            id: card.id,
+        }
+    }
+
+    /// Whether a card sorts strictly after this cursor in `(name, id)` order
+    pub fn is_before(&self, card: &Card) -> bool {
+        (card.name.as_str(), card.id.to_string()) > (self.name.as_str(), self.id.to_string())
+    }
+}
+
+/// Why a cursor token was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorError {
+    /// Not a token produced by this service
+    Malformed,
+    /// Signature doesn't match the payload (tampered, or signed with another secret)
+    BadSignature,
+    /// Token was issued for a different query
+    QueryMismatch,
+    /// Card data has been re-imported since the token was issued
+    Stale,
+}
+
+impl CursorError {
+    /// Stable machine-readable reason, reported in error `details`
+    pub fn reason(&self) -> &'static str {
+        match self {
+            CursorError::Malformed => "malformed",
+            CursorError::BadSignature => "bad_signature",
+            CursorError::QueryMismatch => "query_mismatch",
+            CursorError::Stale => "stale",
+        }
+    }
+}
+
+impl fmt::Display for CursorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CursorError::Malformed => write!(f, "Invalid cursor"),
+            CursorError::BadSignature => write!(f, "Invalid cursor signature"),
+            CursorError::QueryMismatch => write!(f, "Cursor was issued for a different query"),
+            CursorError::Stale => write!(
+                f,
+                "Cursor has expired because card data was refreshed; restart from the first page"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CursorError {}
+
+/// Signed token contents: the keyset position plus what it is bound to
+#[derive(Debug, Serialize, Deserialize)]
+struct CursorPayload {
+    #[serde(rename = "n")]
+    name: String,
+    #[serde(rename = "i")]
+    id: Uuid,
+    /// Data generation (last bulk import time) the cursor was issued against
+    #[serde(rename = "g")]
+    generation: i64,
+    /// Fingerprint of the query the cursor was issued for
+    #[serde(rename = "q")]
+    query: String,
+}
+
+/// Signs and verifies opaque cursor tokens.
+///
+/// Tokens are `<hex payload>.<hex HMAC-SHA256>`, so clients can't forge positions, reuse
+/// a cursor with a different query, or carry one across a bulk data refresh.
+#[derive(Clone)]
+pub struct CursorSigner {
+    key: Vec<u8>,
+}
+
+impl CursorSigner {
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: secret.as_ref().to_vec(),
+        }
+    }
+
+    /// Signer with a random per-process secret. Cursors don't survive a restart and
+    /// aren't accepted by other instances.
+    pub fn random() -> Self {
+        let mut key = Uuid::new_v4().as_bytes().to_vec();
+        key.extend_from_slice(Uuid::new_v4().as_bytes());
+        Self { key }
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length")
+    }
+
+    /// Encode a cursor as an opaque, URL-safe token bound to `query` and `generation`
+    pub fn sign(&self, cursor: &SearchCursor, query: &str, generation: i64) -> String {
+        let payload = CursorPayload {
+            name: cursor.name.clone(),
+            id: cursor.id,
+            generation,
+            query: query_fingerprint(query),
+        };
+        let json = serde_json::to_vec(&payload).unwrap_or_default();
+
+        let mut mac = self.mac();
+        mac.update(&json);
+        format!(
+            "{}.{}",
+            hex::encode(&json),
+            hex::encode(mac.finalize().into_bytes())
+        )
+    }
+
+    /// Decode a token produced by `sign` for the same query and data generation
+    pub fn verify(
+        &self,
+        token: &str,
+        query: &str,
+        generation: i64,
+    ) -> Result<SearchCursor, CursorError> {
+        let (payload, signature) = token.trim().split_once('.').ok_or(CursorError::Malformed)?;
+        let payload = hex::decode(payload).map_err(|_| CursorError::Malformed)?;
+        let signature = hex::decode(signature).map_err(|_| CursorError::Malformed)?;
+
+        let mut mac = self.mac();
+        mac.update(&payload);
+        mac.verify_slice(&signature)
+            .map_err(|_| CursorError::BadSignature)?;
+
+        let payload: CursorPayload =
+            serde_json::from_slice(&payload).map_err(|_| CursorError::Malformed)?;
+        if payload.query != query_fingerprint(query) {
+            return Err(CursorError::QueryMismatch);
+        }
+        if payload.generation != generation {
+            return Err(CursorError::Stale);
+        }
+
+        Ok(SearchCursor {
+            name: payload.name,
+            id: payload.id,
+        })
+    }
+}
+
+fn query_fingerprint(query: &str) -> String {
+    let digest = Sha256::digest(query.trim().as_bytes());
+    hex::encode(&digest[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor() -> SearchCursor {
+        SearchCursor {
+            name: "Lightning Bolt".to_string(),
+            id: Uuid::parse_str("550c74d4-1fcb-406a-b02a-639a760a4380").unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let signer = CursorSigner::new("secret");
+        let token = signer.sign(&cursor(), "t:instant", 42);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit() || c == '.'));
+        assert_eq!(signer.verify(&token, "t:instant", 42).unwrap(), cursor());
+    }
+
+    #[test]
+    fn test_cursor_rejects_garbage() {
+        let signer = CursorSigner::new("secret");
+        assert_eq!(
+            signer.verify("not-a-cursor", "t:instant", 42),
+            Err(CursorError::Malformed)
+        );
+        assert_eq!(
+            signer.verify(&hex::encode("{}"), "t:instant", 42),
+            Err(CursorError::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_cursor_rejects_forgery_and_replay() {
+        let signer = CursorSigner::new("secret");
+        let token = signer.sign(&cursor(), "t:instant", 42);
+
+        // Payload edited by the client
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{}",
+            hex::encode(r#"{"n":"Z","i":"550c74d4-1fcb-406a-b02a-639a760a4380","g":42,"q":""}"#),
+            signature
+        );
+        assert_eq!(
+            signer.verify(&forged, "t:instant", 42),
+            Err(CursorError::BadSignature)
+        );
+
+        // Signed with another secret
+        assert_eq!(
+            CursorSigner::new("other").verify(&token, "t:instant", 42),
+            Err(CursorError::BadSignature)
+        );
+
+        assert_eq!(
+            signer.verify(&token, "t:creature", 42),
+            Err(CursorError::QueryMismatch)
+        );
+        assert_eq!(
+            signer.verify(&token, "t:instant", 43),
+            Err(CursorError::Stale)
+        );
+    }
+}
@@ -1,7 +1,12 @@
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use utoipa::ToSchema;
+
+use crate::config::Settings;
 
 /// Query complexity limits to prevent abuse
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct QueryLimits {
     /// Maximum query string length (characters)
     pub max_query_length: usize,
@@ -15,8 +20,11 @@ pub struct QueryLimits {
     /// Maximum number of results to return
     pub max_results: i64,
 
-    /// Query execution timeout (seconds)
+    /// Longest a search may run, including its Scryfall fallback (seconds)
     pub query_timeout_seconds: u64,
+
+    /// Maximum length of a regex pattern (`o:/pattern/`), in characters
+    pub max_regex_length: usize,
 }
 
 impl Default for QueryLimits {
@@ -27,34 +35,197 @@ impl Default for QueryLimits {
             max_or_clauses: 10,
             max_results: 1000,
             query_timeout_seconds: 30,
+            max_regex_length: 100,
         }
     }
 }
 
 impl QueryLimits {
-    /// Create limits from environment variables
-    pub fn from_env() -> Self {
+    /// Create limits from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
         Self {
-            max_query_length: std::env::var("QUERY_MAX_LENGTH")
+            max_query_length: settings
+                .var("QUERY_MAX_LENGTH")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(1000),
-            max_nesting_depth: std::env::var("QUERY_MAX_NESTING")
+            max_nesting_depth: settings
+                .var("QUERY_MAX_NESTING")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(5),
-            max_or_clauses: std::env::var("QUERY_MAX_OR_CLAUSES")
+            max_or_clauses: settings
+                .var("QUERY_MAX_OR_CLAUSES")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(10),
-            max_results: std::env::var("QUERY_MAX_RESULTS")
+            max_results: settings
+                .var("QUERY_MAX_RESULTS")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(1000),
-            query_timeout_seconds: std::env::var("QUERY_TIMEOUT_SECONDS")
+            query_timeout_seconds: settings
+                .var("QUERY_TIMEOUT_SECONDS")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(30),
+            max_regex_length: settings
+                .var("QUERY_MAX_REGEX_LENGTH")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
+        }
+    }
+
+    /// Check that every limit is positive
+    pub fn validate(&self) -> Result<()> {
+        let zero = [
+            ("max_query_length", self.max_query_length == 0),
+            ("max_nesting_depth", self.max_nesting_depth == 0),
+            ("max_or_clauses", self.max_or_clauses == 0),
+            ("max_results", self.max_results <= 0),
+            ("query_timeout_seconds", self.query_timeout_seconds == 0),
+            ("max_regex_length", self.max_regex_length == 0),
+        ];
+        match zero.iter().find(|(_, invalid)| *invalid) {
+            Some((name, _)) => Err(anyhow!("{} must be greater than zero", name)),
+            None => Ok(()),
+        }
+    }
+
+    /// These limits with every field set in `update` replaced
+    pub fn merged(&self, update: &QueryLimitsUpdate) -> Self {
+        Self {
+            max_query_length: update.max_query_length.unwrap_or(self.max_query_length),
+            max_nesting_depth: update.max_nesting_depth.unwrap_or(self.max_nesting_depth),
+            max_or_clauses: update.max_or_clauses.unwrap_or(self.max_or_clauses),
+            max_results: update.max_results.unwrap_or(self.max_results),
+            query_timeout_seconds: update
+                .query_timeout_seconds
+                .unwrap_or(self.query_timeout_seconds),
+            max_regex_length: update.max_regex_length.unwrap_or(self.max_regex_length),
+        }
+    }
+}
+
+/// Partial update of [`QueryLimits`]; omitted fields keep their current value
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct QueryLimitsUpdate {
+    pub max_query_length: Option<usize>,
+    pub max_nesting_depth: Option<usize>,
+    pub max_or_clauses: Option<usize>,
+    pub max_results: Option<i64>,
+    pub query_timeout_seconds: Option<u64>,
+    pub max_regex_length: Option<usize>,
+}
+
+/// Query limits that can be replaced at runtime (e.g. through the admin API).
+///
+/// Readers take a cheap snapshot with [`RuntimeQueryLimits::load`] and keep using it for
+/// the rest of the request, so a concurrent update never changes limits mid-validation.
+/// Clones share the same limits.
+#[derive(Debug, Clone)]
+pub struct RuntimeQueryLimits {
+    current: Arc<RwLock<Arc<QueryLimits>>>,
+}
+
+impl RuntimeQueryLimits {
+    pub fn new(limits: QueryLimits) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(limits))),
         }
     }
+
+    /// Snapshot of the current limits
+    pub fn load(&self) -> Arc<QueryLimits> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Replace the limits, returning the previous ones
+    pub fn store(&self, limits: QueryLimits) -> Arc<QueryLimits> {
+        std::mem::replace(&mut *self.current.write().unwrap(), Arc::new(limits))
+    }
+
+    /// Merge `update` into the current limits and store the result, holding the write
+    /// lock throughout so concurrent updates of different fields don't undo each other.
+    /// Returns the previous and new limits; invalid limits are rejected and not stored.
+    pub fn update(
+        &self,
+        update: &QueryLimitsUpdate,
+    ) -> Result<(Arc<QueryLimits>, Arc<QueryLimits>)> {
+        let mut current = self.current.write().unwrap();
+        let limits = current.merged(update);
+        limits.validate()?;
+        let limits = Arc::new(limits);
+        let previous = std::mem::replace(&mut *current, limits.clone());
+        Ok((previous, limits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_and_validate() {
+        let limits = QueryLimits::default();
+        let update = QueryLimitsUpdate {
+            max_query_length: Some(4000),
+            ..Default::default()
+        };
+        let merged = limits.merged(&update);
+        assert_eq!(merged.max_query_length, 4000);
+        assert_eq!(merged.max_nesting_depth, limits.max_nesting_depth);
+        assert!(merged.validate().is_ok());
+
+        let update = QueryLimitsUpdate {
+            max_nesting_depth: Some(0),
+            ..Default::default()
+        };
+        let err = limits.merged(&update).validate().unwrap_err();
+        assert!(err.to_string().contains("max_nesting_depth"));
+    }
+
+    #[test]
+    fn test_runtime_limits_shared_between_clones() {
+        let runtime = RuntimeQueryLimits::new(QueryLimits::default());
+        let clone = runtime.clone();
+        let previous = clone.store(QueryLimits {
+            max_query_length: 50,
+            ..QueryLimits::default()
+        });
+
+        assert_eq!(previous.max_query_length, 1000);
+        assert_eq!(runtime.load().max_query_length, 50);
+    }
+
+    #[test]
+    fn test_runtime_limits_update() {
+        let runtime = RuntimeQueryLimits::new(QueryLimits::default());
+        let (previous, current) = runtime
+            .update(&QueryLimitsUpdate {
+                max_results: Some(50),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(previous.max_results, 1000);
+        assert_eq!(current.max_results, 50);
+
+        // A second update keeps the first one's field
+        runtime
+            .update(&QueryLimitsUpdate {
+                max_or_clauses: Some(3),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(runtime.load().max_results, 50);
+        assert_eq!(runtime.load().max_or_clauses, 3);
+
+        let invalid = QueryLimitsUpdate {
+            max_results: Some(0),
+            ..Default::default()
+        };
+        assert!(runtime.update(&invalid).is_err());
+        assert_eq!(runtime.load().max_results, 50);
+    }
 }
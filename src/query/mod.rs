@@ -1,8 +1,16 @@
+pub mod colors;
+pub mod cursor;
 pub mod executor;
 pub mod limits;
+pub mod order;
 pub mod parser;
+pub mod pattern;
+pub mod unique;
 pub mod validator;
 
-pub use limits::QueryLimits;
-pub use parser::QueryParser;
+pub use cursor::{CursorError, CursorSigner, SearchCursor};
+pub use limits::{QueryLimits, QueryLimitsUpdate, RuntimeQueryLimits};
+pub use order::{SearchOrder, SortDirection, SortOrder};
+pub use parser::{ParseError, QueryParser};
+pub use unique::UniqueMode;
 pub use validator::QueryValidator;
@@ -0,0 +1,263 @@
+//! Search result ordering, Scryfall's `order` and `dir` parameters.
+
+use std::cmp::Ordering;
+
+use crate::models::card::Card;
+
+/// Field search results are sorted by. Ties (and cards without a value, which always
+/// sort last) fall back to `(name, id)` order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Card name
+    #[default]
+    Name,
+    /// Release date of the printing
+    Released,
+    /// Price in USD
+    Usd,
+    /// Price in EUR
+    Eur,
+    /// Price in MTGO tickets
+    Tix,
+    /// EDHREC popularity rank
+    Edhrec,
+    /// Penny Dreadful popularity rank
+    Penny,
+}
+
+impl SortOrder {
+    /// Parse an `order` parameter value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "name" => Some(SortOrder::Name),
+            "released" => Some(SortOrder::Released),
+            "usd" => Some(SortOrder::Usd),
+            "eur" => Some(SortOrder::Eur),
+            "tix" => Some(SortOrder::Tix),
+            "edhrec" => Some(SortOrder::Edhrec),
+            "penny" => Some(SortOrder::Penny),
+            _ => None,
+        }
+    }
+
+    /// Name used by Scryfall's `order` parameter and `order:` keyword
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Name => "name",
+            SortOrder::Released => "released",
+            SortOrder::Usd => "usd",
+            SortOrder::Eur => "eur",
+            SortOrder::Tix => "tix",
+            SortOrder::Edhrec => "edhrec",
+            SortOrder::Penny => "penny",
+        }
+    }
+
+    /// Column holding the sort key; `None` for name order, which is the tie-breaker itself
+    fn column(&self) -> Option<&'static str> {
+        match self {
+            SortOrder::Name => None,
+            SortOrder::Released => Some("released_at"),
+            SortOrder::Usd => Some("price_usd"),
+            SortOrder::Eur => Some("price_eur"),
+            SortOrder::Tix => Some("price_tix"),
+            SortOrder::Edhrec => Some("edhrec_rank"),
+            SortOrder::Penny => Some("penny_rank"),
+        }
+    }
+
+    /// Direction of `dir=auto`, as on Scryfall: newest and most expensive first, names
+    /// and ranks (1 = most popular) ascending
+    fn descending_by_default(&self) -> bool {
+        matches!(
+            self,
+            SortOrder::Released | SortOrder::Usd | SortOrder::Eur | SortOrder::Tix
+        )
+    }
+}
+
+/// Sort direction, Scryfall's `dir` parameter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortDirection {
+    /// The natural direction of the order
+    #[default]
+    Auto,
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    /// Parse a `dir` parameter value (`auto`, `asc` or `desc`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "auto" => Some(SortDirection::Auto),
+            "asc" => Some(SortDirection::Asc),
+            "desc" => Some(SortDirection::Desc),
+            _ => None,
+        }
+    }
+
+    /// Name used by Scryfall's `dir` parameter and `direction:` keyword
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortDirection::Auto => "auto",
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        }
+    }
+}
+
+/// How a search's results are ordered. The default, ascending by name, is the only
+/// order keyset cursors can resume.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOrder {
+    pub order: SortOrder,
+    pub dir: SortDirection,
+}
+
+impl SearchOrder {
+    pub fn new(order: SortOrder, dir: SortDirection) -> Self {
+        Self { order, dir }
+    }
+
+    fn descending(&self) -> bool {
+        match self.dir {
+            SortDirection::Auto => self.order.descending_by_default(),
+            SortDirection::Asc => false,
+            SortDirection::Desc => true,
+        }
+    }
+
+    /// Whether results are in `(name, id)` order, which `SearchCursor` relies on
+    pub fn supports_cursor(&self) -> bool {
+        self.order == SortOrder::Name && !self.descending()
+    }
+
+    /// `ORDER BY` expression, valid on both backends
+    pub fn order_by(&self) -> String {
+        let direction = if self.descending() { "DESC" } else { "ASC" };
+        match self.order.column() {
+            None if self.descending() => "name DESC, id DESC".to_string(),
+            None => "name, id".to_string(),
+            Some(column) => format!("{} {} NULLS LAST, name, id", column, direction),
+        }
+    }
+
+    /// Compare two cards the way `order_by` sorts them, for results sorted in memory
+    pub fn compare(&self, a: &Card, b: &Card) -> Ordering {
+        let by_name = || (&a.name, a.id.to_string()).cmp(&(&b.name, b.id.to_string()));
+        let key = match self.order {
+            SortOrder::Name => {
+                let ordering = by_name();
+                return if self.descending() {
+                    ordering.reverse()
+                } else {
+                    ordering
+                };
+            }
+            SortOrder::Released => compare_present(a.released_at, b.released_at),
+            SortOrder::Usd => compare_present(a.price_usd, b.price_usd),
+            SortOrder::Eur => compare_present(a.price_eur, b.price_eur),
+            SortOrder::Tix => compare_present(a.price_tix, b.price_tix),
+            SortOrder::Edhrec => compare_present(a.edhrec_rank, b.edhrec_rank),
+            SortOrder::Penny => compare_present(a.penny_rank, b.penny_rank),
+        };
+        let key = match key {
+            // Missing values sort last in either direction
+            KeyOrdering::Values(ordering) if self.descending() => ordering.reverse(),
+            KeyOrdering::Values(ordering) | KeyOrdering::Missing(ordering) => ordering,
+        };
+        key.then_with(by_name)
+    }
+
+    /// Scryfall keywords requesting this order, appended to searches sent upstream
+    pub fn upstream_keywords(&self) -> Option<String> {
+        (*self != Self::default()).then(|| {
+            format!(
+                "order:{} direction:{}",
+                self.order.as_str(),
+                if self.descending() { "desc" } else { "asc" }
+            )
+        })
+    }
+}
+
+/// Comparison of two optional sort keys: either both present, or by which one is missing
+enum KeyOrdering {
+    Values(Ordering),
+    Missing(Ordering),
+}
+
+fn compare_present<T: PartialOrd>(a: Option<T>, b: Option<T>) -> KeyOrdering {
+    match (a, b) {
+        (Some(a), Some(b)) => KeyOrdering::Values(a.partial_cmp(&b).unwrap_or(Ordering::Equal)),
+        (Some(_), None) => KeyOrdering::Missing(Ordering::Less),
+        (None, Some(_)) => KeyOrdering::Missing(Ordering::Greater),
+        (None, None) => KeyOrdering::Missing(Ordering::Equal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(name: &str, edhrec_rank: Option<i64>, usd: Option<&str>) -> Card {
+        Card::from_scryfall_json(serde_json::json!({
+            "id": uuid::Uuid::new_v4(),
+            "name": name,
+            "edhrec_rank": edhrec_rank,
+            "prices": {"usd": usd},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_sort_order() {
+        assert_eq!(SortOrder::parse("EDHREC"), Some(SortOrder::Edhrec));
+        assert_eq!(SortOrder::parse("usd"), Some(SortOrder::Usd));
+        assert_eq!(SortOrder::parse("popularity"), None);
+        assert_eq!(SortDirection::parse("desc"), Some(SortDirection::Desc));
+        assert_eq!(SortDirection::parse("down"), None);
+    }
+
+    #[test]
+    fn test_order_by() {
+        assert_eq!(SearchOrder::default().order_by(), "name, id");
+        assert!(SearchOrder::default().supports_cursor());
+        assert_eq!(
+            SearchOrder::new(SortOrder::Edhrec, SortDirection::Auto).order_by(),
+            "edhrec_rank ASC NULLS LAST, name, id"
+        );
+        assert_eq!(
+            SearchOrder::new(SortOrder::Usd, SortDirection::Auto).order_by(),
+            "price_usd DESC NULLS LAST, name, id"
+        );
+        let released = SearchOrder::new(SortOrder::Released, SortDirection::Asc);
+        assert_eq!(released.order_by(), "released_at ASC NULLS LAST, name, id");
+        assert!(!released.supports_cursor());
+        assert_eq!(
+            released.upstream_keywords().as_deref(),
+            Some("order:released direction:asc")
+        );
+        assert!(SearchOrder::default().upstream_keywords().is_none());
+    }
+
+    #[test]
+    fn test_compare_puts_missing_values_last() {
+        let mut cards = vec![
+            card("Unranked", None, None),
+            card("Sol Ring", Some(1), Some("1.50")),
+            card("Arcane Signet", Some(2), Some("0.50")),
+        ];
+
+        let edhrec = SearchOrder::new(SortOrder::Edhrec, SortDirection::Auto);
+        cards.sort_by(|a, b| edhrec.compare(a, b));
+        let names: Vec<_> = cards.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Sol Ring", "Arcane Signet", "Unranked"]);
+
+        let usd = SearchOrder::new(SortOrder::Usd, SortDirection::Asc);
+        cards.sort_by(|a, b| usd.compare(a, b));
+        let names: Vec<_> = cards.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Arcane Signet", "Sol Ring", "Unranked"]);
+    }
+}
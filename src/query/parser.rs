@@ -1,6 +1,26 @@
 use anyhow::Result;
+use serde::Serialize;
 use std::fmt;
 
+/// Fields that also accept Scryfall's comparison syntax without a colon (`pow>=3`, `tou=*`,
+/// `c>=uw`)
+const COMPARISON_FIELDS: &[&str] = &[
+    "c",
+    "color",
+    "id",
+    "identity",
+    "cmc",
+    "power",
+    "pow",
+    "toughness",
+    "tou",
+    "loyalty",
+    "loy",
+    "usd",
+    "eur",
+    "tix",
+];
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum QueryNode {
     And(Vec<QueryNode>),
@@ -43,51 +63,125 @@ impl fmt::Display for Operator {
     }
 }
 
+/// Query parse error carrying the location of the offending input, so clients can
+/// highlight it. Returned (wrapped in `anyhow::Error`) by `QueryParser::parse`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseError {
+    pub message: String,
+    /// Character offset into the query where the error was detected
+    pub position: usize,
+    /// Length of the offending token in characters (0 at end of query)
+    pub length: usize,
+    /// The offending token, or `None` at end of query
+    pub token: Option<String>,
+    /// What the parser expected at `position`
+    pub expected: Vec<String>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A token and the character offset it starts at
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    text: String,
+    offset: usize,
+}
+
 pub struct QueryParser {
-    tokens: Vec<String>,
+    tokens: Vec<Token>,
     position: usize,
+    /// Character length of the query (offset reported for end-of-query errors)
+    end: usize,
 }
 
 impl QueryParser {
     pub fn new(query: &str) -> Self {
-        let tokens = Self::tokenize(query);
+        let tokens = Self::tokenize_with_offsets(query);
         Self {
             tokens,
             position: 0,
+            end: query.chars().count(),
         }
     }
 
-    /// Parse a Scryfall query into an AST
+    /// Parse a Scryfall query into an AST.
+    /// Errors are `ParseError`s and can be recovered with `downcast_ref`.
     pub fn parse(query: &str) -> Result<QueryNode> {
         let mut parser = Self::new(query);
-        parser.parse_expression()
+        let node = parser.parse_expression()?;
+
+        // Anything left over is a stray closing parenthesis
+        if parser.current().is_some() {
+            return Err(parser.error_at_current("Unexpected token", &["end of query"]).into());
+        }
+
+        Ok(node)
     }
 
     /// Tokenize the query string
+    #[cfg(test)]
     fn tokenize(query: &str) -> Vec<String> {
+        Self::tokenize_with_offsets(query)
+            .into_iter()
+            .map(|t| t.text)
+            .collect()
+    }
+
+    fn tokenize_with_offsets(query: &str) -> Vec<Token> {
         let mut tokens = Vec::new();
         let mut current = String::new();
+        let mut start = 0;
         let mut in_quotes = false;
-        let chars = query.chars().peekable();
 
-        for ch in chars {
+        let flush = |current: &mut String, start: usize, tokens: &mut Vec<Token>| {
+            if !current.is_empty() {
+                tokens.push(Token {
+                    text: std::mem::take(current),
+                    offset: start,
+                });
+            }
+        };
+
+        // Inside a `field:/pattern/` regex; spaces and parentheses belong to the pattern
+        let mut in_regex = false;
+
+        for (i, ch) in query.chars().enumerate() {
+            if current.is_empty() {
+                start = i;
+            }
+            if in_regex {
+                in_regex = ch != '/' || current.ends_with('\\');
+                current.push(ch);
+                continue;
+            }
             match ch {
+                '/' if !in_quotes && current.ends_with(':') => {
+                    in_regex = true;
+                    current.push(ch);
+                }
+                // An escaped quote inside a quoted phrase is part of the phrase
+                '"' if in_quotes && current.ends_with('\\') => {
+                    current.push(ch);
+                }
                 '"' => {
                     in_quotes = !in_quotes;
                     current.push(ch);
                 }
                 ' ' if !in_quotes => {
-                    if !current.is_empty() {
-                        tokens.push(current.clone());
-                        current.clear();
-                    }
+                    flush(&mut current, start, &mut tokens);
                 }
                 '(' | ')' if !in_quotes => {
-                    if !current.is_empty() {
-                        tokens.push(current.clone());
-                        current.clear();
-                    }
-                    tokens.push(ch.to_string());
+                    flush(&mut current, start, &mut tokens);
+                    tokens.push(Token {
+                        text: ch.to_string(),
+                        offset: i,
+                    });
                 }
                 _ => {
                     current.push(ch);
@@ -95,21 +189,35 @@ impl QueryParser {
             }
         }
 
-        if !current.is_empty() {
-            tokens.push(current);
-        }
+        flush(&mut current, start, &mut tokens);
 
         tokens
     }
 
     fn current(&self) -> Option<&String> {
-        self.tokens.get(self.position)
+        self.tokens.get(self.position).map(|t| &t.text)
     }
 
     fn advance(&mut self) {
         self.position += 1;
     }
 
+    /// Build an error pointing at the current token (or the end of the query)
+    fn error_at_current(&self, message: &str, expected: &[&str]) -> ParseError {
+        let token = self.tokens.get(self.position);
+        let message = match token {
+            Some(t) => format!("{} '{}'", message, t.text),
+            None => message.to_string(),
+        };
+        ParseError {
+            message,
+            position: token.map(|t| t.offset).unwrap_or(self.end),
+            length: token.map(|t| t.text.chars().count()).unwrap_or(0),
+            token: token.map(|t| t.text.clone()),
+            expected: expected.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
     fn parse_expression(&mut self) -> Result<QueryNode> {
         self.parse_or()
     }
@@ -162,16 +270,26 @@ impl QueryParser {
     }
 
     fn parse_term(&mut self) -> Result<QueryNode> {
-        let token = self
-            .current()
-            .ok_or_else(|| anyhow::anyhow!("Unexpected end of query"))?
-            .clone();
+        let token = match self.current() {
+            Some(token) => token.clone(),
+            None => {
+                return Err(self
+                    .error_at_current("Unexpected end of query", &["filter", "("])
+                    .into())
+            }
+        };
+
+        if token == ")" {
+            return Err(self.error_at_current("Unexpected token", &["filter", "("]).into());
+        }
 
         if token == "(" {
             self.advance();
             let expr = self.parse_expression()?;
             if self.current() == Some(&")".to_string()) {
                 self.advance();
+            } else {
+                return Err(self.error_at_current("Unclosed parenthesis", &[")"]).into());
             }
             return Ok(expr);
         }
@@ -186,32 +304,81 @@ impl QueryParser {
     }
 
     fn parse_filter(&mut self) -> Result<QueryNode> {
-        let token = self
-            .current()
-            .ok_or_else(|| anyhow::anyhow!("Expected filter"))?
-            .clone();
+        let token = match self.current() {
+            Some(token) => token.clone(),
+            None => return Err(self.error_at_current("Expected filter", &["filter"]).into()),
+        };
 
         self.advance();
 
-        // Parse field:value or field>=value patterns
-        if let Some((field, rest)) = token.split_once(':') {
-            let (operator, value) = self.parse_operator_and_value(rest)?;
+        // A leading '-' negates the filter (e.g. -is:token, -!"Lightning Bolt")
+        let (body, negated) = match token.strip_prefix('-') {
+            Some(body) if !body.is_empty() => (body, true),
+            _ => (token.as_str(), false),
+        };
 
-            Ok(QueryNode::Filter(Filter {
-                field: self.normalize_field(field),
-                operator,
-                value: value.trim_matches('"').to_string(),
-            }))
+        let filter = QueryNode::Filter(self.filter_from_token(body)?);
+        if negated {
+            Ok(QueryNode::Not(Box::new(filter)))
         } else {
-            // Default to name search
-            Ok(QueryNode::Filter(Filter {
+            Ok(filter)
+        }
+    }
+
+    /// Filter for a single token: `!name` or `!"exact name"`, `field:value`,
+    /// `field:>=value` or `field>=value`, and anything else (including quoted phrases
+    /// holding colons or comparison characters) a name search
+    fn filter_from_token(&self, token: &str) -> Result<Filter> {
+        if let Some(name) = token.strip_prefix('!').filter(|name| !name.is_empty()) {
+            return Ok(Filter {
+                field: "name".to_string(),
+                operator: Operator::Equal,
+                value: Self::unquote(name),
+            });
+        }
+
+        let split = if token.starts_with('"') {
+            None
+        } else {
+            token
+                .split_once(':')
+                .or_else(|| Self::split_comparison(token))
+        };
+        match split {
+            Some((field, rest)) => {
+                let (operator, value) = self.parse_operator_and_value(rest)?;
+                Ok(Filter {
+                    field: self.normalize_field(field),
+                    operator,
+                    value: Self::unquote(&value),
+                })
+            }
+            None => Ok(Filter {
                 field: "name".to_string(),
                 operator: Operator::Contains,
-                value: token.trim_matches('"').to_string(),
-            }))
+                value: Self::unquote(token),
+            }),
         }
     }
 
+    /// Value of a possibly quoted token, without the surrounding quotes and with `\"`
+    /// unescaped
+    fn unquote(value: &str) -> String {
+        let value = value.strip_prefix('"').unwrap_or(value);
+        let value = value.strip_suffix('"').unwrap_or(value);
+        value.replace("\\\"", "\"")
+    }
+
+    /// Split `pow>=3` into `("pow", ">=3")` for fields in `COMPARISON_FIELDS`
+    fn split_comparison(token: &str) -> Option<(&str, &str)> {
+        let index = token.find(['<', '>', '=', '!'])?;
+        let field = &token[..index];
+        let name = field.strip_prefix('-').unwrap_or(field).to_lowercase();
+        COMPARISON_FIELDS
+            .contains(&name.as_str())
+            .then(|| token.split_at(index))
+    }
+
     fn parse_operator_and_value(&self, s: &str) -> Result<(Operator, String)> {
         if let Some(rest) = s.strip_prefix(">=") {
             Ok((Operator::GreaterThanOrEqual, rest.to_string()))
@@ -243,6 +410,7 @@ impl QueryParser {
             "pow" => "power",
             "tou" => "toughness",
             "loy" => "loyalty",
+            "language" => "lang",
             _ => field,
         }
         .to_string()
@@ -306,6 +474,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tokenize_keeps_regex_together() {
+        let tokens = QueryParser::tokenize(r"o:/draw (a|two) cards?\/turn/ (c:u)");
+        assert_eq!(
+            tokens,
+            vec![r"o:/draw (a|two) cards?\/turn/", "(", "c:u", ")"]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_offsets() {
+        let tokens = QueryParser::tokenize_with_offsets("(c:red  or t:goblin)");
+        let offsets: Vec<usize> = tokens.iter().map(|t| t.offset).collect();
+        assert_eq!(offsets, vec![0, 1, 8, 11, 19]);
+    }
+
+    #[test]
+    fn test_parse_error_at_end_of_query() {
+        let err = QueryParser::parse("c:red or").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.position, 8);
+        assert_eq!(err.token, None);
+        assert_eq!(err.expected, vec!["filter", "("]);
+    }
+
+    #[test]
+    fn test_parse_error_unbalanced_parentheses() {
+        let err = QueryParser::parse("(c:red t:goblin").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.message, "Unclosed parenthesis");
+        assert_eq!(err.position, 15);
+        assert_eq!(err.expected, vec![")"]);
+
+        let err = QueryParser::parse("c:red ) t:goblin").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.position, 6);
+        assert_eq!(err.token.as_deref(), Some(")"));
+        assert_eq!(err.length, 1);
+    }
+
     #[test]
     fn test_parse_not() {
         let ast = QueryParser::parse("not c:red").unwrap();
@@ -320,4 +528,139 @@ mod tests {
             _ => panic!("Expected Not node"),
         }
     }
+
+    #[test]
+    fn test_parse_comparison_without_colon() {
+        let ast = QueryParser::parse("pow>=3 tou=*").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::And(vec![
+                QueryNode::Filter(Filter {
+                    field: "power".to_string(),
+                    operator: Operator::GreaterThanOrEqual,
+                    value: "3".to_string(),
+                }),
+                QueryNode::Filter(Filter {
+                    field: "toughness".to_string(),
+                    operator: Operator::Equal,
+                    value: "*".to_string(),
+                }),
+            ])
+        );
+
+        let ast = QueryParser::parse("c>=uw").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::Filter(Filter {
+                field: "color".to_string(),
+                operator: Operator::GreaterThanOrEqual,
+                value: "uw".to_string(),
+            })
+        );
+
+        // Other words containing comparison characters stay name searches
+        let ast = QueryParser::parse("a=b").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::Filter(Filter {
+                field: "name".to_string(),
+                operator: Operator::Contains,
+                value: "a=b".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_exact_name() {
+        let ast = QueryParser::parse(r#"!"Lightning Bolt" c:r"#).unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::And(vec![
+                QueryNode::Filter(Filter {
+                    field: "name".to_string(),
+                    operator: Operator::Equal,
+                    value: "Lightning Bolt".to_string(),
+                }),
+                QueryNode::Filter(Filter {
+                    field: "color".to_string(),
+                    operator: Operator::Contains,
+                    value: "r".to_string(),
+                }),
+            ])
+        );
+
+        let ast = QueryParser::parse("!Fire").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::Filter(Filter {
+                field: "name".to_string(),
+                operator: Operator::Equal,
+                value: "Fire".to_string(),
+            })
+        );
+
+        let ast = QueryParser::parse(r#"-!"Lightning Bolt""#).unwrap();
+        assert!(matches!(ast, QueryNode::Not(_)));
+    }
+
+    #[test]
+    fn test_parse_quoted_names_with_special_characters() {
+        // Decklist names with "//", commas, colons, parentheses and exclamation marks
+        for name in [
+            "Fire // Ice",
+            "Jace, the Mind Sculptor",
+            "Circle of Protection: Red",
+            "Borrowed Time (Alt Art)",
+            "Ach! Hans, Run!",
+            "Who // What // When // Where // Why",
+        ] {
+            let exact = QueryParser::parse(&format!("!\"{}\"", name)).unwrap();
+            assert_eq!(
+                exact,
+                QueryNode::Filter(Filter {
+                    field: "name".to_string(),
+                    operator: Operator::Equal,
+                    value: name.to_string(),
+                })
+            );
+
+            let phrase = QueryParser::parse(&format!("(\"{}\" or t:instant)", name)).unwrap();
+            match phrase {
+                QueryNode::Or(nodes) => assert_eq!(
+                    nodes[0],
+                    QueryNode::Filter(Filter {
+                        field: "name".to_string(),
+                        operator: Operator::Contains,
+                        value: name.to_string(),
+                    })
+                ),
+                other => panic!("Expected Or node, got {:?}", other),
+            }
+        }
+
+        let ast = QueryParser::parse(r#"name:"Kongming, \"Sleeping Dragon\"""#).unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::Filter(Filter {
+                field: "name".to_string(),
+                operator: Operator::Contains,
+                value: r#"Kongming, "Sleeping Dragon""#.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_negated_filter() {
+        let ast = QueryParser::parse("-is:token").unwrap();
+        match ast {
+            QueryNode::Not(inner) => match *inner {
+                QueryNode::Filter(filter) => {
+                    assert_eq!(filter.field, "is");
+                    assert_eq!(filter.value, "token");
+                }
+                _ => panic!("Expected Filter node inside Not"),
+            },
+            _ => panic!("Expected Not node"),
+        }
+    }
 }
@@ -0,0 +1,47 @@
+//! Regex patterns from `field:/pattern/` searches.
+//!
+//! Patterns are compiled with the `regex` crate, which matches in linear time and has no
+//! backreferences or look-around, under size and nesting limits. Every pattern must
+//! compile here, which keeps the SQLite `regexp` function (evaluated with this crate)
+//! cheap. PostgreSQL evaluates accepted patterns with its own `~*` engine, which gives no
+//! such guarantee, so searches with a pattern run there under a statement timeout
+//! (`REGEX_QUERY_TIMEOUT`).
+
+use anyhow::{anyhow, Result};
+use regex::{Regex, RegexBuilder};
+
+/// Upper bound on the compiled program size, in bytes
+const SIZE_LIMIT: usize = 1 << 20;
+
+/// Upper bound on group/repetition nesting
+const NEST_LIMIT: u32 = 16;
+
+/// Compile a search pattern. Matching is case-insensitive, as on Scryfall.
+pub fn compile_pattern(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(SIZE_LIMIT)
+        .dfa_size_limit(SIZE_LIMIT)
+        .nest_limit(NEST_LIMIT)
+        .build()
+        .map_err(|e| anyhow!("Invalid regex /{}/: {}", pattern, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_pattern() {
+        let re = compile_pattern("^draw.*cards?$").unwrap();
+        assert!(re.is_match("Draw two cards"));
+
+        assert!(compile_pattern("(unclosed").is_err());
+        // Backreferences and look-around can backtrack exponentially
+        assert!(compile_pattern(r"(a)\1").is_err());
+        assert!(compile_pattern("(?=a)").is_err());
+        let nested = format!("{}a{}", "(".repeat(20), ")".repeat(20));
+        assert!(compile_pattern(&nested).is_err());
+        assert!(compile_pattern("a{1000}{1000}").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Search uniqueness modes, Scryfall's `unique` parameter: which printings of a card a
+//! search returns.
+
+use crate::config::Settings;
+use crate::db::SqlDialect;
+
+/// How search results are deduplicated. Like Scryfall, searches return one printing per
+/// card unless asked otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UniqueMode {
+    /// One printing per card (oracle id)
+    #[default]
+    Cards,
+    /// Every matching printing
+    Prints,
+    /// One printing per unique artwork (illustration id)
+    Art,
+}
+
+impl UniqueMode {
+    /// Parse a `unique` parameter value (`cards`, `prints` or `art`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "cards" => Some(UniqueMode::Cards),
+            "prints" => Some(UniqueMode::Prints),
+            "art" => Some(UniqueMode::Art),
+            _ => None,
+        }
+    }
+
+    /// Mode of searches that don't ask for one, from `SEARCH_DEFAULT_UNIQUE`. `Cards` if
+    /// unset or unrecognised; `prints` opts out of deduplication.
+    pub fn from_settings(settings: &Settings) -> Self {
+        settings
+            .var("SEARCH_DEFAULT_UNIQUE")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+
+    /// Name used by Scryfall's `unique:` keyword
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UniqueMode::Cards => "cards",
+            UniqueMode::Prints => "prints",
+            UniqueMode::Art => "art",
+        }
+    }
+
+    /// SQL expression the printings of one group share, or `None` when every printing is
+    /// its own group. Printings without an oracle or illustration id (some reversible
+    /// cards, art-less tokens) fall back to their own id, so they are never merged.
+    pub fn group_key(&self, dialect: SqlDialect) -> Option<&'static str> {
+        let sqlite = dialect == SqlDialect::Sqlite;
+        match (self, sqlite) {
+            (UniqueMode::Prints, _) => None,
+            (UniqueMode::Cards, false) => Some("COALESCE(oracle_id::text, id::text)"),
+            (UniqueMode::Cards, true) => Some("COALESCE(oracle_id, id)"),
+            // Double-faced cards only have illustration ids on their faces
+            (UniqueMode::Art, false) => Some(
+                "COALESCE(raw_json->>'illustration_id', raw_json->'card_faces'->0->>'illustration_id', id::text)",
+            ),
+            (UniqueMode::Art, true) => Some(
+                "COALESCE(json_extract(raw_json, '$.illustration_id'), json_extract(raw_json, '$.card_faces[0].illustration_id'), id)",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_unique_mode() {
+        assert_eq!(UniqueMode::parse("cards"), Some(UniqueMode::Cards));
+        assert_eq!(UniqueMode::parse("Prints"), Some(UniqueMode::Prints));
+        assert_eq!(UniqueMode::parse("art"), Some(UniqueMode::Art));
+        assert_eq!(UniqueMode::parse("artwork"), None);
+        assert_eq!(UniqueMode::default(), UniqueMode::Cards);
+        assert!(UniqueMode::Prints.group_key(SqlDialect::Postgres).is_none());
+    }
+}
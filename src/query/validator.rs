@@ -1,8 +1,12 @@
 use anyhow::{anyhow, Result};
 use std::collections::HashSet;
 
-use super::limits::QueryLimits;
+use std::sync::Arc;
+
+use super::colors::parse_colors;
+use super::limits::{QueryLimits, RuntimeQueryLimits};
 use super::parser::{Operator, QueryNode};
+use super::pattern::compile_pattern;
 
 /// Valid field names for card queries
 const VALID_FIELDS: &[&str] = &[
@@ -11,6 +15,7 @@ const VALID_FIELDS: &[&str] = &[
     "oracle",
     "color",
     "colors",
+    "color_identity",
     "cmc",
     "mana",
     "power",
@@ -23,16 +28,27 @@ const VALID_FIELDS: &[&str] = &[
     "frame",
     "layout",
     "loyalty",
+    "usd",
+    "eur",
+    "tix",
+    "lang",
+    "is",
 ];
 
+/// Values accepted by the `is:` filter
+const IS_VALUES: &[&str] = &["token", "emblem", "vanilla"];
+
 /// Fields that support numeric operators (>, <, >=, <=)
-const NUMERIC_FIELDS: &[&str] = &["cmc", "power", "toughness", "loyalty"];
+const NUMERIC_FIELDS: &[&str] = &["cmc", "power", "toughness", "loyalty", "usd", "eur", "tix"];
+
+/// Fields that support regex searches (`o:/pattern/`)
+const REGEX_FIELDS: &[&str] = &["name", "type", "oracle"];
 
-/// Valid color codes
-const VALID_COLORS: &[char] = &['w', 'u', 'b', 'r', 'g', 'c'];
+/// Fields compared as color sets, which also take comparison operators (`c>=uw`)
+const COLOR_FIELDS: &[&str] = &["color", "colors", "color_identity"];
 
 pub struct QueryValidator {
-    limits: QueryLimits,
+    limits: RuntimeQueryLimits,
     valid_fields: HashSet<String>,
     numeric_fields: HashSet<String>,
 }
@@ -40,32 +56,43 @@ pub struct QueryValidator {
 impl QueryValidator {
     pub fn new(limits: QueryLimits) -> Self {
         Self {
-            limits,
+            limits: RuntimeQueryLimits::new(limits),
             valid_fields: VALID_FIELDS.iter().map(|s| s.to_string()).collect(),
             numeric_fields: NUMERIC_FIELDS.iter().map(|s| s.to_string()).collect(),
         }
     }
 
-    /// Get reference to query limits
-    pub fn limits(&self) -> &QueryLimits {
+    /// Snapshot of the current query limits
+    pub fn limits(&self) -> Arc<QueryLimits> {
+        self.limits.load()
+    }
+
+    /// Handle for replacing the query limits at runtime
+    pub fn runtime_limits(&self) -> &RuntimeQueryLimits {
         &self.limits
     }
 
     /// Validate query string before parsing
     pub fn validate_query_string(&self, query: &str) -> Result<()> {
+        let limits = self.limits.load();
+
         // Check length
-        if query.len() > self.limits.max_query_length {
+        if query.len() > limits.max_query_length {
             return Err(anyhow!(
                 "Query too long: maximum {} characters allowed, got {}",
-                self.limits.max_query_length,
+                limits.max_query_length,
                 query.len()
             ));
         }
 
-        // Check for balanced parentheses
+        // Check for balanced parentheses, ignoring those inside quoted phrases
         let mut paren_count = 0;
+        let mut in_quotes = false;
+        let mut escaped = false;
         for ch in query.chars() {
             match ch {
+                '"' if !escaped => in_quotes = !in_quotes,
+                _ if in_quotes => {}
                 '(' => paren_count += 1,
                 ')' => {
                     paren_count -= 1;
@@ -77,6 +104,7 @@ impl QueryValidator {
                 }
                 _ => {}
             }
+            escaped = in_quotes && ch == '\\' && !escaped;
         }
         if paren_count != 0 {
             return Err(anyhow!(
@@ -90,45 +118,50 @@ impl QueryValidator {
 
     /// Validate parsed query AST
     pub fn validate_ast(&self, node: &QueryNode) -> Result<()> {
+        let limits = self.limits.load();
+
         // Check nesting depth
         let depth = self.calculate_depth(node);
-        if depth > self.limits.max_nesting_depth {
+        if depth > limits.max_nesting_depth {
             return Err(anyhow!(
                 "Query too complex: maximum nesting depth is {}, got {}",
-                self.limits.max_nesting_depth,
+                limits.max_nesting_depth,
                 depth
             ));
         }
 
         // Check OR clause count
         let or_count = self.count_or_clauses(node);
-        if or_count > self.limits.max_or_clauses {
+        if or_count > limits.max_or_clauses {
             return Err(anyhow!(
                 "Query too complex: maximum {} OR clauses allowed, got {}",
-                self.limits.max_or_clauses,
+                limits.max_or_clauses,
                 or_count
             ));
         }
 
         // Validate filters
-        self.validate_node(node)?;
+        self.validate_node(node, &limits)?;
 
         Ok(())
     }
 
     /// Recursively validate query node
-    fn validate_node(&self, node: &QueryNode) -> Result<()> {
+    fn validate_node(&self, node: &QueryNode, limits: &QueryLimits) -> Result<()> {
         match node {
             QueryNode::And(children) | QueryNode::Or(children) => {
                 for child in children {
-                    self.validate_node(child)?;
+                    self.validate_node(child, limits)?;
                 }
             }
             QueryNode::Not(child) => {
-                self.validate_node(child)?;
+                self.validate_node(child, limits)?;
             }
             QueryNode::Filter(filter) => {
                 self.validate_filter(filter)?;
+                if filter.operator == Operator::Regex {
+                    self.validate_regex(filter, limits)?;
+                }
             }
         }
         Ok(())
@@ -148,7 +181,7 @@ impl QueryValidator {
         }
 
         // Check if operator is valid for this field
-        if !self.numeric_fields.contains(&field) {
+        if !self.numeric_fields.contains(&field) && !COLOR_FIELDS.contains(&field.as_str()) {
             match filter.operator {
                 Operator::GreaterThan
                 | Operator::LessThan
@@ -165,23 +198,49 @@ impl QueryValidator {
             }
         }
 
-        // Validate color codes
-        if field == "color" || field == "colors" {
-            for ch in filter.value.chars() {
-                if !VALID_COLORS.contains(&ch.to_lowercase().next().unwrap_or(' ')) {
-                    return Err(anyhow!(
-                        "Invalid color code '{}' in value '{}': valid colors are {}",
-                        ch,
-                        filter.value,
-                        VALID_COLORS.iter().collect::<String>()
-                    ));
-                }
-            }
+        if field == "is" && !IS_VALUES.contains(&filter.value.to_lowercase().as_str()) {
+            return Err(anyhow!(
+                "Invalid is: value '{}': expected one of [{}]",
+                filter.value,
+                IS_VALUES.join(", ")
+            ));
+        }
+
+        // Validate color values
+        if COLOR_FIELDS.contains(&field.as_str()) && parse_colors(&filter.value).is_none() {
+            return Err(anyhow!(
+                "Invalid color '{}': expected color letters (wubrg, or c for colorless), a color name or a guild, shard or wedge name",
+                filter.value
+            ));
         }
 
         Ok(())
     }
 
+    /// Check that a regex filter targets a text field and that its pattern is short and
+    /// simple enough to run against every card
+    fn validate_regex(&self, filter: &super::parser::Filter, limits: &QueryLimits) -> Result<()> {
+        if !REGEX_FIELDS.contains(&filter.field.to_lowercase().as_str()) {
+            return Err(anyhow!(
+                "Regex not supported for field '{}': use one of [{}]",
+                filter.field,
+                REGEX_FIELDS.join(", ")
+            ));
+        }
+
+        let length = filter.value.chars().count();
+        if length > limits.max_regex_length {
+            return Err(anyhow!(
+                "Regex too long: maximum {} characters allowed, got {}",
+                limits.max_regex_length,
+                length
+            ));
+        }
+
+        compile_pattern(&filter.value)?;
+        Ok(())
+    }
+
     /// Calculate maximum nesting depth
     fn calculate_depth(&self, node: &QueryNode) -> usize {
         match node {
@@ -231,12 +290,30 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("Query too long"));
     }
 
+    #[test]
+    fn test_runtime_limit_update() {
+        let validator = QueryValidator::new(QueryLimits {
+            max_query_length: 10,
+            ..Default::default()
+        });
+        assert!(validator.validate_query_string("name:lightning").is_err());
+
+        validator.runtime_limits().store(QueryLimits::default());
+        assert!(validator.validate_query_string("name:lightning").is_ok());
+    }
+
     #[test]
     fn test_unbalanced_parentheses() {
         let validator = QueryValidator::new(QueryLimits::default());
         assert!(validator.validate_query_string("(name:sol").is_err());
         assert!(validator.validate_query_string("name:sol)").is_err());
         assert!(validator.validate_query_string("(name:sol)").is_ok());
+        assert!(validator
+            .validate_query_string(r#"!"Gisela, the Broken Blade (Alt)" or (c:w)"#)
+            .is_ok());
+        assert!(validator
+            .validate_query_string(r#"(name:"Kongming, \"Sleeping (Dragon\"")"#)
+            .is_ok());
     }
 
     #[test]
@@ -271,6 +348,31 @@ mod tests {
             .contains("not valid for text field"));
     }
 
+    #[test]
+    fn test_color_filters() {
+        let validator = QueryValidator::new(QueryLimits::default());
+        let filter = super::super::parser::Filter {
+            field: "color".to_string(),
+            operator: Operator::GreaterThanOrEqual,
+            value: "esper".to_string(),
+        };
+        assert!(validator.validate_filter(&filter).is_ok());
+
+        let identity = super::super::parser::Filter {
+            field: "color_identity".to_string(),
+            value: "wug".to_string(),
+            ..filter.clone()
+        };
+        assert!(validator.validate_filter(&identity).is_ok());
+
+        let invalid = super::super::parser::Filter {
+            value: "wx".to_string(),
+            ..filter
+        };
+        let result = validator.validate_filter(&invalid);
+        assert!(result.unwrap_err().to_string().contains("Invalid color"));
+    }
+
     #[test]
     fn test_valid_filter() {
         let validator = QueryValidator::new(QueryLimits::default());
@@ -281,4 +383,60 @@ mod tests {
         };
         assert!(validator.validate_filter(&filter).is_ok());
     }
+
+    #[test]
+    fn test_is_filter_values() {
+        let validator = QueryValidator::new(QueryLimits::default());
+        let filter = super::super::parser::Filter {
+            field: "is".to_string(),
+            operator: Operator::Contains,
+            value: "token".to_string(),
+        };
+        assert!(validator.validate_filter(&filter).is_ok());
+
+        let filter = super::super::parser::Filter {
+            value: "foil".to_string(),
+            ..filter
+        };
+        let result = validator.validate_filter(&filter);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid is: value"));
+    }
+
+    #[test]
+    fn test_regex_filters() {
+        let validator = QueryValidator::new(QueryLimits {
+            max_regex_length: 10,
+            ..Default::default()
+        });
+        let limits = validator.limits();
+        let filter = super::super::parser::Filter {
+            field: "oracle".to_string(),
+            operator: Operator::Regex,
+            value: "draw.*card".to_string(),
+        };
+        assert!(validator.validate_regex(&filter, &limits).is_ok());
+
+        let too_long = super::super::parser::Filter {
+            value: "draw.*cards?".to_string(),
+            ..filter.clone()
+        };
+        let result = validator.validate_regex(&too_long, &limits);
+        assert!(result.unwrap_err().to_string().contains("Regex too long"));
+
+        let invalid = super::super::parser::Filter {
+            value: "(draw".to_string(),
+            ..filter.clone()
+        };
+        assert!(validator.validate_regex(&invalid, &limits).is_err());
+
+        let wrong_field = super::super::parser::Filter {
+            field: "cmc".to_string(),
+            ..filter
+        };
+        let result = validator.validate_regex(&wrong_field, &limits);
+        assert!(result.unwrap_err().to_string().contains("not supported"));
+    }
 }
@@ -0,0 +1,284 @@
+//! Card text rendering: oracle text and mana costs as HTML or Markdown, with `{T}`,
+//! `{2}{U}` and the other symbols replaced by the images from Scryfall's symbology.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::card::Card;
+
+/// Output format for `/cards/{id}/rendered`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderFormat {
+    /// `<p>` per paragraph, symbols as `<img class="card-symbol">`
+    Html,
+    /// Blank line between paragraphs, symbols as inline images
+    Markdown,
+}
+
+impl RenderFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "html" => Some(RenderFormat::Html),
+            "markdown" | "md" => Some(RenderFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// A symbol from Scryfall's `/symbology`
+#[derive(Debug, Clone)]
+struct SymbolInfo {
+    svg_uri: Option<String>,
+    english: Option<String>,
+}
+
+/// Symbol lookup built from the `/symbology` response, keyed by the braced symbol (`{T}`)
+#[derive(Debug, Clone, Default)]
+pub struct Symbology {
+    symbols: HashMap<String, SymbolInfo>,
+}
+
+impl Symbology {
+    /// Read Scryfall's symbology list. Entries without a `symbol` are skipped.
+    pub fn from_scryfall(value: &Value) -> Self {
+        let symbols = value
+            .get("data")
+            .and_then(Value::as_array)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|entry| {
+                        let symbol = entry.get("symbol")?.as_str()?.to_string();
+                        let text = |key: &str| entry.get(key)?.as_str().map(str::to_string);
+                        Some((
+                            symbol,
+                            SymbolInfo {
+                                svg_uri: text("svg_uri"),
+                                english: text("english"),
+                            },
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { symbols }
+    }
+
+    fn get(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.symbols.get(symbol)
+    }
+}
+
+/// One face of a rendered card
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RenderedFace {
+    pub name: String,
+    /// Rendered mana cost
+    pub mana_cost: Option<String>,
+    pub type_line: Option<String>,
+    /// Rendered oracle text
+    pub oracle_text: Option<String>,
+}
+
+/// A card's text rendered for display
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RenderedCard {
+    pub id: Uuid,
+    pub name: String,
+    pub format: RenderFormat,
+    /// One entry for single-faced cards, one per face in printed order otherwise
+    pub faces: Vec<RenderedFace>,
+}
+
+/// Render every face of `card`
+pub fn render_card(card: &Card, format: RenderFormat, symbology: &Symbology) -> RenderedCard {
+    let render = |text: &Option<String>| {
+        text.as_deref()
+            .map(|text| render_text(text, format, symbology))
+    };
+
+    let faces = card.faces();
+    let faces = if faces.is_empty() {
+        vec![RenderedFace {
+            name: card.name.clone(),
+            mana_cost: render(&card.mana_cost),
+            type_line: card.type_line.clone(),
+            oracle_text: render(&card.oracle_text),
+        }]
+    } else {
+        faces
+            .into_iter()
+            .map(|face| RenderedFace {
+                mana_cost: render(&face.mana_cost),
+                oracle_text: render(&face.oracle_text),
+                name: face.name,
+                type_line: face.type_line,
+            })
+            .collect()
+    };
+
+    RenderedCard {
+        id: card.id,
+        name: card.name.clone(),
+        format,
+        faces,
+    }
+}
+
+/// Render `text`, one paragraph per line. Symbols missing from `symbology` are kept as
+/// text (escaped for the format), so rendering still works without the symbol list.
+pub fn render_text(text: &str, format: RenderFormat, symbology: &Symbology) -> String {
+    let paragraphs: Vec<String> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| render_line(line, format, symbology))
+        .collect();
+
+    match format {
+        RenderFormat::Html => paragraphs
+            .iter()
+            .map(|p| format!("<p>{}</p>", p))
+            .collect::<Vec<_>>()
+            .join(""),
+        RenderFormat::Markdown => paragraphs.join("\n\n"),
+    }
+}
+
+fn render_line(line: &str, format: RenderFormat, symbology: &Symbology) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&escape(&rest[..start], format));
+        let symbol = &rest[start..start + len + 1];
+        match symbology.get(symbol) {
+            Some(info) => out.push_str(&symbol_tag(symbol, info, format)),
+            None => out.push_str(&escape(symbol, format)),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(&escape(rest, format));
+    out
+}
+
+fn symbol_tag(symbol: &str, info: &SymbolInfo, format: RenderFormat) -> String {
+    let Some(svg_uri) = &info.svg_uri else {
+        return escape(symbol, format);
+    };
+    let title = info.english.as_deref().unwrap_or(symbol);
+    match format {
+        RenderFormat::Html => format!(
+            r#"<img class="card-symbol" src="{}" alt="{}" title="{}">"#,
+            escape_html(svg_uri),
+            escape_html(symbol),
+            escape_html(title)
+        ),
+        RenderFormat::Markdown => format!(
+            r#"![{}]({} "{}")"#,
+            escape_markdown(symbol),
+            svg_uri,
+            title.replace('"', "'")
+        ),
+    }
+}
+
+fn escape(text: &str, format: RenderFormat) -> String {
+    match format {
+        RenderFormat::Html => escape_html(text),
+        RenderFormat::Markdown => escape_markdown(text),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn symbology() -> Symbology {
+        Symbology::from_scryfall(&json!({
+            "object": "list",
+            "data": [
+                {
+                    "symbol": "{T}",
+                    "svg_uri": "https://svgs.scryfall.io/card-symbols/T.svg",
+                    "english": "tap this permanent"
+                },
+                {
+                    "symbol": "{U}",
+                    "svg_uri": "https://svgs.scryfall.io/card-symbols/U.svg",
+                    "english": "one blue mana"
+                }
+            ]
+        }))
+    }
+
+    #[test]
+    fn test_render_html_replaces_symbols_and_escapes_text() {
+        let html = render_text(
+            "{T}: Add {U}.\nWhen <this> dies & more",
+            RenderFormat::Html,
+            &symbology(),
+        );
+        assert_eq!(
+            html,
+            "<p><img class=\"card-symbol\" src=\"https://svgs.scryfall.io/card-symbols/T.svg\" \
+             alt=\"{T}\" title=\"tap this permanent\">: Add <img class=\"card-symbol\" \
+             src=\"https://svgs.scryfall.io/card-symbols/U.svg\" alt=\"{U}\" \
+             title=\"one blue mana\">.</p><p>When &lt;this&gt; dies &amp; more</p>"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_keeps_unknown_symbols() {
+        let markdown = render_text(
+            "{T}, {Q}: Draw a card.\n\nScry 1.",
+            RenderFormat::Markdown,
+            &symbology(),
+        );
+        assert_eq!(
+            markdown,
+            "![{T}](https://svgs.scryfall.io/card-symbols/T.svg \"tap this permanent\"), {Q}: \
+             Draw a card.\n\nScry 1."
+        );
+    }
+
+    #[test]
+    fn test_render_format_parse() {
+        assert_eq!(RenderFormat::parse("HTML"), Some(RenderFormat::Html));
+        assert_eq!(RenderFormat::parse("md"), Some(RenderFormat::Markdown));
+        assert_eq!(RenderFormat::parse("pdf"), None);
+    }
+}
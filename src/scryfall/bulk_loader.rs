@@ -1,22 +1,48 @@
 use anyhow::{Context, Result};
 use chrono::DateTime;
 use flate2::read::GzDecoder;
-use serde::Deserialize;
-use std::io::Read;
+use futures::{Stream, StreamExt};
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, Mutex, MutexGuard, Notify};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
+use uuid::Uuid;
 
+use crate::cache::manager::CacheManager;
 use crate::config::ScryfallConfig;
-use crate::db::Database;
+use crate::db::{AdvisoryLock, Database};
+use crate::errors::ServiceError;
+use crate::events::{self, CacheEvent};
 use crate::metrics::{
     BULK_DATA_CARDS_IMPORTED, BULK_DATA_LAST_LOAD_TIMESTAMP, BULK_DATA_LOAD_DURATION_SECONDS,
 };
+use crate::models::bulk_import::{BulkImport, BulkImportError};
 use crate::models::card::Card;
 
 const BULK_DATA_API: &str = "https://api.scryfall.com/bulk-data";
-const BATCH_SIZE: usize = 500;
 const MAX_RETRIES: u32 = 3;
 const INITIAL_RETRY_DELAY_MS: u64 = 1000; // Start with 1 second
+/// Attempts at importing the downloaded files before a load fails
+const IMPORT_ATTEMPTS: u32 = 2;
+/// Download progress is logged every this many bytes
+const DOWNLOAD_PROGRESS_BYTES: u64 = 100_000_000;
+/// Parse failures recorded per imported file; later ones are only counted
+const MAX_RECORDED_IMPORT_ERRORS: usize = 1000;
+/// Fewest cards a Scryfall bulk data file must yield; fewer means the file is broken
+const MIN_BULK_DATA_CARDS: usize = 1000;
+/// Bulk type recorded in the import history (and card provenance) for restored snapshots
+pub const RESTORE_BULK_TYPE: &str = "restore";
+/// Name of the cluster-wide lock held during loads and restores
+const LOAD_LOCK_NAME: &str = "bulk_load";
 
 #[derive(Debug, Deserialize)]
 struct BulkDataList {
@@ -41,10 +67,74 @@ struct BulkDataInfo {
     content_encoding: String,
 }
 
+/// Result of importing one bulk data file
+struct ImportCounts {
+    /// Cards written to the database
+    imported: usize,
+    /// Cards skipped because an earlier bulk type in the same load already imported them
+    duplicates: usize,
+    /// Cards skipped because their language is not in `bulk_languages`
+    filtered: usize,
+    /// Cards that failed to parse
+    failed: usize,
+    /// The first `MAX_RECORDED_IMPORT_ERRORS` parse failures
+    errors: Vec<BulkImportError>,
+}
+
+/// Bulk data file downloaded to `bulk_download_dir`, deleted when dropped
+struct DownloadedFile {
+    path: PathBuf,
+    size: u64,
+}
+
+impl Drop for DownloadedFile {
+    fn drop(&mut self) {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => debug!("Removed bulk data file {}", self.path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!(
+                "Failed to remove bulk data file {}: {}",
+                self.path.display(),
+                e
+            ),
+        }
+    }
+}
+
+/// Locks held for the duration of a load or restore
+struct LoadGuard<'a> {
+    _local: MutexGuard<'a, ()>,
+    _distributed: Option<AdvisoryLock>,
+}
+
+/// Reader counting the bytes read through it, for import progress
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
 #[derive(Clone)]
 pub struct BulkLoader {
     db: Database,
     config: ScryfallConfig,
+    /// Signalled whenever local card data becomes available or is reloaded
+    data_ready: Arc<Notify>,
+    /// Cancelled on shutdown; imports stop after the batch they are writing
+    shutdown: CancellationToken,
+    /// Caches to invalidate after each successful load
+    cache: Option<Arc<CacheManager>>,
+    /// Held for the duration of a load or restore, which share the staging table
+    load_lock: Arc<Mutex<()>>,
+    /// Replica mode: loads are refused and local data is served as-is
+    read_only: bool,
 }
 
 /// Retry a fallible async operation with exponential backoff
@@ -91,17 +181,312 @@ where
     }
 }
 
+/// Stream one download attempt of `uri` into `path`, replacing any earlier attempt, and
+/// return the number of bytes written
+async fn download_attempt(
+    client: &reqwest::Client,
+    uri: &str,
+    path: &Path,
+    expected_size: i64,
+) -> Result<u64> {
+    let mut response = client
+        .get(uri)
+        .send()
+        .await
+        .context("Failed to send download request")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| String::from("Unable to read response"));
+        return Err(anyhow::anyhow!(
+            "Download failed with status {}: {}",
+            status,
+            body
+        ));
+    }
+
+    let file = tokio::fs::File::create(path)
+        .await
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut out = tokio::io::BufWriter::new(file);
+    let mut written = 0u64;
+    let mut next_report = DOWNLOAD_PROGRESS_BYTES;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("Failed to read bulk data download")?
+    {
+        out.write_all(&chunk)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written += chunk.len() as u64;
+        if written >= next_report {
+            info!(
+                "Downloaded {:.0} of {:.0} MB",
+                written as f64 / 1_000_000.0,
+                expected_size as f64 / 1_000_000.0
+            );
+            next_report += DOWNLOAD_PROGRESS_BYTES;
+        }
+    }
+    out.flush()
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(written)
+}
+
+/// Stream an uploaded snapshot into `path` and return the number of bytes written
+async fn write_upload<S, B, E>(body: S, path: &Path) -> Result<u64>
+where
+    S: Stream<Item = std::result::Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let file = tokio::fs::File::create(path)
+        .await
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut out = tokio::io::BufWriter::new(file);
+    let mut written = 0u64;
+    let mut body = std::pin::pin!(body);
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.context("Failed to read uploaded snapshot")?;
+        out.write_all(chunk.as_ref())
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written += chunk.as_ref().len() as u64;
+    }
+    out.flush()
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(written)
+}
+
+/// Describe a bulk data entry that `Card::from_scryfall_json` rejected
+fn import_error(
+    position: usize,
+    card_json: &serde_json::Value,
+    error: &anyhow::Error,
+) -> BulkImportError {
+    let field = |name: &str| card_json.get(name).and_then(|v| v.as_str());
+    let category = if !card_json.is_object() {
+        "not_an_object"
+    } else if card_json.get("id").is_none() {
+        "missing_id"
+    } else if !field("id").is_some_and(|id| Uuid::parse_str(id).is_ok()) {
+        "invalid_id"
+    } else if field("name").is_none() {
+        "missing_name"
+    } else {
+        "other"
+    };
+
+    BulkImportError {
+        position: position as i64,
+        card_id: card_json.get("id").map(|id| match id.as_str() {
+            Some(id) => id.to_string(),
+            None => id.to_string(),
+        }),
+        card_name: field("name").map(str::to_string),
+        category: category.to_string(),
+        message: format!("{:#}", error),
+    }
+}
+
+/// Read a bulk data file (a JSON array of cards, gzipped or not) one card at a time,
+/// passing each to `each`. Returns the number of cards read, or an error if `each`
+/// returns false to stop early.
+fn read_card_array<R: Read>(
+    reader: R,
+    each: impl FnMut(serde_json::Value) -> bool,
+) -> Result<usize> {
+    let mut reader = BufReader::new(reader);
+    let gzipped = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+    if gzipped {
+        debug!("Bulk data file is gzip-compressed");
+        parse_card_array(BufReader::new(GzDecoder::new(reader)), each)
+    } else {
+        parse_card_array(reader, each)
+    }
+}
+
+fn parse_card_array<R: Read>(
+    reader: R,
+    each: impl FnMut(serde_json::Value) -> bool,
+) -> Result<usize> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let count = (&mut deserializer)
+        .deserialize_seq(CardArrayVisitor(each))
+        .context("Failed to parse bulk data JSON")?;
+    deserializer
+        .end()
+        .context("Unexpected data after the bulk data JSON array")?;
+    Ok(count)
+}
+
+/// Visits the elements of the top-level JSON array without collecting them
+struct CardArrayVisitor<F>(F);
+
+impl<'de, F: FnMut(serde_json::Value) -> bool> Visitor<'de> for CardArrayVisitor<F> {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON array of cards")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<usize, A::Error> {
+        let mut count = 0;
+        while let Some(card) = seq.next_element()? {
+            count += 1;
+            if !(self.0)(card) {
+                return Err(serde::de::Error::custom("reading stopped early"));
+            }
+        }
+        Ok(count)
+    }
+}
+
 impl BulkLoader {
     pub fn new(db: Database, config: ScryfallConfig) -> Self {
-        Self { db, config }
+        Self {
+            db,
+            config,
+            data_ready: Arc::new(Notify::new()),
+            shutdown: CancellationToken::new(),
+            cache: None,
+            load_lock: Arc::new(Mutex::new(())),
+            read_only: false,
+        }
+    }
+
+    /// Refuse to load bulk data, for read replicas that serve whatever the primary loaded
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Whether loads are refused because the service runs read-only
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Invalidate `cache` after each successful load, so cards and search results cached
+    /// before the reload aren't served until they expire
+    pub fn with_cache(mut self, cache: Arc<CacheManager>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Stop imports when `shutdown` is cancelled. An import in progress finishes the
+    /// batch it is writing and then fails without recording the import, so the next
+    /// start loads the data again.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Take the load lock, and with `bulk_distributed_lock` the cluster-wide one, without
+    /// waiting. Fails with `ServiceError::ReloadInProgress` while another load or restore
+    /// holds either.
+    async fn lock_load(&self) -> Result<LoadGuard<'_>> {
+        let local = self
+            .load_lock
+            .try_lock()
+            .map_err(|_| ServiceError::ReloadInProgress("on this instance".to_string()))?;
+        if !self.config.bulk_distributed_lock {
+            return Ok(LoadGuard {
+                _local: local,
+                _distributed: None,
+            });
+        }
+
+        let distributed = match &self.cache {
+            Some(cache) => cache.try_distributed_lock(LOAD_LOCK_NAME).await,
+            None => self.db.try_advisory_lock(LOAD_LOCK_NAME).await,
+        }
+        .context("Failed to take the bulk load lock")?
+        .ok_or_else(|| ServiceError::ReloadInProgress("on another instance".to_string()))?;
+        Ok(LoadGuard {
+            _local: local,
+            _distributed: Some(distributed),
+        })
+    }
+
+    /// Fail if shutdown has been requested
+    fn check_shutdown(&self) -> Result<()> {
+        if self.shutdown.is_cancelled() {
+            anyhow::bail!("Bulk data import interrupted by shutdown");
+        }
+        Ok(())
+    }
+
+    /// Signal that local card data is available (after startup or a reload)
+    pub fn notify_data_ready(&self) {
+        self.data_ready.notify_one();
+    }
+
+    /// Wait for the next data-ready signal. A signal sent while nobody is waiting is
+    /// kept, so a load that finishes between waits is not missed.
+    pub async fn data_ready(&self) {
+        self.data_ready.notified().await;
     }
 
     pub async fn last_import_timestamp(&self) -> Result<Option<chrono::NaiveDateTime>> {
-        self.db.get_last_bulk_import().await
+        Ok(self.db.get_last_bulk_import().await?)
+    }
+
+    /// Most recent bulk imports from the import history, newest first
+    pub async fn recent_imports(&self, limit: i64) -> Result<Vec<BulkImport>> {
+        Ok(self.db.list_bulk_imports(limit).await?)
+    }
+
+    /// Recorded parse failures of an import, or `None` if there is no such import
+    pub async fn import_errors(&self, import_id: i32) -> Result<Option<Vec<BulkImportError>>> {
+        Ok(self.db.list_bulk_import_errors(import_id).await?)
+    }
+
+    /// Check if any card data is already present locally
+    pub async fn has_data(&self) -> Result<bool> {
+        Ok(self.db.check_bulk_data_loaded().await?)
     }
 
-    /// Check if bulk data should be loaded
+    /// Check if bulk data should be loaded: when there are no cards, or when the data is
+    /// stale and (with `bulk_check_upstream`) Scryfall has published newer data since the
+    /// last import. If Scryfall can't be asked, stale data is reloaded.
     pub async fn should_load(&self) -> Result<bool> {
+        if !self.is_stale().await? {
+            return Ok(false);
+        }
+        if !self.config.bulk_check_upstream || !self.has_data().await? {
+            return Ok(true);
+        }
+
+        match self.check_upstream_updated().await {
+            Ok(true) => Ok(true),
+            Ok(false) => {
+                info!("Bulk data is stale but unchanged upstream, skipping reload");
+                Ok(false)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to check upstream bulk data status, reloading stale data: {}",
+                    e
+                );
+                Ok(true)
+            }
+        }
+    }
+
+    /// Whether the upstream check is enabled (`SCRYFALL_BULK_CHECK_UPSTREAM`)
+    pub fn checks_upstream(&self) -> bool {
+        self.config.bulk_check_upstream
+    }
+
+    /// Check if local data is missing or older than `cache_ttl_hours`, without asking
+    /// Scryfall
+    pub async fn is_stale(&self) -> Result<bool> {
         // Check if database has any cards
         let has_cards = self.db.check_bulk_data_loaded().await?;
 
@@ -139,47 +524,206 @@ impl BulkLoader {
         Ok(false)
     }
 
-    /// Load bulk data from Scryfall
+    /// Load bulk data from Scryfall.
+    ///
+    /// Each configured bulk type is imported in order into the same cards table. Bulk files
+    /// overlap (every `oracle_cards` entry is also a `default_cards` printing, and
+    /// `default_cards` is a subset of `all_cards`), and a card ID identifies the same printing
+    /// object in every file, so a card already imported earlier in this load is not written
+    /// again. Its provenance is still recorded for every type it appears in.
+    ///
+    /// Publishes `BulkReloadStarted` and then `BulkReloadCompleted` or `BulkReloadFailed`.
+    /// A successful load also invalidates the query and Redis caches, if a cache manager
+    /// was given, before waking the warm-up job to repopulate them.
+    ///
+    /// Fails with `ServiceError::ReadOnly` without touching the database when read-only, and
+    /// with `ServiceError::ReloadInProgress` while another load or restore is running.
     pub async fn load(&self) -> Result<()> {
+        if self.read_only {
+            return Err(ServiceError::ReadOnly("bulk data loads are disabled".to_string()).into());
+        }
+        let _load = self.lock_load().await?;
+
+        events::publish(CacheEvent::BulkReloadStarted {
+            bulk_types: self.config.bulk_data_types.clone(),
+        });
+
         let start = Instant::now();
-        info!("Starting bulk data import...");
+        match self.import_all().await {
+            Ok(cards_imported) => {
+                if let Some(cache) = &self.cache {
+                    if let Err(e) = cache.invalidate_after_reload().await {
+                        warn!("Failed to invalidate caches after reload: {}", e);
+                    }
+                }
+                events::publish(CacheEvent::BulkReloadCompleted {
+                    cards_imported,
+                    duration_ms: start.elapsed().as_millis() as u64,
+                });
+                self.notify_data_ready();
+                Ok(())
+            }
+            Err(e) => {
+                events::publish(CacheEvent::BulkReloadFailed {
+                    error: format!("{:#}", e),
+                });
+                Err(e)
+            }
+        }
+    }
 
-        // Discover bulk data info
-        let bulk_info = self.discover_bulk_data().await?;
+    /// Import every configured bulk type, returning the number of distinct cards seen
+    ///
+    /// Every bulk file is first downloaded to `bulk_download_dir`, so an import that fails
+    /// is retried from disk rather than downloading again. The files are deleted once the
+    /// load finishes or fails.
+    ///
+    /// Cards are written to a staging table that replaces the live cards only once every
+    /// bulk type has been imported, so searches keep seeing the previous data during the
+    /// load and a failed or cancelled load leaves it untouched. The caller holds the load
+    /// lock.
+    async fn import_all(&self) -> Result<usize> {
+        let start = Instant::now();
         info!(
-            "Found bulk data: type={}, size={}MB",
-            bulk_info.bulk_type,
-            bulk_info.size / 1_000_000
+            "Starting bulk data import ({})...",
+            self.config.bulk_data_types.join(", ")
         );
 
-        // Download and process
-        let total_cards = self.download_and_import(&bulk_info).await?;
+        // Discover bulk data info
+        let bulk_infos = self.discover_bulk_data().await?;
+
+        let mut files = Vec::with_capacity(bulk_infos.len());
+        for bulk_info in &bulk_infos {
+            self.check_shutdown()?;
+            // Validate the upstream timestamp before spending time on the download
+            DateTime::parse_from_rfc3339(&bulk_info.updated_at)
+                .context("Failed to parse updated_at timestamp")?;
+            files.push(self.download_to_disk(bulk_info).await?);
+        }
 
-        // Record the import
-        let _updated_at = DateTime::parse_from_rfc3339(&bulk_info.updated_at)
-            .context("Failed to parse updated_at timestamp")?
-            .naive_utc();
+        let mut attempt = 0;
+        let (imports, cards_seen) = loop {
+            attempt += 1;
+            match self.import_downloaded(&bulk_infos, &files).await {
+                Ok(result) => break result,
+                Err(e) if attempt < IMPORT_ATTEMPTS && !self.shutdown.is_cancelled() => {
+                    warn!(
+                        "Bulk data import attempt {}/{} failed: {:#}. Retrying from the downloaded files...",
+                        attempt, IMPORT_ATTEMPTS, e
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        };
 
-        // Get the bulk type for the source field
-        let source = bulk_info.download_uri.clone();
-        self.db
-            .record_bulk_import(total_cards as i32, &source)
-            .await?;
+        // Record the imports now that their cards are live
+        let mut total_imported = 0;
+        for (import, errors) in &imports {
+            let import_id = self.db.record_bulk_import(import).await?;
+            if let Err(e) = self.db.record_bulk_import_errors(import_id, errors).await {
+                warn!(
+                    "Failed to record {} parse failures of bulk import {}: {}",
+                    errors.len(),
+                    import_id,
+                    e
+                );
+            }
+            total_imported += import.total_cards as usize;
+        }
 
         let duration = start.elapsed();
         info!(
             "Bulk data import completed: {} cards imported in {:.2}s ({:.0} cards/sec)",
-            total_cards,
+            total_imported,
             duration.as_secs_f64(),
-            total_cards as f64 / duration.as_secs_f64()
+            total_imported as f64 / duration.as_secs_f64()
         );
 
         // Record metrics
         BULK_DATA_LOAD_DURATION_SECONDS.set(duration.as_secs_f64());
-        BULK_DATA_CARDS_IMPORTED.set(total_cards as i64);
+        BULK_DATA_CARDS_IMPORTED.set(cards_seen as i64);
         BULK_DATA_LAST_LOAD_TIMESTAMP.set(chrono::Utc::now().timestamp());
 
-        Ok(())
+        Ok(cards_seen)
+    }
+
+    /// Import the downloaded files through a fresh staging table and swap it in, returning
+    /// the import history entries with their parse failures and the number of distinct
+    /// cards seen. The staging table is dropped if any file fails.
+    async fn import_downloaded(
+        &self,
+        bulk_infos: &[BulkDataInfo],
+        files: &[DownloadedFile],
+    ) -> Result<(Vec<(BulkImport, Vec<BulkImportError>)>, usize)> {
+        let mut seen = HashSet::new();
+        self.db
+            .begin_staging()
+            .await
+            .context("Failed to create staging table")?;
+        let staged = async {
+            let imports = self.import_staged(bulk_infos, files, &mut seen).await?;
+            self.db
+                .commit_staging()
+                .await
+                .context("Failed to swap in staged cards")?;
+            Ok::<_, anyhow::Error>(imports)
+        }
+        .await;
+        match staged {
+            Ok(imports) => Ok((imports, seen.len())),
+            Err(e) => {
+                if let Err(discard) = self.db.discard_staging().await {
+                    warn!(
+                        "Failed to drop staging table after failed load: {}",
+                        discard
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Import each downloaded bulk data file into the staging table, returning one import
+    /// history entry per file along with its parse failures
+    async fn import_staged(
+        &self,
+        bulk_infos: &[BulkDataInfo],
+        files: &[DownloadedFile],
+        seen: &mut HashSet<Uuid>,
+    ) -> Result<Vec<(BulkImport, Vec<BulkImportError>)>> {
+        let mut imports = Vec::with_capacity(bulk_infos.len());
+
+        for (bulk_info, file) in bulk_infos.iter().zip(files) {
+            self.check_shutdown()?;
+            info!(
+                "Importing bulk data: type={}, size={}MB",
+                bulk_info.bulk_type,
+                file.size / 1_000_000
+            );
+
+            let type_start = Instant::now();
+            let counts = self
+                .import_file(&bulk_info.bulk_type, file, seen, MIN_BULK_DATA_CARDS)
+                .await?;
+            info!(
+                "Imported bulk type '{}': {} cards written, {} already imported from an earlier type, {} in other languages skipped",
+                bulk_info.bulk_type, counts.imported, counts.duplicates, counts.filtered
+            );
+
+            let import = BulkImport {
+                id: None,
+                bulk_type: bulk_info.bulk_type.clone(),
+                source: bulk_info.download_uri.clone(),
+                total_cards: counts.imported as i32,
+                failed_cards: counts.failed as i32,
+                file_size_bytes: file.size as i64,
+                duration_ms: type_start.elapsed().as_millis() as i64,
+                imported_at: None,
+            };
+            imports.push((import, counts.errors));
+        }
+
+        Ok(imports)
     }
 
     /// Check if Scryfall's bulk data has been updated since our last import
@@ -190,12 +734,17 @@ impl BulkLoader {
         let our_updated_at = self.db.get_last_bulk_import().await?;
 
         // Fetch Scryfall's current bulk data info
-        let bulk_info = self.discover_bulk_data().await?;
-
-        // Parse Scryfall's updated_at timestamp
-        let their_updated_at = chrono::DateTime::parse_from_rfc3339(&bulk_info.updated_at)
-            .context("Failed to parse Scryfall updated_at timestamp")?
-            .naive_utc();
+        let bulk_infos = self.discover_bulk_data().await?;
+
+        // Parse Scryfall's updated_at timestamps; any configured type changing counts
+        let mut their_updated_at = None;
+        for bulk_info in &bulk_infos {
+            let updated_at = chrono::DateTime::parse_from_rfc3339(&bulk_info.updated_at)
+                .context("Failed to parse Scryfall updated_at timestamp")?
+                .naive_utc();
+            their_updated_at = their_updated_at.max(Some(updated_at));
+        }
+        let their_updated_at = their_updated_at.context("No bulk data types configured")?;
 
         // If we have previous import metadata, compare timestamps
         if let Some(our_time) = our_updated_at {
@@ -219,16 +768,14 @@ impl BulkLoader {
         }
     }
 
-    /// Discover the bulk data download URI
-    async fn discover_bulk_data(&self) -> Result<BulkDataInfo> {
+    /// Discover the download info for each configured bulk data type, in configured order
+    async fn discover_bulk_data(&self) -> Result<Vec<BulkDataInfo>> {
         let client = reqwest::Client::builder()
             .user_agent("scryfall-cache/0.1.0")
             .timeout(Duration::from_secs(30))
             .build()
             .context("Failed to build HTTP client")?;
 
-        let bulk_type = self.config.bulk_data_type.clone();
-
         // Retry the bulk data discovery
         let response = retry_with_backoff(
             || async {
@@ -242,7 +789,8 @@ impl BulkLoader {
             MAX_RETRIES,
             "Bulk data API request",
         )
-        .await?;
+        .await
+        .map_err(ServiceError::ScryfallApi)?;
 
         // Check response status
         let status = response.status();
@@ -251,12 +799,13 @@ impl BulkLoader {
                 .text()
                 .await
                 .unwrap_or_else(|_| String::from("Unable to read response body"));
-            return Err(anyhow::anyhow!(
+            return Err(ServiceError::ScryfallApi(anyhow::anyhow!(
                 "Scryfall bulk data API returned error status {}: {}. Check if API is available at {}",
                 status,
                 body,
                 BULK_DATA_API
-            ));
+            ))
+            .into());
         }
 
         debug!("Bulk data API response status: {}", status);
@@ -286,31 +835,37 @@ impl BulkLoader {
             bulk_list.data.iter().map(|d| d.bulk_type.clone()).collect();
         debug!("Available bulk data types: {:?}", available_types);
 
-        // Find the requested bulk data type
-        let bulk_info = bulk_list
-            .data
-            .into_iter()
-            .find(|info| info.bulk_type == bulk_type)
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Bulk data type '{}' not found. Available types: {:?}. Check SCRYFALL_BULK_DATA_TYPE environment variable.",
-                    bulk_type,
-                    available_types
-                )
-            })?;
-
-        info!(
-            "Selected bulk data: type='{}', size={:.2}MB, updated={}",
-            bulk_info.bulk_type,
-            bulk_info.size as f64 / 1_000_000.0,
-            bulk_info.updated_at
-        );
+        // Find the requested bulk data types
+        let mut available = bulk_list.data;
+        let mut selected = Vec::with_capacity(self.config.bulk_data_types.len());
+        for bulk_type in &self.config.bulk_data_types {
+            let pos = available
+                .iter()
+                .position(|info| &info.bulk_type == bulk_type)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Bulk data type '{}' not found. Available types: {:?}. Check SCRYFALL_BULK_DATA_TYPE environment variable.",
+                        bulk_type,
+                        available_types
+                    )
+                })?;
+            let bulk_info = available.swap_remove(pos);
+
+            info!(
+                "Selected bulk data: type='{}', size={:.2}MB, updated={}",
+                bulk_info.bulk_type,
+                bulk_info.size as f64 / 1_000_000.0,
+                bulk_info.updated_at
+            );
+            selected.push(bulk_info);
+        }
 
-        Ok(bulk_info)
+        Ok(selected)
     }
 
-    /// Download and import bulk data
-    async fn download_and_import(&self, bulk_info: &BulkDataInfo) -> Result<usize> {
+    /// Download a bulk data file to `bulk_download_dir`, streaming it to disk rather than
+    /// holding it in memory. The file is deleted if the download fails.
+    async fn download_to_disk(&self, bulk_info: &BulkDataInfo) -> Result<DownloadedFile> {
         let client = reqwest::Client::builder()
             .user_agent("scryfall-cache/0.1.0")
             .timeout(Duration::from_secs(600)) // 10 minutes for large downloads
@@ -324,46 +879,39 @@ impl BulkLoader {
             bulk_info.size
         );
 
-        let download_uri = bulk_info.download_uri.clone();
-        let expected_size = bulk_info.size;
-
-        // Retry the download
-        let bytes = retry_with_backoff(
-            || async {
-                let response = client
-                    .get(&download_uri)
-                    .send()
-                    .await
-                    .context("Failed to send download request")?;
-
-                if !response.status().is_success() {
-                    let status = response.status();
-                    let body = response
-                        .text()
-                        .await
-                        .unwrap_or_else(|_| String::from("Unable to read response"));
-                    return Err(anyhow::anyhow!(
-                        "Download failed with status {}: {}",
-                        status,
-                        body
-                    ));
-                }
+        let dir = &self.config.bulk_download_dir;
+        tokio::fs::create_dir_all(dir).await.with_context(|| {
+            format!("Failed to create bulk download directory {}", dir.display())
+        })?;
+        let mut file = DownloadedFile {
+            path: dir.join(format!(
+                "scryfall-{}-{}.json",
+                bulk_info.bulk_type,
+                Uuid::new_v4()
+            )),
+            size: 0,
+        };
 
-                response
-                    .bytes()
-                    .await
-                    .context("Failed to read response bytes")
-            },
+        // Retry the download; nothing has been imported yet, so shutdown abandons it
+        let download = retry_with_backoff(
+            || download_attempt(&client, &bulk_info.download_uri, &file.path, bulk_info.size),
             MAX_RETRIES,
             "Bulk data download",
-        )
-        .await?;
+        );
+        file.size = tokio::select! {
+            result = download => result.map_err(ServiceError::ScryfallApi)?,
+            _ = self.shutdown.cancelled() => {
+                anyhow::bail!("Bulk data download interrupted by shutdown");
+            }
+        };
 
-        let actual_size = bytes.len() as i64;
+        let actual_size = file.size as i64;
+        let expected_size = bulk_info.size;
         info!(
-            "Download complete: {:.2} MB ({} bytes)",
+            "Download complete: {:.2} MB ({} bytes) saved to {}",
             actual_size as f64 / 1_000_000.0,
-            actual_size
+            actual_size,
+            file.path.display()
         );
 
         // Verify download size is reasonable
@@ -388,83 +936,86 @@ impl BulkLoader {
             );
         }
 
-        info!("Parsing bulk data...");
-
-        // Try to parse as JSON directly first (in case reqwest auto-decompressed)
-        let json_array: Vec<serde_json::Value> = match serde_json::from_slice::<
-            Vec<serde_json::Value>,
-        >(&bytes)
-        {
-            Ok(array) => {
-                info!("Successfully parsed JSON directly (data was already decompressed)");
-                debug!("Parsed {} top-level elements", array.len());
-                array
-            }
-            Err(direct_parse_error) => {
-                // If direct parsing fails, try decompressing first
-                debug!(
-                    "Direct JSON parsing failed: {}. Attempting gzip decompression...",
-                    direct_parse_error
-                );
-
-                let mut decoder = GzDecoder::new(&bytes[..]);
-                let mut decompressed = Vec::new();
-
-                decoder.read_to_end(&mut decompressed).context(format!(
-                    "Failed to decompress gzipped bulk data. This may not be gzip-encoded data. Original parse error was: {}",
-                    direct_parse_error
-                ))?;
-
-                let decompressed_size = decompressed.len();
-                info!(
-                    "Decompression complete: {:.2} MB ({} bytes). Parsing JSON...",
-                    decompressed_size as f64 / 1_000_000.0,
-                    decompressed_size
-                );
+        Ok(file)
+    }
 
-                serde_json::from_slice(&decompressed).context(format!(
-                    "Failed to parse bulk data JSON after decompression. Decompressed size: {} bytes. Data preview: {}",
-                    decompressed_size,
-                    String::from_utf8_lossy(&decompressed[..decompressed_size.min(200)])
-                ))?
-            }
+    /// Import a downloaded bulk data file, skipping cards whose IDs are already in `seen`.
+    /// Fails if fewer than `min_cards` cards parse.
+    ///
+    /// The file is parsed on a blocking thread one card at a time, so memory use stays at
+    /// about one batch of cards however large the file is.
+    async fn import_file(
+        &self,
+        bulk_type: &str,
+        file: &DownloadedFile,
+        seen: &mut HashSet<Uuid>,
+        min_cards: usize,
+    ) -> Result<ImportCounts> {
+        let batch_size = self.db.import_batch_size();
+        let bytes_read = Arc::new(AtomicU64::new(0));
+        let (cards_tx, mut cards_rx) = mpsc::channel(batch_size);
+        let reader = {
+            let path = file.path.clone();
+            let bytes_read = bytes_read.clone();
+            tokio::task::spawn_blocking(move || {
+                let file = std::fs::File::open(&path)
+                    .with_context(|| format!("Failed to open {}", path.display()))?;
+                let reader = CountingReader {
+                    inner: file,
+                    count: bytes_read,
+                };
+                // Stop reading once the importer has gone away
+                read_card_array(reader, |card| cards_tx.blocking_send(card).is_ok())
+            })
         };
 
-        let total_cards = json_array.len();
-        info!(
-            "Parsed {} cards from JSON array, starting import...",
-            total_cards
-        );
-
-        if total_cards == 0 {
-            return Err(anyhow::anyhow!(
-                "Bulk data JSON array is empty. Expected thousands of cards but got 0."
-            ));
-        }
-
         let mut imported = 0;
+        let mut duplicates = 0;
+        let mut filtered = 0;
         let mut failed = 0;
-        let mut batch = Vec::with_capacity(BATCH_SIZE);
-
-        for (idx, card_json) in json_array.into_iter().enumerate() {
+        let mut errors = Vec::new();
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut provenance = Vec::with_capacity(batch_size);
+        let mut cards_received = 0;
+
+        while let Some(card_json) = cards_rx.recv().await {
+            let idx = cards_received;
+            cards_received += 1;
             match Card::from_scryfall_json(card_json.clone()) {
+                Ok(card) if !self.wants_language(&card) => {
+                    filtered += 1;
+                }
                 Ok(card) => {
+                    provenance.push(card.id);
+                    if provenance.len() >= batch_size {
+                        self.db
+                            .record_card_provenance(bulk_type, &provenance)
+                            .await
+                            .context(format!("Failed to record provenance at index {}", idx))?;
+                        provenance.clear();
+                    }
+
+                    if !seen.insert(card.id) {
+                        duplicates += 1;
+                        continue;
+                    }
                     batch.push(card);
 
-                    if batch.len() >= BATCH_SIZE {
+                    if batch.len() >= batch_size {
                         self.db
-                            .insert_cards_batch(&batch)
+                            .insert_cards_staging(&batch)
                             .await
                             .context(format!("Failed to insert batch at index {}", idx))?;
                         imported += batch.len();
                         batch.clear();
+                        self.check_shutdown()?;
 
                         if imported % 5000 == 0 {
+                            let read = bytes_read.load(Ordering::Relaxed);
                             info!(
-                                "Progress: {}/{} cards imported ({:.1}%), {} failed",
+                                "Progress: {} cards imported ({:.1}% of file read), {} failed",
                                 imported,
-                                total_cards,
-                                (imported as f64 / total_cards as f64) * 100.0,
+                                (read as f64 / file.size as f64 * 100.0).min(100.0),
                                 failed
                             );
                         }
@@ -472,6 +1023,9 @@ impl BulkLoader {
                 }
                 Err(e) => {
                     failed += 1;
+                    if errors.len() < MAX_RECORDED_IMPORT_ERRORS {
+                        errors.push(import_error(idx, &card_json, &e));
+                    }
                     if failed <= 10 {
                         // Log first 10 failures with details
                         warn!(
@@ -491,28 +1045,48 @@ impl BulkLoader {
             }
         }
 
+        // The channel closes when the whole file has been read or reading failed
+        let total_cards = reader
+            .await
+            .context("Bulk data reader task failed")?
+            .with_context(|| format!("Failed to read bulk data from {}", file.path.display()))?;
+        if total_cards == 0 {
+            return Err(anyhow::anyhow!(
+                "Bulk data JSON array is empty. Expected thousands of cards but got 0."
+            ));
+        }
+
         // Import remaining cards
         if !batch.is_empty() {
             self.db
-                .insert_cards_batch(&batch)
+                .insert_cards_staging(&batch)
                 .await
                 .context("Failed to insert final batch")?;
             imported += batch.len();
         }
+        self.db
+            .record_card_provenance(bulk_type, &provenance)
+            .await
+            .context("Failed to record final provenance batch")?;
 
+        let parsed = imported + duplicates + filtered;
         info!(
-            "Import complete: {}/{} cards imported successfully, {} failed to parse ({:.2}% success rate)",
-            imported,
+            "Import complete: {}/{} cards parsed successfully ({} new, {} duplicates, {} other languages), {} failed to parse ({:.2}% success rate)",
+            parsed,
             total_cards,
+            imported,
+            duplicates,
+            filtered,
             failed,
-            (imported as f64 / total_cards as f64) * 100.0
+            (parsed as f64 / total_cards as f64) * 100.0
         );
 
-        // Verify we imported a reasonable number of cards
-        if imported < 1000 {
+        // Verify we parsed a reasonable number of cards
+        if parsed < min_cards {
             return Err(anyhow::anyhow!(
-                "Import verification failed: Only {} cards imported. Expected at least 1000. This indicates a problem with the bulk data.",
-                imported
+                "Import verification failed: Only {} cards imported. Expected at least {}. This indicates a problem with the bulk data.",
+                parsed,
+                min_cards
             ));
         }
 
@@ -525,7 +1099,21 @@ impl BulkLoader {
             );
         }
 
-        Ok(imported)
+        Ok(ImportCounts {
+            imported,
+            duplicates,
+            filtered,
+            failed,
+            errors,
+        })
+    }
+
+    /// Whether a card's language is selected by `bulk_languages` (cards without a
+    /// language are treated as English)
+    fn wants_language(&self, card: &Card) -> bool {
+        let languages = &self.config.bulk_languages;
+        let lang = card.lang.as_deref().unwrap_or("en");
+        languages.is_empty() || languages.iter().any(|l| l == lang)
     }
 
     /// Force reload bulk data regardless of cache status
@@ -533,4 +1121,241 @@ impl BulkLoader {
         info!("Force loading bulk data...");
         self.load().await
     }
+
+    /// Load a snapshot written by `/admin/backup` (or any Scryfall bulk data file), so a
+    /// deployment can be moved without downloading bulk data again. Returns the import
+    /// history entry recorded for it.
+    ///
+    /// The upload is written to `bulk_download_dir` and imported through the staging table
+    /// like a load: the snapshot's cards replace their stored versions, other cards are
+    /// kept, and a snapshot that fails to import leaves the current cards untouched. A
+    /// snapshot that isn't a JSON array of cards fails with `ServiceError::Validation`.
+    ///
+    /// Fails with `ServiceError::ReadOnly` without touching the database when read-only, and
+    /// with `ServiceError::ReloadInProgress` while a load or another restore is running.
+    pub async fn restore<S, B, E>(&self, body: S) -> Result<BulkImport>
+    where
+        S: Stream<Item = std::result::Result<B, E>>,
+        B: AsRef<[u8]>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        if self.read_only {
+            return Err(ServiceError::ReadOnly("restores are disabled".to_string()).into());
+        }
+
+        let _load = self.lock_load().await?;
+        let start = Instant::now();
+
+        let dir = &self.config.bulk_download_dir;
+        tokio::fs::create_dir_all(dir).await.with_context(|| {
+            format!("Failed to create bulk download directory {}", dir.display())
+        })?;
+        let mut file = DownloadedFile {
+            path: dir.join(format!("restore-{}.json", Uuid::new_v4())),
+            size: 0,
+        };
+        file.size = write_upload(body, &file.path).await?;
+        if file.size == 0 {
+            return Err(ServiceError::Validation("Snapshot is empty".to_string()).into());
+        }
+        info!(
+            "Restoring snapshot: {:.2} MB saved to {}",
+            file.size as f64 / 1_000_000.0,
+            file.path.display()
+        );
+
+        self.db
+            .begin_staging()
+            .await
+            .context("Failed to create staging table")?;
+        let staged = async {
+            let counts = self
+                .import_file(RESTORE_BULK_TYPE, &file, &mut HashSet::new(), 1)
+                .await?;
+            self.db
+                .commit_staging()
+                .await
+                .context("Failed to swap in staged cards")?;
+            Ok::<_, anyhow::Error>(counts)
+        }
+        .await;
+        let counts = match staged {
+            Ok(counts) => counts,
+            Err(e) => {
+                if let Err(discard) = self.db.discard_staging().await {
+                    warn!(
+                        "Failed to drop staging table after failed restore: {}",
+                        discard
+                    );
+                }
+                if e.downcast_ref::<serde_json::Error>().is_some() {
+                    return Err(
+                        ServiceError::Validation(format!("Invalid snapshot: {:#}", e)).into(),
+                    );
+                }
+                return Err(e);
+            }
+        };
+
+        let mut import = BulkImport {
+            id: None,
+            bulk_type: RESTORE_BULK_TYPE.to_string(),
+            source: "upload".to_string(),
+            total_cards: counts.imported as i32,
+            failed_cards: counts.failed as i32,
+            file_size_bytes: file.size as i64,
+            duration_ms: start.elapsed().as_millis() as i64,
+            imported_at: None,
+        };
+        let import_id = self.db.record_bulk_import(&import).await?;
+        if let Err(e) = self
+            .db
+            .record_bulk_import_errors(import_id, &counts.errors)
+            .await
+        {
+            warn!(
+                "Failed to record {} parse failures of restore {}: {}",
+                counts.errors.len(),
+                import_id,
+                e
+            );
+        }
+        import.id = Some(import_id);
+        info!(
+            "Snapshot restored: {} cards imported in {:.2}s",
+            counts.imported,
+            start.elapsed().as_secs_f64()
+        );
+
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.invalidate_after_reload().await {
+                warn!("Failed to invalidate caches after restore: {}", e);
+            }
+        }
+        self.notify_data_ready();
+        Ok(import)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::TestDb;
+    use crate::db::SqlDialect;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    const BULK_JSON: &str = r#"[{"name":"Fury Sliver"},{"name":"Sol Ring"}]"#;
+
+    #[test]
+    fn test_read_card_array() {
+        let mut names = Vec::new();
+        let count = read_card_array(BULK_JSON.as_bytes(), |card| {
+            names.push(card["name"].as_str().unwrap().to_string());
+            true
+        })
+        .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(names, ["Fury Sliver", "Sol Ring"]);
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(BULK_JSON.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        assert_eq!(read_card_array(&gzipped[..], |_| true).unwrap(), 2);
+
+        // The importer going away stops reading
+        assert!(read_card_array(BULK_JSON.as_bytes(), |_| false).is_err());
+        assert!(read_card_array(&b"{\"object\":\"error\"}"[..], |_| true).is_err());
+    }
+
+    #[test]
+    fn test_import_error_category() {
+        let category = |card_json: serde_json::Value| {
+            let error = Card::from_scryfall_json(card_json.clone()).unwrap_err();
+            import_error(7, &card_json, &error).category
+        };
+        assert_eq!(category(serde_json::json!("Sol Ring")), "not_an_object");
+        assert_eq!(
+            category(serde_json::json!({"name": "Sol Ring"})),
+            "missing_id"
+        );
+        assert_eq!(category(serde_json::json!({"id": 42})), "invalid_id");
+        assert_eq!(
+            category(serde_json::json!({"id": "0000579f-7b35-4ed3-b44c-db2a538066fe"})),
+            "missing_name"
+        );
+
+        let card_json = serde_json::json!({"id": "not-a-uuid", "name": "Sol Ring"});
+        let error = Card::from_scryfall_json(card_json.clone()).unwrap_err();
+        let error = import_error(7, &card_json, &error);
+        assert_eq!(error.position, 7);
+        assert_eq!(error.card_id.as_deref(), Some("not-a-uuid"));
+        assert_eq!(error.card_name.as_deref(), Some("Sol Ring"));
+    }
+
+    fn test_config() -> ScryfallConfig {
+        ScryfallConfig {
+            rate_limit_per_second: 10,
+            bulk_data_types: vec!["default_cards".to_string()],
+            bulk_languages: Vec::new(),
+            cache_ttl_hours: 24,
+            bulk_check_upstream: true,
+            bulk_download_dir: std::env::temp_dir(),
+            bulk_distributed_lock: false,
+            circuit_breaker: Default::default(),
+            retry: Default::default(),
+            search_max_pages: 20,
+            search_max_cards: 3500,
+            upstreams: Vec::new(),
+            hedge: Default::default(),
+        }
+    }
+
+    fn test_loader(db: TestDb, config: ScryfallConfig) -> BulkLoader {
+        BulkLoader::new(Arc::new(db) as Database, config)
+    }
+
+    #[tokio::test]
+    async fn test_lock_load_rejects_concurrent_loads() {
+        let loader = test_loader(TestDb::new(SqlDialect::Postgres), test_config());
+
+        let guard = loader.lock_load().await.unwrap();
+        let Err(error) = loader.lock_load().await else {
+            panic!("a second load took the lock");
+        };
+        assert!(matches!(
+            ServiceError::from(error),
+            ServiceError::ReloadInProgress(_)
+        ));
+
+        drop(guard);
+        assert!(loader.lock_load().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_should_load() {
+        let hours_ago = |hours| chrono::Utc::now().naive_utc() - chrono::Duration::hours(hours);
+
+        // No cards: load without asking Scryfall
+        let loader = test_loader(TestDb::new(SqlDialect::Postgres), test_config());
+        assert!(loader.is_stale().await.unwrap());
+        assert!(loader.should_load().await.unwrap());
+
+        // Fresh data: nothing to do
+        let db = TestDb::new(SqlDialect::Postgres).with_bulk_import(hours_ago(1));
+        let loader = test_loader(db, test_config());
+        assert!(!loader.is_stale().await.unwrap());
+        assert!(!loader.should_load().await.unwrap());
+
+        // Stale data with the upstream check disabled: reload on age alone
+        let config = ScryfallConfig {
+            bulk_check_upstream: false,
+            ..test_config()
+        };
+        let db = TestDb::new(SqlDialect::Postgres).with_bulk_import(hours_ago(48));
+        let loader = test_loader(db, config);
+        assert!(!loader.checks_upstream());
+        assert!(loader.is_stale().await.unwrap());
+        assert!(loader.should_load().await.unwrap());
+    }
 }
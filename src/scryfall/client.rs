@@ -1,41 +1,119 @@
-use anyhow::{Context, Result};
+use anyhow::Context;
 use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
-use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError};
+use crate::circuit_breaker::{CircuitBreakerConfig, CircuitBreakerError, CircuitBreakerRegistry};
 use crate::config::ScryfallConfig;
-use crate::metrics::registry::{SCRYFALL_API_CALLS_TOTAL, SCRYFALL_API_ERRORS_TOTAL};
-use crate::models::card::Card;
+use crate::deadline;
+use crate::errors::{ServiceError, ServiceResult};
+use crate::metrics::registry::{
+    SCRYFALL_API_CALLS_TOTAL, SCRYFALL_API_ERRORS_TOTAL, SCRYFALL_HEDGED_REQUESTS_TOTAL,
+    SCRYFALL_HEDGE_WINS_TOTAL, SCRYFALL_RATE_LIMIT_WAITS_TOTAL, SCRYFALL_UPSTREAM_FAILOVERS_TOTAL,
+};
+use crate::models::card::{Card, ExternalId};
+use crate::scryfall::hedge::{HedgeConfig, LatencyTracker};
 use crate::scryfall::rate_limiter::RateLimiter;
-
-const SCRYFALL_API_BASE: &str = "https://api.scryfall.com";
+use crate::scryfall::retry::{parse_retry_after, RetryConfig};
+use crate::scryfall::upstream::UpstreamConfig;
+
+/// Upper bound on how long a reachability probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Endpoints with their own circuit breaker, registered up front so they show up
+/// in `/admin/circuit-breakers` before their first request
+const ENDPOINTS: &[&str] = &[
+    "cards_search",
+    "cards_named",
+    "cards_id",
+    "cards_set_number",
+    "cards_external_id",
+    "cards_collection",
+    "sets",
+    "catalog",
+    "symbology",
+];
 
 #[derive(Debug, Deserialize)]
 struct SearchResponse {
+    /// Matches across all pages; Scryfall omits it on some list responses
+    #[serde(default)]
+    total_cards: Option<usize>,
     data: Vec<serde_json::Value>,
     has_more: bool,
     next_page: Option<String>,
 }
 
+/// Cards collected from a paginated Scryfall search
+#[derive(Debug, Default)]
+pub struct SearchResults {
+    pub cards: Vec<Card>,
+    /// Matching cards reported by Scryfall, which exceeds `cards.len()` when truncated
+    pub total_cards: usize,
+    /// More pages were available but not fetched because of the page or card cap
+    pub truncated: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct CollectionResponse {
     data: Vec<serde_json::Value>,
 }
 
-/// Rate-limited Scryfall API client with circuit breaker
+/// A server the client sends Scryfall API requests to, with its own rate limit
 #[derive(Clone)]
-pub struct ScryfallClient {
+struct Upstream {
+    name: String,
+    base_url: String,
     rate_limiter: RateLimiter,
+}
+
+/// Rate-limited Scryfall API client with a circuit breaker per endpoint. Requests go to
+/// the first configured upstream that is up, failing over to the next one while an
+/// upstream is unreachable, answers with server errors or stays rate limited.
+#[derive(Clone)]
+pub struct ScryfallClient {
+    /// In priority order; never empty
+    upstreams: Vec<Upstream>,
     http_client: reqwest::Client,
-    circuit_breaker: Arc<CircuitBreaker>,
+    circuit_breakers: CircuitBreakerRegistry,
+    retry: RetryConfig,
+    hedge: HedgeConfig,
+    /// Recent latencies per endpoint, for the hedge delay
+    latencies: Arc<LatencyTracker>,
+    search_max_pages: usize,
+    search_max_cards: usize,
 }
 
 impl ScryfallClient {
     pub fn new(config: &ScryfallConfig) -> Self {
-        let rate_limiter = RateLimiter::new(config.rate_limit_per_second);
-        let cb_config = CircuitBreakerConfig::from_env();
-        let circuit_breaker = Arc::new(CircuitBreaker::new("scryfall_api", cb_config));
+        let upstreams: Vec<Upstream> = if config.upstreams.is_empty() {
+            vec![UpstreamConfig::scryfall(config.rate_limit_per_second)]
+        } else {
+            config.upstreams.clone()
+        }
+        .into_iter()
+        .map(|upstream| Upstream {
+            name: upstream.name(),
+            rate_limiter: RateLimiter::new(upstream.rate_limit_per_second),
+            base_url: upstream.base_url,
+        })
+        .collect();
+
+        // With several upstreams each gets its own breakers, named `<endpoint>@<upstream>`
+        let circuit_breakers = if upstreams.len() == 1 {
+            endpoint_circuit_breakers(config.circuit_breaker.clone())
+        } else {
+            let circuit_breakers = CircuitBreakerRegistry::new(config.circuit_breaker.clone());
+            for upstream in &upstreams {
+                for endpoint in ENDPOINTS {
+                    circuit_breakers.get(&format!("{}@{}", endpoint, upstream.name));
+                }
+            }
+            circuit_breakers
+        };
 
         // Build HTTP client with required headers
         let http_client = reqwest::Client::builder()
@@ -51,118 +129,380 @@ impl ScryfallClient {
             .build()
             .expect("Failed to build HTTP client");
 
-        info!(
-            "Initialized Scryfall client with rate limit: {} req/sec",
-            config.rate_limit_per_second
-        );
+        for upstream in &upstreams {
+            info!(
+                "Initialized Scryfall upstream {} with rate limit: {} req/sec",
+                upstream.base_url,
+                upstream.rate_limiter.requests_per_second()
+            );
+        }
 
         Self {
-            rate_limiter,
+            upstreams,
             http_client,
-            circuit_breaker,
+            circuit_breakers,
+            retry: config.retry.clone(),
+            hedge: config.hedge.clone(),
+            latencies: Arc::new(LatencyTracker::new()),
+            search_max_pages: config.search_max_pages,
+            search_max_cards: config.search_max_cards,
         }
     }
 
-    /// Make an HTTP request through the circuit breaker
-    async fn make_request(&self, endpoint: &'static str, url: String) -> Result<reqwest::Response> {
-        SCRYFALL_API_CALLS_TOTAL.with_label_values(&[endpoint]).inc();
-
-        // Wait for rate limit first
-        self.rate_limiter.acquire().await;
+    /// Circuit breakers guarding each Scryfall endpoint
+    pub fn circuit_breakers(&self) -> &CircuitBreakerRegistry {
+        &self.circuit_breakers
+    }
 
-        // Execute through circuit breaker
+    /// Make a GET request through the circuit breakers. `target` is either a path, tried
+    /// on each upstream in order, or an absolute URL returned by an upstream (a search's
+    /// `next_page`), sent to that upstream only since its page links only work there.
+    /// With hedging enabled, a request still unanswered after the endpoint's hedge delay
+    /// gets a second attempt and the first success is used.
+    async fn make_request(
+        &self,
+        endpoint: &'static str,
+        target: String,
+    ) -> ServiceResult<reqwest::Response> {
         let client = self.http_client.clone();
-        match self
-            .circuit_breaker
-            .call(async move {
-                client
-                    .get(&url)
-                    .send()
-                    .await
-                    .context("Failed to send request to Scryfall")
-            })
-            .await
-        {
-            Ok(response) => Ok(response),
-            Err(CircuitBreakerError::Open) => {
-                warn!("Circuit breaker open, request rejected");
-                Err(anyhow::anyhow!(
-                    "Circuit breaker is open - Scryfall API unavailable"
-                ))
+        let relative = target.starts_with('/');
+        let build_request = |base: &str| {
+            if relative {
+                client.get(format!("{}{}", base, target))
+            } else {
+                client.get(&target)
+            }
+        };
+        let upstreams = if relative {
+            self.upstreams.as_slice()
+        } else {
+            let upstream = self
+                .upstreams
+                .iter()
+                .find(|upstream| target.starts_with(&upstream.base_url))
+                .unwrap_or(&self.upstreams[0]);
+            std::slice::from_ref(upstream)
+        };
+
+        if !self.hedge.enabled {
+            return self
+                .send_with_failover(endpoint, upstreams, &build_request)
+                .await;
+        }
+
+        let started = Instant::now();
+        let primary = self.send_with_failover(endpoint, upstreams, &build_request);
+        tokio::pin!(primary);
+        let delay = self.latencies.hedge_delay(endpoint, &self.hedge);
+        let result = match tokio::time::timeout(delay, &mut primary).await {
+            Ok(result) => result,
+            Err(_) => {
+                let upstream = &upstreams[0];
+                self.race_hedged(endpoint, upstream, primary, || {
+                    build_request(&upstream.base_url)
+                })
+                .await
             }
-            Err(CircuitBreakerError::Inner(e)) => Err(e),
+        };
+        if result.is_ok() {
+            self.latencies.record(endpoint, started.elapsed());
+        }
+        result
+    }
+
+    /// Race a request that outlived the hedge delay against a second attempt on `upstream`,
+    /// returning the first success (or the original request's result if both fail). The
+    /// second attempt is only sent when `upstream`'s rate limiter has a spare slot.
+    async fn race_hedged<P, F>(
+        &self,
+        endpoint: &'static str,
+        upstream: &Upstream,
+        mut primary: Pin<&mut P>,
+        build_request: F,
+    ) -> ServiceResult<reqwest::Response>
+    where
+        P: Future<Output = ServiceResult<reqwest::Response>>,
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        if !upstream.rate_limiter.try_acquire() {
+            debug!(
+                "No spare request slot, not hedging slow {} request",
+                endpoint
+            );
+            return primary.await;
+        }
+
+        debug!("Hedging slow {} request on {}", endpoint, upstream.name);
+        SCRYFALL_HEDGED_REQUESTS_TOTAL
+            .with_label_values(&[endpoint])
+            .inc();
+        let hedged = self.send_with_retry(endpoint, upstream, true, build_request);
+        tokio::pin!(hedged);
+
+        tokio::select! {
+            result = &mut primary => match result {
+                Ok(response) => Ok(response),
+                Err(e) => hedged.await.map_err(|_| e),
+            },
+            result = &mut hedged => match result {
+                Ok(response) => {
+                    SCRYFALL_HEDGE_WINS_TOTAL.with_label_values(&[endpoint]).inc();
+                    Ok(response)
+                }
+                Err(_) => primary.await,
+            },
         }
     }
 
-    /// Make a POST JSON request through the circuit breaker
+    /// Make a POST JSON request for `path` through the circuit breakers
     async fn make_post_json(
         &self,
         endpoint: &'static str,
-        url: String,
+        path: &str,
         body: serde_json::Value,
-    ) -> Result<reqwest::Response> {
-        SCRYFALL_API_CALLS_TOTAL.with_label_values(&[endpoint]).inc();
+    ) -> ServiceResult<reqwest::Response> {
+        let client = self.http_client.clone();
+        self.send_with_failover(endpoint, &self.upstreams, |base| {
+            client.post(format!("{}{}", base, path)).json(&body)
+        })
+        .await
+    }
 
-        // Wait for rate limit first
-        self.rate_limiter.acquire().await;
+    /// Circuit breaker name for `endpoint` on `upstream`
+    fn breaker_name(&self, endpoint: &str, upstream: &Upstream) -> String {
+        if self.upstreams.len() == 1 {
+            endpoint.to_string()
+        } else {
+            format!("{}@{}", endpoint, upstream.name)
+        }
+    }
 
-        // Execute through circuit breaker
-        let client = self.http_client.clone();
-        match self
-            .circuit_breaker
-            .call(async move {
-                client
-                    .post(&url)
-                    .json(&body)
-                    .send()
-                    .await
-                    .context("Failed to send request to Scryfall")
-            })
-            .await
-        {
-            Ok(response) => Ok(response),
-            Err(CircuitBreakerError::Open) => {
-                warn!("Circuit breaker open, request rejected");
-                Err(anyhow::anyhow!(
-                    "Circuit breaker is open - Scryfall API unavailable"
-                ))
+    /// Send a request to the first of `upstreams` that is up. An upstream whose breaker is
+    /// open, that can't be reached, or that answers with a server error or a 429 it keeps
+    /// repeating is skipped for the next one; the last upstream's answer is returned as is.
+    async fn send_with_failover<F>(
+        &self,
+        endpoint: &'static str,
+        upstreams: &[Upstream],
+        build_request: F,
+    ) -> ServiceResult<reqwest::Response>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        for (index, upstream) in upstreams.iter().enumerate() {
+            let result = self
+                .send_with_retry(endpoint, upstream, false, || {
+                    build_request(&upstream.base_url)
+                })
+                .await;
+            if index + 1 == upstreams.len() {
+                return result;
             }
-            Err(CircuitBreakerError::Inner(e)) => Err(e),
+
+            let reason = match &result {
+                Ok(response)
+                    if response.status().is_server_error()
+                        || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS =>
+                {
+                    response.status().to_string()
+                }
+                Ok(_) => return result,
+                Err(e) => e.to_string(),
+            };
+            warn!(
+                "Scryfall upstream {} unavailable for {} ({}), failing over to {}",
+                upstream.name,
+                endpoint,
+                reason,
+                upstreams[index + 1].name
+            );
+            SCRYFALL_UPSTREAM_FAILOVERS_TOTAL
+                .with_label_values(&[&upstream.name])
+                .inc();
         }
+        unreachable!("a client always has at least one upstream")
+    }
+
+    /// Send a request to `upstream` through its rate limiter and circuit breaker. A 429
+    /// response slows the rate limiter down and is retried after `Retry-After` (or
+    /// exponential backoff) until the retry count or time budget runs out, then returned
+    /// to the caller as is. `slot_acquired` means the caller already took the rate limiter
+    /// slot for the first attempt.
+    async fn send_with_retry<F>(
+        &self,
+        endpoint: &'static str,
+        upstream: &Upstream,
+        slot_acquired: bool,
+        build_request: F,
+    ) -> ServiceResult<reqwest::Response>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let breaker = self
+            .circuit_breakers
+            .get(&self.breaker_name(endpoint, upstream));
+        let mut attempt = 0;
+        let mut waited = Duration::ZERO;
+
+        loop {
+            SCRYFALL_API_CALLS_TOTAL
+                .with_label_values(&[endpoint])
+                .inc();
+
+            // Wait for rate limit first
+            if attempt > 0 || !slot_acquired {
+                upstream.rate_limiter.acquire().await;
+            }
+
+            // Execute through circuit breaker
+            let request = build_request();
+            let response = match breaker
+                .call(async move {
+                    request
+                        .send()
+                        .await
+                        .context("Failed to send request to Scryfall")
+                })
+                .await
+            {
+                Ok(response) => response,
+                Err(CircuitBreakerError::Open) => {
+                    warn!(
+                        "Circuit breaker open for {}, request rejected",
+                        breaker.name()
+                    );
+                    return Err(ServiceError::CircuitOpen(breaker.name().to_string()));
+                }
+                Err(CircuitBreakerError::Inner(e)) => return Err(ServiceError::ScryfallApi(e)),
+            };
+
+            if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            upstream.rate_limiter.throttle(self.retry.throttle_duration);
+
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            let delay = self.retry.delay(attempt, retry_after);
+            if attempt >= self.retry.max_retries || waited + delay > self.retry.budget {
+                warn!(
+                    "Scryfall {} still rate limited after {} retries, giving up",
+                    endpoint, attempt
+                );
+                return Ok(response);
+            }
+
+            warn!(
+                "Scryfall {} returned 429, retrying in {:?} (retry {}/{})",
+                endpoint,
+                delay,
+                attempt + 1,
+                self.retry.max_retries
+            );
+            SCRYFALL_RATE_LIMIT_WAITS_TOTAL.with_label_values(&[]).inc();
+            tokio::time::sleep(delay).await;
+            waited += delay;
+            attempt += 1;
+        }
+    }
+
+    /// Cheap reachability check against the bulk data index. Bypasses the circuit
+    /// breakers so it reports on the upstreams themselves rather than our view of them;
+    /// only server errors and failed connections count as unreachable. Succeeds as soon as
+    /// one upstream is reachable.
+    pub async fn probe(&self) -> ServiceResult<()> {
+        let mut result = Ok(());
+        for upstream in &self.upstreams {
+            result = self.probe_upstream(upstream).await;
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+
+    async fn probe_upstream(&self, upstream: &Upstream) -> ServiceResult<()> {
+        SCRYFALL_API_CALLS_TOTAL.with_label_values(&["probe"]).inc();
+        upstream.rate_limiter.acquire().await;
+
+        let response = self
+            .http_client
+            .get(format!("{}/bulk-data", upstream.base_url))
+            .timeout(PROBE_TIMEOUT)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", upstream.name))
+            .map_err(ServiceError::ScryfallApi)?;
+
+        if response.status().is_server_error() {
+            return Err(status_error(response).await);
+        }
+        Ok(())
     }
 
     /// Search for cards using Scryfall query syntax
-    pub async fn search_cards(&self, query: &str) -> Result<Vec<Card>> {
+    /// Stops following `next_page` at `SCRYFALL_SEARCH_MAX_PAGES` pages or
+    /// `SCRYFALL_SEARCH_MAX_CARDS` cards, marking the results as truncated.
+    pub async fn search_cards(&self, query: &str) -> ServiceResult<SearchResults> {
+        self.search_all_pages(query, false, true).await
+    }
+
+    /// Search for cards, treating Scryfall's 404 "no cards found" response as an
+    /// empty result instead of an error. Follows every page: used by incremental sync,
+    /// which must see all changed cards.
+    pub async fn search_cards_allow_empty(&self, query: &str) -> ServiceResult<Vec<Card>> {
+        Ok(self.search_all_pages(query, true, false).await?.cards)
+    }
+
+    async fn search_all_pages(
+        &self,
+        query: &str,
+        allow_empty: bool,
+        capped: bool,
+    ) -> ServiceResult<SearchResults> {
         debug!("Searching Scryfall for: {}", query);
 
         let mut cards = Vec::new();
-        let mut next_page: Option<String> = Some(format!(
-            "{}/cards/search?q={}",
-            SCRYFALL_API_BASE,
-            urlencoding::encode(query)
-        ));
+        let mut pages = 0;
+        let mut truncated = false;
+        let mut total_cards = None;
+        // The first page is a path tried on every upstream, later ones the absolute
+        // `next_page` links of the upstream that answered
+        let mut next_page: Option<String> =
+            Some(format!("/cards/search?q={}", urlencoding::encode(query)));
 
         while let Some(url) = next_page {
+            // Stop paginating once the request that needs these results has run out of time
+            if deadline::expired() {
+                warn!(
+                    "Request deadline passed after {} cards, abandoning Scryfall search: {}",
+                    cards.len(),
+                    query
+                );
+                return Err(ServiceError::Timeout("Scryfall search".to_string()));
+            }
+
             // Make request through circuit breaker
             let response = self.make_request("cards_search", url.clone()).await?;
 
+            if allow_empty && response.status() == reqwest::StatusCode::NOT_FOUND {
+                break;
+            }
+
             if !response.status().is_success() {
-                let status = response.status();
-                SCRYFALL_API_ERRORS_TOTAL
-                    .with_label_values(&[&status.as_u16().to_string()])
-                    .inc();
-                let error_text = response.text().await.unwrap_or_default();
-                return Err(anyhow::anyhow!(
-                    "Scryfall API error: {} - {}",
-                    status,
-                    error_text
-                ));
+                return Err(status_error(response).await);
             }
 
             let search_response: SearchResponse = response
                 .json()
                 .await
-                .context("Failed to parse Scryfall response")?;
+                .context("Failed to parse Scryfall response")
+                .map_err(ServiceError::ScryfallApi)?;
+            total_cards = total_cards.or(search_response.total_cards);
 
             // Convert each card
             for card_json in search_response.data {
@@ -174,12 +514,31 @@ impl ScryfallClient {
                 }
             }
 
+            pages += 1;
+
             // Check if there are more pages
             if search_response.has_more {
                 next_page = search_response.next_page;
             } else {
                 next_page = None;
             }
+
+            if capped && next_page.is_some() && self.search_cap_reached(pages, cards.len()) {
+                warn!(
+                    "Stopping Scryfall search after {} pages ({} cards), more results \
+                     available: {}",
+                    pages,
+                    cards.len(),
+                    query
+                );
+                truncated = true;
+                break;
+            }
+        }
+
+        if capped && self.search_max_cards > 0 && cards.len() > self.search_max_cards {
+            cards.truncate(self.search_max_cards);
+            truncated = true;
         }
 
         info!(
@@ -187,20 +546,31 @@ impl ScryfallClient {
             cards.len(),
             query
         );
-        Ok(cards)
+        // Cards that fail to convert are dropped, so only trust Scryfall's count when we
+        // didn't fetch every page
+        let total_cards = match total_cards {
+            Some(total) if truncated => total.max(cards.len()),
+            _ => cards.len(),
+        };
+        Ok(SearchResults {
+            cards,
+            total_cards,
+            truncated,
+        })
+    }
+
+    /// Whether a capped search has fetched as many pages or cards as it may
+    fn search_cap_reached(&self, pages: usize, cards: usize) -> bool {
+        (self.search_max_pages > 0 && pages >= self.search_max_pages)
+            || (self.search_max_cards > 0 && cards >= self.search_max_cards)
     }
 
     /// Get a card by exact name
-    pub async fn get_card_by_name(&self, name: &str, fuzzy: bool) -> Result<Option<Card>> {
+    pub async fn get_card_by_name(&self, name: &str, fuzzy: bool) -> ServiceResult<Option<Card>> {
         debug!("Fetching card by name: {} (fuzzy={})", name, fuzzy);
 
         let endpoint = if fuzzy { "fuzzy" } else { "exact" };
-        let url = format!(
-            "{}/cards/named?{}={}",
-            SCRYFALL_API_BASE,
-            endpoint,
-            urlencoding::encode(name)
-        );
+        let url = format!("/cards/named?{}={}", endpoint, urlencoding::encode(name));
 
         let response = self.make_request("cards_named", url).await?;
 
@@ -209,34 +579,46 @@ impl ScryfallClient {
         }
 
         if !response.status().is_success() {
-            let status = response.status();
-            SCRYFALL_API_ERRORS_TOTAL
-                .with_label_values(&[&status.as_u16().to_string()])
-                .inc();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Scryfall API error: {} - {}",
-                status,
-                error_text
-            ));
+            return Err(status_error(response).await);
         }
 
         let card_json: serde_json::Value = response
             .json()
             .await
-            .context("Failed to parse Scryfall response")?;
+            .context("Failed to parse Scryfall response")
+            .map_err(ServiceError::ScryfallApi)?;
 
-        let card =
-            Card::from_scryfall_json(card_json).context("Failed to convert Scryfall card")?;
+        let card = Card::from_scryfall_json(card_json)
+            .context("Failed to convert Scryfall card")
+            .map_err(ServiceError::ScryfallApi)?;
 
         Ok(Some(card))
     }
 
+    /// Get a card by name, printed in `lang`. Scryfall's named lookup only returns English
+    /// printings, so this runs a language-filtered search and takes the first match.
+    pub async fn get_card_by_name_localized(
+        &self,
+        name: &str,
+        fuzzy: bool,
+        lang: &str,
+    ) -> ServiceResult<Option<Card>> {
+        let name_query = if fuzzy {
+            name.to_string()
+        } else {
+            format!("!\"{}\"", name.replace('"', ""))
+        };
+        let query = format!("{} lang:{}", name_query, lang);
+
+        let cards = self.search_cards_allow_empty(&query).await?;
+        Ok(cards.into_iter().next())
+    }
+
     /// Get a card by Scryfall ID
-    pub async fn get_card_by_id(&self, id: uuid::Uuid) -> Result<Option<Card>> {
+    pub async fn get_card_by_id(&self, id: uuid::Uuid) -> ServiceResult<Option<Card>> {
         debug!("Fetching card by ID: {}", id);
 
-        let url = format!("{}/cards/{}", SCRYFALL_API_BASE, id);
+        let url = format!("/cards/{}", id);
 
         let response = self.make_request("cards_id", url).await?;
 
@@ -245,69 +627,170 @@ impl ScryfallClient {
         }
 
         if !response.status().is_success() {
-            let status = response.status();
-            SCRYFALL_API_ERRORS_TOTAL
-                .with_label_values(&[&status.as_u16().to_string()])
-                .inc();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Scryfall API error: {} - {}",
-                status,
-                error_text
-            ));
+            return Err(status_error(response).await);
         }
 
         let card_json: serde_json::Value = response
             .json()
             .await
-            .context("Failed to parse Scryfall response")?;
+            .context("Failed to parse Scryfall response")
+            .map_err(ServiceError::ScryfallApi)?;
 
-        let card =
-            Card::from_scryfall_json(card_json).context("Failed to convert Scryfall card")?;
+        let card = Card::from_scryfall_json(card_json)
+            .context("Failed to convert Scryfall card")
+            .map_err(ServiceError::ScryfallApi)?;
 
         Ok(Some(card))
     }
 
+    /// Get a printing by set code and collector number
+    pub async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>> {
+        debug!(
+            "Fetching card by set and collector number: {}/{}",
+            set_code, collector_number
+        );
+
+        let url = format!(
+            "/cards/{}/{}",
+            urlencoding::encode(&set_code.to_lowercase()),
+            urlencoding::encode(collector_number)
+        );
+
+        let response = self.make_request("cards_set_number", url).await?;
+
+        if response.status() == 404 {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error(response).await);
+        }
+
+        let card_json: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to parse Scryfall response")
+            .map_err(ServiceError::ScryfallApi)?;
+
+        let card = Card::from_scryfall_json(card_json)
+            .context("Failed to convert Scryfall card")
+            .map_err(ServiceError::ScryfallApi)?;
+
+        Ok(Some(card))
+    }
+
+    /// Get a card by its multiverse, MTG Arena or MTGO id
+    pub async fn get_card_by_external_id(
+        &self,
+        external_id: ExternalId,
+    ) -> ServiceResult<Option<Card>> {
+        debug!("Fetching card by external id: {}", external_id);
+
+        let url = format!("/cards/{}", external_id);
+        let response = self.make_request("cards_external_id", url).await?;
+
+        if response.status() == 404 {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error(response).await);
+        }
+
+        let card_json: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to parse Scryfall response")
+            .map_err(ServiceError::ScryfallApi)?;
+
+        let card = Card::from_scryfall_json(card_json)
+            .context("Failed to convert Scryfall card")
+            .map_err(ServiceError::ScryfallApi)?;
+
+        Ok(Some(card))
+    }
+
+    /// Fetch a non-card API resource (e.g. `/sets`, `/catalog/card-names`) as raw JSON.
+    /// Returns `None` if Scryfall responds with 404.
+    pub async fn get_json(
+        &self,
+        endpoint: &'static str,
+        path: &str,
+    ) -> ServiceResult<Option<serde_json::Value>> {
+        debug!("Fetching Scryfall resource: {}", path);
+
+        let response = self.make_request(endpoint, path.to_string()).await?;
+
+        if response.status() == 404 {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error(response).await);
+        }
+
+        let value = response
+            .json()
+            .await
+            .context("Failed to parse Scryfall response")
+            .map_err(ServiceError::ScryfallApi)?;
+
+        Ok(Some(value))
+    }
+
     /// Fetch multiple cards by ID using Scryfall's collection endpoint (chunked).
     /// This avoids N per-card GETs and is typically much faster.
-    pub async fn get_cards_by_ids_collection(&self, ids: &[uuid::Uuid]) -> Result<Vec<Card>> {
-        if ids.is_empty() {
-            return Ok(Vec::new());
-        }
+    pub async fn get_cards_by_ids_collection(
+        &self,
+        ids: &[uuid::Uuid],
+    ) -> ServiceResult<Vec<Card>> {
+        let identifiers: Vec<serde_json::Value> = ids
+            .iter()
+            .map(|id| serde_json::json!({ "id": id.to_string() }))
+            .collect();
+        self.get_cards_collection(&identifiers).await
+    }
 
-        let mut all_cards = Vec::new();
+    /// Fetch multiple cards by exact name using Scryfall's collection endpoint (chunked).
+    /// Names are matched case-insensitively; unknown names are left out of the result.
+    pub async fn get_cards_by_names_collection(
+        &self,
+        names: &[String],
+    ) -> ServiceResult<Vec<Card>> {
+        let identifiers: Vec<serde_json::Value> = names
+            .iter()
+            .map(|name| serde_json::json!({ "name": name }))
+            .collect();
+        self.get_cards_collection(&identifiers).await
+    }
 
-        // Scryfall cards/collection supports up to 75 identifiers per request.
-        for chunk in ids.chunks(75) {
-            let identifiers: Vec<serde_json::Value> = chunk
-                .iter()
-                .map(|id| serde_json::json!({ "id": id.to_string() }))
-                .collect();
+    /// POST `identifiers` to /cards/collection, 75 at a time (Scryfall's limit)
+    async fn get_cards_collection(
+        &self,
+        identifiers: &[serde_json::Value],
+    ) -> ServiceResult<Vec<Card>> {
+        let mut all_cards = Vec::new();
 
-            let url = format!("{}/cards/collection", SCRYFALL_API_BASE);
-            let body = serde_json::json!({ "identifiers": identifiers });
+        for chunk in identifiers.chunks(75) {
+            let body = serde_json::json!({ "identifiers": chunk });
 
             let response = self
-                .make_post_json("cards_collection", url, body)
+                .make_post_json("cards_collection", "/cards/collection", body)
                 .await?;
 
             if !response.status().is_success() {
-                let status = response.status();
-                SCRYFALL_API_ERRORS_TOTAL
-                    .with_label_values(&[&status.as_u16().to_string()])
-                    .inc();
-                let error_text = response.text().await.unwrap_or_default();
-                return Err(anyhow::anyhow!(
-                    "Scryfall API error: {} - {}",
-                    status,
-                    error_text
-                ));
+                return Err(status_error(response).await);
             }
 
             let collection: CollectionResponse = response
                 .json()
                 .await
-                .context("Failed to parse Scryfall collection response")?;
+                .context("Failed to parse Scryfall collection response")
+                .map_err(ServiceError::ScryfallApi)?;
 
             for card_json in collection.data {
                 if let Ok(card) = Card::from_scryfall_json(card_json) {
@@ -320,6 +803,25 @@ impl ScryfallClient {
     }
 }
 
+/// Circuit breaker registry with a breaker for every Scryfall endpoint already registered
+pub fn endpoint_circuit_breakers(config: CircuitBreakerConfig) -> CircuitBreakerRegistry {
+    let circuit_breakers = CircuitBreakerRegistry::new(config);
+    for endpoint in ENDPOINTS {
+        circuit_breakers.get(endpoint);
+    }
+    circuit_breakers
+}
+
+/// Turn a non-success Scryfall response into an error, counting it by status code
+async fn status_error(response: reqwest::Response) -> ServiceError {
+    let status = response.status();
+    SCRYFALL_API_ERRORS_TOTAL
+        .with_label_values(&[&status.as_u16().to_string()])
+        .inc();
+    let error_text = response.text().await.unwrap_or_default();
+    ServiceError::ScryfallApi(anyhow::anyhow!("{} - {}", status, error_text))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -328,11 +830,183 @@ mod tests {
     async fn test_rate_limiter_initialization() {
         let config = ScryfallConfig {
             rate_limit_per_second: 10,
-            bulk_data_type: "default_cards".to_string(),
+            bulk_data_types: vec!["default_cards".to_string()],
+            bulk_languages: Vec::new(),
             cache_ttl_hours: 24,
+            bulk_check_upstream: true,
+            bulk_download_dir: std::env::temp_dir(),
+            bulk_distributed_lock: false,
+            circuit_breaker: Default::default(),
+            retry: Default::default(),
+            search_max_pages: 20,
+            search_max_cards: 3500,
+            upstreams: Vec::new(),
+            hedge: Default::default(),
         };
 
         let client = ScryfallClient::new(&config);
-        assert_eq!(client.rate_limiter.requests_per_second(), 10);
+        assert_eq!(client.upstreams[0].rate_limiter.requests_per_second(), 10);
+        assert_eq!(client.upstreams[0].base_url, "https://api.scryfall.com");
+        assert!(client.circuit_breakers().find("cards_search").is_some());
+    }
+
+    #[test]
+    fn test_search_cap_reached() {
+        let mut config = ScryfallConfig {
+            rate_limit_per_second: 10,
+            bulk_data_types: vec!["default_cards".to_string()],
+            bulk_languages: Vec::new(),
+            cache_ttl_hours: 24,
+            bulk_check_upstream: true,
+            bulk_download_dir: std::env::temp_dir(),
+            bulk_distributed_lock: false,
+            circuit_breaker: Default::default(),
+            retry: Default::default(),
+            search_max_pages: 2,
+            search_max_cards: 300,
+            upstreams: Vec::new(),
+            hedge: Default::default(),
+        };
+        let client = ScryfallClient::new(&config);
+        assert!(!client.search_cap_reached(1, 175));
+        assert!(client.search_cap_reached(2, 200));
+        assert!(client.search_cap_reached(1, 300));
+
+        config.search_max_pages = 0;
+        config.search_max_cards = 0;
+        let client = ScryfallClient::new(&config);
+        assert!(!client.search_cap_reached(1000, 175_000));
+    }
+
+    const CARD_JSON: &str =
+        r#"{"object":"card","id":"0000579f-7b35-4ed3-b44c-db2a538066fe","name":"Fury Sliver"}"#;
+
+    fn test_config(upstreams: Vec<UpstreamConfig>, hedge: HedgeConfig) -> ScryfallConfig {
+        ScryfallConfig {
+            rate_limit_per_second: 10,
+            bulk_data_types: vec!["default_cards".to_string()],
+            bulk_languages: Vec::new(),
+            cache_ttl_hours: 24,
+            bulk_check_upstream: true,
+            bulk_download_dir: std::env::temp_dir(),
+            bulk_distributed_lock: false,
+            circuit_breaker: Default::default(),
+            retry: Default::default(),
+            search_max_pages: 20,
+            search_max_cards: 3500,
+            upstreams,
+            hedge,
+        }
+    }
+
+    /// Local upstream answering every request with `body`, the first one only after
+    /// `first_delay`
+    async fn serve(body: &'static str, first_delay: Duration) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut delay = first_delay;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let wait = std::mem::take(&mut delay);
+                tokio::spawn(async move {
+                    let mut request = [0u8; 4096];
+                    let _ = socket.read(&mut request).await;
+                    tokio::time::sleep(wait).await;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                         content-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_next_upstream() {
+        // Nothing listens on a port once its listener is dropped
+        let down = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let up = serve(CARD_JSON, Duration::ZERO).await;
+        let upstreams =
+            crate::scryfall::upstream::parse_upstreams(&format!("{},{}", down, up), 10).unwrap();
+        let config = test_config(upstreams, HedgeConfig::default());
+        let client = ScryfallClient::new(&config);
+        let down_name = config.upstreams[0].name();
+        let up_name = config.upstreams[1].name();
+        assert!(client.circuit_breakers().find("cards_id").is_none());
+
+        let card = client.get_card_by_id(uuid::Uuid::nil()).await.unwrap();
+        assert_eq!(card.unwrap().name, "Fury Sliver");
+        let down_breaker = client
+            .circuit_breakers()
+            .find(&format!("cards_id@{}", down_name))
+            .unwrap();
+        let up_breaker = client
+            .circuit_breakers()
+            .find(&format!("cards_id@{}", up_name))
+            .unwrap();
+        assert_eq!(down_breaker.metrics().await.failure_count, 1);
+        assert_eq!(up_breaker.metrics().await.failure_count, 0);
+        assert!(client.probe().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_truncated_search_reports_scryfall_total() {
+        const PAGE_JSON: &str = r#"{"object":"list","total_cards":1200,"has_more":true,
+            "next_page":"http://127.0.0.1:9/cards/search?page=2","data":[
+            {"object":"card","id":"0000579f-7b35-4ed3-b44c-db2a538066fe","name":"Fury Sliver"}]}"#;
+
+        let upstream = serve(PAGE_JSON, Duration::ZERO).await;
+        let config = ScryfallConfig {
+            search_max_pages: 1,
+            ..test_config(
+                crate::scryfall::upstream::parse_upstreams(&upstream, 10).unwrap(),
+                HedgeConfig::default(),
+            )
+        };
+        let client = ScryfallClient::new(&config);
+
+        let results = client.search_cards("t:sliver").await.unwrap();
+        assert!(results.truncated);
+        assert_eq!(results.cards.len(), 1);
+        assert_eq!(results.total_cards, 1200);
+    }
+
+    #[tokio::test]
+    async fn test_hedges_slow_request() {
+        let upstream = serve(CARD_JSON, Duration::from_secs(5)).await;
+        let hedge = HedgeConfig {
+            enabled: true,
+            percentile: 95.0,
+            min_delay: Duration::from_millis(50),
+            max_delay: Duration::from_millis(50),
+        };
+        let config = test_config(
+            crate::scryfall::upstream::parse_upstreams(&upstream, 10).unwrap(),
+            hedge,
+        );
+        let client = ScryfallClient::new(&config);
+        let wins = SCRYFALL_HEDGE_WINS_TOTAL
+            .with_label_values(&["cards_id"])
+            .get();
+
+        let started = Instant::now();
+        let card = client.get_card_by_id(uuid::Uuid::nil()).await.unwrap();
+        assert_eq!(card.unwrap().name, "Fury Sliver");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            SCRYFALL_HEDGE_WINS_TOTAL
+                .with_label_values(&["cards_id"])
+                .get(),
+            wins + 1
+        );
     }
 }
@@ -0,0 +1,140 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::Settings;
+
+/// Latencies kept per endpoint to estimate when a request counts as slow
+const LATENCY_WINDOW: usize = 200;
+
+/// Samples needed before the percentile is trusted; until then hedges wait `max_delay`
+const MIN_SAMPLES: usize = 20;
+
+/// Hedged requests: when a lookup is still unanswered after the endpoint's usual latency,
+/// a second identical request is sent and whichever succeeds first is used
+#[derive(Debug, Clone)]
+pub struct HedgeConfig {
+    pub enabled: bool,
+    /// Latency percentile (of recent successful requests) after which a hedge is sent
+    pub percentile: f64,
+    /// Shortest wait before hedging, however fast the endpoint usually is
+    pub min_delay: Duration,
+    /// Longest wait before hedging, also used until enough latencies are recorded
+    pub max_delay: Duration,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            percentile: 95.0,
+            min_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl HedgeConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        let min_delay = Duration::from_millis(
+            settings
+                .var("SCRYFALL_HEDGE_MIN_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
+        );
+        Self {
+            enabled: settings
+                .var("SCRYFALL_HEDGE_ENABLED")
+                .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(defaults.enabled),
+            percentile: settings
+                .var("SCRYFALL_HEDGE_PERCENTILE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|p: &f64| *p > 0.0 && *p <= 100.0)
+                .unwrap_or(defaults.percentile),
+            min_delay,
+            max_delay: Duration::from_millis(
+                settings
+                    .var("SCRYFALL_HEDGE_MAX_DELAY_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(2000),
+            )
+            .max(min_delay),
+        }
+    }
+}
+
+/// Recent latencies of successful requests, per endpoint
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    samples: Mutex<HashMap<&'static str, VecDeque<Duration>>>,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, endpoint: &'static str, latency: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        let window = samples.entry(endpoint).or_default();
+        if window.len() == LATENCY_WINDOW {
+            window.pop_front();
+        }
+        window.push_back(latency);
+    }
+
+    /// How long to wait for a request to `endpoint` before hedging it: the configured
+    /// percentile of its recent latencies, clamped to `min_delay..=max_delay`
+    pub fn hedge_delay(&self, endpoint: &str, config: &HedgeConfig) -> Duration {
+        let samples = self.samples.lock().unwrap();
+        let Some(window) = samples.get(endpoint).filter(|w| w.len() >= MIN_SAMPLES) else {
+            return config.max_delay;
+        };
+
+        let mut sorted: Vec<Duration> = window.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = ((config.percentile / 100.0) * sorted.len() as f64).ceil() as usize;
+        let latency = sorted[rank.clamp(1, sorted.len()) - 1];
+        latency.clamp(config.min_delay, config.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hedge_delay_percentile() {
+        let config = HedgeConfig {
+            enabled: true,
+            ..HedgeConfig::default()
+        };
+        let tracker = LatencyTracker::new();
+        assert_eq!(tracker.hedge_delay("cards_id", &config), config.max_delay);
+
+        for ms in 1..=100 {
+            tracker.record("cards_id", Duration::from_millis(ms * 10));
+        }
+        assert_eq!(
+            tracker.hedge_delay("cards_id", &config),
+            Duration::from_millis(950)
+        );
+
+        // Clamped to the configured bounds
+        let fast = HedgeConfig {
+            percentile: 5.0,
+            ..config.clone()
+        };
+        assert_eq!(tracker.hedge_delay("cards_id", &fast), fast.min_delay);
+        let slow = HedgeConfig {
+            max_delay: Duration::from_millis(500),
+            ..config
+        };
+        assert_eq!(tracker.hedge_delay("cards_id", &slow), slow.max_delay);
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDateTime};
+use tracing::{info, warn};
+
+use crate::db::Database;
+use crate::events::{self, CacheEvent};
+use crate::models::sync::{SyncRun, SyncStatus};
+use crate::scryfall::client::ScryfallClient;
+
+const UPSERT_BATCH_SIZE: usize = 500;
+
+/// Incremental card sync between full bulk loads.
+///
+/// Scryfall doesn't expose a per-card modification timestamp, so changed cards are found
+/// with a `date>=` search (release/preview date) starting a lookback window before the
+/// last watermark. That picks up newly previewed and released printings, which make up
+/// most changes between bulk exports, and refreshes printings released within the window.
+/// Older cards' prices, legalities and oracle errata are only updated by full bulk loads,
+/// which the refresh job still runs once the bulk data is stale. Cards are upserted and
+/// every run is written to the sync ledger.
+pub struct IncrementalSync {
+    db: Database,
+    client: ScryfallClient,
+    lookback_days: u32,
+}
+
+impl IncrementalSync {
+    pub fn new(db: Database, client: ScryfallClient, lookback_days: u32) -> Self {
+        Self {
+            db,
+            client,
+            lookback_days,
+        }
+    }
+
+    /// Point in time local data is known to be current as of: the later of the last
+    /// full bulk import and the last successful incremental sync.
+    pub async fn watermark(&self) -> Result<Option<NaiveDateTime>> {
+        let last_import = self.db.get_last_bulk_import().await?;
+        let last_sync = self.db.get_last_sync().await?;
+        Ok(last_import.max(last_sync))
+    }
+
+    /// Build the Scryfall search used to find cards changed since `since`
+    pub fn build_query(since: NaiveDateTime, lookback_days: u32) -> String {
+        let from = (since - Duration::days(lookback_days as i64)).date();
+        format!("date>={} unique:prints include:extras", from.format("%Y-%m-%d"))
+    }
+
+    /// Fetch and upsert cards changed since the watermark.
+    ///
+    /// Fails without touching the ledger if there is no watermark yet (a full load is
+    /// required first); otherwise the run is recorded whether it succeeds or not.
+    pub async fn sync(&self) -> Result<SyncRun> {
+        let started_at = chrono::Utc::now().naive_utc();
+        let since = self
+            .watermark()
+            .await?
+            .context("No previous bulk import or sync found, a full load is required")?;
+
+        info!("Starting incremental sync (since {})", since);
+        let result = self.fetch_and_upsert(since).await;
+
+        let run = SyncRun {
+            started_at,
+            completed_at: chrono::Utc::now().naive_utc(),
+            since,
+            cards_updated: result.as_ref().map(|n| *n as i32).unwrap_or(0),
+            status: if result.is_ok() {
+                SyncStatus::Success
+            } else {
+                SyncStatus::Failed
+            },
+            error: result.as_ref().err().map(|e| e.to_string()),
+        };
+
+        if let Err(e) = self.db.record_sync(&run).await {
+            warn!("Failed to record sync run in ledger: {}", e);
+        }
+
+        let updated = result?;
+        info!("Incremental sync completed: {} cards updated", updated);
+        Ok(run)
+    }
+
+    async fn fetch_and_upsert(&self, since: NaiveDateTime) -> Result<usize> {
+        let query = Self::build_query(since, self.lookback_days);
+        let cards = self
+            .client
+            .search_cards_allow_empty(&query)
+            .await
+            .context("Failed to fetch changed cards from Scryfall")?;
+
+        for batch in cards.chunks(UPSERT_BATCH_SIZE) {
+            self.db
+                .insert_cards_batch(batch)
+                .await
+                .context("Failed to upsert changed cards")?;
+            for card in batch {
+                events::publish(CacheEvent::CardUpdated {
+                    id: card.id,
+                    name: card.name.clone(),
+                });
+            }
+        }
+
+        Ok(cards.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_query_applies_lookback() {
+        let since = chrono::NaiveDate::from_ymd_opt(2024, 3, 10)
+            .unwrap()
+            .and_hms_opt(12, 30, 0)
+            .unwrap();
+        assert_eq!(
+            IncrementalSync::build_query(since, 7),
+            "date>=2024-03-03 unique:prints include:extras"
+        );
+        assert!(IncrementalSync::build_query(since, 0).starts_with("date>=2024-03-10 "));
+    }
+}
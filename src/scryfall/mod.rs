@@ -1,3 +1,9 @@
 pub mod bulk_loader;
 pub mod client;
+pub mod hedge;
+pub mod incremental;
+pub mod preview;
 pub mod rate_limiter;
+pub mod retry;
+pub mod source;
+pub mod upstream;
@@ -0,0 +1,233 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Instant;
+use tracing::{info, warn};
+
+use crate::config::{ScryfallConfig, Settings};
+use crate::db::Database;
+use crate::events::{self, CacheEvent};
+use crate::scryfall::client::ScryfallClient;
+use crate::scryfall::upstream::UpstreamConfig;
+
+const UPSERT_BATCH_SIZE: usize = 500;
+
+/// Configuration for the preview poller
+#[derive(Debug, Clone)]
+pub struct PreviewPollConfig {
+    /// Whether the poller runs (off by default; meant for preview season)
+    pub enabled: bool,
+    /// Interval between polls (minutes)
+    pub interval_minutes: u64,
+    /// Cards with a release or preview date this many days back or later are fetched
+    pub lookback_days: u32,
+    /// Requests per second to Scryfall, on a rate budget separate from the main client
+    pub rate_limit_per_second: u32,
+}
+
+impl Default for PreviewPollConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 30,
+            lookback_days: 14,
+            rate_limit_per_second: 2,
+        }
+    }
+}
+
+impl PreviewPollConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: settings
+                .var("PREVIEW_POLL_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            interval_minutes: settings
+                .var("PREVIEW_POLL_INTERVAL_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&minutes| minutes > 0)
+                .unwrap_or(defaults.interval_minutes),
+            lookback_days: settings
+                .var("PREVIEW_POLL_LOOKBACK_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.lookback_days),
+            rate_limit_per_second: settings
+                .var("PREVIEW_POLL_RATE_LIMIT_PER_SECOND")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&rate| rate > 0)
+                .unwrap_or(defaults.rate_limit_per_second),
+        }
+    }
+}
+
+/// Outcome of a preview poll
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct PreviewPollRun {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Cards fetched from Scryfall and upserted
+    pub cards_upserted: usize,
+    /// Why the poll failed, if it did
+    pub error: Option<String>,
+}
+
+/// Preview poller settings and activity since startup, for admin stats
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct PreviewPollStatus {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    pub lookback_days: u32,
+    pub rate_limit_per_second: u32,
+    /// Polls run since startup
+    pub runs: u64,
+    /// Cards upserted by all polls since startup
+    pub cards_upserted_total: u64,
+    pub last_run: Option<PreviewPollRun>,
+}
+
+/// Fetches recently released and previewed cards between bulk loads.
+///
+/// Daily bulk exports lag behind during preview season, so this polls Scryfall for every
+/// printing whose release date is within the lookback window or later (previewed cards
+/// carry their set's upcoming release date) and upserts only those. It uses its own
+/// Scryfall client so its requests don't eat into the rate budget of API traffic, and
+/// leaves the incremental sync ledger alone.
+pub struct PreviewPoller {
+    db: Database,
+    client: ScryfallClient,
+    config: PreviewPollConfig,
+    runs: AtomicU64,
+    cards_upserted_total: AtomicU64,
+    last_run: RwLock<Option<PreviewPollRun>>,
+    /// Serializes polls so a slow one doesn't overlap the next tick
+    running: tokio::sync::Mutex<()>,
+}
+
+impl PreviewPoller {
+    pub fn new(db: Database, scryfall: &ScryfallConfig, config: PreviewPollConfig) -> Self {
+        // The poller's own rate limit applies to every upstream
+        let upstreams = scryfall
+            .upstreams
+            .iter()
+            .map(|upstream| UpstreamConfig {
+                rate_limit_per_second: config.rate_limit_per_second,
+                ..upstream.clone()
+            })
+            .collect();
+        let client = ScryfallClient::new(&ScryfallConfig {
+            rate_limit_per_second: config.rate_limit_per_second,
+            upstreams,
+            ..scryfall.clone()
+        });
+        Self {
+            db,
+            client,
+            config,
+            runs: AtomicU64::new(0),
+            cards_upserted_total: AtomicU64::new(0),
+            last_run: RwLock::new(None),
+            running: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn config(&self) -> &PreviewPollConfig {
+        &self.config
+    }
+
+    /// Settings and activity since startup
+    pub fn status(&self) -> PreviewPollStatus {
+        PreviewPollStatus {
+            enabled: self.config.enabled,
+            interval_minutes: self.config.interval_minutes,
+            lookback_days: self.config.lookback_days,
+            rate_limit_per_second: self.config.rate_limit_per_second,
+            runs: self.runs.load(Ordering::Relaxed),
+            cards_upserted_total: self.cards_upserted_total.load(Ordering::Relaxed),
+            last_run: self.last_run.read().unwrap().clone(),
+        }
+    }
+
+    /// Build the Scryfall search for cards released or previewed since `today` minus
+    /// `lookback_days`
+    pub fn build_query(today: NaiveDate, lookback_days: u32) -> String {
+        let from = today - Duration::days(lookback_days as i64);
+        format!(
+            "date>={} unique:prints include:extras",
+            from.format("%Y-%m-%d")
+        )
+    }
+
+    /// Fetch and upsert recently released and previewed cards
+    pub async fn poll(&self) -> PreviewPollRun {
+        let _running = self.running.lock().await;
+        let started_at = Utc::now();
+        let start = Instant::now();
+
+        let result = self.fetch_and_upsert(started_at.date_naive()).await;
+        let run = PreviewPollRun {
+            started_at,
+            duration_ms: start.elapsed().as_millis() as u64,
+            cards_upserted: result.as_ref().copied().unwrap_or(0),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+        };
+        match &run.error {
+            None => info!(
+                "Preview poll completed: {} cards upserted in {}ms",
+                run.cards_upserted, run.duration_ms
+            ),
+            Some(error) => warn!("Preview poll failed: {}", error),
+        }
+
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        self.cards_upserted_total
+            .fetch_add(run.cards_upserted as u64, Ordering::Relaxed);
+        *self.last_run.write().unwrap() = Some(run.clone());
+        run
+    }
+
+    async fn fetch_and_upsert(&self, today: NaiveDate) -> Result<usize> {
+        let query = Self::build_query(today, self.config.lookback_days);
+        let cards = self
+            .client
+            .search_cards_allow_empty(&query)
+            .await
+            .context("Failed to fetch previewed cards from Scryfall")?;
+
+        for batch in cards.chunks(UPSERT_BATCH_SIZE) {
+            self.db
+                .insert_cards_batch(batch)
+                .await
+                .context("Failed to upsert previewed cards")?;
+            for card in batch {
+                events::publish(CacheEvent::CardUpdated {
+                    id: card.id,
+                    name: card.name.clone(),
+                });
+            }
+        }
+
+        Ok(cards.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_query_applies_lookback() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        assert_eq!(
+            PreviewPoller::build_query(today, 14),
+            "date>=2024-02-25 unique:prints include:extras"
+        );
+    }
+}
@@ -4,17 +4,27 @@ use governor::{
     Quota, RateLimiter as GovernorRateLimiter,
 };
 use std::num::NonZeroU32;
-use std::sync::Arc;
-use tokio::time::sleep;
-use tracing::debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+use tracing::{debug, warn};
 
 use crate::metrics::registry::SCRYFALL_RATE_LIMIT_WAITS_TOTAL;
 
+/// Temporarily reduced rate after Scryfall answered 429
+#[derive(Debug)]
+struct Throttle {
+    requests_per_second: u32,
+    until: Instant,
+    next_slot: Instant,
+}
+
 /// Rate limiter for Scryfall API requests
 #[derive(Clone)]
 pub struct RateLimiter {
     limiter: Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
     requests_per_second: u32,
+    throttle: Arc<Mutex<Option<Throttle>>>,
 }
 
 impl RateLimiter {
@@ -29,6 +39,7 @@ impl RateLimiter {
         Self {
             limiter: Arc::new(limiter),
             requests_per_second,
+            throttle: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -50,11 +61,70 @@ impl RateLimiter {
                 }
             }
         }
+
+        if let Some(wait_time) = self.throttle_wait() {
+            debug!("Throttled after 429, waiting {:?}", wait_time);
+            SCRYFALL_RATE_LIMIT_WAITS_TOTAL.with_label_values(&[]).inc();
+            sleep(wait_time).await;
+        }
+    }
+
+    /// Reserve the next request slot at the throttled rate, returning how long to wait for it
+    fn throttle_wait(&self) -> Option<Duration> {
+        let mut throttle = self.throttle.lock().unwrap();
+        let now = Instant::now();
+        let active = throttle.as_mut().filter(|t| t.until > now)?;
+
+        let slot = active.next_slot.max(now);
+        active.next_slot = slot + Duration::from_secs(1) / active.requests_per_second;
+        Some(slot - now).filter(|wait| !wait.is_zero())
+    }
+
+    /// Halve the effective rate (down to 1 req/sec) for `duration`. Called when Scryfall
+    /// answers 429; repeated calls keep halving and extend the slowdown.
+    pub fn throttle(&self, duration: Duration) {
+        let mut throttle = self.throttle.lock().unwrap();
+        let now = Instant::now();
+        let current = throttle
+            .as_ref()
+            .filter(|t| t.until > now)
+            .map(|t| t.requests_per_second)
+            .unwrap_or(self.requests_per_second);
+        let reduced = (current / 2).max(1);
+
+        warn!(
+            "Scryfall rate limited us; slowing to {} req/sec for {:?}",
+            reduced, duration
+        );
+        let next_slot = throttle.as_ref().map(|t| t.next_slot).unwrap_or(now);
+        *throttle = Some(Throttle {
+            requests_per_second: reduced,
+            until: now + duration,
+            next_slot,
+        });
     }
 
-    /// Try to acquire without waiting
+    /// Current effective requests per second, accounting for any 429 throttling
+    pub fn effective_requests_per_second(&self) -> u32 {
+        self.throttle
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|t| t.until > Instant::now())
+            .map(|t| t.requests_per_second)
+            .unwrap_or(self.requests_per_second)
+    }
+
+    /// Try to acquire without waiting. Always fails while throttled after a 429, so
+    /// optional requests (hedges) don't compete for the reduced rate.
     pub fn try_acquire(&self) -> bool {
-        self.limiter.check().is_ok()
+        let throttled = self
+            .throttle
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|t| t.until > Instant::now());
+        !throttled && self.limiter.check().is_ok()
     }
 
     /// Get the configured requests per second
@@ -99,4 +169,23 @@ mod tests {
         // 10 requests at 5 req/sec should take roughly >= 1s in practice.
         assert!(elapsed >= Duration::from_millis(900));
     }
+
+    #[tokio::test]
+    async fn test_throttle_halves_rate_temporarily() {
+        let limiter = RateLimiter::new(10);
+        limiter.throttle(Duration::from_secs(60));
+        assert_eq!(limiter.effective_requests_per_second(), 5);
+        limiter.throttle(Duration::from_secs(60));
+        assert_eq!(limiter.effective_requests_per_second(), 2);
+
+        // 2 req/sec: the third request waits a full second for its slot
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(900));
+
+        limiter.throttle(Duration::ZERO);
+        assert_eq!(limiter.effective_requests_per_second(), 10);
+    }
 }
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use crate::config::Settings;
+
+/// How the client retries requests Scryfall rejected with 429 Too Many Requests
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Retries per request before giving up and returning the 429
+    pub max_retries: u32,
+    /// Total time one request may spend waiting on 429s
+    pub budget: Duration,
+    /// Base delay when Scryfall doesn't send `Retry-After` (doubled per attempt)
+    pub base_delay: Duration,
+    /// How long the rate limiter stays slowed down after a 429
+    pub throttle_duration: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            budget: Duration::from_secs(30),
+            base_delay: Duration::from_secs(1),
+            throttle_duration: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            max_retries: settings
+                .var("SCRYFALL_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            budget: Duration::from_secs(
+                settings
+                    .var("SCRYFALL_RETRY_BUDGET_SECONDS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(30),
+            ),
+            base_delay: Duration::from_secs(1),
+            throttle_duration: Duration::from_secs(
+                settings
+                    .var("SCRYFALL_THROTTLE_SECONDS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(60),
+            ),
+        }
+    }
+
+    /// Delay before retry number `attempt` (0-based): `Retry-After` if Scryfall sent one,
+    /// otherwise exponential backoff, plus up to 25% jitter so concurrent requests don't
+    /// retry in lockstep
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let delay =
+            retry_after.unwrap_or_else(|| self.base_delay.saturating_mul(1 << attempt.min(10)));
+        delay + jitter(delay / 4)
+    }
+}
+
+/// Parse a `Retry-After` header: either delay-seconds or an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.signed_duration_since(chrono::Utc::now());
+    Some(delay.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Random duration in `[0, max]`
+fn jitter(max: Duration) -> Duration {
+    let max_millis = max.as_millis() as u64;
+    if max_millis == 0 {
+        return Duration::ZERO;
+    }
+    let random = uuid::Uuid::new_v4().as_u128() as u64;
+    Duration::from_millis(random % (max_millis + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("5"), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+        // Dates in the past mean "retry now"
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_delay_backoff_and_jitter() {
+        let config = RetryConfig {
+            max_retries: 3,
+            budget: Duration::from_secs(30),
+            base_delay: Duration::from_secs(1),
+            throttle_duration: Duration::from_secs(60),
+        };
+
+        let first = config.delay(0, None);
+        assert!(first >= Duration::from_secs(1) && first <= Duration::from_millis(1250));
+        let third = config.delay(2, None);
+        assert!(third >= Duration::from_secs(4) && third <= Duration::from_secs(5));
+
+        let explicit = config.delay(0, Some(Duration::from_secs(10)));
+        assert!(explicit >= Duration::from_secs(10) && explicit <= Duration::from_millis(12500));
+    }
+}
@@ -0,0 +1,365 @@
+//! Upstream card sources. `CacheManager` fetches cards missing from the database through
+//! a `CardSource`: normally the Scryfall API client, but any upstream serving Scryfall card
+//! objects (a mirror, or `MockCardSource` in tests) can stand in for it.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::circuit_breaker::{CircuitBreakerConfig, CircuitBreakerRegistry};
+use crate::errors::{ServiceError, ServiceResult};
+use crate::models::card::{Card, ExternalId};
+use crate::scryfall::client::{endpoint_circuit_breakers, ScryfallClient, SearchResults};
+
+/// Where cards missing from the database are fetched from
+#[async_trait]
+pub trait CardSource: Send + Sync {
+    /// Circuit breakers guarding each upstream endpoint
+    fn circuit_breakers(&self) -> &CircuitBreakerRegistry;
+
+    /// Cheap reachability check, failing only when the upstream itself is down
+    async fn probe(&self) -> ServiceResult<()>;
+
+    /// Search with Scryfall query syntax, possibly truncated by the source's caps
+    async fn search_cards(&self, query: &str) -> ServiceResult<SearchResults>;
+
+    /// Get a card by exact or fuzzy name
+    async fn get_card_by_name(&self, name: &str, fuzzy: bool) -> ServiceResult<Option<Card>>;
+
+    /// Get a card by name, printed in `lang`
+    async fn get_card_by_name_localized(
+        &self,
+        name: &str,
+        fuzzy: bool,
+        lang: &str,
+    ) -> ServiceResult<Option<Card>>;
+
+    /// Get a card by Scryfall id
+    async fn get_card_by_id(&self, id: uuid::Uuid) -> ServiceResult<Option<Card>>;
+
+    /// Get a printing by set code and collector number
+    async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>>;
+
+    /// Get a card by its multiverse, MTG Arena or MTGO id
+    async fn get_card_by_external_id(&self, external_id: ExternalId)
+        -> ServiceResult<Option<Card>>;
+
+    /// Fetch a non-card resource (e.g. `/sets`) as raw JSON, `None` if it doesn't exist
+    async fn get_json(
+        &self,
+        endpoint: &'static str,
+        path: &str,
+    ) -> ServiceResult<Option<serde_json::Value>>;
+
+    /// Fetch many cards by id, leaving unknown ids out
+    async fn get_cards_by_ids_collection(&self, ids: &[uuid::Uuid]) -> ServiceResult<Vec<Card>>;
+
+    /// Fetch many cards by exact name, leaving unknown names out
+    async fn get_cards_by_names_collection(&self, names: &[String]) -> ServiceResult<Vec<Card>>;
+}
+
+#[async_trait]
+impl CardSource for ScryfallClient {
+    fn circuit_breakers(&self) -> &CircuitBreakerRegistry {
+        ScryfallClient::circuit_breakers(self)
+    }
+
+    async fn probe(&self) -> ServiceResult<()> {
+        ScryfallClient::probe(self).await
+    }
+
+    async fn search_cards(&self, query: &str) -> ServiceResult<SearchResults> {
+        ScryfallClient::search_cards(self, query).await
+    }
+
+    async fn get_card_by_name(&self, name: &str, fuzzy: bool) -> ServiceResult<Option<Card>> {
+        ScryfallClient::get_card_by_name(self, name, fuzzy).await
+    }
+
+    async fn get_card_by_name_localized(
+        &self,
+        name: &str,
+        fuzzy: bool,
+        lang: &str,
+    ) -> ServiceResult<Option<Card>> {
+        ScryfallClient::get_card_by_name_localized(self, name, fuzzy, lang).await
+    }
+
+    async fn get_card_by_id(&self, id: uuid::Uuid) -> ServiceResult<Option<Card>> {
+        ScryfallClient::get_card_by_id(self, id).await
+    }
+
+    async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>> {
+        ScryfallClient::get_card_by_set_and_number(self, set_code, collector_number).await
+    }
+
+    async fn get_card_by_external_id(
+        &self,
+        external_id: ExternalId,
+    ) -> ServiceResult<Option<Card>> {
+        ScryfallClient::get_card_by_external_id(self, external_id).await
+    }
+
+    async fn get_json(
+        &self,
+        endpoint: &'static str,
+        path: &str,
+    ) -> ServiceResult<Option<serde_json::Value>> {
+        ScryfallClient::get_json(self, endpoint, path).await
+    }
+
+    async fn get_cards_by_ids_collection(&self, ids: &[uuid::Uuid]) -> ServiceResult<Vec<Card>> {
+        ScryfallClient::get_cards_by_ids_collection(self, ids).await
+    }
+
+    async fn get_cards_by_names_collection(&self, names: &[String]) -> ServiceResult<Vec<Card>> {
+        ScryfallClient::get_cards_by_names_collection(self, names).await
+    }
+}
+
+/// Card source answering from cards held in memory, for tests and offline runs. It never
+/// touches the network: lookups match the given cards by id, name, set and collector
+/// number or external id, and searches return the cards registered for the exact query
+/// string received (`CacheManager` sends `(<query>) unique:<mode>`).
+pub struct MockCardSource {
+    cards: Vec<Card>,
+    searches: HashMap<String, Vec<Card>>,
+    json: HashMap<String, serde_json::Value>,
+    unavailable: bool,
+    requests: AtomicUsize,
+    circuit_breakers: CircuitBreakerRegistry,
+}
+
+impl Default for MockCardSource {
+    fn default() -> Self {
+        Self {
+            cards: Vec::new(),
+            searches: HashMap::new(),
+            json: HashMap::new(),
+            unavailable: false,
+            requests: AtomicUsize::new(0),
+            circuit_breakers: endpoint_circuit_breakers(CircuitBreakerConfig::default()),
+        }
+    }
+}
+
+impl MockCardSource {
+    /// A source that knows no cards
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve lookups from `cards`
+    pub fn with_cards(mut self, cards: Vec<Card>) -> Self {
+        self.cards = cards;
+        self
+    }
+
+    /// Answer searches for exactly `query` with `cards`; other searches find nothing
+    pub fn with_search(mut self, query: impl Into<String>, cards: Vec<Card>) -> Self {
+        self.searches.insert(query.into(), cards);
+        self
+    }
+
+    /// Answer JSON requests for `path` (e.g. `/symbology`) with `value`; other paths are
+    /// not found
+    pub fn with_json(mut self, path: impl Into<String>, value: serde_json::Value) -> Self {
+        self.json.insert(path.into(), value);
+        self
+    }
+
+    /// Fail every request, like an unreachable upstream
+    pub fn unavailable(mut self) -> Self {
+        self.unavailable = true;
+        self
+    }
+
+    /// Requests received so far, including failed ones
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Count a request, failing it when the source is unavailable
+    fn request(&self) -> ServiceResult<()> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if self.unavailable {
+            return Err(ServiceError::ScryfallApi(anyhow::anyhow!(
+                "Mock card source is unavailable"
+            )));
+        }
+        Ok(())
+    }
+
+    fn find(&self, matches: impl Fn(&Card) -> bool) -> Option<Card> {
+        self.cards.iter().find(|card| matches(card)).cloned()
+    }
+
+    fn name_matches(card: &Card, name: &str, fuzzy: bool) -> bool {
+        let card_name = card.name.to_lowercase();
+        let name = name.to_lowercase();
+        if fuzzy {
+            card_name.contains(&name)
+        } else {
+            card_name == name
+        }
+    }
+}
+
+#[async_trait]
+impl CardSource for MockCardSource {
+    fn circuit_breakers(&self) -> &CircuitBreakerRegistry {
+        &self.circuit_breakers
+    }
+
+    async fn probe(&self) -> ServiceResult<()> {
+        self.request()
+    }
+
+    async fn search_cards(&self, query: &str) -> ServiceResult<SearchResults> {
+        self.request()?;
+        let cards = self.searches.get(query).cloned().unwrap_or_default();
+        Ok(SearchResults {
+            total_cards: cards.len(),
+            cards,
+            truncated: false,
+        })
+    }
+
+    async fn get_card_by_name(&self, name: &str, fuzzy: bool) -> ServiceResult<Option<Card>> {
+        self.request()?;
+        Ok(self.find(|card| Self::name_matches(card, name, fuzzy)))
+    }
+
+    async fn get_card_by_name_localized(
+        &self,
+        name: &str,
+        fuzzy: bool,
+        lang: &str,
+    ) -> ServiceResult<Option<Card>> {
+        self.request()?;
+        Ok(self.find(|card| {
+            Self::name_matches(card, name, fuzzy) && card.lang.as_deref() == Some(lang)
+        }))
+    }
+
+    async fn get_card_by_id(&self, id: uuid::Uuid) -> ServiceResult<Option<Card>> {
+        self.request()?;
+        Ok(self.find(|card| card.id == id))
+    }
+
+    async fn get_card_by_set_and_number(
+        &self,
+        set_code: &str,
+        collector_number: &str,
+    ) -> ServiceResult<Option<Card>> {
+        self.request()?;
+        Ok(self.find(|card| {
+            card.set_code
+                .as_deref()
+                .is_some_and(|set| set.eq_ignore_ascii_case(set_code))
+                && card.collector_number.as_deref() == Some(collector_number)
+        }))
+    }
+
+    async fn get_card_by_external_id(
+        &self,
+        external_id: ExternalId,
+    ) -> ServiceResult<Option<Card>> {
+        self.request()?;
+        Ok(self.find(|card| match external_id {
+            ExternalId::Multiverse(id) => card
+                .multiverse_ids
+                .as_ref()
+                .is_some_and(|ids| ids.contains(&id)),
+            ExternalId::Arena(id) => card.arena_id == Some(id),
+            ExternalId::Mtgo(id) => card.mtgo_id == Some(id) || card.mtgo_foil_id == Some(id),
+        }))
+    }
+
+    async fn get_json(
+        &self,
+        _endpoint: &'static str,
+        path: &str,
+    ) -> ServiceResult<Option<serde_json::Value>> {
+        self.request()?;
+        Ok(self.json.get(path).cloned())
+    }
+
+    async fn get_cards_by_ids_collection(&self, ids: &[uuid::Uuid]) -> ServiceResult<Vec<Card>> {
+        self.request()?;
+        Ok(ids
+            .iter()
+            .filter_map(|id| self.find(|card| card.id == *id))
+            .collect())
+    }
+
+    async fn get_cards_by_names_collection(&self, names: &[String]) -> ServiceResult<Vec<Card>> {
+        self.request()?;
+        Ok(names
+            .iter()
+            .filter_map(|name| self.find(|card| Self::name_matches(card, name, false)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(name: &str) -> Card {
+        Card::from_scryfall_json(serde_json::json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "name": name,
+            "set": "lea",
+            "collector_number": "161",
+            "lang": "en"
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mock_card_source_lookups() {
+        let bolt = card("Lightning Bolt");
+        let source = MockCardSource::new()
+            .with_cards(vec![bolt.clone()])
+            .with_search("c:r", vec![bolt.clone()]);
+
+        let found = source.get_card_by_id(bolt.id).await.unwrap();
+        assert_eq!(found.map(|card| card.id), Some(bolt.id));
+        assert!(source
+            .get_card_by_name("light", true)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(source
+            .get_card_by_name("light", false)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(source
+            .get_card_by_set_and_number("LEA", "161")
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(source.search_cards("c:r").await.unwrap().total_cards, 1);
+        assert!(source.search_cards("c:u").await.unwrap().cards.is_empty());
+        assert_eq!(source.requests(), 6);
+        assert!(source.circuit_breakers().find("cards_search").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_mock_card_source() {
+        let source = MockCardSource::new().unavailable();
+        assert!(source.probe().await.is_err());
+        assert!(source.search_cards("c:r").await.is_err());
+        assert_eq!(source.requests(), 2);
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+
+/// Scryfall's own API, the upstream used when none are configured
+pub const SCRYFALL_API_BASE: &str = "https://api.scryfall.com";
+
+/// One server answering Scryfall API requests: Scryfall itself or a mirror of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpstreamConfig {
+    /// Base URL without a trailing slash (e.g. `https://api.scryfall.com`)
+    pub base_url: String,
+    /// Requests per second sent to this upstream
+    pub rate_limit_per_second: u32,
+}
+
+impl UpstreamConfig {
+    /// Scryfall's own API at `rate_limit_per_second`
+    pub fn scryfall(rate_limit_per_second: u32) -> Self {
+        Self {
+            base_url: SCRYFALL_API_BASE.to_string(),
+            rate_limit_per_second,
+        }
+    }
+
+    /// Host (and port, if any) of the base URL, naming the upstream in circuit breakers,
+    /// metrics and logs
+    pub fn name(&self) -> String {
+        reqwest::Url::parse(&self.base_url)
+            .ok()
+            .and_then(|url| {
+                let host = url.host_str()?.to_string();
+                Some(match url.port() {
+                    Some(port) => format!("{}:{}", host, port),
+                    None => host,
+                })
+            })
+            .unwrap_or_else(|| self.base_url.clone())
+    }
+}
+
+/// Parse `SCRYFALL_UPSTREAMS`: comma-separated base URLs in priority order, each optionally
+/// followed by `=<requests per second>` (e.g. `http://mirror:8080=50,https://api.scryfall.com`).
+/// Upstreams without a rate get `default_rate`. Empty means Scryfall's own API only.
+pub fn parse_upstreams(value: &str, default_rate: u32) -> Result<Vec<UpstreamConfig>> {
+    let mut upstreams: Vec<UpstreamConfig> = Vec::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (url, rate) = match entry.rsplit_once('=') {
+            Some((url, rate)) => {
+                let rate: u32 = rate.trim().parse().with_context(|| {
+                    format!(
+                        "SCRYFALL_UPSTREAMS has an invalid rate limit in '{}'",
+                        entry
+                    )
+                })?;
+                (url.trim(), rate)
+            }
+            None => (entry, default_rate),
+        };
+        if rate == 0 {
+            anyhow::bail!(
+                "SCRYFALL_UPSTREAMS rate limits must be > 0 (got '{}')",
+                entry
+            );
+        }
+
+        let parsed = reqwest::Url::parse(url)
+            .with_context(|| format!("SCRYFALL_UPSTREAMS has an invalid URL '{}'", url))?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            anyhow::bail!(
+                "SCRYFALL_UPSTREAMS URLs must be http(s) URLs (got '{}')",
+                url
+            );
+        }
+
+        let base_url = url.trim_end_matches('/').to_string();
+        if !upstreams.iter().any(|u| u.base_url == base_url) {
+            upstreams.push(UpstreamConfig {
+                base_url,
+                rate_limit_per_second: rate,
+            });
+        }
+    }
+    Ok(upstreams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_upstreams() {
+        let upstreams = parse_upstreams(
+            "http://mirror.internal:8080/=50, https://api.scryfall.com",
+            10,
+        )
+        .unwrap();
+        assert_eq!(
+            upstreams,
+            vec![
+                UpstreamConfig {
+                    base_url: "http://mirror.internal:8080".to_string(),
+                    rate_limit_per_second: 50,
+                },
+                UpstreamConfig::scryfall(10),
+            ]
+        );
+        assert_eq!(upstreams[0].name(), "mirror.internal:8080");
+        assert_eq!(upstreams[1].name(), "api.scryfall.com");
+
+        assert!(parse_upstreams("", 10).unwrap().is_empty());
+        assert!(parse_upstreams("http://mirror=fast", 10).is_err());
+        assert!(parse_upstreams("http://mirror=0", 10).is_err());
+        assert!(parse_upstreams("mirror.internal", 10).is_err());
+        assert!(parse_upstreams("ftp://mirror.internal", 10).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+mod registry;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::config::Settings;
+use crate::utils::hash::hash_query;
+
+pub use registry::{Tenant, TenantError, TenantRegistry, TenantStats};
+
+/// Tenant of requests that carry neither an API key nor a tenant header, and of
+/// background jobs. Its query cache keys are unprefixed, as before tenants existed.
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Longest accepted tenant id
+const MAX_TENANT_ID_LENGTH: usize = 64;
+
+tokio::task_local! {
+    /// Tenant of the request being served, set by the tenant middleware
+    static CURRENT: Arc<Tenant>;
+}
+
+#[derive(Debug, Clone)]
+pub struct TenantConfig {
+    /// Resolve a tenant per request; when disabled every request uses `DEFAULT_TENANT`
+    pub enabled: bool,
+    /// Header naming the tenant of requests without an API key
+    pub header: String,
+    /// Tenant of each API key (sent as `X-API-Key`)
+    pub api_keys: HashMap<String, String>,
+    /// Requests per minute allowed per tenant; 0 disables rate limiting
+    pub rate_limit_per_minute: u32,
+    /// Per-tenant overrides of `rate_limit_per_minute`
+    pub rate_limits: HashMap<String, u32>,
+    /// Distinct tenants tracked before requests for new tenants are rejected
+    pub max_tenants: usize,
+}
+
+impl Default for TenantConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            header: "x-tenant-id".to_string(),
+            api_keys: HashMap::new(),
+            rate_limit_per_minute: 0,
+            rate_limits: HashMap::new(),
+            max_tenants: 1000,
+        }
+    }
+}
+
+impl TenantConfig {
+    /// Load configuration from `settings`
+    pub fn from_settings(settings: &Settings) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: settings
+                .var("TENANTS_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            header: settings
+                .var("TENANT_HEADER")
+                .ok()
+                .map(|v| v.trim().to_ascii_lowercase())
+                .filter(|v| !v.is_empty())
+                .unwrap_or(defaults.header),
+            api_keys: settings
+                .var("TENANT_API_KEYS")
+                .map(|v| parse_pairs("TENANT_API_KEYS", &v, parse_tenant_id))
+                .unwrap_or_default(),
+            rate_limit_per_minute: settings
+                .var("TENANT_RATE_LIMIT_PER_MINUTE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.rate_limit_per_minute),
+            rate_limits: settings
+                .var("TENANT_RATE_LIMITS")
+                .map(|v| parse_pairs("TENANT_RATE_LIMITS", &v, |limit| limit.parse().ok()))
+                .unwrap_or_default(),
+            max_tenants: settings
+                .var("TENANT_MAX_TENANTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_tenants),
+        }
+    }
+
+    /// Requests per minute allowed for `tenant_id`, or `None` if unlimited
+    pub fn rate_limit(&self, tenant_id: &str) -> Option<u32> {
+        let limit = self
+            .rate_limits
+            .get(tenant_id)
+            .copied()
+            .unwrap_or(self.rate_limit_per_minute);
+        (limit > 0).then_some(limit)
+    }
+}
+
+/// Parse comma-separated `key:value` pairs, skipping (and logging) entries that aren't a
+/// pair or whose value `parse` rejects
+fn parse_pairs<T>(
+    name: &str,
+    value: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> HashMap<String, T> {
+    let mut pairs = HashMap::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let parsed = entry
+            .split_once(':')
+            .and_then(|(key, value)| Some((key.trim().to_string(), parse(value.trim())?)));
+        match parsed {
+            Some((key, value)) => {
+                pairs.insert(key, value);
+            }
+            None => warn!("Ignoring malformed {} entry", name),
+        }
+    }
+    pairs
+}
+
+fn parse_tenant_id(value: &str) -> Option<String> {
+    is_valid_tenant_id(value).then(|| value.to_string())
+}
+
+/// Tenant ids are 1-64 ASCII letters, digits, `-` or `_`, so they can't be confused with
+/// the hex query hashes they prefix
+pub fn is_valid_tenant_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_TENANT_ID_LENGTH
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Run `future` as a request of `tenant`
+pub async fn scope<F: Future>(tenant: Arc<Tenant>, future: F) -> F::Output {
+    CURRENT.scope(tenant, future).await
+}
+
+/// Id of the tenant being served, or `DEFAULT_TENANT` outside a tenant scope
+pub fn current_id() -> String {
+    CURRENT
+        .try_with(|tenant| tenant.id().to_string())
+        .unwrap_or_else(|_| DEFAULT_TENANT.to_string())
+}
+
+/// Key of `query` in the query caches of the current tenant: the query hash, prefixed
+/// with `<tenant>:` for every tenant but the default one
+pub fn query_cache_key(query: &str) -> String {
+    let query_hash = hash_query(query);
+    match CURRENT.try_with(|tenant| tenant.id().to_string()) {
+        Ok(tenant_id) if tenant_id != DEFAULT_TENANT => format!("{}:{}", tenant_id, query_hash),
+        _ => query_hash,
+    }
+}
+
+/// Count a search answered by cache tier `source` against the current tenant
+pub fn record_search(source: &str) {
+    let _ = CURRENT.try_with(|tenant| tenant.record_search(source));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenant_id_validation() {
+        assert!(is_valid_tenant_id("acme"));
+        assert!(is_valid_tenant_id("team_42-prod"));
+        assert!(!is_valid_tenant_id(""));
+        assert!(!is_valid_tenant_id("acme:prod"));
+        assert!(!is_valid_tenant_id("a b"));
+        assert!(!is_valid_tenant_id(&"x".repeat(65)));
+    }
+
+    #[test]
+    fn test_parse_pairs() {
+        let keys = parse_pairs(
+            "TENANT_API_KEYS",
+            "k1:acme, k2:beta,broken,k3:bad id",
+            parse_tenant_id,
+        );
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys["k1"], "acme");
+        assert_eq!(keys["k2"], "beta");
+
+        let limits = parse_pairs("TENANT_RATE_LIMITS", "acme:600,beta:lots", |l| {
+            l.parse().ok()
+        });
+        assert_eq!(limits.len(), 1);
+        assert_eq!(limits["acme"], 600u32);
+    }
+
+    #[test]
+    fn test_rate_limit_overrides() {
+        let config = TenantConfig {
+            rate_limit_per_minute: 60,
+            rate_limits: HashMap::from([("acme".to_string(), 600), ("free".to_string(), 0)]),
+            ..TenantConfig::default()
+        };
+        assert_eq!(config.rate_limit("acme"), Some(600));
+        assert_eq!(config.rate_limit("other"), Some(60));
+        assert_eq!(config.rate_limit("free"), None);
+    }
+
+    #[tokio::test]
+    async fn test_query_cache_key_is_tenant_scoped() {
+        let unscoped = query_cache_key("c:red");
+        assert_eq!(unscoped, hash_query("c:red"));
+        assert_eq!(current_id(), DEFAULT_TENANT);
+
+        let registry = TenantRegistry::new(TenantConfig::default());
+        let acme = registry.get("acme").unwrap();
+        let key = scope(acme, async { query_cache_key("c:red") }).await;
+        assert_eq!(key, format!("acme:{}", unscoped));
+
+        let default = registry.get(DEFAULT_TENANT).unwrap();
+        let key = scope(default, async { query_cache_key("c:red") }).await;
+        assert_eq!(key, unscoped);
+    }
+}
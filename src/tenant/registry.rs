@@ -0,0 +1,359 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use super::{is_valid_tenant_id, TenantConfig, DEFAULT_TENANT};
+
+/// Why a request could not be assigned a tenant
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TenantError {
+    #[error("Unknown API key")]
+    UnknownApiKey,
+
+    #[error("Tenant '{0}' requires its API key")]
+    ApiKeyRequired(String),
+
+    #[error("Invalid tenant id '{0}': use 1-64 letters, digits, '-' or '_'")]
+    InvalidTenantId(String),
+
+    #[error("Too many tenants (limit {0})")]
+    TooManyTenants(usize),
+}
+
+/// Usage counters of one tenant, as reported by `/api/admin/stats/tenants`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TenantStats {
+    pub tenant_id: String,
+    /// Requests accepted since startup
+    pub requests: u64,
+    /// Requests rejected by the tenant's rate limit
+    pub rate_limited: u64,
+    /// Searches run (REST, GraphQL and batch)
+    pub searches: u64,
+    /// Searches answered from Redis, the query cache or the local database
+    pub cache_hits: u64,
+    /// Searches that fell back to the Scryfall API
+    pub api_fallbacks: u64,
+    /// Requests per minute allowed, if rate limited
+    pub rate_limit_per_minute: Option<u32>,
+    /// Entries this tenant has in the database query cache
+    pub query_cache_entries: i64,
+}
+
+/// A tenant: its rate limit bucket and usage counters
+#[derive(Debug)]
+pub struct Tenant {
+    id: String,
+    rate_limit: Option<u32>,
+    bucket: Mutex<TokenBucket>,
+    requests: AtomicU64,
+    rate_limited: AtomicU64,
+    searches: AtomicU64,
+    cache_hits: AtomicU64,
+    api_fallbacks: AtomicU64,
+}
+
+impl Tenant {
+    fn new(id: &str, rate_limit: Option<u32>) -> Self {
+        Self {
+            id: id.to_string(),
+            rate_limit,
+            bucket: Mutex::new(TokenBucket::full(rate_limit.unwrap_or(0))),
+            requests: AtomicU64::new(0),
+            rate_limited: AtomicU64::new(0),
+            searches: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            api_fallbacks: AtomicU64::new(0),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Count a request against the rate limit. Returns how long to wait before retrying
+    /// if the tenant has used up its allowance.
+    pub fn admit(&self) -> Result<(), Duration> {
+        if let Some(per_minute) = self.rate_limit {
+            let admitted = self.bucket.lock().unwrap().take(per_minute);
+            if let Err(retry_after) = admitted {
+                self.rate_limited.fetch_add(1, Ordering::Relaxed);
+                return Err(retry_after);
+            }
+        }
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Count a search answered by cache tier `source`
+    pub fn record_search(&self, source: &str) {
+        self.searches.fetch_add(1, Ordering::Relaxed);
+        match source {
+            "redis" | "query_cache" | "database" => {
+                self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            }
+            "api" => {
+                self.api_fallbacks.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn stats(&self) -> TenantStats {
+        TenantStats {
+            tenant_id: self.id.clone(),
+            requests: self.requests.load(Ordering::Relaxed),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+            searches: self.searches.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            api_fallbacks: self.api_fallbacks.load(Ordering::Relaxed),
+            rate_limit_per_minute: self.rate_limit,
+            query_cache_entries: 0,
+        }
+    }
+}
+
+/// Token bucket holding up to a minute's allowance, refilled continuously
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn full(per_minute: u32) -> Self {
+        Self {
+            tokens: per_minute as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    fn take(&mut self, per_minute: u32) -> Result<(), Duration> {
+        let capacity = per_minute as f64;
+        let per_second = capacity / 60.0;
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(capacity);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / per_second))
+        }
+    }
+}
+
+/// Tenants seen since startup, created on first request
+#[derive(Debug)]
+pub struct TenantRegistry {
+    config: TenantConfig,
+    tenants: RwLock<BTreeMap<String, Arc<Tenant>>>,
+}
+
+impl TenantRegistry {
+    pub fn new(config: TenantConfig) -> Self {
+        Self {
+            config,
+            tenants: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Whether requests are assigned tenants at all
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Header naming the tenant of requests without an API key
+    pub fn header(&self) -> &str {
+        &self.config.header
+    }
+
+    /// Tenant of a request: the tenant of its API key if one was sent, else the tenant
+    /// named by the tenant header, else the default tenant.
+    ///
+    /// The header is unauthenticated, so it can't name a tenant that has an API key, and
+    /// once any keys are configured requests without one all share the default tenant
+    /// (and its rate limit) whatever header they send.
+    pub fn resolve(
+        &self,
+        api_key: Option<&str>,
+        tenant_header: Option<&str>,
+    ) -> Result<Arc<Tenant>, TenantError> {
+        if let Some(api_key) = api_key {
+            return match self.config.api_keys.get(api_key) {
+                Some(tenant_id) => self.get(tenant_id),
+                None => Err(TenantError::UnknownApiKey),
+            };
+        }
+        let Some(tenant_id) = tenant_header.map(str::trim) else {
+            return self.get(DEFAULT_TENANT);
+        };
+        let api_keys = &self.config.api_keys;
+        if api_keys.values().any(|keyed| keyed == tenant_id) {
+            return Err(TenantError::ApiKeyRequired(tenant_id.to_string()));
+        }
+        if api_keys.is_empty() {
+            self.get(tenant_id)
+        } else {
+            self.get(DEFAULT_TENANT)
+        }
+    }
+
+    /// Get tenant `id`, creating it on first use
+    pub fn get(&self, id: &str) -> Result<Arc<Tenant>, TenantError> {
+        if !is_valid_tenant_id(id) {
+            return Err(TenantError::InvalidTenantId(id.to_string()));
+        }
+        if let Some(tenant) = self.tenants.read().unwrap().get(id) {
+            return Ok(tenant.clone());
+        }
+
+        let mut tenants = self.tenants.write().unwrap();
+        if !tenants.contains_key(id)
+            && id != DEFAULT_TENANT
+            && tenants.len() >= self.config.max_tenants
+        {
+            return Err(TenantError::TooManyTenants(self.config.max_tenants));
+        }
+        Ok(tenants
+            .entry(id.to_string())
+            .or_insert_with(|| Arc::new(Tenant::new(id, self.config.rate_limit(id))))
+            .clone())
+    }
+
+    /// Usage counters of every tenant seen since startup, ordered by tenant id
+    pub fn stats(&self) -> Vec<TenantStats> {
+        self.tenants
+            .read()
+            .unwrap()
+            .values()
+            .map(|tenant| tenant.stats())
+            .collect()
+    }
+
+    /// `stats` joined with each tenant's database query cache entry count. Tenants with
+    /// cached entries but no requests since startup are included with zero counters.
+    pub fn report(&self, query_cache_entries: &[(String, i64)]) -> Vec<TenantStats> {
+        let mut stats: BTreeMap<String, TenantStats> = self
+            .stats()
+            .into_iter()
+            .map(|s| (s.tenant_id.clone(), s))
+            .collect();
+        for (tenant_id, entries) in query_cache_entries {
+            stats
+                .entry(tenant_id.clone())
+                .or_insert_with(|| {
+                    Tenant::new(tenant_id, self.config.rate_limit(tenant_id)).stats()
+                })
+                .query_cache_entries = *entries;
+        }
+        stats.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_resolve_prefers_api_key() {
+        let registry = TenantRegistry::new(TenantConfig {
+            api_keys: HashMap::from([("secret".to_string(), "acme".to_string())]),
+            ..TenantConfig::default()
+        });
+
+        let tenant = registry.resolve(Some("secret"), Some("beta")).unwrap();
+        assert_eq!(tenant.id(), "acme");
+        assert_eq!(registry.resolve(None, None).unwrap().id(), DEFAULT_TENANT);
+        assert_eq!(
+            registry.resolve(Some("wrong"), None).unwrap_err(),
+            TenantError::UnknownApiKey
+        );
+
+        // The header can't claim a keyed tenant, and with keys configured every other
+        // header value shares the default tenant
+        assert_eq!(
+            registry.resolve(None, Some("acme")).unwrap_err(),
+            TenantError::ApiKeyRequired("acme".to_string())
+        );
+        let header_tenant = registry.resolve(None, Some("beta")).unwrap();
+        assert_eq!(header_tenant.id(), DEFAULT_TENANT);
+        assert_eq!(registry.stats().len(), 2);
+    }
+
+    #[test]
+    fn test_resolve_header_without_api_keys() {
+        let registry = TenantRegistry::new(TenantConfig::default());
+
+        assert_eq!(registry.resolve(None, Some("beta")).unwrap().id(), "beta");
+        assert_eq!(registry.resolve(None, None).unwrap().id(), DEFAULT_TENANT);
+        assert!(matches!(
+            registry.resolve(None, Some("not a tenant")),
+            Err(TenantError::InvalidTenantId(_))
+        ));
+    }
+
+    #[test]
+    fn test_tenant_limit() {
+        let registry = TenantRegistry::new(TenantConfig {
+            max_tenants: 1,
+            ..TenantConfig::default()
+        });
+
+        registry.get("acme").unwrap();
+        assert!(registry.get("acme").is_ok());
+        assert_eq!(
+            registry.get("beta").unwrap_err(),
+            TenantError::TooManyTenants(1)
+        );
+        assert!(registry.get(DEFAULT_TENANT).is_ok());
+    }
+
+    #[test]
+    fn test_rate_limit_and_counters() {
+        let registry = TenantRegistry::new(TenantConfig {
+            rate_limits: HashMap::from([("acme".to_string(), 2)]),
+            ..TenantConfig::default()
+        });
+
+        let acme = registry.get("acme").unwrap();
+        assert!(acme.admit().is_ok());
+        assert!(acme.admit().is_ok());
+        let retry_after = acme.admit().unwrap_err();
+        assert!(retry_after > Duration::from_secs(25));
+
+        let beta = registry.get("beta").unwrap();
+        for _ in 0..10 {
+            assert!(beta.admit().is_ok());
+        }
+        beta.record_search("redis");
+        beta.record_search("api");
+
+        let stats = registry.stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].tenant_id, "acme");
+        assert_eq!(stats[0].requests, 2);
+        assert_eq!(stats[0].rate_limited, 1);
+        assert_eq!(stats[0].rate_limit_per_minute, Some(2));
+        assert_eq!(stats[1].requests, 10);
+        assert_eq!(stats[1].searches, 2);
+        assert_eq!(stats[1].cache_hits, 1);
+        assert_eq!(stats[1].api_fallbacks, 1);
+        assert_eq!(stats[1].rate_limit_per_minute, None);
+
+        let report = registry.report(&[("acme".to_string(), 3), ("default".to_string(), 7)]);
+        assert_eq!(report.len(), 3);
+        assert_eq!(report[0].tenant_id, "acme");
+        assert_eq!(report[0].query_cache_entries, 3);
+        assert_eq!(report[0].requests, 2);
+        assert_eq!(report[2].tenant_id, "default");
+        assert_eq!(report[2].query_cache_entries, 7);
+        assert_eq!(report[2].requests, 0);
+    }
+}
@@ -0,0 +1,208 @@
+//! TLS termination for deployments that can't put a reverse proxy in front of the service.
+//! Serving HTTPS needs the `tls` feature; the certificate is re-read on SIGHUP, so renewed
+//! certificates are picked up without a restart.
+
+use anyhow::{bail, Context, Result};
+use axum::{
+    extract::State,
+    http::{header::HOST, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Router,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Settings;
+
+/// Certificate and key for serving HTTPS on the API port
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// PEM certificate chain
+    pub cert_path: String,
+    /// PEM private key
+    pub key_path: String,
+    /// Also serve plain HTTP on this port, redirecting every request to HTTPS
+    pub redirect_http_port: Option<u16>,
+}
+
+impl TlsConfig {
+    /// Load `TLS_CERT_PATH`, `TLS_KEY_PATH` and `TLS_REDIRECT_HTTP_PORT`. `None` (plain
+    /// HTTP) unless a certificate is configured.
+    pub fn from_settings(settings: &Settings) -> Result<Option<Self>> {
+        let path = |name: &str| settings.var(name).ok().filter(|v| !v.trim().is_empty());
+        let (cert_path, key_path) = match (path("TLS_CERT_PATH"), path("TLS_KEY_PATH")) {
+            (None, None) => return Ok(None),
+            (Some(cert), Some(key)) => (cert, key),
+            _ => bail!("TLS_CERT_PATH and TLS_KEY_PATH must be set together"),
+        };
+        if !cfg!(feature = "tls") {
+            bail!("TLS_CERT_PATH is set but TLS support is not compiled (missing tls feature)");
+        }
+
+        let redirect_http_port = settings
+            .var("TLS_REDIRECT_HTTP_PORT")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse())
+            .transpose()
+            .context("TLS_REDIRECT_HTTP_PORT must be a valid port number")?;
+        Ok(Some(Self {
+            cert_path,
+            key_path,
+            redirect_http_port,
+        }))
+    }
+}
+
+/// Serve `app` over HTTPS on `addr` until `shutdown` is cancelled, then let in-flight
+/// requests finish
+#[cfg(feature = "tls")]
+pub async fn serve(
+    addr: &str,
+    app: Router,
+    config: &TlsConfig,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    use axum_server::tls_rustls::RustlsConfig;
+    use tracing::info;
+
+    let rustls = RustlsConfig::from_pem_file(&config.cert_path, &config.key_path)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to load TLS certificate {} and key {}",
+                config.cert_path, config.key_path
+            )
+        })?;
+    let socket_addr = tokio::net::lookup_host(addr)
+        .await
+        .with_context(|| format!("Failed to resolve {}", addr))?
+        .next()
+        .with_context(|| format!("{} did not resolve to an address", addr))?;
+
+    #[cfg(unix)]
+    tokio::spawn(reload_on_sighup(
+        rustls.clone(),
+        config.clone(),
+        shutdown.clone(),
+    ));
+
+    let handle = axum_server::Handle::new();
+    tokio::spawn({
+        let handle = handle.clone();
+        async move {
+            shutdown.cancelled().await;
+            handle.graceful_shutdown(None);
+        }
+    });
+
+    info!("Server listening on {} (HTTPS)", socket_addr);
+    axum_server::bind_rustls(socket_addr, rustls)
+        .handle(handle)
+        .serve(app.into_make_service())
+        .await
+        .context("HTTPS server error")
+}
+
+#[cfg(not(feature = "tls"))]
+pub async fn serve(
+    _addr: &str,
+    _app: Router,
+    _config: &TlsConfig,
+    _shutdown: CancellationToken,
+) -> Result<()> {
+    bail!("TLS support not compiled (missing tls feature)")
+}
+
+/// Re-read the certificate and key on every SIGHUP. A certificate that fails to load is
+/// logged and the current one kept.
+#[cfg(all(feature = "tls", unix))]
+async fn reload_on_sighup(
+    rustls: axum_server::tls_rustls::RustlsConfig,
+    config: TlsConfig,
+    shutdown: CancellationToken,
+) {
+    use tokio::signal::unix::{signal, SignalKind};
+    use tracing::{error, info, warn};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!(
+                "Failed to install SIGHUP handler, TLS certificate reload disabled: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    loop {
+        tokio::select! {
+            _ = hangup.recv() => {}
+            _ = shutdown.cancelled() => return,
+        }
+
+        match rustls
+            .reload_from_pem_file(&config.cert_path, &config.key_path)
+            .await
+        {
+            Ok(()) => info!("Reloaded TLS certificate from {}", config.cert_path),
+            Err(e) => error!(
+                "Failed to reload TLS certificate from {}, keeping the current one: {}",
+                config.cert_path, e
+            ),
+        }
+    }
+}
+
+/// Router answering every request with a permanent redirect to the same URL over HTTPS
+/// on `https_port`
+pub fn redirect_router(https_port: u16) -> Router {
+    Router::new()
+        .fallback(redirect_to_https)
+        .with_state(https_port)
+}
+
+async fn redirect_to_https(
+    State(https_port): State<u16>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    let Some(host) = headers.get(HOST).and_then(|v| v.to_str().ok()) else {
+        return (StatusCode::BAD_REQUEST, "Missing Host header").into_response();
+    };
+    let path = uri.path_and_query().map_or("/", |pq| pq.as_str());
+    Redirect::permanent(&https_url(host, https_port, path)).into_response()
+}
+
+/// `https://` URL for `path` on `host` (with any port replaced by `https_port`)
+fn https_url(host: &str, https_port: u16, path: &str) -> String {
+    // Strip the port, leaving bracketed IPv6 addresses intact
+    let hostname = match host.rsplit_once(':') {
+        Some((name, port)) if !port.contains(']') => name,
+        _ => host,
+    };
+    if https_port == 443 {
+        format!("https://{}{}", hostname, path)
+    } else {
+        format!("https://{}:{}{}", hostname, https_port, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_https_url() {
+        assert_eq!(
+            https_url("cards.example.com:8080", 443, "/cards/search?q=c:r"),
+            "https://cards.example.com/cards/search?q=c:r"
+        );
+        assert_eq!(
+            https_url("cards.example.com", 8443, "/health"),
+            "https://cards.example.com:8443/health"
+        );
+        assert_eq!(https_url("[::1]:80", 8443, "/"), "https://[::1]:8443/");
+        assert_eq!(https_url("[::1]", 443, "/"), "https://[::1]/");
+    }
+}
@@ -0,0 +1,74 @@
+//! Compile-time feature introspection, used by `/admin/features`.
+
+/// Cargo features this binary was built with
+pub fn compiled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "postgres") {
+        features.push("postgres");
+    }
+    if cfg!(feature = "sqlite") {
+        features.push("sqlite");
+    }
+    if cfg!(feature = "redis_cache") {
+        features.push("redis_cache");
+    }
+    if cfg!(feature = "native-tls") {
+        features.push("native-tls");
+    }
+    if cfg!(feature = "rustls") {
+        features.push("rustls");
+    }
+    if cfg!(feature = "tls") {
+        features.push("tls");
+    }
+    if cfg!(feature = "minimal") {
+        features.push("minimal");
+    }
+    if cfg!(feature = "parquet_export") {
+        features.push("parquet_export");
+    }
+    if cfg!(feature = "grpc") {
+        features.push("grpc");
+    }
+    if cfg!(feature = "client") {
+        features.push("client");
+    }
+    features
+}
+
+/// Database backend compiled into this binary
+pub fn database_backend() -> &'static str {
+    if cfg!(feature = "postgres") {
+        "postgres"
+    } else if cfg!(feature = "sqlite") {
+        "sqlite"
+    } else {
+        "none"
+    }
+}
+
+/// TLS implementation used for outbound HTTPS (Scryfall API and bulk downloads).
+/// reqwest prefers native TLS when both are compiled in.
+pub fn tls_backend() -> &'static str {
+    if cfg!(feature = "native-tls") {
+        "native-tls"
+    } else if cfg!(feature = "rustls") {
+        "rustls"
+    } else {
+        "none"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reported_backends_are_compiled() {
+        let features = compiled_features();
+        let db = database_backend();
+        assert!(db == "none" || features.contains(&db));
+        let tls = tls_backend();
+        assert!(tls == "none" || features.contains(&tls));
+    }
+}
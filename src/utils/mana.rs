@@ -0,0 +1,122 @@
+//! Mana cost parsing, e.g. `{2}{W}{W}` or `{1}{U/B}{G/P}`
+
+/// WUBRG, the colors a mana symbol can require
+const COLORS: [&str; 5] = ["W", "U", "B", "R", "G"];
+
+/// One distinct symbol of a mana cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManaSymbol {
+    /// The symbol without braces, e.g. `2`, `W`, `U/B`, `G/P` or `X`
+    pub symbol: String,
+    /// How many times the symbol appears in the cost
+    pub count: u32,
+    /// Colors that can pay for it; empty for generic, colorless and snow mana
+    pub colors: Vec<String>,
+    /// Payable with either of two kinds of mana, e.g. `{U/B}` or `{2/W}`
+    pub hybrid: bool,
+    /// Payable with 2 life instead, e.g. `{G/P}`
+    pub phyrexian: bool,
+    /// What one occurrence adds to the mana value (`X` adds 0)
+    pub mana_value: f64,
+}
+
+/// Parse a mana cost into its distinct symbols, in order of first appearance. Text
+/// outside braces, such as the ` // ` between the halves of a split card, is skipped.
+pub fn parse_mana_cost(cost: &str) -> Vec<ManaSymbol> {
+    let mut symbols: Vec<ManaSymbol> = Vec::new();
+
+    for token in cost.split('{').skip(1) {
+        let Some((symbol, _)) = token.split_once('}') else {
+            continue;
+        };
+        let symbol = symbol.trim().to_ascii_uppercase();
+        if symbol.is_empty() {
+            continue;
+        }
+
+        match symbols.iter_mut().find(|s| s.symbol == symbol) {
+            Some(existing) => existing.count += 1,
+            None => symbols.push(parse_symbol(symbol)),
+        }
+    }
+
+    symbols
+}
+
+fn parse_symbol(symbol: String) -> ManaSymbol {
+    let parts: Vec<&str> = symbol.split('/').collect();
+    let phyrexian = parts.len() > 1 && parts.contains(&"P");
+    let payments: Vec<&str> = parts.iter().copied().filter(|p| *p != "P").collect();
+
+    let colors = COLORS
+        .iter()
+        .filter(|color| {
+            payments
+                .iter()
+                .any(|p| p == *color || p.strip_prefix('H') == Some(**color))
+        })
+        .map(|color| color.to_string())
+        .collect();
+
+    // A hybrid symbol with a generic half ({2/W}) is worth the generic amount
+    let mana_value = payments
+        .iter()
+        .map(|p| match *p {
+            "X" | "Y" | "Z" => 0.0,
+            "½" => 0.5,
+            p if p.starts_with('H') && p.len() == 2 => 0.5,
+            p => p.parse::<f64>().unwrap_or(1.0),
+        })
+        .fold(0.0, f64::max);
+
+    ManaSymbol {
+        hybrid: payments.len() > 1,
+        symbol,
+        count: 1,
+        colors,
+        phyrexian,
+        mana_value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mana_cost_counts_repeated_symbols() {
+        let symbols = parse_mana_cost("{2}{W}{W}");
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].symbol, "2");
+        assert_eq!(symbols[0].mana_value, 2.0);
+        assert!(symbols[0].colors.is_empty());
+        assert_eq!(symbols[1].symbol, "W");
+        assert_eq!(symbols[1].count, 2);
+        assert_eq!(symbols[1].colors, ["W"]);
+    }
+
+    #[test]
+    fn test_parse_mana_cost_hybrid_and_phyrexian() {
+        let symbols = parse_mana_cost("{X}{u/b}{2/R}{G/U/P}");
+        let [x, ub, two_r, gup] = symbols.as_slice() else {
+            panic!("expected 4 symbols, got {:?}", symbols);
+        };
+        assert_eq!(x.mana_value, 0.0);
+        assert_eq!(ub.symbol, "U/B");
+        assert!(ub.hybrid && !ub.phyrexian);
+        assert_eq!(ub.colors, ["U", "B"]);
+        assert_eq!(two_r.mana_value, 2.0);
+        assert_eq!(two_r.colors, ["R"]);
+        assert!(gup.hybrid && gup.phyrexian);
+        assert_eq!(gup.colors, ["U", "G"]);
+        assert_eq!(gup.mana_value, 1.0);
+    }
+
+    #[test]
+    fn test_parse_mana_cost_split_card_and_empty() {
+        let symbols = parse_mana_cost("{1}{R} // {2}{U}");
+        let names: Vec<_> = symbols.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(names, ["1", "R", "2", "U"]);
+        assert!(parse_mana_cost("").is_empty());
+    }
+}
@@ -1 +1,3 @@
+pub mod features;
 pub mod hash;
+pub mod mana;
@@ -1,49 +1,13 @@
 use axum::{
     body::Body,
-    http::{Request, StatusCode},
+    http::{HeaderValue, Request, StatusCode},
 };
 use serde_json::{json, Value};
 use tower::Service;
 
-// Helper to create test app
-async fn create_test_app() -> axum::Router {
-    use scryfall_cache::{api, cache, config, db, query, scryfall};
-    use std::sync::Arc;
+mod common;
 
-    let config = config::Config::from_env().expect("Failed to load configuration from environment");
-
-    let db_pool = db::init_database(&config.database)
-        .await
-        .expect("Failed to connect to database");
-
-    let scryfall_client = scryfall::client::ScryfallClient::new(&config.scryfall);
-    let cache_manager = Arc::new(cache::manager::CacheManager::new(
-        None, // Redis optional in tests
-        db_pool.clone(),
-        scryfall_client,
-        config.cache.query_cache_ttl_hours as i32,
-    ));
-    let bulk_loader =
-        scryfall::bulk_loader::BulkLoader::new(db_pool.clone(), config.scryfall.clone());
-    let query_validator =
-        scryfall_cache::query::QueryValidator::new(query::QueryLimits::from_env());
-
-    // GraphQL schema is part of AppStateInner and needs access to shared state.
-    let graphql_schema = scryfall_cache::graphql::create_schema(
-        cache_manager.clone(),
-        Arc::new(bulk_loader.clone()),
-    );
-
-    let state = Arc::new(api::handlers::AppStateInner {
-        cache_manager,
-        bulk_loader,
-        query_validator,
-        graphql_schema,
-        instance_id: config.server.instance_id.clone(),
-    });
-
-    api::routes::create_router(state)
-}
+use common::{create_test_app, create_test_app_with_config, create_test_app_with_source};
 
 // Helper to send request and parse JSON response
 async fn send_json_request(app: &mut axum::Router, method: &str, uri: &str) -> (StatusCode, Value) {
@@ -121,6 +85,88 @@ async fn test_health_ready_endpoint() {
     assert_eq!(body["status"], "ready");
     assert!(body["checks"].is_object());
     assert_eq!(body["checks"]["database"], "ok");
+    assert_eq!(body["checks"]["redis"], "disabled");
+    assert!(body["checks"]["circuit_breakers"].is_object());
+    assert!(body["checks"].get("scryfall").is_none());
+}
+
+#[tokio::test]
+async fn test_get_card_sample() {
+    let mut app = create_test_app().await;
+    let ids = |body: &Value| -> Vec<String> {
+        body["data"]["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|card| card["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let (status, first) = send_json_request(&mut app, "GET", "/cards/sample?n=3&seed=42").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["data"]["seed"], 42);
+    assert_eq!(ids(&first).len(), 3);
+    assert!(first["data"]["total"].as_u64().unwrap() >= 3);
+
+    let (_, second) = send_json_request(&mut app, "GET", "/cards/sample?n=3&seed=42").await;
+    assert_eq!(ids(&first), ids(&second));
+
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/sample?q=c:r&n=500").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["seed"].is_u64());
+    assert_eq!(
+        ids(&body).len() as u64,
+        body["data"]["total"].as_u64().unwrap()
+    );
+
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/sample?n=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn test_search_cards_parquet() {
+    let mut app = create_test_app().await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/cards/search.parquet?q=sol+ring")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+
+    if !cfg!(feature = "parquet_export") {
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/vnd.apache.parquet"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(body.starts_with(b"PAR1") && body.ends_with(b"PAR1"));
+}
+
+#[tokio::test]
+async fn test_get_card_rendered() {
+    let mut app = create_test_app().await;
+    let uri = "/cards/5af35231-4baa-4f71-9ecb-a96620a74656/rendered?format=markdown";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["format"], "markdown");
+    let faces = body["data"]["faces"].as_array().unwrap();
+    assert_eq!(faces.len(), 1);
+    assert_eq!(faces[0]["name"], "Goblin Guide");
+    assert!(faces[0]["oracle_text"].is_string());
+
+    let uri = "/cards/5af35231-4baa-4f71-9ecb-a96620a74656/rendered?format=pdf";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
 }
 
 #[tokio::test]
@@ -133,6 +179,8 @@ async fn test_admin_overview_endpoint() {
     assert!(body["data"].is_object());
     assert_eq!(body["data"]["service"], "scryfall-cache");
     assert!(body["data"]["cards_total"].is_number());
+    assert!(body["data"]["preview_poll"]["enabled"].is_boolean());
+    assert_eq!(body["data"]["preview_poll"]["runs"], 0);
 }
 
 #[tokio::test]
@@ -146,6 +194,105 @@ async fn test_search_cards_basic() {
     assert!(body["data"]["data"].is_array());
 }
 
+#[tokio::test]
+async fn test_search_cards_summary() {
+    let mut app = create_test_app().await;
+    let uri = "/cards/search?q=c:r&summary=true";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+
+    assert_eq!(status, StatusCode::OK);
+    for card in body["data"]["data"].as_array().unwrap() {
+        assert_eq!(card["raw_json"], json!({}));
+        assert!(card["card_faces"].is_null());
+    }
+}
+
+#[tokio::test]
+async fn test_search_cards_unique() {
+    let mut app = create_test_app().await;
+    let (status, prints) =
+        send_json_request(&mut app, "GET", "/cards/search?q=sol+ring&unique=prints").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, cards) =
+        send_json_request(&mut app, "GET", "/cards/search?q=sol+ring&unique=cards").await;
+    assert_eq!(status, StatusCode::OK);
+    let total = |body: &Value| body["data"]["total"].as_u64().unwrap();
+    assert_eq!(total(&prints), 2);
+    assert_eq!(total(&cards), 1);
+    // The most recent printing represents the card
+    assert_eq!(cards["data"]["data"][0]["set_code"], "c21");
+
+    let (status, art) =
+        send_json_request(&mut app, "GET", "/cards/search?q=sol+ring&unique=art").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(total(&art), 2);
+
+    // Searches are deduplicated by card unless they opt out
+    let (status, default) = send_json_request(&mut app, "GET", "/cards/search?q=sol+ring").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(total(&default), total(&cards));
+
+    let uri = "/cards/search?q=sol+ring&unique=everything";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn test_search_cards_regex() {
+    let mut app = create_test_app().await;
+    let names = |body: &Value| {
+        let mut names: Vec<String> = body["data"]["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|card| card["name"].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    let uri = "/cards/search?q=o:/draw+(three%7Ctwo)+cards/";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), ["Brainstorm"]);
+
+    let uri = "/cards/search?q=t:/goblin+(scout%7Cwarrior)/";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), ["Goblin Bushwhacker", "Goblin Guide"]);
+
+    // Backreferences can backtrack exponentially and are rejected before reaching the database
+    let uri = "/cards/search?q=o:/(a)%5C1/";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn test_search_cards_exact_name() {
+    let mut app = create_test_app().await;
+    let names = |body: &Value| -> Vec<String> {
+        body["data"]["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|card| card["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let uri = "/cards/search?q=!%22lightning+bolt%22";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), ["Lightning Bolt"]);
+
+    // One face of a split card finds the whole card
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/search?q=!Fire").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), ["Fire // Ice"]);
+}
+
 #[tokio::test]
 async fn test_search_cards_invalid_query() {
     let mut app = create_test_app().await;
@@ -157,6 +304,17 @@ async fn test_search_cards_invalid_query() {
     assert!(body["error"]["message"].is_string());
 }
 
+#[tokio::test]
+async fn test_search_cards_parse_error_details() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/search?q=c:red%20or").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_QUERY");
+    assert_eq!(body["error"]["details"]["position"], 8);
+    assert!(body["error"]["details"]["expected"].is_array());
+}
+
 #[tokio::test]
 async fn test_search_cards_pagination() {
     let mut app = create_test_app().await;
@@ -169,6 +327,83 @@ async fn test_search_cards_pagination() {
     assert!(body["data"]["total"].is_number());
 }
 
+#[tokio::test]
+async fn test_search_cards_cursor_pagination() {
+    let mut app = create_test_app().await;
+    let (status, first_page) =
+        send_json_request(&mut app, "GET", "/cards/search?q=c:u&page_size=5").await;
+    assert_eq!(status, StatusCode::OK);
+
+    if let Some(cursor) = first_page["data"]["next_cursor"].as_str() {
+        let uri = format!("/cards/search?q=c:u&page_size=5&cursor={}", cursor);
+        let (status, second_page) = send_json_request(&mut app, "GET", &uri).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_ne!(
+            first_page["data"]["data"][0]["id"],
+            second_page["data"]["data"][0]["id"]
+        );
+    }
+
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/cards/search?q=c:u&cursor=garbage").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+    assert_eq!(body["error"]["details"]["reason"], "malformed");
+
+    // Cursors are bound to the query they were issued for
+    if let Some(cursor) = first_page["data"]["next_cursor"].as_str() {
+        let uri = format!("/cards/search?q=c:r&page_size=5&cursor={}", cursor);
+        let (status, body) = send_json_request(&mut app, "GET", &uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["details"]["reason"], "query_mismatch");
+    }
+}
+
+#[tokio::test]
+async fn test_search_cards_order() {
+    let mut app = create_test_app().await;
+    let names = |body: &Value| -> Vec<String> {
+        body["data"]["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|card| card["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // Most popular first, unranked cards last
+    let uri = "/cards/search?q=c:u&order=edhrec&page_size=2";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), ["Brainstorm", "Counterspell"]);
+    assert_eq!(body["data"]["has_more"], true);
+    assert!(body["data"]["next_cursor"].is_null());
+
+    // Prices sort most expensive first unless asked otherwise
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/search?q=c:u&order=usd").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body)[0], "Counterspell");
+
+    let uri = "/cards/search?q=c:u&order=usd&dir=asc";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body).last().unwrap(), "Counterspell");
+
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/cards/search?q=c:u&order=popularity").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+
+    let (_, first_page) =
+        send_json_request(&mut app, "GET", "/cards/search?q=c:u&page_size=2").await;
+    let cursor = first_page["data"]["next_cursor"].as_str().unwrap();
+    let uri = format!("/cards/search?q=c:u&order=released&cursor={}", cursor);
+    let (status, body) = send_json_request(&mut app, "GET", &uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+}
+
 #[tokio::test]
 async fn test_batch_get_cards() {
     let mut app = create_test_app().await;
@@ -289,98 +524,1167 @@ async fn test_get_card_not_found() {
 }
 
 #[tokio::test]
-async fn test_named_card_exact() {
-    let mut app = create_test_app().await;
-    let (status, body) =
-        send_json_request(&mut app, "GET", "/cards/named?exact=Lightning%20Bolt").await;
+async fn test_get_card_fetched_from_upstream() {
+    let id = "5e6c1b5f-3e2a-4f1b-9c55-8a7f4d1c2b3a";
+    let card = scryfall_cache::models::card::Card::from_scryfall_json(json!({
+        "id": id,
+        "name": "Black Lotus",
+        "set": "lea",
+        "collector_number": "232",
+        "lang": "en"
+    }))
+    .unwrap();
+    let source = std::sync::Arc::new(
+        scryfall_cache::scryfall::source::MockCardSource::new().with_cards(vec![card]),
+    );
+    let mut app = create_test_app_with_source(source.clone()).await;
 
-    if status == StatusCode::OK {
-        assert_eq!(body["success"], true);
-        assert!(body["data"]["name"].as_str().unwrap().contains("Lightning"));
-    }
+    let (status, body) = send_json_request(&mut app, "GET", &format!("/cards/{}", id)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["name"], "Black Lotus");
+    assert_eq!(source.requests(), 1);
 }
 
 #[tokio::test]
-async fn test_named_card_fuzzy() {
-    let mut app = create_test_app().await;
-    let (status, body) =
-        send_json_request(&mut app, "GET", "/cards/named?fuzzy=light%20bolt").await;
+async fn test_get_symbology_cached() {
+    let symbology = json!({
+        "object": "list",
+        "data": [{ "symbol": "{R}", "colors": ["R"], "cmc": 1.0 }]
+    });
+    let source = std::sync::Arc::new(
+        scryfall_cache::scryfall::source::MockCardSource::new()
+            .with_json("/symbology", symbology.clone()),
+    );
+    let mut app = create_test_app_with_source(source.clone()).await;
 
-    if status == StatusCode::OK {
-        assert_eq!(body["success"], true);
-        assert!(body["data"]["name"].is_string());
-    }
+    let (status, body) = send_json_request(&mut app, "GET", "/symbology").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], symbology);
+    assert_eq!(source.requests(), 1);
+
+    // The second call is answered from the reference data cache
+    let (status, body) = send_json_request(&mut app, "GET", "/symbology").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], symbology);
+    assert_eq!(source.requests(), 1);
 }
 
 #[tokio::test]
-async fn test_autocomplete() {
-    let mut app = create_test_app().await;
-    let (status, body) =
-        send_json_request(&mut app, "GET", "/cards/autocomplete?q=lightning").await;
+async fn test_get_card_by_set_and_number() {
+    let card = scryfall_cache::models::card::Card::from_scryfall_json(json!({
+        "id": "5e6c1b5f-3e2a-4f1b-9c55-8a7f4d1c2b3a",
+        "name": "Black Lotus",
+        "set": "lea",
+        "collector_number": "232",
+        "lang": "en"
+    }))
+    .unwrap();
+    let source = std::sync::Arc::new(
+        scryfall_cache::scryfall::source::MockCardSource::new().with_cards(vec![card]),
+    );
+    let mut app = create_test_app_with_source(source.clone()).await;
 
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/LEA/161").await;
     assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["object"], "catalog");
-    assert!(body["data"].is_array());
+    assert_eq!(body["data"]["id"], "4414175f-075a-4c11-b8f9-f2c1b5f00b67");
+    assert_eq!(source.requests(), 0);
+
+    // Printings missing locally are fetched from Scryfall
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/lea/232").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["name"], "Black Lotus");
+    assert_eq!(source.requests(), 1);
+
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/lea/9999").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "CARD_NOT_FOUND");
 }
 
 #[tokio::test]
-async fn test_cache_stats() {
+async fn test_search_fetched_from_upstream_is_stored() {
+    let id = "5e6c1b5f-3e2a-4f1b-9c55-8a7f4d1c2b3a";
+    let card = scryfall_cache::models::card::Card::from_scryfall_json(json!({
+        "id": id,
+        "name": "Black Lotus",
+        "set": "lea",
+        "collector_number": "232",
+        "lang": "en"
+    }))
+    .unwrap();
+    let source = std::sync::Arc::new(
+        scryfall_cache::scryfall::source::MockCardSource::new()
+            .with_search("(lotus) unique:cards", vec![card]),
+    );
+    let mut app = create_test_app_with_source(source.clone()).await;
+    let cache = app.state().cache_manager.clone();
+
+    let cards = cache.search("lotus", None).await.unwrap();
+    assert_eq!(cards.len(), 1);
+    assert_eq!(source.requests(), 1);
+
+    // Flush the card writer, which stores the results in the background
+    cache.shutdown().await;
+
+    let (status, body) = send_json_request(&mut app, "GET", &format!("/cards/{}", id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["name"], "Black Lotus");
+    let cards = cache.search("lotus", None).await.unwrap();
+    assert_eq!(cards.len(), 1);
+    assert_eq!(source.requests(), 1);
+}
+
+#[tokio::test]
+async fn test_get_card_faces_not_found() {
     let mut app = create_test_app().await;
-    let (status, body) = send_json_request(&mut app, "GET", "/stats").await;
+    let uri = "/cards/00000000-0000-0000-0000-000000000000/faces";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["success"], false);
+    assert_eq!(body["error"]["code"], "CARD_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_get_related_cards() {
+    let mut app = create_test_app().await;
+    // Goblin Guide lists itself and Goblin Bushwhacker in all_parts
+    let uri = "/cards/5af35231-4baa-4f71-9ecb-a96620a74656/related";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
 
     assert_eq!(status, StatusCode::OK);
-    assert_eq!(body["success"], true);
-    assert!(body["data"]["total_cards"].is_number());
-    assert!(body["data"]["total_cache_entries"].is_number());
+    let related = body["data"].as_array().unwrap();
+    assert_eq!(related.len(), 1);
+    assert_eq!(related[0]["component"], "combo_piece");
+    assert_eq!(related[0]["id"], "abb6afd8-cbe0-4ec4-96d8-78dba689db3b");
+    assert_eq!(related[0]["card"]["name"], "Goblin Bushwhacker");
+
+    let uri = "/cards/91f8dcba-ae18-43e3-ad71-07557a862265/related";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!([]));
+
+    let query = r#"{ card(id: "5af35231-4baa-4f71-9ecb-a96620a74656") { relatedCards { component card { name } } } }"#;
+    let (status, body) =
+        send_json_body_request(&mut app, "POST", "/graphql", json!({ "query": query })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    assert_eq!(
+        body["data"]["card"]["relatedCards"][0]["card"]["name"],
+        "Goblin Bushwhacker"
+    );
+
+    let uri = "/cards/00000000-0000-0000-0000-000000000000/related";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "CARD_NOT_FOUND");
 }
 
 #[tokio::test]
-async fn test_metrics_endpoint() {
+async fn test_get_card_by_external_id_rejects_non_numeric_id() {
     let mut app = create_test_app().await;
+    for uri in [
+        "/cards/multiverse/abc",
+        "/cards/arena/abc",
+        "/cards/mtgo/abc",
+    ] {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", uri);
+    }
+}
 
-    let request = Request::builder()
-        .method("GET")
-        .uri("/metrics")
-        .body(Body::empty())
-        .unwrap();
+#[tokio::test]
+async fn test_random_card() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/random?q=t:instant").await;
 
-    let response = app.call(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
+    if status == StatusCode::OK {
+        assert_eq!(body["success"], true);
+        assert!(body["data"]["id"].is_string());
+    } else {
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "CARD_NOT_FOUND");
+    }
+}
 
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-        .await
-        .unwrap();
-    let text = String::from_utf8(body.to_vec()).unwrap();
+#[tokio::test]
+async fn test_card_collection() {
+    let mut app = create_test_app().await;
 
-    // Check for Prometheus format metrics
-    assert!(text.contains("# HELP"));
-    assert!(text.contains("# TYPE"));
+    let (status, body) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/cards/collection",
+        json!({ "identifiers": [] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+
+    let (status, body) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/cards/collection",
+        json!({ "identifiers": [{ "id": "00000000-0000-0000-0000-000000000000" }] }),
+    )
+    .await;
+    // Unknown IDs are looked up on Scryfall, which may be unreachable in tests
+    if status == StatusCode::OK {
+        assert_eq!(body["data"]["data"].as_array().unwrap().len(), 0);
+        assert_eq!(
+            body["data"]["not_found"][0]["id"],
+            "00000000-0000-0000-0000-000000000000"
+        );
+    }
 }
 
 #[tokio::test]
-async fn test_query_validation_max_length() {
+async fn test_collections() {
     let mut app = create_test_app().await;
-    let long_query = "a".repeat(2000); // Exceeds default 1000 char limit
-    let uri = format!("/cards/search?q={}", long_query);
-    let (status, body) = send_json_request(&mut app, "GET", &uri).await;
 
+    let (status, body) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/collections/binder/cards",
+        json!({ "cards": [] }),
+    )
+    .await;
     assert_eq!(status, StatusCode::BAD_REQUEST);
-    assert_eq!(body["success"], false);
-    assert!(body["error"]["message"]
-        .as_str()
-        .unwrap()
-        .contains("too long"));
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+
+    let (status, _) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/collections/binder/cards",
+        json!({ "cards": [{ "set": "lea", "quantity": 1 }] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) =
+        send_json_request(&mut app, "GET", "/collections/binder/value?currency=gbp").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/collections/empty-binder/value").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["currency"], "usd");
+    assert_eq!(body["data"]["entries"], 0);
+    assert_eq!(body["data"]["total"], 0.0);
 }
 
 #[tokio::test]
-async fn test_error_response_structure() {
+async fn test_saved_searches() {
     let mut app = create_test_app().await;
-    let (_, body) = send_json_request(&mut app, "GET", "/cards/search?q=((((").await;
 
-    // Verify ErrorResponse structure
-    assert_eq!(body["success"], false);
-    assert!(body["error"].is_object());
+    let (status, body) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/saved-searches",
+        json!({ "query": "(t:creature" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
     assert!(body["error"]["code"].is_string());
-    assert!(body["error"]["message"].is_string());
-    assert!(body["error"]["request_id"].is_string());
+
+    let (status, body) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/saved-searches",
+        json!({ "query": "name:zzzznotacardname", "name": "nothing" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["runs"], 0);
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let (status, body) = send_json_request(&mut app, "GET", "/saved-searches").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|search| search["id"] == id.as_str()));
+
+    let uri = format!("/saved-searches/{}/new", id);
+    let (status, body) = send_json_request(&mut app, "GET", &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["search"]["query"], "name:zzzznotacardname");
+    assert_eq!(body["data"]["cards"].as_array().unwrap().len(), 0);
+
+    let uri = format!("/saved-searches/{}", id);
+    let (status, _) = send_json_request(&mut app, "DELETE", &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = send_json_request(&mut app, "DELETE", &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_named_card_exact() {
+    let mut app = create_test_app().await;
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/cards/named?exact=Lightning%20Bolt").await;
+
+    if status == StatusCode::OK {
+        assert_eq!(body["success"], true);
+        assert!(body["data"]["name"].as_str().unwrap().contains("Lightning"));
+    }
+}
+
+#[tokio::test]
+async fn test_named_card_fuzzy() {
+    let mut app = create_test_app().await;
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/cards/named?fuzzy=light%20bolt").await;
+
+    if status == StatusCode::OK {
+        assert_eq!(body["success"], true);
+        assert!(body["data"]["name"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn test_named_card_invalid_lang() {
+    let mut app = create_test_app().await;
+    let uri = "/cards/named?exact=Lightning%20Bolt&lang=japanese";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn test_autocomplete() {
+    let mut app = create_test_app().await;
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/cards/autocomplete?q=lightning").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["object"], "catalog");
+    assert!(body["data"].is_array());
+}
+
+#[tokio::test]
+async fn test_autocomplete_limit_and_filters() {
+    let mut app = create_test_app().await;
+    let uri = "/cards/autocomplete?q=li&limit=3&include_extras=true&exclude_digital=true";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["object"], "catalog");
+    assert!(body["data"].as_array().unwrap().len() <= 3);
+}
+
+#[tokio::test]
+async fn test_autocomplete_scryfall_format() {
+    let mut app = create_test_app().await;
+    let uri = "/cards/autocomplete?q=l&format=scryfall";
+    let (status, body) = send_json_request(&mut app, "GET", uri).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["object"], "catalog");
+    assert_eq!(body["total_values"], 0);
+}
+
+#[tokio::test]
+async fn test_named_card_scryfall_format_error() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/named?format=scryfall").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["object"], "error");
+    assert_eq!(body["code"], "bad_request");
+    assert_eq!(body["status"], 400);
+}
+
+#[tokio::test]
+async fn test_search_cards_csv() {
+    let mut app = create_test_app().await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/cards/search?q=sol+ring&unique=prints&format=csv&columns=name,set_code,collector_number")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "name,set_code,collector_number");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1..].iter().all(|line| line.starts_with("Sol Ring,")));
+
+    let (status, body) = send_json_request(
+        &mut app,
+        "GET",
+        "/cards/search?q=sol+ring&format=csv&columns=name,artist",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn test_search_invalid_format() {
+    let mut app = create_test_app().await;
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/cards/search?q=c:red&format=xml").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn test_admin_query_limits() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/api/admin/config/query-limits").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["max_query_length"].is_number());
+
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/api/admin/config/query-limits")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "max_nesting_depth": 8 }).to_string()))
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_, body) = send_json_request(&mut app, "GET", "/api/admin/config/query-limits").await;
+    assert_eq!(body["data"]["max_nesting_depth"], 8);
+
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/api/admin/config/query-limits")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "max_query_length": 0 }).to_string()))
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_cache_stats() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/stats").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["success"], true);
+    assert!(body["data"]["total_cards"].is_number());
+    assert!(body["data"]["total_cache_entries"].is_number());
+    assert!(body["data"]["total_cache_hits"].is_number());
+    assert!(body["data"]["uptime_seconds"].is_number());
+    assert!(body["data"]["cache_generation"].is_number());
+    // The test database is seeded with cards and has no Redis
+    assert_eq!(body["data"]["bulk_import"]["loaded"], true);
+    assert!(body["data"]["redis"].is_null());
+
+    let tiers = body["data"]["tiers"]
+        .as_array()
+        .expect("tiers should be an array");
+    let names: Vec<&str> = tiers.iter().filter_map(|t| t["tier"].as_str()).collect();
+    assert_eq!(names, vec!["query_cache", "redis", "database", "api"]);
+}
+
+#[tokio::test]
+async fn test_invalidate_after_reload() {
+    let app = create_test_app().await;
+    let cache = app.state().cache_manager.clone();
+    cache.search("c:r", None).await.unwrap();
+    let stats = cache.get_stats().await.unwrap();
+    assert_eq!(stats.total_cache_entries, 1);
+    assert_eq!(stats.cache_generation, 0);
+
+    assert_eq!(cache.invalidate_after_reload().await.unwrap(), (1, 0));
+    let stats = cache.get_stats().await.unwrap();
+    assert_eq!(stats.total_cache_entries, 0);
+    assert_eq!(stats.cache_generation, 1);
+
+    // Searches started after the reload are cached again
+    cache.search("c:r", None).await.unwrap();
+    assert_eq!(cache.get_stats().await.unwrap().total_cache_entries, 1);
+}
+
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let mut app = create_test_app().await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/metrics")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+
+    // Check for Prometheus format metrics
+    assert!(text.contains("# HELP"));
+    assert!(text.contains("# TYPE"));
+}
+
+#[tokio::test]
+async fn test_search_latency_metrics() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/cards/search?q=c:r").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["total"].as_u64().unwrap() > 0);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/metrics")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+
+    // The local database answered the paginated search
+    for series in [
+        r#"search_total_duration_seconds_count{tier="database"}"#,
+        r#"cache_tier_duration_seconds_count{operation="search",tier="database"}"#,
+    ] {
+        assert!(text.contains(series), "missing {}", series);
+    }
+}
+
+#[tokio::test]
+async fn test_admin_token_required() {
+    let mut app = create_test_app_with_config(|config| {
+        config.server.admin_token = Some("s3cret".to_string());
+    })
+    .await;
+
+    for uri in ["/metrics", "/admin/features", "/api/admin/imports"] {
+        let (status, body) = send_json_request(&mut app, "GET", uri).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", uri);
+        assert_eq!(body["error"]["code"], "UNAUTHORIZED");
+
+        let request = Request::builder()
+            .uri(uri)
+            .header("authorization", "Bearer s3cret")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+    }
+
+    // Health checks and the card API stay open
+    let (status, _) = send_json_request(&mut app, "GET", "/health/live").await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_json_request(&mut app, "GET", "/cards/search?q=c:r").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_cors_allowed_origins() {
+    let mut app = create_test_app_with_config(|config| {
+        config.cors.allowed_origins = Some(vec![HeaderValue::from_static("https://decks.example")]);
+    })
+    .await;
+
+    for (origin, allowed) in [
+        ("https://decks.example", true),
+        ("https://elsewhere.example", false),
+    ] {
+        let request = Request::builder()
+            .method("OPTIONS")
+            .uri("/cards/search?q=c:r")
+            .header("origin", origin)
+            .header("access-control-request-method", "GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.call(request).await.unwrap();
+        let allow_origin = response.headers().get("access-control-allow-origin");
+        if allowed {
+            assert_eq!(allow_origin.unwrap(), origin);
+        } else {
+            assert!(allow_origin.is_none());
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_search_cache_headers() {
+    let mut app = create_test_app().await;
+    let request = Request::builder()
+        .uri("/cards/search?q=c:r")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let cache_control = response.headers()["cache-control"].to_str().unwrap();
+    assert!(cache_control.starts_with("public, max-age="));
+
+    // Errors are never cached
+    let request = Request::builder()
+        .uri("/cards/search?q=((((")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    assert!(response.headers().get("cache-control").is_none());
+}
+
+#[tokio::test]
+async fn test_admin_routes_moved_to_admin_port() {
+    let mut app = create_test_app_with_config(|config| {
+        config.server.admin_port = Some(9091);
+    })
+    .await;
+
+    let (status, _) = send_json_request(&mut app, "GET", "/metrics").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send_json_request(&mut app, "GET", "/api/admin/imports").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send_json_request(&mut app, "GET", "/health").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_admin_schema_version() {
+    let mut app = create_test_app().await;
+
+    let (status, body) = send_json_request(&mut app, "GET", "/api/admin/schema/version").await;
+    assert_eq!(status, StatusCode::OK);
+    let data = &body["data"];
+    assert!(data["latest_version"].as_i64().unwrap() >= 1);
+    assert_eq!(data["current_version"], data["latest_version"]);
+    assert_eq!(data["pending"], json!([]));
+    let applied = data["applied"]
+        .as_array()
+        .expect("expected applied migrations");
+    assert_eq!(applied[0]["version"], 1);
+}
+
+#[tokio::test]
+async fn test_admin_export_csv() {
+    let mut app = create_test_app().await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/export?format=csv&q=lightning")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    assert!(text.starts_with("id,oracle_id,name,"));
+}
+
+#[tokio::test]
+async fn test_admin_backup_and_restore() {
+    let mut app = create_test_app().await;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/backup")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-disposition"]
+        .to_str()
+        .unwrap()
+        .contains("scryfall-cache-backup-"));
+    let snapshot = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let cards: Vec<Value> = serde_json::from_slice(&snapshot).unwrap();
+    assert!(cards.iter().any(|card| card["name"] == "Sol Ring"));
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/restore")
+        .header("content-type", "application/json")
+        .body(Body::from(snapshot))
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["data"]["bulk_type"], "restore");
+    assert_eq!(body["data"]["total_cards"], cards.len());
+
+    let (status, body) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/admin/restore",
+        json!({"not": "a snapshot"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["success"], false);
+
+    let (status, body) = send_json_request(&mut app, "GET", "/api/admin/imports").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"][0]["bulk_type"], "restore");
+}
+
+#[tokio::test]
+async fn test_admin_repair() {
+    let sol_ring = common::fixture_cards()
+        .into_iter()
+        .find(|card| card.name == "Sol Ring")
+        .unwrap();
+    let source = std::sync::Arc::new(
+        scryfall_cache::scryfall::source::MockCardSource::new().with_cards(vec![sol_ring.clone()]),
+    );
+    let mut app = create_test_app_with_source(source.clone()).await;
+
+    // Overwrite Sol Ring with a copy that lost its oracle_id
+    let mut corrupt = sol_ring.raw_json.clone();
+    corrupt.as_object_mut().unwrap().remove("oracle_id");
+    let (status, _) =
+        send_json_body_request(&mut app, "POST", "/admin/restore", json!([corrupt])).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send_json_request(&mut app, "POST", "/admin/repair").await;
+    assert_eq!(status, StatusCode::OK);
+    let id = sol_ring.id.to_string();
+    let issues = body["data"]["issues"].as_array().unwrap();
+    assert!(issues
+        .iter()
+        .any(|issue| issue["id"] == id.as_str() && issue["issue"] == "missing_oracle_id"));
+    assert!(body["data"]["repaired"].as_u64().unwrap() >= 1);
+    assert!(body["data"]["error"].is_null());
+
+    let (status, body) = send_json_request(&mut app, "POST", "/admin/repair").await;
+    assert_eq!(status, StatusCode::OK);
+    let issues = body["data"]["issues"].as_array().unwrap();
+    assert!(!issues.iter().any(|issue| issue["id"] == id.as_str()));
+}
+
+#[tokio::test]
+async fn test_admin_export_invalid_format() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/admin/export?format=xml").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["success"], false);
+}
+
+#[tokio::test]
+async fn test_admin_circuit_breakers() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/admin/circuit-breakers").await;
+
+    assert_eq!(status, StatusCode::OK);
+    let breakers = body["data"].as_array().expect("expected breaker list");
+    assert!(breakers.iter().any(|b| b["name"] == "cards_search"));
+
+    let (status, body) =
+        send_json_request(&mut app, "POST", "/admin/circuit-breakers/cards_search/reset").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["state"], "closed");
+
+    let (status, body) =
+        send_json_request(&mut app, "POST", "/admin/circuit-breakers/nope/reset").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_admin_query_cache() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/admin/cache/queries?limit=5").await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = body["data"].as_array().expect("expected cache entries");
+    assert!(entries.len() <= 5);
+
+    let (status, body) =
+        send_json_request(&mut app, "DELETE", "/admin/cache/queries/not-a-cached-hash").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+
+    let (status, body) = send_json_request(&mut app, "DELETE", "/admin/cache/queries").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["database_entries"].is_number());
+
+    let (_, body) = send_json_request(&mut app, "GET", "/admin/cache/queries").await;
+    assert_eq!(body["data"].as_array().map(Vec::len), Some(0));
+}
+
+#[tokio::test]
+async fn test_graphql_subscription_root() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/graphql",
+        json!({ "query": "{ __schema { subscriptionType { fields { name } } } }" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let fields: Vec<&str> = body["data"]["__schema"]["subscriptionType"]["fields"]
+        .as_array()
+        .expect("expected subscription fields")
+        .iter()
+        .filter_map(|f| f["name"].as_str())
+        .collect();
+    assert!(fields.contains(&"bulkReload"));
+    assert!(fields.contains(&"circuitBreakerStateChanged"));
+    assert!(fields.contains(&"cardUpdated"));
+}
+
+#[tokio::test]
+async fn test_graphql_batched_card_lookups() {
+    let mut app = create_test_app().await;
+    let (_, search_body) = send_json_request(&mut app, "GET", "/cards/search?q=t:goblin").await;
+
+    let cards = search_body["data"]["data"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let ids: Vec<&str> = cards
+        .iter()
+        .filter_map(|c| c["id"].as_str())
+        .take(2)
+        .collect();
+    if ids.len() < 2 {
+        return;
+    }
+
+    let query = format!(
+        r#"{{ first: card(id: "{}") {{ id name }} second: card(id: "{}") {{ id faces {{ name }} }} }}"#,
+        ids[0], ids[1]
+    );
+    let (status, body) =
+        send_json_body_request(&mut app, "POST", "/graphql", json!({ "query": query })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    assert_eq!(body["data"]["first"]["id"], ids[0]);
+    assert_eq!(body["data"]["second"]["id"], ids[1]);
+    assert!(body["data"]["second"]["faces"].is_array());
+}
+
+#[tokio::test]
+async fn test_graphql_card_named_and_autocomplete() {
+    let mut app = create_test_app().await;
+    let query = r#"{
+        exact: cardNamed(exact: "Lightning Bolt") { name }
+        fuzzy: cardNamed(fuzzy: "light bolt") { name }
+        autocomplete(prefix: "light", limit: 5)
+    }"#;
+    let (status, body) =
+        send_json_body_request(&mut app, "POST", "/graphql", json!({ "query": query })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    assert!(body["data"]["autocomplete"].as_array().unwrap().len() <= 5);
+    if let Some(name) = body["data"]["exact"]["name"].as_str() {
+        assert_eq!(name, "Lightning Bolt");
+    }
+
+    let query = r#"{ cardNamed(exact: "Lightning Bolt", fuzzy: "bolt") { name } }"#;
+    let (status, body) =
+        send_json_body_request(&mut app, "POST", "/graphql", json!({ "query": query })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"][0]["message"]
+        .as_str()
+        .unwrap()
+        .contains("exactly one"));
+}
+
+#[tokio::test]
+async fn test_admin_top_queries() {
+    let mut app = create_test_app().await;
+    send_json_request(&mut app, "GET", "/cards/search?q=t:goblin").await;
+
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/api/admin/stats/top-queries?limit=5").await;
+    assert_eq!(status, StatusCode::OK);
+    let queries = body["data"].as_array().expect("expected top queries");
+    assert!(queries.len() <= 5);
+    let goblins = queries
+        .iter()
+        .find(|q| q["query_text"] == "t:goblin")
+        .expect("searched query should be counted");
+    assert!(goblins["count"].as_u64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn test_admin_import_history() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/api/admin/imports?limit=5").await;
+
+    assert_eq!(status, StatusCode::OK);
+    let imports = body["data"].as_array().expect("expected import history");
+    assert!(imports.len() <= 5);
+    for import in imports {
+        assert!(import["bulk_type"].is_string());
+        assert!(import["total_cards"].is_number());
+        assert!(import["failed_cards"].is_number());
+        assert!(import["duration_ms"].is_number());
+
+        let uri = format!("/api/admin/imports/{}/errors?limit=10", import["id"]);
+        let (status, body) = send_json_request(&mut app, "GET", &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["data"]["categories"].is_object());
+        assert!(body["data"]["errors"].as_array().unwrap().len() <= 10);
+    }
+
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/api/admin/imports/2147483647/errors").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_admin_audit_log_records_mutations() {
+    let mut app = create_test_app().await;
+    let (status, _) = send_json_body_request(
+        &mut app,
+        "PUT",
+        "/api/admin/config/query-limits",
+        json!({"max_query_length": 0}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) =
+        send_json_request(&mut app, "GET", "/api/admin/audit?actor=anonymous&limit=50").await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = body["data"].as_array().expect("expected audit entries");
+    let entry = entries
+        .iter()
+        .find(|e| e["path"] == "/api/admin/config/query-limits" && e["status"] == 400)
+        .expect("expected the rejected update to be recorded");
+    assert_eq!(entry["actor"], "anonymous");
+    assert_eq!(entry["method"], "PUT");
+    assert_eq!(entry["outcome"], "failure");
+    assert!(entry["parameters"]
+        .as_str()
+        .unwrap()
+        .contains("max_query_length"));
+}
+
+#[tokio::test]
+async fn test_admin_tenant_stats() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/api/admin/stats/tenants").await;
+
+    assert_eq!(status, StatusCode::OK);
+    let tenants = body["data"].as_array().expect("expected tenant stats");
+    for tenant in tenants {
+        assert!(tenant["tenant_id"].is_string());
+        assert!(tenant["requests"].is_number());
+        assert!(tenant["query_cache_entries"].is_number());
+    }
+}
+
+#[tokio::test]
+async fn test_admin_warmup_without_queries() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/admin/warmup").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["configured_queries"], 0);
+    assert!(body["data"]["last_run"].is_null());
+
+    let (status, body) = send_json_request(&mut app, "POST", "/admin/warmup").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn test_query_validation_max_length() {
+    let mut app = create_test_app().await;
+    let long_query = "a".repeat(2000); // Exceeds default 1000 char limit
+    let uri = format!("/cards/search?q={}", long_query);
+    let (status, body) = send_json_request(&mut app, "GET", &uri).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["success"], false);
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("too long"));
+}
+
+#[tokio::test]
+async fn test_error_response_structure() {
+    let mut app = create_test_app().await;
+    let (_, body) = send_json_request(&mut app, "GET", "/cards/search?q=((((").await;
+
+    // Verify ErrorResponse structure
+    assert_eq!(body["success"], false);
+    assert!(body["error"].is_object());
+    assert!(body["error"]["code"].is_string());
+    assert!(body["error"]["message"].is_string());
+    assert!(body["error"]["request_id"].is_string());
+}
+
+#[tokio::test]
+async fn test_openapi_document_served() {
+    let mut app = create_test_app().await;
+    let (status, body) = send_json_request(&mut app, "GET", "/api-docs/openapi.json").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["components"]["securitySchemes"]["api_key"].is_object());
+    assert!(body["components"]["responses"]["RateLimited"].is_object());
+}
+
+#[tokio::test]
+async fn test_batch_body_limits() {
+    let mut app = create_test_app().await;
+
+    let ids: Vec<String> = (0..1001)
+        .map(|_| "00000000-0000-0000-0000-000000000000".to_string())
+        .collect();
+    let (status, body) =
+        send_json_body_request(&mut app, "POST", "/cards/batch", json!({ "ids": ids })).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body["error"]["code"], "PAYLOAD_TOO_LARGE");
+    assert_eq!(body["error"]["details"]["limit"], "items");
+
+    let mut nested = json!([]);
+    for _ in 0..20 {
+        nested = json!([nested]);
+    }
+    let (status, body) = send_json_body_request(
+        &mut app,
+        "POST",
+        "/queries/batch",
+        json!({ "queries": nested }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body["error"]["details"]["limit"], "json_depth");
+}
+
+// Helper to send a JSON POST with an Idempotency-Key, returning the replay header too
+async fn send_idempotent_request(
+    app: &mut axum::Router,
+    uri: &str,
+    key: &str,
+    body: &Value,
+) -> (StatusCode, Option<HeaderValue>, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("idempotency-key", key)
+        .body(Body::from(serde_json::to_vec(body).unwrap()))
+        .unwrap();
+
+    let response = app.call(request).await.unwrap();
+    let status = response.status();
+    let replayed = response.headers().get("idempotent-replayed").cloned();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap_or(json!({}));
+
+    (status, replayed, json)
+}
+
+#[tokio::test]
+async fn test_idempotency_key_replay_and_conflicts() {
+    use scryfall_cache::api::middleware::idempotency::{idempotency_scope, request_fingerprint};
+
+    let mut app = create_test_app().await;
+    let batch = json!({
+        "queries": [{ "id": "q1", "query": "c:r", "page": 1, "page_size": 5 }]
+    });
+
+    let (status, replayed, first) =
+        send_idempotent_request(&mut app, "/queries/batch", "batch-1", &batch).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(replayed.is_none());
+
+    // A retry gets the stored response
+    let (status, replayed, second) =
+        send_idempotent_request(&mut app, "/queries/batch", "batch-1", &batch).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(replayed.unwrap(), "true");
+    assert_eq!(second, first);
+
+    // The same key for a different body is rejected
+    let other = json!({
+        "queries": [{ "id": "q1", "query": "c:u", "page": 1, "page_size": 5 }]
+    });
+    let (status, _, body) =
+        send_idempotent_request(&mut app, "/queries/batch", "batch-1", &other).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "IDEMPOTENCY_KEY_MISMATCH");
+
+    // A key whose request is still running is rejected
+    let bytes = serde_json::to_vec(&batch).unwrap();
+    let scope = idempotency_scope(&Default::default(), "/queries/batch", "batch-2");
+    let hash = request_fingerprint(&axum::http::Method::POST, "/queries/batch", None, &bytes);
+    let claimed = app
+        .state()
+        .cache_manager
+        .claim_idempotency_key(&scope, &hash, 60)
+        .await
+        .unwrap();
+    assert!(claimed.is_none());
+    let (status, _, body) =
+        send_idempotent_request(&mut app, "/queries/batch", "batch-2", &batch).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "IDEMPOTENCY_CONFLICT");
+
+    // Once released, the key runs again
+    app.state()
+        .cache_manager
+        .release_idempotency_key(&scope)
+        .await
+        .unwrap();
+    let (status, replayed, _) =
+        send_idempotent_request(&mut app, "/queries/batch", "batch-2", &batch).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(replayed.is_none());
+}
+
+#[tokio::test]
+async fn test_reload_while_loading_conflicts() {
+    use futures::StreamExt;
+
+    let mut app = create_test_app().await;
+
+    // A restore whose upload never finishes holds the load lock until it is dropped
+    let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+    let upload = futures::stream::once(async move {
+        let _ = started_tx.send(());
+        Ok::<_, std::io::Error>(b"[".to_vec())
+    })
+    .chain(futures::stream::pending());
+    let loader = app.state().bulk_loader.clone();
+    let restore = tokio::spawn(async move { loader.restore(upload).await });
+    started_rx.await.unwrap();
+
+    let (status, body) = send_json_request(&mut app, "POST", "/admin/reload").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "RELOAD_IN_PROGRESS");
+
+    restore.abort();
 }